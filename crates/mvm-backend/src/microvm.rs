@@ -653,7 +653,8 @@ pub fn run_from_build(config: &FlakeRunConfig) -> Result<()> {
     network::tap_create(slot)?;
     let mut tap_guard = TapGuard::new(slot);
 
-    // Apply network policy (iptables egress filtering) if not unrestricted
+    // Install the per-VM nftables egress table (mandatory-deny always;
+    // allow-list + final drop unless unrestricted)
     network::apply_network_policy(slot, &config.network_policy)?;
 
     // Plan 113 §Task 13 / ADR-064 — spawn `mvm-firecracker-bridge`
@@ -782,7 +783,8 @@ pub fn restore_from_template_snapshot(
     network::tap_create(slot)?;
     let mut tap_guard = TapGuard::new(slot);
 
-    // Apply network policy (iptables egress filtering) if not unrestricted
+    // Install the per-VM nftables egress table (mandatory-deny always;
+    // allow-list + final drop unless unrestricted)
     network::apply_network_policy(slot, &config.network_policy)?;
 
     // Copy snapshot files to per-VM directory
@@ -1099,7 +1101,7 @@ pub fn stop_vm(name: &str) -> Result<()> {
         // Reconstruct slot to find TAP name — scan for the index
         if let Some(idx) = read_slot_index(&abs_dir) {
             let slot = VmSlot::new(vm_name, idx);
-            // Clean up any network policy iptables rules before destroying TAP
+            // Remove the VM's nftables policy table before destroying TAP
             if let Err(e) = network::cleanup_network_policy(&slot) {
                 warn!("failed to clean up network policy rules: {e}");
             }
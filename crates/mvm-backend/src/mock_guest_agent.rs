@@ -343,7 +343,9 @@ fn dispatch(req: GuestRequest, next_token: &AtomicU64) -> GuestResponse {
         }
 
         // ── Process verbs ───────────────────────────────────────────
-        GuestRequest::ProcStart { .. } => {
+        // PTY starts fall through to the catch-all: the mock has no
+        // terminal to relay.
+        GuestRequest::ProcStart { pty: None, .. } => {
             let n = next_token.fetch_add(1, Ordering::Relaxed);
             GuestResponse::ProcResult(ProcResult::Started {
                 pid_token: format!("proc-{n}"),
@@ -443,6 +445,7 @@ mod tests {
             cwd: None,
             stdin: Vec::new(),
             timeout_secs: None,
+            pty: None,
         };
         let path = dir.path().to_string_lossy();
         let r1 = send_proc_request(&path, req()).expect("start 1");
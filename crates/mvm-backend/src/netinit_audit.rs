@@ -105,16 +105,17 @@ pub fn emit_netinit_audit(report: &Report, vm_name: &str) {
         mvm_core::audit_emit!(
             NetworkMandatoryDeny,
             vm: vm_name,
-            "scope=install,layer=guest_netinit,cidrs={cidrs},categories={cats}",
+            "scope=install,layer=guest_netinit,cidrs={cidrs},categories={cats},ipv6={ipv6}",
             cidrs = cidrs.join("|"),
             cats = distinct_categories.join("|"),
+            ipv6 = report.ipv6.as_str(),
         );
     }
 
     // Fail-closed-class summary — emitted when any route
     // failed to install. `effect=continue` documents that
     // `/init` does NOT abort the boot today; the host-side
-    // iptables (where it applies) is the primary layer.
+    // nftables table (where it applies) is the primary layer.
     // mvmd ADR 0022 §"Failure semantics" names the keys.
     if report.has_failures() {
         let failed_cidrs: Vec<String> = report.failed.iter().map(|r| r.cidr.to_string()).collect();
@@ -139,7 +140,7 @@ pub fn emit_netinit_audit(report: &Report, vm_name: &str) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mvm_guest::netinit::{REPORT_MARKER, Report, RouteFailed, RouteInstalled};
+    use mvm_guest::netinit::{Ipv6Deny, REPORT_MARKER, Report, RouteFailed, RouteInstalled};
 
     fn report_with_one_installed() -> Report {
         Report {
@@ -149,6 +150,7 @@ mod tests {
             }],
             failed: vec![],
             skipped_ipv6: vec![],
+            ipv6: Ipv6Deny::Enforced,
         }
     }
 
@@ -164,6 +166,7 @@ mod tests {
                 reason: "operation not permitted".to_string(),
             }],
             skipped_ipv6: vec![],
+            ipv6: Ipv6Deny::Enforced,
         }
     }
 
@@ -1,8 +1,11 @@
+use std::net::{IpAddr, ToSocketAddrs};
+
 use anyhow::Result;
 
 use mvm_base::config::*;
-use mvm_base::shell::run_in_vm_visible;
+use mvm_base::shell::{run_in_vm_stdout, run_in_vm_visible};
 use mvm_base::ui;
+use mvm_core::policy::nft;
 
 // ============================================================================
 // Legacy dev-mode TAP networking (single VM, used by `mvm start/stop`)
@@ -139,68 +142,73 @@ pub fn tap_destroy(slot: &VmSlot) -> Result<()> {
 // Network policy enforcement (domain-based egress filtering)
 // ============================================================================
 
-/// Apply iptables-based network policy for a VM slot.
+/// Install the VM's per-VM nftables table (`mvm_policy_<vm>`).
+///
+/// Must be called after `tap_create()`. The table always drops the
+/// plan-74 W2 mandatory-deny ranges (cloud metadata, link-local,
+/// CGNAT, loopback, ULA) on IPv4 and IPv6 regardless of policy —
+/// even an `unrestricted` workload cannot reach `169.254.169.254`
+/// after this runs. Restricted policies add the allow-list and a
+/// final drop. The table is swapped in as one `nft -f` transaction,
+/// so re-applying replaces the previous ruleset atomically.
 ///
-/// Must be called after `tap_create()`. Always installs the
-/// plan-74 W2 mandatory-deny rules (cloud metadata, link-local,
-/// CGNAT, loopback) regardless of policy — even an
-/// `unrestricted` workload cannot reach `169.254.169.254` after
-/// this runs. The per-policy script (if any) is appended first
-/// so the deny rules land at the TOP of FORWARD via `-I` and
-/// fire before any allow rule.
+/// Allow-list hostnames are resolved here, at install time. DNS is
+/// only allowed to the bridge gateway ([`BRIDGE_IP`]), the guest's
+/// nameserver; queries to any other resolver are dropped.
 pub fn apply_network_policy(
     slot: &VmSlot,
     policy: &mvm_core::network_policy::NetworkPolicy,
 ) -> Result<()> {
-    let mut combined = String::from("set -euo pipefail\n");
-
-    // Per-policy rules (allow-list, DROP, DNS, ESTABLISHED).
-    // Emitted FIRST so subsequent `-I` of the mandatory-deny
-    // rules pushes them under the deny set in chain order. None
-    // for unrestricted policies — that's fine; the deny rules
-    // still apply.
-    if let Some(script) = policy.iptables_script(BRIDGE_DEV, &slot.guest_ip) {
-        combined.push_str(&script);
-    }
-
-    // Mandatory-deny rules. Emitted LAST so they land at the
-    // top of FORWARD (highest priority). Plan 74 W2 §item 4.
-    combined.push_str(&mvm_core::network_policy::mandatory_deny_iptables_script(
-        BRIDGE_DEV,
-        &slot.guest_ip,
-    ));
+    let spec = guest_nft_spec(slot)?;
+    let rules = nft::render_policy_table(&spec, policy, resolve_host)?;
 
     ui::info(&format!(
         "Applying network policy for VM '{}'...",
         slot.name
     ));
-    run_in_vm_visible(&combined)
+    run_in_vm_visible(&nft_script(&rules))
 }
 
-/// Remove all network policy iptables rules for a VM slot.
-/// Flushes any FORWARD rules matching this guest IP, regardless of what
-/// policy was originally applied. Safe to call even if no policy was set.
+/// Remove the VM's nftables table. Idempotent: safe to call when
+/// no policy was applied, or twice.
 pub fn cleanup_network_policy(slot: &VmSlot) -> Result<()> {
-    let mut script = format!(
-        "# Clean up all FORWARD rules for {ip}\n\
-         while sudo iptables -D FORWARD -i {br} -s {ip} -j DROP 2>/dev/null; do :; done\n\
-         while sudo iptables -D FORWARD -i {br} -s {ip} -m state --state ESTABLISHED,RELATED -j ACCEPT 2>/dev/null; do :; done\n\
-         while sudo iptables -D FORWARD -i {br} -s {ip} -p udp --dport 53 -j ACCEPT 2>/dev/null; do :; done\n\
-         while sudo iptables -D FORWARD -i {br} -s {ip} -p tcp --dport 53 -j ACCEPT 2>/dev/null; do :; done\n\
-         while sudo iptables -D FORWARD -i {br} -s {ip} -p tcp -j ACCEPT 2>/dev/null; do :; done\n",
-        br = BRIDGE_DEV,
-        ip = slot.guest_ip,
-    );
-
-    // Plan 74 W2 §item 4 — drain any mandatory-deny rules added
-    // by `apply_network_policy`. Symmetric with the apply path
-    // so a stop/start cycle doesn't accumulate stale entries.
-    script.push_str(
-        &mvm_core::network_policy::mandatory_deny_iptables_cleanup_script(
-            BRIDGE_DEV,
-            &slot.guest_ip,
-        ),
-    );
-
-    run_in_vm_visible(&script)
+    let table = nft::policy_table_name(&slot.name)?;
+    run_in_vm_visible(&nft_script(&nft::delete_table_script(&table)?))
+}
+
+/// Read the allow / drop / DNS / mandatory-deny counters of the
+/// VM's table for the per-VM metrics.
+pub fn network_policy_counters(slot: &VmSlot) -> Result<nft::VmNftCounters> {
+    let table = nft::policy_table_name(&slot.name)?;
+    let args = nft::list_counters_args(&table)?.join(" ");
+    let json = run_in_vm_stdout(&format!("sudo nft {args}"))?;
+    Ok(nft::parse_counters(&table, &json)?)
+}
+
+fn guest_nft_spec(slot: &VmSlot) -> Result<nft::GuestNftSpec> {
+    Ok(
+        nft::GuestNftSpec::new(&slot.name, BRIDGE_DEV, &slot.guest_ip, Some(&slot.mac))?
+            .with_dns_endpoint(BRIDGE_IP.parse()?),
+    )
+}
+
+fn resolve_host(host: &str) -> Vec<IpAddr> {
+    match (host, 0).to_socket_addrs() {
+        Ok(addrs) => addrs.map(|a| a.ip()).collect(),
+        Err(e) => {
+            ui::warn(&format!("network policy: cannot resolve {host}: {e}"));
+            Vec::new()
+        }
+    }
+}
+
+/// Feed a ruleset to `nft -f -` through a quoted heredoc (no shell
+/// expansion inside the rules).
+fn nft_script(rules: &str) -> String {
+    format!(
+        "set -euo pipefail
+sudo nft -f - <<'MVM_NFT'
+{rules}MVM_NFT
+"
+    )
 }
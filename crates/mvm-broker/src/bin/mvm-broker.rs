@@ -25,9 +25,12 @@
 //!   handler needs a UDS path to forward to. If the supervisor spawns
 //!   without an audit-signer (test fixtures, doctor probes), the
 //!   binary logs a warn and `host.audit.v1` calls return `NotBound`.
-//!
-//! `host.time.v1`, `host.cost.v1`, and `broker.v1` are still
-//! unregistered (W3 / W4a / W3 wave the handler scaffolds).
+//! - `host.time.v1` — signed host time. Always registered; the signing
+//!   key is minted fresh at every spawn.
+//! - `host.cost.v1` — per-workload usage readouts. Only registered
+//!   when `cfg.metering_samples_path` is set.
+//! - `broker.v1` — `list_services` introspection. Always registered,
+//!   last, so its listing covers every other handler.
 
 use std::io::Read;
 use std::sync::Arc;
@@ -38,7 +41,10 @@ use tracing::{error, info, warn};
 
 use mvm_broker::audit_client::AuditClient;
 use mvm_broker::config::{SubprocessConfig, parse as parse_config};
+use mvm_broker::handlers::broker_v1::BrokerV1Handler;
 use mvm_broker::handlers::host_audit_v1::HostAuditV1Handler;
+use mvm_broker::handlers::host_cost_v1::HostCostV1Handler;
+use mvm_broker::handlers::host_time_v1::HostTimeV1Handler;
 use mvm_broker::registry::Registry;
 use mvm_broker::server::serve_on_listener;
 
@@ -112,6 +118,25 @@ fn main() -> Result<()> {
 /// leaves the registry without that handler; callers get
 /// `Err(NotBound)` for the missing service.
 fn register_handlers(registry: &mut Registry, cfg: &SubprocessConfig) {
+    registry.register(Arc::new(HostTimeV1Handler::new()));
+    info!("host.time.v1 handler registered");
+
+    match &cfg.metering_samples_path {
+        Some(path) => {
+            registry.register(Arc::new(HostCostV1Handler::new(path.clone())));
+            info!(
+                metering_samples_path = %path.display(),
+                "host.cost.v1 handler registered"
+            );
+        }
+        None => {
+            warn!(
+                "host.cost.v1 NOT registered: SubprocessConfig.metering_samples_path missing; \
+                 calls will return NotBound"
+            );
+        }
+    }
+
     match &cfg.audit_signer_uds_path {
         Some(path) => {
             let client = AuditClient::new(path.clone());
@@ -128,4 +153,9 @@ fn register_handlers(registry: &mut Registry, cfg: &SubprocessConfig) {
             );
         }
     }
+
+    // Last: the listing snapshots everything registered above.
+    let meta = BrokerV1Handler::for_registry(registry);
+    registry.register(Arc::new(meta));
+    info!("broker.v1 handler registered");
 }
@@ -38,6 +38,11 @@ pub struct SubprocessConfig {
     /// in the config now so W1b doesn't change the envelope shape.
     #[serde(default)]
     pub audit_signer_uds_path: Option<PathBuf>,
+    /// JSONL file of `MeteringSample`s the supervisor's sampler appends
+    /// for this workload. `host.cost.v1` tails it for usage readouts;
+    /// absent → `host.cost.v1` is not registered.
+    #[serde(default)]
+    pub metering_samples_path: Option<PathBuf>,
     /// Maximum frame size in bytes. Plan 104 §"Capability gating" gate 1
     /// caps this at 64 KiB by default.
     #[serde(default = "default_max_frame_bytes")]
@@ -48,11 +53,13 @@ pub struct SubprocessConfig {
     pub parse_timeout: Duration,
 }
 
-fn default_max_frame_bytes() -> usize {
+/// Plan 104 §"Capability gating" gate 1 frame cap.
+pub fn default_max_frame_bytes() -> usize {
     65_536
 }
 
-fn default_parse_timeout_ms() -> Duration {
+/// Plan 104 §"Capability gating" gate 1 parse budget.
+pub fn default_parse_timeout_ms() -> Duration {
     Duration::from_millis(50)
 }
 
@@ -98,6 +105,7 @@ mod tests {
             uds_path: PathBuf::from("/tmp/test/broker.sock"),
             host_signer_public_key_path: PathBuf::from("/tmp/test/host-signer.pub"),
             audit_signer_uds_path: Some(PathBuf::from("/tmp/test/audit-signer.sock")),
+            metering_samples_path: Some(PathBuf::from("/tmp/test/metering.jsonl")),
             max_frame_bytes: 65_536,
             parse_timeout: Duration::from_millis(50),
         };
@@ -109,7 +117,7 @@ mod tests {
     #[test]
     fn defaults_for_optional_fields() {
         // Minimal config — defaults supply max_frame_bytes / parse_timeout /
-        // audit_signer_uds_path.
+        // audit_signer_uds_path / metering_samples_path.
         let json = serde_json::json!({
             "workload_id": "wl-min",
             "tenant_id": "t-min",
@@ -121,6 +129,7 @@ mod tests {
         assert_eq!(parsed.max_frame_bytes, 65_536);
         assert_eq!(parsed.parse_timeout, Duration::from_millis(50));
         assert!(parsed.audit_signer_uds_path.is_none());
+        assert!(parsed.metering_samples_path.is_none());
     }
 
     #[test]
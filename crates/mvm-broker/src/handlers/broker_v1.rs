@@ -0,0 +1,205 @@
+//! `broker.v1` — broker introspection (Plan 104 W3).
+//!
+//! `list_services` returns every service bound in this broker with its
+//! verbs, admitted profiles, and deprecation date. The registry is
+//! static after startup, so the handler snapshots it once at
+//! construction: register `broker.v1` last, via
+//! [`BrokerV1Handler::for_registry`], and the snapshot includes every
+//! other handler plus `broker.v1` itself.
+//!
+//! Refusal semantics:
+//!
+//! - Unknown verb → `ServiceErrorCode::NotImplemented`.
+//! - Non-empty payload → `ServiceErrorCode::BadRequest`.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use mvm_core::policy::security::AgentProfile;
+use mvm_core::protocol::broker::{AuditDurability, Idempotency, ServiceErrorCode, ServiceId};
+use mvm_core::protocol::broker_meta::{ListServicesRequest, ListServicesResponse};
+use mvm_core::protocol::handler::{
+    ServiceCallCtx, ServiceDispatchResult, ServiceError, ServiceHandler,
+};
+
+use crate::registry::{Registry, describe};
+
+/// The handler itself. Holds the pre-rendered `list_services` response.
+pub struct BrokerV1Handler {
+    listing: ListServicesResponse,
+}
+
+impl BrokerV1Handler {
+    /// Snapshot `registry` (plus this handler) for `list_services`.
+    pub fn for_registry(registry: &Registry) -> Self {
+        let mut handler = Self {
+            listing: ListServicesResponse {
+                services: registry.descriptors(),
+            },
+        };
+        let own = describe(&handler);
+        let services = &mut handler.listing.services;
+        services.retain(|d| d.service != own.service);
+        services.push(own);
+        services.sort_by(|a, b| a.service.as_str().cmp(b.service.as_str()));
+        handler
+    }
+
+    fn handle_list_services(&self, payload: serde_json::Value) -> ServiceDispatchResult {
+        let _req: ListServicesRequest = serde_json::from_value(payload).map_err(|e| {
+            ServiceError::new(
+                ServiceErrorCode::BadRequest,
+                format!("list_services payload parse failed: {e}"),
+            )
+        })?;
+        serde_json::to_value(&self.listing).map_err(|e| {
+            ServiceError::new(
+                ServiceErrorCode::InternalError,
+                format!("list_services response encode failed: {e}"),
+            )
+        })
+    }
+}
+
+impl ServiceHandler for BrokerV1Handler {
+    fn id(&self) -> ServiceId {
+        ServiceId::parse("broker.v1").expect("broker.v1 is a valid ServiceId")
+    }
+
+    fn profiles(&self) -> &[AgentProfile] {
+        // Always on (Plan 104 §A2 `service-broker-meta`): every
+        // workload may discover what it's bound to.
+        &[
+            AgentProfile::SealedProd,
+            AgentProfile::Dev,
+            AgentProfile::Builder,
+        ]
+    }
+
+    fn audit_durability(&self) -> AuditDurability {
+        AuditDurability::default_batched()
+    }
+
+    fn idempotency(&self) -> Idempotency {
+        // The listing is fixed for the broker's lifetime.
+        Idempotency::CacheRecent { ttl_ms: 60_000 }
+    }
+
+    fn call_timeout(&self) -> Duration {
+        Duration::from_millis(2)
+    }
+
+    fn verbs(&self) -> &[&'static str] {
+        &["list_services"]
+    }
+
+    fn dispatch<'a>(
+        &'a self,
+        _ctx: &'a ServiceCallCtx,
+        verb: &'a str,
+        payload: serde_json::Value,
+    ) -> Pin<Box<dyn std::future::Future<Output = ServiceDispatchResult> + Send + 'a>> {
+        Box::pin(async move {
+            match verb {
+                "list_services" => self.handle_list_services(payload),
+                other => Err(ServiceError::new(
+                    ServiceErrorCode::NotImplemented,
+                    format!("broker.v1: unknown verb `{other}`"),
+                )),
+            }
+        })
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use mvm_core::protocol::broker::CorrelationId;
+
+    use super::*;
+    use crate::handlers::host_cost_v1::HostCostV1Handler;
+    use crate::handlers::host_time_v1::HostTimeV1Handler;
+
+    /// A handler scheduled for removal, to exercise the deprecation
+    /// flag.
+    struct OldTimeHandler;
+
+    impl ServiceHandler for OldTimeHandler {
+        fn id(&self) -> ServiceId {
+            ServiceId::parse("host.time.v0").unwrap()
+        }
+        fn profiles(&self) -> &[AgentProfile] {
+            &[AgentProfile::Dev]
+        }
+        fn audit_durability(&self) -> AuditDurability {
+            AuditDurability::default_batched()
+        }
+        fn idempotency(&self) -> Idempotency {
+            Idempotency::MintFresh
+        }
+        fn call_timeout(&self) -> Duration {
+            Duration::from_millis(2)
+        }
+        fn deprecated_in(&self) -> Option<&'static str> {
+            Some("2027-Q1")
+        }
+        fn dispatch<'a>(
+            &'a self,
+            _ctx: &'a ServiceCallCtx,
+            _verb: &'a str,
+            _payload: serde_json::Value,
+        ) -> Pin<Box<dyn std::future::Future<Output = ServiceDispatchResult> + Send + 'a>> {
+            Box::pin(async move { Ok(serde_json::Value::Null) })
+        }
+    }
+
+    fn ctx() -> ServiceCallCtx {
+        ServiceCallCtx {
+            workload_id: "wl-001".into(),
+            tenant_id: "t-001".into(),
+            correlation_id: CorrelationId::new("01HCORR0000000000000000"),
+            session_id: "sess-001".into(),
+            profile: AgentProfile::Dev,
+            composition_depth: 0,
+            composition_width: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn list_services_returns_bound_set_with_deprecation() {
+        let mut registry = Registry::new();
+        registry.register(Arc::new(HostTimeV1Handler::new()));
+        registry.register(Arc::new(HostCostV1Handler::in_memory()));
+        registry.register(Arc::new(OldTimeHandler));
+        let meta = BrokerV1Handler::for_registry(&registry);
+
+        let value = meta
+            .dispatch(&ctx(), "list_services", serde_json::json!({}))
+            .await
+            .unwrap();
+        let resp: ListServicesResponse = serde_json::from_value(value).unwrap();
+        let ids: Vec<_> = resp.services.iter().map(|d| d.service.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["broker.v1", "host.cost.v1", "host.time.v0", "host.time.v1"]
+        );
+        assert_eq!(resp.services[1].verbs, ["workload", "tenant"]);
+        assert_eq!(resp.services[2].deprecated_in.as_deref(), Some("2027-Q1"));
+        assert!(resp.services[3].deprecated_in.is_none());
+    }
+
+    #[tokio::test]
+    async fn unexpected_payload_is_bad_request() {
+        let meta = BrokerV1Handler::for_registry(&Registry::new());
+        let err = meta
+            .dispatch(&ctx(), "list_services", serde_json::json!({ "all": true }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ServiceErrorCode::BadRequest);
+    }
+}
@@ -339,6 +339,10 @@ impl ServiceHandler for HostAuditV1Handler {
         32 * 1024
     }
 
+    fn verbs(&self) -> &[&'static str] {
+        &["emit", "emit_batch"]
+    }
+
     fn dispatch<'a>(
         &'a self,
         ctx: &'a ServiceCallCtx,
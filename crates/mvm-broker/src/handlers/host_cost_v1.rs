@@ -0,0 +1,365 @@
+//! `host.cost.v1` — per-VM usage readouts (Plan 104 W4a).
+//!
+//! The supervisor's sampler (`instance_sampler::meter_once`) appends
+//! one JSON [`MeteringSample`] per line to the file named by
+//! `SubprocessConfig.metering_samples_path`. No supervisor runs that
+//! sampler or spawns the broker yet, so outside tests the file stays
+//! absent and readouts are zero. This handler tails that file on each
+//! `workload` call (reading only the bytes appended since the last
+//! call) and folds every sample into a per-`(tenant_id, instance_id)`
+//! ledger. The readout returned is the ledger entry for the caller's
+//! `ServiceCallCtx` — the request carries no ids, so a workload only
+//! ever sees its own usage.
+//!
+//! A truncated or rotated samples file restarts the tail from offset 0;
+//! totals already folded in are kept. Lines that fail to parse are
+//! skipped with a warn. A partial trailing line (sampler mid-write) is
+//! left for the next call.
+//!
+//! Refusal semantics:
+//!
+//! - `tenant` verb → `ServiceErrorCode::NotImplemented` until W4b.
+//! - Unknown verb → `ServiceErrorCode::NotImplemented`.
+//! - Payload parse failure → `ServiceErrorCode::BadRequest`.
+//! - Samples file unreadable (other than not-yet-created) →
+//!   `ServiceErrorCode::Unavailable`.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use mvm_core::metering::MeteringSample;
+use mvm_core::policy::security::AgentProfile;
+use mvm_core::protocol::broker::{AuditDurability, Idempotency, ServiceErrorCode, ServiceId};
+use mvm_core::protocol::handler::{
+    ServiceCallCtx, ServiceDispatchResult, ServiceError, ServiceHandler,
+};
+use mvm_core::protocol::host_cost::{UsageTotals, WorkloadCostRequest, WorkloadCostResponse};
+use tracing::warn;
+
+/// Running totals for one `(tenant_id, instance_id)`.
+#[derive(Debug, Default)]
+struct LedgerEntry {
+    totals: UsageTotals,
+    sample_count: u64,
+    first_sample_at: Option<SystemTime>,
+    last_sample_at: Option<SystemTime>,
+}
+
+/// Ledger plus the samples-file tail position.
+#[derive(Debug, Default)]
+struct CostState {
+    entries: HashMap<(String, String), LedgerEntry>,
+    offset: u64,
+}
+
+impl CostState {
+    fn record(&mut self, sample: &MeteringSample) {
+        let entry = self
+            .entries
+            .entry((sample.tenant_id.clone(), sample.instance_id.clone()))
+            .or_default();
+        entry.totals.add(sample);
+        entry.sample_count += 1;
+        entry.first_sample_at = Some(match entry.first_sample_at {
+            Some(first) => first.min(sample.ts),
+            None => sample.ts,
+        });
+        entry.last_sample_at = Some(match entry.last_sample_at {
+            Some(last) => last.max(sample.ts),
+            None => sample.ts,
+        });
+    }
+
+    /// Fold in every complete line appended to `path` since the last
+    /// call.
+    fn poll(&mut self, path: &Path) -> std::io::Result<()> {
+        let mut file = match File::open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        if file.metadata()?.len() < self.offset {
+            self.offset = 0;
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        let Some(end) = buf.iter().rposition(|b| *b == b'\n') else {
+            return Ok(());
+        };
+        for line in buf[..end].split(|b| *b == b'\n') {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            match serde_json::from_slice::<MeteringSample>(line) {
+                Ok(sample) => self.record(&sample),
+                Err(e) => warn!(
+                    path = %path.display(),
+                    error = %e,
+                    "host.cost.v1: skipping malformed metering sample"
+                ),
+            }
+        }
+        self.offset += end as u64 + 1;
+        Ok(())
+    }
+}
+
+/// The handler itself.
+pub struct HostCostV1Handler {
+    samples_path: Option<PathBuf>,
+    state: Mutex<CostState>,
+}
+
+impl HostCostV1Handler {
+    /// Handler fed by the JSONL samples file at `samples_path`.
+    pub fn new(samples_path: PathBuf) -> Self {
+        Self {
+            samples_path: Some(samples_path),
+            state: Mutex::new(CostState::default()),
+        }
+    }
+
+    /// Handler with no samples file; samples arrive via
+    /// [`Self::record`] only. Used by tests and in-process embedding.
+    pub fn in_memory() -> Self {
+        Self {
+            samples_path: None,
+            state: Mutex::new(CostState::default()),
+        }
+    }
+
+    /// Fold one sample into the ledger directly.
+    pub fn record(&self, sample: &MeteringSample) {
+        self.lock().record(sample);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CostState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn handle_workload(
+        &self,
+        ctx: &ServiceCallCtx,
+        payload: serde_json::Value,
+    ) -> ServiceDispatchResult {
+        let _req: WorkloadCostRequest = serde_json::from_value(payload).map_err(|e| {
+            ServiceError::new(
+                ServiceErrorCode::BadRequest,
+                format!("workload payload parse failed: {e}"),
+            )
+        })?;
+
+        let mut state = self.lock();
+        if let Some(path) = &self.samples_path {
+            state.poll(path).map_err(|e| {
+                ServiceError::new(
+                    ServiceErrorCode::Unavailable,
+                    format!("metering samples unreadable: {}", e.kind()),
+                )
+            })?;
+        }
+        let key = (ctx.tenant_id.clone(), ctx.workload_id.clone());
+        let resp = match state.entries.get(&key) {
+            Some(entry) => WorkloadCostResponse {
+                workload_id: ctx.workload_id.clone(),
+                tenant_id: ctx.tenant_id.clone(),
+                totals: entry.totals,
+                sample_count: entry.sample_count,
+                first_sample_at: entry.first_sample_at,
+                last_sample_at: entry.last_sample_at,
+            },
+            None => WorkloadCostResponse {
+                workload_id: ctx.workload_id.clone(),
+                tenant_id: ctx.tenant_id.clone(),
+                totals: UsageTotals::default(),
+                sample_count: 0,
+                first_sample_at: None,
+                last_sample_at: None,
+            },
+        };
+        drop(state);
+
+        serde_json::to_value(resp).map_err(|e| {
+            ServiceError::new(
+                ServiceErrorCode::InternalError,
+                format!("workload response encode failed: {e}"),
+            )
+        })
+    }
+}
+
+impl ServiceHandler for HostCostV1Handler {
+    fn id(&self) -> ServiceId {
+        ServiceId::parse("host.cost.v1").expect("host.cost.v1 is a valid ServiceId")
+    }
+
+    fn profiles(&self) -> &[AgentProfile] {
+        // Denied in the builder profile (Plan 104 W4a): build usage is
+        // billed to the builder, not surfaced to the recipe.
+        &[AgentProfile::SealedProd, AgentProfile::Dev]
+    }
+
+    fn audit_durability(&self) -> AuditDurability {
+        AuditDurability::default_batched()
+    }
+
+    fn idempotency(&self) -> Idempotency {
+        Idempotency::CacheRecent { ttl_ms: 1000 }
+    }
+
+    fn call_timeout(&self) -> Duration {
+        // Plan 104 §C4: host.cost.v1::workload = 5ms. `tenant` gets
+        // its own 150ms budget when W4b lands.
+        Duration::from_millis(5)
+    }
+
+    fn response_size_cap(&self) -> usize {
+        4 * 1024
+    }
+
+    fn verbs(&self) -> &[&'static str] {
+        &["workload", "tenant"]
+    }
+
+    fn dispatch<'a>(
+        &'a self,
+        ctx: &'a ServiceCallCtx,
+        verb: &'a str,
+        payload: serde_json::Value,
+    ) -> Pin<Box<dyn std::future::Future<Output = ServiceDispatchResult> + Send + 'a>> {
+        Box::pin(async move {
+            match verb {
+                "workload" => self.handle_workload(ctx, payload),
+                "tenant" => Err(ServiceError::not_implemented(verb)),
+                other => Err(ServiceError::new(
+                    ServiceErrorCode::NotImplemented,
+                    format!("host.cost.v1: unknown verb `{other}`"),
+                )),
+            }
+        })
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    use mvm_core::protocol::broker::CorrelationId;
+
+    use super::*;
+
+    fn ctx() -> ServiceCallCtx {
+        ServiceCallCtx {
+            workload_id: "wl-001".into(),
+            tenant_id: "t-001".into(),
+            correlation_id: CorrelationId::new("01HCORR0000000000000000"),
+            session_id: "sess-001".into(),
+            profile: AgentProfile::Dev,
+            composition_depth: 0,
+            composition_width: 0,
+        }
+    }
+
+    fn sample(tenant: &str, instance: &str, secs: u64, cpu_ns: u64) -> MeteringSample {
+        MeteringSample {
+            instance_id: instance.into(),
+            tenant_id: tenant.into(),
+            tags: BTreeMap::new(),
+            ts: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            cpu_ns,
+            mem_byte_seconds: 10,
+            storage_byte_seconds_cold: 0,
+            storage_byte_seconds_hot: 1,
+        }
+    }
+
+    async fn workload(handler: &HostCostV1Handler) -> WorkloadCostResponse {
+        let value = handler
+            .dispatch(&ctx(), "workload", serde_json::json!({}))
+            .await
+            .unwrap();
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn readout_is_scoped_to_the_calling_workload() {
+        let handler = HostCostV1Handler::in_memory();
+        handler.record(&sample("t-001", "wl-001", 100, 5));
+        handler.record(&sample("t-001", "wl-001", 101, 7));
+        handler.record(&sample("t-001", "wl-other", 100, 1_000));
+        handler.record(&sample("t-other", "wl-001", 100, 1_000));
+
+        let resp = workload(&handler).await;
+        assert_eq!(resp.workload_id, "wl-001");
+        assert_eq!(resp.totals.cpu_ns, 12);
+        assert_eq!(resp.totals.mem_byte_seconds, 20);
+        assert_eq!(resp.sample_count, 2);
+        assert_eq!(
+            resp.first_sample_at,
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(100))
+        );
+        assert_eq!(
+            resp.last_sample_at,
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(101))
+        );
+    }
+
+    #[tokio::test]
+    async fn no_samples_yields_zero_readout() {
+        let dir = tempfile::tempdir().unwrap();
+        let handler = HostCostV1Handler::new(dir.path().join("missing.jsonl"));
+        let resp = workload(&handler).await;
+        assert_eq!(resp.sample_count, 0);
+        assert_eq!(resp.totals, UsageTotals::default());
+        assert!(resp.first_sample_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn tails_the_samples_file_incrementally() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metering.jsonl");
+        let mut file = File::create(&path).unwrap();
+        let line = |s: &MeteringSample| format!("{}\n", serde_json::to_string(s).unwrap());
+        write!(file, "{}", line(&sample("t-001", "wl-001", 1, 3))).unwrap();
+        writeln!(file, "not json").unwrap();
+        let handler = HostCostV1Handler::new(path.clone());
+        assert_eq!(workload(&handler).await.totals.cpu_ns, 3);
+
+        // A partial line is left for the next poll.
+        let next = line(&sample("t-001", "wl-001", 2, 4));
+        let (head, tail) = next.split_at(10);
+        write!(file, "{head}").unwrap();
+        assert_eq!(workload(&handler).await.sample_count, 1);
+        write!(file, "{tail}").unwrap();
+        let resp = workload(&handler).await;
+        assert_eq!(resp.sample_count, 2);
+        assert_eq!(resp.totals.cpu_ns, 7);
+
+        // Rotation: totals survive, the new file is read from the start.
+        let mut file = File::create(&path).unwrap();
+        write!(file, "{}", line(&sample("t-001", "wl-001", 3, 1))).unwrap();
+        assert_eq!(workload(&handler).await.totals.cpu_ns, 8);
+    }
+
+    #[tokio::test]
+    async fn tenant_verb_is_not_implemented_and_builder_is_denied() {
+        let handler = HostCostV1Handler::in_memory();
+        let err = handler
+            .dispatch(&ctx(), "tenant", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ServiceErrorCode::NotImplemented);
+        assert!(!handler.profiles().contains(&AgentProfile::Builder));
+    }
+}
@@ -0,0 +1,295 @@
+//! `host.time.v1` — trusted host time for guests (Plan 104 W3).
+//!
+//! `now` returns the host wall clock plus a broker-monotonic clock,
+//! signed with an Ed25519 key the handler mints at construction (one
+//! key per broker spawn — Plan 104 §H-L4.2). The guest pins the
+//! published key on first use and verifies every later reading
+//! against it; see `mvm_core::protocol::host_time`.
+//!
+//! Time integrity (§H-L5.5): `monotonic_ns` comes from `Instant`
+//! (CLOCK_MONOTONIC) so it never steps. Each reading also compares the
+//! wall-clock delta against the monotonic delta since the previous
+//! reading; a divergence beyond [`CLOCK_JUMP_TOLERANCE`] logs a
+//! `clock jump detected` warning. The reading is still served — the
+//! wall clock is what the host believes, and the guest can see the
+//! step itself by comparing the two clocks.
+//!
+//! Refusal semantics:
+//!
+//! - Unknown verb → `ServiceErrorCode::NotImplemented`.
+//! - Payload parse failure or oversize nonce → `ServiceErrorCode::BadRequest`.
+//! - Host wall clock before the Unix epoch → `ServiceErrorCode::InternalError`.
+
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use mvm_core::policy::security::AgentProfile;
+use mvm_core::protocol::broker::{AuditDurability, Idempotency, ServiceErrorCode, ServiceId};
+use mvm_core::protocol::handler::{
+    ServiceCallCtx, ServiceDispatchResult, ServiceError, ServiceHandler,
+};
+use mvm_core::protocol::host_time::{
+    HOST_TIME_NONCE_MAX_BYTES, NowRequest, TimeReading, sign_reading,
+};
+use rand::rngs::OsRng;
+use tracing::warn;
+
+/// Wall-vs-monotonic divergence between two readings above which the
+/// handler reports a clock jump. NTP slews stay well under this; a
+/// step (`date -s`, VM resume with a stale clock) does not.
+pub const CLOCK_JUMP_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// Last reading handed out, for `seq` and jump detection.
+#[derive(Default)]
+struct ClockState {
+    seq: u64,
+    last: Option<(u64, u64)>,
+}
+
+/// The handler itself. Holds the per-spawn signing key and the
+/// monotonic origin.
+pub struct HostTimeV1Handler {
+    signing_key: SigningKey,
+    origin: Instant,
+    state: Mutex<ClockState>,
+}
+
+impl HostTimeV1Handler {
+    /// New handler with a freshly generated signing key.
+    pub fn new() -> Self {
+        Self::with_signing_key(SigningKey::generate(&mut OsRng))
+    }
+
+    /// Test/override hook for the signing key. Production uses a fresh
+    /// key per spawn via [`Self::new`].
+    pub fn with_signing_key(signing_key: SigningKey) -> Self {
+        Self {
+            signing_key,
+            origin: Instant::now(),
+            state: Mutex::new(ClockState::default()),
+        }
+    }
+
+    /// The key readings are signed with.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    fn handle_now(
+        &self,
+        ctx: &ServiceCallCtx,
+        payload: serde_json::Value,
+    ) -> ServiceDispatchResult {
+        let req: NowRequest = serde_json::from_value(payload).map_err(|e| {
+            ServiceError::new(
+                ServiceErrorCode::BadRequest,
+                format!("now payload parse failed: {e}"),
+            )
+        })?;
+        if req
+            .nonce
+            .as_ref()
+            .is_some_and(|n| n.len() > HOST_TIME_NONCE_MAX_BYTES)
+        {
+            return Err(ServiceError::new(
+                ServiceErrorCode::BadRequest,
+                format!("nonce exceeds {HOST_TIME_NONCE_MAX_BYTES} bytes"),
+            ));
+        }
+
+        let (wall_unix_ns, monotonic_ns, seq) = self.read_clocks(ctx)?;
+        let reading = TimeReading {
+            wall_unix_ns,
+            monotonic_ns,
+            seq,
+            workload_id: ctx.workload_id.clone(),
+            nonce: req.nonce,
+        };
+        serde_json::to_value(sign_reading(&self.signing_key, reading)).map_err(|e| {
+            ServiceError::new(
+                ServiceErrorCode::InternalError,
+                format!("now response encode failed: {e}"),
+            )
+        })
+    }
+
+    /// Sample both clocks under the state lock so `seq` order matches
+    /// `monotonic_ns` order.
+    fn read_clocks(&self, ctx: &ServiceCallCtx) -> Result<(u64, u64, u64), ServiceError> {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        let monotonic_ns = duration_ns(self.origin.elapsed());
+        let wall_unix_ns = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(duration_ns)
+            .map_err(|_| {
+                ServiceError::new(
+                    ServiceErrorCode::InternalError,
+                    "host wall clock is before the Unix epoch",
+                )
+            })?;
+
+        if let Some((last_wall, last_mono)) = state.last {
+            let expected = last_wall.saturating_add(monotonic_ns - last_mono);
+            let drift = wall_unix_ns.abs_diff(expected);
+            if drift > duration_ns(CLOCK_JUMP_TOLERANCE) {
+                warn!(
+                    workload_id = %ctx.workload_id,
+                    backward = wall_unix_ns < expected,
+                    drift_ms = drift / 1_000_000,
+                    "host.time.v1: clock jump detected"
+                );
+            }
+        }
+        state.seq += 1;
+        state.last = Some((wall_unix_ns, monotonic_ns));
+        Ok((wall_unix_ns, monotonic_ns, state.seq))
+    }
+}
+
+impl Default for HostTimeV1Handler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn duration_ns(d: Duration) -> u64 {
+    u64::try_from(d.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl ServiceHandler for HostTimeV1Handler {
+    fn id(&self) -> ServiceId {
+        ServiceId::parse("host.time.v1").expect("host.time.v1 is a valid ServiceId")
+    }
+
+    fn profiles(&self) -> &[AgentProfile] {
+        // Builder VMs need trusted time too (TLS validity on fetches).
+        &[
+            AgentProfile::SealedProd,
+            AgentProfile::Dev,
+            AgentProfile::Builder,
+        ]
+    }
+
+    fn audit_durability(&self) -> AuditDurability {
+        AuditDurability::default_batched()
+    }
+
+    fn idempotency(&self) -> Idempotency {
+        // A cached reading would be stale by definition.
+        Idempotency::MintFresh
+    }
+
+    fn call_timeout(&self) -> Duration {
+        // Plan 104 §C4: host.time.v1 = 2ms.
+        Duration::from_millis(2)
+    }
+
+    fn response_size_cap(&self) -> usize {
+        // ~400 bytes with a full-length nonce.
+        4 * 1024
+    }
+
+    fn verbs(&self) -> &[&'static str] {
+        &["now"]
+    }
+
+    fn dispatch<'a>(
+        &'a self,
+        ctx: &'a ServiceCallCtx,
+        verb: &'a str,
+        payload: serde_json::Value,
+    ) -> Pin<Box<dyn std::future::Future<Output = ServiceDispatchResult> + Send + 'a>> {
+        Box::pin(async move {
+            match verb {
+                "now" => self.handle_now(ctx, payload),
+                other => Err(ServiceError::new(
+                    ServiceErrorCode::NotImplemented,
+                    format!("host.time.v1: unknown verb `{other}`"),
+                )),
+            }
+        })
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use mvm_core::protocol::broker::CorrelationId;
+    use mvm_core::protocol::host_time::{NowResponse, verify_now_response};
+
+    use super::*;
+
+    fn ctx() -> ServiceCallCtx {
+        ServiceCallCtx {
+            workload_id: "wl-001".into(),
+            tenant_id: "t-001".into(),
+            correlation_id: CorrelationId::new("01HCORR0000000000000000"),
+            session_id: "sess-001".into(),
+            profile: AgentProfile::Dev,
+            composition_depth: 0,
+            composition_width: 0,
+        }
+    }
+
+    async fn now(handler: &HostTimeV1Handler, nonce: Option<&str>) -> NowResponse {
+        let payload = serde_json::to_value(NowRequest {
+            nonce: nonce.map(str::to_string),
+        })
+        .unwrap();
+        let value = handler.dispatch(&ctx(), "now", payload).await.unwrap();
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn now_returns_sane_signed_time() {
+        let handler = HostTimeV1Handler::new();
+        let resp = now(&handler, Some("n-1")).await;
+        let key = verify_now_response(&resp, Some(&handler.verifying_key()), Some("n-1")).unwrap();
+        assert_eq!(key, handler.verifying_key());
+
+        let host_now = duration_ns(
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap(),
+        );
+        assert!(host_now.abs_diff(resp.reading.wall_unix_ns) < 5_000_000_000);
+        assert_eq!(resp.reading.workload_id, "wl-001");
+    }
+
+    #[tokio::test]
+    async fn monotonic_and_seq_advance() {
+        let handler = HostTimeV1Handler::new();
+        let a = now(&handler, None).await.reading;
+        let b = now(&handler, None).await.reading;
+        assert!(b.monotonic_ns >= a.monotonic_ns);
+        assert_eq!(b.seq, a.seq + 1);
+    }
+
+    #[tokio::test]
+    async fn oversize_nonce_is_bad_request() {
+        let handler = HostTimeV1Handler::new();
+        let payload = serde_json::json!({ "nonce": "x".repeat(HOST_TIME_NONCE_MAX_BYTES + 1) });
+        let err = handler.dispatch(&ctx(), "now", payload).await.unwrap_err();
+        assert_eq!(err.code, ServiceErrorCode::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn unknown_field_and_verb_are_refused() {
+        let handler = HostTimeV1Handler::new();
+        let err = handler
+            .dispatch(&ctx(), "now", serde_json::json!({ "workload_id": "spoof" }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ServiceErrorCode::BadRequest);
+        let err = handler
+            .dispatch(&ctx(), "set", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ServiceErrorCode::NotImplemented);
+    }
+}
@@ -3,4 +3,7 @@
 //! Each submodule is one handler; the binary's `main` wires them into
 //! the [`crate::registry::Registry`] at startup.
 
+pub mod broker_v1;
 pub mod host_audit_v1;
+pub mod host_cost_v1;
+pub mod host_time_v1;
@@ -1,15 +1,16 @@
 //! Handler registry — the in-subprocess lookup that dispatches a
 //! `ServiceCall` to the right [`ServiceHandler`].
 //!
-//! W1a ships the registry shape with zero handlers registered (every
-//! call returns `Err(NotBound)`). W3 (`host.time.v1`), W4a
-//! (`host.cost.v1` workload verb), and the `broker.v1/list_services`
-//! introspection verb wire in their handlers via [`Registry::register`].
+//! The binary registers `host.time.v1`, `host.cost.v1`,
+//! `host.audit.v1`, and finally `broker.v1` (whose `list_services`
+//! reads [`Registry::descriptors`]) via [`Registry::register`]. Any
+//! service not registered returns `Err(NotBound)`.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use mvm_core::protocol::broker::{ServiceErrorCode, ServiceId};
+use mvm_core::protocol::broker_meta::ServiceDescriptor;
 use mvm_core::protocol::handler::{
     ServiceCallCtx, ServiceDispatchResult, ServiceError, ServiceHandler,
 };
@@ -62,6 +63,27 @@ impl Registry {
     pub fn is_empty(&self) -> bool {
         self.handlers.is_empty()
     }
+
+    /// Descriptors for every registered handler, sorted by service id.
+    pub fn descriptors(&self) -> Vec<ServiceDescriptor> {
+        let mut out: Vec<_> = self
+            .handlers
+            .values()
+            .map(|h| describe(h.as_ref()))
+            .collect();
+        out.sort_by(|a, b| a.service.as_str().cmp(b.service.as_str()));
+        out
+    }
+}
+
+/// The `broker.v1/list_services` view of one handler's static metadata.
+pub fn describe(handler: &dyn ServiceHandler) -> ServiceDescriptor {
+    ServiceDescriptor {
+        service: handler.id(),
+        verbs: handler.verbs().iter().map(|v| v.to_string()).collect(),
+        profiles: handler.profiles().to_vec(),
+        deprecated_in: handler.deprecated_in().map(str::to_string),
+    }
 }
 
 impl Default for Registry {
@@ -153,6 +175,18 @@ mod tests {
         assert_eq!(result, payload);
     }
 
+    #[test]
+    fn descriptors_report_handler_metadata() {
+        let mut registry = Registry::new();
+        registry.register(Arc::new(EchoHandler));
+        let descriptors = registry.descriptors();
+        assert_eq!(descriptors.len(), 1);
+        assert_eq!(descriptors[0].service.as_str(), "host.dev.echo.v1");
+        assert_eq!(descriptors[0].profiles, vec![AgentProfile::Dev]);
+        assert!(descriptors[0].verbs.is_empty());
+        assert!(descriptors[0].deprecated_in.is_none());
+    }
+
     #[test]
     fn empty_registry_reports_empty() {
         assert!(Registry::new().is_empty());
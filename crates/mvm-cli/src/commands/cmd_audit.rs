@@ -184,6 +184,7 @@ impl Commands {
             Commands::Snapshot(_) => "snapshot",
             Commands::Volume(_) => "volume",
             Commands::Secret(_) => "secret",
+            Commands::Approvals(_) => "approvals",
             Commands::Attest(_) => "attest",
             Commands::Bundle(_) => "bundle",
             Commands::Trust(_) => "trust",
@@ -2,17 +2,28 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::io::Cursor;
+use std::io::{Cursor, IsTerminal, Read};
 use std::path::{Component, Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
+use base64::Engine as _;
 use clap::{Args as ClapArgs, Subcommand};
+use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
 use flate2::read::GzDecoder;
 use mvm_build::rootfs::{MaterializeExt4Input, MaterializeExt4Options, materialize_ext4};
 use mvm_oci::{
-    ImageReference, LayerDescriptor, LayerFetchOptions, LinuxPlatform, OciLayerFetcher,
-    OciManifestFetcher, RegistryAuthConfig, UnpackOptions, unpack_layer, verify_sha256_digest,
+    ClientProtocol, CosignSignatures, ImageReference, LayerDescriptor, LayerFetchOptions,
+    LinuxPlatform, OciLayerFetcher, OciManifestFetcher, RegistryAuthConfig, UnpackOptions,
+    exchange_identity_token, unpack_layer, verify_sha256_digest,
 };
+use mvm_security::image_verify::{
+    CosignEvidence, CosignTrust, VerifyError, verify_oci_image_signature,
+};
+use mvm_security::registry_credentials::{
+    DockerCredentialHelper, RegistryCredential, RegistryCredentialStore, registry_entry_name,
+};
+use mvm_security::secret_store::{self, SecretStore};
+use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -36,7 +47,7 @@ pub(in crate::commands) enum ImageAction {
     Pull {
         /// OCI image reference
         reference: String,
-        /// Production policy: require an immutable digest-pinned reference
+        /// Production policy: require an immutable digest-pinned, cosign-signed reference
         #[arg(long)]
         prod: bool,
     },
@@ -62,6 +73,27 @@ pub(in crate::commands) enum ImageAction {
         /// Image reference or resolved digest
         reference: String,
     },
+    /// Store a registry credential in the mvm secret store. The
+    /// secret is prompted for on a TTY and read from stdin otherwise
+    Login {
+        /// Registry host (e.g. ghcr.io, registry.example.com:5000)
+        registry: String,
+        /// Username for basic auth (password or access token as the secret)
+        #[arg(
+            long,
+            conflicts_with = "identity_token",
+            required_unless_present = "identity_token"
+        )]
+        username: Option<String>,
+        /// The secret is an OAuth2 identity (refresh) token
+        #[arg(long)]
+        identity_token: bool,
+    },
+    /// Remove a stored registry credential
+    Logout {
+        /// Registry host
+        registry: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -103,6 +135,9 @@ pub(in crate::commands) struct ResolvedOciRunImage {
     pub pulled: bool,
     pub provenance: OciProvenance,
     pub auth_source: Option<String>,
+    /// Verified [`SignatureRecord`] for prod runs; plan admission
+    /// re-verifies it through `SignedImageRef.cosign_bundle`.
+    pub signature_record: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -206,13 +241,19 @@ impl OciTrustDecision {
         }
     }
 
-    fn cosign_verified(identity: &CosignIdentity) -> Self {
-        Self {
-            trust_policy: "prod-cosign-required".to_string(),
-            verification_status: format!(
+    fn cosign_verified(anchor: CosignAnchor<'_>) -> Self {
+        let verification_status = match anchor {
+            CosignAnchor::Identity(identity) => format!(
                 "cosign-verified identity={} issuer={}",
                 identity.certificate_identity, identity.certificate_oidc_issuer
             ),
+            CosignAnchor::Key(key) => {
+                format!("cosign-verified key={}", key.public_key.display())
+            }
+        };
+        Self {
+            trust_policy: "prod-cosign-required".to_string(),
+            verification_status,
         }
     }
 }
@@ -226,6 +267,22 @@ struct OciRegistryPolicy {
     require_signatures: bool,
     #[serde(default)]
     cosign: Vec<CosignIdentity>,
+    /// `[[cosign_key]]` — public keys for `cosign sign --key`
+    /// signatures, checked in-process.
+    #[serde(default)]
+    cosign_key: Vec<CosignKey>,
+    /// Sigstore `trusted_root.json`. When set, `[[cosign]]` identities
+    /// are checked in-process against the image's Sigstore bundles,
+    /// offline. Required for `[[cosign]]` identities unless
+    /// `online_keyless_verification` opts into `cosign verify`.
+    #[serde(default)]
+    trusted_root: Option<PathBuf>,
+    /// Verify `[[cosign]]` identities with `cosign verify`, which
+    /// consults Rekor and Fulcio over the network, when no
+    /// `trusted_root` is pinned. Off by default: without it a keyless
+    /// policy with no trusted root is refused.
+    #[serde(default)]
+    online_keyless_verification: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -235,12 +292,36 @@ struct CosignIdentity {
     certificate_oidc_issuer: String,
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct CosignKey {
+    /// PEM public key (`cosign.pub`).
+    public_key: PathBuf,
+}
+
+/// One trust anchor from the policy. Identities are tried before keys.
+#[derive(Debug, Clone, Copy)]
+enum CosignAnchor<'a> {
+    Identity(&'a CosignIdentity),
+    Key(&'a CosignKey),
+}
+
+impl OciRegistryPolicy {
+    fn cosign_anchors(&self) -> Vec<CosignAnchor<'_>> {
+        self.cosign
+            .iter()
+            .map(CosignAnchor::Identity)
+            .chain(self.cosign_key.iter().map(CosignAnchor::Key))
+            .collect()
+    }
+}
+
 fn default_require_signatures() -> bool {
     true
 }
 
 trait CosignVerifier {
-    fn verify(&self, reference: &str, identity: &CosignIdentity) -> Result<(), CosignVerifyError>;
+    fn verify(&self, reference: &str, anchor: CosignAnchor<'_>) -> Result<(), CosignVerifyError>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -248,6 +329,7 @@ enum CosignVerifyError {
     MissingSignature(String),
     InvalidSignature(String),
     ToolUnavailable(String),
+    AnchorUnavailable(String),
 }
 
 impl std::fmt::Display for CosignVerifyError {
@@ -256,6 +338,7 @@ impl std::fmt::Display for CosignVerifyError {
             Self::MissingSignature(msg) => write!(f, "missing signature: {msg}"),
             Self::InvalidSignature(msg) => write!(f, "invalid signature: {msg}"),
             Self::ToolUnavailable(msg) => write!(f, "cosign unavailable: {msg}"),
+            Self::AnchorUnavailable(msg) => write!(f, "trust anchor unavailable: {msg}"),
         }
     }
 }
@@ -263,21 +346,26 @@ impl std::fmt::Display for CosignVerifyError {
 struct CosignCommandVerifier;
 
 impl CosignVerifier for CosignCommandVerifier {
-    fn verify(&self, reference: &str, identity: &CosignIdentity) -> Result<(), CosignVerifyError> {
+    fn verify(&self, reference: &str, anchor: CosignAnchor<'_>) -> Result<(), CosignVerifyError> {
         let cosign = which::which("cosign").map_err(|e| {
             CosignVerifyError::ToolUnavailable(format!(
-                "cosign is required for production OCI policy; install cosign or run without --prod ({e})"
+                "online_keyless_verification needs the cosign CLI; \
+                 install cosign or pin a trusted_root in the OCI policy ({e})"
             ))
         })?;
-        let output = std::process::Command::new(cosign)
-            .args([
-                "verify",
+        let mut command = std::process::Command::new(cosign);
+        command.arg("verify");
+        match anchor {
+            CosignAnchor::Identity(identity) => command.args([
                 "--certificate-identity",
                 &identity.certificate_identity,
                 "--certificate-oidc-issuer",
                 &identity.certificate_oidc_issuer,
-                reference,
-            ])
+            ]),
+            CosignAnchor::Key(key) => command.arg("--key").arg(&key.public_key),
+        };
+        let output = command
+            .arg(reference)
             .output()
             .map_err(|e| CosignVerifyError::ToolUnavailable(e.to_string()))?;
         if output.status.success() {
@@ -306,14 +394,548 @@ impl CosignVerifier for CosignCommandVerifier {
     }
 }
 
+/// Checks signatures in-process against a [`SignatureRecord`].
+/// Public keys, and keyless identities when the policy pins a
+/// `trusted_root`, never leave the process or touch the network.
+/// Keyless identities without a trusted root go to `fallback`
+/// (`cosign verify`, which consults Rekor online) only when the
+/// policy sets `online_keyless_verification`.
+struct OciSignatureVerifier<'a> {
+    manifest_digest: String,
+    manifest_bytes: Vec<u8>,
+    evidence: Vec<CosignEvidence>,
+    trusted_root: Option<Vec<u8>>,
+    online_fallback: bool,
+    fallback: &'a dyn CosignVerifier,
+}
+
+impl<'a> OciSignatureVerifier<'a> {
+    fn from_record(
+        record: &SignatureRecord,
+        policy: &OciRegistryPolicy,
+        fallback: &'a dyn CosignVerifier,
+    ) -> Result<Self> {
+        let trusted_root = match &policy.trusted_root {
+            Some(path) => Some(
+                fs::read(path)
+                    .with_context(|| format!("reading Sigstore trusted root {}", path.display()))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            manifest_digest: record.manifest_digest.clone(),
+            manifest_bytes: record.manifest_bytes()?,
+            evidence: record.evidence()?,
+            trusted_root,
+            online_fallback: policy.online_keyless_verification,
+            fallback,
+        })
+    }
+}
+
+impl CosignVerifier for OciSignatureVerifier<'_> {
+    fn verify(&self, reference: &str, anchor: CosignAnchor<'_>) -> Result<(), CosignVerifyError> {
+        let trust = match anchor {
+            CosignAnchor::Key(key) => CosignTrust::PublicKey {
+                pem: fs::read_to_string(&key.public_key).map_err(|e| {
+                    CosignVerifyError::AnchorUnavailable(format!(
+                        "reading cosign public key {}: {e}",
+                        key.public_key.display()
+                    ))
+                })?,
+            },
+            CosignAnchor::Identity(identity) => match &self.trusted_root {
+                Some(root) => CosignTrust::Keyless {
+                    trusted_root_json: root.clone(),
+                    certificate_identity: identity.certificate_identity.clone(),
+                    certificate_oidc_issuer: identity.certificate_oidc_issuer.clone(),
+                },
+                None if self.online_fallback => return self.fallback.verify(reference, anchor),
+                None => {
+                    return Err(CosignVerifyError::AnchorUnavailable(
+                        "keyless identities need a trusted_root in the OCI policy \
+                         (or online_keyless_verification = true)"
+                            .to_string(),
+                    ));
+                }
+            },
+        };
+        verify_oci_image_signature(
+            &self.manifest_digest,
+            &self.manifest_bytes,
+            &self.evidence,
+            &trust,
+        )
+        .map_err(|e| match e {
+            VerifyError::Unsigned { .. } => CosignVerifyError::MissingSignature(e.to_string()),
+            other => CosignVerifyError::InvalidSignature(other.to_string()),
+        })
+    }
+}
+
+/// Signature material collected for one image manifest, cached at
+/// `signatures/<manifest-hex>.cosign.json`. Untrusted input: every
+/// use re-verifies it under the current policy. Carries the manifest
+/// bytes so plan admission can re-verify from the record alone, via
+/// the path in `SignedImageRef.cosign_bundle`. The cosign signatures
+/// cover only the manifest; the rootfs binding is vouched for by the
+/// host signer instead.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct SignatureRecord {
+    schema_version: u32,
+    /// `registry/repository@digest` the signatures were fetched for.
+    reference: String,
+    manifest_digest: String,
+    /// Base64 manifest bytes.
+    manifest: String,
+    #[serde(default)]
+    simple_signing: Vec<RecordedSimpleSigning>,
+    /// Base64 Sigstore bundle JSON.
+    #[serde(default)]
+    bundles: Vec<String>,
+    /// Hex SHA-256 of the `rootfs.ext4` materialized from this
+    /// manifest. Plan admission requires it to equal the plan's
+    /// `SignedImageRef.sha256`, and trusts it only under a valid
+    /// `rootfs_signature`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rootfs_sha256: Option<String>,
+    /// Base64 Ed25519 signature by the host signer over
+    /// [`rootfs_binding_message`], made once the cosign signatures
+    /// verified and the rootfs was materialized from the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rootfs_signature: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct RecordedSimpleSigning {
+    /// Base64 simple-signing payload.
+    payload: String,
+    signature: String,
+}
+
+impl SignatureRecord {
+    fn new(
+        reference: String,
+        manifest_digest: &str,
+        manifest_bytes: &[u8],
+        signatures: &CosignSignatures,
+    ) -> Self {
+        let b64 = base64::engine::general_purpose::STANDARD;
+        Self {
+            schema_version: 1,
+            reference,
+            manifest_digest: manifest_digest.to_string(),
+            manifest: b64.encode(manifest_bytes),
+            simple_signing: signatures
+                .simple_signing
+                .iter()
+                .map(|sig| RecordedSimpleSigning {
+                    payload: b64.encode(&sig.payload),
+                    signature: sig.signature.clone(),
+                })
+                .collect(),
+            bundles: signatures
+                .bundles
+                .iter()
+                .map(|bundle| b64.encode(bundle))
+                .collect(),
+            rootfs_sha256: None,
+            rootfs_signature: None,
+        }
+    }
+
+    /// Bind the record to the rootfs materialized from its manifest,
+    /// signing the binding with the host signer.
+    fn bind_rootfs(&mut self, rootfs: &Path, signer: &SigningKey) -> Result<()> {
+        let sha = mvm_security::image_verify::sha256_file(rootfs)
+            .with_context(|| format!("hashing OCI rootfs at {}", rootfs.display()))?;
+        let signature = signer.sign(&rootfs_binding_message(
+            &self.reference,
+            &self.manifest_digest,
+            &sha,
+        ));
+        self.rootfs_signature =
+            Some(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()));
+        self.rootfs_sha256 = Some(sha);
+        Ok(())
+    }
+
+    /// Whether the rootfs binding carries a host signature that
+    /// verifies under `host_key`.
+    fn rootfs_binding_verifies(&self, host_key: &VerifyingKey) -> bool {
+        let (Some(sha), Some(signature)) = (
+            self.rootfs_sha256.as_deref(),
+            self.rootfs_signature.as_deref(),
+        ) else {
+            return false;
+        };
+        let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(signature) else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_slice(&bytes) else {
+            return false;
+        };
+        host_key
+            .verify(
+                &rootfs_binding_message(&self.reference, &self.manifest_digest, sha),
+                &signature,
+            )
+            .is_ok()
+    }
+
+    fn manifest_bytes(&self) -> Result<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.manifest)
+            .context("decoding manifest in signature record")
+    }
+
+    fn evidence(&self) -> Result<Vec<CosignEvidence>> {
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let mut evidence = Vec::with_capacity(self.simple_signing.len() + self.bundles.len());
+        for sig in &self.simple_signing {
+            evidence.push(CosignEvidence::SimpleSigning {
+                payload: b64
+                    .decode(&sig.payload)
+                    .context("decoding simple-signing payload in signature record")?,
+                signature_b64: sig.signature.clone(),
+            });
+        }
+        for bundle in &self.bundles {
+            evidence.push(CosignEvidence::Bundle {
+                bundle_json: b64
+                    .decode(bundle)
+                    .context("decoding sigstore bundle in signature record")?,
+            });
+        }
+        Ok(evidence)
+    }
+}
+
+/// Bytes the host signer signs to bind a verified manifest to the
+/// rootfs materialized from it.
+fn rootfs_binding_message(reference: &str, manifest_digest: &str, rootfs_sha256: &str) -> Vec<u8> {
+    format!("mvm.oci.rootfs-binding.v1\n{reference}\n{manifest_digest}\n{rootfs_sha256}\n")
+        .into_bytes()
+}
+
+fn signature_record_path(manifest_digest: &str) -> Result<String> {
+    Ok(format!(
+        "signatures/{}.cosign.json",
+        sha256_hex(manifest_digest)?
+    ))
+}
+
+fn load_signature_record(
+    cache_root: &Path,
+    manifest_digest: &str,
+) -> Result<Option<SignatureRecord>> {
+    let path = safe_cache_path(cache_root, &signature_record_path(manifest_digest)?)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    read_signature_record(&path).map(Some)
+}
+
+/// Digest-pinned reference of the image a signature record covers,
+/// for a plan that boots its rootfs (`mvmctl up --image-signature`).
+pub(in crate::commands) fn signature_record_reference(path: &Path) -> Result<String> {
+    Ok(read_signature_record(path)?.reference)
+}
+
+fn read_signature_record(path: &Path) -> Result<SignatureRecord> {
+    let bytes = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("parse cosign signature record {}", path.display()))
+}
+
+fn write_signature_record(cache_root: &Path, record: &SignatureRecord) -> Result<()> {
+    write_cache_file(
+        cache_root,
+        &signature_record_path(&record.manifest_digest)?,
+        &serde_json::to_vec_pretty(record).context("serialize cosign signature record")?,
+    )
+}
+
+/// Verify a signature record under the production policy, checking
+/// in-process where the policy allows it.
+fn enforce_signature_record(
+    image_ref: &ImageReference,
+    record: &SignatureRecord,
+    policy: &OciRegistryPolicy,
+) -> Result<OciTrustDecision> {
+    let verifier = OciSignatureVerifier::from_record(record, policy, &CosignCommandVerifier)?;
+    enforce_oci_trust_policy_with(image_ref, &record.manifest_digest, policy, &verifier)
+}
+
+/// Plan-admission gate for prod plans: `image.cosign_bundle` must
+/// point at a [`SignatureRecord`] in the image cache for the plan's
+/// exact image (see [`check_record_covers_image`]), and the record
+/// must verify under today's OCI policy. An image with no record is
+/// unsigned and refused. `host_key` yields the host signer's public
+/// key, which must have signed the record's rootfs binding.
+pub(in crate::commands) fn verify_plan_image_signature(
+    image: &mvm_plan::SignedImageRef,
+    host_key: impl FnOnce() -> Result<VerifyingKey>,
+) -> Result<()> {
+    let Some(record_path) = image.cosign_bundle.as_deref() else {
+        bail!(
+            "refusing unsigned image {} for a prod plan: no cosign signature record",
+            image.name
+        );
+    };
+    let record = read_cached_signature_record(&oci_cache_root(), Path::new(record_path))?;
+    let signed_ref = check_record_covers_image(image, record_path, &record, &host_key()?)?;
+    let policy = load_oci_registry_policy()?;
+    enforce_signature_record(&signed_ref, &record, &policy)?;
+    Ok(())
+}
+
+/// Read a signature record, refusing any that is not the one the
+/// image cache holds for its manifest digest.
+fn read_cached_signature_record(cache_root: &Path, path: &Path) -> Result<SignatureRecord> {
+    let record = read_signature_record(path)?;
+    let cached = safe_cache_path(cache_root, &signature_record_path(&record.manifest_digest)?)?;
+    let same_file = match (fs::canonicalize(path), fs::canonicalize(&cached)) {
+        (Ok(supplied), Ok(cached)) => supplied == cached,
+        _ => false,
+    };
+    if !same_file {
+        bail!(
+            "cosign signature record {} is not the image cache's record for {}; \
+             pass the path `mvmctl image pull --prod` printed",
+            path.display(),
+            record.manifest_digest
+        );
+    }
+    Ok(record)
+}
+
+/// A record covers a plan image when it is for the same repository,
+/// the plan pins the record's manifest digest (a tag could move), and
+/// the plan boots the rootfs the host signer bound to that manifest.
+fn check_record_covers_image(
+    image: &mvm_plan::SignedImageRef,
+    record_path: &str,
+    record: &SignatureRecord,
+    host_key: &VerifyingKey,
+) -> Result<ImageReference> {
+    let signed_ref: ImageReference = record.reference.parse()?;
+    let named: ImageReference = image.name.parse()?;
+    let Some(digest) = named.digest.as_deref() else {
+        bail!(
+            "prod plan image {} is not digest-pinned; a cosign signature record only covers a manifest digest",
+            image.name
+        );
+    };
+    if (named.registry.as_str(), named.repository.as_str())
+        != (signed_ref.registry.as_str(), signed_ref.repository.as_str())
+        || digest != record.manifest_digest
+    {
+        bail!(
+            "cosign signature record {} is for {}, not image {}",
+            record_path,
+            record.reference,
+            image.name
+        );
+    }
+    match record.rootfs_sha256.as_deref() {
+        Some(_) if !record.rootfs_binding_verifies(host_key) => bail!(
+            "cosign signature record {} has no valid host-signed rootfs binding; \
+             re-run `mvmctl image pull --prod {}`",
+            record_path,
+            record.reference
+        ),
+        Some(sha) if sha == image.sha256 => Ok(signed_ref),
+        Some(sha) => bail!(
+            "cosign signature record {} covers rootfs sha256 {}, but the plan boots {}",
+            record_path,
+            sha,
+            image.sha256
+        ),
+        None => bail!(
+            "cosign signature record {} names no rootfs; re-run `mvmctl image pull --prod {}`",
+            record_path,
+            record.reference
+        ),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct OciRegistryAuthDecision {
     auth: RegistryAuthConfig,
     source: String,
 }
 
+/// Per-registry credential sources, from `registry-auth.toml`. The
+/// mvm secret store is always consulted; helpers run only when named
+/// here, and are never discovered from Docker's config.
+///
+/// ```toml
+/// # Fallback helper for registries with no stored login.
+/// credential_helper = "desktop"
+///
+/// [registries."123456789012.dkr.ecr.us-east-1.amazonaws.com"]
+/// credential_helper = "ecr-login"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct RegistryAuthSettings {
+    #[serde(default)]
+    credential_helper: Option<String>,
+    #[serde(default)]
+    registries: BTreeMap<String, RegistryAuthEntry>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct RegistryAuthEntry {
+    /// `docker-credential-<name>` to ask before the secret store.
+    #[serde(default)]
+    credential_helper: Option<String>,
+}
+
+/// Resolve pull credentials for `image_ref`, first match wins:
+/// `MVM_OCI_BEARER_TOKEN_<REGISTRY>` / `MVM_OCI_BEARER_TOKEN`, the
+/// registry's own credential helper, the mvm secret store
+/// (`mvmctl image login`), the default credential helper, anonymous.
+/// Identity tokens are exchanged for a scoped bearer token here.
 fn registry_auth_for(image_ref: &ImageReference) -> Result<OciRegistryAuthDecision> {
-    registry_auth_from_lookup(image_ref, |name| std::env::var(name).ok())
+    let from_env = registry_auth_from_lookup(image_ref, |name| std::env::var(name).ok())?;
+    if from_env.auth.is_authenticated() {
+        return Ok(from_env);
+    }
+    let settings = load_registry_auth_settings()?;
+    let secrets = secret_store::default_secret_store();
+    let Some((credential, source)) =
+        stored_registry_credential(&image_ref.registry, &settings, secrets.as_ref(), |name| {
+            DockerCredentialHelper::new(name)
+        })?
+    else {
+        return Ok(from_env);
+    };
+    Ok(OciRegistryAuthDecision {
+        auth: registry_auth_config(image_ref, credential)
+            .with_context(|| format!("registry credential from {source}"))?,
+        source,
+    })
+}
+
+fn stored_registry_credential(
+    registry: &str,
+    settings: &RegistryAuthSettings,
+    secrets: &dyn SecretStore,
+    helper: impl Fn(&str) -> Result<DockerCredentialHelper>,
+) -> Result<Option<(RegistryCredential, String)>> {
+    let ask_helper = |name: &str| -> Result<Option<(RegistryCredential, String)>> {
+        let helper = helper(name)?;
+        Ok(helper
+            .get(&helper_server_url(registry))?
+            .map(|credential| (credential, format!("helper:{}", helper.name()))))
+    };
+    if let Some(name) = settings
+        .registries
+        .get(registry)
+        .and_then(|entry| entry.credential_helper.as_deref())
+        && let Some(found) = ask_helper(name)?
+    {
+        return Ok(Some(found));
+    }
+    if let Some(credential) = RegistryCredentialStore::new(secrets).get(registry)? {
+        return Ok(Some((credential, format!("store:{registry}"))));
+    }
+    match settings.credential_helper.as_deref() {
+        Some(name) => ask_helper(name),
+        None => Ok(None),
+    }
+}
+
+/// Server URL docker credential helpers key Docker Hub under; every
+/// other registry is keyed by its host.
+fn helper_server_url(registry: &str) -> String {
+    if registry == "docker.io" {
+        "https://index.docker.io/v1/".to_string()
+    } else {
+        registry.to_string()
+    }
+}
+
+fn registry_auth_config(
+    image_ref: &ImageReference,
+    credential: RegistryCredential,
+) -> Result<RegistryAuthConfig> {
+    Ok(match credential {
+        RegistryCredential::Basic { username, password } => {
+            RegistryAuthConfig::Basic { username, password }
+        }
+        RegistryCredential::Bearer { token } => RegistryAuthConfig::Bearer { token },
+        RegistryCredential::IdentityToken { token } => {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .context("build Tokio runtime for registry token exchange")?;
+            runtime.block_on(exchange_identity_token(
+                image_ref,
+                &token,
+                &ClientProtocol::Https,
+            ))?
+        }
+    })
+}
+
+fn load_registry_auth_settings() -> Result<RegistryAuthSettings> {
+    let path = match std::env::var_os("MVM_OCI_REGISTRY_AUTH") {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(mvm_core::config::mvm_data_dir()).join("registry-auth.toml"),
+    };
+    if !path.exists() {
+        return Ok(RegistryAuthSettings::default());
+    }
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("reading registry auth settings {}", path.display()))?;
+    parse_registry_auth_settings(&text)
+        .with_context(|| format!("parsing registry auth settings {}", path.display()))
+}
+
+fn parse_registry_auth_settings(text: &str) -> Result<RegistryAuthSettings> {
+    let settings: RegistryAuthSettings = toml::from_str(text)?;
+    let helpers = settings.credential_helper.iter().chain(
+        settings
+            .registries
+            .values()
+            .filter_map(|entry| entry.credential_helper.as_ref()),
+    );
+    for name in helpers {
+        DockerCredentialHelper::new(name)?;
+    }
+    for registry in settings.registries.keys() {
+        if normalize_registry_host(registry)? != *registry {
+            bail!("registry key {registry:?} must be a lowercase host[:port]");
+        }
+    }
+    Ok(settings)
+}
+
+/// Lowercase `registry` and check it is a bare `host[:port]`.
+fn normalize_registry_host(registry: &str) -> Result<String> {
+    let registry = registry.trim().to_ascii_lowercase();
+    registry_entry_name(&registry)?;
+    Ok(registry)
+}
+
+fn read_registry_secret(registry: &str) -> Result<String> {
+    if std::io::stdin().is_terminal() {
+        return inquire::Password::new(&format!("Secret for {registry}"))
+            .without_confirmation()
+            .prompt()
+            .context("reading registry secret from interactive prompt");
+    }
+    let mut buf = String::new();
+    std::io::stdin()
+        .read_to_string(&mut buf)
+        .context("reading registry secret from stdin")?;
+    Ok(buf.trim_end_matches(['\r', '\n']).to_string())
 }
 
 fn registry_auth_from_lookup(
@@ -415,6 +1037,13 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
                     cache_root.join(rootfs_path).display()
                 ));
             }
+            if prod {
+                ui::info(&format!(
+                    "Signature record: {} (pass to `mvmctl up --image-signature`)",
+                    safe_cache_path(&cache_root, &signature_record_path(&image.resolved_digest)?)?
+                        .display()
+                ));
+            }
             Ok(())
         }
         ImageAction::Ls { registry, json } => {
@@ -435,6 +1064,49 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
             }
             Ok(())
         }
+        ImageAction::Login {
+            registry,
+            username,
+            identity_token,
+        } => {
+            let registry = normalize_registry_host(&registry)?;
+            let secret = SecretString::from(read_registry_secret(&registry)?);
+            if secret.expose_secret().is_empty() {
+                bail!("refusing to store an empty secret for {registry}");
+            }
+            let credential = match username {
+                Some(username) if !identity_token => RegistryCredential::Basic {
+                    username,
+                    password: secret,
+                },
+                _ => RegistryCredential::IdentityToken { token: secret },
+            };
+            let secrets = secret_store::default_secret_store();
+            RegistryCredentialStore::new(secrets.as_ref()).put(&registry, &credential)?;
+            mvm_core::audit_emit!(
+                RegistryLogin,
+                "registry={} kind={}",
+                registry,
+                credential.kind()
+            );
+            ui::success(&format!(
+                "Stored {} credential for {registry}.",
+                credential.kind()
+            ));
+            Ok(())
+        }
+        ImageAction::Logout { registry } => {
+            let registry = normalize_registry_host(&registry)?;
+            let secrets = secret_store::default_secret_store();
+            let removed = RegistryCredentialStore::new(secrets.as_ref()).delete(&registry)?;
+            mvm_core::audit_emit!(RegistryLogout, "registry={} removed={}", registry, removed);
+            if removed {
+                ui::success(&format!("Removed stored credential for {registry}."));
+            } else {
+                ui::info(&format!("No stored credential for {registry}."));
+            }
+            Ok(())
+        }
         ImageAction::Rm { reference } => {
             let outcome = remove_image(&cache_root, &reference)?;
             ui::success(&format!(
@@ -497,7 +1169,15 @@ pub(in crate::commands) fn resolve_or_pull_run_image(
     }
     let trust = match trust_from_pull {
         Some(trust) => trust,
-        None => trust_decision_for_cached_image(&image_ref, &image, prod, &CosignCommandVerifier)?,
+        None => trust_decision_for_cached_image(cache_root, &image_ref, &image, prod)?,
+    };
+    let signature_record = if prod {
+        Some(safe_cache_path(
+            cache_root,
+            &signature_record_path(&image.resolved_digest)?,
+        )?)
+    } else {
+        None
     };
     Ok(ResolvedOciRunImage {
         provenance: image.provenance("run_image", reference, &trust),
@@ -506,6 +1186,7 @@ pub(in crate::commands) fn resolve_or_pull_run_image(
         rootfs_path,
         pulled,
         auth_source: auth_source_from_pull,
+        signature_record,
     })
 }
 
@@ -521,26 +1202,86 @@ fn pull_image_with_trust(
     pull_image_ref(cache_root, image_ref, reference, prod)
 }
 
+/// Prod trust for an image already in the cache. Uses the cached
+/// [`SignatureRecord`] when there is one (no network), otherwise
+/// collects the signatures from the registry and caches them once
+/// they verify.
 fn trust_decision_for_cached_image(
+    cache_root: &Path,
     image_ref: &ImageReference,
     image: &CachedOciImage,
     prod: bool,
-    verifier: &dyn CosignVerifier,
-) -> Result<OciTrustDecision> {
-    enforce_oci_trust_policy(image_ref, &image.resolved_digest, prod, verifier)
-}
-
-fn enforce_oci_trust_policy(
-    image_ref: &ImageReference,
-    resolved_digest: &str,
-    prod: bool,
-    verifier: &dyn CosignVerifier,
 ) -> Result<OciTrustDecision> {
     if !prod {
         return Ok(OciTrustDecision::dev_digest_only(image_ref));
     }
     let policy = load_oci_registry_policy()?;
-    enforce_oci_trust_policy_with(image_ref, resolved_digest, &policy, verifier)
+    enforce_registry_allowlist(image_ref, &policy)?;
+    ensure_signature_policy_is_configured(&policy)?;
+    if let Some(mut record) = load_signature_record(cache_root, &image.resolved_digest)? {
+        let trust = enforce_signature_record(image_ref, &record, &policy)?;
+        let host = super::vm::host_signer::load_or_init()?;
+        if !record.rootfs_binding_verifies(&host.verifying) {
+            cache_signature_record(cache_root, image, &mut record, &host.signing)?;
+        }
+        return Ok(trust);
+    }
+    let manifest_bytes =
+        read_verified_cache_file(cache_root, &image.manifest_path, &image.resolved_digest)?
+            .with_context(|| format!("cached manifest for {} is missing", image.reference))?;
+    let registry_auth = registry_auth_for(image_ref)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("build Tokio runtime for cosign signature fetch")?;
+    let manifest_fetcher = OciManifestFetcher::with_auth(registry_auth.auth);
+    let mut record = fetch_signature_record(
+        &runtime,
+        &manifest_fetcher,
+        image_ref,
+        &image.resolved_digest,
+        &manifest_bytes,
+    )?;
+    let trust = enforce_signature_record(image_ref, &record, &policy)?;
+    let host = super::vm::host_signer::load_or_init()?;
+    cache_signature_record(cache_root, image, &mut record, &host.signing)?;
+    Ok(trust)
+}
+
+/// Bind a verified record to `image`'s materialized rootfs, when
+/// there is one, and cache it.
+fn cache_signature_record(
+    cache_root: &Path,
+    image: &CachedOciImage,
+    record: &mut SignatureRecord,
+    signer: &SigningKey,
+) -> Result<()> {
+    if let Some(rootfs) = image.rootfs_path.as_deref() {
+        record.bind_rootfs(&safe_cache_path(cache_root, rootfs)?, signer)?;
+    }
+    write_signature_record(cache_root, record)
+}
+
+/// Collect the cosign signatures published for `resolved_digest`.
+fn fetch_signature_record(
+    runtime: &tokio::runtime::Runtime,
+    manifest_fetcher: &OciManifestFetcher,
+    image_ref: &ImageReference,
+    resolved_digest: &str,
+    manifest_bytes: &[u8],
+) -> Result<SignatureRecord> {
+    let mut signed_ref = image_ref.clone();
+    signed_ref.tag = None;
+    signed_ref.digest = Some(resolved_digest.to_string());
+    let signatures = runtime
+        .block_on(manifest_fetcher.fetch_cosign_signatures(&signed_ref))
+        .with_context(|| format!("fetch cosign signatures for {}", signed_ref.canonical()))?;
+    Ok(SignatureRecord::new(
+        cosign_verification_reference(image_ref, resolved_digest),
+        resolved_digest,
+        manifest_bytes,
+        &signatures,
+    ))
 }
 
 fn enforce_oci_trust_policy_with(
@@ -553,9 +1294,9 @@ fn enforce_oci_trust_policy_with(
     ensure_signature_policy_is_configured(policy)?;
     let verification_ref = cosign_verification_reference(image_ref, resolved_digest);
     let mut failures = Vec::new();
-    for identity in &policy.cosign {
-        match verifier.verify(&verification_ref, identity) {
-            Ok(()) => return Ok(OciTrustDecision::cosign_verified(identity)),
+    for anchor in policy.cosign_anchors() {
+        match verifier.verify(&verification_ref, anchor) {
+            Ok(()) => return Ok(OciTrustDecision::cosign_verified(anchor)),
             Err(err) => failures.push(err.to_string()),
         }
     }
@@ -588,8 +1329,21 @@ fn ensure_signature_policy_is_configured(policy: &OciRegistryPolicy) -> Result<(
     if !policy.require_signatures {
         bail!("production OCI policy cannot disable cosign signatures");
     }
-    if policy.cosign.is_empty() {
-        bail!("production OCI policy requires signatures but has no [[cosign]] trusted identity");
+    if policy.cosign.is_empty() && policy.cosign_key.is_empty() {
+        bail!(
+            "production OCI policy requires signatures but has no [[cosign]] trusted identity \
+             or [[cosign_key]] public key"
+        );
+    }
+    if !policy.cosign.is_empty()
+        && policy.trusted_root.is_none()
+        && !policy.online_keyless_verification
+    {
+        bail!(
+            "production OCI policy has [[cosign]] identities but no trusted_root; pin a \
+             Sigstore trusted_root for offline verification, or set \
+             online_keyless_verification = true to verify through Rekor with `cosign verify`"
+        );
     }
     Ok(())
 }
@@ -648,6 +1402,18 @@ fn validate_oci_registry_policy(policy: &OciRegistryPolicy) -> Result<()> {
             bail!("invalid empty or control-character cosign identity in OCI policy");
         }
     }
+    for key in &policy.cosign_key {
+        if key.public_key.as_os_str().is_empty() {
+            bail!("invalid empty [[cosign_key]] public_key path in OCI policy");
+        }
+    }
+    if policy
+        .trusted_root
+        .as_ref()
+        .is_some_and(|path| path.as_os_str().is_empty())
+    {
+        bail!("invalid empty trusted_root path in OCI policy");
+    }
     Ok(())
 }
 
@@ -686,14 +1452,19 @@ fn pull_image_ref(
         );
     }
 
-    let trust = match &prod_policy {
-        Some(policy) => enforce_oci_trust_policy_with(
-            &image_ref,
-            &manifest.digest,
-            policy,
-            &CosignCommandVerifier,
-        )?,
-        None => OciTrustDecision::dev_digest_only(&image_ref),
+    let (trust, signature_record) = match &prod_policy {
+        Some(policy) => {
+            let record = fetch_signature_record(
+                &runtime,
+                &manifest_fetcher,
+                &image_ref,
+                &manifest.digest,
+                &manifest.bytes,
+            )?;
+            let trust = enforce_signature_record(&image_ref, &record, policy)?;
+            (trust, Some(record))
+        }
+        None => (OciTrustDecision::dev_digest_only(&image_ref), None),
     };
 
     let manifest_hex = sha256_hex(&manifest.digest)?;
@@ -736,10 +1507,15 @@ fn pull_image_ref(
     let rootfs_path = format!("rootfs/{manifest_hex}/rootfs.ext4");
     let rootfs_abs = cache_root.join(&rootfs_path);
     materialize_ext4(
-        &MaterializeExt4Input::new(unpacked_root, rootfs_abs, unpacked_size),
+        &MaterializeExt4Input::new(unpacked_root, rootfs_abs.clone(), unpacked_size),
         &MaterializeExt4Options::default(),
     )
     .context("materialize OCI rootfs.ext4")?;
+    if let Some(mut record) = signature_record {
+        let host = super::vm::host_signer::load_or_init()?;
+        record.bind_rootfs(&rootfs_abs, &host.signing)?;
+        write_signature_record(cache_root, &record)?;
+    }
 
     let provenance = OciProvenance {
         schema_version: 1,
@@ -1289,7 +2065,7 @@ mod tests {
         fn verify(
             &self,
             _reference: &str,
-            _identity: &CosignIdentity,
+            _anchor: CosignAnchor<'_>,
         ) -> Result<(), CosignVerifyError> {
             self.results.borrow_mut().remove(0)
         }
@@ -1426,6 +2202,7 @@ mod tests {
         r#"
 allowed_registries = ["docker.io", "ghcr.io"]
 require_signatures = true
+online_keyless_verification = true
 
 [[cosign]]
 certificate_identity = "https://github.com/tinylabscom/mvm/.github/workflows/release.yml@refs/tags/v0.14.0"
@@ -1552,6 +2329,501 @@ require_signatures = false
         );
     }
 
+    const SIGNED_MANIFEST: &[u8] = br#"{"schemaVersion":2,"layers":[]}"#;
+
+    fn signed_manifest_digest() -> String {
+        format!(
+            "sha256:{}",
+            hex::encode(<sha2::Sha256 as sha2::Digest>::digest(SIGNED_MANIFEST))
+        )
+    }
+
+    /// Writes the public half of a fixed P-256 key to `dir` and
+    /// returns `(key path, cosign signatures over the test manifest)`.
+    fn keyed_signature(dir: &Path) -> (PathBuf, CosignSignatures) {
+        use p256::ecdsa::signature::Signer as _;
+        use p256::pkcs8::{EncodePublicKey as _, LineEnding};
+
+        let key = p256::ecdsa::SigningKey::from_bytes(&[7u8; 32].into()).expect("valid scalar");
+        let key_path = dir.join("cosign.pub");
+        fs::write(
+            &key_path,
+            key.verifying_key()
+                .to_public_key_pem(LineEnding::LF)
+                .expect("pem encodes"),
+        )
+        .expect("write public key");
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "critical": {
+                "identity": { "docker-reference": "ghcr.io/acme/app" },
+                "image": { "docker-manifest-digest": signed_manifest_digest() },
+                "type": "cosign container image signature"
+            },
+            "optional": null
+        }))
+        .expect("payload serializes");
+        let signature: p256::ecdsa::Signature = key.sign(&payload);
+        let signatures = CosignSignatures {
+            simple_signing: vec![mvm_oci::SimpleSigningSignature {
+                payload,
+                signature: base64::engine::general_purpose::STANDARD
+                    .encode(signature.to_der().as_bytes()),
+            }],
+            bundles: Vec::new(),
+        };
+        (key_path, signatures)
+    }
+
+    fn keyed_policy(key_path: &Path) -> OciRegistryPolicy {
+        parse_oci_registry_policy(&format!(
+            "allowed_registries = [\"ghcr.io\"]\n\n[[cosign_key]]\npublic_key = {:?}\n",
+            key_path.display().to_string()
+        ))
+        .expect("policy parses")
+    }
+
+    fn signed_ref() -> ImageReference {
+        format!("ghcr.io/acme/app@{}", signed_manifest_digest())
+            .parse()
+            .expect("valid image ref")
+    }
+
+    #[test]
+    fn oci_policy_parses_cosign_keys_and_trusted_root() {
+        let policy = parse_oci_registry_policy(
+            r#"
+trusted_root = "/etc/mvm/trusted_root.json"
+
+[[cosign_key]]
+public_key = "/etc/mvm/cosign.pub"
+"#,
+        )
+        .expect("key-only policy parses");
+
+        assert!(policy.cosign.is_empty());
+        assert_eq!(
+            policy.cosign_key[0].public_key,
+            PathBuf::from("/etc/mvm/cosign.pub")
+        );
+        assert_eq!(
+            policy.trusted_root.as_deref(),
+            Some(Path::new("/etc/mvm/trusted_root.json"))
+        );
+    }
+
+    #[test]
+    fn keyed_signature_record_verifies_in_process() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let (key_path, signatures) = keyed_signature(tmp.path());
+        let record = SignatureRecord::new(
+            format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &signed_manifest_digest(),
+            SIGNED_MANIFEST,
+            &signatures,
+        );
+
+        let trust = enforce_signature_record(&signed_ref(), &record, &keyed_policy(&key_path))
+            .expect("keyed signature verifies");
+
+        assert_eq!(trust.trust_policy, "prod-cosign-required");
+        assert!(
+            trust
+                .verification_status
+                .starts_with("cosign-verified key="),
+            "got {}",
+            trust.verification_status
+        );
+    }
+
+    #[test]
+    fn unsigned_record_is_refused_under_production_policy() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let (key_path, _) = keyed_signature(tmp.path());
+        let record = SignatureRecord::new(
+            format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &signed_manifest_digest(),
+            SIGNED_MANIFEST,
+            &CosignSignatures::default(),
+        );
+
+        let err = enforce_signature_record(&signed_ref(), &record, &keyed_policy(&key_path))
+            .expect_err("unsigned image refused");
+
+        assert!(
+            err.to_string().contains("missing signature"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn keyless_identity_without_trusted_root_falls_back_to_cosign() {
+        let policy = parse_oci_registry_policy(policy_text()).expect("policy parses");
+        let record = SignatureRecord::new(
+            format!("docker.io/library/alpine@{}", signed_manifest_digest()),
+            &signed_manifest_digest(),
+            SIGNED_MANIFEST,
+            &CosignSignatures::default(),
+        );
+        let fallback = MockCosignVerifier::new(vec![Ok(())]);
+        let verifier =
+            OciSignatureVerifier::from_record(&record, &policy, &fallback).expect("verifier");
+        let image_ref: ImageReference =
+            format!("docker.io/library/alpine@{}", signed_manifest_digest())
+                .parse()
+                .expect("valid image ref");
+
+        let trust = enforce_oci_trust_policy_with(
+            &image_ref,
+            &signed_manifest_digest(),
+            &policy,
+            &verifier,
+        )
+        .expect("fallback verifier accepted");
+
+        assert!(
+            fallback.results.borrow().is_empty(),
+            "cosign fallback must run"
+        );
+        assert!(trust.verification_status.contains("identity="));
+    }
+
+    fn host_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    /// A record bound by [`host_key`] to a rootfs with the given hash.
+    fn bound_record(rootfs_sha256: &str) -> SignatureRecord {
+        let mut record = SignatureRecord::new(
+            format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &signed_manifest_digest(),
+            SIGNED_MANIFEST,
+            &CosignSignatures::default(),
+        );
+        let signature = host_key().sign(&rootfs_binding_message(
+            &record.reference,
+            &record.manifest_digest,
+            rootfs_sha256,
+        ));
+        record.rootfs_sha256 = Some(rootfs_sha256.to_string());
+        record.rootfs_signature =
+            Some(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()));
+        record
+    }
+
+    fn plan_image(name: &str, sha256: &str) -> mvm_plan::SignedImageRef {
+        mvm_plan::SignedImageRef {
+            name: name.to_string(),
+            sha256: sha256.to_string(),
+            cosign_bundle: Some("record.json".to_string()),
+        }
+    }
+
+    #[test]
+    fn signature_record_covers_the_pinned_digest_and_rootfs() {
+        let rootfs = "ab".repeat(32);
+        let image = plan_image(
+            &format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &rootfs,
+        );
+
+        let signed = check_record_covers_image(
+            &image,
+            "record.json",
+            &bound_record(&rootfs),
+            &host_key().verifying_key(),
+        )
+        .expect("record covers the plan image");
+
+        assert_eq!(signed, signed_ref());
+    }
+
+    #[test]
+    fn signature_record_for_another_digest_of_the_repo_is_refused() {
+        let rootfs = "ab".repeat(32);
+        let other_digest = format!("sha256:{}", "cd".repeat(32));
+        let image = plan_image(&format!("ghcr.io/acme/app@{other_digest}"), &rootfs);
+
+        let err = check_record_covers_image(
+            &image,
+            "record.json",
+            &bound_record(&rootfs),
+            &host_key().verifying_key(),
+        )
+        .expect_err("record for a different digest must not cover the plan");
+
+        assert!(err.to_string().contains("not image"), "got {err}");
+    }
+
+    #[test]
+    fn tag_only_plan_image_is_refused() {
+        let rootfs = "ab".repeat(32);
+        let image = plan_image("ghcr.io/acme/app:latest", &rootfs);
+
+        let err = check_record_covers_image(
+            &image,
+            "record.json",
+            &bound_record(&rootfs),
+            &host_key().verifying_key(),
+        )
+        .expect_err("a tag cannot be bound to a signature");
+
+        assert!(err.to_string().contains("not digest-pinned"), "got {err}");
+    }
+
+    #[test]
+    fn signature_record_for_another_rootfs_is_refused() {
+        let image = plan_image(
+            &format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &"ef".repeat(32),
+        );
+
+        let host = host_key().verifying_key();
+
+        let err = check_record_covers_image(
+            &image,
+            "record.json",
+            &bound_record(&"ab".repeat(32)),
+            &host,
+        )
+        .expect_err("record must cover the rootfs the plan boots");
+        assert!(err.to_string().contains("but the plan boots"), "got {err}");
+
+        let mut unbound = bound_record("");
+        unbound.rootfs_sha256 = None;
+        let err = check_record_covers_image(&image, "record.json", &unbound, &host)
+            .expect_err("a record with no rootfs covers nothing");
+        assert!(err.to_string().contains("names no rootfs"), "got {err}");
+    }
+
+    #[test]
+    fn rewritten_rootfs_binding_is_refused() {
+        let rootfs = "ef".repeat(32);
+        let image = plan_image(
+            &format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &rootfs,
+        );
+        // A genuinely signed record edited to name another rootfs.
+        let mut forged = bound_record(&"ab".repeat(32));
+        forged.rootfs_sha256 = Some(rootfs.clone());
+
+        let err =
+            check_record_covers_image(&image, "record.json", &forged, &host_key().verifying_key())
+                .expect_err("the host signature must cover the rootfs hash");
+        assert!(
+            err.to_string().contains("host-signed rootfs binding"),
+            "got {err}"
+        );
+
+        let other_host = SigningKey::from_bytes(&[9u8; 32]).verifying_key();
+        let err =
+            check_record_covers_image(&image, "record.json", &bound_record(&rootfs), &other_host)
+                .expect_err("a binding from another host signer is not trusted");
+        assert!(
+            err.to_string().contains("host-signed rootfs binding"),
+            "got {err}"
+        );
+    }
+
+    #[test]
+    fn signature_record_outside_the_image_cache_is_refused() {
+        let cache = tempfile::tempdir().expect("cache");
+        let elsewhere = tempfile::tempdir().expect("elsewhere");
+        let record = bound_record(&"ab".repeat(32));
+        write_signature_record(cache.path(), &record).expect("write record");
+        let copy = elsewhere.path().join("record.cosign.json");
+        fs::write(&copy, serde_json::to_vec(&record).expect("serialize")).expect("write copy");
+
+        let err = read_cached_signature_record(cache.path(), &copy)
+            .expect_err("a copy outside the cache is refused");
+        assert!(
+            err.to_string().contains("not the image cache's record"),
+            "got {err}"
+        );
+
+        let cached = safe_cache_path(
+            cache.path(),
+            &signature_record_path(&signed_manifest_digest()).expect("path"),
+        )
+        .expect("cache path");
+        let loaded = read_cached_signature_record(cache.path(), &cached).expect("cached record");
+        assert_eq!(loaded, record);
+    }
+
+    #[test]
+    fn keyless_policy_without_trusted_root_requires_online_opt_in() {
+        let text = policy_text().replace("online_keyless_verification = true\n", "");
+
+        let err = parse_oci_registry_policy(&text)
+            .expect_err("keyless policy without a trusted root must opt into online checks");
+
+        assert!(err.to_string().contains("no trusted_root"), "got {err}");
+    }
+
+    #[test]
+    fn signature_record_round_trips_through_the_cache() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let (_, signatures) = keyed_signature(tmp.path());
+        let record = SignatureRecord::new(
+            format!("ghcr.io/acme/app@{}", signed_manifest_digest()),
+            &signed_manifest_digest(),
+            SIGNED_MANIFEST,
+            &signatures,
+        );
+
+        write_signature_record(tmp.path(), &record).expect("write record");
+        let loaded = load_signature_record(tmp.path(), &signed_manifest_digest())
+            .expect("load record")
+            .expect("record present");
+
+        assert_eq!(loaded, record);
+        assert_eq!(loaded.manifest_bytes().expect("manifest"), SIGNED_MANIFEST);
+        assert_eq!(loaded.evidence().expect("evidence").len(), 1);
+    }
+
+    fn fake_credential_helper(dir: &Path, name: &str, username: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(format!("docker-credential-{name}"));
+        fs::write(
+            &path,
+            format!("#!/bin/sh\nprintf '{{\"Username\":\"{username}\",\"Secret\":\"s\"}}'\n"),
+        )
+        .expect("write helper");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).expect("chmod helper");
+        path
+    }
+
+    fn helper_in(dir: &Path) -> impl Fn(&str) -> Result<DockerCredentialHelper> + '_ {
+        move |name| {
+            Ok(DockerCredentialHelper::new(name)?
+                .with_program(dir.join(format!("docker-credential-{name}"))))
+        }
+    }
+
+    fn basic_username(found: Option<(RegistryCredential, String)>) -> (String, String) {
+        match found {
+            Some((RegistryCredential::Basic { username, .. }, source)) => (username, source),
+            other => panic!("expected a basic credential, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn registry_auth_settings_parse_default_and_per_registry_helpers() {
+        let settings = parse_registry_auth_settings(
+            r#"
+credential_helper = "desktop"
+
+[registries."123456789012.dkr.ecr.us-east-1.amazonaws.com"]
+credential_helper = "ecr-login"
+"#,
+        )
+        .expect("settings parse");
+
+        assert_eq!(settings.credential_helper.as_deref(), Some("desktop"));
+        assert_eq!(
+            settings.registries["123456789012.dkr.ecr.us-east-1.amazonaws.com"]
+                .credential_helper
+                .as_deref(),
+            Some("ecr-login")
+        );
+    }
+
+    #[test]
+    fn registry_auth_settings_reject_path_helpers_and_odd_hosts() {
+        assert!(parse_registry_auth_settings(r#"credential_helper = "../../bin/sh""#).is_err());
+        assert!(
+            parse_registry_auth_settings("[registries.\"GHCR.io\"]\ncredential_helper = \"x\"\n")
+                .is_err()
+        );
+        assert!(parse_registry_auth_settings("[registries.\"ghcr.io/acme\"]\n").is_err());
+    }
+
+    #[test]
+    fn stored_login_is_used_when_no_helper_is_configured() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let secrets = mvm_security::secret_store::FileSecretStore::with_dir(tmp.path());
+        RegistryCredentialStore::new(&secrets)
+            .put(
+                "ghcr.io",
+                &RegistryCredential::Basic {
+                    username: "alice".to_string(),
+                    password: SecretString::from("pat"),
+                },
+            )
+            .expect("store login");
+
+        let found = stored_registry_credential(
+            "ghcr.io",
+            &RegistryAuthSettings::default(),
+            &secrets,
+            helper_in(tmp.path()),
+        )
+        .expect("resolve");
+
+        assert_eq!(
+            basic_username(found),
+            ("alice".to_string(), "store:ghcr.io".to_string())
+        );
+    }
+
+    #[test]
+    fn per_registry_helper_wins_over_store_and_default_helper_is_last() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fake_credential_helper(tmp.path(), "ecr-login", "AWS");
+        fake_credential_helper(tmp.path(), "desktop", "fallback");
+        let secrets = mvm_security::secret_store::FileSecretStore::with_dir(tmp.path().join("s"));
+        RegistryCredentialStore::new(&secrets)
+            .put(
+                "ecr.example",
+                &RegistryCredential::Basic {
+                    username: "stored".to_string(),
+                    password: SecretString::from("pat"),
+                },
+            )
+            .expect("store login");
+        let settings = parse_registry_auth_settings(
+            "credential_helper = \"desktop\"\n[registries.\"ecr.example\"]\ncredential_helper = \"ecr-login\"\n",
+        )
+        .expect("settings parse");
+
+        let ecr =
+            stored_registry_credential("ecr.example", &settings, &secrets, helper_in(tmp.path()))
+                .expect("resolve ecr");
+        assert_eq!(
+            basic_username(ecr),
+            ("AWS".to_string(), "helper:ecr-login".to_string())
+        );
+        let other =
+            stored_registry_credential("quay.io", &settings, &secrets, helper_in(tmp.path()))
+                .expect("resolve quay");
+        assert_eq!(
+            basic_username(other),
+            ("fallback".to_string(), "helper:desktop".to_string())
+        );
+    }
+
+    #[test]
+    fn no_login_and_no_helper_resolves_to_nothing() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let secrets = mvm_security::secret_store::FileSecretStore::with_dir(tmp.path());
+        let found = stored_registry_credential(
+            "ghcr.io",
+            &RegistryAuthSettings::default(),
+            &secrets,
+            helper_in(tmp.path()),
+        )
+        .expect("resolve");
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn docker_hub_helpers_are_asked_for_the_index_url() {
+        assert_eq!(
+            helper_server_url("docker.io"),
+            "https://index.docker.io/v1/"
+        );
+        assert_eq!(helper_server_url("ghcr.io"), "ghcr.io");
+    }
+
     #[test]
     fn registry_env_key_normalizes_registry_host() {
         assert_eq!(registry_env_key("ghcr.io").expect("key"), "GHCR_IO");
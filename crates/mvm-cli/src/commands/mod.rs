@@ -145,6 +145,8 @@ pub(in crate::commands) enum Commands {
     Volume(vm::volume::Args),
     /// Manage local secret namespaces
     Secret(ops::secret::Args),
+    /// Approve or deny commands parked by the command gate
+    Approvals(ops::approvals::Args),
     /// Emit or verify host attestation reports
     Attest(ops::attest::Args),
     /// Seal or verify portable VM bundles
@@ -306,6 +308,7 @@ pub fn run() -> Result<()> {
         Commands::Snapshot(a) => vm::pause::run_snapshot(&cli, a, &cfg),
         Commands::Volume(a) => vm::volume::run(&cli, a, &cfg),
         Commands::Secret(a) => ops::secret::run(&cli, a, &cfg),
+        Commands::Approvals(a) => ops::approvals::run(&cli, a, &cfg),
         Commands::Attest(a) => ops::attest::run(&cli, a, &cfg),
         Commands::Bundle(a) => bundle::run(&cli, a, &cfg),
         Commands::Trust(a) => trust::run(&cli, a, &cfg),
@@ -0,0 +1,410 @@
+//! `mvmctl approvals ls/approve/deny` — the human side of the
+//! command-gate approval broker ([`mvm_security::approval`]).
+//!
+//! `mvmctl exec`, `mvmctl run`, `mvmctl proc start` and MCP tool calls
+//! run their command text through the command gate before dispatch.
+//! A `RequireApproval` match parks the call in the queue under
+//! `<mvm_runtime_dir>/approvals/` until someone runs `mvmctl approvals
+//! approve|deny <id>` or `[security] approval_timeout_secs` elapses.
+//! MCP clients that support elicitation are asked directly as well
+//! (see `ops::mcp`); whichever answer lands first wins.
+//!
+//! ## Audit
+//!
+//! Parked calls and decisions are chain-signed through the host
+//! signer's [`Recorder`] as `policy.approval.requested` and
+//! `policy.approval.<approved|denied|timed_out>`, labelled with the
+//! approver identity. The process that makes a decision emits it:
+//! `mvmctl approvals` for operator verdicts, the MCP server for
+//! elicitation answers, the parked caller for timeouts.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use clap::{Args as ClapArgs, Subcommand};
+use mvm_core::security::ApprovalVerdict;
+use mvm_core::user_config::MvmConfig;
+use mvm_security::approval::{
+    ApprovalAuditSink, ApprovalBroker, ApprovalDecision, ApprovalQueue, ApprovalRequest,
+    ApprovalSubject, GateOutcome, verdict_label,
+};
+use mvm_security::command_gate::{CommandGate, default_blocklist};
+use mvm_supervisor::{EventCategory, Recorder};
+
+use super::Cli;
+use crate::ui;
+
+/// Requests whose caller exited while parked are dropped from the
+/// queue this long after they expire.
+const ABANDONED_REQUEST_GRACE: Duration = Duration::from_secs(3600);
+
+#[derive(ClapArgs, Debug, Clone)]
+pub(in crate::commands) struct Args {
+    #[command(subcommand)]
+    pub action: ApprovalsAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(in crate::commands) enum ApprovalsAction {
+    /// List calls waiting for approval
+    Ls {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Release a parked call
+    Approve {
+        /// Approval id from `mvmctl approvals ls`
+        id: String,
+    },
+    /// Reject a parked call
+    Deny {
+        /// Approval id from `mvmctl approvals ls`
+        id: String,
+        /// Reason reported back to the caller
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Result<()> {
+    run_with_broker(&broker(cfg)?, args.action)
+}
+
+/// Same dispatch as [`run`] against an injected broker. Test seam.
+fn run_with_broker(broker: &ApprovalBroker, action: ApprovalsAction) -> Result<()> {
+    match action {
+        ApprovalsAction::Ls { json } => {
+            broker.queue().prune(ABANDONED_REQUEST_GRACE)?;
+            let pending = broker.queue().pending()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&pending)?);
+            } else if pending.is_empty() {
+                ui::info("No calls waiting for approval.");
+            } else {
+                println!(
+                    "{:<32}  {:<8} {:<16} {:<20} COMMAND",
+                    "ID", "SUBJECT", "TARGET", "EXPIRES"
+                );
+                for request in &pending {
+                    println!(
+                        "{:<32}  {:<8} {:<16} {:<20} {}",
+                        request.id,
+                        request.subject,
+                        request.target.as_deref().unwrap_or("-"),
+                        request.expires_at.format("%Y-%m-%d %H:%M:%S"),
+                        request.command
+                    );
+                }
+            }
+            Ok(())
+        }
+        ApprovalsAction::Approve { id } => {
+            broker.decide(&id, ApprovalVerdict::Approved, &local_identity())?;
+            ui::success(&format!("Approved {id}."));
+            Ok(())
+        }
+        ApprovalsAction::Deny { id, reason } => {
+            let approver = local_identity();
+            let reason = reason.unwrap_or_else(|| "denied by operator".to_string());
+            broker.decide(&id, ApprovalVerdict::Denied { reason }, &approver)?;
+            ui::success(&format!("Denied {id}."));
+            Ok(())
+        }
+    }
+}
+
+/// Broker over the built-in blocklist plus `[security]
+/// command_blocklist`, with the configured timeout and chain audit
+/// when the host signer is available.
+pub(in crate::commands) fn broker(cfg: &MvmConfig) -> Result<ApprovalBroker> {
+    let mut broker = broker_over(cfg, ApprovalQueue::open(ApprovalQueue::default_dir())?);
+    if let Some(recorder) = crate::commands::cmd_audit::build_cmd_recorder() {
+        broker = broker.with_audit_sink(Arc::new(RecorderApprovalSink { recorder }));
+    }
+    Ok(broker)
+}
+
+fn broker_over(cfg: &MvmConfig, queue: ApprovalQueue) -> ApprovalBroker {
+    let mut blocklist = default_blocklist();
+    blocklist.extend(cfg.security.command_blocklist.iter().cloned());
+    ApprovalBroker::new(CommandGate::new(blocklist), queue).with_timeout(Duration::from_secs(
+        cfg.security.effective_approval_timeout_secs(),
+    ))
+}
+
+/// Gate `command` for a CLI caller: parked calls print how to
+/// approve them and block until decided. Errors when the call may
+/// not proceed.
+pub(in crate::commands) fn authorize(
+    cfg: &MvmConfig,
+    subject: ApprovalSubject,
+    target: Option<&str>,
+    command: &str,
+) -> Result<()> {
+    let broker = broker(cfg)?;
+    let outcome = broker.authorize(subject, target, command, &local_identity(), |request| {
+        announce_parked(&broker, request)
+    })?;
+    conclude(outcome)
+}
+
+/// Park `command` for `reason` without consulting the command gate —
+/// for a threat-gate `RequireApproval` match — and block like
+/// [`authorize`] does.
+pub(in crate::commands) fn hold(
+    cfg: &MvmConfig,
+    subject: ApprovalSubject,
+    target: Option<&str>,
+    command: &str,
+    reason: &str,
+) -> Result<()> {
+    let broker = broker(cfg)?;
+    let outcome = broker.hold(
+        subject,
+        target,
+        command,
+        reason,
+        &local_identity(),
+        |request| announce_parked(&broker, request),
+    )?;
+    conclude(outcome)
+}
+
+fn announce_parked(broker: &ApprovalBroker, request: &ApprovalRequest) {
+    ui::warn(&format!(
+        "{} needs approval ({}). Waiting up to {}s for `mvmctl approvals approve {}` \
+         (or `deny`).",
+        request.subject,
+        request.reason,
+        broker.timeout().as_secs(),
+        request.id
+    ));
+}
+
+fn conclude(outcome: GateOutcome) -> Result<()> {
+    if let Some(refusal) = outcome.refusal() {
+        bail!(refusal);
+    }
+    if let GateOutcome::Approved(decision) = outcome {
+        ui::info(&format!("Approved by {}.", decision.approver));
+    }
+    Ok(())
+}
+
+/// Identity recorded for approvers and parked callers on this host:
+/// login name plus uid. The uid is what the queue's 0700 directory
+/// actually trusts; the name is for humans reading the audit chain.
+pub(in crate::commands) fn local_identity() -> String {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    // SAFETY: getuid has no preconditions and cannot fail.
+    let uid = unsafe { libc::getuid() };
+    format!("{user} (uid {uid})")
+}
+
+/// Chain-signs approval events through the host signer's recorder.
+struct RecorderApprovalSink {
+    recorder: Recorder,
+}
+
+impl RecorderApprovalSink {
+    fn emit(&self, event: String, labels: Vec<(String, String)>) {
+        let rt = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => rt,
+            Err(e) => {
+                tracing::warn!(error = %e, "building tokio runtime for approval audit emit");
+                return;
+            }
+        };
+        if let Err(e) = rt.block_on(self.recorder.record_unbound(
+            EventCategory::Policy,
+            event,
+            labels,
+        )) {
+            tracing::warn!(error = %e, "Recorder emit failed for approval event");
+        }
+    }
+}
+
+impl ApprovalAuditSink for RecorderApprovalSink {
+    fn requested(&self, request: &ApprovalRequest) {
+        self.emit(
+            "policy.approval.requested".to_string(),
+            request_labels(request),
+        );
+    }
+
+    fn decided(&self, request: &ApprovalRequest, decision: &ApprovalDecision) {
+        self.emit(
+            format!("policy.approval.{}", verdict_label(&decision.verdict)),
+            decision_labels(request, decision),
+        );
+    }
+}
+
+fn request_labels(request: &ApprovalRequest) -> Vec<(String, String)> {
+    let mut labels = vec![
+        ("approval_id".to_string(), request.id.clone()),
+        ("subject".to_string(), request.subject.to_string()),
+        ("command".to_string(), request.command.clone()),
+        ("reason".to_string(), request.reason.clone()),
+        ("requested_by".to_string(), request.requested_by.clone()),
+        ("expires_at".to_string(), request.expires_at.to_rfc3339()),
+    ];
+    if let Some(target) = &request.target {
+        labels.push(("target".to_string(), target.clone()));
+    }
+    labels
+}
+
+fn decision_labels(
+    request: &ApprovalRequest,
+    decision: &ApprovalDecision,
+) -> Vec<(String, String)> {
+    let mut labels = request_labels(request);
+    labels.push(("approver".to_string(), decision.approver.clone()));
+    labels.push((
+        "verdict".to_string(),
+        verdict_label(&decision.verdict).to_string(),
+    ));
+    if let ApprovalVerdict::Denied { reason } = &decision.verdict {
+        labels.push(("denial_reason".to_string(), reason.clone()));
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mvm_core::security::{BlocklistAction, BlocklistEntry, BlocklistSeverity};
+    use mvm_plan::TenantId;
+    use mvm_security::approval::Evaluation;
+    use mvm_supervisor::CapturingAuditSigner;
+
+    fn test_broker(dir: &std::path::Path) -> (ApprovalBroker, Arc<CapturingAuditSigner>) {
+        let signer = Arc::new(CapturingAuditSigner::new());
+        let recorder = Recorder::new(signer.clone(), TenantId("local".to_string()));
+        let gate = CommandGate::new(vec![BlocklistEntry {
+            pattern: "git push".to_string(),
+            category: "exfiltration".to_string(),
+            severity: BlocklistSeverity::High,
+            action: BlocklistAction::RequireApproval,
+        }]);
+        let broker = ApprovalBroker::new(gate, ApprovalQueue::open(dir).unwrap())
+            .with_audit_sink(Arc::new(RecorderApprovalSink { recorder }));
+        (broker, signer)
+    }
+
+    fn park(broker: &ApprovalBroker) -> ApprovalRequest {
+        match broker
+            .evaluate(
+                ApprovalSubject::Process,
+                Some("dev1"),
+                "git push origin main",
+                "ci (uid 1000)",
+            )
+            .unwrap()
+        {
+            Evaluation::Parked(request) => request,
+            other => panic!("expected a parked request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn approve_is_chain_audited_with_the_approver() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, signer) = test_broker(tmp.path());
+        let request = park(&broker);
+        run_with_broker(
+            &broker,
+            ApprovalsAction::Approve {
+                id: request.id.clone(),
+            },
+        )
+        .unwrap();
+
+        let entries = signer.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, "policy.approval.requested");
+        assert_eq!(entries[1].event, "policy.approval.approved");
+        let labels = &entries[1].labels;
+        assert_eq!(labels.get("approval_id"), Some(&request.id));
+        assert_eq!(labels.get("approver"), Some(&local_identity()));
+        assert_eq!(labels.get("target"), Some(&"dev1".to_string()));
+        assert_eq!(
+            labels.get("command"),
+            Some(&"git push origin main".to_string())
+        );
+    }
+
+    #[test]
+    fn deny_records_the_reason_and_releases_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, signer) = test_broker(tmp.path());
+        let request = park(&broker);
+        run_with_broker(
+            &broker,
+            ApprovalsAction::Deny {
+                id: request.id.clone(),
+                reason: Some("wrong branch".to_string()),
+            },
+        )
+        .unwrap();
+
+        let decision = broker.wait(&request).unwrap();
+        assert_eq!(
+            decision.verdict,
+            ApprovalVerdict::Denied {
+                reason: "wrong branch".to_string()
+            }
+        );
+        let entries = signer.entries();
+        assert_eq!(entries[1].event, "policy.approval.denied");
+        assert_eq!(
+            entries[1].labels.get("denial_reason"),
+            Some(&"wrong branch".to_string())
+        );
+    }
+
+    #[test]
+    fn approving_an_unknown_id_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, signer) = test_broker(tmp.path());
+        let err =
+            run_with_broker(&broker, ApprovalsAction::Approve { id: "0".repeat(32) }).unwrap_err();
+        assert!(err.to_string().contains("no pending approval"), "{err}");
+        assert!(signer.entries().is_empty());
+    }
+
+    #[test]
+    fn broker_appends_configured_entries_to_the_default_blocklist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cfg = MvmConfig::default();
+        cfg.security.command_blocklist.push(BlocklistEntry {
+            pattern: "terraform apply".to_string(),
+            category: "infrastructure".to_string(),
+            severity: BlocklistSeverity::High,
+            action: BlocklistAction::Block,
+        });
+        let broker = broker_over(&cfg, ApprovalQueue::open(tmp.path()).unwrap());
+        assert!(matches!(
+            broker
+                .evaluate(ApprovalSubject::Exec, None, "terraform apply", "t")
+                .unwrap(),
+            Evaluation::Blocked { .. }
+        ));
+        assert!(matches!(
+            broker
+                .evaluate(ApprovalSubject::Exec, None, "mkfs.ext4 /dev/vda", "t")
+                .unwrap(),
+            Evaluation::Blocked { .. }
+        ));
+        assert_eq!(broker.timeout(), Duration::from_secs(300));
+    }
+}
@@ -58,6 +58,7 @@ pub fn admit_probe_plan(
         image_name: vm_name,
         image_sha256: &sha,
         image_cosign_bundle: None,
+        variant: mvm_plan::Variant::Dev,
         intent: None,
         seccomp_tier: PlanSeccompTier::Standard,
         network_policy_ref: None,
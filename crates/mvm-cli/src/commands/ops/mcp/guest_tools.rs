@@ -0,0 +1,401 @@
+//! `mvm.fs.*` / `mvm.proc.*` — guest FS and process RPC as MCP tools.
+//!
+//! Each tool is a [`HostMediatedTool`] in the dispatcher's
+//! [`ToolRegistry`], so a call goes through the same threat gate and
+//! `cmd.tool.<name>.{completed,failed}` chain audit as `mvm.web_fetch`.
+//! The tool policy is checked first: a name missing from
+//! `ToolPolicy.allowed` is refused before the session is looked up.
+//!
+//! Calls run in the warm VM of an open `run` session and serialise
+//! with `run` on its per-session lock. They count as session activity
+//! for the idle reaper.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use mvm_guest::vsock::{FsResult, GuestRequest, ProcResult};
+use mvm_mcp::GuestToolCall;
+use mvm_mcp::SessionMap;
+use mvm_supervisor::tools::{HostMediatedTool, ToolInvokeError};
+use mvm_supervisor::{PolicyToolGate, ToolDecision, ToolGate, ToolRegistry};
+use serde_json::{Value, json};
+
+use super::WarmVms;
+
+/// Largest read `mvm.fs.read` returns in one call.
+const FS_READ_CAP: u64 = 1024 * 1024;
+
+/// Mode of files `mvm.fs.write` creates when the call names none.
+const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// Mode of directories `mvm.fs.mkdir` creates.
+const DEFAULT_DIR_MODE: u32 = 0o755;
+
+/// Register every guest tool on `registry`. `gate` holds the
+/// workload's `ToolPolicy.allowed`.
+pub(super) fn register(
+    registry: &mut ToolRegistry,
+    gate: &Arc<PolicyToolGate>,
+    sessions: &Arc<Mutex<SessionMap>>,
+    warm_vms: &WarmVms,
+) {
+    for &name in mvm_mcp::tools::guest::GUEST_TOOL_NAMES {
+        registry.register(Box::new(GuestTool {
+            name,
+            gate: Arc::clone(gate),
+            sessions: Arc::clone(sessions),
+            warm_vms: Arc::clone(warm_vms),
+        }));
+    }
+}
+
+struct GuestTool {
+    name: &'static str,
+    gate: Arc<PolicyToolGate>,
+    sessions: Arc<Mutex<SessionMap>>,
+    warm_vms: WarmVms,
+}
+
+impl GuestTool {
+    fn upstream(&self, message: impl Into<String>) -> ToolInvokeError {
+        ToolInvokeError::Upstream {
+            tool: self.name.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// The warm VM of session-map key `session`, touching the session
+    /// so an active client keeps it alive.
+    fn session_vm(
+        &self,
+        session: &str,
+    ) -> Result<Arc<Mutex<crate::exec::SessionVm>>, ToolInvokeError> {
+        {
+            let mut map = self
+                .sessions
+                .lock()
+                .map_err(|_| self.upstream("session map lock poisoned"))?;
+            let env = map
+                .get(session)
+                .map(|state| state.env.clone())
+                .ok_or_else(|| {
+                    self.upstream(format!(
+                        "no open session '{session}'; start one with the `run` tool"
+                    ))
+                })?;
+            map.touch_or_insert(session, &env, None);
+        }
+        self.warm_vms
+            .lock()
+            .ok()
+            .and_then(|warm| warm.get(session).cloned())
+            .ok_or_else(|| self.upstream(format!("session '{session}' has no running VM yet")))
+    }
+}
+
+#[async_trait]
+impl HostMediatedTool for GuestTool {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn invoke(&self, params: Value) -> Result<Value, ToolInvokeError> {
+        let refused = |reason: String| ToolInvokeError::Refused {
+            tool: self.name.to_string(),
+            reason,
+        };
+        match self.gate.check(self.name).await {
+            Ok(ToolDecision::Allow) => {}
+            Ok(ToolDecision::Deny { reason }) => return Err(refused(reason)),
+            Err(e) => return Err(refused(e.to_string())),
+        }
+        let call = GuestToolCall::parse(self.name, params).map_err(|message| {
+            ToolInvokeError::InvalidParams {
+                tool: self.name.to_string(),
+                message,
+            }
+        })?;
+        let vm = self.session_vm(call.session())?;
+        let session = call.session().to_string();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let vm = vm
+                .lock()
+                .map_err(|_| anyhow::anyhow!("warm-VM lock poisoned for session '{session}'"))?;
+            dispatch(&vm.vm_name, call)
+        })
+        .await
+        .map_err(|e| self.upstream(format!("guest call task failed: {e}")))?;
+        outcome.map_err(|e| self.upstream(format!("{e:#}")))
+    }
+}
+
+/// Run `call` against session VM `vm_name` and render the tool result.
+fn dispatch(vm_name: &str, call: GuestToolCall) -> anyhow::Result<Value> {
+    match call {
+        GuestToolCall::FsRead(p) => {
+            let offset = p.offset.unwrap_or(0);
+            let FsResult::Read {
+                content,
+                total_size,
+            } = fs(
+                vm_name,
+                GuestRequest::FsRead {
+                    path: p.path.clone(),
+                    offset: Some(offset),
+                    length: p.length.unwrap_or(FS_READ_CAP).clamp(1, FS_READ_CAP),
+                    follow_symlinks: true,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsRead reply");
+            };
+            let mut out = json!({
+                "path": p.path,
+                "offset": offset,
+                "bytes": content.len(),
+                "total_size": total_size,
+            });
+            match String::from_utf8(content) {
+                Ok(text) => out["content"] = Value::String(text),
+                Err(e) => {
+                    out["content_base64"] = Value::String(URL_SAFE_NO_PAD.encode(e.as_bytes()))
+                }
+            }
+            Ok(out)
+        }
+        GuestToolCall::FsWrite(p) => {
+            let content = match (p.content, p.content_base64) {
+                (Some(text), _) => text.into_bytes(),
+                (None, Some(encoded)) => URL_SAFE_NO_PAD
+                    .decode(encoded.trim_end_matches('='))
+                    .map_err(|e| anyhow::anyhow!("decoding content_base64: {e}"))?,
+                (None, None) => Vec::new(),
+            };
+            let FsResult::Write { bytes_written } = fs(
+                vm_name,
+                GuestRequest::FsWrite {
+                    path: p.path.clone(),
+                    content,
+                    mode: p.mode.unwrap_or(DEFAULT_FILE_MODE),
+                    create_parents: p.create_parents.unwrap_or(false),
+                    follow_symlinks: false,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsWrite reply");
+            };
+            Ok(json!({ "path": p.path, "bytes_written": bytes_written }))
+        }
+        GuestToolCall::FsList(p) => {
+            let FsResult::List { entries, truncated } = fs(
+                vm_name,
+                GuestRequest::FsList {
+                    path: p.path.clone(),
+                    follow_symlinks: true,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsList reply");
+            };
+            Ok(json!({ "path": p.path, "entries": entries, "truncated": truncated }))
+        }
+        GuestToolCall::FsStat(p) => {
+            let FsResult::Stat(stat) = fs(
+                vm_name,
+                GuestRequest::FsStat {
+                    path: p.path,
+                    follow_symlinks: true,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsStat reply");
+            };
+            Ok(serde_json::to_value(stat)?)
+        }
+        GuestToolCall::FsMkdir(p) => {
+            let FsResult::Mkdir = fs(
+                vm_name,
+                GuestRequest::FsMkdir {
+                    path: p.path.clone(),
+                    mode: DEFAULT_DIR_MODE,
+                    parents: p.parents.unwrap_or(false),
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsMkdir reply");
+            };
+            Ok(json!({ "path": p.path, "created": true }))
+        }
+        GuestToolCall::FsRemove(p) => {
+            let FsResult::Remove { entries_removed } = fs(
+                vm_name,
+                GuestRequest::FsRemove {
+                    path: p.path.clone(),
+                    recursive: p.recursive.unwrap_or(false),
+                    follow_symlinks: false,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsRemove reply");
+            };
+            Ok(json!({ "path": p.path, "entries_removed": entries_removed }))
+        }
+        GuestToolCall::FsMove(p) => {
+            let FsResult::Move = fs(
+                vm_name,
+                GuestRequest::FsMove {
+                    from: p.from.clone(),
+                    to: p.to.clone(),
+                    follow_symlinks: false,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected FsMove reply");
+            };
+            Ok(json!({ "from": p.from, "to": p.to, "moved": true }))
+        }
+        GuestToolCall::ProcStart(p) => {
+            let mut env = p.env;
+            env.entry("PATH".to_string())
+                .or_insert_with(|| crate::exec::SESSION_PROC_PATH.to_string());
+            let ProcResult::Started { pid_token } = proc(
+                vm_name,
+                GuestRequest::ProcStart {
+                    argv: p.argv,
+                    env,
+                    cwd: p.cwd,
+                    stdin: p.stdin.map(String::into_bytes).unwrap_or_default(),
+                    timeout_secs: p.timeout_secs,
+                    pty: None,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected ProcStart reply");
+            };
+            Ok(json!({ "pid_token": pid_token }))
+        }
+        GuestToolCall::ProcList(_) => {
+            let ProcResult::List { processes } = proc(vm_name, GuestRequest::ProcList)? else {
+                anyhow::bail!("unexpected ProcList reply");
+            };
+            Ok(json!({ "processes": processes }))
+        }
+        GuestToolCall::ProcSignal(p) => {
+            let ProcResult::Signaled = proc(
+                vm_name,
+                GuestRequest::ProcSignal {
+                    pid_token: p.pid_token.clone(),
+                    signum: p.signum,
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected ProcSignal reply");
+            };
+            Ok(json!({ "pid_token": p.pid_token, "signum": p.signum }))
+        }
+        GuestToolCall::ProcInput(p) => {
+            let ProcResult::InputAccepted { bytes_accepted } = proc(
+                vm_name,
+                GuestRequest::ProcSendInput {
+                    pid_token: p.pid_token,
+                    bytes: p.input.into_bytes(),
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected ProcSendInput reply");
+            };
+            Ok(json!({ "bytes_accepted": bytes_accepted }))
+        }
+        GuestToolCall::ProcWait(p) => {
+            let timeout = super::clamp_timeout(p.timeout_secs);
+            let out = crate::exec::wait_session_proc(vm_name, &p.pid_token, timeout)?;
+            Ok(json!({
+                "exit_code": out.exit_code,
+                "stdout": super::truncate_with_marker(&out.stdout),
+                "stderr": super::truncate_with_marker(&out.stderr),
+            }))
+        }
+        GuestToolCall::ProcKill(p) => {
+            let ProcResult::Killed = proc(
+                vm_name,
+                GuestRequest::ProcKill {
+                    pid_token: p.pid_token.clone(),
+                },
+            )?
+            else {
+                anyhow::bail!("unexpected ProcKill reply");
+            };
+            Ok(json!({ "pid_token": p.pid_token, "killed": true }))
+        }
+    }
+}
+
+/// [`crate::exec::session_fs_request`] with the verb's own error
+/// folded into the `Err` side.
+fn fs(vm_name: &str, request: GuestRequest) -> anyhow::Result<FsResult> {
+    match crate::exec::session_fs_request(vm_name, request)? {
+        FsResult::Error { kind, message } => anyhow::bail!("guest FS error ({kind:?}): {message}"),
+        FsResult::Backpressure { reason, detail } => {
+            anyhow::bail!("guest FS quota throttled the call ({reason:?}): {detail}")
+        }
+        result => Ok(result),
+    }
+}
+
+/// [`crate::exec::session_proc_request`] with the verb's own error
+/// folded into the `Err` side.
+fn proc(vm_name: &str, request: GuestRequest) -> anyhow::Result<ProcResult> {
+    match crate::exec::session_proc_request(vm_name, request)? {
+        ProcResult::Error { kind, message } => {
+            anyhow::bail!("guest process error ({kind:?}): {message}")
+        }
+        result => Ok(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn tool(name: &'static str, allowed: &[&str]) -> GuestTool {
+        GuestTool {
+            name,
+            gate: Arc::new(PolicyToolGate::new(allowed.iter().copied())),
+            sessions: Arc::new(Mutex::new(SessionMap::new(
+                mvm_mcp::SessionConfig::default(),
+            ))),
+            warm_vms: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    fn invoke(tool: &GuestTool, params: Value) -> Result<Value, ToolInvokeError> {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(tool.invoke(params))
+    }
+
+    #[test]
+    fn tool_outside_the_policy_is_refused_before_params_are_read() {
+        let t = tool("mvm.fs.read", &["mvm.fs.stat"]);
+        let err = invoke(&t, json!({})).unwrap_err();
+        assert!(matches!(err, ToolInvokeError::Refused { .. }), "{err}");
+        assert!(err.to_string().contains("not in policy allowlist"), "{err}");
+    }
+
+    #[test]
+    fn allowed_tool_needs_an_open_session() {
+        let t = tool("mvm.fs.stat", &["mvm.fs.stat"]);
+        let err = invoke(&t, json!({"session": "s1", "path": "/tmp"})).unwrap_err();
+        assert!(err.to_string().contains("no open session 's1'"), "{err}");
+
+        let bad = invoke(&t, json!({"session": "s1"})).unwrap_err();
+        assert!(
+            matches!(bad, ToolInvokeError::InvalidParams { .. }),
+            "{bad}"
+        );
+    }
+}
@@ -0,0 +1,2260 @@
+//! `mvmctl mcp` — Model Context Protocol server entry point.
+//!
+//! Two transports: `stdio` reads JSON-RPC requests from stdin and
+//! writes responses to stdout; `http` serves the streamable-HTTP
+//! transport on a loopback port or unix socket so several clients
+//! share one host process. Both dispatch `tools/call run` into
+//! microVMs via [`crate::exec::run_in_session`], which reports
+//! progress and kills the guest process when the client cancels.
+//! Warm sessions are also exposed as MCP resources (guest files, boot
+//! report, audit trail), and the `mvm.fs.*` / `mvm.proc.*` tools in
+//! [`guest_tools`] reach into them when the tool policy allows. ADR-003
+//! has the threat model and design.
+//!
+//! Note: `mvmctl mcp` is *always* present in CLI builds (no Cargo
+//! feature gate at the host level), matching `mvmctl exec`'s pattern.
+//! The guest agent's dev-only process RPC and `Exec` handler are the
+//! actual gate per ADR-002 §W4.3 — production guest agents are built
+//! without `dev-shell`, so the `tools/call run` dispatch returns
+//! "exec not available" instead of executing. This composition is
+//! intentional: the MCP server is useful when pointed at dev VMs,
+//! harmless when pointed at prod ones.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Args as ClapArgs, Subcommand};
+
+use mvm_core::security::ApprovalVerdict;
+use mvm_core::user_config::MvmConfig;
+use mvm_mcp::{
+    ClientPeer, ContentBlock, Dispatcher, ElicitAction, GuestToolCall, HttpServer, JsonRpcError,
+    Listen, ReapReason, Reaper, Resource, ResourceContents, ResourceTemplate, RunParams,
+    SessionConfig, SessionLookup, SessionMap, SessionState, ToolResult, ToolSchema,
+};
+use mvm_policy::{ThreatPolicy, ToolPolicy};
+use mvm_security::approval::{ApprovalBroker, ApprovalRequest, ApprovalSubject, Evaluation};
+use mvm_security::threat_gate::ThreatGate;
+use mvm_supervisor::tools::{download, staging, upload, web_fetch, web_search};
+use mvm_supervisor::{PolicyToolGate, ToolRegistry};
+use secrecy::{ExposeSecret, SecretBox};
+
+use super::Cli;
+
+mod guest_tools;
+
+/// Per-session warm-VM handles, keyed by session ID. Locked
+/// independently of [`SessionMap`] so a long-running dispatch
+/// against one session doesn't block bookkeeping reads of others.
+type WarmVms = Arc<Mutex<BTreeMap<String, Arc<Mutex<crate::exec::SessionVm>>>>>;
+
+#[derive(ClapArgs, Debug, Clone)]
+pub(in crate::commands) struct Args {
+    #[command(subcommand)]
+    pub transport: McpTransport,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(in crate::commands) enum McpTransport {
+    /// Speak MCP over stdio (the standard MCP transport for local
+    /// developer tools). Reads JSON-RPC frames from stdin, writes
+    /// responses to stdout. All non-protocol output goes to stderr —
+    /// putting anything else on stdout corrupts the wire.
+    Stdio,
+    /// Speak MCP's streamable-HTTP transport on a loopback port or a
+    /// unix socket, so several clients (editors, CI) share one host
+    /// process. Every request must carry `Authorization: Bearer
+    /// <token>`; the token is read from the local secret store.
+    Http(HttpArgs),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub(in crate::commands) struct HttpArgs {
+    /// Loopback address to listen on. Non-loopback addresses are
+    /// refused.
+    #[arg(long, default_value = DEFAULT_HTTP_LISTEN, conflicts_with = "socket")]
+    pub listen: std::net::SocketAddr,
+    /// Listen on a unix socket (created mode 0600) instead of TCP.
+    #[arg(long)]
+    pub socket: Option<std::path::PathBuf>,
+    /// Local secret holding the bearer token clients must present.
+    /// Store one with `mvmctl secret put <name>`.
+    #[arg(long, default_value = DEFAULT_HTTP_TOKEN_SECRET)]
+    pub token_secret: String,
+}
+
+/// Default `mvmctl mcp http --listen`.
+const DEFAULT_HTTP_LISTEN: &str = "127.0.0.1:7313";
+
+/// Default `mvmctl mcp http --token-secret`.
+const DEFAULT_HTTP_TOKEN_SECRET: &str = "mcp-http-token";
+
+pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Result<()> {
+    match args.transport {
+        McpTransport::Stdio => {
+            mvm_mcp::init_stderr_tracing();
+            let (threat, tools) = mcp_policy()?;
+            let dispatcher = ExecDispatcher::new(
+                Some(Arc::new(super::approvals::broker(cfg)?)),
+                &threat,
+                &tools,
+            );
+            // Spawn the session reaper. Drops out when the process exits;
+            // sessions still in the map at shutdown get drained by the
+            // dispatcher's Drop impl (RAII via Arc<Mutex<SessionMap>>
+            // + the Drop on ExecDispatcher).
+            dispatcher.spawn_reaper();
+            // The loop reads stdin on its own thread, so hand it a
+            // `Send` reader rather than a `StdinLock`.
+            let stdin = std::io::BufReader::new(std::io::stdin());
+            let stdout = std::io::stdout();
+            mvm_mcp::run_with_dispatcher(stdin, &mut stdout.lock(), &dispatcher)
+        }
+        McpTransport::Http(http) => {
+            mvm_mcp::init_stderr_tracing();
+            let listen = match http.socket {
+                Some(path) => Listen::Unix(path),
+                None => Listen::Tcp(http.listen),
+            };
+            let store = mvm_security::secret_store::default_secret_store();
+            let token = http_bearer_token(&http.token_secret, store.as_ref())?;
+            let server = HttpServer::bind(&listen, token, SessionConfig::from_env())?;
+            let (threat, tools) = mcp_policy()?;
+            let dispatcher = ExecDispatcher::new(
+                Some(Arc::new(super::approvals::broker(cfg)?)),
+                &threat,
+                &tools,
+            );
+            dispatcher.spawn_reaper();
+            server.serve(&dispatcher, &dispatcher.transport_reaper())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ExecDispatcher — bridges MCP protocol to crate::exec::run_in_session
+// ---------------------------------------------------------------------------
+
+/// stdout/stderr cap per call (cross-cutting "A: resource limits").
+/// Truncated tail is replaced by an explicit `[truncated, N more
+/// bytes]` marker so the LLM sees the failure mode instead of a
+/// silently chopped payload.
+const STREAM_CAP_BYTES: usize = 64 * 1024;
+
+/// Default per-call timeout in seconds. Bounded `[1, 600]`; values
+/// outside that range are clamped (not errored) so an LLM that picks
+/// `timeout_secs: 0` still makes progress.
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const MIN_TIMEOUT_SECS: u64 = 1;
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Default concurrency cap. Configurable via `MVM_MCP_MAX_INFLIGHT`.
+const DEFAULT_MAX_INFLIGHT: usize = 4;
+
+/// Default memory ceiling in MiB. Configurable via
+/// `MVM_MCP_MEM_CEILING_MIB`.
+const DEFAULT_MEM_CEILING_MIB: u32 = 4096;
+
+/// Default vCPUs handed to the transient microVM. Templates' vCPU
+/// counts are not honored in v1 — every `tools/call run` uses the
+/// same fixed shape so concurrency math stays predictable.
+const DEFAULT_VM_CPUS: u32 = 2;
+const DEFAULT_VM_MEM_MIB: u32 = 1024;
+
+/// How often the reaper sweeps the session map. Smaller intervals
+/// reap closer to the configured idle/max boundary at the cost of
+/// extra wake-ups; the default is generous because session timeouts
+/// are measured in minutes-to-hours.
+const REAPER_TICK_SECS: u64 = 30;
+
+/// How long a warm call waits for the session VM's guest agent. A VM
+/// that stays unreachable this long is treated as lost and its
+/// session closed with [`ReapReason::VmLost`].
+const SESSION_AGENT_WAIT_SECS: u64 = 30;
+
+/// Concrete dispatcher backed by [`crate::exec::run_in_session`], in a
+/// VM booted for the call (cold) or the session's warm VM (when
+/// `session=ID`).
+///
+/// Plan 32 / Proposal A.2:
+/// - **Bookkeeping (v1)**: the `SessionMap` records each session's
+///   metadata, and a 30 s-tick reaper sweeps idle/expired entries.
+/// - **Warm VM materialisation (v2)**: the per-session handle map
+///   `warm_vms` keeps the booted [`crate::exec::SessionVm`] alive
+///   across calls. First call in a session boots; subsequent calls
+///   reuse. `close: true` and the reaper both tear down via
+///   [`crate::exec::tear_down_session_vm`].
+struct ExecDispatcher {
+    inflight: AtomicUsize,
+    max_inflight: usize,
+    mem_ceiling_mib: u32,
+    sessions: Arc<Mutex<SessionMap>>,
+    warm_vms: WarmVms,
+    reaper: Arc<DispatcherReaper>,
+    /// Plan 60 Phase 7 host-mediated tools (`mvm.time_now`,
+    /// `mvm.web_fetch`, `mvm.web_search`). Built once at dispatcher
+    /// construction; shared across every `tools/call` invocation.
+    /// Default registry ships fail-closed for the web tools —
+    /// operators wire per-tenant allowlists by replacing this with
+    /// a configured registry in a follow-up slice.
+    tool_registry: Arc<ToolRegistry>,
+    /// Command gate for `run` code and tool calls. `RequireApproval`
+    /// matches are put to the client as an elicitation when it
+    /// supports one, and wait on `mvmctl approvals` otherwise.
+    /// `None` skips the gate (tests). Shared with the tool registry,
+    /// which parks threat-gate `RequireApproval` matches on it.
+    approvals: Option<Arc<ApprovalBroker>>,
+    /// `ToolPolicy.allowed` for the guest tools (`mvm.fs.*`,
+    /// `mvm.proc.*`). Only allowed ones are listed; the tools check it
+    /// again on every call.
+    tool_gate: Arc<PolicyToolGate>,
+    /// Threat classifier built from the MCP `[threat]` policy. The
+    /// tool registry screens every call's params with it, and
+    /// `mvm.proc.start` argv is screened again as a command line.
+    threat_gate: Arc<ThreatGate>,
+}
+
+impl Default for ExecDispatcher {
+    fn default() -> Self {
+        Self::new(None, &ThreatPolicy::default(), &ToolPolicy::default())
+    }
+}
+
+impl ExecDispatcher {
+    /// `threat` sets the per-category actions the tool registry
+    /// applies to `tools/call` params; `tools` allowlists the guest
+    /// tools.
+    fn new(
+        approvals: Option<Arc<ApprovalBroker>>,
+        threat: &ThreatPolicy,
+        tools: &ToolPolicy,
+    ) -> Self {
+        let warm_vms: WarmVms = Arc::new(Mutex::new(BTreeMap::new()));
+        let sessions = Arc::new(Mutex::new(SessionMap::new(SessionConfig::from_env())));
+        let tool_gate = Arc::new(PolicyToolGate::from_policy(tools));
+        let threat_gate = Arc::new(mvm_supervisor::threat_gate_from_policy(threat));
+        let mut registry = build_tool_registry(Arc::clone(&threat_gate), approvals.clone());
+        guest_tools::register(&mut registry, &tool_gate, &sessions, &warm_vms);
+        Self {
+            inflight: AtomicUsize::new(0),
+            max_inflight: parse_env_usize("MVM_MCP_MAX_INFLIGHT", DEFAULT_MAX_INFLIGHT),
+            mem_ceiling_mib: parse_env_u32("MVM_MCP_MEM_CEILING_MIB", DEFAULT_MEM_CEILING_MIB),
+            sessions,
+            reaper: Arc::new(DispatcherReaper {
+                warm_vms: Arc::clone(&warm_vms),
+            }),
+            warm_vms,
+            tool_registry: Arc::new(registry),
+            approvals,
+            tool_gate,
+            threat_gate,
+        }
+    }
+
+    /// Run `command` through the approval broker. `Some` carries the
+    /// error result to return instead of dispatching.
+    fn gate(
+        &self,
+        subject: ApprovalSubject,
+        target: &str,
+        command: &str,
+        peer: &dyn ClientPeer,
+    ) -> Option<ToolResult> {
+        let broker = self.approvals.as_ref()?;
+        let request = match broker.evaluate(subject, Some(target), command, &requested_by(peer)) {
+            Ok(Evaluation::Allowed) => return None,
+            Ok(Evaluation::Blocked { pattern, reason }) => {
+                return Some(error_result(format!(
+                    "blocked by command gate ({reason}): matched {pattern:?}"
+                )));
+            }
+            Ok(Evaluation::Parked(request)) => request,
+            Err(e) => return Some(error_result(format!("approval broker: {e:#}"))),
+        };
+        decide(broker, &request, peer).err().map(error_result)
+    }
+
+    /// Gate and threat-screen an `mvm.proc.start` call the way
+    /// `mvmctl proc start` does: as the command line its argv
+    /// spells, under `ApprovalSubject::Process`. The tool-call gate
+    /// sees only the JSON params, which no blocklist pattern for a
+    /// command line matches.
+    fn gate_proc_start(
+        &self,
+        session: &str,
+        argv: &[String],
+        peer: &dyn ClientPeer,
+    ) -> Option<ToolResult> {
+        let command = argv.join(" ");
+        if let Some(refused) = self.gate(ApprovalSubject::Process, session, &command, peer) {
+            return Some(refused);
+        }
+        crate::commands::vm::threat_screen::screen_with(
+            &self.threat_gate,
+            crate::commands::vm::threat_screen::Surface::ProcStart,
+            Some(session),
+            &command,
+            |decisive| {
+                let Some(broker) = self.approvals.as_ref() else {
+                    anyhow::bail!(
+                        "{} requires approval and no approval broker is wired",
+                        decisive.reason()
+                    );
+                };
+                let request = broker.park(
+                    ApprovalSubject::Process,
+                    Some(session),
+                    &command,
+                    &decisive.reason(),
+                    &requested_by(peer),
+                )?;
+                decide(broker, &request, peer).map_err(anyhow::Error::msg)
+            },
+        )
+        .err()
+        .map(|e| error_result(format!("{e:#}")))
+    }
+}
+
+/// Put a parked `request` to the client, or wait on the queue
+/// when it can't answer. `Err` carries the refusal.
+fn decide(
+    broker: &ApprovalBroker,
+    request: &ApprovalRequest,
+    peer: &dyn ClientPeer,
+) -> std::result::Result<(), String> {
+    tracing::warn!(
+        id = %request.id,
+        subject = %request.subject,
+        reason = %request.reason,
+        "call parked for approval; `mvmctl approvals approve {}` releases it",
+        request.id
+    );
+    let decision = match elicit_verdict(request, peer) {
+        Some(verdict) => broker.settle(request, verdict, &requested_by(peer)),
+        None => broker.wait(request),
+    };
+    match decision {
+        Ok(decision) => match broker.finish(request, decision).refusal() {
+            None => Ok(()),
+            Some(refusal) => Err(refusal),
+        },
+        Err(e) => Err(format!("approval broker: {e:#}")),
+    }
+}
+
+/// Who a call from `peer` is recorded as requested (or decided) by.
+fn requested_by(peer: &dyn ClientPeer) -> String {
+    let client = peer.client_name().unwrap_or_else(|| "unknown".to_string());
+    format!(
+        "mcp client {client} as {}",
+        super::approvals::local_identity()
+    )
+}
+
+/// Ask the client's user about a parked call. `None` when the client
+/// can't elicit, cancelled the prompt or the exchange failed — the
+/// call then waits on `mvmctl approvals` until it times out.
+fn elicit_verdict(request: &ApprovalRequest, peer: &dyn ClientPeer) -> Option<ApprovalVerdict> {
+    if !peer.supports_elicitation() {
+        return None;
+    }
+    let message = format!(
+        "mvm parked a {} call ({}):\n\n{}\n\nApprove it? Unanswered calls are rejected at {}.",
+        request.subject,
+        request.reason,
+        request.command,
+        request.expires_at.to_rfc3339()
+    );
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "approve": {
+                "type": "boolean",
+                "title": "Approve",
+                "description": "Let the call run",
+            },
+            "reason": {
+                "type": "string",
+                "title": "Reason",
+                "description": "Recorded when the call is denied",
+            },
+        },
+        "required": ["approve"],
+    });
+    let answer = match peer.elicit(&message, schema) {
+        Ok(answer) => answer,
+        Err(e) => {
+            tracing::warn!(id = %request.id, error = %e.message, "approval elicitation failed");
+            return None;
+        }
+    };
+    let content = answer.content.unwrap_or_default();
+    let reason = || {
+        content
+            .get("reason")
+            .and_then(|r| r.as_str())
+            .filter(|r| !r.is_empty())
+            .unwrap_or("denied by MCP client user")
+            .to_string()
+    };
+    match answer.action {
+        ElicitAction::Accept if content.get("approve").and_then(|a| a.as_bool()) == Some(true) => {
+            Some(ApprovalVerdict::Approved)
+        }
+        ElicitAction::Accept | ElicitAction::Decline => {
+            Some(ApprovalVerdict::Denied { reason: reason() })
+        }
+        ElicitAction::Cancel => None,
+    }
+}
+
+/// Plan 65 W4 — operator-rotatable provider credentials. When set,
+/// the value names a secret stored via `mvmctl secret put` (default
+/// tenant `local`). The supervisor fetches the value through
+/// `mvm_security::secret_store::default_secret_store()` — OS
+/// keyring on Mac/Linux with gnome-keyring, file fallback elsewhere
+/// (mode 0600 under `~/.mvm/secrets/local/`).
+///
+/// Pattern: each provider gets *one* "from secret" env var paired
+/// with the existing "direct value" env var. The direct value wins
+/// when both are set (backward compatibility); operators rotating
+/// from env-var to secret-store posture rm-and-rebuild their shell
+/// init.
+const BRAVE_API_KEY_FROM_SECRET_ENV_VAR: &str = "BRAVE_API_KEY_FROM_SECRET";
+const TAVILY_API_KEY_FROM_SECRET_ENV_VAR: &str = "TAVILY_API_KEY_FROM_SECRET";
+const GOOGLE_API_KEY_FROM_SECRET_ENV_VAR: &str = "GOOGLE_API_KEY_FROM_SECRET";
+const GOOGLE_CSE_ID_FROM_SECRET_ENV_VAR: &str = "GOOGLE_CSE_ID_FROM_SECRET";
+
+/// Tenant id under which provider credentials are stored. Matches
+/// the default tenant `mvmctl secret put` uses, so an operator can
+/// run `mvmctl secret put brave-api-key --value-file <(cat key)`
+/// and reference it via `BRAVE_API_KEY_FROM_SECRET=brave-api-key`.
+const PROVIDER_CREDENTIAL_TENANT: &str = "local";
+
+/// Plan 65 W4 — resolve a provider credential from either the
+/// direct-value env var (v0 posture, visible to the calling user
+/// via `/proc/<pid>/environ`) or a named secret in the secret store
+/// (hardened posture, file mode 0600 or OS keyring).
+///
+/// Resolution order:
+/// 1. `direct_env_var` — if set and non-empty, use it verbatim.
+/// 2. `secret_ref_env_var` — if set, look up that name in the
+///    secret store under the `local` tenant. If the lookup fails
+///    (missing entry, permission error, store wedged), log a
+///    `tracing::warn` and return `None` — the existing
+///    "allowed-but-unregistered" config-drift error fires at
+///    invoke time, which is the operator's downstream signal.
+/// 3. Otherwise — `None` (caller treats as unconfigured).
+///
+/// Plan 65 W6: the return type is `SecretBox<String>` (not a raw
+/// `String`) so the bytes zeroize on drop — even if the provider
+/// constructor that consumes it panics mid-build, the
+/// SecretBox-wrapped value still gets cleaned up. Each provider's
+/// `new()` accepts `SecretBox<String>` directly; this resolver
+/// hands ownership through end-to-end.
+fn resolve_provider_credential(
+    direct_env_var: &str,
+    secret_ref_env_var: &str,
+    store: &dyn mvm_security::secret_store::SecretStore,
+) -> Option<SecretBox<String>> {
+    if let Ok(direct) = std::env::var(direct_env_var)
+        && !direct.is_empty()
+    {
+        return Some(SecretBox::new(Box::new(direct)));
+    }
+    let secret_name = std::env::var(secret_ref_env_var).ok()?;
+    if secret_name.is_empty() {
+        return None;
+    }
+    match store.get(PROVIDER_CREDENTIAL_TENANT, &secret_name) {
+        Ok(secret) => Some(secret),
+        Err(e) => {
+            tracing::warn!(
+                env_var = secret_ref_env_var,
+                secret_name = %secret_name,
+                tenant = PROVIDER_CREDENTIAL_TENANT,
+                error = %e,
+                "could not resolve provider credential from secret_store"
+            );
+            None
+        }
+    }
+}
+
+/// Bearer token for `mvmctl mcp http`, from the named secret under
+/// the `local` tenant. Unlike provider credentials there is no env
+/// var fallback and no unauthenticated mode: a missing or empty
+/// secret is an error.
+fn http_bearer_token(
+    secret_name: &str,
+    store: &dyn mvm_security::secret_store::SecretStore,
+) -> Result<SecretBox<String>> {
+    let token = store
+        .get(PROVIDER_CREDENTIAL_TENANT, secret_name)
+        .with_context(|| {
+            format!(
+                "reading MCP HTTP bearer token '{secret_name}'; store one with \
+                 `mvmctl secret put {secret_name}`"
+            )
+        })?;
+    if token.expose_secret().trim().is_empty() {
+        anyhow::bail!("MCP HTTP bearer token '{secret_name}' is empty");
+    }
+    Ok(token)
+}
+
+/// Build the Phase 7 tool registry the MCP dispatcher hands out.
+///
+/// Today:
+/// - `mvm.time_now` — always reachable.
+/// - `mvm.web_fetch` — uses [`web_fetch::ReqwestHttpFetcher`] when
+///   constructable; allowlist comes from
+///   `$MVM_WEB_FETCH_ALLOWLIST` (comma-separated). When the env var
+///   is unset, the allowlist is empty and every fetch fails closed.
+/// - `mvm.web_search` — allowlist comes from
+///   `$MVM_WEB_SEARCH_ALLOWLIST`; default provider comes from
+///   `$MVM_WEB_SEARCH_DEFAULT` (or the first allowlist entry).
+///   When `"brave"` is on the allowlist AND `$BRAVE_SEARCH_API_KEY`
+///   is set, a [`web_search::BraveSearchProvider`] is registered.
+///   Without the key, the existing "allowed but unregistered =
+///   config drift" error fires on first invoke so the
+///   misconfiguration is loud, not silent.
+///
+/// On `ReqwestHttpFetcher::new()` / `BraveSearchProvider::new()`
+/// failure (extraordinarily rare), we log a `tracing::warn` and
+/// fall back to the substrate's Noop default so the registry still
+/// ships.
+/// Names the `<tenant>:<workload>` bundle whose `[threat]` section
+/// gates `tools/call` params and whose `[tool]` allowlist opens the
+/// guest tools. Unset: log-only, and no guest tools.
+const THREAT_POLICY_ENV_VAR: &str = "MVM_MCP_THREAT_POLICY";
+
+/// The `[threat]` and `[tool]` policies [`THREAT_POLICY_ENV_VAR`]
+/// points at. A set but unloadable ref is an error so a typo can't
+/// silently downgrade the gate to log-only.
+fn mcp_policy() -> Result<(ThreatPolicy, ToolPolicy)> {
+    let Ok(value) = std::env::var(THREAT_POLICY_ENV_VAR) else {
+        return Ok((ThreatPolicy::default(), ToolPolicy::default()));
+    };
+    let (tenant, workload) = value
+        .split_once(':')
+        .filter(|(t, w)| {
+            !t.is_empty() && !w.is_empty() && !t.contains(['/', '\\']) && !w.contains(['/', '\\'])
+        })
+        .with_context(|| format!("{THREAT_POLICY_ENV_VAR}={value:?} is not <tenant>:<workload>"))?;
+    let base = mvm_policy::toml_loader::default_policy_dir()
+        .context("resolving ~/.mvm/policies for the MCP threat policy")?;
+    let bundle = mvm_policy::toml_loader::load_bundle_from_path(&base, tenant, workload)
+        .with_context(|| format!("loading {THREAT_POLICY_ENV_VAR}={value:?}"))?;
+    Ok((bundle.threat, bundle.tool))
+}
+
+fn build_tool_registry(
+    threat_gate: Arc<ThreatGate>,
+    approvals: Option<Arc<ApprovalBroker>>,
+) -> ToolRegistry {
+    let mut registry = ToolRegistry::with_defaults();
+
+    // Plan 60 Phase 4 wire-up: every successful tool invocation
+    // emits a `cmd.tool.<name>.{completed,failed}` chain-signed
+    // entry through the host signer's FileAuditSigner. When the
+    // signer isn't reachable (no $HOME, key not initialized, loose
+    // perms) we log a warning in `build_cmd_recorder` and skip the
+    // attachment — tool calls still succeed; the audit footprint
+    // just doesn't land. Same posture as `cmd.*` envelopes.
+    if let Some(rec) = crate::commands::cmd_audit::build_cmd_recorder() {
+        registry = registry.with_recorder(rec);
+    }
+
+    // Every call's params go through the threat classifier; matches
+    // are chain-audited through the same recorder and `Block` /
+    // `RequireApproval` categories refuse or park the call.
+    registry = registry.with_threat_gate(threat_gate);
+    if let Some(broker) = approvals {
+        registry = registry.with_approvals(
+            broker,
+            format!("mcp tool call as {}", super::approvals::local_identity()),
+        );
+    }
+
+    // mvm.web_fetch
+    let allow = web_fetch::allowlist_from_env_var(web_fetch::ALLOWLIST_ENV_VAR);
+    let mut fetch_tool = web_fetch::WebFetchTool::with_allowlist(allow);
+    match web_fetch::ReqwestHttpFetcher::new() {
+        Ok(f) => {
+            fetch_tool = fetch_tool.with_fetcher(Arc::new(f));
+        }
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "ReqwestHttpFetcher init failed; mvm.web_fetch will use Noop fallback"
+            );
+        }
+    }
+    registry.register(Box::new(fetch_tool));
+
+    // mvm.web_search
+    let search_allow = web_search::allowlist_from_env_var(web_search::ALLOWLIST_ENV_VAR);
+    let default_provider = std::env::var(web_search::DEFAULT_PROVIDER_ENV_VAR)
+        .ok()
+        .or_else(|| search_allow.iter().next().cloned())
+        .unwrap_or_else(|| "noop".to_string());
+    let mut search_tool =
+        web_search::WebSearchTool::with_allowlist(search_allow.iter().cloned(), default_provider);
+    // Plan 65 W4 — credentials resolve through env var OR named
+    // secret-store entry. The secret_store backs `mvmctl secret`
+    // (OS keyring on Mac/Linux with gnome-keyring, file fallback
+    // elsewhere). Operators rotate by re-running `mvmctl secret
+    // put`; the supervisor picks up the new value on next
+    // `mvmctl mcp stdio` boot.
+    let secret_store = mvm_security::secret_store::default_secret_store();
+    if search_allow.contains("brave")
+        && let Some(key) = resolve_provider_credential(
+            web_search::BRAVE_API_KEY_ENV_VAR,
+            BRAVE_API_KEY_FROM_SECRET_ENV_VAR,
+            secret_store.as_ref(),
+        )
+    {
+        match web_search::BraveSearchProvider::new(key) {
+            Ok(p) => {
+                search_tool = search_tool.register_provider(Arc::new(p));
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    "BraveSearchProvider init failed; mvm.web_search 'brave' provider will be \
+                     allowlisted-but-unregistered"
+                );
+            }
+        }
+    }
+    if search_allow.contains("tavily")
+        && let Some(key) = resolve_provider_credential(
+            web_search::TAVILY_API_KEY_ENV_VAR,
+            TAVILY_API_KEY_FROM_SECRET_ENV_VAR,
+            secret_store.as_ref(),
+        )
+    {
+        match web_search::TavilySearchProvider::new(key) {
+            Ok(p) => {
+                search_tool = search_tool.register_provider(Arc::new(p));
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    "TavilySearchProvider init failed; mvm.web_search 'tavily' provider will be \
+                     allowlisted-but-unregistered"
+                );
+            }
+        }
+    }
+    if search_allow.contains("google") {
+        let api_key = resolve_provider_credential(
+            web_search::GOOGLE_API_KEY_ENV_VAR,
+            GOOGLE_API_KEY_FROM_SECRET_ENV_VAR,
+            secret_store.as_ref(),
+        );
+        let cse_id = resolve_provider_credential(
+            web_search::GOOGLE_CSE_ID_ENV_VAR,
+            GOOGLE_CSE_ID_FROM_SECRET_ENV_VAR,
+            secret_store.as_ref(),
+        );
+        match (api_key, cse_id) {
+            (Some(api_key), Some(cse_id)) => {
+                match web_search::GoogleSearchProvider::new(api_key, cse_id) {
+                    Ok(p) => {
+                        search_tool = search_tool.register_provider(Arc::new(p));
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            error = %e,
+                            "GoogleSearchProvider init failed; mvm.web_search 'google' provider will \
+                             be allowlisted-but-unregistered"
+                        );
+                    }
+                }
+            }
+            _ => {
+                // Helpful diagnostic — one or both of API key + CSE
+                // ID isn't reachable via env var or secret store.
+                // The existing "allowed-but-unregistered" config-
+                // drift error fires at invoke time too, but this
+                // warning surfaces the issue at supervisor boot.
+                tracing::warn!(
+                    "mvm.web_search 'google' is allowlisted but the API key and/or CSE ID is not \
+                     reachable via env var ({}, {}) or secret_store ({}, {}); provider will be \
+                     allowlisted-but-unregistered",
+                    web_search::GOOGLE_API_KEY_ENV_VAR,
+                    web_search::GOOGLE_CSE_ID_ENV_VAR,
+                    GOOGLE_API_KEY_FROM_SECRET_ENV_VAR,
+                    GOOGLE_CSE_ID_FROM_SECRET_ENV_VAR,
+                );
+            }
+        }
+    }
+    registry.register(Box::new(search_tool));
+
+    // mvm.upload + mvm.download — share one per-tenant FsStagingArea.
+    // Default tenant matches the rest of the host-local scope ("local");
+    // a future slice plumbs the per-call tenant once mvmctl mcp gains
+    // the corresponding policy bundle.
+    let staging_area = staging::default_for_tenant("local");
+    registry.register(Box::new(upload::UploadTool::with_staging(
+        staging_area.clone(),
+    )));
+    registry.register(Box::new(download::DownloadTool::with_staging(staging_area)));
+
+    registry
+}
+
+impl ExecDispatcher {
+    /// Cold-boot path: every call boots its own transient VM, runs,
+    /// and tears it down. Used when the client did not supply a
+    /// `session` parameter. The teardown is also what stops a
+    /// cancelled command on an agent without process RPC.
+    fn run_cold(
+        &self,
+        env: &str,
+        code: &str,
+        timeout: u64,
+        watch: &mut dyn FnMut(Duration, usize) -> bool,
+    ) -> Result<crate::exec::ExecOutput, anyhow::Error> {
+        let vm = crate::exec::boot_session_vm(env, "mcp-run", DEFAULT_VM_CPUS, DEFAULT_VM_MEM_MIB)?;
+        let result = crate::exec::run_in_session(&vm, code.to_string(), timeout, watch);
+        crate::exec::tear_down_session_vm(vm);
+        result
+    }
+
+    /// Warm-VM path (A.2 v2): boot the session's VM on first call,
+    /// reuse it on subsequent calls. The per-session lock serialises
+    /// concurrent dispatches against the same session — stdout/stderr
+    /// from the guest agent over a single vsock socket aren't
+    /// interleave-safe.
+    ///
+    /// A VM whose agent stays unreachable closes the session as
+    /// [`ReapReason::VmLost`], so the next call with the same id boots
+    /// afresh instead of failing against a dead VM forever. A call
+    /// cancelled on an agent without process RPC closes it as
+    /// [`ReapReason::Cancelled`]: tearing the VM down is the only way
+    /// to stop the command.
+    fn run_warm(
+        &self,
+        session_id: &str,
+        env: &str,
+        code: &str,
+        timeout: u64,
+        watch: &mut dyn FnMut(Duration, usize) -> bool,
+    ) -> Result<crate::exec::ExecOutput, anyhow::Error> {
+        let handle = self.get_or_boot_warm_vm(session_id, env)?;
+        let result = {
+            let vm = handle
+                .lock()
+                .map_err(|_| anyhow::anyhow!("warm-VM lock poisoned for session '{session_id}'"))?;
+            if crate::exec::wait_for_agent(&vm.vm_name, SESSION_AGENT_WAIT_SECS) {
+                Some(crate::exec::run_in_session(
+                    &vm,
+                    code.to_string(),
+                    timeout,
+                    watch,
+                ))
+            } else {
+                None
+            }
+        };
+        drop(handle);
+        let Some(result) = result else {
+            if let Ok(mut map) = self.sessions.lock() {
+                map.remove(session_id, ReapReason::VmLost, self.reaper.as_ref());
+            }
+            self.tear_down_if_orphaned(session_id);
+            anyhow::bail!(
+                "session '{session_id}' VM stopped responding; the session was closed and the \
+                 next call with this id starts a fresh VM"
+            );
+        };
+        if let Err(e) = &result
+            && e.is::<crate::exec::SessionCallAbandoned>()
+            && let Ok(mut map) = self.sessions.lock()
+        {
+            map.remove(session_id, ReapReason::Cancelled, self.reaper.as_ref());
+        }
+        self.tear_down_if_orphaned(session_id);
+        result
+    }
+
+    /// Tear down the warm VM of a session that is no longer in the
+    /// map. The reaper normally does this, but a boot that finishes
+    /// after its session was reaped inserts a handle the reaper never
+    /// sees. Called after every warm dispatch.
+    fn tear_down_if_orphaned(&self, session_id: &str) {
+        let live = self
+            .sessions
+            .lock()
+            .map(|map| map.get(session_id).is_some())
+            .unwrap_or(true);
+        if live {
+            return;
+        }
+        let orphan = self
+            .warm_vms
+            .lock()
+            .ok()
+            .and_then(|mut warm| warm.remove(session_id));
+        if let Some(handle) = orphan {
+            tracing::debug!(session = session_id, "tearing down orphaned session VM");
+            retire_session_vm(handle, ReapReason::Closed);
+        }
+    }
+
+    /// Look up an existing warm VM for the session, or boot a new one
+    /// if none exists. Returns the per-session handle (an
+    /// `Arc<Mutex<SessionVm>>` so concurrent dispatches serialise on
+    /// the same VM).
+    fn get_or_boot_warm_vm(
+        &self,
+        session_id: &str,
+        env: &str,
+    ) -> Result<Arc<Mutex<crate::exec::SessionVm>>, anyhow::Error> {
+        // Fast path: handle already in the map.
+        if let Ok(warm) = self.warm_vms.lock()
+            && let Some(handle) = warm.get(session_id)
+        {
+            return Ok(Arc::clone(handle));
+        }
+
+        // Slow path: boot a new VM. Releasing the warm_vms lock
+        // before booting avoids holding it across a multi-second
+        // operation; the worst case is two concurrent first-calls
+        // race to boot, the second discovers the first's handle and
+        // tears down its own boot. That's correct (no leak) at the
+        // cost of an extra VM start in pathological cases.
+        let prefix = format!("mcp-session-{}", short_id(session_id));
+        let booted =
+            crate::exec::boot_session_vm(env, &prefix, DEFAULT_VM_CPUS, DEFAULT_VM_MEM_MIB)
+                .with_context(|| format!("booting warm VM for session '{session_id}'"))?;
+        let booted_name = booted.vm_name.clone();
+        let handle = Arc::new(Mutex::new(booted));
+
+        let race_winner: Option<Arc<Mutex<crate::exec::SessionVm>>> = {
+            let mut warm = self
+                .warm_vms
+                .lock()
+                .map_err(|_| anyhow::anyhow!("warm-VM map lock poisoned"))?;
+            if let Some(existing) = warm.get(session_id) {
+                Some(Arc::clone(existing))
+            } else {
+                warm.insert(session_id.to_string(), Arc::clone(&handle));
+                None
+            }
+        };
+
+        if let Some(existing) = race_winner {
+            // Another thread booted in parallel. Tear down our VM and
+            // return theirs.
+            if let Ok(extra_mutex) = Arc::try_unwrap(handle)
+                && let Ok(extra) = extra_mutex.into_inner()
+            {
+                tracing::debug!(vm = %extra.vm_name, "tearing down racing session VM");
+                crate::exec::tear_down_session_vm(extra);
+            }
+            return Ok(existing);
+        }
+
+        // We won the boot race. Update the SessionMap's recorded
+        // vm_name so the reaper and audit logs see it. If the session
+        // was reaped while we booted, `run_warm`'s orphan check tears
+        // the VM down after this call.
+        if let Ok(mut map) = self.sessions.lock() {
+            map.set_vm_name(session_id, booted_name);
+        }
+        Ok(handle)
+    }
+
+    /// Start a background thread that sweeps the session map every
+    /// [`REAPER_TICK_SECS`]. Idempotent — safe to call once at
+    /// startup. The thread is detached: it dies with the process.
+    fn spawn_reaper(&self) {
+        let sessions = Arc::clone(&self.sessions);
+        let reaper = Arc::clone(&self.reaper);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(Duration::from_secs(REAPER_TICK_SECS));
+                let n = match sessions.lock() {
+                    Ok(mut map) => map.reap_expired(reaper.as_ref()),
+                    Err(_) => return, // poisoned mutex = process is unwinding
+                };
+                if n > 0 {
+                    tracing::debug!(reaped = n, "MCP session reaper swept");
+                }
+            }
+        });
+    }
+}
+
+impl ExecDispatcher {
+    /// Reaper for the HTTP transport's own sessions. Closing one
+    /// also closes the `run` sessions scoped to it (and their warm
+    /// VMs) with the same reason, rather than leaving them to idle
+    /// out.
+    fn transport_reaper(&self) -> TransportReaper {
+        TransportReaper {
+            sessions: Arc::clone(&self.sessions),
+            reaper: Arc::clone(&self.reaper),
+        }
+    }
+}
+
+/// On Drop, drain the session map and audit-log every remaining
+/// session as `Shutdown`. Kicks in when the stdio loop exits cleanly.
+impl Drop for ExecDispatcher {
+    fn drop(&mut self) {
+        if let Ok(mut map) = self.sessions.lock() {
+            let n = map.drain(self.reaper.as_ref());
+            if n > 0 {
+                tracing::info!(drained = n, "MCP server shutdown drained sessions");
+            }
+        }
+    }
+}
+
+/// Reaper impl that audit-logs the close *and* tears down the warm
+/// VM (A.2 v2). The trait-based design (per `mvm_mcp::session`) means
+/// mvmd's hosted variant can plug in its own reaper that uses its
+/// per-tenant orchestrator without changing the map contract.
+struct DispatcherReaper {
+    warm_vms: WarmVms,
+}
+
+impl Reaper for DispatcherReaper {
+    fn on_reap(&self, session_id: &str, state: &SessionState, reason: ReapReason) {
+        let detail = serde_json::json!({
+            "session": session_id,
+            "env": state.env,
+            "reason": reason_str(reason),
+            "vm_name": state.vm_name,
+            "lifetime_secs": state.started_at.elapsed().as_secs(),
+        })
+        .to_string();
+        mvm_core::policy::audit::emit(
+            mvm_core::policy::audit::LocalAuditKind::McpSessionClosed,
+            state.vm_name.as_deref(),
+            Some(&detail),
+        );
+
+        // A.2 v2: actually tear down the warm VM. The handle lives in
+        // `warm_vms`, which we own a strong ref to. Removing it drops
+        // the Arc; if another Arc is still held by an in-flight
+        // dispatch the VM survives until that completes — but the
+        // dispatcher won't route new calls to it because the
+        // SessionMap entry is already gone.
+        let handle = self
+            .warm_vms
+            .lock()
+            .ok()
+            .and_then(|mut warm| warm.remove(session_id));
+        if let Some(handle) = handle {
+            retire_session_vm(handle, reason);
+        }
+    }
+}
+
+/// See [`ExecDispatcher::transport_reaper`].
+struct TransportReaper {
+    sessions: Arc<Mutex<SessionMap>>,
+    reaper: Arc<DispatcherReaper>,
+}
+
+impl Reaper for TransportReaper {
+    fn on_reap(&self, session_id: &str, state: &SessionState, reason: ReapReason) {
+        let scoped = format!("{session_id}:");
+        let closed_runs = self
+            .sessions
+            .lock()
+            .map(|mut map| {
+                map.remove_matching(|id| id.starts_with(&scoped), reason, self.reaper.as_ref())
+            })
+            .unwrap_or(0);
+        let detail = serde_json::json!({
+            "session": session_id,
+            "transport": "http",
+            "reason": reason_str(reason),
+            "run_sessions_closed": closed_runs,
+            "lifetime_secs": state.started_at.elapsed().as_secs(),
+        })
+        .to_string();
+        mvm_core::policy::audit::emit(
+            mvm_core::policy::audit::LocalAuditKind::McpSessionClosed,
+            None,
+            Some(&detail),
+        );
+    }
+}
+
+/// Tear down a warm VM that has left `warm_vms`.
+///
+/// Stopping a VM can take seconds, and the reaper runs under the
+/// session-map lock, so teardown happens on a detached thread —
+/// except at shutdown, where the process exits right after the drain
+/// and would kill the thread first. A handle an in-flight dispatch
+/// still holds is torn down once that dispatch releases the VM lock.
+fn retire_session_vm(handle: Arc<Mutex<crate::exec::SessionVm>>, reason: ReapReason) {
+    match Arc::try_unwrap(handle) {
+        Ok(vm_mutex) => {
+            let vm = vm_mutex
+                .into_inner()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if reason == ReapReason::Shutdown {
+                crate::exec::tear_down_session_vm(vm);
+            } else {
+                std::thread::spawn(move || crate::exec::tear_down_session_vm(vm));
+            }
+        }
+        Err(shared) => {
+            std::thread::spawn(move || {
+                let vm_name = shared
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .vm_name
+                    .clone();
+                crate::exec::tear_down_session_vm(crate::exec::SessionVm { vm_name });
+            });
+        }
+    }
+}
+
+fn reason_str(r: ReapReason) -> &'static str {
+    match r {
+        ReapReason::Idle => "idle",
+        ReapReason::MaxLifetime => "max_lifetime",
+        ReapReason::Closed => "closed",
+        ReapReason::Shutdown => "shutdown",
+        ReapReason::VmLost => "vm_lost",
+        ReapReason::Cancelled => "cancelled",
+    }
+}
+
+/// Touch (or open) `session_id` for a call in `env`. A session runs
+/// in one warm VM booted from the env of its first call, so a later
+/// call naming a different env is refused rather than silently run in
+/// the wrong image.
+fn touch_session(
+    map: &mut SessionMap,
+    session_id: &str,
+    env: &str,
+) -> Result<SessionLookup, String> {
+    if let Some(state) = map.get(session_id)
+        && state.env != env
+    {
+        return Err(format!(
+            "session '{session_id}' is bound to env '{}'; open a new session for env '{env}'",
+            state.env
+        ));
+    }
+    Ok(map.touch_or_insert(session_id, env, None))
+}
+
+/// Truncate a session id to the first 8 chars for use in VM names.
+/// Keeps `mvmctl ls` readable when the LLM client sends a long UUID.
+fn short_id(session_id: &str) -> String {
+    session_id.chars().take(8).collect()
+}
+
+impl Dispatcher for ExecDispatcher {
+    fn run_with_peer(&self, params: RunParams, peer: &dyn ClientPeer) -> ToolResult {
+        if let Some(refused) = self.gate(ApprovalSubject::Exec, &params.env, &params.code, peer) {
+            return refused;
+        }
+        // Progress is reported against the call's timeout; a cancelled
+        // call has its guest process killed at the next tick.
+        let total = clamp_timeout(params.timeout_secs) as f64;
+        let cancel = peer.cancel_token();
+        self.run_watched(params, &mut |elapsed, output_bytes| {
+            if cancel.is_cancelled() {
+                return false;
+            }
+            peer.progress(
+                elapsed.as_secs() as f64,
+                Some(total),
+                Some(&format!("{output_bytes} bytes of output")),
+            );
+            true
+        })
+    }
+
+    fn invoke_tool_with_peer(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        peer: &dyn ClientPeer,
+    ) -> ToolResult {
+        let command = format!("{name} {params}");
+        if let Some(refused) = self.gate(ApprovalSubject::Tool, name, &command, peer) {
+            return refused;
+        }
+        if self.tool_gate.is_allowed(name)
+            && let Ok(GuestToolCall::ProcStart(p)) = GuestToolCall::parse(name, params.clone())
+            && let Some(refused) = self.gate_proc_start(&p.session, &p.argv, peer)
+        {
+            return refused;
+        }
+        self.invoke_tool(name, params)
+    }
+
+    fn run(&self, params: RunParams) -> ToolResult {
+        self.run_watched(params, &mut |_, _| true)
+    }
+
+    /// The `run` tool lists the registered templates as its envs;
+    /// guest tools are listed when the tool policy allows them.
+    fn list_tools(&self) -> Vec<ToolSchema> {
+        let mut tools = mvm_mcp::all_tools_for_envs(&registered_envs());
+        tools.extend(
+            mvm_mcp::guest_tools()
+                .into_iter()
+                .filter(|t| self.tool_gate.is_allowed(&t.name)),
+        );
+        tools
+    }
+
+    fn tools_revision(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        registered_envs().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn list_resources(&self, peer: &dyn ClientPeer) -> Vec<Resource> {
+        let scope = peer.session_scope();
+        let Ok(map) = self.sessions.lock() else {
+            return Vec::new();
+        };
+        map.iter()
+            .filter_map(|(key, state)| {
+                let id = client_session_id(key, scope.as_deref())?;
+                Some(session_resources(id, &state.env))
+            })
+            .flatten()
+            .collect()
+    }
+
+    fn resource_templates(&self) -> Vec<ResourceTemplate> {
+        vec![ResourceTemplate {
+            uri_template: format!("{SESSION_RESOURCE_PREFIX}{{session}}/files/{{+path}}"),
+            name: "session-file".to_string(),
+            description: Some(
+                "A file or directory inside a warm `run` session's microVM.".to_string(),
+            ),
+            mime_type: None,
+        }]
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+        peer: &dyn ClientPeer,
+    ) -> Result<Vec<ResourceContents>, JsonRpcError> {
+        let (id, tail) =
+            parse_session_uri(uri).ok_or_else(|| JsonRpcError::resource_not_found(uri))?;
+        let key = session_key(id, peer.session_scope().as_deref());
+        // The audit trail outlives the session; the rest needs its VM.
+        if tail == "audit" {
+            let events = session_audit_events(&key);
+            return Ok(vec![ResourceContents::text(
+                uri,
+                "application/json",
+                serde_json::to_string_pretty(&events).unwrap_or_default(),
+            )]);
+        }
+        let vm_name = self
+            .sessions
+            .lock()
+            .ok()
+            .and_then(|map| map.get(&key).and_then(|s| s.vm_name.clone()))
+            .ok_or_else(|| JsonRpcError::resource_not_found(uri))?;
+        if tail == "boot-report" {
+            let report = super::super::vm::wait::fetch_readiness(&vm_name)
+                .map_err(|e| JsonRpcError::internal_error(format!("reading boot report: {e:#}")))?;
+            return Ok(vec![ResourceContents::text(
+                uri,
+                "application/json",
+                serde_json::to_string_pretty(&report).unwrap_or_default(),
+            )]);
+        }
+        let path = tail
+            .strip_prefix("files/")
+            .ok_or_else(|| JsonRpcError::resource_not_found(uri))?;
+        read_session_path(&vm_name, uri, &format!("/{path}"))
+    }
+
+    /// Plan 60 Phase 7 — route registry tools through
+    /// `mvm_supervisor::ToolRegistry`. The legacy `run` tool stays
+    /// on its dedicated method; everything else (`mvm.time_now`,
+    /// `mvm.web_fetch`, `mvm.web_search`, …) lands here.
+    ///
+    /// `ToolRegistry::invoke` is async; the MCP wire layer is sync,
+    /// so we spin up a current-thread runtime per call (same pattern
+    /// as `vm::audit_chain::AuditEmitter`'s `block_on`). Tool calls
+    /// are infrequent relative to per-call cost, and the runtime
+    /// build is dominated by everything else in the dispatch path.
+    fn invoke_tool(&self, name: &str, params: serde_json::Value) -> ToolResult {
+        let rt = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => rt,
+            Err(e) => {
+                return error_result(format!(
+                    "internal: building tokio runtime for tool {name:?}: {e}"
+                ));
+            }
+        };
+        match rt.block_on(self.tool_registry.invoke(name, params)) {
+            Ok(value) => {
+                // Render the JSON value as a single Text content
+                // block. The LLM client parses the body; pretty-
+                // printing keeps it human-readable when an operator
+                // inspects audit logs.
+                let text = serde_json::to_string_pretty(&value)
+                    .unwrap_or_else(|e| format!("(failed to serialize tool result: {e})"));
+                ToolResult {
+                    content: vec![ContentBlock::Text { text }],
+                    is_error: false,
+                }
+            }
+            Err(e) => error_result(e.to_string()),
+        }
+    }
+}
+
+impl ExecDispatcher {
+    /// [`Dispatcher::run`] with a `watch` callback for the guest
+    /// process (see [`crate::exec::run_in_session`]).
+    fn run_watched(
+        &self,
+        params: RunParams,
+        watch: &mut dyn FnMut(Duration, usize) -> bool,
+    ) -> ToolResult {
+        // Concurrency gate (cross-cutting "A: resource limits").
+        let prev = self.inflight.fetch_add(1, Ordering::SeqCst);
+        let _guard = InflightGuard(&self.inflight);
+        if prev >= self.max_inflight {
+            return error_result(format!(
+                "MCP server busy: {} calls in flight (cap MVM_MCP_MAX_INFLIGHT={}). Retry shortly.",
+                prev + 1,
+                self.max_inflight
+            ));
+        }
+
+        // Validate env against the local template registry.
+        if let Err(e) = validate_env(&params.env) {
+            return error_result(format!("{e}"));
+        }
+
+        // Session bookkeeping (A.2 v1). Touch the map before the
+        // dispatch so audit logs see "session started" before
+        // "tools/call ran".
+        if let Some(session_id) = params.session.as_deref() {
+            let lookup = match self.sessions.lock() {
+                Ok(mut map) => match touch_session(&mut map, session_id, &params.env) {
+                    Ok(lookup) => lookup,
+                    Err(e) => return error_result(e),
+                },
+                Err(_) => SessionLookup::Created,
+            };
+            if matches!(lookup, SessionLookup::Created) {
+                let detail = serde_json::json!({
+                    "session": session_id,
+                    "env": params.env,
+                })
+                .to_string();
+                mvm_core::policy::audit::emit(
+                    mvm_core::policy::audit::LocalAuditKind::McpSessionStarted,
+                    Some(&params.env),
+                    Some(&detail),
+                );
+            }
+        }
+
+        // Memory ceiling check: reject envs whose recorded mem_mib
+        // exceeds MVM_MCP_MEM_CEILING_MIB. Missing spec is a soft
+        // pass since we don't know the size.
+        if let Ok(spec) = mvm::vm::template::lifecycle::template_load(&params.env)
+            && spec.mem_mib > self.mem_ceiling_mib
+        {
+            return error_result(format!(
+                "env '{}' requests {} MiB which exceeds MVM_MCP_MEM_CEILING_MIB={}",
+                params.env, spec.mem_mib, self.mem_ceiling_mib
+            ));
+        }
+
+        let timeout = clamp_timeout(params.timeout_secs);
+
+        let started = std::time::Instant::now();
+        let result = match params.session.as_deref() {
+            Some(session_id) => {
+                self.run_warm(session_id, &params.env, &params.code, timeout, watch)
+            }
+            None => self.run_cold(&params.env, &params.code, timeout, watch),
+        };
+        let elapsed = started.elapsed();
+
+        // After the dispatch completes (regardless of success), honour
+        // an explicit close request so the reaper has nothing left to
+        // do for this session — also tears down the warm VM via the
+        // reaper impl.
+        if let (Some(session_id), Some(true)) = (params.session.as_deref(), params.close)
+            && let Ok(mut map) = self.sessions.lock()
+        {
+            map.remove(session_id, ReapReason::Closed, self.reaper.as_ref());
+        }
+
+        match result {
+            Ok(out) => {
+                let stdout = truncate_with_marker(&out.stdout);
+                let stderr = truncate_with_marker(&out.stderr);
+                audit_call_complete(
+                    &params.env,
+                    params.code.len(),
+                    out.exit_code,
+                    elapsed.as_millis() as u64,
+                    params.session.as_deref(),
+                );
+                ToolResult {
+                    content: vec![
+                        ContentBlock::Text { text: stdout },
+                        ContentBlock::Text {
+                            text: format!("[stderr]\n{stderr}"),
+                        },
+                        ContentBlock::Text {
+                            text: format!("exit_code={}", out.exit_code),
+                        },
+                    ],
+                    is_error: out.exit_code != 0,
+                }
+            }
+            Err(e) => {
+                audit_call_error(
+                    &params.env,
+                    params.code.len(),
+                    elapsed.as_millis() as u64,
+                    params.session.as_deref(),
+                    &format!("{e:#}"),
+                );
+                error_result(format!("microVM exec failed: {e:#}"))
+            }
+        }
+    }
+}
+
+struct InflightGuard<'a>(&'a AtomicUsize);
+impl Drop for InflightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn error_result(msg: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![ContentBlock::Text { text: msg.into() }],
+        is_error: true,
+    }
+}
+
+fn clamp_timeout(t: Option<u64>) -> u64 {
+    t.unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS)
+}
+
+/// Cap `s` at [`STREAM_CAP_BYTES`] and append a marker reporting how
+/// many bytes were dropped. UTF-8 boundary aware.
+fn truncate_with_marker(s: &str) -> String {
+    if s.len() <= STREAM_CAP_BYTES {
+        return s.to_string();
+    }
+    let mut end = STREAM_CAP_BYTES;
+    while end > 0 && !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    let dropped = s.len() - end;
+    format!("{}\n[truncated, {} more bytes]", &s[..end], dropped)
+}
+
+fn parse_env_usize(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+fn parse_env_u32(name: &str, default: u32) -> u32 {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Registered template names; empty when the registry can't be read.
+fn registered_envs() -> Vec<String> {
+    mvm::vm::template::lifecycle::template_list().unwrap_or_default()
+}
+
+/// Session resources live under `mvm://sessions/<session>/`, where
+/// `<session>` is the id the client passed to `run`.
+const SESSION_RESOURCE_PREFIX: &str = "mvm://sessions/";
+
+/// Largest guest file returned by `resources/read`.
+const RESOURCE_READ_CAP: u64 = 1024 * 1024;
+
+/// Most recent audit events returned for one session.
+const RESOURCE_AUDIT_EVENTS: usize = 500;
+
+/// The client's id for session-map key `key`, if a peer with `scope`
+/// may see it. HTTP transport sessions key their `run` sessions as
+/// `<scope>:<id>`; stdio has no scope and sees every session.
+fn client_session_id<'k>(key: &'k str, scope: Option<&str>) -> Option<&'k str> {
+    match scope {
+        None => Some(key),
+        Some(scope) => key.strip_prefix(scope)?.strip_prefix(':'),
+    }
+}
+
+/// Inverse of [`client_session_id`].
+fn session_key(id: &str, scope: Option<&str>) -> String {
+    match scope {
+        None => id.to_string(),
+        Some(scope) => format!("{scope}:{id}"),
+    }
+}
+
+/// Split `mvm://sessions/<id>/<tail>` into `(id, tail)`.
+fn parse_session_uri(uri: &str) -> Option<(&str, &str)> {
+    let (id, tail) = uri.strip_prefix(SESSION_RESOURCE_PREFIX)?.split_once('/')?;
+    (!id.is_empty()).then_some((id, tail))
+}
+
+fn session_resources(id: &str, env: &str) -> Vec<Resource> {
+    let resource = |tail: &str, name: &str, description: String| Resource {
+        uri: format!("{SESSION_RESOURCE_PREFIX}{id}/{tail}"),
+        name: format!("{id} {name}"),
+        description: Some(description),
+        mime_type: Some("application/json".to_string()),
+    };
+    vec![
+        resource(
+            "files/",
+            "files",
+            format!("Root directory of session '{id}' ({env}); read a file under files/<path>."),
+        ),
+        resource(
+            "boot-report",
+            "boot report",
+            format!("Guest readiness and boot timings of session '{id}'."),
+        ),
+        resource(
+            "audit",
+            "audit",
+            format!("MCP audit events of session '{id}'."),
+        ),
+    ]
+}
+
+/// The local audit log's MCP events for session-map key `key`, most
+/// recent last.
+fn session_audit_events(key: &str) -> Vec<serde_json::Value> {
+    use mvm_core::policy::audit::{LocalAuditEvent, LocalAuditKind};
+    use std::io::BufRead;
+
+    let Ok(file) = std::fs::File::open(mvm_core::policy::audit::default_audit_log()) else {
+        return Vec::new();
+    };
+    let mut events: std::collections::VecDeque<serde_json::Value> =
+        std::collections::VecDeque::new();
+    for line in std::io::BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(event) = serde_json::from_str::<LocalAuditEvent>(&line) else {
+            continue;
+        };
+        if !matches!(
+            event.kind,
+            LocalAuditKind::McpSessionStarted
+                | LocalAuditKind::McpSessionClosed
+                | LocalAuditKind::McpToolsCallRun
+                | LocalAuditKind::McpToolsCallRunError
+        ) {
+            continue;
+        }
+        let detail: serde_json::Value = event
+            .detail
+            .as_deref()
+            .and_then(|d| serde_json::from_str(d).ok())
+            .unwrap_or_default();
+        if detail.get("session").and_then(serde_json::Value::as_str) != Some(key) {
+            continue;
+        }
+        if events.len() == RESOURCE_AUDIT_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(serde_json::json!({
+            "timestamp": event.timestamp,
+            "kind": event.kind,
+            "vm_name": event.vm_name,
+            "detail": detail,
+        }));
+    }
+    events.into()
+}
+
+/// Read `path` in session VM `vm_name`: a directory as a JSON
+/// listing, a file as text (or base64 when it is not UTF-8).
+fn read_session_path(
+    vm_name: &str,
+    uri: &str,
+    path: &str,
+) -> Result<Vec<ResourceContents>, JsonRpcError> {
+    use mvm_guest::vsock::{FsEntryKind, FsErrorKind, FsResult, GuestRequest};
+
+    let fs = |request: GuestRequest| match crate::exec::session_fs_request(vm_name, request) {
+        Ok(FsResult::Error {
+            kind: FsErrorKind::NotFound,
+            ..
+        }) => Err(JsonRpcError::resource_not_found(uri)),
+        Ok(FsResult::Error { kind, message }) => Err(JsonRpcError::internal_error(format!(
+            "guest FS error ({kind:?}): {message}"
+        ))),
+        Ok(FsResult::Backpressure { reason, detail }) => Err(JsonRpcError::internal_error(
+            format!("guest FS quota throttled the call ({reason:?}): {detail}"),
+        )),
+        Ok(result) => Ok(result),
+        Err(e) => Err(JsonRpcError::internal_error(format!("{e:#}"))),
+    };
+    let stat = fs(GuestRequest::FsStat {
+        path: path.to_string(),
+        follow_symlinks: true,
+    })?;
+    let FsResult::Stat(stat) = stat else {
+        return Err(JsonRpcError::internal_error("unexpected FsStat reply"));
+    };
+
+    if stat.kind == FsEntryKind::Dir {
+        let FsResult::List { entries, truncated } = fs(GuestRequest::FsList {
+            path: path.to_string(),
+            follow_symlinks: true,
+        })?
+        else {
+            return Err(JsonRpcError::internal_error("unexpected FsList reply"));
+        };
+        let base = uri.trim_end_matches('/');
+        let entries: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|e| {
+                let slash = if e.kind == FsEntryKind::Dir { "/" } else { "" };
+                serde_json::json!({
+                    "name": e.name,
+                    "kind": e.kind,
+                    "size": e.size,
+                    "uri": format!("{base}/{}{slash}", e.name),
+                })
+            })
+            .collect();
+        let listing =
+            serde_json::json!({ "path": path, "entries": entries, "truncated": truncated });
+        return Ok(vec![ResourceContents::text(
+            uri,
+            "application/json",
+            serde_json::to_string_pretty(&listing).unwrap_or_default(),
+        )]);
+    }
+
+    if stat.size > RESOURCE_READ_CAP {
+        return Err(JsonRpcError::invalid_params(format!(
+            "{path} is {} bytes; resources/read returns files up to {RESOURCE_READ_CAP} bytes",
+            stat.size
+        )));
+    }
+    let FsResult::Read { content, .. } = fs(GuestRequest::FsRead {
+        path: path.to_string(),
+        offset: None,
+        length: RESOURCE_READ_CAP,
+        follow_symlinks: true,
+    })?
+    else {
+        return Err(JsonRpcError::internal_error("unexpected FsRead reply"));
+    };
+    Ok(vec![match String::from_utf8(content) {
+        Ok(text) => ResourceContents::text(uri, "text/plain", text),
+        Err(e) => {
+            use base64::Engine;
+            ResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some("application/octet-stream".to_string()),
+                text: None,
+                blob: Some(base64::engine::general_purpose::STANDARD.encode(e.into_bytes())),
+            }
+        }
+    }])
+}
+
+fn validate_env(env: &str) -> anyhow::Result<()> {
+    let envs = mvm::vm::template::lifecycle::template_list()?;
+    if envs.iter().any(|e| e == env) {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "env '{env}' is not a registered mvmctl template. Available envs: [{}]. \
+         Build new ones via `mvmctl template create … && mvmctl template build <name>`.",
+        envs.join(", ")
+    ))
+}
+
+fn audit_call_complete(
+    env: &str,
+    code_len: usize,
+    exit_code: i32,
+    elapsed_ms: u64,
+    session: Option<&str>,
+) {
+    // `LocalAuditKind::McpToolsCallRun` is the v1 mvm-core kind. The
+    // existing local audit API takes a free-form `detail` string, so
+    // we serialise the structured payload into JSON. Audit is
+    // best-effort; failures land in `tracing::warn!` only.
+    let detail = serde_json::json!({
+        "code_len": code_len,
+        "exit_code": exit_code,
+        "elapsed_ms": elapsed_ms,
+        "session": session,
+    })
+    .to_string();
+    mvm_core::policy::audit::emit(
+        mvm_core::policy::audit::LocalAuditKind::McpToolsCallRun,
+        Some(env),
+        Some(&detail),
+    );
+}
+
+fn audit_call_error(
+    env: &str,
+    code_len: usize,
+    elapsed_ms: u64,
+    session: Option<&str>,
+    error: &str,
+) {
+    let detail = serde_json::json!({
+        "code_len": code_len,
+        "elapsed_ms": elapsed_ms,
+        "session": session,
+        "error": error,
+    })
+    .to_string();
+    mvm_core::policy::audit::emit(
+        mvm_core::policy::audit::LocalAuditKind::McpToolsCallRunError,
+        Some(env),
+        Some(&detail),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_under_cap_passes_through() {
+        let s = "hello world";
+        assert_eq!(truncate_with_marker(s), s);
+    }
+
+    #[test]
+    fn truncate_over_cap_appends_marker() {
+        let s = "x".repeat(STREAM_CAP_BYTES + 100);
+        let out = truncate_with_marker(&s);
+        assert!(out.contains("[truncated, 100 more bytes]"));
+        assert!(out.len() < STREAM_CAP_BYTES + 50, "marker is short");
+    }
+
+    #[test]
+    fn truncate_respects_utf8_boundary() {
+        let prefix = "x".repeat(STREAM_CAP_BYTES - 1);
+        let s = format!("{prefix}éééé");
+        let out = truncate_with_marker(&s);
+        // Truncated form must still parse as valid UTF-8 (Rust string
+        // literal guarantees this) and contain the marker.
+        assert!(out.contains("[truncated"));
+    }
+
+    #[test]
+    fn timeout_clamps_to_bounds() {
+        assert_eq!(clamp_timeout(None), DEFAULT_TIMEOUT_SECS);
+        assert_eq!(clamp_timeout(Some(0)), MIN_TIMEOUT_SECS);
+        assert_eq!(clamp_timeout(Some(99_999)), MAX_TIMEOUT_SECS);
+        assert_eq!(clamp_timeout(Some(30)), 30);
+    }
+
+    #[test]
+    fn touch_session_opens_then_reuses() {
+        let mut map = SessionMap::default();
+        assert_eq!(
+            touch_session(&mut map, "s1", "shell"),
+            Ok(SessionLookup::Created)
+        );
+        assert_eq!(
+            touch_session(&mut map, "s1", "shell"),
+            Ok(SessionLookup::Existing)
+        );
+    }
+
+    #[test]
+    fn session_uris_round_trip_through_the_transport_scope() {
+        assert_eq!(
+            parse_session_uri("mvm://sessions/s1/files/tmp/out.txt"),
+            Some(("s1", "files/tmp/out.txt"))
+        );
+        assert_eq!(
+            parse_session_uri("mvm://sessions/s1/audit"),
+            Some(("s1", "audit"))
+        );
+        assert_eq!(parse_session_uri("mvm://sessions//audit"), None);
+        assert_eq!(parse_session_uri("file:///etc/passwd"), None);
+
+        assert_eq!(session_key("s1", None), "s1");
+        assert_eq!(session_key("s1", Some("abc")), "abc:s1");
+        assert_eq!(client_session_id("abc:s1", Some("abc")), Some("s1"));
+        assert_eq!(client_session_id("xyz:s1", Some("abc")), None);
+        assert_eq!(client_session_id("abcd:s1", Some("abc")), None);
+        assert_eq!(client_session_id("abc:s1", None), Some("abc:s1"));
+    }
+
+    /// A peer on an HTTP transport session.
+    struct ScopedPeer(&'static str);
+
+    impl ClientPeer for ScopedPeer {
+        fn supports_elicitation(&self) -> bool {
+            false
+        }
+
+        fn client_name(&self) -> Option<String> {
+            None
+        }
+
+        fn elicit(
+            &self,
+            _message: &str,
+            _requested_schema: serde_json::Value,
+        ) -> Result<mvm_mcp::ElicitResult, mvm_mcp::JsonRpcError> {
+            unreachable!("resources never elicit")
+        }
+
+        fn session_scope(&self) -> Option<String> {
+            Some(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn resources_list_only_the_peers_sessions() {
+        let dispatcher = ExecDispatcher::default();
+        {
+            let mut map = dispatcher.sessions.lock().unwrap();
+            map.touch_or_insert("abc:s1", "shell", None);
+            map.touch_or_insert("xyz:s2", "python", None);
+        }
+        let uris: Vec<String> = dispatcher
+            .list_resources(&ScopedPeer("abc"))
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+        assert_eq!(
+            uris,
+            [
+                "mvm://sessions/s1/files/",
+                "mvm://sessions/s1/boot-report",
+                "mvm://sessions/s1/audit",
+            ]
+        );
+        // stdio sees every session under its full key.
+        assert_eq!(dispatcher.list_resources(&mvm_mcp::NoClientPeer).len(), 6);
+
+        // Another transport session's VM is not reachable by id.
+        let err = dispatcher
+            .read_resource("mvm://sessions/s2/files/", &ScopedPeer("abc"))
+            .unwrap_err();
+        assert_eq!(err.code, -32002);
+        let err = dispatcher
+            .read_resource("mvm://sessions/s1/nope", &ScopedPeer("abc"))
+            .unwrap_err();
+        assert_eq!(err.code, -32002);
+        // Drop the sessions without the dispatcher's audit-emitting reaper.
+        struct Quiet;
+        impl Reaper for Quiet {
+            fn on_reap(&self, _: &str, _: &SessionState, _: ReapReason) {}
+        }
+        dispatcher.sessions.lock().unwrap().drain(&Quiet);
+    }
+
+    #[test]
+    fn touch_session_refuses_a_different_env() {
+        let mut map = SessionMap::default();
+        touch_session(&mut map, "s1", "shell").unwrap();
+        let err = touch_session(&mut map, "s1", "python").unwrap_err();
+        assert!(err.contains("bound to env 'shell'"), "{err}");
+        assert_eq!(map.get("s1").unwrap().env, "shell");
+    }
+
+    #[test]
+    fn vm_lost_has_its_own_audit_reason() {
+        assert_eq!(reason_str(ReapReason::VmLost), "vm_lost");
+        assert_eq!(reason_str(ReapReason::Cancelled), "cancelled");
+    }
+
+    // ──────────────────────────────────────────────────────────────
+    // Plan 60 Phase 7 — invoke_tool routes through ToolRegistry
+    // ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn invoke_tool_routes_time_now_through_registry() {
+        // The default ExecDispatcher carries a registry with the 3
+        // Phase 7 builtins. `mvm.time_now` is unconditionally
+        // reachable (no allowlist needed), so this is the
+        // simplest "the wiring works" smoke test.
+        let dispatcher = ExecDispatcher::default();
+        let result = dispatcher.invoke_tool("mvm.time_now", serde_json::json!({}));
+        assert!(!result.is_error, "got error: {result:?}");
+        let ContentBlock::Text { text } = &result.content[0];
+        // The registry's TimeNowResult shape has `"time"` + `"format"` fields.
+        assert!(text.contains("\"time\""), "got: {text}");
+        assert!(text.contains("\"format\""), "got: {text}");
+    }
+
+    #[test]
+    fn guest_tools_are_listed_and_served_only_when_the_policy_allows() {
+        let names = |d: &ExecDispatcher| -> Vec<String> {
+            d.list_tools().into_iter().map(|t| t.name).collect()
+        };
+        let default = ExecDispatcher::default();
+        assert!(!names(&default).iter().any(|n| mvm_mcp::is_guest_tool(n)));
+        let refused = default.invoke_tool(
+            "mvm.fs.stat",
+            serde_json::json!({"session": "s1", "path": "/"}),
+        );
+        assert!(refused.is_error);
+        let ContentBlock::Text { text } = &refused.content[0];
+        assert!(text.contains("not in policy allowlist"), "got: {text}");
+
+        let policy = ToolPolicy {
+            allowed: vec!["mvm.fs.stat".to_string(), "mvm.proc.start".to_string()],
+        };
+        let allowed = ExecDispatcher::new(None, &ThreatPolicy::default(), &policy);
+        let listed: Vec<String> = names(&allowed)
+            .into_iter()
+            .filter(|n| mvm_mcp::is_guest_tool(n))
+            .collect();
+        assert_eq!(listed, ["mvm.fs.stat", "mvm.proc.start"]);
+    }
+
+    #[test]
+    fn invoke_tool_renders_unknown_tool_as_is_error() {
+        // The registry's `UnknownTool` error must surface as an
+        // `is_error: true` ToolResult (NOT a JSON-RPC error), so
+        // the LLM client sees the failure instead of retrying.
+        let dispatcher = ExecDispatcher::default();
+        let result = dispatcher.invoke_tool("mvm.does_not_exist", serde_json::json!({}));
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(text.contains("mvm.does_not_exist"), "got: {text}");
+    }
+
+    #[test]
+    fn invoke_tool_web_fetch_fails_closed_with_clear_message() {
+        // The default registry registers `mvm.web_fetch` with an
+        // empty allowlist (Default::default() = fail_closed). The
+        // operator sees a clear "not on allowlist" error rather
+        // than a silent fall-through.
+        let dispatcher = ExecDispatcher::default();
+        let result = dispatcher.invoke_tool(
+            "mvm.web_fetch",
+            serde_json::json!({ "url": "https://example.com/" }),
+        );
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(text.contains("not on per-tenant allowlist"), "got: {text}");
+    }
+
+    #[test]
+    fn invoke_tool_refuses_params_in_a_blocked_threat_category() {
+        let policy = ThreatPolicy {
+            default_action: None,
+            categories: [(
+                mvm_core::security::ThreatCategory::Destructive,
+                mvm_core::security::BlocklistAction::Block,
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let dispatcher = ExecDispatcher::new(None, &policy, &ToolPolicy::default());
+        let result = dispatcher.invoke_tool(
+            "mvm.time_now",
+            serde_json::json!({ "format": "rm -rf / --no-preserve-root" }),
+        );
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(text.contains("pattern rm_rf_root"), "got: {text}");
+
+        let clean = dispatcher.invoke_tool("mvm.time_now", serde_json::json!({}));
+        assert!(!clean.is_error, "got error: {clean:?}");
+    }
+
+    // ──────────────────────────────────────────────────────────────
+    // Approval broker — RequireApproval tool calls are elicited
+    // from the client, and fall back to the queue when it can't
+    // answer.
+    // ──────────────────────────────────────────────────────────────
+
+    struct ScriptedPeer(Option<mvm_mcp::ElicitResult>);
+
+    impl ClientPeer for ScriptedPeer {
+        fn supports_elicitation(&self) -> bool {
+            self.0.is_some()
+        }
+
+        fn client_name(&self) -> Option<String> {
+            Some("test-client".to_string())
+        }
+
+        fn elicit(
+            &self,
+            _message: &str,
+            _requested_schema: serde_json::Value,
+        ) -> Result<mvm_mcp::ElicitResult, mvm_mcp::JsonRpcError> {
+            Ok(self
+                .0
+                .clone()
+                .expect("elicit called without a scripted answer"))
+        }
+    }
+
+    fn gated_dispatcher(dir: &std::path::Path) -> ExecDispatcher {
+        use mvm_core::security::{BlocklistAction, BlocklistEntry, BlocklistSeverity};
+        use mvm_security::approval::ApprovalQueue;
+        use mvm_security::command_gate::CommandGate;
+        let gate = CommandGate::new(vec![BlocklistEntry {
+            pattern: "mvm.time_now".to_string(),
+            category: "test".to_string(),
+            severity: BlocklistSeverity::Medium,
+            action: BlocklistAction::RequireApproval,
+        }]);
+        let broker = ApprovalBroker::new(gate, ApprovalQueue::open(dir).unwrap())
+            .with_timeout(Duration::from_millis(200))
+            .with_poll_interval(Duration::from_millis(10));
+        ExecDispatcher::new(
+            Some(Arc::new(broker)),
+            &ThreatPolicy::default(),
+            &ToolPolicy::default(),
+        )
+    }
+
+    fn answer(action: ElicitAction, content: serde_json::Value) -> ScriptedPeer {
+        ScriptedPeer(Some(mvm_mcp::ElicitResult {
+            action,
+            content: Some(content),
+        }))
+    }
+
+    #[test]
+    fn gated_tool_runs_once_the_client_approves() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dispatcher = gated_dispatcher(tmp.path());
+        let peer = answer(ElicitAction::Accept, serde_json::json!({ "approve": true }));
+        let result = dispatcher.invoke_tool_with_peer("mvm.time_now", serde_json::json!({}), &peer);
+        assert!(!result.is_error, "got error: {result:?}");
+        assert!(
+            dispatcher
+                .approvals
+                .as_ref()
+                .unwrap()
+                .queue()
+                .pending()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn gated_tool_reports_the_clients_denial() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dispatcher = gated_dispatcher(tmp.path());
+        let peer = answer(
+            ElicitAction::Accept,
+            serde_json::json!({ "approve": false, "reason": "not today" }),
+        );
+        let result = dispatcher.invoke_tool_with_peer("mvm.time_now", serde_json::json!({}), &peer);
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(
+            text.contains("denied by mcp client test-client"),
+            "got: {text}"
+        );
+        assert!(text.contains("not today"), "got: {text}");
+    }
+
+    #[test]
+    fn gated_tool_without_elicitation_times_out_on_the_queue() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dispatcher = gated_dispatcher(tmp.path());
+        let result = dispatcher.invoke_tool_with_peer(
+            "mvm.time_now",
+            serde_json::json!({}),
+            &ScriptedPeer(None),
+        );
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(text.contains("timed out"), "got: {text}");
+    }
+
+    fn proc_start_call(argv: &[&str]) -> serde_json::Value {
+        serde_json::json!({ "session": "s1", "argv": argv })
+    }
+
+    #[test]
+    fn blocklisted_argv_is_refused_through_proc_start() {
+        use mvm_core::security::{BlocklistAction, BlocklistEntry, BlocklistSeverity};
+        use mvm_security::approval::ApprovalQueue;
+        use mvm_security::command_gate::CommandGate;
+        let tmp = tempfile::tempdir().unwrap();
+        let gate = CommandGate::new(vec![BlocklistEntry {
+            pattern: "rm -rf /".to_string(),
+            category: "destructive".to_string(),
+            severity: BlocklistSeverity::Critical,
+            action: BlocklistAction::Block,
+        }]);
+        let broker = ApprovalBroker::new(gate, ApprovalQueue::open(tmp.path()).unwrap());
+        let tools = ToolPolicy {
+            allowed: vec!["mvm.proc.start".to_string()],
+        };
+        let dispatcher =
+            ExecDispatcher::new(Some(Arc::new(broker)), &ThreatPolicy::default(), &tools);
+        let result = dispatcher.invoke_tool_with_peer(
+            "mvm.proc.start",
+            proc_start_call(&["rm", "-rf", "/"]),
+            &ScriptedPeer(None),
+        );
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(text.contains("blocked by command gate"), "got: {text}");
+    }
+
+    #[test]
+    fn proc_start_argv_is_threat_screened_as_a_command_line() {
+        let threat = ThreatPolicy {
+            default_action: None,
+            categories: [(
+                mvm_core::security::ThreatCategory::Destructive,
+                mvm_core::security::BlocklistAction::Block,
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let tools = ToolPolicy {
+            allowed: vec!["mvm.proc.start".to_string()],
+        };
+        let dispatcher = ExecDispatcher::new(None, &threat, &tools);
+        let result = dispatcher.invoke_tool_with_peer(
+            "mvm.proc.start",
+            proc_start_call(&["mkfs.ext4", "/dev/vda"]),
+            &ScriptedPeer(None),
+        );
+        assert!(result.is_error);
+        let ContentBlock::Text { text } = &result.content[0];
+        assert!(text.contains("proc-start blocked"), "got: {text}");
+    }
+
+    // ──────────────────────────────────────────────────────────────
+    // Plan 65 W4 — resolve_provider_credential
+    //
+    // Env-var manipulation in tests is process-global, so each
+    // test uses a unique env-var name to avoid races with parallel
+    // tests touching the same key.
+    // ──────────────────────────────────────────────────────────────
+
+    use mvm_security::secret_store::{FileSecretStore, SecretStore};
+    use secrecy::{ExposeSecret, SecretBox};
+
+    /// Expose the inner string of a `SecretBox` for test
+    /// assertions. The production path never does this — only
+    /// `BraveSearchProvider::search` etc. expose at the wire
+    /// boundary.
+    fn exposed(opt: Option<SecretBox<String>>) -> Option<String> {
+        opt.map(|s| s.expose_secret().clone())
+    }
+
+    fn tempdir_store() -> (tempfile::TempDir, FileSecretStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileSecretStore::with_dir(dir.path());
+        (dir, store)
+    }
+
+    #[test]
+    fn resolve_credential_returns_direct_env_var_when_set() {
+        let direct = "MVM_TEST_CRED_DIRECT_RETURNED";
+        let secret = "MVM_TEST_CRED_DIRECT_RETURNED_FROM_SECRET";
+        unsafe {
+            std::env::set_var(direct, "direct-value");
+        }
+        let (_dir, store) = tempdir_store();
+        let resolved = resolve_provider_credential(direct, secret, &store);
+        unsafe {
+            std::env::remove_var(direct);
+        }
+        assert_eq!(exposed(resolved).as_deref(), Some("direct-value"));
+    }
+
+    #[test]
+    fn resolve_credential_returns_secret_store_value_when_direct_unset() {
+        let direct = "MVM_TEST_CRED_FALLBACK_DIRECT";
+        let secret_ref = "MVM_TEST_CRED_FALLBACK_FROM_SECRET";
+        unsafe {
+            std::env::set_var(secret_ref, "my-stored-key");
+        }
+        let (_dir, store) = tempdir_store();
+        store
+            .put(
+                PROVIDER_CREDENTIAL_TENANT,
+                "my-stored-key",
+                &SecretBox::new(Box::new("from-store".to_string())),
+            )
+            .unwrap();
+        let resolved = resolve_provider_credential(direct, secret_ref, &store);
+        unsafe {
+            std::env::remove_var(secret_ref);
+        }
+        assert_eq!(exposed(resolved).as_deref(), Some("from-store"));
+    }
+
+    #[test]
+    fn resolve_credential_prefers_direct_when_both_set() {
+        // Backward-compat invariant: the direct value wins when an
+        // operator has both env vars set.
+        let direct = "MVM_TEST_CRED_PRIORITY_DIRECT";
+        let secret_ref = "MVM_TEST_CRED_PRIORITY_FROM_SECRET";
+        unsafe {
+            std::env::set_var(direct, "direct-wins");
+            std::env::set_var(secret_ref, "stored-name");
+        }
+        let (_dir, store) = tempdir_store();
+        store
+            .put(
+                PROVIDER_CREDENTIAL_TENANT,
+                "stored-name",
+                &SecretBox::new(Box::new("stored-loses".to_string())),
+            )
+            .unwrap();
+        let resolved = resolve_provider_credential(direct, secret_ref, &store);
+        unsafe {
+            std::env::remove_var(direct);
+            std::env::remove_var(secret_ref);
+        }
+        assert_eq!(exposed(resolved).as_deref(), Some("direct-wins"));
+    }
+
+    #[test]
+    fn resolve_credential_returns_none_when_neither_env_var_set() {
+        let (_dir, store) = tempdir_store();
+        let resolved = resolve_provider_credential(
+            "MVM_TEST_CRED_UNSET_DIRECT_DEFINITELY",
+            "MVM_TEST_CRED_UNSET_REF_DEFINITELY",
+            &store,
+        );
+        assert!(resolved.is_none());
+    }
+
+    #[test]
+    fn resolve_credential_skips_empty_direct_value() {
+        // An empty env var is functionally unset. Fall through to
+        // the secret-store lookup.
+        let direct = "MVM_TEST_CRED_EMPTY_DIRECT";
+        let secret_ref = "MVM_TEST_CRED_EMPTY_FROM_SECRET";
+        unsafe {
+            std::env::set_var(direct, "");
+            std::env::set_var(secret_ref, "actual-name");
+        }
+        let (_dir, store) = tempdir_store();
+        store
+            .put(
+                PROVIDER_CREDENTIAL_TENANT,
+                "actual-name",
+                &SecretBox::new(Box::new("from-store-fallback".to_string())),
+            )
+            .unwrap();
+        let resolved = resolve_provider_credential(direct, secret_ref, &store);
+        unsafe {
+            std::env::remove_var(direct);
+            std::env::remove_var(secret_ref);
+        }
+        assert_eq!(exposed(resolved).as_deref(), Some("from-store-fallback"));
+    }
+
+    #[test]
+    fn resolve_credential_returns_none_on_secret_store_miss() {
+        // The secret-name env var is set but the named secret
+        // doesn't exist in the store. The resolver returns None
+        // so the caller's existing "allowed-but-unregistered"
+        // config-drift path fires; a tracing::warn surfaces the
+        // miss to the operator at boot time.
+        let secret_ref = "MVM_TEST_CRED_MISS_FROM_SECRET";
+        unsafe {
+            std::env::set_var(secret_ref, "no-such-name");
+        }
+        let (_dir, store) = tempdir_store();
+        let resolved =
+            resolve_provider_credential("MVM_TEST_CRED_MISS_DIRECT_UNSET", secret_ref, &store);
+        unsafe {
+            std::env::remove_var(secret_ref);
+        }
+        assert!(resolved.is_none());
+    }
+
+    #[test]
+    fn http_bearer_token_reads_the_named_secret() {
+        let (_dir, store) = tempdir_store();
+        store
+            .put(
+                PROVIDER_CREDENTIAL_TENANT,
+                "mcp-http-token",
+                &SecretBox::new(Box::new("bearer".to_string())),
+            )
+            .unwrap();
+        let token = http_bearer_token("mcp-http-token", &store).unwrap();
+        assert_eq!(token.expose_secret(), "bearer");
+    }
+
+    #[test]
+    fn http_bearer_token_missing_secret_names_the_fix() {
+        let (_dir, store) = tempdir_store();
+        let err = http_bearer_token("absent-token", &store)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("mvmctl secret put absent-token"), "{err}");
+    }
+
+    #[test]
+    fn transport_reaper_closes_scoped_run_sessions() {
+        let dispatcher = ExecDispatcher::default();
+        {
+            let mut map = dispatcher.sessions.lock().unwrap();
+            map.touch_or_insert("t1:a", "shell", None);
+            map.touch_or_insert("t1:b", "shell", None);
+            map.touch_or_insert("t2:a", "shell", None);
+        }
+        let transport = SessionState {
+            env: "mcp-http".to_string(),
+            vm_name: None,
+            started_at: std::time::Instant::now(),
+            last_used: std::time::Instant::now(),
+        };
+        dispatcher
+            .transport_reaper()
+            .on_reap("t1", &transport, ReapReason::Closed);
+        let map = dispatcher.sessions.lock().unwrap();
+        assert_eq!(map.len(), 1);
+        assert!(map.get("t2:a").is_some());
+    }
+}
@@ -40,6 +40,18 @@ pub fn env_vars_to_drive_file(env_vars: &[String]) -> Option<microvm::DriveFile>
     })
 }
 
+/// The VM's `[fs_quota]` limits as a config-drive file. mkGuest's
+/// `/init` stages it for the guest agent's FS quota backstop.
+pub fn fs_quota_drive_file(
+    policy: &mvm_core::security::FsQuotaPolicy,
+) -> Result<microvm::DriveFile> {
+    Ok(microvm::DriveFile {
+        name: mvm_guest::fs_quota::FS_QUOTA_DRIVE_FILE.to_string(),
+        content: serde_json::to_string(policy)?,
+        mode: 0o444,
+    })
+}
+
 /// Read all regular files from a directory into `DriveFile` entries.
 pub fn read_dir_to_drive_files(dir: &str, default_mode: u32) -> Result<Vec<microvm::DriveFile>> {
     let mut files = Vec::new();
@@ -15,7 +15,9 @@ mod state;
 mod vsock;
 
 pub(super) use build_mode::BuildModeFlags;
-pub(super) use drive::{env_vars_to_drive_file, ports_to_drive_file, read_dir_to_drive_files};
+pub(super) use drive::{
+    env_vars_to_drive_file, fs_quota_drive_file, ports_to_drive_file, read_dir_to_drive_files,
+};
 pub(super) use event::PhaseEvent;
 pub(super) use format::{human_age_secs, human_bytes};
 pub(super) use hints::with_hints;
@@ -1763,6 +1763,8 @@ fn exec_default_manifest_argv_only() {
             env,
             timeout,
             launch_plan,
+            tty,
+            interactive,
             argv,
         }) => {
             assert!(manifest.is_none(), "manifest should default to None");
@@ -1772,6 +1774,7 @@ fn exec_default_manifest_argv_only() {
             assert!(env.is_empty());
             assert_eq!(timeout, 60);
             assert!(launch_plan.is_none(), "launch_plan should default to None");
+            assert!(!tty && !interactive);
             assert_eq!(argv, vec!["uname".to_string(), "-a".to_string()]);
         }
         _ => panic!("Expected Exec command"),
@@ -2092,6 +2095,7 @@ fn cp_host_to_guest_parses() {
             destination,
             force,
             create_parents,
+            recursive,
             max_bytes,
             json,
         }) => {
@@ -2099,6 +2103,7 @@ fn cp_host_to_guest_parses() {
             assert_eq!(destination, "vm1:/tmp/host.txt");
             assert!(force);
             assert!(create_parents);
+            assert!(!recursive);
             assert_eq!(max_bytes, 1024);
             assert!(!json);
         }
@@ -2118,6 +2123,16 @@ fn cp_json_flag_parses() {
     }
 }
 
+#[test]
+fn cp_recursive_flag_parses() {
+    let cli =
+        Cli::try_parse_from(["mvmctl", "cp", "-r", "vm1:/srv/data", "./data"]).expect("parse");
+    match cli.command {
+        Commands::Cp(cp::Args { recursive, .. }) => assert!(recursive),
+        _ => panic!("Expected Cp command"),
+    }
+}
+
 #[test]
 fn cp_guest_to_host_defaults_parse() {
     let cli =
@@ -2128,6 +2143,7 @@ fn cp_guest_to_host_defaults_parse() {
             destination,
             force,
             create_parents,
+            recursive,
             max_bytes,
             json,
         }) => {
@@ -2135,6 +2151,7 @@ fn cp_guest_to_host_defaults_parse() {
             assert_eq!(destination, "./out.txt");
             assert!(!force);
             assert!(!create_parents);
+            assert!(!recursive);
             assert_eq!(max_bytes, 16 * 1024 * 1024);
             assert!(!json);
         }
@@ -2239,6 +2256,58 @@ fn exec_with_add_dir_and_env() {
     }
 }
 
+#[test]
+fn exec_it_takes_vm_then_argv() {
+    let cli = Cli::try_parse_from([
+        "mvmctl",
+        "exec",
+        "-it",
+        "vm1",
+        "--",
+        "/usr/bin/python3",
+        "-q",
+    ])
+    .expect("parse");
+    match cli.command {
+        Commands::Exec(exec::Args {
+            tty,
+            interactive,
+            argv,
+            ..
+        }) => {
+            assert!(tty && interactive);
+            let (vm, command) = exec::split_tty_target(&argv).expect("vm and argv");
+            assert_eq!(vm, "vm1");
+            assert_eq!(command, ["/usr/bin/python3", "-q"]);
+        }
+        _ => panic!("Expected Exec command"),
+    }
+}
+
+#[test]
+fn exec_tty_target_without_separator_or_command() {
+    let values = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let plain = values(&["vm1", "/bin/sh"]);
+    assert_eq!(exec::split_tty_target(&plain), Some(("vm1", &plain[1..])));
+    assert!(exec::split_tty_target(&values(&["vm1", "--"])).is_none());
+    assert!(exec::split_tty_target(&values(&["vm1"])).is_none());
+}
+
+#[test]
+fn exec_interactive_requires_tty() {
+    let cli = Cli::try_parse_from(["mvmctl", "exec", "-i", "vm1", "--", "/bin/sh"]);
+    assert!(cli.is_err(), "-i without -t must be rejected");
+}
+
+#[test]
+fn exec_tty_conflicts_with_manifest() {
+    let cli = Cli::try_parse_from(["mvmctl", "exec", "-t", "-m", "tpl", "vm1", "--", "/bin/sh"]);
+    assert!(
+        cli.is_err(),
+        "-t attaches to a running VM; --manifest boots one"
+    );
+}
+
 #[test]
 fn exec_requires_argv() {
     // Without trailing argv, Clap should reject because `argv` is required.
@@ -2588,6 +2657,51 @@ fn test_up_no_supervisor_flag_parses() {
     }
 }
 
+#[test]
+fn test_up_allow_unsigned_image_is_opt_in() {
+    let cli = Cli::try_parse_from(["mvmctl", "up"]).expect("parse");
+    match cli.command {
+        Commands::Up(up::Args {
+            allow_unsigned_image,
+            ..
+        }) => assert!(!allow_unsigned_image),
+        _ => panic!("Expected Up command"),
+    }
+    let cli = Cli::try_parse_from(["mvmctl", "up", "--allow-unsigned-image"]).expect("parse");
+    match cli.command {
+        Commands::Up(up::Args {
+            allow_unsigned_image,
+            ..
+        }) => assert!(allow_unsigned_image),
+        _ => panic!("Expected Up command"),
+    }
+}
+
+#[test]
+fn test_up_image_signature_conflicts_with_allow_unsigned_image() {
+    let cli = Cli::try_parse_from(["mvmctl", "up", "--image-signature", "/tmp/r.cosign.json"])
+        .expect("parse");
+    match cli.command {
+        Commands::Up(up::Args {
+            image_signature, ..
+        }) => assert_eq!(
+            image_signature.as_deref(),
+            Some(std::path::Path::new("/tmp/r.cosign.json"))
+        ),
+        _ => panic!("Expected Up command"),
+    }
+    assert!(
+        Cli::try_parse_from([
+            "mvmctl",
+            "up",
+            "--image-signature",
+            "/tmp/r.cosign.json",
+            "--allow-unsigned-image",
+        ])
+        .is_err()
+    );
+}
+
 // ---- SDK-port Phase 7d — `mvmctl compile --from-recording` ----
 
 #[test]
@@ -33,6 +33,16 @@
 //! follow-up tightens this to "audit failure fails the boot" once
 //! the chain is reliably reachable.
 //!
+//! ## Stream destinations
+//!
+//! A policy bundle's `[audit].stream_destinations` adds replicas next
+//! to the local chain: `file://` entries become exact-file chains,
+//! while `unix://`, `tcp+tls://` and `https://` entries become
+//! `mvm_supervisor::StreamAuditSigner`s whose spools live under
+//! `<audit_dir>/spool/`. A collector outage leaves entries spooled
+//! for replay on the next emit; only a full spool surfaces as an
+//! emit error.
+//!
 //! [`AuditEmitter`] is the public-to-the-module surface; tests use
 //! `AuditEmitter::with_dir` to inject a tempdir.
 
@@ -41,7 +51,10 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use ed25519_dalek::SigningKey;
 use mvm_plan::ExecutionPlan;
-use mvm_supervisor::{AuditEntry, AuditSigner, FileAuditSigner};
+use mvm_supervisor::{
+    AuditEntry, AuditSigner, AuditStreamDestination, FileAuditSigner, StreamAuditSigner,
+    StreamConfig,
+};
 
 use crate::commands::image::OciProvenance;
 
@@ -136,11 +149,12 @@ pub fn find_snapshot_saved_sha(
     Ok(latest)
 }
 
-/// Host-side emitter wrapping `FileAuditSigner`. Owns its own signing
-/// key half (cloned from the host signer at construction); calls
+/// Host-side emitter wrapping `FileAuditSigner` (plus any policy
+/// stream replicas). Owns its own signing key half (cloned from the
+/// host signer at construction); calls
 /// `tokio::runtime::Builder::new_current_thread()` per emit.
 pub struct AuditEmitter {
-    signers: Vec<FileAuditSigner>,
+    signers: Vec<Box<dyn AuditSigner>>,
 }
 
 impl AuditEmitter {
@@ -175,15 +189,15 @@ impl AuditEmitter {
         let signer = FileAuditSigner::open(signing_key, audit_dir)
             .with_context(|| format!("opening FileAuditSigner at {}", audit_dir.display()))?;
         Ok(Self {
-            signers: vec![signer],
+            signers: vec![Box::new(signer)],
         })
     }
 
     /// Construct an emitter from a parsed policy bundle's `[audit]`
     /// section. The default local chain is always kept; `file://`
-    /// destinations add exact-file replicas. Network/unix
-    /// replication is intentionally fail-closed until those
-    /// transports are implemented.
+    /// destinations add exact-file replicas, and `unix://` /
+    /// `tcp+tls://` / `https://` destinations add spool-backed
+    /// streams (see the module docs).
     pub fn with_policy(
         signing_key: SigningKey,
         audit_dir: &Path,
@@ -198,10 +212,18 @@ impl AuditEmitter {
         let mut emitter = Self::with_dir(signing_key.clone(), audit_dir)?;
         for destination in &policy.stream_destinations {
             let Some(raw_path) = destination.strip_prefix("file://") else {
-                anyhow::bail!(
-                    "audit stream destination {destination:?} is not wired yet; \
-                     only file:// destinations are supported"
-                );
+                let parsed = AuditStreamDestination::parse(destination)?;
+                let spool_path =
+                    StreamAuditSigner::spool_path_for(&audit_dir.join("spool"), destination);
+                let signer = StreamAuditSigner::open(
+                    signing_key.clone(),
+                    parsed,
+                    &spool_path,
+                    StreamConfig::default(),
+                )
+                .with_context(|| format!("opening audit stream spool {}", spool_path.display()))?;
+                emitter.signers.push(Box::new(signer));
+                continue;
             };
             if raw_path.is_empty() {
                 anyhow::bail!("audit file:// destination must include an absolute path");
@@ -212,7 +234,7 @@ impl AuditEmitter {
             }
             let signer = FileAuditSigner::open_file(signing_key.clone(), &path)
                 .with_context(|| format!("opening audit stream {}", path.display()))?;
-            emitter.signers.push(signer);
+            emitter.signers.push(Box::new(signer));
         }
         Ok(emitter)
     }
@@ -256,6 +278,19 @@ impl AuditEmitter {
         )
     }
 
+    /// Emit `plan.image_signature_exempted` — fires when a prod plan
+    /// was admitted without the cosign image check because the
+    /// operator passed `mvmctl up --allow-unsigned-image`. Binds the
+    /// exemption to the plan id so it shows up next to
+    /// `plan.admitted` in `mvmctl audit tail --chain`.
+    pub fn emit_image_signature_exempted(&self, plan: &ExecutionPlan, flag: &str) -> Result<()> {
+        self.emit(
+            plan,
+            "plan.image_signature_exempted",
+            [("flag".to_string(), flag.to_string())],
+        )
+    }
+
     /// Emit `plan.oci_provenance` — binds an OCI image admission to
     /// the same plan id as the launch decision. The labels are
     /// intentionally digest-oriented; raw registry credentials and
@@ -725,18 +760,57 @@ mod tests {
     }
 
     #[test]
-    fn policy_refuses_unwired_replication_schemes() {
+    fn policy_refuses_plaintext_remote_collector() {
         let dir = tempfile::tempdir().unwrap();
         let key = SigningKey::generate(&mut OsRng);
         let policy = mvm_policy::AuditPolicy {
             chain_signing: true,
-            stream_destinations: vec!["https://audit.example.com/ingest".to_string()],
+            stream_destinations: vec!["http://audit.example.com/ingest".to_string()],
         };
         let err = match AuditEmitter::with_policy(key, dir.path(), &policy) {
-            Ok(_) => panic!("unwired replication schemes must be rejected"),
+            Ok(_) => panic!("plaintext remote collectors must be rejected"),
             Err(err) => err,
         };
-        assert!(format!("{err:#}").contains("not wired yet"));
+        assert!(format!("{err:#}").contains("plaintext"));
+    }
+
+    #[test]
+    fn policy_unix_destination_streams_to_collector() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixListener;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sock = dir.path().join("collector.sock");
+        let listener = UnixListener::bind(&sock).unwrap();
+        // Stand-in collector: ack each envelope with the hash the
+        // next entry's `prev_hash` will carry.
+        let collector = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut line = String::new();
+            BufReader::new(stream).read_line(&mut line).unwrap();
+            let line = line.trim_end().to_string();
+            let digest = <sha2::Sha256 as sha2::Digest>::digest(line.as_bytes());
+            let ack =
+                base64::Engine::encode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, digest);
+            writeln!(writer, "{{\"ack\":\"{ack}\"}}").unwrap();
+            line
+        });
+
+        let key = SigningKey::generate(&mut OsRng);
+        let policy = mvm_policy::AuditPolicy {
+            chain_signing: true,
+            stream_destinations: vec![format!("unix://{}", sock.display())],
+        };
+        let emitter = AuditEmitter::with_policy(key, dir.path(), &policy).unwrap();
+        let plan = fixture_plan("local", "plan-U");
+        emitter.emit_admitted(&plan, "host:test").unwrap();
+
+        let shipped = collector.join().unwrap();
+        assert!(shipped.contains("plan.admitted"));
+        assert!(shipped.contains("\"plan-U\""));
+        assert!(dir.path().join("local.jsonl").exists());
+        assert!(dir.path().join("spool").is_dir());
     }
 
     #[test]
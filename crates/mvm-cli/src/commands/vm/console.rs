@@ -180,14 +180,35 @@ pub(in crate::commands) fn console_interactive(name: &str) -> Result<()> {
     mvm_core::audit_emit!(ConsoleSessionStart, vm: name, "session_id={session_id}");
 
     // Set up SIGWINCH handler to forward terminal resizes
-    let resize_sender = setup_sigwinch_handler(transport.clone(), session_id);
+    let resize_sender = setup_sigwinch_handler(move |cols, rows| {
+        // Send ConsoleResize via the control channel (best-effort).
+        let _ = transport
+            .connect(mvm_guest::vsock::GUEST_AGENT_PORT)
+            .ok()
+            .and_then(|mut stream| {
+                mvm_guest::vsock::require_capabilities(
+                    &mut stream,
+                    &[mvm_guest::vsock::GuestCapability::Console],
+                )
+                .ok()?;
+                mvm_guest::vsock::send_request(
+                    &mut stream,
+                    &mvm_guest::vsock::GuestRequest::ConsoleResize {
+                        session_id,
+                        cols,
+                        rows,
+                    },
+                )
+                .ok()
+            });
+    });
 
     // Enter raw terminal mode and suppress the Ctrl-C handler so that
     // Ctrl+C is forwarded as a raw byte (\x03) to the guest shell
     // instead of killing mvmctl.
     IN_CONSOLE_MODE.store(true, std::sync::atomic::Ordering::SeqCst);
     let orig_termios = enter_raw_mode()?;
-    let result = run_console_relay(data_stream);
+    let result = run_console_relay(data_stream, true);
 
     // Restore terminal and clean up
     restore_terminal(&orig_termios);
@@ -207,12 +228,13 @@ extern "C" fn sigwinch_handler(_sig: libc::c_int) {
     SIGWINCH_RECEIVED.store(true, std::sync::atomic::Ordering::SeqCst);
 }
 
-/// Set up a SIGWINCH signal handler that forwards terminal resizes to the guest.
+/// Set up a SIGWINCH signal handler that calls `resize(cols, rows)`
+/// with the new terminal size whenever it changes. Shared by the
+/// console and `mvmctl exec -t`.
 ///
 /// Returns a sender that keeps the background thread alive. Drop it to stop.
-fn setup_sigwinch_handler(
-    transport: Arc<dyn VsockTransport>,
-    session_id: u32,
+pub(super) fn setup_sigwinch_handler(
+    resize: impl Fn(u16, u16) + Send + 'static,
 ) -> Option<std::sync::mpsc::Sender<()>> {
     use std::sync::atomic::Ordering;
 
@@ -241,27 +263,7 @@ fn setup_sigwinch_handler(
             }
 
             let (cols, rows) = get_terminal_size();
-
-            // Send ConsoleResize via the control channel (best-effort).
-            let _ = transport
-                .connect(mvm_guest::vsock::GUEST_AGENT_PORT)
-                .ok()
-                .and_then(|mut stream| {
-                    mvm_guest::vsock::require_capabilities(
-                        &mut stream,
-                        &[mvm_guest::vsock::GuestCapability::Console],
-                    )
-                    .ok()?;
-                    mvm_guest::vsock::send_request(
-                        &mut stream,
-                        &mvm_guest::vsock::GuestRequest::ConsoleResize {
-                            session_id,
-                            cols,
-                            rows,
-                        },
-                    )
-                    .ok()
-                });
+            resize(cols, rows);
         }
     });
 
@@ -269,7 +271,7 @@ fn setup_sigwinch_handler(
 }
 
 /// Get the current terminal size.
-fn get_terminal_size() -> (u16, u16) {
+pub(super) fn get_terminal_size() -> (u16, u16) {
     // SAFETY: ioctl with valid fd (stdout)
     unsafe {
         let mut ws: libc::winsize = std::mem::zeroed();
@@ -282,7 +284,7 @@ fn get_terminal_size() -> (u16, u16) {
 }
 
 /// Put the terminal in raw mode and return the original termios for restoration.
-fn enter_raw_mode() -> Result<libc::termios> {
+pub(super) fn enter_raw_mode() -> Result<libc::termios> {
     unsafe {
         let mut orig: libc::termios = std::mem::zeroed();
         if libc::tcgetattr(0, &mut orig) != 0 {
@@ -300,7 +302,7 @@ fn enter_raw_mode() -> Result<libc::termios> {
 }
 
 /// Restore the terminal to its original mode.
-fn restore_terminal(orig: &libc::termios) {
+pub(super) fn restore_terminal(orig: &libc::termios) {
     unsafe {
         libc::tcsetattr(0, libc::TCSANOW, orig);
     }
@@ -310,9 +312,13 @@ fn restore_terminal(orig: &libc::termios) {
 ///
 /// Exits when the guest closes the connection (e.g. `exit` or Ctrl+D
 /// in the shell) or when the user types the `~.` escape sequence
-/// (Enter, then `~.`, same as SSH).
+/// (Enter, then `~.`, same as SSH). With `forward_stdin` false only
+/// guest output is relayed (`mvmctl exec -t` without `-i`).
 ///
-fn run_console_relay(data_stream: std::os::unix::net::UnixStream) -> Result<()> {
+pub(super) fn run_console_relay(
+    data_stream: std::os::unix::net::UnixStream,
+    forward_stdin: bool,
+) -> Result<()> {
     use std::io::{Read, Write};
     use std::os::unix::io::AsRawFd;
 
@@ -320,13 +326,24 @@ fn run_console_relay(data_stream: std::os::unix::net::UnixStream) -> Result<()>
         .try_clone()
         .context("Failed to clone data stream")?;
     let write_stream = data_stream;
-    let stdin_fd = std::io::stdin().as_raw_fd();
+    // poll() ignores negative fds, so an unforwarded stdin is never read.
+    let stdin_fd = if forward_stdin {
+        std::io::stdin().as_raw_fd()
+    } else {
+        -1
+    };
     let vsock_fd = read_stream.as_raw_fd();
 
     // Save original flags so we can restore stdin after the relay exits.
-    let orig_stdin_flags = unsafe { libc::fcntl(stdin_fd, libc::F_GETFL) };
+    let orig_stdin_flags = if forward_stdin {
+        unsafe { libc::fcntl(stdin_fd, libc::F_GETFL) }
+    } else {
+        0
+    };
     unsafe {
-        libc::fcntl(stdin_fd, libc::F_SETFL, orig_stdin_flags | libc::O_NONBLOCK);
+        if forward_stdin {
+            libc::fcntl(stdin_fd, libc::F_SETFL, orig_stdin_flags | libc::O_NONBLOCK);
+        }
         libc::fcntl(vsock_fd, libc::F_SETFL, libc::O_NONBLOCK);
     }
 
@@ -391,8 +408,10 @@ fn run_console_relay(data_stream: std::os::unix::net::UnixStream) -> Result<()>
     }
 
     // Restore stdin to its original blocking mode
-    unsafe {
-        libc::fcntl(stdin_fd, libc::F_SETFL, orig_stdin_flags);
+    if forward_stdin {
+        unsafe {
+            libc::fcntl(stdin_fd, libc::F_SETFL, orig_stdin_flags);
+        }
     }
 
     Ok(())
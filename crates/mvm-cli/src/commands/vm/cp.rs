@@ -1,4 +1,10 @@
-//! `mvmctl cp` — copy one file between the host and a running VM.
+//! `mvmctl cp` — copy a file or (with `-r`) a directory tree between
+//! the host and a running VM.
+//!
+//! Files up to `fs::SINGLE_SHOT_MAX_BYTES` go through the single-shot
+//! `FsWrite` / `FsRead` verbs; larger files and every file in a tree
+//! copy use the chunked, SHA-256-verified streaming verbs, so an
+//! interrupted copy of a large file resumes when re-run.
 
 use anyhow::{Context, Result, bail};
 use clap::Args as ClapArgs;
@@ -8,12 +14,14 @@ use std::path::{Path, PathBuf};
 
 use mvm_core::naming::validate_vm_name;
 use mvm_core::user_config::MvmConfig;
-use mvm_guest::vsock::{FsResult, GuestRequest};
+use mvm_guest::vsock::{FsEntryKind, FsErrorKind, FsResult, GuestRequest};
 
 use super::Cli;
+use super::fs::unwrap_fs;
 
 const DEFAULT_MAX_BYTES: u64 = 16 * 1024 * 1024;
 const DEFAULT_MODE: u32 = 0o644;
+const DEFAULT_DIR_MODE: u32 = 0o755;
 
 #[derive(ClapArgs, Debug, Clone)]
 pub(in crate::commands) struct Args {
@@ -27,7 +35,11 @@ pub(in crate::commands) struct Args {
     /// Create destination parent directories.
     #[arg(long, short = 'p')]
     pub create_parents: bool,
-    /// Maximum bytes to copy. Defaults to 16 MiB.
+    /// Copy a directory tree. Regular files and directories are
+    /// copied; symlinks and special files are skipped with a warning.
+    #[arg(long, short = 'r')]
+    pub recursive: bool,
+    /// Maximum bytes to copy per file. Defaults to 16 MiB.
     #[arg(long, default_value_t = DEFAULT_MAX_BYTES)]
     pub max_bytes: u64,
     /// Print a machine-readable copy summary as JSON.
@@ -51,8 +63,10 @@ struct CopySummary {
     vm: String,
     guest_path: String,
     bytes_copied: u64,
+    files_copied: u64,
     force: bool,
     create_parents: bool,
+    recursive: bool,
     max_bytes: u64,
 }
 
@@ -86,13 +100,17 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
         match (&source, &destination) {
             (Endpoint::Host(_), Endpoint::Guest { .. }) => {
                 eprintln!(
-                    "copied {} bytes to {}:{}",
-                    summary.bytes_copied, summary.vm, summary.guest_path
+                    "copied {}{} bytes to {}:{}",
+                    summary.files_note(),
+                    summary.bytes_copied,
+                    summary.vm,
+                    summary.guest_path
                 );
             }
             (Endpoint::Guest { .. }, Endpoint::Host(host)) => {
                 eprintln!(
-                    "copied {} bytes to {}",
+                    "copied {}{} bytes to {}",
+                    summary.files_note(),
                     summary.bytes_copied,
                     host.display()
                 );
@@ -106,6 +124,15 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
 fn copy_host_to_guest(host: &Path, vm: &str, guest_path: &str, args: &Args) -> Result<CopySummary> {
     let meta = std::fs::metadata(host)
         .with_context(|| format!("Failed to stat host source {}", host.display()))?;
+    if meta.is_dir() {
+        if !args.recursive {
+            bail!(
+                "Host source {} is a directory; pass -r to copy it",
+                host.display()
+            );
+        }
+        return copy_tree_host_to_guest(host, vm, guest_path, args);
+    }
     if !meta.is_file() {
         bail!("Host source {} is not a regular file", host.display());
     }
@@ -117,11 +144,26 @@ fn copy_host_to_guest(host: &Path, vm: &str, guest_path: &str, args: &Args) -> R
             args.max_bytes
         );
     }
-    let content = std::fs::read(host)
-        .with_context(|| format!("Failed to read host source {}", host.display()))?;
     if !args.force && guest_exists(vm, guest_path)? {
         bail!("Guest destination {vm}:{guest_path} exists; pass --force to overwrite");
     }
+    if meta.len() > super::fs::SINGLE_SHOT_MAX_BYTES {
+        let bytes_written = upload_file(host, vm, guest_path, args.create_parents)?;
+        mvm_core::audit_emit!(
+            VmFileCopy,
+            vm: vm,
+            "direction=host_to_guest path={guest_path} bytes={bytes_written}"
+        );
+        return Ok(CopySummary::new(
+            CopyDirection::HostToGuest,
+            vm,
+            guest_path,
+            bytes_written,
+            args,
+        ));
+    }
+    let content = std::fs::read(host)
+        .with_context(|| format!("Failed to read host source {}", host.display()))?;
     let dir = super::fs::instance_dir_for(vm)?;
     let req = GuestRequest::FsWrite {
         path: guest_path.to_string(),
@@ -130,11 +172,7 @@ fn copy_host_to_guest(host: &Path, vm: &str, guest_path: &str, args: &Args) -> R
         create_parents: args.create_parents,
         follow_symlinks: false,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit. Emit
-    // before dispatch so a failure on the wire still leaves an
-    // audit trail of "host tried to write to this guest path".
-    super::shared::emit_vsock_rpc_audit(vm, &req);
-    match unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)? {
+    match unwrap_fs(super::fs::send_fs(vm, &dir, req)?)? {
         FsResult::Write { bytes_written } => {
             mvm_core::audit_emit!(
                 VmFileCopy,
@@ -155,7 +193,13 @@ fn copy_host_to_guest(host: &Path, vm: &str, guest_path: &str, args: &Args) -> R
 
 fn copy_guest_to_host(vm: &str, guest_path: &str, host: &Path, args: &Args) -> Result<CopySummary> {
     let stat = guest_stat(vm, guest_path)?;
-    if !matches!(stat.kind, mvm_guest::vsock::FsEntryKind::File) {
+    if matches!(stat.kind, FsEntryKind::Dir) {
+        if !args.recursive {
+            bail!("Guest source {vm}:{guest_path} is a directory; pass -r to copy it");
+        }
+        return copy_tree_guest_to_host(vm, guest_path, host, args);
+    }
+    if !matches!(stat.kind, FsEntryKind::File) {
         bail!("Guest source {vm}:{guest_path} is not a regular file");
     }
     if stat.size > args.max_bytes {
@@ -182,6 +226,21 @@ fn copy_guest_to_host(vm: &str, guest_path: &str, host: &Path, args: &Args) -> R
             )
         })?;
     }
+    if stat.size > super::fs::SINGLE_SHOT_MAX_BYTES {
+        let bytes = download_file(vm, guest_path, host, args.force)?;
+        mvm_core::audit_emit!(
+            VmFileCopy,
+            vm: vm,
+            "direction=guest_to_host path={guest_path} bytes={bytes}"
+        );
+        return Ok(CopySummary::new(
+            CopyDirection::GuestToHost,
+            vm,
+            guest_path,
+            bytes,
+            args,
+        ));
+    }
     let dir = super::fs::instance_dir_for(vm)?;
     let req = GuestRequest::FsRead {
         path: guest_path.to_string(),
@@ -189,9 +248,7 @@ fn copy_guest_to_host(vm: &str, guest_path: &str, host: &Path, args: &Args) -> R
         length: stat.size,
         follow_symlinks: true,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(vm, &req);
-    match unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)? {
+    match unwrap_fs(super::fs::send_fs(vm, &dir, req)?)? {
         FsResult::Read { content, .. } => {
             if content.len() as u64 != stat.size {
                 bail!(
@@ -230,7 +287,156 @@ fn copy_guest_to_host(vm: &str, guest_path: &str, host: &Path, args: &Args) -> R
     }
 }
 
+/// Upload one host file over the streaming verb.
+fn upload_file(host: &Path, vm: &str, guest_path: &str, create_parents: bool) -> Result<u64> {
+    let mut file = std::fs::File::open(host)
+        .with_context(|| format!("Failed to read host source {}", host.display()))?;
+    super::fs::stream_upload(vm, guest_path, &mut file, DEFAULT_MODE, create_parents)
+}
+
+/// Download one guest file over the streaming verb. Without `force`
+/// the final rename refuses to replace an existing host file.
+fn download_file(vm: &str, guest_path: &str, host: &Path, force: bool) -> Result<u64> {
+    if host.exists() && !force {
+        bail!(
+            "Host destination {} exists; pass --force to overwrite",
+            host.display()
+        );
+    }
+    super::fs::stream_download(vm, guest_path, host)
+}
+
+fn guest_child(parent: &str, name: &str) -> String {
+    format!("{}/{}", parent.trim_end_matches('/'), name)
+}
+
+fn copy_tree_host_to_guest(
+    host_root: &Path,
+    vm: &str,
+    guest_root: &str,
+    args: &Args,
+) -> Result<CopySummary> {
+    if !args.force && guest_exists(vm, guest_root)? {
+        bail!("Guest destination {vm}:{guest_root} exists; pass --force to overwrite");
+    }
+    guest_mkdir(vm, guest_root, args.create_parents)?;
+    let (mut bytes, mut files) = (0u64, 0u64);
+    let mut stack = vec![(host_root.to_path_buf(), guest_root.to_string())];
+    while let Some((host_dir, guest_dir)) = stack.pop() {
+        let mut entries = std::fs::read_dir(&host_dir)
+            .with_context(|| format!("Failed to list host directory {}", host_dir.display()))?
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let host_path = entry.path();
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                bail!("Host path {} is not valid UTF-8", host_path.display());
+            };
+            let guest_path = guest_child(&guest_dir, &name);
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                guest_mkdir(vm, &guest_path, false)?;
+                stack.push((host_path, guest_path));
+            } else if file_type.is_file() {
+                let len = entry.metadata()?.len();
+                if len > args.max_bytes {
+                    bail!(
+                        "Host source {} is {} bytes, above --max-bytes {}",
+                        host_path.display(),
+                        len,
+                        args.max_bytes
+                    );
+                }
+                bytes += upload_file(&host_path, vm, &guest_path, false)?;
+                files += 1;
+            } else {
+                eprintln!("skipping {} (not a regular file)", host_path.display());
+            }
+        }
+    }
+    mvm_core::audit_emit!(
+        VmFileCopy,
+        vm: vm,
+        "direction=host_to_guest path={guest_root} bytes={bytes} files={files}"
+    );
+    let mut summary = CopySummary::new(CopyDirection::HostToGuest, vm, guest_root, bytes, args);
+    summary.files_copied = files;
+    Ok(summary)
+}
+
+fn copy_tree_guest_to_host(
+    vm: &str,
+    guest_root: &str,
+    host_root: &Path,
+    args: &Args,
+) -> Result<CopySummary> {
+    if host_root.exists() && !args.force {
+        bail!(
+            "Host destination {} exists; pass --force to overwrite",
+            host_root.display()
+        );
+    }
+    let create = |dir: &Path, parents: bool| -> Result<()> {
+        let created = if parents {
+            std::fs::create_dir_all(dir)
+        } else {
+            std::fs::create_dir(dir)
+        };
+        match created {
+            Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => {
+                Err(e).with_context(|| format!("Failed to create host directory {}", dir.display()))
+            }
+            _ => Ok(()),
+        }
+    };
+    create(host_root, args.create_parents)?;
+    let (mut bytes, mut files) = (0u64, 0u64);
+    let mut stack = vec![(guest_root.to_string(), host_root.to_path_buf())];
+    while let Some((guest_dir, host_dir)) = stack.pop() {
+        for entry in guest_list(vm, &guest_dir)? {
+            let guest_path = guest_child(&guest_dir, &entry.name);
+            let host_path = host_dir.join(&entry.name);
+            match entry.kind {
+                FsEntryKind::Dir => {
+                    create(&host_path, false)?;
+                    stack.push((guest_path, host_path));
+                }
+                FsEntryKind::File => {
+                    if entry.size > args.max_bytes {
+                        bail!(
+                            "Guest source {vm}:{guest_path} is {} bytes, above --max-bytes {}",
+                            entry.size,
+                            args.max_bytes
+                        );
+                    }
+                    bytes += download_file(vm, &guest_path, &host_path, args.force)?;
+                    files += 1;
+                }
+                FsEntryKind::Symlink | FsEntryKind::Other => {
+                    eprintln!("skipping {vm}:{guest_path} (not a regular file)");
+                }
+            }
+        }
+    }
+    mvm_core::audit_emit!(
+        VmFileCopy,
+        vm: vm,
+        "direction=guest_to_host path={guest_root} bytes={bytes} files={files}"
+    );
+    let mut summary = CopySummary::new(CopyDirection::GuestToHost, vm, guest_root, bytes, args);
+    summary.files_copied = files;
+    Ok(summary)
+}
+
 impl CopySummary {
+    fn files_note(&self) -> String {
+        if self.files_copied == 1 {
+            String::new()
+        } else {
+            format!("{} files, ", self.files_copied)
+        }
+    }
+
     fn new(
         direction: CopyDirection,
         vm: &str,
@@ -244,8 +450,10 @@ impl CopySummary {
             vm: vm.to_string(),
             guest_path: guest_path.to_string(),
             bytes_copied,
+            files_copied: 1,
             force: args.force,
             create_parents: args.create_parents,
+            recursive: args.recursive,
             max_bytes: args.max_bytes,
         }
     }
@@ -271,12 +479,10 @@ fn guest_exists(vm: &str, path: &str) -> Result<bool> {
         path: path.to_string(),
         follow_symlinks: false,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(vm, &req);
-    match mvm_guest::vsock::send_fs_request(&dir, req)? {
+    match super::fs::send_fs(vm, &dir, req)? {
         FsResult::Stat(_) => Ok(true),
         FsResult::Error {
-            kind: mvm_guest::vsock::FsErrorKind::NotFound,
+            kind: FsErrorKind::NotFound,
             ..
         } => Ok(false),
         FsResult::Error { kind, message } => bail!("Guest FS error ({:?}): {}", kind, message),
@@ -284,27 +490,57 @@ fn guest_exists(vm: &str, path: &str) -> Result<bool> {
     }
 }
 
+/// `mkdir` in the guest; an existing directory is fine (tree copies
+/// into an existing destination under `--force`).
+fn guest_mkdir(vm: &str, path: &str, parents: bool) -> Result<()> {
+    let dir = super::fs::instance_dir_for(vm)?;
+    let req = GuestRequest::FsMkdir {
+        path: path.to_string(),
+        mode: DEFAULT_DIR_MODE,
+        parents,
+    };
+    match super::fs::send_fs(vm, &dir, req)? {
+        FsResult::Mkdir
+        | FsResult::Error {
+            kind: FsErrorKind::AlreadyExists,
+            ..
+        } => Ok(()),
+        other => unwrap_fs(other).map(|_| ()),
+    }
+}
+
+fn guest_list(vm: &str, path: &str) -> Result<Vec<mvm_guest::vsock::FsEntry>> {
+    let dir = super::fs::instance_dir_for(vm)?;
+    let req = GuestRequest::FsList {
+        path: path.to_string(),
+        follow_symlinks: false,
+    };
+    match unwrap_fs(super::fs::send_fs(vm, &dir, req)?)? {
+        FsResult::List {
+            truncated: true, ..
+        } => {
+            bail!("Guest directory {vm}:{path} has more entries than one listing returns")
+        }
+        FsResult::List { mut entries, .. } => {
+            entries.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(entries)
+        }
+        other => bail!("Unexpected FsResult variant for List: {:?}", other),
+    }
+}
+
 fn guest_stat(vm: &str, path: &str) -> Result<mvm_guest::vsock::FsStat> {
     let dir = super::fs::instance_dir_for(vm)?;
     let req = GuestRequest::FsStat {
         path: path.to_string(),
         follow_symlinks: true,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(vm, &req);
-    match unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)? {
+    match unwrap_fs(super::fs::send_fs(vm, &dir, req)?)? {
         FsResult::Stat(stat) => Ok(stat),
         other => bail!("Unexpected FsResult variant for Stat: {:?}", other),
     }
 }
 
-fn unwrap_fs(result: FsResult) -> Result<FsResult> {
-    if let FsResult::Error { kind, message } = &result {
-        bail!("Guest FS error ({:?}): {}", kind, message);
-    }
-    Ok(result)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -340,6 +576,7 @@ mod tests {
             destination: "vm1:/tmp/source.txt".to_string(),
             force: true,
             create_parents: true,
+            recursive: false,
             max_bytes: 4096,
             json: true,
         };
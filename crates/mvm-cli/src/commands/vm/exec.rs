@@ -1,4 +1,6 @@
 //! `mvmctl exec` — boot a transient microVM, run a single command, tear down.
+//! With `-t` it instead attaches a terminal to a command in a running VM
+//! (`mvmctl exec -it <vm> -- <argv>`), over the process RPC.
 
 use anyhow::{Context, Result};
 use base64::Engine as _;
@@ -53,6 +55,15 @@ pub(in crate::commands) struct Args {
     /// trailing argv. Mutually exclusive with the trailing `<ARGV>...`.
     #[arg(long, value_name = "PATH", conflicts_with = "argv")]
     pub launch_plan: Option<String>,
+    /// Run the command on a terminal in an already-running VM instead of
+    /// a transient one: `mvmctl exec -it <vm> -- <argv>`. The first
+    /// trailing value names the VM; `argv[0]` must be an absolute path.
+    /// The VM must be running a dev agent; `--timeout` does not apply.
+    #[arg(short = 't', long, conflicts_with_all = ["manifest", "add_dir", "launch_plan"])]
+    pub tty: bool,
+    /// Forward this terminal's input to the command. Requires `--tty`.
+    #[arg(short = 'i', long, requires = "tty")]
+    pub interactive: bool,
     /// Argv to run inside the guest (use `--` to separate). Required unless
     /// `--launch-plan` is supplied.
     #[arg(
@@ -213,6 +224,8 @@ impl RunArgs {
             env: self.env,
             timeout: self.timeout,
             launch_plan: self.launch_plan,
+            tty: false,
+            interactive: false,
             argv: self.argv,
         }
     }
@@ -267,6 +280,7 @@ pub(in crate::commands) fn run_secure(cli: &Cli, args: RunArgs, cfg: &MvmConfig)
         let image = args.image.clone();
         let prod = args.prod;
         let req = build_exec_request(args.into_exec_args(), "`mvmctl run`", image, prod)?;
+        authorize_exec(cfg, &req)?;
         let output = crate::exec::run_captured(req)?;
         if !json_requested && !output.stdout.is_empty() {
             print!("{}", output.stdout);
@@ -376,8 +390,17 @@ fn validate_run_profile(args: &RunArgs) -> Result<()> {
     Ok(())
 }
 
-pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Result<()> {
+pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Result<()> {
+    if args.tty {
+        let Some((vm, argv)) = split_tty_target(&args.argv) else {
+            anyhow::bail!(
+                "`mvmctl exec -t` needs a VM name and a command: exec -it <vm> -- <argv>"
+            );
+        };
+        return super::proc::attach_tty(cfg, vm, argv, &args.env, args.interactive);
+    }
     let req = build_exec_request(args, "`mvmctl exec`", None, false)?;
+    authorize_exec(cfg, &req)?;
     let exit_code = crate::exec::run(req)?;
     if exit_code != 0 {
         std::process::exit(exit_code);
@@ -385,14 +408,27 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
     Ok(())
 }
 
+/// Split `exec -t` trailing values into the VM name and the command.
+/// Once the trailing argv has started, clap keeps a later `--` as a
+/// value, so `<vm> -- <argv>` arrives as `[vm, "--", argv..]`.
+pub(in crate::commands) fn split_tty_target(values: &[String]) -> Option<(&str, &[String])> {
+    let (vm, rest) = values.split_first()?;
+    let argv = match rest.split_first() {
+        Some((sep, argv)) if sep == "--" => argv,
+        _ => rest,
+    };
+    (!argv.is_empty()).then_some((vm.as_str(), argv))
+}
+
 fn run_run_args(
     _cli: &Cli,
     args: Args,
-    _cfg: &MvmConfig,
+    cfg: &MvmConfig,
     image: Option<String>,
     prod: bool,
 ) -> Result<()> {
     let req = build_exec_request(args, "`mvmctl run`", image, prod)?;
+    authorize_exec(cfg, &req)?;
     let exit_code = crate::exec::run(req)?;
     if exit_code != 0 {
         std::process::exit(exit_code);
@@ -400,6 +436,24 @@ fn run_run_args(
     Ok(())
 }
 
+/// Run the command the guest will execute through the command gate
+/// and the threat classifier, parking it for approval on a
+/// `RequireApproval` match.
+fn authorize_exec(cfg: &MvmConfig, req: &crate::exec::ExecRequest) -> Result<()> {
+    let argv = match &req.target {
+        crate::exec::ExecTarget::Inline { argv } => argv,
+        crate::exec::ExecTarget::LaunchPlan { entrypoint } => &entrypoint.command,
+    };
+    let command = argv.join(" ");
+    crate::commands::ops::approvals::authorize(
+        cfg,
+        mvm_security::approval::ApprovalSubject::Exec,
+        None,
+        &command,
+    )?;
+    super::threat_screen::screen(cfg, super::threat_screen::Surface::Exec, None, &command)
+}
+
 fn build_exec_request(
     args: Args,
     command_name: &str,
@@ -454,8 +508,14 @@ fn build_exec_request(
                 "Using OCI image {} ({})",
                 cached.reference, cached.resolved_digest
             ));
-            emit_oci_run_admission(&cached, args.cpus, u64::from(memory_mib), args.timeout)
-                .context("admitting OCI image provenance for mvmctl run --image")?;
+            emit_oci_run_admission(
+                &cached,
+                prod,
+                args.cpus,
+                u64::from(memory_mib),
+                args.timeout,
+            )
+            .context("admitting OCI image provenance for mvmctl run --image")?;
             if cached.pulled {
                 let auth_source = cached.auth_source.as_deref().unwrap_or("unknown");
                 mvm_core::audit_emit!(
@@ -506,6 +566,7 @@ fn build_exec_request(
 
 fn emit_oci_run_admission(
     image: &super::super::image::ResolvedOciRunImage,
+    prod: bool,
     cpus: u32,
     mem_mib: u64,
     timeout_secs: u64,
@@ -518,13 +579,22 @@ fn emit_oci_run_admission(
             )
         })?;
     let exec_timeout_secs = u32::try_from(timeout_secs).unwrap_or(u32::MAX);
+    let signature_record = image
+        .signature_record
+        .as_ref()
+        .map(|path| path.display().to_string());
     let input = SynthesisInput {
         vm_name: "run-oci",
         tenant: None,
         backend_name: "transient-run",
         image_name: &image.provenance.canonical_reference,
         image_sha256: &image_sha256,
-        image_cosign_bundle: None,
+        image_cosign_bundle: signature_record.as_deref(),
+        variant: if prod {
+            mvm_plan::Variant::Prod
+        } else {
+            mvm_plan::Variant::Dev
+        },
         intent: Some("vm:run"),
         seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
         network_policy_ref: None,
@@ -8,14 +8,22 @@
 //! before sending because the agent owns the canonical answer
 //! (which may differ from what the host sees, e.g. on virtio-fs
 //! shares).
+//!
+//! Payloads above [`SINGLE_SHOT_MAX_BYTES`] switch from the single-shot
+//! `FsRead` / `FsWrite` verbs to `FsDownloadStream` / `FsUploadStream`,
+//! which move chunked, SHA-256-verified bytes over a dedicated vsock
+//! data port (`mvm_guest::fs_stream`). `fs watch` uses the same port
+//! for inotify events. The helpers here are shared with `mvmctl cp`.
 
 use anyhow::{Context, Result, bail};
 use clap::{Args as ClapArgs, Subcommand};
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 use mvm_backend::microvm;
 use mvm_core::naming::validate_vm_name;
 use mvm_core::user_config::MvmConfig;
+use mvm_guest::fs_stream::{self, FsStreamStatus, FsWatchEventKind};
 use mvm_guest::vsock::{FsResult, GuestRequest};
 
 use super::Cli;
@@ -39,9 +47,11 @@ pub(in crate::commands) enum FsCmd {
         /// Byte offset to start reading from (default 0)
         #[arg(long, default_value = "0")]
         offset: u64,
-        /// Max bytes to read in this call (default 16 MiB).
-        #[arg(long, default_value_t = 16 * 1024 * 1024)]
-        length: u64,
+        /// Max bytes to read in a single call (at most 16 MiB). Without
+        /// `--length` or `--offset` the whole file is streamed and
+        /// checksum-verified.
+        #[arg(long)]
+        length: Option<u64>,
     },
     /// Write stdin (or `--content`) to a file in the VM
     Write {
@@ -123,8 +133,30 @@ pub(in crate::commands) enum FsCmd {
         /// Destination path
         to: String,
     },
+    /// Stream change events for a path in the VM until interrupted
+    Watch {
+        /// Name of the VM
+        #[arg(value_parser = clap_vm_name)]
+        name: String,
+        /// File or directory path inside the VM
+        path: String,
+        /// Also watch every subdirectory, including new ones
+        #[arg(long, short)]
+        recursive: bool,
+        /// Print one JSON object per event
+        #[arg(long)]
+        json: bool,
+    },
 }
 
+/// Largest payload sent through the single-shot `FsWrite` verb — the
+/// agent's `fs_rpc::Caps::max_write_bytes`. Bigger payloads (and every
+/// file in a tree copy) use the streaming verbs.
+pub(super) const SINGLE_SHOT_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Single-shot `FsRead` cap on the agent (`fs_rpc::Caps::max_read_bytes`).
+const SINGLE_SHOT_READ_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
 pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Result<()> {
     match args.command {
         FsCmd::Read {
@@ -132,7 +164,15 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
             path,
             offset,
             length,
-        } => cmd_read(&name, &path, offset, length),
+        } => match length {
+            None if offset == 0 => cmd_read_stream(&name, &path),
+            length => cmd_read(
+                &name,
+                &path,
+                offset,
+                length.unwrap_or(SINGLE_SHOT_READ_MAX_BYTES),
+            ),
+        },
         FsCmd::Write {
             name,
             path,
@@ -160,6 +200,12 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
             recursive,
         } => cmd_rm(&name, &path, recursive),
         FsCmd::Mv { name, from, to } => cmd_mv(&name, &from, &to),
+        FsCmd::Watch {
+            name,
+            path,
+            recursive,
+            json,
+        } => cmd_watch(&name, &path, recursive, json),
     }
 }
 
@@ -178,11 +224,160 @@ pub(super) fn instance_dir_for(name: &str) -> Result<String> {
     microvm::resolve_running_vm_dir(name)
 }
 
-fn unwrap_fs(result: FsResult) -> Result<FsResult> {
-    if let FsResult::Error { kind, message } = &result {
-        bail!("Guest FS error ({:?}): {}", kind, message);
+pub(super) fn unwrap_fs(result: FsResult) -> Result<FsResult> {
+    match &result {
+        FsResult::Error { kind, message } => bail!("Guest FS error ({:?}): {}", kind, message),
+        FsResult::Backpressure { reason, detail } => {
+            bail!(
+                "Guest FS quota throttled the call ({:?}): {}",
+                reason,
+                detail
+            )
+        }
+        _ => Ok(result),
+    }
+}
+
+/// Send a single-shot FS verb to `vm`: audit it, meter it against
+/// the VM's FS quota, then dispatch. Shared with `mvmctl cp`.
+pub(super) fn send_fs(vm: &str, dir: &str, req: GuestRequest) -> Result<FsResult> {
+    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit. Emitted
+    // first so a throttled or failed call still leaves a trail of
+    // what the host tried to do to the guest.
+    super::shared::emit_vsock_rpc_audit(vm, &req);
+    crate::fs_quota::admit(vm, &req, || fs_quota_source_for_vm(vm))?;
+    mvm_guest::vsock::send_fs_request(dir, req)
+}
+
+/// The `[fs_quota]` limits of the bundle `vm`'s persisted plan names,
+/// with the session total kept in the VM state dir for that plan. A
+/// VM with no persisted plan gets the default limits, metered in
+/// this process only.
+fn fs_quota_source_for_vm(vm: &str) -> Result<crate::fs_quota::FsQuotaSource> {
+    let Ok(plan) = super::plan_persist::read_plan(vm) else {
+        return Ok(crate::fs_quota::FsQuotaSource::in_process(
+            Default::default(),
+        ));
+    };
+    let slots = super::policy_resolver::resolve_supervisor_components(&plan)
+        .map_err(|e| anyhow::Error::new(e).context("resolving FS quota policy"))?;
+    Ok(crate::fs_quota::FsQuotaSource {
+        policy: slots.fs_quota,
+        ledger: Some(crate::fs_quota::SessionLedger {
+            path: super::plan_persist::vm_state_dir(vm)?.join(crate::fs_quota::LEDGER_FILENAME),
+            boot_id: plan.plan_id.0,
+        }),
+    })
+}
+
+/// Open a streaming verb and return `(data_port, offset, total_size)`.
+fn open_stream(vm: &str, dir: &str, req: GuestRequest) -> Result<(u32, u64, u64)> {
+    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
+    super::shared::emit_vsock_rpc_audit(vm, &req);
+    crate::fs_quota::admit(vm, &req, || fs_quota_source_for_vm(vm))?;
+    let metered = req.clone();
+    let result = unwrap_fs(mvm_guest::vsock::open_fs_stream(dir, req)?)?;
+    crate::fs_quota::settle(vm, &metered, &result)?;
+    match result {
+        FsResult::StreamOpened {
+            data_port,
+            offset,
+            total_size,
+            ..
+        } => Ok((data_port, offset, total_size)),
+        other => bail!("Unexpected FsResult variant for stream open: {:?}", other),
+    }
+}
+
+/// Upload everything `src` yields to `guest_path` over
+/// `FsUploadStream`. Re-running after an interrupted upload of the
+/// same content resumes where the agent's staged copy stopped.
+pub(super) fn stream_upload<R: Read + Seek>(
+    vm: &str,
+    guest_path: &str,
+    src: &mut R,
+    mode: u32,
+    create_parents: bool,
+) -> Result<u64> {
+    let dir = instance_dir_for(vm)?;
+    let (size, digest) = fs_stream::sha256_reader(src).context("Failed to hash upload source")?;
+    let req = GuestRequest::FsUploadStream {
+        path: guest_path.to_string(),
+        size,
+        sha256: hex::encode(digest),
+        mode,
+        create_parents,
+    };
+    let (data_port, offset, _) = open_stream(vm, &dir, req)?;
+    src.seek(SeekFrom::Start(offset))?;
+    let mut conn = mvm_guest::vsock::connect_fs_stream(&dir, data_port)?;
+    match fs_stream::send_upload(&mut conn, src, digest)? {
+        FsStreamStatus::Complete { bytes, .. } => Ok(bytes),
+        FsStreamStatus::Error { kind, message } => {
+            bail!("Guest FS error ({:?}): {}", kind, message)
+        }
+    }
+}
+
+/// Download `guest_path` into `dest` over `FsDownloadStream`. Bytes
+/// land in `<dest>.mvm-partial` first; an interrupted download resumes
+/// from that file on the next run, and the whole file is verified
+/// against the agent's SHA-256 before the rename onto `dest`.
+pub(super) fn stream_download(vm: &str, guest_path: &str, dest: &Path) -> Result<u64> {
+    let dir = instance_dir_for(vm)?;
+    let mut partial = dest.as_os_str().to_owned();
+    partial.push(".mvm-partial");
+    let partial = PathBuf::from(partial);
+    let offset = std::fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+    let req = GuestRequest::FsDownloadStream {
+        path: guest_path.to_string(),
+        offset,
+        follow_symlinks: true,
+    };
+    let (data_port, _, total_size) = open_stream(vm, &dir, req).with_context(|| {
+        if offset > 0 {
+            format!(
+                "Failed to resume from {} ({offset} bytes); delete it to start over",
+                partial.display()
+            )
+        } else {
+            format!("Failed to open download of {vm}:{guest_path}")
+        }
+    })?;
+    let mut conn = mvm_guest::vsock::connect_fs_stream(&dir, data_port)?;
+    let mut prefix: Box<dyn Read> = if offset == 0 {
+        Box::new(std::io::empty())
+    } else {
+        Box::new(std::fs::File::open(&partial)?.take(offset))
+    };
+    let mut out = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&partial)
+        .with_context(|| format!("Failed to open {}", partial.display()))?;
+    if let Err(e) = fs_stream::receive_download(&mut conn, &mut prefix, &mut out) {
+        if e.is::<fs_stream::ChecksumMismatch>() {
+            std::fs::remove_file(&partial).ok();
+        }
+        return Err(e);
     }
-    Ok(result)
+    std::fs::rename(&partial, dest)
+        .with_context(|| format!("Failed to move download into {}", dest.display()))?;
+    Ok(total_size)
+}
+
+fn cmd_read_stream(name: &str, path: &str) -> Result<()> {
+    let dir = instance_dir_for(name)?;
+    let req = GuestRequest::FsDownloadStream {
+        path: path.to_string(),
+        offset: 0,
+        follow_symlinks: true,
+    };
+    let (data_port, _, _) = open_stream(name, &dir, req)?;
+    let mut conn = mvm_guest::vsock::connect_fs_stream(&dir, data_port)?;
+    let mut stdout = std::io::stdout().lock();
+    fs_stream::receive_download(&mut conn, &mut std::io::empty(), &mut stdout)?;
+    Ok(())
 }
 
 fn cmd_read(name: &str, path: &str, offset: u64, length: u64) -> Result<()> {
@@ -193,9 +388,7 @@ fn cmd_read(name: &str, path: &str, offset: u64, length: u64) -> Result<()> {
         length,
         follow_symlinks: true,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::Read { content, .. } => {
             std::io::stdout().write_all(&content)?;
@@ -222,6 +415,20 @@ fn cmd_write(
             buf
         }
     };
+    if bytes.len() as u64 > SINGLE_SHOT_MAX_BYTES {
+        // The stream stages and renames, so it never writes through
+        // a symlink at `path` regardless of `follow_symlinks`.
+        let bytes_written = stream_upload(
+            name,
+            path,
+            &mut std::io::Cursor::new(bytes),
+            mode,
+            create_parents,
+        )?;
+        eprintln!("wrote {} bytes", bytes_written);
+        mvm_core::audit_emit!(VmFsMutate, vm: name, "op=write path={path} bytes={bytes_written}");
+        return Ok(());
+    }
     let req = GuestRequest::FsWrite {
         path: path.to_string(),
         content: bytes,
@@ -229,9 +436,7 @@ fn cmd_write(
         create_parents,
         follow_symlinks,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::Write { bytes_written } => {
             eprintln!("wrote {} bytes", bytes_written);
@@ -248,9 +453,7 @@ fn cmd_ls(name: &str, path: &str, json: bool) -> Result<()> {
         path: path.to_string(),
         follow_symlinks: true,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::List { entries, truncated } => {
             if json {
@@ -291,9 +494,7 @@ fn cmd_stat(name: &str, path: &str, follow_symlinks: bool, json: bool) -> Result
         path: path.to_string(),
         follow_symlinks,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::Stat(s) => {
             if json {
@@ -320,9 +521,7 @@ fn cmd_mkdir(name: &str, path: &str, mode: u32, parents: bool) -> Result<()> {
         mode,
         parents,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::Mkdir => {
             mvm_core::audit_emit!(VmFsMutate, vm: name, "op=mkdir path={path} mode={mode:o} parents={parents}");
@@ -339,9 +538,7 @@ fn cmd_rm(name: &str, path: &str, recursive: bool) -> Result<()> {
         recursive,
         follow_symlinks: false,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::Remove { entries_removed } => {
             eprintln!("removed {} entries", entries_removed);
@@ -359,9 +556,7 @@ fn cmd_mv(name: &str, from: &str, to: &str) -> Result<()> {
         to: to.to_string(),
         follow_symlinks: false,
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
-    super::shared::emit_vsock_rpc_audit(name, &req);
-    let result = unwrap_fs(mvm_guest::vsock::send_fs_request(&dir, req)?)?;
+    let result = unwrap_fs(send_fs(name, &dir, req)?)?;
     match result {
         FsResult::Move => {
             mvm_core::audit_emit!(VmFsMutate, vm: name, "op=mv from={from} to={to}");
@@ -370,3 +565,37 @@ fn cmd_mv(name: &str, from: &str, to: &str) -> Result<()> {
         other => bail!("Unexpected FsResult variant for Move: {:?}", other),
     }
 }
+
+fn cmd_watch(name: &str, path: &str, recursive: bool, json: bool) -> Result<()> {
+    let dir = instance_dir_for(name)?;
+    let req = GuestRequest::FsWatch {
+        path: path.to_string(),
+        recursive,
+    };
+    let (data_port, _, _) = open_stream(name, &dir, req)?;
+    let mut conn = mvm_guest::vsock::connect_fs_stream(&dir, data_port)?;
+    // Events arrive whenever the guest changes something; don't let
+    // the connect-time read timeout end a quiet watch.
+    conn.set_read_timeout(None)?;
+    eprintln!("watching {}:{} (Ctrl-C to stop)", name, path);
+    let mut stdout = std::io::stdout().lock();
+    fs_stream::receive_watch(&mut conn, |event| {
+        let line = if json {
+            serde_json::to_string(event).unwrap_or_default()
+        } else {
+            let kind = match event.kind {
+                FsWatchEventKind::Created => "created",
+                FsWatchEventKind::Modified => "modified",
+                FsWatchEventKind::Removed => "removed",
+                FsWatchEventKind::MovedFrom => "moved-from",
+                FsWatchEventKind::MovedTo => "moved-to",
+                FsWatchEventKind::Overflow => "overflow",
+            };
+            let suffix = if event.is_dir { "/" } else { "" };
+            format!("{kind:<10} {}{suffix}", event.path)
+        };
+        writeln!(stdout, "{line}")
+            .and_then(|()| stdout.flush())
+            .is_ok()
+    })
+}
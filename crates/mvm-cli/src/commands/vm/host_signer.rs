@@ -353,6 +353,7 @@ mod tests {
             image_name: "img",
             image_sha256: &"a".repeat(64),
             image_cosign_bundle: None,
+            variant: mvm_plan::Variant::Dev,
             intent: None,
             seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
             network_policy_ref: None,
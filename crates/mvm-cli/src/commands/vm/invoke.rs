@@ -158,7 +158,7 @@ pub(in crate::commands) struct Args {
     pub source_path: Option<String>,
 }
 
-pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Result<()> {
+pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Result<()> {
     if args.no_vm {
         // Dev shortcut — bypass VM boot and run the wrapper directly
         // on the host. Plan 60 Phase 5 Slice E1b. See
@@ -243,7 +243,13 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
     // Run the call. Pass the session id so a transport drop coincident
     // with `mvmctl session kill` is attributed as `SessionKilled`
     // rather than a generic I/O error.
-    let dispatch_result = dispatch(&vm.vm_name, stdin_bytes, args.timeout, session_id.as_ref());
+    let dispatch_result = dispatch(
+        cfg,
+        &vm.vm_name,
+        stdin_bytes,
+        args.timeout,
+        session_id.as_ref(),
+    );
 
     // Tear down lifecycle:
     //   - default: kill the VM and drop the session record (matches
@@ -366,11 +372,18 @@ pub(in crate::commands) fn read_stdin_payload(spec: Option<&str>) -> Result<Vec<
 /// emit `SessionKilled` because by the time the kill takes effect
 /// it's already going down.
 pub(in crate::commands) fn dispatch(
+    cfg: &MvmConfig,
     vm_name: &str,
     stdin: Vec<u8>,
     timeout_secs: u64,
     session_id: Option<&mvm_core::session::SessionId>,
 ) -> Result<i32> {
+    super::threat_screen::screen(
+        cfg,
+        super::threat_screen::Surface::RunEntrypoint,
+        Some(vm_name),
+        &String::from_utf8_lossy(&stdin),
+    )?;
     match dispatch_inner(vm_name, stdin, timeout_secs) {
         Ok(code) => Ok(code),
         Err(err) => {
@@ -27,6 +27,7 @@ pub(super) mod sandbox;
 pub(super) mod session;
 pub(super) mod set_ttl;
 pub(super) mod tenant_resolution;
+pub(super) mod threat_screen;
 pub(super) mod up;
 pub(super) mod volume;
 pub(super) mod wait;
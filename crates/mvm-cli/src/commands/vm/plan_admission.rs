@@ -143,12 +143,48 @@ pub struct BundleAdmissionContext<'a> {
 ///   - `plan replay detected for signer {id}; nonce {hex}` — G4 nonce
 ///   - `bundle re-verify failed: {detail}` — pinned bundle missing,
 ///     unknown publisher, tampered, or sha256/sig/key_id mismatch
+///   - `refusing unsigned image {name} for a prod plan` — prod
+///     variant without a cosign signature record (a record that no
+///     longer verifies fails with the cosign verification error)
 pub fn admit_for_run(
     input: &SynthesisInput<'_>,
     clock: &dyn Clock,
     ledger: &InMemoryNonceLedger,
     host_signer_keys_dir: Option<&std::path::Path>,
     bundle_ctx: Option<&BundleAdmissionContext<'_>>,
+) -> Result<AdmittedPlan> {
+    admit(input, clock, ledger, host_signer_keys_dir, bundle_ctx, true)
+}
+
+/// [`admit_for_run`] without the prod image-signature check, for a
+/// host-built rootfs the operator explicitly exempted
+/// (`mvmctl up --allow-unsigned-image`). The plan keeps its prod
+/// variant; the caller must record the exemption on the plan's audit
+/// chain.
+pub fn admit_for_run_with_unsigned_image(
+    input: &SynthesisInput<'_>,
+    clock: &dyn Clock,
+    ledger: &InMemoryNonceLedger,
+    host_signer_keys_dir: Option<&std::path::Path>,
+    bundle_ctx: Option<&BundleAdmissionContext<'_>>,
+) -> Result<AdmittedPlan> {
+    admit(
+        input,
+        clock,
+        ledger,
+        host_signer_keys_dir,
+        bundle_ctx,
+        false,
+    )
+}
+
+fn admit(
+    input: &SynthesisInput<'_>,
+    clock: &dyn Clock,
+    ledger: &InMemoryNonceLedger,
+    host_signer_keys_dir: Option<&std::path::Path>,
+    bundle_ctx: Option<&BundleAdmissionContext<'_>>,
+    require_signed_prod_image: bool,
 ) -> Result<AdmittedPlan> {
     // Build the unsigned plan first. Synthesis failures are caught
     // before we touch the keystore — keeps "signed bad plan" from
@@ -157,10 +193,24 @@ pub fn admit_for_run(
 
     // Load or generate the host signer. W2's load_or_init refuses
     // loose perms; that error propagates verbatim.
-    let signer = match host_signer_keys_dir {
-        Some(dir) => super::host_signer::load_or_init_at(dir)?,
-        None => super::host_signer::load_or_init()?,
+    let load_signer = || match host_signer_keys_dir {
+        Some(dir) => super::host_signer::load_or_init_at(dir),
+        None => super::host_signer::load_or_init(),
     };
+
+    // Prod plans only run signed images: the plan must name a cosign
+    // signature record and it must still verify under the host's OCI
+    // policy, with a rootfs binding this host signed. Checked before
+    // signing so an unsigned prod image never gets a signed plan; the
+    // host key is only loaded once the plan names a record.
+    if input.variant.is_prod() && require_signed_prod_image {
+        crate::commands::image::verify_plan_image_signature(&plan.image, || {
+            load_signer().map(|signer| signer.verifying)
+        })
+        .context("prod image signature check")?;
+    }
+
+    let signer = load_signer()?;
     let signer_id = host_signer_id();
 
     // Sign + verify roundtrip. Verifying our own signature catches
@@ -370,6 +420,7 @@ mod tests {
             image_name: "img",
             image_sha256: FIXTURE_SHA,
             image_cosign_bundle: None,
+            variant: mvm_plan::Variant::Dev,
             intent: None,
             seccomp_tier: PlanSeccompTier::Standard,
             network_policy_ref: None,
@@ -531,6 +582,51 @@ mod tests {
         assert_ne!(a1.plan.nonce, a2.plan.nonce);
     }
 
+    #[test]
+    fn prod_plan_with_unsigned_image_is_refused_before_signing() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut input = fixture_input("vm1");
+        input.variant = mvm_plan::Variant::Prod;
+        let err = admit_for_run(
+            &input,
+            &SystemClock,
+            &InMemoryNonceLedger::new(),
+            Some(dir.path()),
+            None,
+        )
+        .expect_err("unsigned prod image must be refused");
+        assert!(
+            err.chain()
+                .any(|e| e.to_string().contains("refusing unsigned image")),
+            "unexpected error: {err:#}"
+        );
+        // Refused before the keystore was touched.
+        assert!(std::fs::read_dir(dir.path()).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn prod_plan_with_unreadable_signature_record_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.cosign.json");
+        let missing = missing.display().to_string();
+        let mut input = fixture_input("vm1");
+        input.variant = mvm_plan::Variant::Prod;
+        input.image_cosign_bundle = Some(&missing);
+        let err = admit_for_run(
+            &input,
+            &SystemClock,
+            &InMemoryNonceLedger::new(),
+            Some(dir.path()),
+            None,
+        )
+        .expect_err("missing record must be refused");
+        assert!(
+            err.chain()
+                .any(|e| e.to_string().contains("prod image signature check")),
+            "unexpected error: {err:#}"
+        );
+    }
+
     // ── ADR-002 claim 9: admit-time bundle re-verify ─────────────
     //
     // Tests exercise the boundary between `synthesize_plan`'s
@@ -41,7 +41,8 @@ use mvm_plan::{
     AdmissionProfile, ArtifactPolicy, AttestationMode, AttestationRequirement, AuditTaxonomy,
     DepsVolumeBinding, ExecutionPlan, FsPolicyRef, KeyRotationSpec, Nonce, PlanId, PlanSeccompTier,
     PolicyRef, PostRunLifecycle, Resources, RuntimeProfileRef, SCHEMA_VERSION, SecretBinding,
-    SecretReleasePolicy, SignedImageRef, TenantId, TimeoutSpec, WorkloadId, WorkloadIntent,
+    SecretReleasePolicy, SignedImageRef, TenantId, TimeoutSpec, Variant, WorkloadId,
+    WorkloadIntent,
 };
 use rand::RngCore;
 use std::collections::BTreeMap;
@@ -89,6 +90,10 @@ pub struct SynthesisInput<'a> {
     pub image_name: &'a str,
     pub image_sha256: &'a str,
     pub image_cosign_bundle: Option<&'a str>,
+    /// Dev or prod. Admission refuses a prod plan unless
+    /// `image_cosign_bundle` names a cosign signature record that
+    /// verifies under the host's OCI policy.
+    pub variant: Variant,
     /// Purpose this run is admitted for. `None` means
     /// [`DEFAULT_INTENT`].
     pub intent: Option<&'a str>,
@@ -325,6 +330,7 @@ mod tests {
             image_name: "myimage",
             image_sha256: "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
             image_cosign_bundle: None,
+            variant: Variant::Dev,
             intent: None,
             seccomp_tier: PlanSeccompTier::Standard,
             network_policy_ref: None,
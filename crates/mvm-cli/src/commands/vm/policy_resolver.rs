@@ -21,11 +21,13 @@
 //! Live consumers shipped after parsing a `<tenant>:<workload>`
 //! bundle:
 //!
-//! - `egress_policy` → `L7EgressProxy::new` from
-//!   `mvm_supervisor::l7_proxy`. The chain wraps a
+//! - `egress_policy` → `mvm_supervisor::build_l7_egress`, the same
+//!   builder `Supervisor::with_l7_egress` uses. The chain wraps a
 //!   `DestinationPolicy::new(bundle.egress.allow_list)`; CONNECT
 //!   targets that miss the allow-list return 403 + audit. Plain-HTTP
-//!   is gated on `bundle.egress.allow_plain_http` per ADR-002.
+//!   is gated on `bundle.egress.allow_plain_http` per ADR-002; the
+//!   byte / bandwidth budget and `tls_intercept` come from the
+//!   bundle too.
 //! - `tool_policy` → `PolicyToolGate::from_policy(&bundle.tool)`
 //!   from `mvm_supervisor::policy_tool_gate`. RPC calls to tool
 //!   names absent from `bundle.tool.allowed` get
@@ -71,10 +73,10 @@ use std::sync::Arc;
 use mvm_plan::{ExecutionPlan, FsPolicyRef, PolicyRef};
 use mvm_supervisor::{
     ArtifactCollector, AuditPolicyValidationError, EgressPolicyValidationError, EgressProxy,
-    KeystoreReleaser, L4Gate, L4SpecError, L7EgressProxy, LiveArtifactCollector,
+    InterceptCa, KeystoreReleaser, L4Gate, L4SpecError, LiveArtifactCollector,
     LiveKeystoreReleaser, LiveL4Gate, NoopArtifactCollector, NoopEgressAuditSink, NoopEgressProxy,
     NoopKeystoreReleaser, NoopL4Gate, NoopToolGate, PiiPolicyError, PolicyToolGate,
-    TokioDnsResolver, ToolGate, build_inspector_chain_with_pii,
+    TokioDnsResolver, ToolGate, build_inspector_chain_with_pii, build_l7_egress,
     validate_audit_policy_stream_destinations, validate_egress_policy_inspector_names,
 };
 
@@ -104,6 +106,16 @@ pub struct ResolvedSlots {
     pub keystore: Box<dyn KeystoreReleaser>,
     pub artifacts: Box<dyn ArtifactCollector>,
     pub audit: Option<mvm_policy::AuditPolicy>,
+    /// `[threat]` actions for the vsock request path and the tool
+    /// registry. Unset (log-only) for `"local-default"`.
+    pub threat: mvm_policy::ThreatPolicy,
+    /// `[fs_quota]` limits for the host-side FS RPC meter
+    /// (`crate::fs_quota`). Built-in defaults when the bundle omits
+    /// the section, and for `"local-default"`.
+    pub fs_quota: mvm_core::security::FsQuotaPolicy,
+    /// Per-VM interception CA when the bundle sets
+    /// `[egress].tls_intercept`. `None` otherwise.
+    pub intercept_ca: Option<Arc<InterceptCa>>,
 }
 
 /// Errors `resolve_supervisor_components` can return.
@@ -408,6 +420,9 @@ fn noop_slots() -> ResolvedSlots {
         keystore: Box::new(NoopKeystoreReleaser),
         artifacts: Box::new(NoopArtifactCollector),
         audit: None,
+        threat: mvm_policy::ThreatPolicy::default(),
+        fs_quota: Default::default(),
+        intercept_ca: None,
     }
 }
 
@@ -454,12 +469,12 @@ fn slots_from_bundle(
     )?;
 
     // Same fail-loud posture for `[audit].stream_destinations` —
-    // shape-check each URL's scheme prefix against
-    // `KNOWN_AUDIT_STREAM_SCHEMES`. The eventual replicator (Plan 60
-    // Phase 4 follow-on after the mvm-hostd lift) is the live
+    // shape-check each URL against `KNOWN_AUDIT_STREAM_SCHEMES` and
+    // the stream parser. `AuditEmitter::with_policy` is the live
     // consumer; this admission gate catches typos like
-    // `htpps://audit...` so the operator sees them at boot rather
-    // than after a forensic dig through the audit chain.
+    // `htpps://audit...` (or plaintext `http://` to a remote
+    // collector) so the operator sees them at boot rather than after
+    // a forensic dig through the audit chain.
     validate_audit_policy_stream_destinations(&bundle.audit).map_err(
         |e: AuditPolicyValidationError| ResolveError::AuditPolicyInvalid {
             value: ref_value.to_string(),
@@ -487,18 +502,19 @@ fn slots_from_bundle(
             detail: e.to_string(),
         },
     )?;
-    let body_cap = if bundle.egress.body_cap_bytes == 0 {
-        mvm_policy::DEFAULT_BODY_CAP_BYTES as usize
-    } else {
-        bundle.egress.body_cap_bytes as usize
-    };
-    let l7 = L7EgressProxy::new(
-        Arc::new(chain),
+    // Same wiring as `Supervisor::with_l7_egress`: body cap, byte /
+    // bandwidth budget and TLS interception all come from the bundle.
+    let (l7, intercept_ca) = build_l7_egress(
+        &bundle.egress,
+        chain,
         Arc::new(TokioDnsResolver),
         Arc::new(NoopEgressAuditSink),
-        body_cap,
-        bundle.egress.allow_plain_http,
-    );
+    )
+    .map_err(|e| ResolveError::EgressPolicyInvalid {
+        value: ref_value.to_string(),
+        path: path.to_path_buf(),
+        detail: e.to_string(),
+    })?;
     let tool_gate = PolicyToolGate::from_policy(&bundle.tool);
     // Artifact collector — carries the bundle's `capture_paths` +
     // `retention_days` on the public fields so a future in-process
@@ -521,6 +537,9 @@ fn slots_from_bundle(
         keystore: Box::new(keystore),
         artifacts: Box::new(artifacts),
         audit: Some(bundle.audit.clone()),
+        threat: bundle.threat.clone(),
+        fs_quota: bundle.fs_quota.unwrap_or_default(),
+        intercept_ca,
     })
 }
 
@@ -542,7 +561,9 @@ fn load_tenant_workload(
         }
         Err(
             mvm_policy::toml_loader::LoadError::Parse { detail, .. }
-            | mvm_policy::toml_loader::LoadError::Io { detail, .. },
+            | mvm_policy::toml_loader::LoadError::Io { detail, .. }
+            | mvm_policy::toml_loader::LoadError::InvalidAllowList { detail, .. }
+            | mvm_policy::toml_loader::LoadError::InvalidEgressLimit { detail, .. },
         ) => Err(ResolveError::BundleParseFailed {
             field: "network_policy",
             value: ref_value.to_string(),
@@ -930,6 +951,31 @@ allowed = ["{name}"]
         });
     }
 
+    #[test]
+    fn egress_limits_and_tls_intercept_flow_into_the_live_slot() {
+        let tmp = tempfile::tempdir().unwrap();
+        let plain = fixture_bundle_with_tool_allow("web_search");
+        write_bundle(tmp.path(), "acme", "plain", &plain);
+        let intercepting = plain.replace(
+            "allow_plain_http = false\n",
+            "allow_plain_http = false\ntls_intercept = true\nbyte_cap_bytes = 1048576\n\
+             bandwidth_bytes_per_sec = 65536\n",
+        );
+        write_bundle(tmp.path(), "acme", "intercepting", &intercepting);
+
+        let mut plan = fixture_plan();
+        set_all_refs(&mut plan, "acme:plain");
+        let slots = resolve_supervisor_components_with_dir(&plan, tmp.path())
+            .unwrap_or_else(|e| panic!("plain bundle: {e}"));
+        assert!(slots.intercept_ca.is_none());
+
+        set_all_refs(&mut plan, "acme:intercepting");
+        let slots = resolve_supervisor_components_with_dir(&plan, tmp.path())
+            .unwrap_or_else(|e| panic!("intercepting bundle: {e}"));
+        let ca = slots.intercept_ca.expect("tls_intercept builds a CA");
+        assert!(ca.cert_pem().contains("BEGIN CERTIFICATE"));
+    }
+
     #[test]
     fn slice_a_returns_policy_tool_gate_for_parsed_bundle() {
         // A parsed bundle's `tool.allowed` list controls
@@ -971,6 +1017,66 @@ allowed = ["{name}"]
         });
     }
 
+    #[test]
+    fn parsed_bundle_carries_threat_policy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let body = format!(
+            "{}\n[threat.categories]\nDestructive = \"Block\"\n",
+            fixture_bundle_with_tool_allow("web_search")
+        );
+        write_bundle(tmp.path(), "acme", "web-worker", &body);
+        let mut plan = fixture_plan();
+        set_all_refs(&mut plan, "acme:web-worker");
+
+        let slots = resolve_supervisor_components_with_dir(&plan, tmp.path())
+            .unwrap_or_else(|e| panic!("expected live slots, got {e}"));
+        assert_eq!(
+            slots
+                .threat
+                .action_for(&mvm_core::security::ThreatCategory::Destructive),
+            mvm_core::security::BlocklistAction::Block
+        );
+
+        let local = resolve_supervisor_components_with_dir(&fixture_plan(), tmp.path())
+            .unwrap_or_else(|e| panic!("expected noop slots, got {e}"));
+        assert!(local.threat.is_unset());
+    }
+
+    #[test]
+    fn parsed_bundle_carries_fs_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let body = format!(
+            "{}\n[fs_quota]\nsession_bytes = 4096\n",
+            fixture_bundle_with_tool_allow("web_search")
+        );
+        write_bundle(tmp.path(), "acme", "web-worker", &body);
+        let mut plan = fixture_plan();
+        set_all_refs(&mut plan, "acme:web-worker");
+
+        let slots = resolve_supervisor_components_with_dir(&plan, tmp.path())
+            .unwrap_or_else(|e| panic!("expected live slots, got {e}"));
+        assert_eq!(slots.fs_quota.session_bytes, 4096);
+        assert_eq!(
+            slots.fs_quota.ops_per_second,
+            mvm_core::security::FsQuotaPolicy::default().ops_per_second
+        );
+
+        // The staged drive file is what the guest agent's backstop
+        // reads back.
+        let staged = crate::commands::shared::fs_quota_drive_file(&slots.fs_quota).unwrap();
+        assert_eq!(staged.name, mvm_guest::fs_quota::FS_QUOTA_DRIVE_FILE);
+        let path = tmp.path().join(&staged.name);
+        std::fs::write(&path, &staged.content).unwrap();
+        assert_eq!(
+            mvm_guest::fs_quota::staged_policy(&path),
+            Some(slots.fs_quota)
+        );
+
+        let local = resolve_supervisor_components_with_dir(&fixture_plan(), tmp.path())
+            .unwrap_or_else(|e| panic!("expected noop slots, got {e}"));
+        assert_eq!(local.fs_quota, mvm_core::security::FsQuotaPolicy::default());
+    }
+
     fn fixture_bundle_with_key_rotation(days: u32) -> String {
         format!(
             r#"
@@ -1748,7 +1854,7 @@ bundle_version = 1
     // The resolver runs `validate_audit_policy_stream_destinations`
     // before constructing slots so a typo on an audit-stream URL
     // fails admission rather than silently dropping the entry
-    // downstream of the (yet-to-ship) audit replicator.
+    // downstream of the audit stream replicator.
     // ──────────────────────────────────────────────────────────────
 
     fn fixture_bundle_with_audit_destinations(destinations: &[&str]) -> String {
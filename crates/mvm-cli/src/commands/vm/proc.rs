@@ -6,6 +6,10 @@
 //! return `ProcErrorKind::UnsupportedInProduction`. The host CLI
 //! surface is always available — only the guest-side handler is
 //! gated.
+//!
+//! `mvmctl exec -it <vm> -- <argv>` lands here too ([`attach_tty`]):
+//! a `ProcStart` with a PTY whose data port is relayed to the local
+//! terminal, then `ProcWait` for the exit status.
 
 use anyhow::{Context, Result, bail};
 use clap::{Args as ClapArgs, Subcommand};
@@ -15,7 +19,7 @@ use std::io::Write;
 use mvm_backend::microvm;
 use mvm_core::naming::validate_vm_name;
 use mvm_core::user_config::MvmConfig;
-use mvm_guest::vsock::{GuestRequest, ProcResult, ProcWaitEvent};
+use mvm_guest::vsock::{GuestRequest, ProcResult, ProcWaitEvent, PtySize};
 
 use super::Cli;
 use super::shared::clap_vm_name;
@@ -96,14 +100,14 @@ pub(in crate::commands) enum ProcCmd {
     },
 }
 
-pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Result<()> {
+pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Result<()> {
     match args.command {
         ProcCmd::Start {
             name,
             argv,
             envs,
             cwd,
-        } => cmd_start(&name, &argv, &envs, cwd.as_deref()),
+        } => cmd_start(cfg, &name, &argv, &envs, cwd.as_deref()),
         ProcCmd::Ls { name, json } => cmd_ls(&name, json),
         ProcCmd::Signal {
             name,
@@ -160,18 +164,26 @@ fn parse_envs(raw: &[String]) -> Result<BTreeMap<String, String>> {
     Ok(out)
 }
 
-fn cmd_start(name: &str, argv: &[String], envs: &[String], cwd: Option<&str>) -> Result<()> {
+fn cmd_start(
+    cfg: &MvmConfig,
+    name: &str,
+    argv: &[String],
+    envs: &[String],
+    cwd: Option<&str>,
+) -> Result<()> {
     if argv.is_empty() {
         bail!("argv cannot be empty");
     }
     let dir = instance_dir_for(name)?;
     let env = parse_envs(envs)?;
+    authorize_start(cfg, name, argv)?;
     let req = GuestRequest::ProcStart {
         argv: argv.to_vec(),
         env,
         cwd: cwd.map(str::to_string),
         stdin: vec![],
         timeout_secs: None,
+        pty: None,
     };
     // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
     super::shared::emit_vsock_rpc_audit(name, &req);
@@ -186,6 +198,127 @@ fn cmd_start(name: &str, argv: &[String], envs: &[String], cwd: Option<&str>) ->
     }
 }
 
+/// Approval gate + threat screen shared by every `ProcStart`.
+fn authorize_start(cfg: &MvmConfig, name: &str, argv: &[String]) -> Result<()> {
+    let command = argv.join(" ");
+    crate::commands::ops::approvals::authorize(
+        cfg,
+        mvm_security::approval::ApprovalSubject::Process,
+        Some(name),
+        &command,
+    )?;
+    super::threat_screen::screen(
+        cfg,
+        super::threat_screen::Surface::ProcStart,
+        Some(name),
+        &command,
+    )
+}
+
+/// Environment for a terminal session before `--env` overrides:
+/// `ProcStart` replaces the agent's environment, and interactive
+/// programs need `PATH` and `TERM` to behave.
+fn tty_env(envs: &[String]) -> Result<BTreeMap<String, String>> {
+    let term = std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string());
+    let mut env = BTreeMap::from([
+        (
+            "PATH".to_string(),
+            crate::exec::SESSION_PROC_PATH.to_string(),
+        ),
+        ("TERM".to_string(), term),
+    ]);
+    env.extend(parse_envs(envs)?);
+    Ok(env)
+}
+
+/// `mvmctl exec -t [-i] <vm> -- <argv>` — run `argv` on a PTY in a
+/// running VM and attach this terminal to it until it exits. Resizes
+/// follow SIGWINCH; with `interactive` the local terminal goes raw and
+/// keystrokes (Ctrl-C included) go to the guest program. Exits with
+/// the program's status.
+pub(super) fn attach_tty(
+    cfg: &MvmConfig,
+    name: &str,
+    argv: &[String],
+    envs: &[String],
+    interactive: bool,
+) -> Result<()> {
+    if argv.is_empty() {
+        bail!("argv cannot be empty");
+    }
+    // SAFETY: isatty on the process's own stdin.
+    if interactive && unsafe { libc::isatty(0) } != 1 {
+        bail!("`mvmctl exec -i` needs a terminal on stdin");
+    }
+    let dir = instance_dir_for(name)?;
+    let env = tty_env(envs)?;
+    authorize_start(cfg, name, argv)?;
+    let (cols, rows) = super::console::get_terminal_size();
+    let req = GuestRequest::ProcStart {
+        argv: argv.to_vec(),
+        env,
+        cwd: None,
+        stdin: vec![],
+        timeout_secs: None,
+        pty: Some(PtySize { cols, rows }),
+    };
+    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
+    super::shared::emit_vsock_rpc_audit(name, &req);
+    let (token, data_port) = match unwrap_proc(mvm_guest::vsock::send_proc_request(&dir, req)?)? {
+        ProcResult::PtyStarted {
+            pid_token,
+            data_port,
+        } => (pid_token, data_port),
+        other => bail!("Unexpected ProcResult variant for Start: {:?}", other),
+    };
+    mvm_core::audit_emit!(VmProcStart, vm: name, "argv0={} token={token} pty=1", argv[0]);
+    let conn = mvm_guest::vsock::connect_proc_pty(&dir, data_port)
+        .context("Failed to connect to the process terminal")?;
+
+    let resize_dir = dir.clone();
+    let resize_token = token.clone();
+    let resize_sender = super::console::setup_sigwinch_handler(move |cols, rows| {
+        // Best-effort: a lost resize only leaves the old geometry.
+        let _ = mvm_guest::vsock::send_proc_request(
+            &resize_dir,
+            GuestRequest::ProcResize {
+                pid_token: resize_token.clone(),
+                cols,
+                rows,
+            },
+        );
+    });
+    let relayed = if interactive {
+        // Raw mode + suppressed Ctrl-C handler, as for the console.
+        super::shared::IN_CONSOLE_MODE.store(true, std::sync::atomic::Ordering::SeqCst);
+        let orig_termios = super::console::enter_raw_mode()?;
+        let relayed = super::console::run_console_relay(conn, true);
+        super::console::restore_terminal(&orig_termios);
+        super::shared::IN_CONSOLE_MODE.store(false, std::sync::atomic::Ordering::SeqCst);
+        relayed
+    } else {
+        super::console::run_console_relay(conn, false)
+    };
+    drop(resize_sender);
+    relayed?;
+
+    let wait = GuestRequest::ProcWait {
+        pid_token: token.clone(),
+        timeout_secs: None,
+    };
+    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
+    super::shared::emit_vsock_rpc_audit(name, &wait);
+    match mvm_guest::vsock::send_proc_wait(&dir, &token, None, |_| {})? {
+        ProcWaitEvent::Exit { code } => std::process::exit(code),
+        ProcWaitEvent::Killed { signal } => std::process::exit(128 + signal),
+        ProcWaitEvent::TimedOut => std::process::exit(124),
+        ProcWaitEvent::Error { kind, message } => {
+            bail!("ProcWait error ({:?}): {}", kind, message)
+        }
+        other => bail!("Unexpected terminal event: {:?}", other),
+    }
+}
+
 fn cmd_ls(name: &str, json: bool) -> Result<()> {
     let dir = instance_dir_for(name)?;
     // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit.
@@ -286,6 +286,7 @@ fn synthesis_input_for_app<'a>(workload: &'a Workload, app: &'a App) -> Result<S
         image_name: &app.name,
         image_sha256: leaked,
         image_cosign_bundle: None,
+        variant: mvm_plan::Variant::Dev,
         intent: Some("code:execute"),
         seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
         network_policy_ref: None,
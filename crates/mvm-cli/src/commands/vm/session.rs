@@ -202,7 +202,7 @@ pub(in crate::commands) struct ReapArgs {
     pub verbose: bool,
 }
 
-pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Result<()> {
+pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Result<()> {
     // Opportunistic lazy reap: every session verb sweeps idle records
     // before its own work. Failures are logged, not propagated —
     // reaping is best-effort cleanup, not load-bearing for the verb.
@@ -217,8 +217,8 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, _cfg: &MvmConfig) -> Resu
         Cmd::Kill(a) => cmd_kill(a),
         Cmd::SetTimeout(a) => cmd_set_timeout(a),
         Cmd::Start(a) => cmd_start(a),
-        Cmd::Attach(a) => cmd_attach(a),
-        Cmd::Exec(a) => cmd_exec(a),
+        Cmd::Attach(a) => cmd_attach(a, cfg),
+        Cmd::Exec(a) => cmd_exec(a, cfg),
         Cmd::RunCode(a) => cmd_run_code(a),
         Cmd::Console(a) => cmd_console(a),
         Cmd::Reap(a) => cmd_reap(a),
@@ -608,7 +608,7 @@ fn enforce_creator_pid_gate(
     Ok(())
 }
 
-fn cmd_attach(args: AttachArgs) -> Result<()> {
+fn cmd_attach(args: AttachArgs, cfg: &MvmConfig) -> Result<()> {
     let (id, record) = require_running_session(&args.session_id)?;
 
     let stdin_bytes = super::invoke::read_stdin_payload(args.stdin.as_deref())?;
@@ -621,8 +621,9 @@ fn cmd_attach(args: AttachArgs) -> Result<()> {
         Some(&record.vm_name),
         Some(&format!("session={id}")),
     );
-    let exit_code = super::invoke::dispatch(&record.vm_name, stdin_bytes, args.timeout, Some(&id))
-        .with_context(|| format!("dispatching into session {id}"))?;
+    let exit_code =
+        super::invoke::dispatch(cfg, &record.vm_name, stdin_bytes, args.timeout, Some(&id))
+            .with_context(|| format!("dispatching into session {id}"))?;
 
     // Bump the session's invoke counter / last-used timestamp so
     // observers (`mvmctl session info`) see the activity.
@@ -640,7 +641,7 @@ fn cmd_attach(args: AttachArgs) -> Result<()> {
     Ok(())
 }
 
-fn cmd_exec(args: ExecArgs) -> Result<()> {
+fn cmd_exec(args: ExecArgs, cfg: &MvmConfig) -> Result<()> {
     let (id, record) = require_running_session(&args.session_id)?;
     require_dev_mode(&id, &record, "exec")?;
 
@@ -666,6 +667,12 @@ fn cmd_exec(args: ExecArgs) -> Result<()> {
         .map(|a| shell_quote(a))
         .collect::<Vec<_>>()
         .join(" ");
+    super::threat_screen::screen(
+        cfg,
+        super::threat_screen::Surface::Exec,
+        Some(&record.vm_name),
+        &cmd_line,
+    )?;
     run_in_session(&id, &record, cmd_line, args.timeout)
 }
 
@@ -1221,11 +1228,14 @@ mod tests {
         let rec = session::SessionRecord::new_running("vm-1", "wl", session::SessionMode::Dev);
         let id = rec.id.to_string();
         session::write_session(&rec).unwrap();
-        let err = cmd_exec(ExecArgs {
-            session_id: id,
-            argv: vec![],
-            timeout: 30,
-        })
+        let err = cmd_exec(
+            ExecArgs {
+                session_id: id,
+                argv: vec![],
+                timeout: 30,
+            },
+            &MvmConfig::default(),
+        )
         .unwrap_err();
         assert!(
             err.to_string().contains("at least one argv element"),
@@ -1255,11 +1265,14 @@ mod tests {
         // before any attempt to talk to a vsock.
         let _guard = isolated_runtime_dir();
         let id = SessionId::new().to_string();
-        let err = cmd_attach(AttachArgs {
-            session_id: id,
-            stdin: None,
-            timeout: 1,
-        })
+        let err = cmd_attach(
+            AttachArgs {
+                session_id: id,
+                stdin: None,
+                timeout: 1,
+            },
+            &MvmConfig::default(),
+        )
         .unwrap_err();
         assert!(
             err.to_string().contains("no session with id"),
@@ -0,0 +1,218 @@
+//! Threat classifier gate on the host side of the vsock request path.
+//!
+//! `Exec` and `ProcStart` argv and `RunEntrypoint` stdin are run
+//! through [`mvm_security::threat_gate::ThreatGate`] before the
+//! request leaves the host. The per-category actions come from the
+//! `[threat]` section of the bundle the VM's persisted plan names
+//! (see [`super::policy_resolver`]); transient VMs and VMs booted
+//! without a plan get a log-only gate.
+//!
+//! Every match is chain-audited as `policy.threat.<action>` with its
+//! `pattern_id` and severity — never the matched text, which may be
+//! the secret the classifier found. A `Block` match refuses the
+//! request; a `RequireApproval` match parks it on the same approval
+//! queue `mvmctl approvals` serves.
+
+use anyhow::{Result, bail};
+use mvm_core::security::BlocklistAction;
+use mvm_core::user_config::MvmConfig;
+use mvm_policy::ThreatPolicy;
+use mvm_security::approval::ApprovalSubject;
+use mvm_security::threat_gate::{ThreatGate, ThreatMatch, ThreatScreening};
+use mvm_supervisor::EventCategory;
+
+/// Which vsock request the screened text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::commands) enum Surface {
+    Exec,
+    ProcStart,
+    RunEntrypoint,
+}
+
+impl Surface {
+    /// Audit label, matching the `GuestRequest::kind_name` spelling.
+    fn label(self) -> &'static str {
+        match self {
+            Self::Exec => "exec",
+            Self::ProcStart => "proc-start",
+            Self::RunEntrypoint => "run-entrypoint",
+        }
+    }
+
+    fn subject(self) -> ApprovalSubject {
+        match self {
+            Self::Exec => ApprovalSubject::Exec,
+            Self::ProcStart => ApprovalSubject::Process,
+            Self::RunEntrypoint => ApprovalSubject::Entrypoint,
+        }
+    }
+}
+
+/// Screen `text` bound for `vm_name` (`None` for a transient VM).
+/// Errors when a match blocks the request or its approval is denied.
+pub(in crate::commands) fn screen(
+    cfg: &MvmConfig,
+    surface: Surface,
+    vm_name: Option<&str>,
+    text: &str,
+) -> Result<()> {
+    let policy = match vm_name {
+        Some(name) => threat_policy_for_vm(name)?,
+        None => ThreatPolicy::default(),
+    };
+    let gate = mvm_supervisor::threat_gate_from_policy(&policy);
+    screen_with(&gate, surface, vm_name, text, |decisive| {
+        crate::commands::ops::approvals::hold(
+            cfg,
+            surface.subject(),
+            vm_name,
+            text,
+            &decisive.reason(),
+        )
+    })
+}
+
+/// Screen `text` against a caller-built `gate` — the MCP dispatcher's,
+/// which comes from its own `[threat]` bundle. `hold` parks a
+/// `RequireApproval` match and blocks until it is decided.
+pub(in crate::commands) fn screen_with(
+    gate: &ThreatGate,
+    surface: Surface,
+    vm_name: Option<&str>,
+    text: &str,
+    hold: impl FnOnce(&ThreatMatch) -> Result<()>,
+) -> Result<()> {
+    let screening = gate.screen(text);
+    if screening.is_clean() {
+        return Ok(());
+    }
+    emit_audit(&screening, surface, vm_name);
+    enforce(&screening, surface, vm_name, hold)
+}
+
+/// The `[threat]` policy of the bundle `vm_name`'s persisted plan
+/// names. A VM with no persisted plan gets the unset (log-only)
+/// policy; a plan whose bundle fails to load is an error, as it is at
+/// boot.
+fn threat_policy_for_vm(vm_name: &str) -> Result<ThreatPolicy> {
+    let Ok(plan) = super::plan_persist::read_plan(vm_name) else {
+        return Ok(ThreatPolicy::default());
+    };
+    let slots = super::policy_resolver::resolve_supervisor_components(&plan)
+        .map_err(|e| anyhow::Error::new(e).context("resolving threat policy"))?;
+    Ok(slots.threat)
+}
+
+/// Apply the decisive action. `hold` parks a `RequireApproval` match
+/// and blocks until it is decided.
+fn enforce(
+    screening: &ThreatScreening,
+    surface: Surface,
+    vm_name: Option<&str>,
+    hold: impl FnOnce(&ThreatMatch) -> Result<()>,
+) -> Result<()> {
+    let Some(decisive) = screening.decisive() else {
+        return Ok(());
+    };
+    match decisive.action {
+        BlocklistAction::Log => {
+            tracing::warn!(
+                surface = surface.label(),
+                vm = vm_name.unwrap_or("-"),
+                reason = %decisive.reason(),
+                "threat classifier match (logged)"
+            );
+            Ok(())
+        }
+        BlocklistAction::Block => bail!("{} blocked by {}", surface.label(), decisive.reason()),
+        BlocklistAction::RequireApproval => hold(decisive),
+    }
+}
+
+fn emit_audit(screening: &ThreatScreening, surface: Surface, vm_name: Option<&str>) {
+    let Some(recorder) = crate::commands::cmd_audit::build_cmd_recorder() else {
+        return;
+    };
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            tracing::warn!(error = %e, "building tokio runtime for threat audit emit");
+            return;
+        }
+    };
+    for m in &screening.matches {
+        if let Err(e) = rt.block_on(recorder.record_unbound(
+            EventCategory::Policy,
+            m.audit_event(),
+            m.audit_labels(surface.label(), vm_name),
+        )) {
+            tracing::warn!(error = %e, "Recorder emit failed for threat event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mvm_core::security::ThreatCategory;
+
+    fn screening(action: BlocklistAction, text: &str) -> ThreatScreening {
+        ThreatGate::new(
+            BlocklistAction::Log,
+            [(ThreatCategory::Destructive, action)]
+                .into_iter()
+                .collect(),
+        )
+        .screen(text)
+    }
+
+    #[test]
+    fn logged_match_lets_the_request_through() {
+        let s = screening(BlocklistAction::Log, "mkfs.ext4 /dev/vda");
+        enforce(&s, Surface::Exec, None, |_| panic!("nothing to hold")).unwrap();
+    }
+
+    #[test]
+    fn blocked_match_refuses_without_parking() {
+        let s = screening(BlocklistAction::Block, "mkfs.ext4 /dev/vda");
+        let err = enforce(&s, Surface::ProcStart, Some("vm1"), |_| {
+            panic!("blocked requests are never parked")
+        })
+        .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("proc-start blocked"), "{msg}");
+        assert!(msg.contains("pattern mkfs"), "{msg}");
+    }
+
+    #[test]
+    fn approval_match_is_held_with_the_decisive_finding() {
+        let s = screening(BlocklistAction::RequireApproval, "rm -rf / ");
+        let mut held = None;
+        enforce(&s, Surface::RunEntrypoint, Some("vm1"), |m| {
+            held = Some(m.finding.category.clone());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(held, Some(ThreatCategory::Destructive));
+    }
+
+    #[test]
+    fn denied_approval_refuses_the_request() {
+        let s = screening(BlocklistAction::RequireApproval, "mkfs /dev/vda");
+        let err = enforce(&s, Surface::Exec, None, |_| bail!("approval denied")).unwrap_err();
+        assert_eq!(err.to_string(), "approval denied");
+    }
+
+    #[test]
+    fn surfaces_map_to_approval_subjects() {
+        assert_eq!(Surface::Exec.subject(), ApprovalSubject::Exec);
+        assert_eq!(Surface::ProcStart.subject(), ApprovalSubject::Process);
+        assert_eq!(
+            Surface::RunEntrypoint.subject(),
+            ApprovalSubject::Entrypoint
+        );
+    }
+}
@@ -21,7 +21,7 @@ use super::host_signer::load_or_init_at;
 use super::managed_secrets::lower_workload_secrets;
 use super::plan_admission::{
     AdmittedPlan, BundleAdmissionContext, InMemoryNonceLedger, SystemClock, admit_for_run,
-    populate_audit_substrate, stash_plan_for_bridge,
+    admit_for_run_with_unsigned_image, populate_audit_substrate, stash_plan_for_bridge,
 };
 use super::plan_builder::SynthesisInput;
 use super::policy_resolver::{
@@ -30,9 +30,9 @@ use super::policy_resolver::{
 };
 use super::shared::{
     VmStartParams, VolumeSpec, clap_flake_ref, clap_port_spec, clap_vm_name, clap_volume_spec,
-    env_vars_to_drive_file, parse_port_specs, parse_volume_spec, ports_to_drive_file,
-    read_dir_to_drive_files, request_port_forward, resolve_flake_ref, resolve_network_policy,
-    wait_for_guest_agent,
+    env_vars_to_drive_file, fs_quota_drive_file, parse_port_specs, parse_volume_spec,
+    ports_to_drive_file, read_dir_to_drive_files, request_port_forward, resolve_flake_ref,
+    resolve_network_policy, wait_for_guest_agent,
 };
 
 /// Inputs for [`admit_plan_for_boot`]. Grouped so the helper avoids
@@ -143,6 +143,27 @@ struct AdmitPlanForBootParams<'a> {
     /// the on-disk sealed volume before launch — ADR-047 claim 9.
     /// `None` preserves the claim-8 baseline (no deps gate).
     pub deps_volume: Option<mvm_plan::DepsVolumeBinding>,
+    /// Plan variant, from the resolved `--dev` / `--prod` build mode.
+    /// Prod plans are refused unless the image carries a verifying
+    /// cosign signature record.
+    pub variant: mvm_plan::Variant,
+    /// `--allow-unsigned-image`: admit a prod plan for a host-built
+    /// rootfs that has no cosign signature. Recorded on the plan's
+    /// audit chain as `plan.image_signature_exempted`.
+    pub allow_unsigned_image: bool,
+    /// `--image-signature`: the cosign signature record
+    /// `mvmctl image pull --prod` cached for this rootfs. The plan
+    /// names the record's digest-pinned reference as its image and
+    /// carries the path as `SignedImageRef.cosign_bundle`.
+    pub image_signature: Option<&'a std::path::Path>,
+}
+
+/// Plan variant for a resolved build mode.
+fn plan_variant(build_mode: mvm_build::pipeline::BuildMode) -> mvm_plan::Variant {
+    match build_mode {
+        mvm_build::pipeline::BuildMode::Dev => mvm_plan::Variant::Dev,
+        mvm_build::pipeline::BuildMode::Prod => mvm_plan::Variant::Prod,
+    }
 }
 
 fn plan_seccomp_tier(
@@ -166,6 +187,22 @@ fn plan_seccomp_tier(
 pub(super) struct AdmissionContext {
     pub(super) admitted: AdmittedPlan,
     pub(super) emitter: AuditEmitter,
+    /// The bundle's `[fs_quota]` limits (defaults when it has none);
+    /// staged on the config drive so the guest agent's backstop
+    /// enforces what the host meter does.
+    pub(super) fs_quota: mvm_core::security::FsQuotaPolicy,
+}
+
+impl AdmissionContext {
+    /// Config-drive files the resolved policy stages for the guest.
+    ///
+    /// Never the `tls_intercept` CA: the resolved L7 proxy that holds
+    /// its key is dropped after admission, so no proxy here would
+    /// present the leaves a guest trusting it expects. Interception
+    /// needs the supervisor launch path, which runs that proxy.
+    pub(super) fn policy_drive_files(&self) -> Result<Vec<microvm::DriveFile>> {
+        Ok(vec![fs_quota_drive_file(&self.fs_quota)?])
+    }
 }
 
 // allow(secret-debug): hand-written Debug elides the AuditEmitter's
@@ -204,9 +241,9 @@ impl std::fmt::Debug for AdmissionContext {
 /// `plan.launched` / `plan.failed` events bind to the same plan_id.
 ///
 /// The image name on the plan is the VM name (the workload identifier
-/// the rest of the supervisor surface uses). Once `mvm-hostd` lifts
-/// the supervisor in-process, the proper `mvm_security::image_verify`
-/// signed-manifest path can replace this.
+/// the rest of the supervisor surface uses), or, with
+/// `--image-signature`, the digest-pinned reference the signature
+/// record covers.
 fn admit_plan_for_boot(p: AdmitPlanForBootParams<'_>) -> Result<Option<AdmissionContext>> {
     if p.no_supervisor {
         return Ok(None);
@@ -248,13 +285,23 @@ fn admit_plan_for_boot(p: AdmitPlanForBootParams<'_>) -> Result<Option<Admission
         None => (None, None, None),
     };
 
+    let signed_image = p
+        .image_signature
+        .map(|path| {
+            crate::commands::image::signature_record_reference(path)
+                .map(|reference| (reference, path.display().to_string()))
+        })
+        .transpose()?;
     let input = SynthesisInput {
         vm_name: p.vm_name,
         tenant: Some(p.tenant),
         backend_name: p.backend_name,
-        image_name: p.vm_name,
+        image_name: signed_image
+            .as_ref()
+            .map_or(p.vm_name, |(reference, _)| reference.as_str()),
         image_sha256: &sha,
-        image_cosign_bundle: None,
+        image_cosign_bundle: signed_image.as_ref().map(|(_, path)| path.as_str()),
+        variant: p.variant,
         intent: None,
         seccomp_tier: p.seccomp_tier,
         network_policy_ref: None,
@@ -280,7 +327,13 @@ fn admit_plan_for_boot(p: AdmitPlanForBootParams<'_>) -> Result<Option<Admission
         }),
         _ => None,
     };
-    let admitted = admit_for_run(
+    let exempt_image_signature = p.variant.is_prod() && p.allow_unsigned_image;
+    let admit = if exempt_image_signature {
+        admit_for_run_with_unsigned_image
+    } else {
+        admit_for_run
+    };
+    let admitted = admit(
         &input,
         &SystemClock,
         p.ledger,
@@ -346,6 +399,16 @@ fn admit_plan_for_boot(p: AdmitPlanForBootParams<'_>) -> Result<Option<Admission
     if let Err(e) = emitter.emit_admitted(&admitted.plan, &admitted.signer_id) {
         tracing::warn!(error = %e, "audit emit_admitted failed (non-fatal)");
     }
+    if exempt_image_signature {
+        tracing::warn!(
+            plan_id = %admitted.plan_id.0,
+            workload = %p.vm_name,
+            "prod plan admitted with an unsigned image (--allow-unsigned-image)"
+        );
+        emitter
+            .emit_image_signature_exempted(&admitted.plan, "--allow-unsigned-image")
+            .context("recording --allow-unsigned-image on the audit chain")?;
+    }
 
     // Resolve the plan's four policy refs into concrete supervisor
     // component slots. Today the slots are constructed-and-dropped —
@@ -356,13 +419,18 @@ fn admit_plan_for_boot(p: AdmitPlanForBootParams<'_>) -> Result<Option<Admission
     // loudly *now* instead of silently passing through with Noops.
     emit_policy_resolved(&admitted.plan, &emitter, resolved.slots_mode);
 
-    Ok(Some(AdmissionContext { admitted, emitter }))
+    Ok(Some(AdmissionContext {
+        admitted,
+        emitter,
+        fs_quota: resolved.fs_quota,
+    }))
 }
 
 #[derive(Debug)]
 struct PolicyAdmissionResolution {
     slots_mode: &'static str,
     audit: Option<mvm_policy::AuditPolicy>,
+    fs_quota: mvm_core::security::FsQuotaPolicy,
 }
 
 fn build_default_audit_emitter(
@@ -412,7 +480,16 @@ fn resolve_policy_for_admission(
             // Drop the slots — no live consumer in mvmctl today. The
             // construction itself is the validation. Return the
             // resolved-mode so the caller can audit it after the
-            // policy-derived emitter is constructed.
+            // policy-derived emitter is constructed, and the FS quota
+            // so the launch path can stage it.
+            if slots.intercept_ca.is_some() {
+                tracing::warn!(
+                    plan_id = %plan.plan_id.0,
+                    "egress policy sets tls_intercept, but mvmctl runs no \
+                     intercepting proxy; the CA is not staged and tunnels \
+                     are not intercepted",
+                );
+            }
             let mode = if plan.network_policy.0 == LOCAL_DEFAULT {
                 "noop"
             } else {
@@ -426,6 +503,7 @@ fn resolve_policy_for_admission(
             Ok(PolicyAdmissionResolution {
                 slots_mode: mode,
                 audit: slots.audit,
+                fs_quota: slots.fs_quota,
             })
         }
         Err(rerr) => Err(anyhow::Error::new(rerr).context("resolving plan policy refs")),
@@ -785,6 +863,18 @@ pub(in crate::commands) struct Args {
     /// `--dev` warns and continues.
     #[command(flatten)]
     pub build_mode: super::super::shared::BuildModeFlags,
+    /// Admit a `--prod` plan whose rootfs carries no cosign signature
+    /// (a locally built image). Without this flag prod admission
+    /// refuses unsigned images. Every use is recorded on the plan's
+    /// audit chain as `plan.image_signature_exempted`.
+    #[arg(long)]
+    pub allow_unsigned_image: bool,
+    /// Cosign signature record for the rootfs being booted, as cached
+    /// by `mvmctl image pull --prod` (the path it prints). Prod
+    /// admission only reads records from the image cache, re-verifies
+    /// it, and requires its host-signed binding to cover this rootfs.
+    #[arg(long, value_name = "PATH", conflicts_with = "allow_unsigned_image")]
+    pub image_signature: Option<std::path::PathBuf>,
     /// Path to a Workload IR JSON describing the app being booted.
     /// When the IR carries `App.dependencies = Dependencies::Python
     /// | Dependencies::Node`, `mvmctl up` resolves the lockfile
@@ -1020,6 +1110,8 @@ pub(in crate::commands) fn run(_cli: &Cli, args: Args, cfg: &MvmConfig) -> Resul
         no_supervisor: args.no_supervisor,
         bundle_pin: args.bundle_pin.as_deref(),
         build_mode,
+        allow_unsigned_image: args.allow_unsigned_image,
+        image_signature: args.image_signature.as_deref(),
         workload_ir_path: args.from_workload_ir.as_deref(),
         up_json: args.up_json,
         services_health_timeout_secs: cfg.effective_services_health_timeout_secs(),
@@ -1112,6 +1204,10 @@ pub(in crate::commands) struct RunParams<'a> {
     /// `Some(p)` populates `PlanArtifact` in the synthesised plan.
     pub(super) bundle_pin: Option<&'a std::path::Path>,
     pub(super) build_mode: mvm_build::pipeline::BuildMode,
+    /// `--allow-unsigned-image`; see [`AdmitPlanForBootParams`].
+    pub(super) allow_unsigned_image: bool,
+    /// `--image-signature`; see [`AdmitPlanForBootParams`].
+    pub(super) image_signature: Option<&'a std::path::Path>,
     /// Optional path to a Workload IR JSON. When set + the IR
     /// carries `App.dependencies = Dependencies::Python |
     /// Dependencies::Node`, the deps install pipeline runs +
@@ -1166,6 +1262,8 @@ pub(super) fn cmd_run(params: RunParams<'_>) -> Result<()> {
         no_supervisor,
         bundle_pin,
         build_mode,
+        allow_unsigned_image,
+        image_signature,
         workload_ir_path,
         up_json: _up_json,
         services_health_timeout_secs,
@@ -1314,6 +1412,9 @@ pub(super) fn cmd_run(params: RunParams<'_>) -> Result<()> {
             policy_dir: None,
             bundle_pin,
             deps_volume: deps_volume_binding.clone(),
+            variant: plan_variant(build_mode),
+            allow_unsigned_image,
+            image_signature,
         })?;
 
         let mut start_config = mvm_core::vm_backend::VmStartConfig {
@@ -1329,6 +1430,15 @@ pub(super) fn cmd_run(params: RunParams<'_>) -> Result<()> {
         // the bridge-factory path. None keeps the legacy supervisor path
         // for no-admission flows.
         if let Some(ctx) = admission.as_ref() {
+            for f in ctx.policy_drive_files()? {
+                start_config
+                    .config_files
+                    .push(mvm_core::vm_backend::VmFile {
+                        name: f.name,
+                        content: f.content,
+                        mode: f.mode,
+                    });
+            }
             populate_audit_substrate(&mut start_config, &ctx.admitted)?;
             // Plan 113 §Task 13 — Firecracker bridge sidecar reads
             // plan.json + bundle.json from the per-VM state dir at
@@ -1673,7 +1783,13 @@ pub(super) fn cmd_run(params: RunParams<'_>) -> Result<()> {
         policy_dir: None,
         bundle_pin,
         deps_volume: deps_volume_binding.clone(),
+        variant: plan_variant(build_mode),
+        allow_unsigned_image,
+        image_signature,
     })?;
+    if let Some(ctx) = admission_main.as_ref() {
+        config_files.extend(ctx.policy_drive_files()?);
+    }
 
     // If a template snapshot exists AND the backend supports snapshots,
     // restore from it instead of cold-booting.
@@ -2050,6 +2166,9 @@ pub(super) fn cmd_run(params: RunParams<'_>) -> Result<()> {
                 policy_dir: None,
                 bundle_pin,
                 deps_volume: deps_volume_binding.clone(),
+                variant: plan_variant(build_mode),
+                allow_unsigned_image,
+                image_signature,
             }) {
                 Ok(ctx) => ctx,
                 Err(e) => {
@@ -2059,6 +2178,9 @@ pub(super) fn cmd_run(params: RunParams<'_>) -> Result<()> {
                     continue;
                 }
             };
+            if let Some(ctx) = watch_admission.as_ref() {
+                w_config_files.extend(ctx.policy_drive_files()?);
+            }
             let mut w_start_config = VmStartParams {
                 name: vm_name_owned.clone(),
                 rootfs_path: result.rootfs_path,
@@ -2332,6 +2454,9 @@ mod admit_plan_tests {
             policy_dir: None,
             bundle_pin: None,
             deps_volume: None,
+            variant: mvm_plan::Variant::Dev,
+            allow_unsigned_image: false,
+            image_signature: None,
         })
         .expect("must succeed");
         assert!(result.is_none(), "no_supervisor must return None");
@@ -2361,6 +2486,9 @@ mod admit_plan_tests {
             policy_dir: None,
             bundle_pin: None,
             deps_volume: None,
+            variant: mvm_plan::Variant::Dev,
+            allow_unsigned_image: false,
+            image_signature: None,
         })
         .expect("admission")
         .expect("Some when admission ran");
@@ -2411,6 +2539,9 @@ mod admit_plan_tests {
             policy_dir: None,
             bundle_pin: None,
             deps_volume: None,
+            variant: mvm_plan::Variant::Dev,
+            allow_unsigned_image: false,
+            image_signature: None,
         })
         .expect_err("missing rootfs must fail");
         assert!(
@@ -2447,6 +2578,9 @@ mod admit_plan_tests {
             policy_dir: None,
             bundle_pin: None,
             deps_volume: None,
+            variant: mvm_plan::Variant::Dev,
+            allow_unsigned_image: false,
+            image_signature: None,
         })
         .unwrap()
         .unwrap();
@@ -2467,6 +2601,9 @@ mod admit_plan_tests {
             policy_dir: None,
             bundle_pin: None,
             deps_volume: None,
+            variant: mvm_plan::Variant::Dev,
+            allow_unsigned_image: false,
+            image_signature: None,
         })
         .unwrap()
         .unwrap();
@@ -2529,6 +2666,9 @@ mod admit_plan_tests {
             policy_dir: Some(policy_dir.path()),
             bundle_pin: None,
             deps_volume: None,
+            variant: mvm_plan::Variant::Dev,
+            allow_unsigned_image: false,
+            image_signature: None,
         })
         .expect("admission")
         .expect("Some when admission ran");
@@ -2548,6 +2688,133 @@ mod admit_plan_tests {
         assert!(content.contains(&ctx.admitted.plan_id.0));
     }
 
+    fn prod_boot_params<'a>(
+        rootfs: &'a std::path::Path,
+        ledger: &'a InMemoryNonceLedger,
+        keys_dir: &'a std::path::Path,
+        audit_dir: &'a std::path::Path,
+        policy_dir: &'a std::path::Path,
+        allow_unsigned_image: bool,
+    ) -> AdmitPlanForBootParams<'a> {
+        AdmitPlanForBootParams {
+            tenant: "local",
+            vm_name: "vm-prod",
+            backend_name: "firecracker",
+            rootfs_path: rootfs,
+            cpus: 1,
+            mem_mib: 128,
+            seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
+            secret_release: mvm_plan::SecretReleasePolicy::None,
+            secrets: Vec::new(),
+            no_supervisor: false,
+            ledger,
+            keys_dir: Some(keys_dir),
+            audit_dir: Some(audit_dir),
+            policy_dir: Some(policy_dir),
+            bundle_pin: None,
+            deps_volume: None,
+            variant: plan_variant(mvm_build::pipeline::BuildMode::Prod),
+            allow_unsigned_image,
+            image_signature: None,
+        }
+    }
+
+    #[test]
+    fn prod_boot_refuses_unsigned_rootfs_without_the_flag() {
+        let keys_dir = tempfile::tempdir().unwrap();
+        let audit_dir = tempfile::tempdir().unwrap();
+        let policy_dir = tempfile::tempdir().unwrap();
+        let rootfs_dir = tempfile::tempdir().unwrap();
+        let rootfs = write_rootfs(rootfs_dir.path(), b"prod-payload");
+        let ledger = InMemoryNonceLedger::new();
+        let err = admit_plan_for_boot(prod_boot_params(
+            &rootfs,
+            &ledger,
+            keys_dir.path(),
+            audit_dir.path(),
+            policy_dir.path(),
+            false,
+        ))
+        .expect_err("unsigned prod rootfs must be refused");
+        assert!(
+            err.chain()
+                .any(|e| e.to_string().contains("prod image signature check")),
+            "unexpected error: {err:#}"
+        );
+        assert!(!audit_dir.path().join("local.jsonl").exists());
+    }
+
+    #[test]
+    fn image_signature_reaches_the_prod_signature_check() {
+        let keys_dir = tempfile::tempdir().unwrap();
+        let audit_dir = tempfile::tempdir().unwrap();
+        let policy_dir = tempfile::tempdir().unwrap();
+        let rootfs_dir = tempfile::tempdir().unwrap();
+        let rootfs = write_rootfs(rootfs_dir.path(), b"prod-payload");
+        let digest = format!("sha256:{}", "ab".repeat(32));
+        let record = rootfs_dir.path().join("record.cosign.json");
+        std::fs::write(
+            &record,
+            serde_json::json!({
+                "schema_version": 1,
+                "reference": format!("ghcr.io/acme/app@{digest}"),
+                "manifest_digest": digest,
+                "manifest": "",
+                "rootfs_sha256": "cd".repeat(32),
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let ledger = InMemoryNonceLedger::new();
+        let mut params = prod_boot_params(
+            &rootfs,
+            &ledger,
+            keys_dir.path(),
+            audit_dir.path(),
+            policy_dir.path(),
+            false,
+        );
+        params.image_signature = Some(&record);
+
+        // The plan names the record's image and carries its path, so
+        // the check gets past "unsigned" and reads the record, which
+        // is refused because it is not the image cache's copy.
+        let err = admit_plan_for_boot(params).expect_err("record outside the image cache");
+        assert!(
+            err.chain()
+                .any(|e| e.to_string().contains("not the image cache's record")),
+            "unexpected error: {err:#}"
+        );
+    }
+
+    #[test]
+    fn allow_unsigned_image_admits_prod_and_records_the_exemption() {
+        let keys_dir = tempfile::tempdir().unwrap();
+        let audit_dir = tempfile::tempdir().unwrap();
+        let policy_dir = tempfile::tempdir().unwrap();
+        let rootfs_dir = tempfile::tempdir().unwrap();
+        let rootfs = write_rootfs(rootfs_dir.path(), b"prod-payload");
+        let ledger = InMemoryNonceLedger::new();
+        let ctx = admit_plan_for_boot(prod_boot_params(
+            &rootfs,
+            &ledger,
+            keys_dir.path(),
+            audit_dir.path(),
+            policy_dir.path(),
+            true,
+        ))
+        .expect("admission")
+        .expect("Some when admission ran");
+
+        let content = std::fs::read_to_string(audit_dir.path().join("local.jsonl")).unwrap();
+        let exempted = content
+            .lines()
+            .find(|l| l.contains("plan.image_signature_exempted"))
+            .unwrap_or_else(|| panic!("exemption must be on the chain: {content}"));
+        assert!(exempted.contains(&ctx.admitted.plan_id.0));
+        assert!(exempted.contains("--allow-unsigned-image"));
+    }
+
     #[test]
     fn admission_emits_policy_resolved_live_when_bundle_parses() {
         // Manually stage a bundle whose tenant matches the synthesized
@@ -2598,6 +2865,7 @@ chain_signing = true
                 image_name: "vm-live",
                 image_sha256: &sha,
                 image_cosign_bundle: None,
+                variant: mvm_plan::Variant::Dev,
                 intent: None,
                 seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
                 network_policy_ref: None,
@@ -2696,6 +2964,7 @@ stream_destinations = ["file://{}"]
                 image_name: "vm-stream",
                 image_sha256: &sha,
                 image_cosign_bundle: None,
+                variant: mvm_plan::Variant::Dev,
                 intent: None,
                 seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
                 network_policy_ref: None,
@@ -2793,6 +3062,7 @@ chain_signing = false
                 image_name: "vm-unsigned-audit",
                 image_sha256: &sha,
                 image_cosign_bundle: None,
+                variant: mvm_plan::Variant::Dev,
                 intent: None,
                 seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
                 network_policy_ref: None,
@@ -2865,6 +3135,7 @@ chain_signing = false
                 image_name: "vm-nope",
                 image_sha256: &sha,
                 image_cosign_bundle: None,
+                variant: mvm_plan::Variant::Dev,
                 intent: None,
                 seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
                 network_policy_ref: None,
@@ -2957,6 +3228,7 @@ disabled_inspectors = ["ssrf_guarrd"]
                 image_name: "vm-typo",
                 image_sha256: &sha,
                 image_cosign_bundle: None,
+                variant: mvm_plan::Variant::Dev,
                 intent: None,
                 seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
                 network_policy_ref: None,
@@ -3055,6 +3327,7 @@ port_hi  = 443
                 image_name: "vm-bad",
                 image_sha256: &sha,
                 image_cosign_bundle: None,
+                variant: mvm_plan::Variant::Dev,
                 intent: None,
                 seccomp_tier: mvm_plan::PlanSeccompTier::Standard,
                 network_policy_ref: None,
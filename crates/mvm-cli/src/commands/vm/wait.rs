@@ -21,7 +21,7 @@ use mvm_core::naming::validate_vm_name;
 use mvm_core::user_config::MvmConfig;
 use mvm_guest::vsock::{
     ComponentState, GUEST_AGENT_PORT, GuestCapability, GuestRequest, GuestResponse,
-    ReadinessReport, negotiate_protocol, send_request,
+    MandatoryDenyReadiness, ReadinessReport, negotiate_protocol, send_request,
 };
 
 use super::Cli;
@@ -180,6 +180,11 @@ pub(in crate::commands::vm) fn print_human_report(vm: &str, report: &ReadinessRe
     print_row("integrations", &report.integrations);
     print_row("probes", &report.probes);
     print_row("volumes", &report.volumes);
+    println!(
+        "  {:<14} {}",
+        "mandatory deny",
+        render_mandatory_deny(report.mandatory_deny.as_ref())
+    );
 
     let t = &report.boot_millis;
     println!("  timings:");
@@ -202,6 +207,24 @@ fn print_row(label: &str, state: &ComponentState) {
     println!("  {label:<14} {rendered}");
 }
 
+fn render_mandatory_deny(deny: Option<&MandatoryDenyReadiness>) -> String {
+    let Some(deny) = deny else {
+        return "unknown (no netinit report)".to_string();
+    };
+    let v6 = deny.installed.iter().filter(|c| c.addr().is_ipv6()).count();
+    let mut rendered = format!(
+        "{} routes ({} IPv4, {v6} IPv6), ipv6={}",
+        deny.installed.len(),
+        deny.installed.len() - v6,
+        deny.ipv6.as_str()
+    );
+    if !deny.failed.is_empty() {
+        let failed: Vec<String> = deny.failed.iter().map(|c| c.to_string()).collect();
+        rendered.push_str(&format!(", failed: {}", failed.join(" ")));
+    }
+    rendered
+}
+
 fn print_timing(label: &str, ms: Option<u64>) {
     match ms {
         Some(v) => println!("{label}: {v} ms"),
@@ -215,13 +238,13 @@ fn print_timing(label: &str, ms: Option<u64>) {
 
 /// Single readiness round-trip over vsock. Used by `wait`,
 /// `boot-report`, and `up --timings`.
-pub(in crate::commands::vm) fn fetch_readiness(vm_name: &str) -> Result<ReadinessReport> {
+pub(in crate::commands) fn fetch_readiness(vm_name: &str) -> Result<ReadinessReport> {
     let transport: Box<dyn VsockTransport> = vsock_transport::for_vm(vm_name)?;
     let mut stream = transport.connect(GUEST_AGENT_PORT)?;
     let _ = negotiate_protocol(&mut stream, vec![GuestCapability::Readiness])?;
     let resp = send_request(&mut stream, &GuestRequest::ReadinessStatus)?;
     match resp {
-        GuestResponse::ReadinessStatusReport(report) => Ok(report),
+        GuestResponse::ReadinessStatusReport(report) => Ok(*report),
         GuestResponse::Error { message } => bail!("guest readiness error: {message}"),
         GuestResponse::UnsupportedInProfile { profile, verb } => bail!(
             "agent refused {verb} in profile {:?} — this should be impossible for ReadinessStatus",
@@ -319,9 +342,27 @@ mod tests {
                 entrypoint_ready_ms: Some(40),
                 ..Default::default()
             },
+            mandatory_deny: None,
         }
     }
 
+    #[test]
+    fn render_mandatory_deny_counts_families_and_failures() {
+        assert_eq!(render_mandatory_deny(None), "unknown (no netinit report)");
+        let deny = MandatoryDenyReadiness {
+            installed: vec![
+                "169.254.169.254/32".parse().unwrap(),
+                "fd00:ec2::254/128".parse().unwrap(),
+            ],
+            failed: vec!["fc00::/7".parse().unwrap()],
+            ipv6: mvm_guest::netinit::Ipv6Deny::Enforced,
+        };
+        assert_eq!(
+            render_mandatory_deny(Some(&deny)),
+            "2 routes (1 IPv4, 1 IPv6), ipv6=enforced, failed: fc00::/7"
+        );
+    }
+
     #[test]
     fn evaluate_all_with_ready_or_disabled_components_returns_ready() {
         assert_eq!(
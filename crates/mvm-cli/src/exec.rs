@@ -885,6 +885,62 @@ pub fn dispatch_in_session(vm: &SessionVm, code: String, timeout_secs: u64) -> R
     if !wait_for_agent(&vm.vm_name, 30) {
         anyhow::bail!("guest agent did not become reachable within 30s");
     }
+    let wrapper = session_wrapper(code, timeout_secs);
+    let resp = send_request(&vm.vm_name, &wrapper, timeout_secs)?;
+    match resp {
+        mvm_guest::vsock::GuestResponse::ExecResult {
+            exit_code,
+            stdout,
+            stderr,
+        } => Ok(ExecOutput {
+            exit_code,
+            stdout,
+            stderr,
+        }),
+        mvm_guest::vsock::GuestResponse::Error { message } => {
+            anyhow::bail!("guest exec error: {message}")
+        }
+        other => anyhow::bail!("unexpected guest response: {other:?}"),
+    }
+}
+
+/// [`dispatch_in_session`] on a side thread, polling `watch` once a
+/// tick. A cancelled call returns [`SessionCallAbandoned`] without
+/// waiting for the thread, which ends when the VM is torn down.
+fn dispatch_watched(
+    vm: &SessionVm,
+    code: String,
+    timeout_secs: u64,
+    watch: &mut dyn FnMut(std::time::Duration, usize) -> bool,
+) -> Result<ExecOutput> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let owned = SessionVm {
+        vm_name: vm.vm_name.clone(),
+    };
+    std::thread::spawn(move || {
+        let _ = tx.send(dispatch_in_session(&owned, code, timeout_secs));
+    });
+    let started = std::time::Instant::now();
+    loop {
+        match rx.recv_timeout(SESSION_WATCH_TICK) {
+            Ok(result) => return result,
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("session dispatch thread ended without a result")
+            }
+        }
+        // `Exec` returns output only at the end, so none is reported.
+        if !watch(started.elapsed(), 0) {
+            return Err(SessionCallAbandoned {
+                vm_name: vm.vm_name.clone(),
+            }
+            .into());
+        }
+    }
+}
+
+/// The guest shell script for one session call.
+fn session_wrapper(code: String, timeout_secs: u64) -> String {
     // Reuse build_guest_wrapper by constructing a minimal ExecRequest
     // with no add_dirs (sessions don't take --add-dir). The wrapper
     // emits `set -e\n<env exports>\n<argv>\n`.
@@ -900,23 +956,314 @@ pub fn dispatch_in_session(vm: &SessionVm, code: String, timeout_secs: u64) -> R
         },
         timeout_secs,
     };
-    let wrapper = build_guest_wrapper(&req, &[]);
-    let resp = send_request(&vm.vm_name, &wrapper, timeout_secs)?;
-    match resp {
-        mvm_guest::vsock::GuestResponse::ExecResult {
-            exit_code,
-            stdout,
-            stderr,
-        } => Ok(ExecOutput {
-            exit_code,
-            stdout,
-            stderr,
-        }),
-        mvm_guest::vsock::GuestResponse::Error { message } => {
-            anyhow::bail!("guest exec error: {message}")
+    build_guest_wrapper(&req, &[])
+}
+
+/// `PATH` for session commands started over process RPC. `ProcStart`
+/// hands the child only the environment the host sends, where `Exec`
+/// inherits the agent's; this is the NixOS system profile plus the
+/// usual FHS directories.
+pub const SESSION_PROC_PATH: &str = "/run/current-system/sw/bin:/usr/local/bin:/usr/bin:/bin";
+
+/// Output kept per stream by [`run_in_session`]. The MCP layer
+/// truncates further when it builds the response.
+const SESSION_OUTPUT_CAP: usize = 4 * 1024 * 1024;
+
+/// How often [`run_in_session`] calls its `watch` callback.
+const SESSION_WATCH_TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// A session call the client cancelled on an agent without process
+/// RPC. Such an agent can't kill one command, so it is still running
+/// in the guest: the caller must tear the VM down to stop it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionCallAbandoned {
+    pub vm_name: String,
+}
+
+impl std::fmt::Display for SessionCallAbandoned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cancelled by the client; the guest agent in {} has no process RPC, so the command \
+             is stopped by tearing the VM down",
+            self.vm_name
+        )
+    }
+}
+
+impl std::error::Error for SessionCallAbandoned {}
+
+/// Like [`dispatch_in_session`], but runs the command as a tracked
+/// guest process (`ProcStart` + streaming `ProcWait`) so the caller
+/// can watch it. `watch` is called about once a second with the time
+/// elapsed and the output bytes so far; returning `false` kills the
+/// process and fails the call with "cancelled by the client".
+///
+/// Agents without the process RPC capability (prod agents) run the
+/// command with a plain `Exec` on a side thread while `watch` is
+/// polled here. Cancelling then fails the call at once with
+/// [`SessionCallAbandoned`]; the command keeps running until the
+/// caller tears the VM down.
+pub fn run_in_session(
+    vm: &SessionVm,
+    code: String,
+    timeout_secs: u64,
+    watch: &mut dyn FnMut(std::time::Duration, usize) -> bool,
+) -> Result<ExecOutput> {
+    use mvm_guest::vsock::{GuestCapability, GuestRequest, GuestResponse, ProcResult};
+
+    if !wait_for_agent(&vm.vm_name, 30) {
+        anyhow::bail!("guest agent did not become reachable within 30s");
+    }
+    let transport = vsock_transport::for_vm(&vm.vm_name)?;
+    let mut control = transport.connect(mvm_guest::vsock::GUEST_AGENT_PORT)?;
+    let negotiated =
+        mvm_guest::vsock::negotiate_protocol(&mut control, vec![GuestCapability::ProcessRpc])?;
+    if !negotiated
+        .capabilities
+        .contains(&GuestCapability::ProcessRpc)
+    {
+        drop(control);
+        return dispatch_watched(vm, code, timeout_secs, watch);
+    }
+
+    // `/bin/sh -c <wrapper>` is exactly what the agent's Exec handler
+    // runs, so both paths see the same script.
+    let start = GuestRequest::ProcStart {
+        argv: vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            session_wrapper(code, timeout_secs),
+        ],
+        env: BTreeMap::from([("PATH".to_string(), SESSION_PROC_PATH.to_string())]),
+        cwd: None,
+        stdin: Vec::new(),
+        timeout_secs: Some(timeout_secs),
+        pty: None,
+    };
+    emit_rpc_audit(&vm.vm_name, &start);
+    let pid_token = match mvm_guest::vsock::send_request(&mut control, &start)? {
+        GuestResponse::ProcResult(ProcResult::Started { pid_token }) => pid_token,
+        GuestResponse::ProcResult(ProcResult::Error { kind, message }) => {
+            anyhow::bail!("guest proc-start error ({kind:?}): {message}")
+        }
+        GuestResponse::Error { message } => anyhow::bail!("guest proc-start error: {message}"),
+        other => anyhow::bail!("unexpected guest response: {other:?}"),
+    };
+    drop(control);
+
+    let mut stream = transport.connect(mvm_guest::vsock::GUEST_AGENT_PORT)?;
+    mvm_guest::vsock::require_capabilities(&mut stream, &[GuestCapability::ProcessRpc])?;
+    // The stream is silent while the command is; the agent ends it at
+    // the timeout, so only a wedged agent outlives this.
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(timeout_secs + 30)))?;
+    let wait = GuestRequest::ProcWait {
+        pid_token: pid_token.clone(),
+        timeout_secs: Some(timeout_secs),
+    };
+    emit_rpc_audit(&vm.vm_name, &wait);
+    mvm_guest::vsock::write_frame(&mut stream, &wait)?;
+    let closer = stream.try_clone()?;
+
+    let started = std::time::Instant::now();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let exit_code = std::thread::scope(|scope| -> Result<i32> {
+        scope.spawn(move || {
+            loop {
+                let frame = mvm_guest::vsock::read_frame::<GuestResponse>(&mut stream);
+                let more = matches!(
+                    &frame,
+                    Ok(GuestResponse::ProcWaitEvent(ev)) if !ev.is_terminal()
+                );
+                if tx.send(frame).is_err() || !more {
+                    return;
+                }
+            }
+        });
+
+        let mut cancelled = false;
+        let mut last_watch = started;
+        loop {
+            let frame = match rx.recv_timeout(SESSION_WATCH_TICK) {
+                Ok(frame) => Some(frame),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => None,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                    anyhow::bail!("guest proc-wait stream ended without an exit status")
+                }
+            };
+            if let Some(frame) = frame {
+                if cancelled {
+                    // Whatever ends the stream now is the kill landing.
+                    let ends = !matches!(
+                        &frame,
+                        Ok(GuestResponse::ProcWaitEvent(ev)) if !ev.is_terminal()
+                    );
+                    if ends {
+                        anyhow::bail!("cancelled by the client");
+                    }
+                    continue;
+                }
+                if let Some(code) = proc_wait_step(frame, &mut stdout, &mut stderr, timeout_secs)? {
+                    return Ok(code);
+                }
+            }
+            if !cancelled && last_watch.elapsed() >= SESSION_WATCH_TICK {
+                last_watch = std::time::Instant::now();
+                if !watch(started.elapsed(), stdout.len() + stderr.len()) {
+                    cancelled = true;
+                    if let Err(e) = kill_session_proc(&vm.vm_name, transport.as_ref(), &pid_token) {
+                        tracing::warn!(vm = %vm.vm_name, err = %e, "killing cancelled session command");
+                    }
+                    // Unblocks the reader if the kill did not end the stream.
+                    let _ = closer.shutdown(std::net::Shutdown::Both);
+                }
+            }
         }
+    })?;
+    Ok(ExecOutput {
+        exit_code,
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+    })
+}
+
+/// Fold one `ProcWait` frame into the captured output. `Some(code)`
+/// once the process is done.
+fn proc_wait_step(
+    frame: Result<mvm_guest::vsock::GuestResponse>,
+    stdout: &mut Vec<u8>,
+    stderr: &mut Vec<u8>,
+    timeout_secs: u64,
+) -> Result<Option<i32>> {
+    use mvm_guest::vsock::{GuestResponse, ProcWaitEvent};
+
+    let append = |buf: &mut Vec<u8>, chunk: &[u8]| {
+        let room = SESSION_OUTPUT_CAP.saturating_sub(buf.len());
+        buf.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    };
+    match frame.context("reading guest proc-wait stream")? {
+        GuestResponse::ProcWaitEvent(ev) => match ev {
+            ProcWaitEvent::Stdout { chunk } => append(stdout, &chunk),
+            ProcWaitEvent::Stderr { chunk } => append(stderr, &chunk),
+            ProcWaitEvent::Backpressure { reason, detail } => {
+                tracing::debug!(?reason, %detail, "session command output throttled");
+            }
+            ProcWaitEvent::Exit { code } => return Ok(Some(code)),
+            ProcWaitEvent::Killed { signal } => return Ok(Some(128 + signal)),
+            ProcWaitEvent::TimedOut => {
+                append(
+                    stderr,
+                    format!("\nmvm: command timed out after {timeout_secs}s\n").as_bytes(),
+                );
+                return Ok(Some(124));
+            }
+            ProcWaitEvent::Error { kind, message } => {
+                anyhow::bail!("guest proc-wait error ({kind:?}): {message}")
+            }
+        },
+        GuestResponse::Error { message } => anyhow::bail!("guest proc-wait error: {message}"),
         other => anyhow::bail!("unexpected guest response: {other:?}"),
     }
+    Ok(None)
+}
+
+fn kill_session_proc(
+    vm_name: &str,
+    transport: &dyn vsock_transport::VsockTransport,
+    pid_token: &str,
+) -> Result<()> {
+    use mvm_guest::vsock::{GuestCapability, GuestRequest, GuestResponse, ProcResult};
+
+    let mut stream = transport.connect(mvm_guest::vsock::GUEST_AGENT_PORT)?;
+    mvm_guest::vsock::require_capabilities(&mut stream, &[GuestCapability::ProcessRpc])?;
+    let request = GuestRequest::ProcKill {
+        pid_token: pid_token.to_string(),
+    };
+    emit_rpc_audit(vm_name, &request);
+    match mvm_guest::vsock::send_request(&mut stream, &request)? {
+        GuestResponse::ProcResult(ProcResult::Killed) => Ok(()),
+        other => anyhow::bail!("unexpected response to proc-kill: {other:?}"),
+    }
+}
+
+/// Send a filesystem RPC request to a session VM. Session VMs have no
+/// instance dir to hand [`mvm_guest::vsock::send_fs_request`], so
+/// this connects through the VM's vsock transport. Session VMs boot
+/// without a persisted plan, so the call is metered against the
+/// default [`crate::fs_quota`] limits, in this process.
+pub fn session_fs_request(
+    vm_name: &str,
+    request: mvm_guest::vsock::GuestRequest,
+) -> Result<mvm_guest::vsock::FsResult> {
+    use mvm_guest::vsock::{GuestCapability, GuestResponse};
+
+    let transport = vsock_transport::for_vm(vm_name)?;
+    let mut stream = transport.connect(mvm_guest::vsock::GUEST_AGENT_PORT)?;
+    mvm_guest::vsock::require_capabilities(&mut stream, &[GuestCapability::FilesystemRpc])?;
+    emit_rpc_audit(vm_name, &request);
+    crate::fs_quota::admit(vm_name, &request, || {
+        Ok(crate::fs_quota::FsQuotaSource::in_process(
+            Default::default(),
+        ))
+    })?;
+    match mvm_guest::vsock::send_request(&mut stream, &request)? {
+        GuestResponse::FsResult(r) => Ok(r),
+        GuestResponse::Error { message } => anyhow::bail!("guest FS RPC error: {message}"),
+        other => anyhow::bail!("unexpected response to FS RPC verb: {other:?}"),
+    }
+}
+
+/// Send a non-streaming process RPC request (`ProcStart`, `ProcList`,
+/// `ProcSignal`, `ProcSendInput`, `ProcKill`) to a session VM. The
+/// streaming `ProcWait` goes through [`wait_session_proc`].
+pub fn session_proc_request(
+    vm_name: &str,
+    request: mvm_guest::vsock::GuestRequest,
+) -> Result<mvm_guest::vsock::ProcResult> {
+    use mvm_guest::vsock::{GuestCapability, GuestResponse};
+
+    let transport = vsock_transport::for_vm(vm_name)?;
+    let mut stream = transport.connect(mvm_guest::vsock::GUEST_AGENT_PORT)?;
+    mvm_guest::vsock::require_capabilities(&mut stream, &[GuestCapability::ProcessRpc])?;
+    emit_rpc_audit(vm_name, &request);
+    match mvm_guest::vsock::send_request(&mut stream, &request)? {
+        GuestResponse::ProcResult(r) => Ok(r),
+        GuestResponse::Error { message } => anyhow::bail!("guest process RPC error: {message}"),
+        other => anyhow::bail!("unexpected response to process RPC verb: {other:?}"),
+    }
+}
+
+/// Wait for the process `pid_token` in a session VM to finish and
+/// collect its output. The agent kills a process still running after
+/// `timeout_secs`, which reports exit code 124.
+pub fn wait_session_proc(vm_name: &str, pid_token: &str, timeout_secs: u64) -> Result<ExecOutput> {
+    use mvm_guest::vsock::{GuestCapability, GuestRequest, GuestResponse};
+
+    let transport = vsock_transport::for_vm(vm_name)?;
+    let mut stream = transport.connect(mvm_guest::vsock::GUEST_AGENT_PORT)?;
+    mvm_guest::vsock::require_capabilities(&mut stream, &[GuestCapability::ProcessRpc])?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(timeout_secs + 30)))?;
+    let wait = GuestRequest::ProcWait {
+        pid_token: pid_token.to_string(),
+        timeout_secs: Some(timeout_secs),
+    };
+    emit_rpc_audit(vm_name, &wait);
+    mvm_guest::vsock::write_frame(&mut stream, &wait)?;
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let exit_code = loop {
+        let frame = mvm_guest::vsock::read_frame::<GuestResponse>(&mut stream);
+        if let Some(code) = proc_wait_step(frame, &mut stdout, &mut stderr, timeout_secs)? {
+            break code;
+        }
+    };
+    Ok(ExecOutput {
+        exit_code,
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+    })
 }
 
 /// Tear down a session VM. Best-effort — failures (already-stopped,
@@ -975,12 +1322,16 @@ fn send_request(
         stdin: None,
         timeout_secs: Some(timeout_secs),
     };
-    // Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit. exec.rs
-    // is a top-level module that can't reach the private
-    // `commands::shared` re-export, so inline the audit emit
-    // here. The detail format matches
-    // `commands::shared::vsock::emit_vsock_rpc_audit`:
-    // `scope=rpc,direction=in,kind=vsock,verb=<kebab-name>`.
+    emit_rpc_audit(vm_name, &request);
+    mvm_guest::vsock::send_request(&mut stream, &request)
+}
+
+/// Plan 74 W2 / Plan 51 W6 — inbound vsock RPC audit. exec.rs is a
+/// top-level module that can't reach the private `commands::shared`
+/// re-export, so the emit lives here. The detail format matches
+/// `commands::shared::vsock::emit_vsock_rpc_audit`:
+/// `scope=rpc,direction=in,kind=vsock,verb=<kebab-name>`.
+fn emit_rpc_audit(vm_name: &str, request: &mvm_guest::vsock::GuestRequest) {
     let verb = request.kind_name();
     mvm_core::audit_emit!(
         NetworkPolicyAllow,
@@ -988,7 +1339,6 @@ fn send_request(
         "scope=rpc,direction=in,kind=vsock,verb={verb}",
         verb = verb,
     );
-    mvm_guest::vsock::send_request(&mut stream, &request)
 }
 
 #[cfg(test)]
@@ -1179,6 +1529,31 @@ mod tests {
         assert!(!script.contains("-o ro"), "RW mount must not include -o ro");
     }
 
+    #[test]
+    fn proc_wait_step_collects_output_and_maps_terminal_events() {
+        use mvm_guest::vsock::{GuestResponse, ProcWaitEvent};
+        let event = |ev| Ok(GuestResponse::ProcWaitEvent(ev));
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let step = |frame, out: &mut Vec<u8>, err: &mut Vec<u8>| {
+            proc_wait_step(frame, out, err, 5).unwrap()
+        };
+
+        let chunk = ProcWaitEvent::Stdout {
+            chunk: b"hi".to_vec(),
+        };
+        assert_eq!(step(event(chunk), &mut out, &mut err), None);
+        assert_eq!(out, b"hi");
+        let exit = ProcWaitEvent::Exit { code: 3 };
+        assert_eq!(step(event(exit), &mut out, &mut err), Some(3));
+        let killed = ProcWaitEvent::Killed { signal: 9 };
+        assert_eq!(step(event(killed), &mut out, &mut err), Some(137));
+        assert_eq!(
+            step(event(ProcWaitEvent::TimedOut), &mut out, &mut err),
+            Some(124)
+        );
+        assert!(String::from_utf8_lossy(&err).contains("timed out after 5s"));
+    }
+
     #[test]
     fn transient_vm_name_format() {
         let n = transient_vm_name();
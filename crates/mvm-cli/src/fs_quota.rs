@@ -0,0 +1,332 @@
+//! Host-side filesystem RPC quota — Layer 3 of the DoS model.
+//!
+//! Every FS verb `mvmctl fs`, `mvmctl cp` and the MCP `mvm.fs.*` tools
+//! send is metered per VM by a [`mvm_security::quota_meter::QuotaMeter`]
+//! before it leaves the host: ops per second, `FsRead` / `FsWrite`
+//! bytes per second, and a byte budget for the VM's boot. The limits
+//! come from the `[fs_quota]` section of the bundle the VM's plan
+//! names. `mvmctl up` stages the same section on the config drive
+//! (`mvm_guest::fs_quota::FS_QUOTA_DRIVE_FILE`), so the guest agent's
+//! backstop enforces the limits the host does.
+//!
+//! The per-second windows live for the host process. The session
+//! total of a VM with a persisted plan lives in a [`SessionLedger`]
+//! in its state dir, keyed by the plan it booted under, so it spans
+//! `mvmctl fs` / `mvmctl cp` invocations and starts over when the VM
+//! is relaunched. Session VMs have no state dir; their total lives as
+//! long as the MCP server that owns them.
+//!
+//! A rejection never reaches the guest and surfaces as
+//! [`FsQuotaRejected`], carrying the same `BackpressureReason` the
+//! agent would answer with.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use mvm_core::atomic_io::{FileLock, atomic_write};
+use mvm_core::domain::instance::BackpressureReason;
+use mvm_core::security::FsQuotaPolicy;
+use mvm_guest::vsock::{FsResult, GuestRequest};
+use mvm_security::quota_meter::{FsUsage, QuotaMeter};
+use serde::{Deserialize, Serialize};
+
+/// File name of the session ledger inside the VM state dir.
+pub const LEDGER_FILENAME: &str = "fs-quota.json";
+
+/// Where a VM's FS quota comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsQuotaSource {
+    pub policy: FsQuotaPolicy,
+    /// Ledger for the session total. `None` keeps it in this process.
+    pub ledger: Option<SessionLedger>,
+}
+
+impl FsQuotaSource {
+    /// `policy` with the session total kept in this process.
+    pub fn in_process(policy: FsQuotaPolicy) -> Self {
+        Self {
+            policy,
+            ledger: None,
+        }
+    }
+}
+
+/// On-disk session total shared by every host process metering one
+/// VM. Reads and writes hold an exclusive `flock` on a sibling
+/// `.lock` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLedger {
+    pub path: PathBuf,
+    /// The boot the total counts for (the admitted plan's id). A
+    /// ledger written for another boot reads as empty.
+    pub boot_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LedgerRecord {
+    boot_id: String,
+    session_bytes: u64,
+}
+
+impl SessionLedger {
+    /// Run `f` on the VM's session total under the ledger lock and
+    /// persist the total it leaves behind.
+    fn update<T>(&self, f: impl FnOnce(&mut u64) -> T) -> Result<T> {
+        let _lock = FileLock::acquire(&self.path)?;
+        let mut total = self.read()?;
+        let out = f(&mut total);
+        let record = LedgerRecord {
+            boot_id: self.boot_id.clone(),
+            session_bytes: total,
+        };
+        atomic_write(&self.path, &serde_json::to_vec(&record)?)
+            .with_context(|| format!("writing FS quota ledger {}", self.path.display()))?;
+        Ok(out)
+    }
+
+    fn read(&self) -> Result<u64> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("reading FS quota ledger {}", self.path.display()));
+            }
+        };
+        let record: LedgerRecord = serde_json::from_slice(&bytes)
+            .with_context(|| format!("parsing FS quota ledger {}", self.path.display()))?;
+        Ok(if record.boot_id == self.boot_id {
+            record.session_bytes
+        } else {
+            0
+        })
+    }
+}
+
+/// A call the host FS quota refused before dispatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsQuotaRejected {
+    pub verb: &'static str,
+    pub reason: BackpressureReason,
+    pub detail: String,
+}
+
+impl std::fmt::Display for FsQuotaRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} throttled by the host FS quota ({:?}): {}",
+            self.verb, self.reason, self.detail
+        )
+    }
+}
+
+impl std::error::Error for FsQuotaRejected {}
+
+struct VmMeter {
+    meter: QuotaMeter,
+    ledger: Option<SessionLedger>,
+}
+
+fn meters() -> &'static Mutex<HashMap<String, VmMeter>> {
+    static METERS: OnceLock<Mutex<HashMap<String, VmMeter>>> = OnceLock::new();
+    METERS.get_or_init(Default::default)
+}
+
+/// Meter `request` against `vm`'s quota. `source` is consulted only
+/// the first time this process meters `vm`. Fails with
+/// [`FsQuotaRejected`] when the quota rejects the call; non-FS verbs
+/// always pass.
+pub fn admit(
+    vm: &str,
+    request: &GuestRequest,
+    source: impl FnOnce() -> Result<FsQuotaSource>,
+) -> Result<()> {
+    let Some(usage) = request.fs_usage() else {
+        return Ok(());
+    };
+    let known = meters()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(vm);
+    let fresh = if known { None } else { Some(source()?) };
+
+    let mut meters = meters().lock().unwrap_or_else(|e| e.into_inner());
+    let VmMeter { meter, ledger } = meters.entry(vm.to_string()).or_insert_with(|| {
+        let source = fresh.unwrap_or_else(|| FsQuotaSource::in_process(Default::default()));
+        VmMeter {
+            meter: QuotaMeter::new(&source.policy),
+            ledger: source.ledger,
+        }
+    });
+    // Metadata calls never touch the session budget.
+    let checked = match ledger {
+        Some(ledger) if usage != FsUsage::Meta => ledger.update(|total| {
+            meter.restore_session_bytes(*total);
+            let checked = meter.check_and_record(usage);
+            *total = meter.session_bytes();
+            checked
+        })?,
+        _ => meter.check_and_record(usage),
+    };
+    if let Err(exceeded) = checked {
+        let rejected = FsQuotaRejected {
+            verb: request.kind_name(),
+            reason: exceeded.reason(),
+            detail: exceeded.detail(meter.policy()),
+        };
+        tracing::warn!(
+            vm,
+            verb = rejected.verb,
+            reason = ?rejected.reason,
+            detail = %rejected.detail,
+            "FS quota rejected call"
+        );
+        return Err(rejected.into());
+    }
+    Ok(())
+}
+
+/// Charge what a dispatched call turned out to move — a download's
+/// size, reported by the agent when the stream opens.
+pub fn settle(vm: &str, request: &GuestRequest, result: &FsResult) -> Result<()> {
+    let (
+        GuestRequest::FsDownloadStream { .. },
+        FsResult::StreamOpened {
+            offset, total_size, ..
+        },
+    ) = (request, result)
+    else {
+        return Ok(());
+    };
+    let bytes = total_size.saturating_sub(*offset);
+    let mut meters = meters().lock().unwrap_or_else(|e| e.into_inner());
+    let Some(VmMeter { meter, ledger }) = meters.get_mut(vm) else {
+        return Ok(());
+    };
+    match ledger {
+        Some(ledger) => ledger.update(|total| {
+            meter.restore_session_bytes(*total);
+            meter.record_streamed(bytes);
+            *total = meter.session_bytes();
+        }),
+        None => {
+            meter.record_streamed(bytes);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+
+    fn stat() -> GuestRequest {
+        GuestRequest::FsStat {
+            path: "/tmp".to_string(),
+            follow_symlinks: true,
+        }
+    }
+
+    fn download() -> GuestRequest {
+        GuestRequest::FsDownloadStream {
+            path: "/tmp/big".to_string(),
+            offset: 0,
+            follow_symlinks: true,
+        }
+    }
+
+    fn opened(total_size: u64) -> FsResult {
+        FsResult::StreamOpened {
+            stream_id: 1,
+            data_port: 30_001,
+            offset: 0,
+            total_size,
+        }
+    }
+
+    fn session_limited(bytes: u64) -> FsQuotaPolicy {
+        FsQuotaPolicy {
+            session_bytes: bytes,
+            ..FsQuotaPolicy::unlimited()
+        }
+    }
+
+    fn rejection(err: &anyhow::Error) -> &FsQuotaRejected {
+        err.downcast_ref::<FsQuotaRejected>()
+            .unwrap_or_else(|| panic!("expected FsQuotaRejected, got {err:#}"))
+    }
+
+    #[test]
+    fn admit_meters_each_vm_separately() {
+        let source = || {
+            Ok(FsQuotaSource::in_process(FsQuotaPolicy {
+                ops_per_second: 1,
+                ..FsQuotaPolicy::unlimited()
+            }))
+        };
+        admit("fsq-a", &stat(), source).unwrap();
+        let err = admit("fsq-a", &stat(), source).unwrap_err();
+        assert_eq!(rejection(&err).reason, BackpressureReason::FsRateLimited);
+        assert_eq!(rejection(&err).verb, "fs-stat");
+        admit("fsq-b", &stat(), source).unwrap();
+        // Non-FS verbs are never metered.
+        admit("fsq-a", &GuestRequest::Ping, source).unwrap();
+    }
+
+    #[test]
+    fn source_is_resolved_once_per_vm() {
+        let unlimited = || Ok(FsQuotaSource::in_process(FsQuotaPolicy::unlimited()));
+        admit("fsq-once", &stat(), unlimited).unwrap();
+        admit("fsq-once", &stat(), || bail!("policy lookup repeated")).unwrap();
+        assert!(admit("fsq-fails", &stat(), || bail!("bundle missing")).is_err());
+    }
+
+    #[test]
+    fn settled_download_spends_the_session_budget() {
+        let source = || Ok(FsQuotaSource::in_process(session_limited(100)));
+        admit("fsq-dl", &download(), source).unwrap();
+        settle("fsq-dl", &download(), &opened(100)).unwrap();
+        let err = admit("fsq-dl", &download(), source).unwrap_err();
+        assert_eq!(
+            rejection(&err).reason,
+            BackpressureReason::FsSessionQuotaExhausted
+        );
+    }
+
+    #[test]
+    fn ledger_carries_the_session_total_across_meters() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = |boot_id: &str| SessionLedger {
+            path: dir.path().join(LEDGER_FILENAME),
+            boot_id: boot_id.to_string(),
+        };
+        let source = |boot_id: &'static str| {
+            let ledger = ledger(boot_id);
+            move || {
+                Ok(FsQuotaSource {
+                    policy: session_limited(100),
+                    ledger: Some(ledger),
+                })
+            }
+        };
+
+        // Two VM names stand in for two host processes metering the
+        // same VM: each has its own in-process meter, one ledger.
+        admit("fsq-ledger-1", &download(), source("boot-1")).unwrap();
+        settle("fsq-ledger-1", &download(), &opened(100)).unwrap();
+        let err = admit("fsq-ledger-2", &download(), source("boot-1")).unwrap_err();
+        assert_eq!(
+            rejection(&err).reason,
+            BackpressureReason::FsSessionQuotaExhausted
+        );
+
+        // A relaunch under a new plan starts a fresh budget.
+        admit("fsq-ledger-3", &download(), source("boot-2")).unwrap();
+        assert_eq!(ledger("boot-2").read().unwrap(), 0);
+        assert_eq!(ledger("boot-1").read().unwrap(), 0);
+    }
+}
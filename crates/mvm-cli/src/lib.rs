@@ -6,6 +6,7 @@ pub mod commands;
 pub mod config_watcher;
 pub mod doctor;
 pub mod exec;
+pub mod fs_quota;
 pub mod host_binaries;
 pub mod http;
 pub mod logging;
@@ -162,6 +162,12 @@ pub enum BackpressureReason {
     /// The shared builder VM is occupied by another build; this
     /// run is queued behind it.
     BuilderBusy,
+    /// A per-second filesystem RPC rate (ops, read or write bytes)
+    /// is saturated. Clears as the one-second window slides.
+    FsRateLimited,
+    /// The session's filesystem byte budget is spent. Does not clear
+    /// until the meter is reset (agent reboot / new host session).
+    FsSessionQuotaExhausted,
 }
 
 /// Finer-grained "is this VM usable?" state, composed alongside the
@@ -611,6 +617,11 @@ mod tests {
                 "\"artifact_transfer_blocked\"",
             ),
             (BackpressureReason::BuilderBusy, "\"builder_busy\""),
+            (BackpressureReason::FsRateLimited, "\"fs_rate_limited\""),
+            (
+                BackpressureReason::FsSessionQuotaExhausted,
+                "\"fs_session_quota_exhausted\"",
+            ),
         ] {
             let json = serde_json::to_string(&variant).unwrap();
             assert_eq!(
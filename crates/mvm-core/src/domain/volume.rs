@@ -14,9 +14,8 @@
 //! ## Backends
 //!
 //! [`VolumeBackendConfig`] is the declarative shape of a backend. The
-//! mvm-storage crate ships `LocalBackend` only; mvmd ships
-//! `ObjectStoreBackend` (wrapping `opendal`) and `EncryptedBackend<B>`
-//! per Path C of plan 45.
+//! mvm-storage crate ships `LocalBackend`, `ObjectStoreBackend`
+//! (wrapping `opendal`) and the `EncryptedBackend<B>` decorator.
 
 use std::path::PathBuf;
 
@@ -268,7 +267,7 @@ impl std::fmt::Display for SecretRef {
 // ============================================================================
 
 /// Declarative backend shape. Behaviour lives in `mvm-storage`
-/// (LocalBackend) and mvmd-side crates (ObjectStoreBackend +
+/// (LocalBackend + ObjectStoreBackend, optionally wrapped in
 /// EncryptedBackend) — see plan 45 §D5.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
@@ -281,7 +280,8 @@ pub enum VolumeBackendConfig {
     },
 
     /// Object storage via opendal (S3, R2, GCS, Azure, Hetzner, …).
-    /// Implemented in mvmd, not mvm-storage. Data-plane only — not
+    /// mvm-storage implements the S3-compatible and in-memory
+    /// providers; mvmd covers the rest. Data-plane only — not
     /// virtio-fs-mountable in v1.
     #[serde(rename = "object-store")]
     ObjectStore(ObjectStoreSpec),
@@ -313,7 +313,8 @@ impl VolumeBackendConfig {
 /// - `gs://bucket[/prefix]` (Google Cloud Storage).
 /// - `az://container[/prefix]` (Azure Blob).
 /// - `file:///path` (local filesystem — testing only).
-/// - `memory://` (in-memory — testing only).
+/// - `memory://` (in-memory — testing only; `mvm-storage` refuses it
+///   as a volume backing).
 ///
 /// Credentials are referenced, never embedded.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -388,7 +389,7 @@ pub struct VolumeEntry {
 }
 
 // ============================================================================
-// Encryption-at-rest key wrapping (used by mvmd-side EncryptedBackend)
+// Encryption-at-rest key wrapping (used by EncryptedBackend)
 // ============================================================================
 
 /// Algorithm used to wrap a per-volume data key under a master key.
@@ -396,10 +397,10 @@ pub struct VolumeEntry {
 #[serde(rename_all = "kebab-case")]
 pub enum WrapAlgorithm {
     /// AES Key Wrap with Padding (NIST SP 800-38F / RFC 5649).
-    /// Implemented mvmd-side per plan 45 §D5 ("EncryptedBackend
-    /// lives in mvmd, not mvm"); `mvm_security::key_rotation`'s
+    /// Implemented mvmd-side only; `mvm_security::key_rotation`'s
     /// rewrap path returns `UnsupportedAlgorithm` when asked to
-    /// rewrap an `AesKwp` envelope.
+    /// rewrap an `AesKwp` envelope, and mvm-storage's
+    /// `EncryptedBackend` refuses to open one.
     AesKwp,
 
     /// AES-256-GCM AEAD wrap (12-byte nonce || ciphertext || 16-byte
@@ -8,11 +8,12 @@
 //! retroactively delete or modify resource consumption records.
 //!
 //! This module provides the data shapes only — no sampling daemon,
-//! no exporter wiring. Producers (the supervisor's instance sampler,
-//! when it lands) emit `MeteringSample`s; consumers aggregate them
-//! into per-minute `MeteringBucket`s and chain each bucket into the
-//! audit log via the existing `LocalAuditKind::MeteringEpoch`
-//! variant. JSONL serialization helpers are included for the
+//! no exporter wiring. Producers (the supervisor's
+//! `instance_sampler::MeteringLog`, which no production loop drives
+//! yet) emit `MeteringSample`s; consumers
+//! aggregate them into per-minute `MeteringBucket`s and chain each
+//! bucket into the audit log via the existing
+//! `LocalAuditKind::MeteringEpoch` variant. JSONL serialization helpers are included for the
 //! per-tenant rollup file at `~/.mvm/metering/<tenant>/<date>.jsonl`.
 //!
 //! # Three-axis decomposition
@@ -35,9 +36,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::time::SystemTime;
 
-/// One metering sample for one instance at one tick. Producers emit
-/// these at the supervisor's existing tick (≥1 Hz, jittered) when
-/// the sampler integration lands.
+/// One metering sample for one instance at one tick. The supervisor's
+/// sampler emits these per sampling pass.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MeteringSample {
     pub instance_id: String,
@@ -56,6 +56,16 @@ pub struct InstanceMetricsValues {
     pub net_rx_packets: u64,
     /// Cumulative packets transmitted on the TAP iface (counter).
     pub net_tx_packets: u64,
+    /// New egress flows the VM's nftables table allowed (counter).
+    #[serde(default)]
+    pub egress_allowed_packets: u64,
+    /// Egress packets the table's policy dropped (counter).
+    #[serde(default)]
+    pub egress_dropped_packets: u64,
+    /// Egress packets dropped for hitting a mandatory-deny range —
+    /// cloud metadata, link-local, loopback (counter).
+    #[serde(default)]
+    pub egress_mandatory_deny_packets: u64,
     /// Wall-clock seconds since the supervisor first saw this VM
     /// running (gauge).
     pub uptime_secs: u64,
@@ -249,6 +259,24 @@ const METRIC_SPECS: &[MetricSpec] = &[
         help: "Cumulative packets transmitted on the VM TAP interface",
         read: |v| v.net_tx_packets,
     },
+    MetricSpec {
+        name: "mvm_instance_egress_allowed_packets_total",
+        kind: MetricKind::Counter,
+        help: "New egress flows allowed by the VM nftables table",
+        read: |v| v.egress_allowed_packets,
+    },
+    MetricSpec {
+        name: "mvm_instance_egress_dropped_packets_total",
+        kind: MetricKind::Counter,
+        help: "Egress packets dropped by the VM network policy",
+        read: |v| v.egress_dropped_packets,
+    },
+    MetricSpec {
+        name: "mvm_instance_egress_mandatory_deny_packets_total",
+        kind: MetricKind::Counter,
+        help: "Egress packets dropped for targeting a mandatory-deny range",
+        read: |v| v.egress_mandatory_deny_packets,
+    },
     MetricSpec {
         name: "mvm_instance_uptime_seconds",
         kind: MetricKind::Gauge,
@@ -446,6 +474,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn egress_firewall_counters_are_exposed_per_vm() {
+        let reg = InstanceMetricsRegistry::new();
+        reg.register(labels("i-1"));
+        reg.update(
+            "i-1",
+            InstanceMetricsValues {
+                egress_allowed_packets: 12,
+                egress_dropped_packets: 3,
+                egress_mandatory_deny_packets: 1,
+                ..Default::default()
+            },
+        );
+        let out = reg.prometheus_exposition();
+        assert!(out.contains("# TYPE mvm_instance_egress_dropped_packets_total counter"));
+        assert!(out.contains(
+            "mvm_instance_egress_allowed_packets_total{instance_id=\"i-1\",tenant=\"acme\",template=\"python-3.12\"} 12"
+        ));
+        assert!(out.contains(
+            "mvm_instance_egress_mandatory_deny_packets_total{instance_id=\"i-1\",tenant=\"acme\",template=\"python-3.12\"} 1"
+        ));
+    }
+
     #[test]
     fn label_escaping_handles_quotes_backslash_newline() {
         let reg = InstanceMetricsRegistry::new();
@@ -468,7 +519,7 @@ mod tests {
             reg.register(labels(id));
         }
         let out = reg.prometheus_exposition();
-        // 11 metrics × 3 VMs = 33 sample lines, each beginning with
+        // 14 metrics × 3 VMs = 42 sample lines, each beginning with
         // a metric name. Match the cpu_user counter specifically:
         let cpu_user_sample_count = out
             .lines()
@@ -442,6 +442,16 @@ pub enum LocalAuditKind {
     /// `key=value`, matching the Stage 0 kinds):
     ///   `url=<url> sha256=<64hex> pgp=<verified|none|failed> bytes=<n> outcome=<fetched|cache_revalidated>`
     VendorBlobFetched,
+
+    // --- OCI registry credentials ---
+    /// `mvmctl image login` stored or replaced a registry
+    /// credential in the secret store. Detail format:
+    ///   `registry=<host> kind=<basic|identity_token>`
+    /// Never carries the secret itself.
+    RegistryLogin,
+    /// `mvmctl image logout` removed a stored registry credential.
+    /// Detail format: `registry=<host> removed=<true|false>`
+    RegistryLogout,
 }
 
 /// A single local audit log entry.
@@ -1253,6 +1263,9 @@ mod tests {
             LocalAuditKind::FlowPolicyDecision,
             // Plan 93 Phase 3 vendored-blob supply-chain fetch.
             LocalAuditKind::VendorBlobFetched,
+            // OCI registry credentials.
+            LocalAuditKind::RegistryLogin,
+            LocalAuditKind::RegistryLogout,
         ];
         for kind in kinds {
             let json = serde_json::to_string(&kind).unwrap();
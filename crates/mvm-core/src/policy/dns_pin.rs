@@ -18,11 +18,13 @@
 //! rebinding signal and emits `LocalAuditKind::DnsPinReject`
 //! (audit kind reserved by mvm PR #275).
 //!
-//! This module is a **state-only slice**: types + tests, no
-//! resolver, no enforcement, no audit emission. Those land in
-//! mvmd Plan 51 W3 (resolver) and W4 (L7 proxy). Shipping the
-//! type now lets emission sites and resolver impls land
-//! independently without re-bumping the wire format.
+//! This module is the **state-only slice**: types + tests, no
+//! resolver, no enforcement, no audit emission. The supervisor's
+//! guest DNS endpoint (`mvm_supervisor::proxy::dns`) populates the
+//! registry from the answers it serves, emits `DnsPinSet` /
+//! `DnsPinReject`, and its L7 proxy + L4 gate enforce the pins.
+//! Keeping the type here lets mvmd consume the wire format
+//! without depending on the supervisor.
 //!
 //! ## Wire format stability
 //!
@@ -0,0 +1,471 @@
+//! Allow-list rule grammar shared by every egress enforcer.
+//!
+//! An egress allow-list entry names a host pattern and a port range.
+//! The L7 proxy's `DestinationPolicy`, the guest DNS endpoint, the
+//! TLS-interception CA and the per-VM nftables tables all parse
+//! entries through [`AllowRule`], so a pattern means the same thing
+//! at every layer.
+//!
+//! ## Host patterns
+//!
+//! | Pattern             | Matches                                   |
+//! |---------------------|-------------------------------------------|
+//! | `api.example.com`   | exactly that host                         |
+//! | `*.example.com`     | any subdomain, **not** `example.com`      |
+//! | `.example.com`      | `example.com` and any subdomain           |
+//! | `203.0.113.9`       | that IP literal                           |
+//!
+//! Matching is case-insensitive and ignores a trailing dot. A
+//! wildcard or suffix must keep at least two labels (`*.com` and
+//! `.io` are refused — they would allow a whole TLD), `*` may only
+//! stand as the entire leftmost label, and IP literals can't carry
+//! wildcards.
+//!
+//! ## Port ranges
+//!
+//! `443`, `8000-8100` (inclusive), or `*` for any port. `0` is the
+//! pre-existing "any port" spelling of the `(host, 0)` tuple form and
+//! keeps that meaning.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    #[error("empty host pattern")]
+    EmptyHost,
+    #[error("invalid host pattern {pattern:?}: {why}")]
+    InvalidHost { pattern: String, why: &'static str },
+    #[error("invalid port range {range:?}: {why}")]
+    InvalidPorts { range: String, why: &'static str },
+    #[error("expected host:ports, got {0:?}")]
+    MissingPorts(String),
+}
+
+/// A validated host pattern. See the module docs for the grammar.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HostPattern {
+    /// A single host name or IP literal (lowercased).
+    Exact(String),
+    /// `*.<suffix>` — strict subdomains of `suffix`.
+    Wildcard(String),
+    /// `.<suffix>` — `suffix` itself and its subdomains.
+    Suffix(String),
+}
+
+impl HostPattern {
+    pub fn matches(&self, host: &str) -> bool {
+        let host = normalize(host);
+        match self {
+            Self::Exact(h) => host == *h,
+            Self::Wildcard(suffix) => is_strict_subdomain(&host, suffix),
+            Self::Suffix(suffix) => host == *suffix || is_strict_subdomain(&host, suffix),
+        }
+    }
+
+    /// The DNS name whose subtree covers every host this pattern
+    /// matches; `None` for IP literals.
+    pub fn dns_subtree(&self) -> Option<&str> {
+        match self {
+            Self::Exact(h) if h.parse::<IpAddr>().is_ok() => None,
+            Self::Exact(h) | Self::Wildcard(h) | Self::Suffix(h) => Some(h),
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact(_))
+    }
+
+    /// Ordering key for "which rule explains this match": exact
+    /// hosts beat patterns, longer suffixes beat shorter ones.
+    fn specificity(&self) -> (u8, usize) {
+        match self {
+            Self::Exact(h) => (2, h.len()),
+            Self::Wildcard(s) => (1, s.len() + 1),
+            Self::Suffix(s) => (1, s.len()),
+        }
+    }
+}
+
+fn normalize(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn is_strict_subdomain(host: &str, suffix: &str) -> bool {
+    host.len() > suffix.len() + 1
+        && host.ends_with(suffix)
+        && host.as_bytes()[host.len() - suffix.len() - 1] == b'.'
+}
+
+fn validate_dns_name(pattern: &str, name: &str) -> Result<(), RuleError> {
+    let bad = |why| RuleError::InvalidHost {
+        pattern: pattern.to_string(),
+        why,
+    };
+    if name.len() > 253 {
+        return Err(bad("longer than 253 characters"));
+    }
+    for label in name.split('.') {
+        if label.is_empty() {
+            return Err(bad("empty label"));
+        }
+        if label.len() > 63 {
+            return Err(bad("label longer than 63 characters"));
+        }
+        if label.contains('*') {
+            return Err(bad("`*` is only allowed as the whole leftmost label"));
+        }
+        if !label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(bad("labels may only contain [A-Za-z0-9_-]"));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(bad("labels may not start or end with `-`"));
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for HostPattern {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pattern = s.trim();
+        if pattern.is_empty() {
+            return Err(RuleError::EmptyHost);
+        }
+        let bare = pattern.trim_start_matches('[').trim_end_matches(']');
+        if let Ok(ip) = bare.parse::<IpAddr>() {
+            return Ok(Self::Exact(ip.to_string()));
+        }
+        let lowered = normalize(pattern);
+        let (suffix, ctor): (&str, fn(String) -> Self) =
+            if let Some(rest) = lowered.strip_prefix("*.") {
+                (rest, Self::Wildcard)
+            } else if let Some(rest) = lowered.strip_prefix('.') {
+                (rest, Self::Suffix)
+            } else {
+                validate_dns_name(pattern, &lowered)?;
+                return Ok(Self::Exact(lowered));
+            };
+        validate_dns_name(pattern, suffix)?;
+        let bad = |why| RuleError::InvalidHost {
+            pattern: pattern.to_string(),
+            why,
+        };
+        if suffix.parse::<IpAddr>().is_ok() {
+            return Err(bad("IP literals can't be wildcarded"));
+        }
+        if !suffix.contains('.') {
+            return Err(bad("a wildcard or suffix needs at least two labels"));
+        }
+        Ok(ctor(suffix.to_string()))
+    }
+}
+
+impl fmt::Display for HostPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(h) => f.write_str(h),
+            Self::Wildcard(s) => write!(f, "*.{s}"),
+            Self::Suffix(s) => write!(f, ".{s}"),
+        }
+    }
+}
+
+/// Inclusive destination port range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PortRange {
+    pub lo: u16,
+    pub hi: u16,
+}
+
+impl PortRange {
+    pub const ANY: Self = Self {
+        lo: 0,
+        hi: u16::MAX,
+    };
+
+    /// One port; `0` means any port (the tuple-form wildcard).
+    pub fn single(port: u16) -> Self {
+        if port == 0 {
+            Self::ANY
+        } else {
+            Self { lo: port, hi: port }
+        }
+    }
+
+    pub fn new(lo: u16, hi: u16) -> Result<Self, RuleError> {
+        if lo > hi {
+            return Err(RuleError::InvalidPorts {
+                range: format!("{lo}-{hi}"),
+                why: "low bound above high bound",
+            });
+        }
+        if lo == 0 && hi != 0 && hi != u16::MAX {
+            return Err(RuleError::InvalidPorts {
+                range: format!("{lo}-{hi}"),
+                why: "port 0 is not a destination port",
+            });
+        }
+        Ok(if lo == 0 { Self::ANY } else { Self { lo, hi } })
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        (self.lo..=self.hi).contains(&port)
+    }
+
+    pub fn is_any(&self) -> bool {
+        *self == Self::ANY
+    }
+
+    pub fn is_single(&self) -> bool {
+        self.lo == self.hi
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "*" {
+            return Ok(Self::ANY);
+        }
+        let bad = |why| RuleError::InvalidPorts {
+            range: s.to_string(),
+            why,
+        };
+        let parse = |p: &str| {
+            p.trim()
+                .parse::<u16>()
+                .map_err(|_| bad("not a port number"))
+        };
+        match s.split_once('-') {
+            Some((lo, hi)) => Self::new(parse(lo)?, parse(hi)?),
+            None => Ok(Self::single(parse(s)?)),
+        }
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_any() {
+            f.write_str("*")
+        } else if self.is_single() {
+            write!(f, "{}", self.lo)
+        } else {
+            write!(f, "{}-{}", self.lo, self.hi)
+        }
+    }
+}
+
+/// One allow-list entry: host pattern × port range. Displays as
+/// `host:ports`, the form audit records use to name the rule that
+/// matched.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AllowRule {
+    pub host: HostPattern,
+    pub ports: PortRange,
+}
+
+impl AllowRule {
+    pub fn new(host: &str, ports: PortRange) -> Result<Self, RuleError> {
+        Ok(Self {
+            host: host.parse()?,
+            ports,
+        })
+    }
+
+    /// The `(host, port)` tuple form (`port = 0` = any port).
+    pub fn from_tuple(host: &str, port: u16) -> Result<Self, RuleError> {
+        Self::new(host, PortRange::single(port))
+    }
+
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        self.ports.contains(port) && self.host.matches(host)
+    }
+
+    /// Pick the rule that explains a match: the most specific host
+    /// pattern, then the narrowest port range.
+    pub fn best_match<'a>(
+        rules: impl IntoIterator<Item = &'a AllowRule>,
+        host: &str,
+        port: u16,
+    ) -> Option<&'a AllowRule> {
+        rules
+            .into_iter()
+            .filter(|r| r.matches(host, port))
+            .max_by_key(|r| {
+                (
+                    r.host.specificity(),
+                    std::cmp::Reverse(r.ports.hi - r.ports.lo),
+                )
+            })
+    }
+}
+
+impl FromStr for AllowRule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, ports) = s
+            .rsplit_once(':')
+            .ok_or_else(|| RuleError::MissingPorts(s.to_string()))?;
+        Ok(Self {
+            host: host.parse()?,
+            ports: ports.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for AllowRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.host {
+            HostPattern::Exact(h) if h.contains(':') => write!(f, "[{h}]:{}", self.ports),
+            host => write!(f, "{host}:{}", self.ports),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(s: &str) -> AllowRule {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn exact_hosts_match_case_insensitively_and_ignore_trailing_dot() {
+        let p: HostPattern = "API.Example.com".parse().unwrap();
+        assert!(p.matches("api.example.com"));
+        assert!(p.matches("api.example.com."));
+        assert!(!p.matches("x.api.example.com"));
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_but_not_the_apex() {
+        let p: HostPattern = "*.githubusercontent.com".parse().unwrap();
+        assert!(p.matches("raw.githubusercontent.com"));
+        assert!(p.matches("a.b.githubusercontent.com"));
+        assert!(!p.matches("githubusercontent.com"));
+        assert!(!p.matches("evilgithubusercontent.com"));
+        assert!(!p.matches("githubusercontent.com.evil.io"));
+    }
+
+    #[test]
+    fn suffix_matches_apex_and_subdomains() {
+        let p: HostPattern = ".example.com".parse().unwrap();
+        assert!(p.matches("example.com"));
+        assert!(p.matches("api.example.com"));
+        assert!(!p.matches("notexample.com"));
+    }
+
+    #[test]
+    fn unsafe_patterns_are_refused() {
+        for bad in [
+            "",
+            "*",
+            "*.com",
+            ".io",
+            "api.*.com",
+            "*api.example.com",
+            "a..b",
+            "-a.com",
+            "a b.com",
+            "*.10.0.0.1",
+        ] {
+            assert!(
+                bad.parse::<HostPattern>().is_err(),
+                "{bad:?} should be refused"
+            );
+        }
+    }
+
+    #[test]
+    fn ip_literals_are_exact() {
+        assert_eq!(
+            "203.0.113.9".parse::<HostPattern>().unwrap(),
+            HostPattern::Exact("203.0.113.9".into())
+        );
+        assert_eq!(
+            "[2001:DB8::1]".parse::<HostPattern>().unwrap(),
+            HostPattern::Exact("2001:db8::1".into())
+        );
+        assert_eq!(
+            "203.0.113.9".parse::<HostPattern>().unwrap().dns_subtree(),
+            None
+        );
+    }
+
+    #[test]
+    fn port_ranges_parse_and_validate() {
+        assert_eq!("443".parse::<PortRange>().unwrap(), PortRange::single(443));
+        assert_eq!(
+            "8000-8100".parse::<PortRange>().unwrap(),
+            PortRange { lo: 8000, hi: 8100 }
+        );
+        assert_eq!("*".parse::<PortRange>().unwrap(), PortRange::ANY);
+        assert_eq!("0".parse::<PortRange>().unwrap(), PortRange::ANY);
+        assert!("9000-8000".parse::<PortRange>().is_err());
+        assert!("0-80".parse::<PortRange>().is_err());
+        assert!("http".parse::<PortRange>().is_err());
+        assert!("70000".parse::<PortRange>().is_err());
+    }
+
+    #[test]
+    fn rules_round_trip_through_display() {
+        for s in [
+            "api.example.com:443",
+            "*.githubusercontent.com:443",
+            ".example.com:8000-8100",
+            "telemetry.example.com:*",
+            "[2001:db8::1]:443",
+        ] {
+            assert_eq!(rule(s).to_string(), s);
+        }
+        assert!("api.example.com".parse::<AllowRule>().is_err());
+    }
+
+    #[test]
+    fn tuple_form_keeps_port_zero_as_any() {
+        let r = AllowRule::from_tuple("api.example.com", 0).unwrap();
+        assert!(r.matches("api.example.com", 1));
+        assert!(r.matches("api.example.com", 65535));
+        assert_eq!(r.to_string(), "api.example.com:*");
+    }
+
+    #[test]
+    fn best_match_prefers_exact_hosts_then_longer_suffixes_then_narrow_ports() {
+        let rules = [
+            rule(".example.com:*"),
+            rule("*.api.example.com:443"),
+            rule("v1.api.example.com:400-500"),
+            rule("v1.api.example.com:443"),
+        ];
+        let explain = |h, p| AllowRule::best_match(&rules, h, p).map(ToString::to_string);
+        assert_eq!(
+            explain("v1.api.example.com", 443).as_deref(),
+            Some("v1.api.example.com:443")
+        );
+        assert_eq!(
+            explain("v1.api.example.com", 450).as_deref(),
+            Some("v1.api.example.com:400-500")
+        );
+        assert_eq!(
+            explain("v2.api.example.com", 443).as_deref(),
+            Some("*.api.example.com:443")
+        );
+        assert_eq!(
+            explain("v2.api.example.com", 80).as_deref(),
+            Some(".example.com:*")
+        );
+        assert_eq!(explain("example.org", 443), None);
+    }
+}
@@ -5,6 +5,10 @@ pub mod audit;
 /// admission-time pin data model. State-only slice (types +
 /// tests, no resolver / no enforcement / no audit emission).
 pub mod dns_pin;
+/// Allow-list grammar: host patterns, port ranges, rule matching.
+pub mod egress_rule;
 pub mod network_policy;
+/// Per-VM nftables tables: atomic install, teardown, counters.
+pub mod nft;
 pub mod secret_binding;
 pub mod security;
@@ -2,11 +2,20 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
-/// A host:port pair for network allowlist rules.
+use super::egress_rule::{AllowRule, PortRange, RuleError};
+
+/// A host:port pair for network allowlist rules. `host` may be a
+/// wildcard (`*.example.com`) or suffix (`.example.com`) pattern and
+/// the port may be a range; see [`super::egress_rule`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct HostPort {
     pub host: String,
     pub port: u16,
+    /// Inclusive upper bound when the rule covers `port..=port_end`.
+    /// `None` for a single port; omitted from the serialised form so
+    /// existing policies round-trip unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_end: Option<u16>,
 }
 
 impl HostPort {
@@ -14,30 +23,60 @@ impl HostPort {
         Self {
             host: host.into(),
             port,
+            port_end: None,
+        }
+    }
+
+    /// A rule covering the inclusive range `lo..=hi`.
+    pub fn range(host: impl Into<String>, lo: u16, hi: u16) -> Self {
+        Self {
+            host: host.into(),
+            port: lo,
+            port_end: Some(hi),
+        }
+    }
+
+    /// The port range this rule covers. A bare `port = 0` is "any
+    /// port", as in the egress bundle's `(host, 0)` form.
+    pub fn ports(&self) -> Result<PortRange, RuleError> {
+        match self.port_end {
+            Some(hi) => PortRange::new(self.port, hi),
+            None => Ok(PortRange::single(self.port)),
         }
     }
+
+    /// Validate into the matcher the enforcers share.
+    pub fn allow_rule(&self) -> Result<AllowRule, RuleError> {
+        AllowRule::new(&self.host, self.ports()?)
+    }
 }
 
 impl fmt::Display for HostPort {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.host, self.port)
+        match self.port_end {
+            Some(hi) => write!(f, "{}:{}-{hi}", self.host, self.port),
+            None => write!(f, "{}:{}", self.host, self.port),
+        }
     }
 }
 
 impl FromStr for HostPort {
     type Err = anyhow::Error;
 
+    /// `host:port`, `host:lo-hi` or `host:*`, with `host` validated
+    /// as an exact name, IP literal or pattern.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (host, port) = s
-            .rsplit_once(':')
-            .ok_or_else(|| anyhow::anyhow!("expected host:port, got {:?}", s))?;
-        if host.is_empty() {
-            anyhow::bail!("host cannot be empty in {:?}", s);
-        }
-        let port: u16 = port
+        let rule: AllowRule = s
             .parse()
-            .map_err(|_| anyhow::anyhow!("invalid port in {:?}", s))?;
-        Ok(Self::new(host, port))
+            .map_err(|e| anyhow::anyhow!("invalid allow rule {:?}: {e}", s))?;
+        let host = s.rsplit_once(':').map_or(s, |(host, _)| host).to_string();
+        Ok(if rule.ports.is_any() {
+            Self::new(host, 0)
+        } else if rule.ports.is_single() {
+            Self::new(host, rule.ports.lo)
+        } else {
+            Self::range(host, rule.ports.lo, rule.ports.hi)
+        })
     }
 }
 
@@ -79,7 +118,7 @@ impl NetworkPreset {
         }
     }
 
-    /// Whether this preset means "allow everything" (no iptables filtering).
+    /// Whether this preset means "allow everything" (no netfilter filtering).
     pub fn is_unrestricted(&self) -> bool {
         matches!(self, Self::Unrestricted)
     }
@@ -136,11 +175,11 @@ pub enum EgressMode {
     /// unrestricted policy.
     #[default]
     Open,
-    /// L3 only: iptables `FORWARD` allowlist on the bridge. Catches
+    /// L3 only: nftables allowlist on the bridge. Catches
     /// raw-IP exfil; doesn't catch DNS rotation or SNI/Host abuse
     /// over a permitted destination.
     L3Only,
-    /// L3 + L7 stack: iptables allowlist plus an HTTPS proxy on the
+    /// L3 + L7 stack: nftables allowlist plus an HTTPS proxy on the
     /// host that enforces SNI for HTTPS (CONNECT) and Host header
     /// for HTTP. Plan 34 / ADR-004 §"L7" covers the runtime impl;
     /// today this variant returns "egress proxy not implemented" at
@@ -285,78 +324,6 @@ impl NetworkPolicy {
             Self::AllowList { rules, .. } => Some(rules.clone()),
         }
     }
-
-    /// Generate the iptables shell script fragment for this policy.
-    /// Returns `None` if unrestricted (no rules needed).
-    ///
-    /// The script assumes it runs inside the Lima VM with sudo and that
-    /// the bridge device and FORWARD chain are already set up.
-    pub fn iptables_script(&self, bridge_dev: &str, guest_ip: &str) -> Option<String> {
-        let rules = self.resolve_rules()?;
-
-        let mut script = String::new();
-        script.push_str(&format!(
-            "# Network policy: drop all outbound from {} except allowed hosts\n",
-            guest_ip
-        ));
-
-        // Drop all FORWARD from this guest by default
-        script.push_str(&format!(
-            "sudo iptables -I FORWARD -i {br} -s {ip} -j DROP\n",
-            br = bridge_dev,
-            ip = guest_ip,
-        ));
-
-        // Allow ESTABLISHED/RELATED (return traffic)
-        script.push_str(&format!(
-            "sudo iptables -I FORWARD -i {br} -s {ip} -m state --state ESTABLISHED,RELATED -j ACCEPT\n",
-            br = bridge_dev,
-            ip = guest_ip,
-        ));
-
-        // Allow DNS (UDP + TCP port 53) so domain resolution works
-        script.push_str(&format!(
-            "sudo iptables -I FORWARD -i {br} -s {ip} -p udp --dport 53 -j ACCEPT\n",
-            br = bridge_dev,
-            ip = guest_ip,
-        ));
-        script.push_str(&format!(
-            "sudo iptables -I FORWARD -i {br} -s {ip} -p tcp --dport 53 -j ACCEPT\n",
-            br = bridge_dev,
-            ip = guest_ip,
-        ));
-
-        // Allow each specific host:port
-        for rule in &rules {
-            script.push_str(&format!(
-                "sudo iptables -I FORWARD -i {br} -s {ip} -d {host} -p tcp --dport {port} -j ACCEPT\n",
-                br = bridge_dev,
-                ip = guest_ip,
-                host = rule.host,
-                port = rule.port,
-            ));
-        }
-
-        Some(script)
-    }
-
-    /// Generate the iptables cleanup script for this policy.
-    /// Returns `None` if unrestricted (nothing to clean up).
-    pub fn iptables_cleanup_script(&self, bridge_dev: &str, guest_ip: &str) -> Option<String> {
-        if self.is_unrestricted() {
-            return None;
-        }
-
-        Some(format!(
-            "# Clean up network policy rules for {ip}\n\
-             while sudo iptables -D FORWARD -i {br} -s {ip} -j DROP 2>/dev/null; do :; done\n\
-             while sudo iptables -D FORWARD -i {br} -s {ip} -m state --state ESTABLISHED,RELATED -j ACCEPT 2>/dev/null; do :; done\n\
-             while sudo iptables -D FORWARD -i {br} -s {ip} -p udp --dport 53 -j ACCEPT 2>/dev/null; do :; done\n\
-             while sudo iptables -D FORWARD -i {br} -s {ip} -p tcp --dport 53 -j ACCEPT 2>/dev/null; do :; done\n",
-            br = bridge_dev,
-            ip = guest_ip,
-        ))
-    }
 }
 
 impl Default for NetworkPolicy {
@@ -445,6 +412,15 @@ fn agent_rules() -> Vec<HostPort> {
 ///   services. VM-level isolation should already make these
 ///   unreachable; the rule is a belt-and-braces guard against a
 ///   misconfigured bridge.
+/// - **IPv6 cloud metadata** (`fd00:ec2::254/128`) and **IPv6
+///   unique-local** (`fc00::/7`): AWS serves IMDS over IPv6 at a
+///   ULA address, and the providers that hand a guest IPv6 put
+///   their internal services in ULA space too. Same `/128`-inside-
+///   the-range layout as the IPv4 metadata entry.
+/// - **IPv4-mapped IPv6** (`::ffff:0:0/96`): never a legitimate
+///   on-the-wire destination; denying it closes the "spell
+///   `169.254.169.254` as `::ffff:169.254.169.254`" bypass for any
+///   enforcer that only matches the IPv4 entries.
 ///
 /// Deliberately **NOT** in the list:
 ///
@@ -456,13 +432,11 @@ fn agent_rules() -> Vec<HostPort> {
 /// - Unspecified (`0.0.0.0/32`, `::/128`) — doesn't route.
 /// - Multicast (`224.0.0.0/4`, `ff00::/8`) — doesn't reach the
 ///   public internet; out of scope for egress policy.
-/// - IPv6 ULA (`fc00::/7`) — analogous to RFC1918 above.
 ///
-/// Future enforcers (iptables/nft on Linux, the L4Policy
-/// evaluator, the L7 egress proxy) should consult this list
-/// *before* the user's allow-list. The plan 74 W2 follow-up
-/// slice wires this into the iptables FORWARD setup; this PR
-/// ships the data model only.
+/// Enforcers consult this list *before* the user's allow-list:
+/// the per-VM nftables table ([`super::nft::render_policy_table`])
+/// drops both families ahead of any allow rule, and the L4/L7
+/// proxies refuse the same ranges per flow.
 pub const MANDATORY_DENY_RANGES: &[&str] = &[
     // Cloud metadata first — the most consequential entry. A
     // future operator who edits this list should think twice
@@ -473,6 +447,9 @@ pub const MANDATORY_DENY_RANGES: &[&str] = &[
     "127.0.0.0/8",
     "::1/128",
     "fe80::/10",
+    "fd00:ec2::254/128",
+    "fc00::/7",
+    "::ffff:0:0/96",
 ];
 
 /// Parse [`MANDATORY_DENY_RANGES`] into typed [`ipnet::IpNet`]s.
@@ -499,7 +476,7 @@ pub fn mandatory_deny_ranges() -> Vec<ipnet::IpNet> {
 
 /// Returns `true` if `ip` falls within any of the mandatory
 /// deny ranges. The defense-in-depth check every egress
-/// enforcer (iptables setup, L4Policy::evaluate, the L7 proxy)
+/// enforcer (the nftables table, L4Policy::evaluate, the L7 proxy)
 /// should run *before* consulting the user's allow-list — a hit
 /// here means the destination is forbidden full stop, no matter
 /// how permissive the allow-list is.
@@ -512,72 +489,6 @@ pub fn is_mandatory_deny(ip: std::net::IpAddr) -> bool {
     mandatory_deny_ranges().iter().any(|net| net.contains(&ip))
 }
 
-/// Emit the iptables shell fragment that drops outbound from
-/// `guest_ip` on `bridge_dev` to every IPv4 entry in
-/// [`MANDATORY_DENY_RANGES`]. Always returns a non-empty
-/// script — the deny posture applies regardless of the user's
-/// [`NetworkPolicy`].
-///
-/// **Order matters.** The script uses `iptables -I FORWARD`,
-/// which inserts at chain position 1, so a rule emitted *later*
-/// in the script ends up *earlier* in the chain (and is checked
-/// first by the kernel). Callers run this script *after* a
-/// policy's [`NetworkPolicy::iptables_script`] output so the
-/// deny rules end up at the TOP of FORWARD — they fire before
-/// any per-policy allow rule. Without that ordering, a
-/// `--network-preset unrestricted` workload (no allow-list,
-/// nothing scoped to it in FORWARD today) would still hit the
-/// metadata endpoint.
-///
-/// IPv6 entries from the const are intentionally skipped here —
-/// today's bridge wiring is IPv4-only, so a v6 packet from the
-/// guest doesn't have a route to leave anyway. The v6
-/// enforcement lands when the bridge gains v6.
-pub fn mandatory_deny_iptables_script(bridge_dev: &str, guest_ip: &str) -> String {
-    let mut script = String::from(
-        "# Plan 74 W2 §item 4 — mandatory deny ranges (cloud metadata,\n\
-         # link-local, CGNAT, host loopback). These rules sit at the top\n\
-         # of FORWARD via `-I` so they're checked before any per-policy\n\
-         # allow rule — even an `unrestricted` workload cannot reach\n\
-         # 169.254.169.254 (AWS IMDS / GCP / Azure metadata).\n",
-    );
-    for net in mandatory_deny_ranges() {
-        if !net.network().is_ipv4() {
-            continue;
-        }
-        script.push_str(&format!(
-            "sudo iptables -I FORWARD -i {br} -s {ip} -d {cidr} -j DROP\n",
-            br = bridge_dev,
-            ip = guest_ip,
-            cidr = net,
-        ));
-    }
-    script
-}
-
-/// Cleanup counterpart of [`mandatory_deny_iptables_script`].
-/// `iptables -D` removes one matching rule; the
-/// `while … 2>/dev/null; do :; done` form drains *all* matching
-/// rules so a previously-leaked duplicate (from a prior crashed
-/// `apply_network_policy`) doesn't strand a deny rule on the
-/// chain. Mirrors the pattern used by
-/// [`NetworkPolicy::iptables_cleanup_script`].
-pub fn mandatory_deny_iptables_cleanup_script(bridge_dev: &str, guest_ip: &str) -> String {
-    let mut script = String::from("# Clean up mandatory-deny rules\n");
-    for net in mandatory_deny_ranges() {
-        if !net.network().is_ipv4() {
-            continue;
-        }
-        script.push_str(&format!(
-            "while sudo iptables -D FORWARD -i {br} -s {ip} -d {cidr} -j DROP 2>/dev/null; do :; done\n",
-            br = bridge_dev,
-            ip = guest_ip,
-            cidr = net,
-        ));
-    }
-    script
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -604,6 +515,42 @@ mod tests {
         assert!("github.com:abc".parse::<HostPort>().is_err());
     }
 
+    #[test]
+    fn host_port_parse_patterns_and_ranges() {
+        let hp: HostPort = "*.githubusercontent.com:443".parse().unwrap();
+        assert_eq!(hp, HostPort::new("*.githubusercontent.com", 443));
+        assert!(
+            hp.allow_rule()
+                .unwrap()
+                .matches("raw.githubusercontent.com", 443)
+        );
+
+        let hp: HostPort = ".example.com:8000-8100".parse().unwrap();
+        assert_eq!(hp, HostPort::range(".example.com", 8000, 8100));
+        assert_eq!(hp.to_string(), ".example.com:8000-8100");
+        assert!(hp.allow_rule().unwrap().matches("example.com", 8050));
+
+        let hp: HostPort = "telemetry.example.com:*".parse().unwrap();
+        assert_eq!(hp.port, 0);
+        assert!(hp.ports().unwrap().is_any());
+    }
+
+    #[test]
+    fn host_port_parse_rejects_unsafe_patterns() {
+        assert!("*.com:443".parse::<HostPort>().is_err());
+        assert!("api.*.example.com:443".parse::<HostPort>().is_err());
+        assert!("example.com:9000-8000".parse::<HostPort>().is_err());
+    }
+
+    #[test]
+    fn host_port_range_serde_omits_absent_end() {
+        let json = serde_json::to_string(&HostPort::new("github.com", 443)).unwrap();
+        assert!(!json.contains("port_end"));
+        let hp = HostPort::range("grpc.example.com", 50051, 50059);
+        let parsed: HostPort = serde_json::from_str(&serde_json::to_string(&hp).unwrap()).unwrap();
+        assert_eq!(parsed, hp);
+    }
+
     #[test]
     fn host_port_display() {
         let hp = HostPort::new("github.com", 443);
@@ -830,64 +777,6 @@ mod tests {
         assert_eq!(parsed, policy);
     }
 
-    #[test]
-    fn iptables_script_unrestricted_is_none() {
-        let policy = NetworkPolicy::unrestricted();
-        assert!(policy.iptables_script("br-mvm", "172.16.0.2").is_none());
-    }
-
-    #[test]
-    fn iptables_script_deny_all_has_drop_no_host_rules() {
-        let policy = NetworkPolicy::deny_all();
-        let script = policy.iptables_script("br-mvm", "172.16.0.2").unwrap();
-        assert!(script.contains("-j DROP"));
-        assert!(script.contains("--dport 53")); // DNS allowed
-        // No host-specific ACCEPT rules (only DNS + ESTABLISHED)
-        let accept_lines: Vec<&str> = script
-            .lines()
-            .filter(|l| {
-                l.contains("-j ACCEPT") && !l.contains("--dport 53") && !l.contains("ESTABLISHED")
-            })
-            .collect();
-        assert!(
-            accept_lines.is_empty(),
-            "deny-all should have no host ACCEPT rules"
-        );
-    }
-
-    #[test]
-    fn iptables_script_allow_list_has_host_rules() {
-        let policy = NetworkPolicy::allow_list(vec![
-            HostPort::new("github.com", 443),
-            HostPort::new("api.openai.com", 443),
-        ]);
-        let script = policy.iptables_script("br-mvm", "172.16.0.3").unwrap();
-        assert!(script.contains("-d github.com"));
-        assert!(script.contains("-d api.openai.com"));
-        assert!(script.contains("--dport 443"));
-        assert!(script.contains("-s 172.16.0.3"));
-        assert!(script.contains("-i br-mvm"));
-    }
-
-    #[test]
-    fn iptables_cleanup_unrestricted_is_none() {
-        let policy = NetworkPolicy::unrestricted();
-        assert!(
-            policy
-                .iptables_cleanup_script("br-mvm", "172.16.0.2")
-                .is_none()
-        );
-    }
-
-    #[test]
-    fn iptables_cleanup_deny_all_has_commands() {
-        let policy = NetworkPolicy::deny_all();
-        let script = policy
-            .iptables_cleanup_script("br-mvm", "172.16.0.2")
-            .unwrap();
-        assert!(script.contains("iptables -D FORWARD"));
-    }
-
     // --- Plan 34 / ADR-006 egress_mode enrichment ---
 
     #[test]
@@ -1018,6 +907,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ipv6_metadata_ula_and_v4_mapped_are_denied() {
+        for addr in [
+            "fd00:ec2::254",          // AWS IMDS over IPv6
+            "fd12:3456:789a::1",      // unique-local
+            "fc00::1",                // unique-local, lower half of /7
+            "::ffff:169.254.169.254", // IMDS spelled as IPv4-mapped
+            "::ffff:8.8.8.8",         // mapped public — still never on the wire
+        ] {
+            let ip: std::net::IpAddr = addr.parse().unwrap();
+            assert!(is_mandatory_deny(ip), "IPv6 {addr} must be denied");
+        }
+    }
+
     #[test]
     fn cgnat_range_is_denied() {
         // 100.64.0.0/10 = 100.64.0.0 through 100.127.255.255.
@@ -1103,139 +1006,4 @@ mod tests {
              list should see it before anything else"
         );
     }
-
-    // =====================================================================
-    // Plan 74 W2 — iptables wiring for mandatory deny ranges
-    // =====================================================================
-
-    /// The most consequential assertion: the rendered script
-    /// must DROP traffic destined for the cloud metadata
-    /// endpoint. If this fails, AWS IMDS / GCP / Azure metadata
-    /// is reachable from the guest — defeating the entire
-    /// purpose of this slice.
-    #[test]
-    fn mandatory_deny_iptables_script_drops_cloud_metadata() {
-        let script = mandatory_deny_iptables_script("br-mvm", "172.16.0.2");
-        assert!(
-            script.contains("-d 169.254.169.254/32 -j DROP"),
-            "script must drop cloud metadata endpoint; got: {script}"
-        );
-    }
-
-    #[test]
-    fn mandatory_deny_iptables_script_scopes_to_guest_source() {
-        let script = mandatory_deny_iptables_script("br-mvm", "172.16.0.2");
-        // Every line that adds a rule must be scoped to the
-        // guest's source IP — otherwise a sibling guest's
-        // traffic could be affected by cleanup of this one.
-        for line in script.lines().filter(|l| l.contains("iptables -I")) {
-            assert!(
-                line.contains("-s 172.16.0.2"),
-                "deny rule line must scope to the guest IP: {line}"
-            );
-        }
-    }
-
-    #[test]
-    fn mandatory_deny_iptables_script_uses_minus_i_for_top_of_chain() {
-        // `-I FORWARD` inserts at chain position 1 (top). A
-        // future PR that switches to `-A` would silently bury
-        // the deny rules below any pre-existing allow rules —
-        // catastrophic. Catch the regression at the unit level.
-        let script = mandatory_deny_iptables_script("br-mvm", "172.16.0.2");
-        for line in script.lines().filter(|l| l.contains("iptables")) {
-            assert!(
-                line.contains("-I FORWARD"),
-                "rule must use `-I FORWARD` (top-insert); got: {line}"
-            );
-            assert!(
-                !line.contains("-A FORWARD"),
-                "rule must NOT use `-A FORWARD` (would bury below allow rules): {line}"
-            );
-        }
-    }
-
-    #[test]
-    fn mandatory_deny_iptables_script_skips_ipv6_entries() {
-        let script = mandatory_deny_iptables_script("br-mvm", "172.16.0.2");
-        // v6 enforcement lands when the bridge gains v6
-        // routing; until then the v6 deny rules belong in a
-        // future PR, not in this script.
-        assert!(
-            !script.contains("ip6tables"),
-            "ip6tables must not appear; v6 wiring is deferred. got: {script}"
-        );
-        assert!(
-            !script.contains("::1/128"),
-            "IPv6 entries must not appear in v4 script: {script}"
-        );
-        assert!(
-            !script.contains("fe80::"),
-            "IPv6 entries must not appear in v4 script: {script}"
-        );
-    }
-
-    #[test]
-    fn mandatory_deny_iptables_script_covers_every_ipv4_const_entry() {
-        let script = mandatory_deny_iptables_script("br-mvm", "172.16.0.2");
-        for raw in MANDATORY_DENY_RANGES {
-            let net: ipnet::IpNet = raw.parse().unwrap();
-            if !net.network().is_ipv4() {
-                continue;
-            }
-            assert!(
-                script.contains(&format!("-d {net} -j DROP")),
-                "expected a DROP for {net} but it's missing from script: {script}"
-            );
-        }
-    }
-
-    /// Apply emits a DROP per IPv4 entry; cleanup must emit a
-    /// matching `-D` for every one of them. Drift between the
-    /// two scripts strands stale rules on the bridge after a
-    /// VM teardown.
-    #[test]
-    fn mandatory_deny_cleanup_matches_apply_line_for_line() {
-        let apply = mandatory_deny_iptables_script("br-mvm", "172.16.0.2");
-        let cleanup = mandatory_deny_iptables_cleanup_script("br-mvm", "172.16.0.2");
-        // For every `-I` rule in apply, expect a `-D` rule in
-        // cleanup with the same `-d <cidr>` token.
-        let apply_cidrs: Vec<&str> = apply
-            .lines()
-            .filter(|l| l.contains("iptables -I"))
-            .filter_map(|l| l.split("-d ").nth(1))
-            .filter_map(|tail| tail.split(' ').next())
-            .collect();
-        let cleanup_cidrs: Vec<&str> = cleanup
-            .lines()
-            .filter(|l| l.contains("iptables -D"))
-            .filter_map(|l| l.split("-d ").nth(1))
-            .filter_map(|tail| tail.split(' ').next())
-            .collect();
-        assert_eq!(
-            apply_cidrs, cleanup_cidrs,
-            "apply and cleanup must reference identical CIDRs in identical order"
-        );
-        assert!(!apply_cidrs.is_empty(), "apply must emit at least one rule");
-    }
-
-    #[test]
-    fn mandatory_deny_cleanup_uses_drain_loop() {
-        let cleanup = mandatory_deny_iptables_cleanup_script("br-mvm", "172.16.0.2");
-        // A single `-D` removes exactly one matching rule. The
-        // `while … do :; done` form drains all matches so a
-        // leaked duplicate (from a prior crashed apply) doesn't
-        // strand a deny rule. Matches the pattern used by the
-        // pre-W2 cleanup script in `mvm-backend::network`.
-        for line in cleanup.lines().filter(|l| l.contains("iptables -D")) {
-            assert!(
-                line.starts_with("while sudo "),
-                "cleanup must use `while sudo … do :; done` drain loop: {line}"
-            );
-            assert!(
-                line.ends_with("done"),
-                "cleanup must close the `while … done` block: {line}"
-            );
-        }
-    }
 }
@@ -0,0 +1,650 @@
+//! Per-VM nftables rendering — the one netfilter path for egress
+//! enforcement.
+//!
+//! Every enforcer that programs the host's netfilter (the Lima/
+//! Firecracker bridge policy in `mvm-backend`, the supervisor's TAP
+//! firewall in `mvm-supervisor::firewall::linux_nft`) renders its
+//! rules through this module. Each VM owns its own `inet` table, so:
+//!
+//! - **IPv4 and IPv6** are covered by one ruleset — the `inet`
+//!   family hooks both stacks, and the guest's v6 traffic is matched
+//!   by its MAC (the bridge hands out no guest v6 address to key on).
+//! - **Install is atomic.** [`replace_table_script`] declares the
+//!   table, deletes it and re-creates it in one `nft -f` transaction:
+//!   the kernel either swaps in the whole new ruleset or keeps the
+//!   old one. There is never a window with half the rules loaded.
+//! - **Teardown is scoped and idempotent.** [`delete_table_script`]
+//!   removes only the VM's table and succeeds even if it is already
+//!   gone (a second stop, or a crashed start).
+//! - **Decisions are counted.** Rules carry named counters
+//!   ([`COUNTER_ALLOWED`], [`COUNTER_DROPPED`], …) that
+//!   [`parse_counters`] reads back from `nft -j list counters` for
+//!   the per-VM metrics.
+//!
+//! Identifiers are slug-validated (`[A-Za-z0-9_-]`) before they are
+//! interpolated: `nft -f -` reads its script verbatim, so a crafted
+//! VM name must never reach it.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::egress_rule::HostPattern;
+use super::network_policy::{NetworkPolicy, mandatory_deny_ranges};
+
+/// Named counter bumped by new flows the policy allows.
+pub const COUNTER_ALLOWED: &str = "allowed";
+/// Named counter bumped by flows the policy drops (not counting
+/// the mandatory-deny ranges, which have their own counter).
+pub const COUNTER_DROPPED: &str = "dropped";
+/// Named counter bumped by DNS queries to the guest's DNS endpoint
+/// ([`GuestNftSpec::dns_endpoint`]), the one resolver a restricted
+/// guest may reach.
+pub const COUNTER_DNS: &str = "dns";
+/// Named counter bumped by packets to [`MANDATORY_DENY_RANGES`].
+///
+/// [`MANDATORY_DENY_RANGES`]: super::network_policy::MANDATORY_DENY_RANGES
+pub const COUNTER_MANDATORY_DENY: &str = "mandatory_deny";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NftRenderError {
+    #[error("invalid nft identifier {field}: {value:?} (only [A-Za-z0-9_-] permitted)")]
+    InvalidIdent { field: &'static str, value: String },
+    #[error("invalid {field}: {value:?}")]
+    InvalidAddress { field: &'static str, value: String },
+    #[error("unparseable nft counter listing: {0}")]
+    Counters(String),
+}
+
+/// Refuse anything outside `[A-Za-z0-9_-]+`.
+pub fn validate_ident(field: &'static str, value: &str) -> Result<(), NftRenderError> {
+    if value.is_empty()
+        || !value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(NftRenderError::InvalidIdent {
+            field,
+            value: value.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Atomic install: declaring the table first makes the `delete`
+/// valid on a fresh host, and the whole script is one transaction.
+/// `body` is the table's contents (sets, counters, chains).
+pub fn replace_table_script(table: &str, body: &str) -> Result<String, NftRenderError> {
+    validate_ident("table", table)?;
+    Ok(format!(
+        "table inet {table}\n\
+         delete table inet {table}\n\
+         table inet {table} {{\n{body}}}\n"
+    ))
+}
+
+/// Idempotent teardown of one table.
+pub fn delete_table_script(table: &str) -> Result<String, NftRenderError> {
+    validate_ident("table", table)?;
+    Ok(format!("table inet {table}\ndelete table inet {table}\n"))
+}
+
+/// `nft` arguments that dump a table's named counters as JSON, in
+/// the shape [`parse_counters`] reads.
+pub fn list_counters_args(table: &str) -> Result<Vec<String>, NftRenderError> {
+    validate_ident("table", table)?;
+    Ok(["-j", "list", "counters", "table", "inet", table]
+        .into_iter()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Declare named counters inside a table body.
+pub fn counter_declarations(names: &[&str]) -> String {
+    let mut out = String::new();
+    for name in names {
+        let _ = writeln!(out, "\tcounter {name} {{\n\t}}");
+    }
+    out
+}
+
+/// The table name the bridge policy uses for `vm_name`.
+pub fn policy_table_name(vm_name: &str) -> Result<String, NftRenderError> {
+    validate_ident("vm_name", vm_name)?;
+    Ok(format!("mvm_policy_{vm_name}"))
+}
+
+/// Where a VM's egress enters netfilter on the bridge host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestNftSpec {
+    pub vm_name: String,
+    /// Bridge the guest's TAP is enslaved to (`br-mvm`).
+    pub bridge_dev: String,
+    pub guest_ipv4: Ipv4Addr,
+    /// Guest MAC; keys the IPv6 match. `None` leaves v6 to the
+    /// host's defaults (there is no guest v6 address to match).
+    pub guest_mac: Option<String>,
+    /// The nameserver restricted policies let the guest query. `None`
+    /// drops DNS like any other unlisted flow.
+    pub dns_endpoint: Option<IpAddr>,
+}
+
+impl GuestNftSpec {
+    pub fn new(
+        vm_name: impl Into<String>,
+        bridge_dev: impl Into<String>,
+        guest_ipv4: &str,
+        guest_mac: Option<&str>,
+    ) -> Result<Self, NftRenderError> {
+        let spec = Self {
+            vm_name: vm_name.into(),
+            bridge_dev: bridge_dev.into(),
+            guest_ipv4: guest_ipv4
+                .parse()
+                .map_err(|_| NftRenderError::InvalidAddress {
+                    field: "guest_ip",
+                    value: guest_ipv4.to_string(),
+                })?,
+            guest_mac: guest_mac.map(str::to_ascii_lowercase),
+            dns_endpoint: None,
+        };
+        validate_ident("vm_name", &spec.vm_name)?;
+        validate_ident("bridge_dev", &spec.bridge_dev)?;
+        if let Some(mac) = &spec.guest_mac
+            && !is_mac(mac)
+        {
+            return Err(NftRenderError::InvalidAddress {
+                field: "guest_mac",
+                value: mac.clone(),
+            });
+        }
+        Ok(spec)
+    }
+
+    /// Permit DNS (TCP and UDP port 53) to `addr` only.
+    pub fn with_dns_endpoint(mut self, addr: IpAddr) -> Self {
+        self.dns_endpoint = Some(addr);
+        self
+    }
+}
+
+fn is_mac(s: &str) -> bool {
+    let parts: Vec<&str> = s.split(':').collect();
+    parts.len() == 6
+        && parts
+            .iter()
+            .all(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Render the full atomic-install script for a VM's egress policy.
+///
+/// The mandatory-deny ranges are dropped first for every policy,
+/// `unrestricted` included. Restricted policies then allow return
+/// traffic, DNS to the spec's `dns_endpoint`, and each allow-listed
+/// `host:ports` (TCP), and drop
+/// the rest. `resolve` maps an allow-list hostname to its addresses
+/// at install time — the same point-in-time resolution `iptables -d
+/// <host>` did; IP literals bypass it. A name that resolves to
+/// nothing allows nothing and is noted in the ruleset. Wildcard and
+/// suffix patterns name an open-ended set of hosts no install-time
+/// lookup can enumerate, so they are noted and left to the L7 proxy
+/// and guest DNS endpoint; invalid entries are noted and allow
+/// nothing.
+pub fn render_policy_table(
+    spec: &GuestNftSpec,
+    policy: &NetworkPolicy,
+    mut resolve: impl FnMut(&str) -> Vec<IpAddr>,
+) -> Result<String, NftRenderError> {
+    let table = policy_table_name(&spec.vm_name)?;
+    let mut body = counter_declarations(&[
+        COUNTER_ALLOWED,
+        COUNTER_DROPPED,
+        COUNTER_DNS,
+        COUNTER_MANDATORY_DENY,
+    ]);
+
+    let (deny_v4, deny_v6): (Vec<_>, Vec<_>) = mandatory_deny_ranges()
+        .into_iter()
+        .partition(|net| net.network().is_ipv4());
+    push_interval_set(&mut body, "mandatory_deny_v4", "ipv4_addr", &deny_v4);
+    push_interval_set(&mut body, "mandatory_deny_v6", "ipv6_addr", &deny_v6);
+
+    let rules = policy.resolve_rules();
+    let mut skipped = Vec::new();
+    if let Some(rules) = &rules {
+        let mut allow_v4 = BTreeSet::new();
+        let mut allow_v6 = BTreeSet::new();
+        for rule in rules {
+            let allow = match rule.allow_rule() {
+                Ok(allow) => allow,
+                // Debug-escaped: an unvalidated host must not be able
+                // to end the comment line and inject rules.
+                Err(e) => {
+                    skipped.push(format!(
+                        "{:?}: invalid ({e}), not allowed",
+                        rule.to_string()
+                    ));
+                    continue;
+                }
+            };
+            let HostPattern::Exact(host) = &allow.host else {
+                skipped.push(format!("{rule}: host pattern, enforced at L7 and DNS only"));
+                continue;
+            };
+            let addrs = match host.parse::<IpAddr>() {
+                Ok(ip) => vec![ip],
+                Err(_) => resolve(host),
+            };
+            if addrs.is_empty() {
+                skipped.push(format!("{rule}: no addresses resolved, not allowed"));
+            }
+            let ports = if allow.ports.is_single() {
+                allow.ports.lo.to_string()
+            } else {
+                format!("{}-{}", allow.ports.lo, allow.ports.hi)
+            };
+            for ip in addrs {
+                match ip {
+                    IpAddr::V4(v4) => allow_v4.insert(format!("{v4} . {ports}")),
+                    IpAddr::V6(v6) => allow_v6.insert(format!("{v6} . {ports}")),
+                };
+            }
+        }
+        push_set(&mut body, "allow_v4", "ipv4_addr . inet_service", &allow_v4);
+        push_set(&mut body, "allow_v6", "ipv6_addr . inet_service", &allow_v6);
+    }
+
+    let br = &spec.bridge_dev;
+    body.push_str(
+        "\tchain forward {\n\
+         \t\ttype filter hook forward priority 0; policy accept;\n",
+    );
+    let _ = writeln!(
+        body,
+        "\t\tiifname \"{br}\" ip saddr {} jump guest_egress",
+        spec.guest_ipv4
+    );
+    if let Some(mac) = &spec.guest_mac {
+        let _ = writeln!(
+            body,
+            "\t\tiifname \"{br}\" ether saddr {mac} meta nfproto ipv6 jump guest_egress"
+        );
+    }
+    body.push_str("\t}\n");
+
+    body.push_str("\tchain guest_egress {\n");
+    let _ = writeln!(
+        body,
+        "\t\tip daddr @mandatory_deny_v4 counter name \"{COUNTER_MANDATORY_DENY}\" drop"
+    );
+    let _ = writeln!(
+        body,
+        "\t\tip6 daddr @mandatory_deny_v6 counter name \"{COUNTER_MANDATORY_DENY}\" drop"
+    );
+    if rules.is_some() {
+        body.push_str("\t\tct state established,related accept\n");
+        match spec.dns_endpoint {
+            Some(IpAddr::V4(addr)) => {
+                let _ = writeln!(
+                    body,
+                    "\t\tip daddr {addr} meta l4proto {{ tcp, udp }} th dport 53 counter name \"{COUNTER_DNS}\" accept"
+                );
+            }
+            Some(IpAddr::V6(addr)) => {
+                let _ = writeln!(
+                    body,
+                    "\t\tip6 daddr {addr} meta l4proto {{ tcp, udp }} th dport 53 counter name \"{COUNTER_DNS}\" accept"
+                );
+            }
+            None => body.push_str("\t\t# no DNS endpoint: DNS is not allowed\n"),
+        }
+        for note in &skipped {
+            let _ = writeln!(body, "\t\t# {note}");
+        }
+        let _ = writeln!(
+            body,
+            "\t\tip daddr . tcp dport @allow_v4 counter name \"{COUNTER_ALLOWED}\" accept"
+        );
+        let _ = writeln!(
+            body,
+            "\t\tip6 daddr . tcp dport @allow_v6 counter name \"{COUNTER_ALLOWED}\" accept"
+        );
+        let _ = writeln!(body, "\t\tcounter name \"{COUNTER_DROPPED}\" drop");
+    } else {
+        let _ = writeln!(
+            body,
+            "\t\tct state new counter name \"{COUNTER_ALLOWED}\" accept"
+        );
+    }
+    body.push_str("\t}\n");
+
+    replace_table_script(&table, &body)
+}
+
+/// Interval sets may hold overlapping prefixes (the metadata `/32`
+/// inside link-local `/16`); `auto-merge` lets nft fold them.
+fn push_interval_set(body: &mut String, name: &str, ty: &str, nets: &[ipnet::IpNet]) {
+    let _ = writeln!(
+        body,
+        "\tset {name} {{\n\t\ttype {ty}\n\t\tflags interval\n\t\tauto-merge"
+    );
+    if !nets.is_empty() {
+        let elems: Vec<String> = nets.iter().map(ToString::to_string).collect();
+        let _ = writeln!(body, "\t\telements = {{ {} }}", elems.join(", "));
+    }
+    body.push_str("\t}\n");
+}
+
+/// nft rejects `elements = { }`, so an empty set omits the line.
+/// `flags interval` lets allow-list port ranges share the set with
+/// single ports.
+fn push_set(body: &mut String, name: &str, ty: &str, elems: &BTreeSet<String>) {
+    let _ = writeln!(body, "\tset {name} {{\n\t\ttype {ty}\n\t\tflags interval");
+    if !elems.is_empty() {
+        let elems: Vec<&str> = elems.iter().map(String::as_str).collect();
+        let _ = writeln!(body, "\t\telements = {{ {} }}", elems.join(", "));
+    }
+    body.push_str("\t}\n");
+}
+
+/// One named counter's reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftCounter {
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+/// The named counters of a per-VM table. Counters a table doesn't
+/// declare read as zero (the supervisor firewall has no DNS or
+/// mandatory-deny rules).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmNftCounters {
+    pub allowed: NftCounter,
+    pub dropped: NftCounter,
+    pub dns: NftCounter,
+    pub mandatory_deny: NftCounter,
+}
+
+/// Parse `nft -j list counters table inet <table>` output. Entries
+/// for other tables are ignored; unknown counter names too.
+pub fn parse_counters(table: &str, json: &str) -> Result<VmNftCounters, NftRenderError> {
+    let doc: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| NftRenderError::Counters(e.to_string()))?;
+    let items = doc
+        .get("nftables")
+        .and_then(serde_json::Value::as_array)
+        .ok_or_else(|| NftRenderError::Counters("missing `nftables` array".to_string()))?;
+
+    let mut out = VmNftCounters::default();
+    for counter in items.iter().filter_map(|item| item.get("counter")) {
+        if counter.get("table").and_then(serde_json::Value::as_str) != Some(table) {
+            continue;
+        }
+        let read = |key: &str| counter.get(key).and_then(serde_json::Value::as_u64);
+        let (Some(packets), Some(bytes)) = (read("packets"), read("bytes")) else {
+            return Err(NftRenderError::Counters(format!(
+                "counter without packets/bytes: {counter}"
+            )));
+        };
+        let slot = match counter.get("name").and_then(serde_json::Value::as_str) {
+            Some(COUNTER_ALLOWED) => &mut out.allowed,
+            Some(COUNTER_DROPPED) => &mut out.dropped,
+            Some(COUNTER_DNS) => &mut out.dns,
+            Some(COUNTER_MANDATORY_DENY) => &mut out.mandatory_deny,
+            _ => continue,
+        };
+        *slot = NftCounter { packets, bytes };
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_policy::{HostPort, NetworkPreset};
+
+    fn spec() -> GuestNftSpec {
+        GuestNftSpec::new("vm1", "br-mvm", "172.16.0.3", Some("06:00:AC:10:00:03")).unwrap()
+    }
+
+    fn no_dns(_: &str) -> Vec<IpAddr> {
+        Vec::new()
+    }
+
+    #[test]
+    fn replace_script_is_one_declare_delete_create_transaction() {
+        let script = replace_table_script("mvm_x", "\tchain c {\n\t}\n").unwrap();
+        assert!(
+            script.starts_with("table inet mvm_x\ndelete table inet mvm_x\ntable inet mvm_x {\n")
+        );
+        assert!(script.ends_with("}\n"));
+    }
+
+    #[test]
+    fn delete_script_is_idempotent_and_scoped() {
+        assert_eq!(
+            delete_table_script("mvm_policy_vm1").unwrap(),
+            "table inet mvm_policy_vm1\ndelete table inet mvm_policy_vm1\n"
+        );
+    }
+
+    #[test]
+    fn unsafe_identifiers_are_refused() {
+        assert!(matches!(
+            policy_table_name("vm1; flush ruleset"),
+            Err(NftRenderError::InvalidIdent { .. })
+        ));
+        assert!(GuestNftSpec::new("vm1", "br mvm", "172.16.0.3", None).is_err());
+        assert!(GuestNftSpec::new("vm1", "br-mvm", "172.16.0.3; x", None).is_err());
+        assert!(GuestNftSpec::new("vm1", "br-mvm", "172.16.0.3", Some("06:00")).is_err());
+        assert!(delete_table_script("").is_err());
+    }
+
+    #[test]
+    fn every_policy_drops_the_mandatory_deny_set_on_both_families() {
+        for policy in [
+            NetworkPolicy::unrestricted(),
+            NetworkPolicy::deny_all(),
+            NetworkPolicy::preset(NetworkPreset::Dev),
+        ] {
+            let rules = render_policy_table(&spec(), &policy, no_dns).unwrap();
+            assert!(rules.contains("169.254.169.254/32"), "{rules}");
+            assert!(rules.contains("fd00:ec2::254/128"), "{rules}");
+            assert!(
+                rules.contains("ip daddr @mandatory_deny_v4 counter name \"mandatory_deny\" drop")
+            );
+            assert!(
+                rules.contains("ip6 daddr @mandatory_deny_v6 counter name \"mandatory_deny\" drop")
+            );
+        }
+    }
+
+    /// Every const entry, v4 and v6, must land in one of the two
+    /// deny sets — drift here reopens the metadata endpoint.
+    #[test]
+    fn mandatory_deny_sets_cover_every_const_entry() {
+        let rules = render_policy_table(&spec(), &NetworkPolicy::unrestricted(), no_dns).unwrap();
+        for raw in crate::network_policy::MANDATORY_DENY_RANGES {
+            let net: ipnet::IpNet = raw.parse().unwrap();
+            let set = if net.network().is_ipv4() {
+                "mandatory_deny_v4"
+            } else {
+                "mandatory_deny_v6"
+            };
+            let block = rules.split(&format!("set {set} {{")).nth(1).unwrap();
+            let block = &block[..block.find("\t}").unwrap()];
+            assert!(block.contains(&net.to_string()), "{net} missing from {set}");
+        }
+    }
+
+    #[test]
+    fn guest_is_matched_by_ipv4_source_and_by_mac_for_ipv6() {
+        let rules = render_policy_table(&spec(), &NetworkPolicy::deny_all(), no_dns).unwrap();
+        assert!(rules.contains("iifname \"br-mvm\" ip saddr 172.16.0.3 jump guest_egress"));
+        assert!(rules.contains(
+            "iifname \"br-mvm\" ether saddr 06:00:ac:10:00:03 meta nfproto ipv6 jump guest_egress"
+        ));
+
+        let no_mac = GuestNftSpec::new("vm1", "br-mvm", "172.16.0.3", None).unwrap();
+        let rules = render_policy_table(&no_mac, &NetworkPolicy::deny_all(), no_dns).unwrap();
+        assert!(!rules.contains("ether saddr"));
+    }
+
+    #[test]
+    fn patterns_are_left_to_l7_and_ranges_render_as_intervals() {
+        let policy = NetworkPolicy::allow_list(vec![
+            HostPort::new("*.githubusercontent.com", 443),
+            HostPort::range("203.0.113.9", 8000, 8100),
+            HostPort::new("203.0.113.10", 0),
+            HostPort::new("bad host\nflush ruleset", 443),
+        ]);
+        let rules = render_policy_table(&spec(), &policy, |_| {
+            panic!("patterns and IP literals never hit DNS")
+        })
+        .unwrap();
+        assert!(rules.contains("elements = { 203.0.113.10 . 0-65535, 203.0.113.9 . 8000-8100 }"));
+        assert!(rules.contains("\t\tflags interval\n"));
+        assert!(
+            rules.contains(
+                "# *.githubusercontent.com:443: host pattern, enforced at L7 and DNS only"
+            )
+        );
+        assert!(!rules.contains("\nflush ruleset"));
+        assert!(rules.contains("invalid ("));
+    }
+
+    #[test]
+    fn unrestricted_counts_new_flows_without_dropping() {
+        let rules = render_policy_table(&spec(), &NetworkPolicy::unrestricted(), no_dns).unwrap();
+        assert!(rules.contains("ct state new counter name \"allowed\" accept"));
+        assert!(!rules.contains("counter name \"dropped\" drop"));
+        assert!(!rules.contains("set allow_v4"));
+    }
+
+    #[test]
+    fn allow_list_renders_resolved_addresses_per_family() {
+        let policy = NetworkPolicy::allow_list(vec![
+            HostPort::new("example.com", 443),
+            HostPort::new("203.0.113.9", 5432),
+            HostPort::new("nowhere.invalid", 443),
+        ]);
+        let rules = render_policy_table(&spec(), &policy, |host| match host {
+            "example.com" => vec![
+                "93.184.216.34".parse().unwrap(),
+                "2606:2800:220:1::1".parse().unwrap(),
+            ],
+            _ => Vec::new(),
+        })
+        .unwrap();
+
+        assert!(rules.contains("elements = { 203.0.113.9 . 5432, 93.184.216.34 . 443 }"));
+        assert!(rules.contains("elements = { 2606:2800:220:1::1 . 443 }"));
+        assert!(rules.contains("# nowhere.invalid:443: no addresses resolved, not allowed"));
+        assert!(!rules.contains("th dport 53"), "no endpoint, no DNS");
+        assert!(rules.contains("ct state established,related accept"));
+        assert!(rules.contains("ip daddr . tcp dport @allow_v4 counter name \"allowed\" accept"));
+        assert!(rules.contains("ip6 daddr . tcp dport @allow_v6 counter name \"allowed\" accept"));
+        assert!(rules.contains("counter name \"dropped\" drop"));
+    }
+
+    #[test]
+    fn dns_is_allowed_only_to_the_endpoint() {
+        let endpoint = spec().with_dns_endpoint("172.16.0.1".parse().unwrap());
+        let rules = render_policy_table(&endpoint, &NetworkPolicy::deny_all(), no_dns).unwrap();
+        let dns: Vec<&str> = rules.lines().filter(|l| l.contains("dport 53")).collect();
+        assert_eq!(
+            dns,
+            [
+                "\t\tip daddr 172.16.0.1 meta l4proto { tcp, udp } th dport 53 counter name \"dns\" accept"
+            ]
+        );
+
+        let v6 = spec().with_dns_endpoint("fd00::1".parse().unwrap());
+        let rules = render_policy_table(&v6, &NetworkPolicy::deny_all(), no_dns).unwrap();
+        assert!(rules.contains("ip6 daddr fd00::1 meta l4proto { tcp, udp } th dport 53"));
+        assert!(!rules.contains("\tip daddr 172.16.0.1"));
+
+        // Unrestricted policies accept every new flow anyway.
+        let rules = render_policy_table(&endpoint, &NetworkPolicy::unrestricted(), no_dns).unwrap();
+        assert!(!rules.contains("dport 53"));
+    }
+
+    #[test]
+    fn deny_all_declares_empty_allow_sets_and_drops() {
+        let rules = render_policy_table(&spec(), &NetworkPolicy::deny_all(), no_dns).unwrap();
+        assert!(rules.contains(
+            "\tset allow_v4 {\n\t\ttype ipv4_addr . inet_service\n\t\tflags interval\n\t}\n"
+        ));
+        assert!(rules.contains("counter name \"dropped\" drop"));
+        // The final drop is the last rule of the egress chain.
+        let chain = rules.split("chain guest_egress").nth(1).unwrap();
+        let last_rule = chain
+            .lines()
+            .rev()
+            .find(|l| !l.trim().starts_with('}'))
+            .unwrap();
+        assert_eq!(last_rule.trim(), "counter name \"dropped\" drop");
+    }
+
+    #[test]
+    fn rendered_table_declares_every_counter_it_references() {
+        let rules =
+            render_policy_table(&spec(), &NetworkPolicy::preset(NetworkPreset::Dev), no_dns)
+                .unwrap();
+        for name in [
+            COUNTER_ALLOWED,
+            COUNTER_DROPPED,
+            COUNTER_DNS,
+            COUNTER_MANDATORY_DENY,
+        ] {
+            assert!(rules.contains(&format!("\tcounter {name} {{")), "{name}");
+        }
+        assert!(rules.contains("table inet mvm_policy_vm1 {"));
+    }
+
+    #[test]
+    fn counters_parse_from_nft_json_listing() {
+        let json = r#"{"nftables": [
+            {"metainfo": {"version": "1.0.9", "release_name": "Old Doc Yak #3", "json_schema_version": 1}},
+            {"counter": {"family": "inet", "name": "allowed", "table": "mvm_policy_vm1", "handle": 1, "packets": 12, "bytes": 840}},
+            {"counter": {"family": "inet", "name": "dropped", "table": "mvm_policy_vm1", "handle": 2, "packets": 3, "bytes": 180}},
+            {"counter": {"family": "inet", "name": "mandatory_deny", "table": "mvm_policy_vm1", "handle": 4, "packets": 1, "bytes": 60}},
+            {"counter": {"family": "inet", "name": "allowed", "table": "mvm_policy_vm2", "handle": 1, "packets": 99, "bytes": 99}}
+        ]}"#;
+        let counters = parse_counters("mvm_policy_vm1", json).unwrap();
+        assert_eq!(
+            counters.allowed,
+            NftCounter {
+                packets: 12,
+                bytes: 840
+            }
+        );
+        assert_eq!(counters.dropped.packets, 3);
+        assert_eq!(counters.mandatory_deny.bytes, 60);
+        assert_eq!(counters.dns, NftCounter::default());
+    }
+
+    #[test]
+    fn counters_parse_rejects_malformed_listings() {
+        assert!(parse_counters("t", "not json").is_err());
+        assert!(parse_counters("t", r#"{"rules": []}"#).is_err());
+        assert!(
+            parse_counters(
+                "t",
+                r#"{"nftables": [{"counter": {"table": "t", "name": "allowed"}}]}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn list_counters_args_target_one_table() {
+        assert_eq!(
+            list_counters_args("mvm_policy_vm1").unwrap(),
+            ["-j", "list", "counters", "table", "inet", "mvm_policy_vm1"]
+        );
+    }
+}
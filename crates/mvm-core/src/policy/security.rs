@@ -160,6 +160,11 @@ pub struct SecurityPolicy {
     #[serde(default)]
     pub rate_limits: RateLimitPolicy,
 
+    /// Filesystem RPC quota the agent enforces as a backstop to the
+    /// host-side meter.
+    #[serde(default)]
+    pub fs_quota: FsQuotaPolicy,
+
     /// Session lifecycle limits.
     #[serde(default)]
     pub session: SessionPolicy,
@@ -176,6 +181,7 @@ impl Default for SecurityPolicy {
             profile: AgentProfile::SealedProd,
             access: AccessPolicy::default(),
             rate_limits: RateLimitPolicy::default(),
+            fs_quota: FsQuotaPolicy::default(),
             session: SessionPolicy::default(),
             blocklist: Vec::new(),
         }
@@ -255,6 +261,52 @@ impl Default for RateLimitPolicy {
     }
 }
 
+/// Byte- and op-aware quota for filesystem RPC (Layer 3 of the DoS
+/// model). Enforced per VM by the host before dispatch and by the
+/// guest agent as a backstop; a rejected call is answered with a
+/// `BackpressureReason` instead of touching the disk.
+///
+/// Rates are sliding one-second windows. `session_bytes` caps the
+/// total bytes read, written and streamed over the meter's lifetime
+/// (one agent boot on the guest, one host process on the host).
+/// 0 disables a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct FsQuotaPolicy {
+    /// Maximum FS calls per second, streaming opens included.
+    pub ops_per_second: u32,
+    /// Maximum bytes requested by `FsRead` per second.
+    pub read_bytes_per_second: u64,
+    /// Maximum bytes sent by `FsWrite` per second.
+    pub write_bytes_per_second: u64,
+    /// Maximum bytes moved over the session, single-shot and
+    /// streamed transfers combined.
+    pub session_bytes: u64,
+}
+
+impl Default for FsQuotaPolicy {
+    fn default() -> Self {
+        Self {
+            ops_per_second: 200,
+            read_bytes_per_second: 64 * 1024 * 1024,
+            write_bytes_per_second: 64 * 1024 * 1024,
+            session_bytes: 0,
+        }
+    }
+}
+
+impl FsQuotaPolicy {
+    /// No limits at all.
+    pub const fn unlimited() -> Self {
+        Self {
+            ops_per_second: 0,
+            read_bytes_per_second: 0,
+            write_bytes_per_second: 0,
+            session_bytes: 0,
+        }
+    }
+}
+
 /// Session lifecycle limits.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionPolicy {
@@ -342,7 +394,7 @@ pub struct BlocklistEntry {
 ///
 /// Each category represents a class of security concern. A single message
 /// may trigger findings across multiple categories.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ThreatCategory {
     /// Credential or secret exposure (API keys, tokens, private keys).
     SecretExposure,
@@ -595,10 +647,20 @@ mod tests {
         assert!(policy.access.host_communication);
         assert_eq!(policy.rate_limits.frames_per_second, 100);
         assert_eq!(policy.rate_limits.frames_per_minute, 3000);
+        assert_eq!(policy.fs_quota, FsQuotaPolicy::default());
         assert_eq!(policy.session.max_lifetime_secs, 0);
         assert_eq!(policy.session.max_tasks, 0);
     }
 
+    #[test]
+    fn test_fs_quota_partial_json_keeps_other_defaults() {
+        let quota: FsQuotaPolicy = serde_json::from_str(r#"{"session_bytes": 4096}"#).unwrap();
+        assert_eq!(quota.session_bytes, 4096);
+        assert_eq!(quota.ops_per_second, 200);
+        assert_eq!(quota.read_bytes_per_second, 64 * 1024 * 1024);
+        assert!(serde_json::from_str::<FsQuotaPolicy>(r#"{"iops": 1}"#).is_err());
+    }
+
     #[test]
     fn test_agent_profile_serde_kebab_case() {
         // Wire format is kebab-case so a hand-written policy JSON can
@@ -681,6 +743,10 @@ mod tests {
                 frames_per_second: 200,
                 frames_per_minute: 6000,
             },
+            fs_quota: FsQuotaPolicy {
+                session_bytes: 1 << 30,
+                ..FsQuotaPolicy::default()
+            },
             session: SessionPolicy {
                 max_lifetime_secs: 3600,
                 max_tasks: 100,
@@ -701,6 +767,8 @@ mod tests {
         assert!(!parsed.access.filesystem);
         assert!(!parsed.access.build);
         assert_eq!(parsed.rate_limits.frames_per_second, 200);
+        assert_eq!(parsed.fs_quota.session_bytes, 1 << 30);
+        assert_eq!(parsed.fs_quota.ops_per_second, 200);
         assert_eq!(parsed.session.max_lifetime_secs, 3600);
         assert_eq!(parsed.session.max_tasks, 100);
         assert_eq!(parsed.blocklist.len(), 1);
@@ -0,0 +1,40 @@
+//! `broker.v1` payload types — broker introspection (Plan 104 W3).
+//!
+//! One verb:
+//!
+//! - `list_services` — empty payload ([`ListServicesRequest`]);
+//!   response is [`ListServicesResponse`]: every service bound in the
+//!   calling workload's broker, with its verbs, the agent profiles it
+//!   admits, and its deprecation date if any. Sorted by service id so the
+//!   response is stable across calls.
+
+use serde::{Deserialize, Serialize};
+
+use crate::policy::security::AgentProfile;
+use crate::protocol::broker::ServiceId;
+
+/// Payload for `broker.v1::list_services`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListServicesRequest {}
+
+/// One bound service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceDescriptor {
+    pub service: ServiceId,
+    pub verbs: Vec<String>,
+    pub profiles: Vec<AgentProfile>,
+    /// Removal date (e.g. `"2027-Q1"`) for a deprecated service; the
+    /// service still answers until then but callers should move to
+    /// its successor version (Plan 104 §A1). `None` = not deprecated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated_in: Option<String>,
+}
+
+/// Response for `broker.v1::list_services`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListServicesResponse {
+    pub services: Vec<ServiceDescriptor>,
+}
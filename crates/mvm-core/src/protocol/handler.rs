@@ -155,6 +155,19 @@ pub trait ServiceHandler: Send + Sync + 'static {
     fn composes_with(&self) -> &[ServiceId] {
         &[]
     }
+
+    /// Verbs this handler answers, as surfaced by
+    /// `broker.v1/list_services`. Default empty (handler doesn't
+    /// advertise its verbs).
+    fn verbs(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Removal date for a deprecated service (Plan 104 §A1, e.g.
+    /// `"2027-Q1"`). Default `None` (not deprecated).
+    fn deprecated_in(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 // ============================================================================
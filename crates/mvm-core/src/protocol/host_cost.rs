@@ -0,0 +1,97 @@
+//! `host.cost.v1` payload types — per-VM usage readouts (Plan 104 W4a).
+//!
+//! Two verbs:
+//!
+//! - `workload` — payload is [`WorkloadCostRequest`]; response is
+//!   [`WorkloadCostResponse`]: running totals of the
+//!   [`MeteringSample`]s the broker has seen for the calling workload.
+//! - `tenant` — cross-VM roll-up via mvmd. Returns
+//!   `ServiceErrorCode::NotImplemented` until W4b.
+//!
+//! The workload and tenant the readout is scoped to come from the
+//! supervisor's `ServiceCallCtx`; the request carries no ids, so a
+//! workload can only ever read its own usage.
+
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::metering::MeteringSample;
+
+/// Payload for `host.cost.v1::workload`. Empty today; kept as a struct
+/// (rather than `()`) so future filters are additive.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadCostRequest {}
+
+/// Summed usage counters. Each field is the saturating sum of the
+/// matching [`MeteringSample`] delta.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UsageTotals {
+    pub cpu_ns: u64,
+    pub mem_byte_seconds: u64,
+    pub storage_byte_seconds_cold: u64,
+    pub storage_byte_seconds_hot: u64,
+}
+
+impl UsageTotals {
+    /// Fold one sample into the totals.
+    pub fn add(&mut self, sample: &MeteringSample) {
+        self.cpu_ns = self.cpu_ns.saturating_add(sample.cpu_ns);
+        self.mem_byte_seconds = self
+            .mem_byte_seconds
+            .saturating_add(sample.mem_byte_seconds);
+        self.storage_byte_seconds_cold = self
+            .storage_byte_seconds_cold
+            .saturating_add(sample.storage_byte_seconds_cold);
+        self.storage_byte_seconds_hot = self
+            .storage_byte_seconds_hot
+            .saturating_add(sample.storage_byte_seconds_hot);
+    }
+}
+
+/// Response for `host.cost.v1::workload`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadCostResponse {
+    pub workload_id: String,
+    pub tenant_id: String,
+    pub totals: UsageTotals,
+    /// Number of samples folded into `totals`.
+    pub sample_count: u64,
+    /// Timestamp of the earliest sample seen. `None` before the first
+    /// sample arrives.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_sample_at: Option<SystemTime>,
+    /// Timestamp of the latest sample seen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sample_at: Option<SystemTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn totals_saturate_instead_of_wrapping() {
+        let sample = MeteringSample {
+            instance_id: "wl-1".into(),
+            tenant_id: "t-1".into(),
+            tags: BTreeMap::new(),
+            ts: SystemTime::UNIX_EPOCH,
+            cpu_ns: u64::MAX,
+            mem_byte_seconds: 1,
+            storage_byte_seconds_cold: 2,
+            storage_byte_seconds_hot: 3,
+        };
+        let mut totals = UsageTotals::default();
+        totals.add(&sample);
+        totals.add(&sample);
+        assert_eq!(totals.cpu_ns, u64::MAX);
+        assert_eq!(totals.mem_byte_seconds, 2);
+        assert_eq!(totals.storage_byte_seconds_hot, 6);
+    }
+}
@@ -0,0 +1,277 @@
+//! `host.time.v1` payload types — trusted host time for guests
+//! (Plan 104 W3, §H-L5.5).
+//!
+//! One verb:
+//!
+//! - `now` — payload is [`NowRequest`]; response is [`NowResponse`]
+//!   carrying a [`TimeReading`] (wall clock + broker-monotonic clock)
+//!   signed by the broker's per-spawn Ed25519 key.
+//!
+//! The guest supplies an optional `nonce` which the broker echoes
+//! inside the signed reading, so a replayed response from an earlier
+//! call fails verification against the guest's fresh nonce.
+//!
+//! The signing key is ephemeral: the broker mints it at spawn and
+//! publishes the verifying key in every response. Guests pin the key
+//! on first use ([`verify_now_response`] with `expected = None`) and
+//! verify every later reading against the pinned key. A key change
+//! means the broker respawned, which also resets the monotonic clock —
+//! `monotonic_ns` and `seq` are only comparable between readings with
+//! the same `signer_key_id`.
+//!
+//! `workload_id` inside the reading is broker-filled from the
+//! supervisor's `ServiceCallCtx`, never guest-supplied.
+
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::protocol::signed_config::SignedConfigEnvelope;
+use crate::security::SIG_ALG_ED25519;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Domain-separation prefix for the bytes a [`TimeReading`] signature
+/// covers. Keeps a time signature from ever verifying as some other
+/// Ed25519-signed artefact produced by the same key.
+pub const HOST_TIME_SIGNING_DOMAIN: &[u8] = b"mvm.host.time.v1\0";
+
+/// Maximum length of the guest-supplied nonce, in bytes.
+pub const HOST_TIME_NONCE_MAX_BYTES: usize = 64;
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HostTimeError {
+    /// `sig_alg` is not one this codepath knows how to verify.
+    #[error("host time: unsupported sig_alg {sig_alg}")]
+    UnsupportedAlgorithm { sig_alg: u8 },
+    /// Base64 decode of the key or signature failed, or it had the
+    /// wrong length.
+    #[error("host time: malformed {field}")]
+    Malformed { field: &'static str },
+    /// `signer_key_id` does not match the published verifying key.
+    #[error("host time: signer_key_id does not match the published key")]
+    KeyIdMismatch,
+    /// The reading was signed by a key other than the pinned one.
+    #[error("host time: signer_key_id {got} did not match pinned {expected}")]
+    UnexpectedSignerKey { got: String, expected: String },
+    /// The signature didn't verify.
+    #[error("host time: signature verification failed")]
+    SignatureMismatch,
+    /// The signed nonce differs from the one the caller sent.
+    #[error("host time: nonce mismatch (replayed or crossed response)")]
+    NonceMismatch,
+}
+
+// ============================================================================
+// Payloads
+// ============================================================================
+
+/// Payload for `host.time.v1::now`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NowRequest {
+    /// Caller-chosen freshness token, echoed inside the signed
+    /// reading. At most [`HOST_TIME_NONCE_MAX_BYTES`] bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+/// The signed part of a `now` response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeReading {
+    /// Host wall clock, nanoseconds since the Unix epoch.
+    pub wall_unix_ns: u64,
+    /// Nanoseconds since the broker's signing key was minted. Never
+    /// goes backwards for a given `signer_key_id`, even when the host
+    /// wall clock is stepped.
+    pub monotonic_ns: u64,
+    /// Per-key reading counter, strictly increasing.
+    pub seq: u64,
+    /// Workload the reading was issued to (broker-filled).
+    pub workload_id: String,
+    /// Echo of [`NowRequest::nonce`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+/// Response for `host.time.v1::now`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NowResponse {
+    pub reading: TimeReading,
+    /// [`SIG_ALG_ED25519`] (algorithm-identifier byte per Plan 104
+    /// §H-L4.1).
+    pub sig_alg: u8,
+    /// Hex SHA-256 of the verifying key (same derivation as
+    /// [`SignedConfigEnvelope::key_id_for`]).
+    pub signer_key_id: String,
+    /// Base64 of the 32-byte verifying key.
+    pub signer_public_key_b64: String,
+    /// Base64 of the Ed25519 signature over [`signing_bytes`].
+    pub signature_b64: String,
+}
+
+// ============================================================================
+// Sign + verify
+// ============================================================================
+
+/// The exact bytes a [`TimeReading`] signature covers: the domain
+/// prefix followed by the reading's JSON encoding.
+pub fn signing_bytes(reading: &TimeReading) -> Vec<u8> {
+    let mut out = HOST_TIME_SIGNING_DOMAIN.to_vec();
+    out.extend(serde_json::to_vec(reading).expect("serialise own struct"));
+    out
+}
+
+/// Sign a reading with the broker's key and wrap it in a response.
+pub fn sign_reading(signing_key: &SigningKey, reading: TimeReading) -> NowResponse {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let verifying_key = signing_key.verifying_key();
+    let signature = signing_key.sign(&signing_bytes(&reading));
+    NowResponse {
+        reading,
+        sig_alg: SIG_ALG_ED25519,
+        signer_key_id: SignedConfigEnvelope::key_id_for(&verifying_key),
+        signer_public_key_b64: b64.encode(verifying_key.to_bytes()),
+        signature_b64: b64.encode(signature.to_bytes()),
+    }
+}
+
+/// Verify a `now` response. With `expected = Some(key)` the response
+/// must be signed by that key; with `None` the published key is
+/// accepted (trust on first use). `nonce` is the value the caller put
+/// in its [`NowRequest`]. Returns the verifying key so the caller can
+/// pin it.
+pub fn verify_now_response(
+    response: &NowResponse,
+    expected: Option<&VerifyingKey>,
+    nonce: Option<&str>,
+) -> Result<VerifyingKey, HostTimeError> {
+    if response.sig_alg != SIG_ALG_ED25519 {
+        return Err(HostTimeError::UnsupportedAlgorithm {
+            sig_alg: response.sig_alg,
+        });
+    }
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let key_bytes: [u8; 32] = b64
+        .decode(&response.signer_public_key_b64)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(HostTimeError::Malformed {
+            field: "signer_public_key_b64",
+        })?;
+    let published = VerifyingKey::from_bytes(&key_bytes).map_err(|_| HostTimeError::Malformed {
+        field: "signer_public_key_b64",
+    })?;
+    if SignedConfigEnvelope::key_id_for(&published) != response.signer_key_id {
+        return Err(HostTimeError::KeyIdMismatch);
+    }
+    if let Some(pinned) = expected
+        && pinned != &published
+    {
+        return Err(HostTimeError::UnexpectedSignerKey {
+            got: response.signer_key_id.clone(),
+            expected: SignedConfigEnvelope::key_id_for(pinned),
+        });
+    }
+    let sig_bytes: [u8; 64] = b64
+        .decode(&response.signature_b64)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(HostTimeError::Malformed {
+            field: "signature_b64",
+        })?;
+    published
+        .verify(
+            &signing_bytes(&response.reading),
+            &Signature::from_bytes(&sig_bytes),
+        )
+        .map_err(|_| HostTimeError::SignatureMismatch)?;
+    if response.reading.nonce.as_deref() != nonce {
+        return Err(HostTimeError::NonceMismatch);
+    }
+    Ok(published)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    fn reading(nonce: Option<&str>) -> TimeReading {
+        TimeReading {
+            wall_unix_ns: 1_760_000_000_000_000_000,
+            monotonic_ns: 42,
+            seq: 1,
+            workload_id: "wl-1".into(),
+            nonce: nonce.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn signed_reading_verifies_and_pins() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let resp = sign_reading(&sk, reading(Some("n-1")));
+        let key = verify_now_response(&resp, None, Some("n-1")).unwrap();
+        assert_eq!(key, sk.verifying_key());
+        verify_now_response(&resp, Some(&key), Some("n-1")).unwrap();
+    }
+
+    #[test]
+    fn tampered_reading_fails() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let mut resp = sign_reading(&sk, reading(None));
+        resp.reading.wall_unix_ns += 1;
+        assert_eq!(
+            verify_now_response(&resp, None, None).unwrap_err(),
+            HostTimeError::SignatureMismatch
+        );
+    }
+
+    #[test]
+    fn replayed_nonce_fails() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let resp = sign_reading(&sk, reading(Some("old")));
+        assert_eq!(
+            verify_now_response(&resp, None, Some("new")).unwrap_err(),
+            HostTimeError::NonceMismatch
+        );
+    }
+
+    #[test]
+    fn foreign_key_fails_against_pin() {
+        let pinned = SigningKey::generate(&mut OsRng).verifying_key();
+        let other = SigningKey::generate(&mut OsRng);
+        let resp = sign_reading(&other, reading(None));
+        assert!(matches!(
+            verify_now_response(&resp, Some(&pinned), None).unwrap_err(),
+            HostTimeError::UnexpectedSignerKey { .. }
+        ));
+    }
+
+    #[test]
+    fn swapped_public_key_is_caught_by_key_id() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let mut resp = sign_reading(&sk, reading(None));
+        let other = SigningKey::generate(&mut OsRng).verifying_key();
+        resp.signer_public_key_b64 =
+            base64::engine::general_purpose::STANDARD.encode(other.to_bytes());
+        assert_eq!(
+            verify_now_response(&resp, None, None).unwrap_err(),
+            HostTimeError::KeyIdMismatch
+        );
+    }
+}
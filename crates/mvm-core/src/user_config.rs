@@ -2,12 +2,14 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::security::BlocklistEntry;
+
 /// Security-related operator preferences.
 ///
 /// Lives under `[security]` in `~/.mvm/config.toml`. Used by Plan B (the
 /// Docker-tier acknowledgment banner) and is the seam where future
 /// posture knobs land.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SecurityConfig {
     /// Acknowledge that the active backend is the Tier 3 Docker fallback
@@ -15,6 +17,38 @@ pub struct SecurityConfig {
     /// the per-run security warning banner. Equivalent to setting the
     /// `MVM_ACK_DOCKER_TIER=1` environment variable.
     pub ack_docker_tier: bool,
+    /// Seconds a command parked by a `RequireApproval` blocklist match
+    /// waits for `mvmctl approvals approve|deny` before it is rejected
+    /// as timed out (default: 300). Override with
+    /// `MVM_APPROVAL_TIMEOUT_SECS`.
+    pub approval_timeout_secs: u64,
+    /// Extra command-gate entries evaluated after the built-in
+    /// blocklist, as `[[security.command_blocklist]]` tables.
+    pub command_blocklist: Vec<BlocklistEntry>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            ack_docker_tier: false,
+            approval_timeout_secs: 300,
+            command_blocklist: Vec::new(),
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Resolve the effective approval timeout, honoring an
+    /// `MVM_APPROVAL_TIMEOUT_SECS` env-var override over the config
+    /// field (same precedence as the services-health timeout).
+    pub fn effective_approval_timeout_secs(&self) -> u64 {
+        if let Ok(raw) = std::env::var("MVM_APPROVAL_TIMEOUT_SECS")
+            && let Ok(n) = raw.trim().parse::<u64>()
+        {
+            return n;
+        }
+        self.approval_timeout_secs
+    }
 }
 
 /// Persistent operator configuration stored at `~/.mvm/config.toml`.
@@ -238,6 +272,9 @@ mod tests {
         assert!(!cfg.security.ack_docker_tier);
         // ADR-053 §3 / plan 74 W2 default: 30 s services-health wait.
         assert_eq!(cfg.services_health_timeout_secs, 30);
+        // Parked approvals wait five minutes; no extra gate entries.
+        assert_eq!(cfg.security.approval_timeout_secs, 300);
+        assert!(cfg.security.command_blocklist.is_empty());
     }
 
     #[test]
@@ -275,6 +312,7 @@ mod tests {
         let cfg = MvmConfig {
             security: SecurityConfig {
                 ack_docker_tier: true,
+                ..SecurityConfig::default()
             },
             ..MvmConfig::default()
         };
@@ -283,6 +321,28 @@ mod tests {
         assert!(parsed.security.ack_docker_tier);
     }
 
+    #[test]
+    fn test_security_command_blocklist_parses_from_toml() {
+        let text = r#"
+            [security]
+            approval_timeout_secs = 45
+
+            [[security.command_blocklist]]
+            pattern = "git push*"
+            category = "exfiltration"
+            severity = "High"
+            action = "RequireApproval"
+        "#;
+        let cfg: MvmConfig = toml::from_str(text).unwrap();
+        assert_eq!(cfg.security.approval_timeout_secs, 45);
+        assert_eq!(cfg.security.command_blocklist.len(), 1);
+        assert_eq!(
+            cfg.security.command_blocklist[0].action,
+            crate::security::BlocklistAction::RequireApproval
+        );
+        assert!(!cfg.security.ack_docker_tier);
+    }
+
     #[test]
     fn test_legacy_config_without_security_section_still_loads() {
         // Older config files written before the [security] section was
@@ -77,12 +77,16 @@ use mvm_policy::PolicyBundle;
 use mvm_supervisor::audit::AuditSigner;
 #[cfg(target_os = "linux")]
 use mvm_supervisor::audit_file::FileAuditSigner;
-#[cfg(target_os = "linux")]
 use mvm_supervisor::gateway_bridge::{
     AllowAll, BridgeConfig, BridgeEndpoints, spawn_bridge_thread,
 };
 #[cfg(target_os = "linux")]
+use mvm_supervisor::l7_proxy::{SignedEgressAuditSink, TokioDnsResolver};
+#[cfg(target_os = "linux")]
 use mvm_supervisor::network::{ObserverAllowlist, ProviderCapabilities, from_admitted};
+use mvm_supervisor::proxy::datapath::L4Datapath;
+#[cfg(target_os = "linux")]
+use mvm_supervisor::proxy::dns::DnsPinStore;
 #[cfg(target_os = "linux")]
 use std::io::Read;
 #[cfg(target_os = "linux")]
@@ -236,14 +240,47 @@ fn run() -> Result<()> {
         )
     };
 
+    let plan = Arc::new(plan);
+    let bundle = bundle.map(Arc::new);
+
+    // `[[network.l4]]` rows or a restricted `[egress]` allow-list in
+    // the bundle engage the frame-level L4 datapath in the bridge;
+    // bundles with neither keep the unfiltered gateway path. The
+    // datapath carries the VM's guest DNS endpoint: it answers the
+    // guest's queries for `[egress]` allow-listed names and pins each
+    // answer for the L4 gate. Its flow and DNS decisions are
+    // chain-signed with the same signer as the bridge's flow events.
+    // Landlock only lets us write under `cfg.audit_dir`, so the pin
+    // events (`DnsPinSet` / `DnsPinReject`) go to a per-VM log there
+    // instead of the default local audit log.
+    let l4 = match &bundle {
+        Some(b) => L4Datapath::from_bundle(
+            b,
+            Arc::new(
+                DnsPinStore::for_vm(cfg.vm_name.clone())
+                    .with_audit_log(cfg.audit_dir.join(format!("{}-dns.jsonl", cfg.vm_name))),
+            ),
+            Arc::new(TokioDnsResolver),
+            Arc::new(SignedEgressAuditSink::new(
+                Arc::clone(&signer),
+                Arc::clone(&plan),
+                bundle.clone(),
+            )),
+        )
+        .context("build the L4 datapath from the bundle")?
+        .map(Arc::new),
+        None => None,
+    };
+
     let bridge_cfg = BridgeConfig {
         vm_name: cfg.vm_name.clone(),
-        plan: Arc::new(plan),
-        bundle: bundle.map(Arc::new),
+        plan,
+        bundle,
         audit_socket: cfg.audit_socket,
         signer,
         policy: Arc::new(AllowAll),
         observers,
+        l4,
     };
 
     let endpoints = BridgeEndpoints::Passt {
@@ -37,7 +37,7 @@ use mvm_guest::probes::{self, ProbeEntry, ProbeOutputFormat, ProbeResult};
 use mvm_guest::runtime_config::{self, ConcurrencyConfig};
 use mvm_guest::vsock::{
     BootTimingReport, ComponentState, EntrypointEvent, FsChange, FsChangeKind, GUEST_AGENT_PORT,
-    GuestRequest, GuestResponse, ReadinessReport, RunEntrypointError,
+    GuestRequest, GuestResponse, MandatoryDenyReadiness, ReadinessReport, RunEntrypointError,
 };
 use mvm_guest::worker_pool::{DispatchError, DispatchOutcome, WorkerPool};
 use mvm_guest::worker_protocol::WorkerOutcome;
@@ -461,6 +461,11 @@ impl AgentBootState {
             volumes,
             profile: self.profile,
             boot_millis: timing,
+            mandatory_deny: mvm_guest::netinit::read_report(std::path::Path::new(
+                mvm_guest::netinit::REPORT_PATH,
+            ))
+            .as_ref()
+            .map(MandatoryDenyReadiness::from),
         }
     }
 }
@@ -2060,6 +2065,15 @@ fn handle_client(
         return;
     }
 
+    // Layer 3 of the DoS model: meter FS verbs against the image's
+    // `fs_quota` before any handler touches the disk. The host meters
+    // the same verbs first; this is the backstop.
+    if let Some(result) = fs_quota().admit(&req) {
+        write_response(&mut file, &GuestResponse::FsResult(result));
+        return;
+    }
+    let download = matches!(req, GuestRequest::FsDownloadStream { .. }).then(|| req.clone());
+
     let resp = match req {
         // The hello-prelude loop above guarantees `req` is not a
         // ProtocolHello, but keep an explicit, loud panic to catch
@@ -2318,7 +2332,7 @@ fn handle_client(
         // verb a host polls during `mvmctl wait <vm> --for ...`
         // without back-pressure on the rest of the agent.
         GuestRequest::ReadinessStatus => {
-            GuestResponse::ReadinessStatusReport(boot_state.snapshot())
+            GuestResponse::ReadinessStatusReport(Box::new(boot_state.snapshot()))
         }
 
         // FS RPC verbs (W1 / A1). Production-safe surface backed
@@ -2393,6 +2407,38 @@ fn handle_client(
             }),
         ),
 
+        // Streaming FS verbs (W2). Validated on the control channel,
+        // then served on a one-shot data port — see `open_fs_stream`.
+        GuestRequest::FsUploadStream {
+            path,
+            size,
+            sha256,
+            mode,
+            create_parents,
+        } => open_fs_stream(mvm_guest::fs_stream::plan_with_defaults(
+            mvm_guest::fs_stream::StreamRequest::Upload {
+                path: &path,
+                size,
+                sha256: &sha256,
+                mode,
+                create_parents,
+            },
+        )),
+        GuestRequest::FsDownloadStream { path, offset, .. } => {
+            open_fs_stream(mvm_guest::fs_stream::plan_with_defaults(
+                mvm_guest::fs_stream::StreamRequest::Download {
+                    path: &path,
+                    offset,
+                },
+            ))
+        }
+        GuestRequest::FsWatch { path, recursive } => open_fs_stream(
+            mvm_guest::fs_stream::plan_with_defaults(mvm_guest::fs_stream::StreamRequest::Watch {
+                path: &path,
+                recursive,
+            }),
+        ),
+
         // Process control verbs (W1 / A2). Dev-only — the handler
         // lives behind `#[cfg(feature = "dev-shell")]` so its
         // symbols are stripped from prod builds (ADR-002 §W4.3 +
@@ -2406,22 +2452,30 @@ fn handle_client(
             cwd,
             stdin,
             timeout_secs: _, // applied during ProcWait
+            pty,
         } => {
             #[cfg(feature = "dev-shell")]
             {
                 let caps = mvm_guest::process_rpc::Caps::production();
-                GuestResponse::ProcResult(mvm_guest::process_rpc::handle_proc_start(
+                match mvm_guest::process_rpc::handle_proc_start(
                     proc_registry(),
                     &caps,
                     &argv,
                     &env,
                     cwd.as_deref(),
                     &stdin,
-                ))
+                    pty,
+                ) {
+                    mvm_guest::vsock::ProcResult::PtyStarted {
+                        pid_token,
+                        data_port,
+                    } => open_proc_pty(pid_token, data_port),
+                    other => GuestResponse::ProcResult(other),
+                }
             }
             #[cfg(not(feature = "dev-shell"))]
             {
-                let _ = (argv, env, cwd, stdin);
+                let _ = (argv, env, cwd, stdin, pty);
                 GuestResponse::ProcResult(mvm_guest::vsock::ProcResult::Error {
                     kind: mvm_guest::vsock::ProcErrorKind::UnsupportedInProduction,
                     message:
@@ -2506,6 +2560,31 @@ fn handle_client(
                 })
             }
         }
+        GuestRequest::ProcResize {
+            pid_token,
+            cols,
+            rows,
+        } => {
+            #[cfg(feature = "dev-shell")]
+            {
+                GuestResponse::ProcResult(mvm_guest::process_rpc::handle_proc_resize(
+                    proc_registry(),
+                    &pid_token,
+                    cols,
+                    rows,
+                ))
+            }
+            #[cfg(not(feature = "dev-shell"))]
+            {
+                let _ = (pid_token, cols, rows);
+                GuestResponse::ProcResult(mvm_guest::vsock::ProcResult::Error {
+                    kind: mvm_guest::vsock::ProcErrorKind::UnsupportedInProduction,
+                    message:
+                        "process control not available: guest agent built without dev-shell feature"
+                            .to_string(),
+                })
+            }
+        }
         GuestRequest::ProcWait {
             pid_token,
             timeout_secs,
@@ -2568,9 +2647,31 @@ fn handle_client(
         },
     };
 
+    if let (Some(req), GuestResponse::FsResult(result)) = (&download, &resp) {
+        fs_quota().settle(req, result);
+    }
     write_response(&mut file, &resp);
 }
 
+/// FS quota singleton, built on first use from the bundle limits
+/// `/init` staged at `GUEST_FS_QUOTA_PATH`, falling back to the
+/// security policy's `fs_quota` section and then the default limits.
+fn fs_quota() -> &'static mvm_guest::fs_quota::FsQuotaGate {
+    static GATE: OnceLock<mvm_guest::fs_quota::FsQuotaGate> = OnceLock::new();
+    GATE.get_or_init(|| {
+        let staged = std::path::Path::new(mvm_guest::fs_quota::GUEST_FS_QUOTA_PATH);
+        let policy = mvm_guest::fs_quota::staged_policy(staged)
+            .or_else(|| {
+                mvm_guest::builder_agent::load_security_policy()
+                    .ok()
+                    .flatten()
+                    .map(|p| p.fs_quota)
+            })
+            .unwrap_or_default();
+        mvm_guest::fs_quota::FsQuotaGate::new(&policy)
+    })
+}
+
 // ============================================================================
 // Vsock → TCP port forwarders
 // ============================================================================
@@ -2583,6 +2684,146 @@ fn handle_client(
 /// this constant rather than reach for `0.0.0.0` or a configurable host.
 const PORT_FORWARD_TCP_HOST: &str = "127.0.0.1";
 
+/// How long a one-shot data port (FS stream, PTY) waits for the host
+/// to connect before it is given up.
+const DATA_PORT_ACCEPT_TIMEOUT_MS: i32 = 30_000;
+
+/// Bind and listen on a one-shot vsock data port. Returns the
+/// listening fd. Callers bind before answering the control request so
+/// the host can connect as soon as it reads the response.
+fn bind_data_port(port: u32) -> Option<i32> {
+    // SAFETY: libc call with constant arguments.
+    let fd = unsafe { socket(AF_VSOCK, SOCK_STREAM, 0) };
+    let addr = SockAddrVm {
+        svm_family: AF_VSOCK as u16,
+        svm_reserved1: 0,
+        svm_port: port,
+        svm_cid: VMADDR_CID_ANY,
+        svm_zero: [0; 4],
+    };
+    // SAFETY: valid pointer and size; fd checked before use.
+    let bound = fd >= 0
+        && unsafe {
+            bind(
+                fd,
+                &addr as *const SockAddrVm as *const core::ffi::c_void,
+                size_of::<SockAddrVm>() as u32,
+            )
+        } == 0
+        && unsafe { listen(fd, 1) } == 0;
+    if !bound {
+        if fd >= 0 {
+            unsafe {
+                close(fd);
+            }
+        }
+        return None;
+    }
+    Some(fd)
+}
+
+/// Accept the single connection a data port serves, then close the
+/// listener. `None` when the host does not connect in time.
+fn accept_data_conn(fd: i32) -> Option<std::os::unix::net::UnixStream> {
+    let mut pfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // SAFETY: one valid pollfd.
+    let ready = unsafe { libc::poll(&mut pfd, 1, DATA_PORT_ACCEPT_TIMEOUT_MS) } > 0;
+    // SAFETY: null addr pointers are fine when we don't need peer info.
+    let cfd = if ready {
+        unsafe { accept(fd, std::ptr::null_mut(), std::ptr::null_mut()) }
+    } else {
+        -1
+    };
+    unsafe {
+        close(fd);
+    }
+    if cfd < 0 {
+        return None;
+    }
+    // SAFETY: cfd is a valid fd from accept(); UnixStream is a thin
+    // fd wrapper that works for vsock sockets.
+    Some(unsafe { std::os::unix::net::UnixStream::from_raw_fd(cfd as RawFd) })
+}
+
+/// Bind the data port for a planned FS stream and serve it on a
+/// background thread.
+fn open_fs_stream(
+    plan: Result<mvm_guest::fs_stream::StreamPlan, mvm_guest::vsock::FsResult>,
+) -> GuestResponse {
+    use mvm_guest::vsock::{FsErrorKind, FsResult};
+
+    let plan = match plan {
+        Ok(plan) => plan,
+        Err(err) => return GuestResponse::FsResult(err),
+    };
+    let slot = match mvm_guest::fs_stream::StreamSlot::acquire(
+        &mvm_guest::fs_stream::Caps::production(),
+    ) {
+        Ok(slot) => slot,
+        Err(err) => return GuestResponse::FsResult(err),
+    };
+    let Some(fd) = bind_data_port(slot.data_port) else {
+        return GuestResponse::FsResult(FsResult::Error {
+            kind: FsErrorKind::IoError,
+            message: format!("failed to bind vsock port {}", slot.data_port),
+        });
+    };
+
+    let opened = FsResult::StreamOpened {
+        stream_id: slot.stream_id,
+        data_port: slot.data_port,
+        offset: plan.offset(),
+        total_size: plan.total_size(),
+    };
+    std::thread::spawn(move || {
+        // Move the whole slot in (not just `data_port`) so it stays
+        // counted until the stream ends.
+        let slot = slot;
+        let Some(mut conn) = accept_data_conn(fd) else {
+            eprintln!(
+                "fs-stream: no host connection on vsock port {}",
+                slot.data_port
+            );
+            return;
+        };
+        mvm_guest::fs_stream::serve_with_defaults(plan, &mut conn);
+    });
+    GuestResponse::FsResult(opened)
+}
+
+/// Bind the data port of a freshly started PTY process and relay it on
+/// a background thread. The process is killed if the port can't be
+/// bound or the host never connects, so no terminal is left orphaned.
+#[cfg(feature = "dev-shell")]
+fn open_proc_pty(pid_token: String, data_port: u32) -> GuestResponse {
+    use mvm_guest::vsock::{ProcErrorKind, ProcResult};
+
+    let Some(fd) = bind_data_port(data_port) else {
+        let _ = mvm_guest::process_rpc::handle_proc_kill(proc_registry(), &pid_token);
+        return GuestResponse::ProcResult(ProcResult::Error {
+            kind: ProcErrorKind::Other,
+            message: format!("failed to bind vsock port {data_port}"),
+        });
+    };
+    let token = pid_token.clone();
+    std::thread::spawn(move || {
+        let Some(conn) = accept_data_conn(fd) else {
+            eprintln!("proc-pty: no host connection on vsock port {data_port}");
+            let _ = mvm_guest::process_rpc::handle_proc_kill(proc_registry(), &token);
+            return;
+        };
+        mvm_guest::process_rpc::serve_pty(proc_registry(), &token, conn);
+    });
+    GuestResponse::ProcResult(ProcResult::PtyStarted {
+        pid_token,
+        data_port,
+    })
+}
+
 /// Bind a vsock listener and forward each connection to a local TCP port.
 fn run_port_forwarder(vsock_port: u32, tcp_port: u16) {
     // SAFETY: libc call with constant arguments.
@@ -12,10 +12,9 @@
 //!
 //! ## Exit codes
 //!
-//! - 0 — every IPv4 entry installed successfully (or the only
-//!   failures were on entries the kernel doesn't support, which is
-//!   surfaced in the report's `failed` array with `reason` carrying
-//!   the kernel message).
+//! - 0 — every entry installed successfully. On a kernel without an
+//!   IPv6 stack the IPv6 entries are skipped (listed in the report's
+//!   `skipped_ipv6`, with `ipv6: "no_ipv6_stack"`), not failed.
 //! - 1 — one or more routes failed to install. `/init` should
 //!   fail-closed and refuse to fork the workload.
 //! - 2 — could not connect to rtnetlink (kernel built without
@@ -35,6 +34,10 @@
 //! startup logs) doesn't bury the line — `grep
 //! '__MVM_NETINIT_REPORT__'` is the canonical extraction.
 //!
+//! The same JSON is written to
+//! [`mvm_guest::netinit::REPORT_PATH`] so the agent can report the
+//! installed set in its `ReadinessReport`.
+//!
 //! ## Platform
 //!
 //! Linux-only: the module gates on `#[cfg(target_os = "linux")]`.
@@ -76,6 +79,13 @@ async fn main() {
         }
     }
 
+    // Best-effort: the console line above is the audit contract; the
+    // file only feeds the agent's readiness snapshot.
+    let path = std::path::Path::new(mvm_guest::netinit::REPORT_PATH);
+    if let Err(e) = mvm_guest::netinit::write_report(&report, path) {
+        eprintln!("mvm-guest-netinit: write {}: {e}", path.display());
+    }
+
     if report.has_failures() {
         // Exit 1: per-route failures recorded in the report. /init
         // reads the JSON to surface which entries failed.
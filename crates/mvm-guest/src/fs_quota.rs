@@ -0,0 +1,166 @@
+//! Agent-side filesystem RPC quota — the backstop half of Layer 3 of
+//! the DoS model.
+//!
+//! The host meters every FS verb per VM before dispatch; the agent
+//! meters them again here so a host that skips the host meter still
+//! can't hammer the guest disk. Limits come from the policy bundle's
+//! `[fs_quota]` section, which `mvmctl up` writes to the config drive
+//! as [`FS_QUOTA_DRIVE_FILE`] and mkGuest's `/init` stages at
+//! [`GUEST_FS_QUOTA_PATH`]. A guest booted without one falls back to
+//! the `fs_quota` section of its security policy, then to
+//! `FsQuotaPolicy::default()`. The meter lives for one agent boot, so
+//! `session_bytes` is a per-boot budget.
+//!
+//! A rejected call is answered with `FsResult::Backpressure` and never
+//! reaches `fs_rpc` / `fs_stream`.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use mvm_core::security::FsQuotaPolicy;
+use mvm_security::quota_meter::QuotaMeter;
+
+use crate::vsock::{FsResult, GuestRequest};
+
+/// File name of the bundle's `[fs_quota]` limits (JSON) on the VM's
+/// `mvm-config` drive.
+pub const FS_QUOTA_DRIVE_FILE: &str = "fs-quota.json";
+
+/// Where mkGuest's `/init` copies [`FS_QUOTA_DRIVE_FILE`] before the
+/// agent starts.
+pub const GUEST_FS_QUOTA_PATH: &str = "/run/mvm/fs-quota.json";
+
+/// The limits staged at `path`, or `None` when nothing (or nothing
+/// parseable) was staged.
+pub fn staged_policy(path: &Path) -> Option<FsQuotaPolicy> {
+    let bytes = std::fs::read(path).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(policy) => Some(policy),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "ignoring unparseable FS quota");
+            None
+        }
+    }
+}
+
+pub struct FsQuotaGate {
+    meter: Mutex<QuotaMeter>,
+}
+
+impl FsQuotaGate {
+    pub fn new(policy: &FsQuotaPolicy) -> Self {
+        Self {
+            meter: Mutex::new(QuotaMeter::new(policy)),
+        }
+    }
+
+    /// Meter `req`. Returns the response to send instead of
+    /// dispatching when the quota rejects it; `None` for an admitted
+    /// call or a verb that isn't FS RPC.
+    pub fn admit(&self, req: &GuestRequest) -> Option<FsResult> {
+        let usage = req.fs_usage()?;
+        let mut meter = self.meter.lock().unwrap_or_else(|e| e.into_inner());
+        let exceeded = meter.check_and_record(usage).err()?;
+        Some(FsResult::Backpressure {
+            reason: exceeded.reason(),
+            detail: exceeded.detail(meter.policy()),
+        })
+    }
+
+    /// Charge what a dispatched call turned out to move. Only a
+    /// download's size is unknown at admission time.
+    pub fn settle(&self, req: &GuestRequest, result: &FsResult) {
+        if let (
+            GuestRequest::FsDownloadStream { .. },
+            FsResult::StreamOpened {
+                offset, total_size, ..
+            },
+        ) = (req, result)
+        {
+            let mut meter = self.meter.lock().unwrap_or_else(|e| e.into_inner());
+            meter.record_streamed(total_size.saturating_sub(*offset));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mvm_core::domain::instance::BackpressureReason;
+
+    #[test]
+    fn staged_policy_reads_the_bundle_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FS_QUOTA_DRIVE_FILE);
+        assert_eq!(staged_policy(&path), None);
+
+        let policy = FsQuotaPolicy {
+            session_bytes: 4096,
+            ..FsQuotaPolicy::default()
+        };
+        std::fs::write(&path, serde_json::to_vec(&policy).unwrap()).unwrap();
+        assert_eq!(staged_policy(&path), Some(policy));
+
+        std::fs::write(&path, b"not json").unwrap();
+        assert_eq!(staged_policy(&path), None);
+    }
+
+    fn stat() -> GuestRequest {
+        GuestRequest::FsStat {
+            path: "/tmp".to_string(),
+            follow_symlinks: true,
+        }
+    }
+
+    fn download() -> GuestRequest {
+        GuestRequest::FsDownloadStream {
+            path: "/tmp/big".to_string(),
+            offset: 0,
+            follow_symlinks: true,
+        }
+    }
+
+    #[test]
+    fn rejects_with_backpressure_once_ops_limit_is_hit() {
+        let gate = FsQuotaGate::new(&FsQuotaPolicy {
+            ops_per_second: 2,
+            ..FsQuotaPolicy::unlimited()
+        });
+        assert!(gate.admit(&stat()).is_none());
+        assert!(gate.admit(&stat()).is_none());
+        match gate.admit(&stat()) {
+            Some(FsResult::Backpressure { reason, detail }) => {
+                assert_eq!(reason, BackpressureReason::FsRateLimited);
+                assert_eq!(detail, "fs ops limit 2/s");
+            }
+            other => panic!("expected backpressure, got {other:?}"),
+        }
+        // Non-FS verbs are never metered.
+        assert!(gate.admit(&GuestRequest::Ping).is_none());
+    }
+
+    #[test]
+    fn download_size_is_charged_to_the_session_budget() {
+        let gate = FsQuotaGate::new(&FsQuotaPolicy {
+            session_bytes: 1000,
+            ..FsQuotaPolicy::unlimited()
+        });
+        assert!(gate.admit(&download()).is_none());
+        gate.settle(
+            &download(),
+            &FsResult::StreamOpened {
+                stream_id: 1,
+                data_port: 30_001,
+                offset: 0,
+                total_size: 1000,
+            },
+        );
+        match gate.admit(&download()) {
+            Some(FsResult::Backpressure { reason, .. }) => {
+                assert_eq!(reason, BackpressureReason::FsSessionQuotaExhausted);
+            }
+            other => panic!("expected backpressure, got {other:?}"),
+        }
+        assert!(gate.admit(&stat()).is_none());
+    }
+}
@@ -15,15 +15,14 @@
 //! `/etc/mvm/*` and `/run/mvm-secrets/*` even when canonicalization
 //! resolves a guest-side symlink into them.
 //!
-//! # What this module does NOT do (yet)
+//! # What this module does NOT do
 //!
 //! - Streaming (FsUploadStream / FsDownloadStream / FsWatch). Those
-//!   land in W2 and use a dedicated vsock port, separate from this
-//!   single-shot RPC dispatch.
-//! - Inotify-based watches.
+//!   live in [`crate::fs_stream`] and move bytes over a dedicated
+//!   vsock data port; they reuse this module's path validation.
 //! - Per-VM rate-limiting of FS calls (Layer 3 of the DoS model).
-//!   The supervisor's QuotaMeter will own that and feed the handler
-//!   a `RateLimitGuard`.
+//!   The host meters each VM before dispatch and the agent backstops
+//!   it in [`crate::fs_quota`], ahead of this handler.
 
 use std::path::Path;
 
@@ -263,7 +262,7 @@ fn count_entries_capped(root: &Path, cap: u64) -> std::io::Result<u64> {
     Ok(count)
 }
 
-fn map_io_error_kind(err: &std::io::Error) -> FsErrorKind {
+pub(crate) fn map_io_error_kind(err: &std::io::Error) -> FsErrorKind {
     use std::io::ErrorKind as K;
     match err.kind() {
         K::NotFound => FsErrorKind::NotFound,
@@ -280,7 +279,7 @@ fn map_io_error_kind(err: &std::io::Error) -> FsErrorKind {
     }
 }
 
-fn map_policy_error(err: &PolicyError) -> FsErrorKind {
+pub(crate) fn map_policy_error(err: &PolicyError) -> FsErrorKind {
     match err {
         PolicyError::Empty | PolicyError::NotAbsolute { .. } | PolicyError::EmbeddedNul { .. } => {
             FsErrorKind::BadPath
@@ -292,7 +291,7 @@ fn map_policy_error(err: &PolicyError) -> FsErrorKind {
     }
 }
 
-fn err_result(kind: FsErrorKind, message: impl Into<String>) -> FsResult {
+pub(crate) fn err_result(kind: FsErrorKind, message: impl Into<String>) -> FsResult {
     FsResult::Error {
         kind,
         message: message.into(),
@@ -452,7 +451,7 @@ pub fn handle_request<C: PathCanonicalizer, F: FsOps>(
 /// Without the walk-up, `mkdir -p /a/b/c` fails when `/a` doesn't
 /// exist yet because we'd try to canonicalize `/a/b` (the leaf's
 /// parent) and that doesn't exist either.
-fn validate_parent_for_write<C: PathCanonicalizer>(
+pub(crate) fn validate_parent_for_write<C: PathCanonicalizer>(
     policy: &PathPolicy,
     canonicalizer: &C,
     raw: &str,
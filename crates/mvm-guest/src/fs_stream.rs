@@ -0,0 +1,1473 @@
+//! Streaming filesystem transfers and watches — W2 of the
+//! filesystem-volumes plan.
+//!
+//! `FsRead` / `FsWrite` are single-shot and capped (16 MiB / 1 MiB)
+//! because the whole payload rides one JSON control frame. The three
+//! streaming verbs split control from data the way the console does:
+//! `FsUploadStream`, `FsDownloadStream` and `FsWatch` are opened on the
+//! authenticated control channel (so the profile gate, `PathPolicy`
+//! and the host-side vsock RPC audit all apply), and the agent answers
+//! `FsResult::StreamOpened` with a one-shot data port in
+//! `FS_STREAM_PORT_BASE..+FS_STREAM_PORT_SPAN`. Bytes then flow over
+//! that port as binary frames — no JSON-encoded byte arrays, no
+//! per-frame signing.
+//!
+//! # Data-port frames
+//!
+//! ```text
+//! tag: u8 | len: u32 BE | payload
+//! ```
+//!
+//! - `DATA`   raw bytes, at most [`CHUNK_SIZE`]
+//! - `END`    32-byte SHA-256 of the *whole* file
+//! - `STATUS` JSON [`FsStreamStatus`] — the agent's verdict on an
+//!   upload, or a download/watch failure
+//! - `EVENT`  JSON [`FsWatchEvent`]
+//!
+//! # Resume and verification
+//!
+//! Uploads stage into a sibling `.<name>.mvm-upload-<digest prefix>`
+//! file. The staging name is keyed by the declared SHA-256, so
+//! re-issuing the same upload after a dropped connection picks up at
+//! the staged length (`StreamOpened::offset`) and a different file
+//! never resumes someone else's bytes. On `END` the agent re-hashes the
+//! staged file from disk and only renames it onto the target when the
+//! digest matches; a mismatch discards the staging file.
+//!
+//! Downloads start at the caller's `offset` and end with the digest of
+//! the whole file, so a host resuming into a partial file hashes its
+//! prefix plus the new bytes and gets an end-to-end check.
+//!
+//! # Watches
+//!
+//! Linux inotify; `IN_CLOSE_WRITE` is reported as `modified` so a
+//! large write surfaces once rather than per `write(2)`. Recursive
+//! watches add every subdirectory the `PathPolicy` allows (new ones
+//! included) up to [`Caps::max_watch_dirs`]. The watch ends when the
+//! host closes the data connection.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use anyhow::{Context, Result, bail};
+use mvm_security::policy::{OsCanonicalizer, PathCanonicalizer, PathOp, PathPolicy, PolicyError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::fs_rpc::{err_result, map_io_error_kind, map_policy_error, validate_parent_for_write};
+use crate::vsock::{FS_STREAM_PORT_BASE, FS_STREAM_PORT_SPAN, FsErrorKind, FsResult};
+
+/// Maximum payload of a single `DATA` frame.
+pub const CHUNK_SIZE: usize = 128 * 1024;
+
+/// Maximum payload of a JSON `STATUS` / `EVENT` frame. Watch events
+/// carry one path; statuses carry a short message.
+const MAX_CONTROL_FRAME: usize = 64 * 1024;
+
+const TAG_DATA: u8 = 1;
+const TAG_END: u8 = 2;
+const TAG_STATUS: u8 = 3;
+const TAG_EVENT: u8 = 4;
+
+/// Per-agent streaming caps. Production agent wires
+/// `Caps::production()`; tests construct tighter caps.
+#[derive(Debug, Clone, Copy)]
+pub struct Caps {
+    /// Largest file an upload may declare or a download may serve.
+    pub max_stream_bytes: u64,
+    /// Directories a single recursive watch may register.
+    pub max_watch_dirs: usize,
+    /// Streams (of any kind) open at once across the agent.
+    pub max_open_streams: usize,
+}
+
+impl Caps {
+    pub const fn production() -> Self {
+        Self {
+            max_stream_bytes: 4 * 1024 * 1024 * 1024,
+            max_watch_dirs: 1024,
+            max_open_streams: 8,
+        }
+    }
+}
+
+impl Default for Caps {
+    fn default() -> Self {
+        Self::production()
+    }
+}
+
+/// Terminal status frame. Closed enum with `deny_unknown_fields`, same
+/// discipline as `FsResult`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum FsStreamStatus {
+    /// Upload verified and renamed onto the target.
+    Complete { bytes: u64, sha256: String },
+    /// The stream failed; nothing was committed.
+    Error { kind: FsErrorKind, message: String },
+}
+
+/// Kind of change reported by `FsWatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsWatchEventKind {
+    Created,
+    /// A file opened for writing was closed.
+    Modified,
+    Removed,
+    /// Renamed away from `path`.
+    MovedFrom,
+    /// Renamed onto `path`.
+    MovedTo,
+    /// The kernel queue overflowed; events were lost. `path` is the
+    /// watch root.
+    Overflow,
+}
+
+/// One change reported by `FsWatch`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FsWatchEvent {
+    pub kind: FsWatchEventKind,
+    /// Absolute guest path of the entry that changed.
+    pub path: String,
+    /// `true` when the entry is a directory.
+    #[serde(default)]
+    pub is_dir: bool,
+}
+
+/// A download's bytes did not hash to the digest the agent sent.
+/// Typed so a host resuming into a partial file can tell "discard the
+/// partial" apart from "the connection dropped, retry from here".
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "download checksum mismatch: agent sent sha256 {expected}, received bytes hash to {actual}"
+)]
+pub struct ChecksumMismatch {
+    pub expected: String,
+    pub actual: String,
+}
+
+/// One frame on a streaming data port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    Data(Vec<u8>),
+    End([u8; 32]),
+    Status(FsStreamStatus),
+    Event(FsWatchEvent),
+}
+
+/// Write one frame.
+pub fn write_frame<W: Write>(w: &mut W, frame: &Frame) -> std::io::Result<()> {
+    let json;
+    let (tag, payload): (u8, &[u8]) = match frame {
+        Frame::Data(bytes) => (TAG_DATA, bytes),
+        Frame::End(digest) => (TAG_END, digest),
+        Frame::Status(status) => {
+            json = serde_json::to_vec(status)?;
+            (TAG_STATUS, &json)
+        }
+        Frame::Event(event) => {
+            json = serde_json::to_vec(event)?;
+            (TAG_EVENT, &json)
+        }
+    };
+    let mut header = [0u8; 5];
+    header[0] = tag;
+    header[1..].copy_from_slice(&(payload.len() as u32).to_be_bytes());
+    w.write_all(&header)?;
+    w.write_all(payload)?;
+    w.flush()
+}
+
+/// Read one frame. `Ok(None)` on a clean EOF at a frame boundary.
+pub fn read_frame<R: Read>(r: &mut R) -> std::io::Result<Option<Frame>> {
+    let mut header = [0u8; 5];
+    match r.read_exact(&mut header[..1]) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    r.read_exact(&mut header[1..])?;
+    let tag = header[0];
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    let limit = match tag {
+        TAG_DATA => CHUNK_SIZE,
+        TAG_END => 32,
+        TAG_STATUS | TAG_EVENT => MAX_CONTROL_FRAME,
+        other => return Err(invalid_data(format!("unknown fs_stream frame tag {other}"))),
+    };
+    if len > limit {
+        return Err(invalid_data(format!(
+            "fs_stream frame of {len} bytes exceeds {limit} for tag {tag}"
+        )));
+    }
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload)?;
+    Ok(Some(match tag {
+        TAG_DATA => Frame::Data(payload),
+        TAG_END => Frame::End(
+            payload
+                .try_into()
+                .map_err(|_| invalid_data("END frame must carry a 32-byte digest".to_string()))?,
+        ),
+        TAG_STATUS => Frame::Status(serde_json::from_slice(&payload)?),
+        _ => Frame::Event(serde_json::from_slice(&payload)?),
+    }))
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// SHA-256 and length of everything `r` yields.
+pub fn sha256_reader<R: Read>(r: &mut R) -> std::io::Result<(u64, [u8; 32])> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+    loop {
+        let n = r.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        total += n as u64;
+    }
+    Ok((total, hasher.finalize().into()))
+}
+
+fn status_error(kind: FsErrorKind, message: impl Into<String>) -> FsStreamStatus {
+    FsStreamStatus::Error {
+        kind,
+        message: message.into(),
+    }
+}
+
+// ============================================================================
+// Agent side: planning
+// ============================================================================
+
+/// Internal request shape, borrowed from the wire `GuestRequest`
+/// variants — same role as `fs_rpc::FsRequest`.
+pub enum StreamRequest<'a> {
+    Upload {
+        path: &'a str,
+        size: u64,
+        sha256: &'a str,
+        mode: u32,
+        create_parents: bool,
+    },
+    Download {
+        path: &'a str,
+        offset: u64,
+    },
+    Watch {
+        path: &'a str,
+        recursive: bool,
+    },
+}
+
+/// A validated upload: every path has been through `PathPolicy`.
+#[derive(Debug, Clone)]
+pub struct UploadPlan {
+    target: PathBuf,
+    staging: PathBuf,
+    size: u64,
+    sha256: [u8; 32],
+    mode: u32,
+    create_parents: bool,
+    offset: u64,
+}
+
+/// A validated download.
+#[derive(Debug, Clone)]
+pub struct DownloadPlan {
+    source: PathBuf,
+    offset: u64,
+    total_size: u64,
+}
+
+/// A validated watch.
+#[derive(Debug, Clone)]
+pub struct WatchPlan {
+    root: PathBuf,
+    recursive: bool,
+}
+
+/// What the data-port thread will do once the host connects.
+#[derive(Debug, Clone)]
+pub enum StreamPlan {
+    Upload(UploadPlan),
+    Download(DownloadPlan),
+    Watch(WatchPlan),
+}
+
+impl StreamPlan {
+    /// Offset the transfer starts at (`StreamOpened::offset`).
+    pub fn offset(&self) -> u64 {
+        match self {
+            Self::Upload(p) => p.offset,
+            Self::Download(p) => p.offset,
+            Self::Watch(_) => 0,
+        }
+    }
+
+    /// File size (`StreamOpened::total_size`).
+    pub fn total_size(&self) -> u64 {
+        match self {
+            Self::Upload(p) => p.size,
+            Self::Download(p) => p.total_size,
+            Self::Watch(_) => 0,
+        }
+    }
+}
+
+/// Validate a streaming request and work out where it starts.
+/// Read-only: nothing is created until the host connects.
+pub fn plan<C: PathCanonicalizer>(
+    policy: &PathPolicy,
+    canonicalizer: &C,
+    caps: &Caps,
+    request: StreamRequest<'_>,
+) -> Result<StreamPlan, FsResult> {
+    let policy_err = |e: PolicyError| err_result(map_policy_error(&e), e.to_string());
+    match request {
+        StreamRequest::Upload {
+            path,
+            size,
+            sha256,
+            mode,
+            create_parents,
+        } => {
+            if size > caps.max_stream_bytes {
+                return Err(err_result(
+                    FsErrorKind::CapExceeded,
+                    format!(
+                        "size {size} exceeds max_stream_bytes {}",
+                        caps.max_stream_bytes
+                    ),
+                ));
+            }
+            let digest = parse_digest(sha256).ok_or_else(|| {
+                err_result(
+                    FsErrorKind::Other,
+                    "sha256 must be 64 lowercase hex characters",
+                )
+            })?;
+            // Same leaf-then-parent dance as `FsWrite`.
+            let target = match policy.validate(canonicalizer, path, PathOp::Write) {
+                Ok(c) => c,
+                Err(PolicyError::CanonicalizationFailed { .. }) => {
+                    validate_parent_for_write(policy, canonicalizer, path).map_err(policy_err)?
+                }
+                Err(e) => return Err(policy_err(e)),
+            };
+            let target = target.as_path().to_path_buf();
+            let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+                return Err(err_result(
+                    FsErrorKind::BadPath,
+                    "upload target has no parent",
+                ));
+            };
+            let staging = parent.join(format!(
+                ".{}.mvm-upload-{}",
+                name.to_string_lossy(),
+                &sha256[..16]
+            ));
+            // A staged length beyond `size` can't be a prefix of this
+            // file; serve_upload truncates it back to zero.
+            let offset = match std::fs::symlink_metadata(&staging) {
+                Ok(m) if m.is_file() && m.len() <= size => m.len(),
+                _ => 0,
+            };
+            Ok(StreamPlan::Upload(UploadPlan {
+                target,
+                staging,
+                size,
+                sha256: digest,
+                mode,
+                create_parents,
+                offset,
+            }))
+        }
+        StreamRequest::Download { path, offset } => {
+            let source = policy
+                .validate(canonicalizer, path, PathOp::Read)
+                .map_err(policy_err)?;
+            let meta = std::fs::metadata(source.as_path())
+                .map_err(|e| err_result(map_io_error_kind(&e), e.to_string()))?;
+            if !meta.is_file() {
+                return Err(err_result(
+                    FsErrorKind::BadPath,
+                    "download source is not a regular file",
+                ));
+            }
+            if meta.len() > caps.max_stream_bytes {
+                return Err(err_result(
+                    FsErrorKind::CapExceeded,
+                    format!(
+                        "file is {} bytes, above max_stream_bytes {}",
+                        meta.len(),
+                        caps.max_stream_bytes
+                    ),
+                ));
+            }
+            if offset > meta.len() {
+                return Err(err_result(
+                    FsErrorKind::Other,
+                    format!(
+                        "offset {offset} is past the end of a {}-byte file",
+                        meta.len()
+                    ),
+                ));
+            }
+            Ok(StreamPlan::Download(DownloadPlan {
+                source: source.as_path().to_path_buf(),
+                offset,
+                total_size: meta.len(),
+            }))
+        }
+        StreamRequest::Watch { path, recursive } => {
+            if !cfg!(target_os = "linux") {
+                return Err(err_result(
+                    FsErrorKind::Unsupported,
+                    "FsWatch requires inotify (Linux guests only)",
+                ));
+            }
+            let root = policy
+                .validate(canonicalizer, path, PathOp::List)
+                .map_err(policy_err)?;
+            Ok(StreamPlan::Watch(WatchPlan {
+                root: root.as_path().to_path_buf(),
+                recursive,
+            }))
+        }
+    }
+}
+
+/// `plan` with `OsCanonicalizer`, production caps and the default
+/// `PathPolicy`. Used by the agent dispatch arm.
+pub fn plan_with_defaults(request: StreamRequest<'_>) -> Result<StreamPlan, FsResult> {
+    plan(
+        &PathPolicy::default(),
+        &OsCanonicalizer,
+        &Caps::production(),
+        request,
+    )
+}
+
+fn parse_digest(hex_digest: &str) -> Option<[u8; 32]> {
+    if hex_digest.len() != 64 || hex_digest.bytes().any(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    hex::decode(hex_digest).ok()?.try_into().ok()
+}
+
+// ============================================================================
+// Agent side: slots and serving
+// ============================================================================
+
+static NEXT_STREAM_ID: AtomicU32 = AtomicU32::new(0);
+static OPEN_STREAMS: AtomicUsize = AtomicUsize::new(0);
+
+/// Reservation of one concurrent stream and its data port. Dropping
+/// it frees the slot, so the agent holds it for the lifetime of the
+/// data-port thread.
+#[derive(Debug)]
+pub struct StreamSlot {
+    pub stream_id: u32,
+    pub data_port: u32,
+}
+
+impl StreamSlot {
+    /// Reserve a slot, or `CapExceeded` when `max_open_streams` are
+    /// already in flight.
+    pub fn acquire(caps: &Caps) -> Result<Self, FsResult> {
+        let reserved = OPEN_STREAMS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+            (n < caps.max_open_streams).then_some(n + 1)
+        });
+        if reserved.is_err() {
+            return Err(err_result(
+                FsErrorKind::CapExceeded,
+                format!("{} streams already open", caps.max_open_streams),
+            ));
+        }
+        let stream_id = NEXT_STREAM_ID.fetch_add(1, Ordering::SeqCst) % FS_STREAM_PORT_SPAN;
+        Ok(Self {
+            stream_id,
+            data_port: FS_STREAM_PORT_BASE + stream_id,
+        })
+    }
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        OPEN_STREAMS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Serve `plan` on an accepted data connection. Errors are reported
+/// to the host as `STATUS` frames; a dropped upload leaves its staging
+/// file in place for a resumed retry.
+pub fn serve<C, S>(
+    policy: &PathPolicy,
+    canonicalizer: &C,
+    caps: &Caps,
+    plan: StreamPlan,
+    stream: &mut S,
+) where
+    C: PathCanonicalizer,
+    S: Read + Write + AsRawFd,
+{
+    let status = match plan {
+        StreamPlan::Upload(p) => serve_upload(&p, stream),
+        StreamPlan::Download(p) => serve_download(&p, stream),
+        StreamPlan::Watch(p) => serve_watch(policy, canonicalizer, caps, &p, stream),
+    };
+    if let Some(status) = status {
+        let _ = write_frame(stream, &Frame::Status(status));
+    }
+}
+
+/// `serve` with the same defaults as [`plan_with_defaults`].
+pub fn serve_with_defaults<S: Read + Write + AsRawFd>(plan: StreamPlan, stream: &mut S) {
+    serve(
+        &PathPolicy::default(),
+        &OsCanonicalizer,
+        &Caps::production(),
+        plan,
+        stream,
+    )
+}
+
+fn io_status(e: &std::io::Error) -> FsStreamStatus {
+    status_error(map_io_error_kind(e), e.to_string())
+}
+
+/// Open (or create) the staging file without following a symlink at
+/// its name, then check that the fd is a single-link regular file that
+/// still sits at `plan.staging` under the canonical parent the plan was
+/// validated against. Closes the window between `plan`'s
+/// `symlink_metadata` probe and this open.
+fn open_staging(plan: &UploadPlan) -> std::io::Result<std::fs::File> {
+    use std::io::{Error, ErrorKind};
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+
+    let refuse = |msg: &str| Error::new(ErrorKind::PermissionDenied, msg.to_string());
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true)
+        .read(true)
+        .create(true)
+        .mode(0o600)
+        .custom_flags(libc::O_NOFOLLOW);
+    let file = opts.open(&plan.staging)?;
+    let opened = file.metadata()?;
+    if !opened.is_file() {
+        return Err(refuse("upload staging path is not a regular file"));
+    }
+    // A hard link to a file elsewhere would be truncated by `set_len`.
+    if opened.nlink() != 1 {
+        return Err(refuse("upload staging file has other links"));
+    }
+    let parent = plan
+        .staging
+        .parent()
+        .ok_or_else(|| refuse("upload staging path has no parent"))?;
+    if std::fs::canonicalize(parent)? != parent {
+        return Err(refuse("upload staging parent moved after validation"));
+    }
+    let at_path = std::fs::symlink_metadata(&plan.staging)?;
+    if at_path.dev() != opened.dev() || at_path.ino() != opened.ino() {
+        return Err(refuse("upload staging file was replaced while opening"));
+    }
+    Ok(file)
+}
+
+/// Receive an upload. `None` means the host went away before `END`;
+/// the staging file is kept so the next open resumes from it.
+fn serve_upload<S: Read>(plan: &UploadPlan, stream: &mut S) -> Option<FsStreamStatus> {
+    if plan.create_parents
+        && let Some(parent) = plan.target.parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        return Some(io_status(&e));
+    }
+    let mut file = match open_staging(plan) {
+        Ok(f) => f,
+        Err(e) => return Some(io_status(&e)),
+    };
+    if let Err(e) = file
+        .set_len(plan.offset)
+        .and_then(|()| file.seek(SeekFrom::End(0)).map(|_| ()))
+    {
+        return Some(io_status(&e));
+    }
+
+    let discard = |status: FsStreamStatus| {
+        let _ = std::fs::remove_file(&plan.staging);
+        Some(status)
+    };
+    let mut written = plan.offset;
+    loop {
+        let frame = match read_frame(stream) {
+            Ok(Some(frame)) => frame,
+            // Dropped mid-transfer: keep the staged prefix.
+            Ok(None) | Err(_) => return None,
+        };
+        match frame {
+            Frame::Data(bytes) => {
+                if written + bytes.len() as u64 > plan.size {
+                    return discard(status_error(
+                        FsErrorKind::CapExceeded,
+                        format!("upload exceeds its declared size of {} bytes", plan.size),
+                    ));
+                }
+                if let Err(e) = file.write_all(&bytes) {
+                    return Some(io_status(&e));
+                }
+                written += bytes.len() as u64;
+            }
+            Frame::End(digest) => {
+                if digest != plan.sha256 {
+                    return discard(status_error(
+                        FsErrorKind::ChecksumMismatch,
+                        "END digest does not match the digest the upload was opened with",
+                    ));
+                }
+                if written != plan.size {
+                    return discard(status_error(
+                        FsErrorKind::IoError,
+                        format!("upload ended at {written} of {} bytes", plan.size),
+                    ));
+                }
+                return Some(commit_upload(plan, file));
+            }
+            Frame::Status(_) | Frame::Event(_) => {
+                return discard(status_error(
+                    FsErrorKind::Other,
+                    "unexpected frame on an upload stream",
+                ));
+            }
+        }
+    }
+}
+
+/// Re-hash the staged bytes from disk and rename onto the target.
+fn commit_upload(plan: &UploadPlan, mut file: std::fs::File) -> FsStreamStatus {
+    let verified = file
+        .sync_all()
+        .and_then(|()| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| sha256_reader(&mut file));
+    drop(file);
+    let fail = |status| {
+        let _ = std::fs::remove_file(&plan.staging);
+        status
+    };
+    match verified {
+        Ok((len, digest)) if len == plan.size && digest == plan.sha256 => {}
+        Ok(_) => {
+            return fail(status_error(
+                FsErrorKind::ChecksumMismatch,
+                "staged bytes do not hash to the declared sha256",
+            ));
+        }
+        Err(e) => return fail(io_status(&e)),
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) =
+            std::fs::set_permissions(&plan.staging, std::fs::Permissions::from_mode(plan.mode))
+        {
+            return fail(io_status(&e));
+        }
+    }
+    if let Err(e) = std::fs::rename(&plan.staging, &plan.target) {
+        return fail(io_status(&e));
+    }
+    FsStreamStatus::Complete {
+        bytes: plan.size,
+        sha256: hex::encode(plan.sha256),
+    }
+}
+
+/// Send a download: `DATA` from the offset, then `END` with the
+/// whole-file digest. Returns a status only on failure.
+fn serve_download<S: Write>(plan: &DownloadPlan, stream: &mut S) -> Option<FsStreamStatus> {
+    let mut file = match std::fs::File::open(&plan.source) {
+        Ok(f) => f,
+        Err(e) => return Some(io_status(&e)),
+    };
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    // Hash the prefix the host already holds.
+    let mut prefix = (&mut file).take(plan.offset);
+    loop {
+        match prefix.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(e) => return Some(io_status(&e)),
+        }
+    }
+    loop {
+        let n = match file.read(&mut buf) {
+            Ok(n) => n,
+            Err(e) => return Some(io_status(&e)),
+        };
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        if write_frame(stream, &Frame::Data(buf[..n].to_vec())).is_err() {
+            return None;
+        }
+    }
+    let _ = write_frame(stream, &Frame::End(hasher.finalize().into()));
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn serve_watch<C, S>(
+    policy: &PathPolicy,
+    canonicalizer: &C,
+    caps: &Caps,
+    plan: &WatchPlan,
+    stream: &mut S,
+) -> Option<FsStreamStatus>
+where
+    C: PathCanonicalizer,
+    S: Write + AsRawFd,
+{
+    let mut watcher = match inotify::Watcher::new() {
+        Ok(w) => w,
+        Err(e) => return Some(io_status(&e)),
+    };
+    let admit = |dir: &Path| {
+        policy
+            .validate(canonicalizer, &dir.display().to_string(), PathOp::List)
+            .is_ok()
+    };
+    if let Err(status) = watcher.add_tree(&plan.root, plan.recursive, caps.max_watch_dirs, &admit) {
+        return Some(status);
+    }
+    loop {
+        let events = match watcher.wait(stream.as_raw_fd()) {
+            Ok(Some(events)) => events,
+            // Host closed the data connection.
+            Ok(None) => return None,
+            Err(e) => return Some(io_status(&e)),
+        };
+        for event in events {
+            if plan.recursive
+                && event.is_dir
+                && matches!(
+                    event.kind,
+                    FsWatchEventKind::Created | FsWatchEventKind::MovedTo
+                )
+                && let Err(status) =
+                    watcher.add_tree(Path::new(&event.path), true, caps.max_watch_dirs, &admit)
+            {
+                return Some(status);
+            }
+            let event = match event.kind {
+                FsWatchEventKind::Overflow => FsWatchEvent {
+                    path: plan.root.display().to_string(),
+                    ..event
+                },
+                _ => event,
+            };
+            if write_frame(stream, &Frame::Event(event)).is_err() {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn serve_watch<C, S>(
+    _policy: &PathPolicy,
+    _canonicalizer: &C,
+    _caps: &Caps,
+    _plan: &WatchPlan,
+    _stream: &mut S,
+) -> Option<FsStreamStatus>
+where
+    C: PathCanonicalizer,
+    S: Write + AsRawFd,
+{
+    Some(status_error(
+        FsErrorKind::Unsupported,
+        "FsWatch requires inotify (Linux guests only)",
+    ))
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::collections::HashMap;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    use super::{FsStreamStatus, FsWatchEvent, FsWatchEventKind, status_error};
+    use crate::vsock::FsErrorKind;
+
+    const MASK: u32 = libc::IN_CREATE
+        | libc::IN_CLOSE_WRITE
+        | libc::IN_DELETE
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO
+        | libc::IN_DELETE_SELF;
+
+    /// Thin owner of an inotify fd and its watch-descriptor map.
+    pub(super) struct Watcher {
+        fd: i32,
+        dirs: HashMap<i32, PathBuf>,
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            // SAFETY: `fd` is owned by this struct.
+            unsafe {
+                libc::close(self.fd);
+            }
+        }
+    }
+
+    impl Watcher {
+        pub(super) fn new() -> std::io::Result<Self> {
+            // SAFETY: plain syscall, no pointers.
+            let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(Self {
+                fd,
+                dirs: HashMap::new(),
+            })
+        }
+
+        fn add(&mut self, dir: &Path) -> std::io::Result<()> {
+            let c = CString::new(dir.as_os_str().as_bytes())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            // SAFETY: `c` is a valid NUL-terminated path.
+            let wd = unsafe { libc::inotify_add_watch(self.fd, c.as_ptr(), MASK) };
+            if wd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            self.dirs.insert(wd, dir.to_path_buf());
+            Ok(())
+        }
+
+        /// Watch `root` and, when `recursive`, every admitted
+        /// subdirectory below it (symlinks are not followed).
+        pub(super) fn add_tree(
+            &mut self,
+            root: &Path,
+            recursive: bool,
+            max_dirs: usize,
+            admit: &dyn Fn(&Path) -> bool,
+        ) -> Result<(), FsStreamStatus> {
+            let mut stack = vec![root.to_path_buf()];
+            while let Some(dir) = stack.pop() {
+                if self.dirs.len() >= max_dirs {
+                    return Err(status_error(
+                        FsErrorKind::CapExceeded,
+                        format!("watch exceeds max_watch_dirs {max_dirs}"),
+                    ));
+                }
+                if let Err(e) = self.add(&dir) {
+                    // The root must be watchable; a subdirectory that
+                    // vanished mid-walk is not an error.
+                    if dir == root {
+                        return Err(super::io_status(&e));
+                    }
+                    continue;
+                }
+                if !recursive {
+                    continue;
+                }
+                let Ok(entries) = std::fs::read_dir(&dir) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if entry.file_type().is_ok_and(|t| t.is_dir()) && admit(&path) {
+                        stack.push(path);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Block until inotify has events or `peer_fd` is readable /
+        /// hung up. `Ok(None)` means the peer went away.
+        pub(super) fn wait(&mut self, peer_fd: i32) -> std::io::Result<Option<Vec<FsWatchEvent>>> {
+            loop {
+                let mut fds = [
+                    libc::pollfd {
+                        fd: self.fd,
+                        events: libc::POLLIN,
+                        revents: 0,
+                    },
+                    libc::pollfd {
+                        fd: peer_fd,
+                        events: libc::POLLIN,
+                        revents: 0,
+                    },
+                ];
+                // SAFETY: `fds` is a valid array of two pollfds.
+                let rc = unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) };
+                if rc < 0 {
+                    let err = std::io::Error::last_os_error();
+                    if err.kind() == std::io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(err);
+                }
+                // The host never writes on a watch stream, so any
+                // readability (EOF) or hang-up means it disconnected.
+                if fds[1].revents != 0 {
+                    return Ok(None);
+                }
+                if fds[0].revents & libc::POLLIN != 0 {
+                    return self.drain().map(Some);
+                }
+            }
+        }
+
+        fn drain(&mut self) -> std::io::Result<Vec<FsWatchEvent>> {
+            let mut buf = [0u8; 64 * 1024];
+            // SAFETY: `buf` is writable for its full length.
+            let n = unsafe { libc::read(self.fd, buf.as_mut_ptr().cast(), buf.len()) };
+            if n < 0 {
+                let err = std::io::Error::last_os_error();
+                if err.kind() == std::io::ErrorKind::WouldBlock {
+                    return Ok(Vec::new());
+                }
+                return Err(err);
+            }
+            Ok(self.parse(&buf[..n as usize]))
+        }
+
+        fn parse(&mut self, mut buf: &[u8]) -> Vec<FsWatchEvent> {
+            const HEADER: usize = std::mem::size_of::<libc::inotify_event>();
+            let mut out = Vec::new();
+            while buf.len() >= HEADER {
+                // SAFETY: at least HEADER bytes remain; the kernel
+                // writes whole records, read unaligned to be safe.
+                let ev: libc::inotify_event =
+                    unsafe { std::ptr::read_unaligned(buf.as_ptr().cast()) };
+                let end = (HEADER + ev.len as usize).min(buf.len());
+                let name = &buf[HEADER..end];
+                let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+                buf = &buf[end..];
+
+                if ev.mask & libc::IN_Q_OVERFLOW != 0 {
+                    out.push(FsWatchEvent {
+                        kind: FsWatchEventKind::Overflow,
+                        path: String::new(),
+                        is_dir: true,
+                    });
+                    continue;
+                }
+                if ev.mask & libc::IN_IGNORED != 0 {
+                    self.dirs.remove(&ev.wd);
+                    continue;
+                }
+                let Some(dir) = self.dirs.get(&ev.wd) else {
+                    continue;
+                };
+                let path = if name.is_empty() {
+                    dir.clone()
+                } else {
+                    dir.join(std::ffi::OsStr::from_bytes(name))
+                };
+                let kind = if ev.mask & libc::IN_CREATE != 0 {
+                    FsWatchEventKind::Created
+                } else if ev.mask & libc::IN_CLOSE_WRITE != 0 {
+                    FsWatchEventKind::Modified
+                } else if ev.mask & (libc::IN_DELETE | libc::IN_DELETE_SELF) != 0 {
+                    FsWatchEventKind::Removed
+                } else if ev.mask & libc::IN_MOVED_FROM != 0 {
+                    FsWatchEventKind::MovedFrom
+                } else if ev.mask & libc::IN_MOVED_TO != 0 {
+                    FsWatchEventKind::MovedTo
+                } else {
+                    continue;
+                };
+                out.push(FsWatchEvent {
+                    kind,
+                    path: path.display().to_string(),
+                    is_dir: ev.mask & libc::IN_ISDIR != 0,
+                });
+            }
+            out
+        }
+    }
+}
+
+// ============================================================================
+// Host side
+// ============================================================================
+
+/// Send an upload over an open data connection: `src` must already be
+/// positioned at `StreamOpened::offset`. Returns the agent's verdict.
+pub fn send_upload<S: Read + Write, R: Read>(
+    stream: &mut S,
+    src: &mut R,
+    sha256: [u8; 32],
+) -> Result<FsStreamStatus> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = src.read(&mut buf).context("reading upload source")?;
+        if n == 0 {
+            break;
+        }
+        write_frame(stream, &Frame::Data(buf[..n].to_vec())).context("sending upload chunk")?;
+    }
+    write_frame(stream, &Frame::End(sha256)).context("sending upload digest")?;
+    match read_frame(stream).context("reading upload status")? {
+        Some(Frame::Status(status)) => Ok(status),
+        Some(other) => bail!("unexpected frame after upload: {other:?}"),
+        None => bail!("agent closed the upload stream without a status"),
+    }
+}
+
+/// Receive a download into `dest`. `prefix` yields the bytes the host
+/// already holds (`StreamOpened::offset` of them; empty for a fresh
+/// download) so the whole-file digest can be checked. Returns the
+/// number of bytes received on this stream.
+pub fn receive_download<S: Read, P: Read, W: Write>(
+    stream: &mut S,
+    prefix: &mut P,
+    dest: &mut W,
+) -> Result<u64> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = prefix.read(&mut buf).context("hashing download prefix")?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let mut received = 0u64;
+    loop {
+        match read_frame(stream).context("reading download stream")? {
+            Some(Frame::Data(bytes)) => {
+                hasher.update(&bytes);
+                dest.write_all(&bytes).context("writing download chunk")?;
+                received += bytes.len() as u64;
+            }
+            Some(Frame::End(digest)) => {
+                let local: [u8; 32] = hasher.finalize().into();
+                if local != digest {
+                    return Err(ChecksumMismatch {
+                        expected: hex::encode(digest),
+                        actual: hex::encode(local),
+                    }
+                    .into());
+                }
+                dest.flush().context("flushing download")?;
+                return Ok(received);
+            }
+            Some(Frame::Status(FsStreamStatus::Error { kind, message })) => {
+                bail!("Guest FS stream error ({kind:?}): {message}")
+            }
+            Some(other) => bail!("unexpected frame in download stream: {other:?}"),
+            None => bail!("agent closed the download stream after {received} bytes"),
+        }
+    }
+}
+
+/// Read watch events until `on_event` returns `false` or the agent
+/// ends the stream.
+pub fn receive_watch<S: Read, F: FnMut(&FsWatchEvent) -> bool>(
+    stream: &mut S,
+    mut on_event: F,
+) -> Result<()> {
+    loop {
+        match read_frame(stream).context("reading watch stream")? {
+            Some(Frame::Event(event)) => {
+                if !on_event(&event) {
+                    return Ok(());
+                }
+            }
+            Some(Frame::Status(FsStreamStatus::Error { kind, message })) => {
+                bail!("Guest FS watch error ({kind:?}): {message}")
+            }
+            Some(other) => bail!("unexpected frame in watch stream: {other:?}"),
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    fn open_policy() -> PathPolicy {
+        PathPolicy::with_extra_deny::<[std::path::PathBuf; 0], std::path::PathBuf>([])
+    }
+
+    fn digest_of(bytes: &[u8]) -> [u8; 32] {
+        Sha256::digest(bytes).into()
+    }
+
+    fn plan_open(request: StreamRequest<'_>) -> StreamPlan {
+        plan(
+            &open_policy(),
+            &OsCanonicalizer,
+            &Caps::production(),
+            request,
+        )
+        .expect("plan")
+    }
+
+    /// Run the agent half on a thread and hand back the host half.
+    fn serve_on_pair(plan: StreamPlan) -> (UnixStream, std::thread::JoinHandle<()>) {
+        let (host, mut agent) = UnixStream::pair().expect("pair");
+        let handle = std::thread::spawn(move || {
+            serve(
+                &open_policy(),
+                &OsCanonicalizer,
+                &Caps::production(),
+                plan,
+                &mut agent,
+            );
+        });
+        (host, handle)
+    }
+
+    fn payload(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let frames = vec![
+            Frame::Data(vec![1, 2, 3]),
+            Frame::End([7; 32]),
+            Frame::Status(FsStreamStatus::Complete {
+                bytes: 3,
+                sha256: "ab".repeat(32),
+            }),
+            Frame::Event(FsWatchEvent {
+                kind: FsWatchEventKind::MovedTo,
+                path: "/work/a".to_string(),
+                is_dir: false,
+            }),
+        ];
+        let mut wire = Vec::new();
+        for frame in &frames {
+            write_frame(&mut wire, frame).expect("write");
+        }
+        let mut cursor = std::io::Cursor::new(wire);
+        for frame in &frames {
+            assert_eq!(read_frame(&mut cursor).expect("read").as_ref(), Some(frame));
+        }
+        assert_eq!(read_frame(&mut cursor).expect("eof"), None);
+    }
+
+    #[test]
+    fn read_frame_rejects_oversized_data() {
+        let mut wire = vec![TAG_DATA];
+        wire.extend_from_slice(&((CHUNK_SIZE + 1) as u32).to_be_bytes());
+        let err = read_frame(&mut std::io::Cursor::new(wire)).expect_err("oversized");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn upload_verifies_and_renames_onto_target() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = dir.path().join("nested/big.bin");
+        let content = payload(3 * CHUNK_SIZE + 17);
+        let digest = digest_of(&content);
+        let sha = hex::encode(digest);
+        let plan = plan_open(StreamRequest::Upload {
+            path: target.to_str().expect("utf8"),
+            size: content.len() as u64,
+            sha256: &sha,
+            mode: 0o640,
+            create_parents: true,
+        });
+        assert_eq!(plan.offset(), 0);
+
+        let (mut host, agent) = serve_on_pair(plan);
+        let status =
+            send_upload(&mut host, &mut content.as_slice(), digest).expect("upload status");
+        agent.join().expect("agent");
+
+        assert_eq!(
+            status,
+            FsStreamStatus::Complete {
+                bytes: content.len() as u64,
+                sha256: sha,
+            }
+        );
+        assert_eq!(std::fs::read(&target).expect("read back"), content);
+        let leftovers: Vec<_> = std::fs::read_dir(target.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().contains("mvm-upload"))
+            .collect();
+        assert!(leftovers.is_empty(), "staging file left behind");
+    }
+
+    #[test]
+    fn upload_resumes_from_staged_prefix() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = dir.path().join("resume.bin");
+        let content = payload(2 * CHUNK_SIZE + 5);
+        let digest = digest_of(&content);
+        let sha = hex::encode(digest);
+        let request = || StreamRequest::Upload {
+            path: target.to_str().expect("utf8"),
+            size: content.len() as u64,
+            sha256: &sha,
+            mode: 0o644,
+            create_parents: false,
+        };
+
+        // First attempt: one chunk, then the host drops.
+        let (mut host, agent) = serve_on_pair(plan_open(request()));
+        write_frame(&mut host, &Frame::Data(content[..CHUNK_SIZE].to_vec())).expect("chunk");
+        drop(host);
+        agent.join().expect("agent");
+        assert!(!target.exists());
+
+        // Re-issue: the agent resumes at the staged length.
+        let plan = plan_open(request());
+        assert_eq!(plan.offset(), CHUNK_SIZE as u64);
+        let (mut host, agent) = serve_on_pair(plan);
+        let status = send_upload(&mut host, &mut &content[CHUNK_SIZE..], digest).expect("status");
+        agent.join().expect("agent");
+
+        assert!(
+            matches!(status, FsStreamStatus::Complete { .. }),
+            "{status:?}"
+        );
+        assert_eq!(std::fs::read(&target).expect("read back"), content);
+    }
+
+    #[test]
+    fn upload_with_wrong_digest_is_discarded() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = dir.path().join("bad.bin");
+        let content = payload(1000);
+        let declared = digest_of(b"something else");
+        let sha = hex::encode(declared);
+        let plan = plan_open(StreamRequest::Upload {
+            path: target.to_str().expect("utf8"),
+            size: content.len() as u64,
+            sha256: &sha,
+            mode: 0o644,
+            create_parents: false,
+        });
+
+        let (mut host, agent) = serve_on_pair(plan);
+        let status = send_upload(&mut host, &mut content.as_slice(), declared).expect("status");
+        agent.join().expect("agent");
+
+        match status {
+            FsStreamStatus::Error { kind, .. } => assert_eq!(kind, FsErrorKind::ChecksumMismatch),
+            other => panic!("expected ChecksumMismatch, got {other:?}"),
+        }
+        assert!(!target.exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn upload_refuses_staging_name_swapped_after_plan() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let victim = dir.path().join("victim");
+        std::fs::write(&victim, b"keep me").expect("victim");
+        let target = dir.path().join("up.bin");
+        let content = payload(100);
+        let digest = digest_of(&content);
+        let sha = hex::encode(digest);
+        let request = || StreamRequest::Upload {
+            path: target.to_str().expect("utf8"),
+            size: content.len() as u64,
+            sha256: &sha,
+            mode: 0o644,
+            create_parents: false,
+        };
+        let staging = |plan: &StreamPlan| match plan {
+            StreamPlan::Upload(p) => p.staging.clone(),
+            other => panic!("expected an upload plan, got {other:?}"),
+        };
+
+        // A symlink and a hard link planted between plan and serve.
+        let plants: [fn(&Path, &Path) -> std::io::Result<()>; 2] = [
+            |v, s| std::os::unix::fs::symlink(v, s),
+            |v, s| std::fs::hard_link(v, s),
+        ];
+        for plant in plants {
+            let plan = plan_open(request());
+            let staged = staging(&plan);
+            plant(&victim, &staged).expect("plant");
+
+            // The agent refuses before reading any DATA.
+            let (mut host, agent) = serve_on_pair(plan);
+            let status = read_frame(&mut host).expect("status");
+            agent.join().expect("agent");
+
+            assert!(
+                matches!(status, Some(Frame::Status(FsStreamStatus::Error { .. }))),
+                "{status:?}"
+            );
+            assert_eq!(std::fs::read(&victim).expect("victim"), b"keep me");
+            assert!(!target.exists());
+            std::fs::remove_file(&staged).expect("cleanup");
+        }
+    }
+
+    #[test]
+    fn upload_plan_enforces_caps_and_digest_shape() {
+        let caps = Caps {
+            max_stream_bytes: 10,
+            ..Caps::production()
+        };
+        let sha = "00".repeat(32);
+        let upload = |size, sha256| StreamRequest::Upload {
+            path: "/tmp/x",
+            size,
+            sha256,
+            mode: 0o644,
+            create_parents: false,
+        };
+        match plan(&open_policy(), &OsCanonicalizer, &caps, upload(11, &sha)) {
+            Err(FsResult::Error { kind, .. }) => assert_eq!(kind, FsErrorKind::CapExceeded),
+            other => panic!("expected CapExceeded, got {other:?}"),
+        }
+        assert!(plan(&open_policy(), &OsCanonicalizer, &caps, upload(1, "abc")).is_err());
+    }
+
+    #[test]
+    fn download_resumes_with_whole_file_digest() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let source = dir.path().join("src.bin");
+        let content = payload(CHUNK_SIZE + 99);
+        std::fs::write(&source, &content).expect("seed");
+        let offset = 4096;
+        let plan = plan_open(StreamRequest::Download {
+            path: source.to_str().expect("utf8"),
+            offset,
+        });
+        assert_eq!(plan.total_size(), content.len() as u64);
+
+        let (mut host, agent) = serve_on_pair(plan);
+        let mut received = Vec::new();
+        let n = receive_download(&mut host, &mut &content[..offset as usize], &mut received)
+            .expect("download");
+        agent.join().expect("agent");
+
+        assert_eq!(n, content.len() as u64 - offset);
+        assert_eq!(received, &content[offset as usize..]);
+    }
+
+    #[test]
+    fn download_detects_a_corrupt_prefix() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let source = dir.path().join("src.bin");
+        std::fs::write(&source, b"hello world").expect("seed");
+        let plan = plan_open(StreamRequest::Download {
+            path: source.to_str().expect("utf8"),
+            offset: 5,
+        });
+
+        let (mut host, agent) = serve_on_pair(plan);
+        let err =
+            receive_download(&mut host, &mut &b"HELLO"[..], &mut Vec::new()).expect_err("mismatch");
+        agent.join().expect("agent");
+        assert!(err.is::<ChecksumMismatch>(), "{err}");
+    }
+
+    #[test]
+    fn download_plan_rejects_directories_and_denied_paths() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let request = StreamRequest::Download {
+            path: dir.path().to_str().expect("utf8"),
+            offset: 0,
+        };
+        match plan(
+            &open_policy(),
+            &OsCanonicalizer,
+            &Caps::production(),
+            request,
+        ) {
+            Err(FsResult::Error { kind, .. }) => assert_eq!(kind, FsErrorKind::BadPath),
+            other => panic!("expected BadPath, got {other:?}"),
+        }
+        match plan_with_defaults(StreamRequest::Download {
+            path: "/etc/mvm/keys",
+            offset: 0,
+        }) {
+            Err(FsResult::Error { kind, .. }) => {
+                assert!(matches!(
+                    kind,
+                    FsErrorKind::PolicyDenied | FsErrorKind::NotFound
+                ));
+            }
+            other => panic!("expected a refusal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stream_slots_are_capped() {
+        let caps = Caps {
+            max_open_streams: 0,
+            ..Caps::production()
+        };
+        assert!(StreamSlot::acquire(&caps).is_err());
+        let slot = StreamSlot::acquire(&Caps::production()).expect("slot");
+        assert!(
+            (FS_STREAM_PORT_BASE..FS_STREAM_PORT_BASE + FS_STREAM_PORT_SPAN)
+                .contains(&slot.data_port)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn watch_reports_creates_in_new_subdirectories() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonical");
+        let plan = plan_open(StreamRequest::Watch {
+            path: root.to_str().expect("utf8"),
+            recursive: true,
+        });
+        let (mut host, agent) = serve_on_pair(plan);
+        host.set_read_timeout(Some(std::time::Duration::from_secs(10)))
+            .expect("timeout");
+
+        // Give the agent a moment to register the root watch.
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        std::fs::create_dir(root.join("sub")).expect("mkdir");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        std::fs::write(root.join("sub/file.txt"), b"x").expect("write");
+
+        let mut seen = Vec::new();
+        receive_watch(&mut host, |event| {
+            seen.push(event.clone());
+            event.path != root.join("sub/file.txt").display().to_string()
+                || event.kind != FsWatchEventKind::Modified
+        })
+        .expect("watch");
+        drop(host);
+        agent.join().expect("agent");
+
+        assert!(
+            seen.iter()
+                .any(|e| e.kind == FsWatchEventKind::Created && e.is_dir)
+        );
+        assert!(seen.iter().any(|e| {
+            e.kind == FsWatchEventKind::Created
+                && e.path == root.join("sub/file.txt").display().to_string()
+        }));
+    }
+}
@@ -4,7 +4,9 @@
 pub mod builder_agent;
 pub mod console;
 pub mod entrypoint;
+pub mod fs_quota;
 pub mod fs_rpc;
+pub mod fs_stream;
 pub mod integrations;
 pub mod lifecycle_hooks;
 /// Plan 74 W2 — guest-side network defense. The `mvm-guest-netinit`
@@ -16,6 +18,9 @@ pub mod lifecycle_hooks;
 pub mod netinit;
 pub mod probes;
 pub mod runtime_config;
+/// Plan 104 — guest-side client for the host services broker
+/// (`host.time.v1`, `host.cost.v1`, `broker.v1`).
+pub mod services;
 pub mod volume;
 pub mod vsock;
 pub mod worker_pool;
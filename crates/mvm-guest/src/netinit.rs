@@ -1,8 +1,12 @@
 //! Plan 74 W2 — guest-side network defense.
 //!
-//! Installs kernel **blackhole routes** for every IPv4 entry in
-//! [`mvm_core::network_policy::MANDATORY_DENY_RANGES`] inside the
-//! microVM at boot, before the workload entrypoint forks.
+//! Installs kernel **blackhole routes** for every entry in
+//! [`mvm_core::network_policy::MANDATORY_DENY_RANGES`] — IPv4 and
+//! IPv6 — inside the microVM at boot, before the workload
+//! entrypoint forks. IPv6 entries are installed whenever the guest
+//! kernel has an IPv6 stack, so a backend that hands the guest a v6
+//! address can't open a path to link-local, ULA, IPv4-mapped or the
+//! IPv6 metadata endpoint.
 //!
 //! ## Why kernel routes (not nftables / iptables)
 //!
@@ -71,6 +75,31 @@ use serde::{Deserialize, Serialize};
 /// any reasonable log message.
 pub const REPORT_MARKER: &str = "__MVM_NETINIT_REPORT__";
 
+/// Where `mvm-guest-netinit` also writes its [`Report`], so the
+/// agent (which starts later, as uid 901) can surface the installed
+/// set in `ReadinessReport::mandatory_deny` without re-reading the
+/// kernel route table. Lives under `/run`, so it only ever
+/// describes the current boot.
+pub const REPORT_PATH: &str = "/run/mvm/netinit-report.json";
+
+/// Persist `report` to `path` as JSON, creating the parent
+/// directory. The file is world-readable (default umask) so the
+/// unprivileged agent can read it.
+pub fn write_report(report: &Report, path: &std::path::Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec(report).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Read a report written by [`write_report`]. `None` when the file
+/// is missing (netinit didn't run on this image) or unparseable.
+pub fn read_report(path: &std::path::Path) -> Option<Report> {
+    let bytes = std::fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
 /// Parse a console log buffer for the netinit report.
 ///
 /// Scans `log` line-by-line for [`REPORT_MARKER`]; the *last*
@@ -105,19 +134,51 @@ pub fn parse_report_from_console(log: &str) -> Option<Report> {
     last
 }
 
+/// Whether the IPv6 half of the deny set is in force. Reported
+/// alongside the per-route lists so the host can tell "v6 routes
+/// installed" from "the guest has no v6 stack to protect" without
+/// re-deriving it from the CIDRs.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Ipv6Deny {
+    /// Every IPv6 entry was attempted; the outcome of each is in
+    /// `installed` / `failed`.
+    Enforced,
+    /// The guest kernel has no IPv6 stack (`CONFIG_IPV6=n` or
+    /// `ipv6.disable=1`), so there is no v6 path to close. The
+    /// entries are listed in `skipped_ipv6`.
+    NoIpv6Stack,
+    /// The report came from a netinit that predates IPv6
+    /// enforcement. Default so older reports still parse.
+    #[default]
+    NotAttempted,
+}
+
+impl Ipv6Deny {
+    /// Stable snake_case key, used in the audit detail.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Enforced => "enforced",
+            Self::NoIpv6Stack => "no_ipv6_stack",
+            Self::NotAttempted => "not_attempted",
+        }
+    }
+}
+
 /// What was installed for a single CIDR.
 ///
 /// `category` is owned `String` (not `&'static str`) so the
 /// Deserialize impl works for round-trip from an audit-log
 /// reader. Construction at install time still uses string
-/// literals — `categorize_v4` returns `&'static str` and we
+/// literals — `category_for` returns `&'static str` and we
 /// `.to_string()` on insertion.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RouteInstalled {
     pub cidr: IpNet,
     /// The mvm category this CIDR belongs to. Mirrors the audit
     /// detail format in `LocalAuditKind::NetworkMandatoryDeny`:
-    /// `cloud-metadata` | `link-local` | `cgnat` | `loopback`.
+    /// `cloud-metadata` | `link-local` | `cgnat` | `loopback` |
+    /// `link-local-v6` | `unique-local` | `ipv4-mapped`.
     pub category: String,
 }
 
@@ -143,12 +204,14 @@ pub struct RouteFailed {
 pub struct Report {
     pub installed: Vec<RouteInstalled>,
     pub failed: Vec<RouteFailed>,
-    /// IPv6 entries from the const are intentionally skipped (the
-    /// guest's bridge / TAP is IPv4-only on every backend today).
-    /// Reported here so an operator parsing the JSON sees that the
-    /// v6 entries were deliberately not attempted, not silently
-    /// missing.
+    /// IPv6 entries that were not attempted because the guest
+    /// kernel has no IPv6 stack. Empty whenever `ipv6` is
+    /// [`Ipv6Deny::Enforced`].
+    #[serde(default)]
     pub skipped_ipv6: Vec<IpNet>,
+    /// Whether the IPv6 half of the deny set is in force.
+    #[serde(default)]
+    pub ipv6: Ipv6Deny,
 }
 
 impl Report {
@@ -157,6 +220,7 @@ impl Report {
             installed: Vec::new(),
             failed: Vec::new(),
             skipped_ipv6: Vec::new(),
+            ipv6: Ipv6Deny::NotAttempted,
         }
     }
 
@@ -178,6 +242,11 @@ pub trait RouteInstaller: Send + Sync {
     /// the correct semantics (the entry is the desired state, not
     /// a write-once operation).
     async fn install_blackhole(&self, cidr: IpNet) -> Result<(), String>;
+
+    /// `true` when the kernel has an IPv6 stack to route through.
+    /// Without one every v6 `install_blackhole` would fail, and
+    /// there is no v6 path to close anyway.
+    fn ipv6_available(&self) -> bool;
 }
 
 /// Categorize a CIDR for the audit category field. Pure function;
@@ -197,14 +266,19 @@ fn categorize(cidr: &IpNet) -> &'static str {
         "100.64.0.0/10" => "cgnat",
         "127.0.0.0/8" | "::1/128" => "loopback",
         "fe80::/10" => "link-local-v6",
+        "fd00:ec2::254/128" => "cloud-metadata",
+        "fc00::/7" => "unique-local",
+        // `Ipv6Addr` displays the mapped prefix in dotted form.
+        "::ffff:0.0.0.0/96" => "ipv4-mapped",
         _ => "other",
     }
 }
 
-/// Cloud metadata `/32` gets its own category for the audit detail
-/// so a security dashboard can alert on IMDS exfil attempts
+/// The IPv4 cloud metadata `/32` gets its own category for the
+/// audit detail (its IPv6 twin is matched in [`categorize`]) so a
+/// security dashboard can alert on IMDS exfil attempts
 /// distinctly from generic link-local probes.
-fn categorize_v4(cidr: &IpNet) -> &'static str {
+fn category_for(cidr: &IpNet) -> &'static str {
     if cidr.to_string() == "169.254.169.254/32" {
         "cloud-metadata"
     } else {
@@ -212,23 +286,30 @@ fn categorize_v4(cidr: &IpNet) -> &'static str {
     }
 }
 
-/// Install blackhole routes for every IPv4 entry in
-/// `MANDATORY_DENY_RANGES`. IPv6 entries are skipped (the guest
-/// network stack is v4-only on every backend today) and reported
-/// in `report.skipped_ipv6` so an operator can see they were
-/// deliberately not attempted.
+/// Install blackhole routes for every entry in
+/// `MANDATORY_DENY_RANGES`. IPv6 entries are installed when the
+/// kernel has an IPv6 stack; otherwise they are reported in
+/// `report.skipped_ipv6` with `report.ipv6 =
+/// Ipv6Deny::NoIpv6Stack`, so a v4-only guest doesn't fail-closed
+/// on routes it has no way to use.
 ///
 /// The loop is fault-tolerant: a per-route failure is recorded in
 /// `report.failed` but doesn't abort. Callers branch on
 /// `report.has_failures()` for the overall verdict.
 pub async fn install_mandatory_deny<I: RouteInstaller>(installer: &I) -> Report {
     let mut report = Report::empty();
+    let ipv6 = installer.ipv6_available();
+    report.ipv6 = if ipv6 {
+        Ipv6Deny::Enforced
+    } else {
+        Ipv6Deny::NoIpv6Stack
+    };
     for cidr in mvm_core::network_policy::mandatory_deny_ranges() {
-        if !cidr.network().is_ipv4() {
+        if cidr.network().is_ipv6() && !ipv6 {
             report.skipped_ipv6.push(cidr);
             continue;
         }
-        let category = categorize_v4(&cidr).to_string();
+        let category = category_for(&cidr).to_string();
         match installer.install_blackhole(cidr).await {
             Ok(()) => report.installed.push(RouteInstalled { cidr, category }),
             Err(reason) => report.failed.push(RouteFailed {
@@ -278,16 +359,16 @@ mod linux {
     #[async_trait]
     impl RouteInstaller for RtnetlinkInstaller {
         async fn install_blackhole(&self, cidr: IpNet) -> Result<(), String> {
-            // rtnetlink's v4 route builder takes the destination
+            // rtnetlink's route builders take the destination
             // prefix (address + length) and the
             // scope/protocol/kind fields. The `kind` field is what
             // makes it a blackhole — RTN_BLACKHOLE means "the
             // kernel drops packets matching this route without
             // sending ICMP unreachable", which is the strongest
             // form of "this destination is forbidden".
+            use netlink_packet_route::route::{RouteProtocol, RouteScope, RouteType};
             match cidr {
                 IpNet::V4(v4) => {
-                    use netlink_packet_route::route::{RouteProtocol, RouteScope, RouteType};
                     self.handle
                         .route()
                         .add()
@@ -300,19 +381,29 @@ mod linux {
                         .await
                         .map_err(|e| format!("route add {cidr}: {e}"))?;
                 }
-                IpNet::V6(_) => {
-                    // Should never reach here because
-                    // `install_mandatory_deny` skips v6 before
-                    // calling the installer; defending against a
-                    // future refactor.
-                    return Err(format!(
-                        "internal: install_blackhole called with IPv6 cidr {cidr} \
-                         (v6 not supported yet)"
-                    ));
+                IpNet::V6(v6) => {
+                    self.handle
+                        .route()
+                        .add()
+                        .v6()
+                        .destination_prefix(v6.network(), v6.prefix_len())
+                        .kind(RouteType::BlackHole)
+                        .scope(RouteScope::Universe)
+                        .protocol(RouteProtocol::Boot)
+                        .execute()
+                        .await
+                        .map_err(|e| format!("route add {cidr}: {e}"))?;
                 }
             }
             Ok(())
         }
+
+        fn ipv6_available(&self) -> bool {
+            // procfs only grows `if_inet6` when the IPv6 stack is
+            // loaded; `ipv6.disable=1` and `CONFIG_IPV6=n` both
+            // leave it absent.
+            std::path::Path::new("/proc/net/if_inet6").exists()
+        }
     }
 
     /// Convenience: connect to rtnetlink and run the install in
@@ -343,6 +434,7 @@ mod tests {
     struct MockInstaller {
         calls: Mutex<Vec<IpNet>>,
         fail_on: HashSet<IpNet>,
+        ipv6: bool,
     }
 
     impl MockInstaller {
@@ -350,9 +442,15 @@ mod tests {
             Self {
                 calls: Mutex::new(Vec::new()),
                 fail_on: HashSet::new(),
+                ipv6: true,
             }
         }
 
+        fn without_ipv6(mut self) -> Self {
+            self.ipv6 = false;
+            self
+        }
+
         fn fail_on(mut self, cidrs: &[&str]) -> Self {
             for s in cidrs {
                 self.fail_on.insert(s.parse().unwrap());
@@ -375,29 +473,53 @@ mod tests {
                 Ok(())
             }
         }
+
+        fn ipv6_available(&self) -> bool {
+            self.ipv6
+        }
     }
 
     #[tokio::test]
-    async fn install_calls_installer_for_every_ipv4_entry() {
+    async fn install_calls_installer_for_every_entry() {
         let mock = MockInstaller::new();
         let report = install_mandatory_deny(&mock).await;
-        // Every IPv4 entry in `MANDATORY_DENY_RANGES` should have
-        // exactly one call. Mirror the const's IPv4 entry count
-        // exactly so a future const edit that adds a v4 entry
-        // also has to update this test.
-        let v4_count = mvm_core::network_policy::mandatory_deny_ranges()
-            .iter()
-            .filter(|n| n.network().is_ipv4())
-            .count();
-        assert_eq!(mock.recorded().len(), v4_count);
-        assert_eq!(report.installed.len(), v4_count);
+        // Every entry in `MANDATORY_DENY_RANGES` — v4 and v6 —
+        // should have exactly one call when the kernel has IPv6.
+        let count = mvm_core::network_policy::mandatory_deny_ranges().len();
+        assert_eq!(mock.recorded().len(), count);
+        assert_eq!(report.installed.len(), count);
         assert!(report.failed.is_empty());
+        assert!(report.skipped_ipv6.is_empty());
+        assert_eq!(report.ipv6, Ipv6Deny::Enforced);
     }
 
     #[tokio::test]
-    async fn install_skips_ipv6_entries_and_reports_them() {
+    async fn install_covers_ipv6_metadata_ula_and_mapped_ranges() {
         let mock = MockInstaller::new();
         let report = install_mandatory_deny(&mock).await;
+        for (cidr, category) in [
+            ("fd00:ec2::254/128", "cloud-metadata"),
+            ("fc00::/7", "unique-local"),
+            ("::ffff:0:0/96", "ipv4-mapped"),
+            ("fe80::/10", "link-local-v6"),
+        ] {
+            let cidr: IpNet = cidr.parse().unwrap();
+            let entry = report
+                .installed
+                .iter()
+                .find(|r| r.cidr == cidr)
+                .unwrap_or_else(|| panic!("{cidr} must be in the installed set"));
+            assert_eq!(entry.category, category);
+        }
+    }
+
+    #[tokio::test]
+    async fn install_skips_ipv6_entries_without_an_ipv6_stack() {
+        let mock = MockInstaller::new().without_ipv6();
+        let report = install_mandatory_deny(&mock).await;
+        assert_eq!(report.ipv6, Ipv6Deny::NoIpv6Stack);
+        // A v4-only guest must not fail-closed on v6 routes.
+        assert!(!report.has_failures());
         let v6_count = mvm_core::network_policy::mandatory_deny_ranges()
             .iter()
             .filter(|n| !n.network().is_ipv4())
@@ -461,7 +583,7 @@ mod tests {
         let report = install_mandatory_deny(&mock).await;
         let json = serde_json::to_value(&report).unwrap();
         let obj = json.as_object().unwrap();
-        for key in ["installed", "failed", "skipped_ipv6"] {
+        for key in ["installed", "failed", "skipped_ipv6", "ipv6"] {
             assert!(obj.contains_key(key), "report JSON missing key {key}");
         }
         // Each installed entry has the documented field set.
@@ -471,6 +593,7 @@ mod tests {
             .expect("at least one installed entry in clean run");
         assert!(first.get("cidr").is_some());
         assert!(first.get("category").is_some());
+        assert_eq!(obj["ipv6"], "enforced");
     }
 
     // ────────────────────────────────────────────────────────────
@@ -492,6 +615,9 @@ mod tests {
         assert_eq!(report.installed[0].category, "cloud-metadata");
         assert_eq!(report.failed.len(), 1);
         assert_eq!(report.skipped_ipv6.len(), 1);
+        // Reports from a netinit that predates v6 enforcement carry
+        // no `ipv6` key and must still parse.
+        assert_eq!(report.ipv6, Ipv6Deny::NotAttempted);
     }
 
     #[test]
@@ -508,6 +634,16 @@ mod tests {
         assert_eq!(report.installed.len(), 1);
     }
 
+    #[tokio::test]
+    async fn report_file_roundtrips_and_missing_file_reads_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run/mvm/netinit-report.json");
+        assert!(read_report(&path).is_none());
+        let report = install_mandatory_deny(&MockInstaller::new()).await;
+        write_report(&report, &path).unwrap();
+        assert_eq!(read_report(&path), Some(report));
+    }
+
     #[test]
     fn parse_report_returns_none_when_no_marker() {
         let log = "kernel boot ... busybox ... agent up ... no report here";
@@ -571,16 +707,19 @@ mod tests {
     }
 
     #[test]
-    fn categorize_v4_handles_known_entries() {
+    fn category_for_handles_known_entries() {
         let cases = [
             ("169.254.169.254/32", "cloud-metadata"),
             ("169.254.0.0/16", "link-local"),
             ("100.64.0.0/10", "cgnat"),
             ("127.0.0.0/8", "loopback"),
+            ("fd00:ec2::254/128", "cloud-metadata"),
+            ("fc00::/7", "unique-local"),
+            ("::ffff:0:0/96", "ipv4-mapped"),
         ];
         for (s, expected) in cases {
             let cidr: IpNet = s.parse().unwrap();
-            assert_eq!(categorize_v4(&cidr), expected, "category for {s}");
+            assert_eq!(category_for(&cidr), expected, "category for {s}");
         }
     }
 }
@@ -9,10 +9,21 @@
 //! See doc comments on individual handlers for the security
 //! envelope (process_group(0), RLIMIT_CORE=0, env_clear,
 //! PathPolicy on cwd, argv\[0\] validation, PID-token indirection).
+//!
+//! A `ProcStart` with `pty` runs the child on a pseudo-terminal
+//! instead of pipes, under the same envelope: it becomes a session
+//! leader (which also makes it its own process group, so signals
+//! still reach the whole tree) with the PTY slave as its controlling
+//! terminal. The agent binary relays the master over a one-shot vsock
+//! data port via [`serve_pty`]; `ProcWait` still reports the exit
+//! status, and `ProcResize` changes the window size.
 
 use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
 use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
 use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -23,7 +34,10 @@ use std::os::unix::process::CommandExt;
 use mvm_core::domain::instance::BackpressureReason;
 use mvm_security::policy::{OsCanonicalizer, PathOp, PathPolicy};
 
-use crate::vsock::{ProcErrorKind, ProcInfo, ProcResult, ProcState, ProcWaitEvent};
+use crate::vsock::{
+    PROC_PTY_PORT_BASE, PROC_PTY_PORT_SPAN, ProcErrorKind, ProcInfo, ProcResult, ProcState,
+    ProcWaitEvent, PtySize,
+};
 
 // ============================================================================
 // Caps
@@ -76,6 +90,10 @@ struct ProcessRecord {
     child: Mutex<Option<Child>>,
     /// Stdin pipe held by the agent until ProcSendInput drops it.
     stdin: Mutex<Option<ChildStdin>>,
+    /// PTY master for processes started with `pty`. Input, output
+    /// and resizes all go through it; `stdin` and the output buffers
+    /// stay empty.
+    pty_master: Option<File>,
     /// Captured stdout. Background drain thread (holding an `Arc`
     /// clone) fills it; the wait path drains it.
     stdout_buf: Arc<Mutex<Vec<u8>>>,
@@ -186,10 +204,16 @@ fn fresh_token() -> String {
 ///   signal the whole tree.
 /// - `pre_exec` setting RLIMIT_CORE=0 — coredumps disabled before
 ///   the new image runs, no in-memory exfiltration via dumps.
+///
+/// With `pty`, the caller wires stdio to the PTY slave and the child
+/// calls `setsid()` + `TIOCSCTTY` instead of `process_group(0)` (a
+/// group leader cannot start a session, and the new session is its
+/// own process group anyway).
 fn build_command(
     argv: &[String],
     env: &BTreeMap<String, String>,
     cwd: Option<&str>,
+    pty: bool,
 ) -> Result<Command, (ProcErrorKind, String)> {
     if argv.is_empty() {
         return Err((ProcErrorKind::InvalidArgv, "argv is empty".to_string()));
@@ -242,11 +266,13 @@ fn build_command(
     cmd.stderr(Stdio::piped());
 
     #[cfg(unix)]
-    cmd.process_group(0);
+    if !pty {
+        cmd.process_group(0);
+    }
 
     #[cfg(unix)]
     unsafe {
-        cmd.pre_exec(|| {
+        cmd.pre_exec(move || {
             let lim = libc::rlimit {
                 rlim_cur: 0,
                 rlim_max: 0,
@@ -254,6 +280,14 @@ fn build_command(
             if libc::setrlimit(libc::RLIMIT_CORE, &lim) != 0 {
                 return Err(std::io::Error::last_os_error());
             }
+            // stdio is already the PTY slave by the time pre_exec
+            // hooks run, so fd 0 becomes the controlling terminal.
+            if pty
+                && (libc::setsid() < 0
+                    || libc::ioctl(0, libc::TIOCSCTTY as _, 0 as libc::c_int) != 0)
+            {
+                return Err(std::io::Error::last_os_error());
+            }
             Ok(())
         });
     }
@@ -261,6 +295,133 @@ fn build_command(
     Ok(cmd)
 }
 
+/// Allocate a PTY pair sized `size`. Returns `(master, slave)`.
+///
+/// Both fds are close-on-exec from the moment they exist: a
+/// `ProcStart` spawning on another agent thread must not inherit
+/// either, since a leaked slave keeps the master from ever seeing EIO
+/// and `serve_pty` would never finish. `openpty(3)` can't promise
+/// that, so the pair is opened by hand.
+fn open_pty(size: PtySize) -> std::io::Result<(File, File)> {
+    use std::os::fd::{AsRawFd, FromRawFd};
+    use std::os::unix::fs::OpenOptionsExt;
+
+    // SAFETY: plain syscall wrapper; the result is checked below.
+    let fd = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | PTMX_CLOEXEC) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: posix_openpt returned a fresh fd we now own.
+    let master = unsafe { File::from_raw_fd(fd) };
+    #[cfg(not(target_os = "linux"))]
+    set_cloexec(&master)?;
+    // SAFETY: grantpt/unlockpt on a valid PTY master fd.
+    if unsafe { libc::grantpt(master.as_raw_fd()) } != 0
+        || unsafe { libc::unlockpt(master.as_raw_fd()) } != 0
+    {
+        return Err(std::io::Error::last_os_error());
+    }
+    // std opens every file with O_CLOEXEC.
+    let slave = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY)
+        .open(pty_slave_name(&master)?)?;
+    set_window_size(&master, size)?;
+    Ok((master, slave))
+}
+
+/// `posix_openpt` flag that makes the master close-on-exec
+/// atomically. Only Linux (where the agent runs) accepts it; other
+/// hosts set it right after, with `set_cloexec`.
+#[cfg(target_os = "linux")]
+const PTMX_CLOEXEC: libc::c_int = libc::O_CLOEXEC;
+#[cfg(not(target_os = "linux"))]
+const PTMX_CLOEXEC: libc::c_int = 0;
+
+#[cfg(not(target_os = "linux"))]
+fn set_cloexec(file: &File) -> std::io::Result<()> {
+    // SAFETY: fcntl on a valid fd.
+    let rc = unsafe {
+        libc::fcntl(
+            std::os::fd::AsRawFd::as_raw_fd(file),
+            libc::F_SETFD,
+            libc::FD_CLOEXEC,
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Path of the slave side of PTY master `master`.
+#[cfg(target_os = "linux")]
+fn pty_slave_name(master: &File) -> std::io::Result<std::path::PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut buf = [0 as libc::c_char; 64];
+    // SAFETY: valid PTY master fd and a writable buffer of the given length.
+    let rc = unsafe {
+        libc::ptsname_r(
+            std::os::fd::AsRawFd::as_raw_fd(master),
+            buf.as_mut_ptr(),
+            buf.len(),
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::from_raw_os_error(rc));
+    }
+    // SAFETY: ptsname_r NUL-terminated the name inside `buf`.
+    let name = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+    Ok(std::ffi::OsStr::from_bytes(name.to_bytes()).into())
+}
+
+/// Path of the slave side of PTY master `master`. `ptsname_r` is
+/// Linux-only; elsewhere (host-side test builds) the static buffer
+/// is copied out at once.
+#[cfg(not(target_os = "linux"))]
+fn pty_slave_name(master: &File) -> std::io::Result<std::path::PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    // SAFETY: valid PTY master fd; the returned pointer is checked
+    // and copied immediately.
+    let name = unsafe { libc::ptsname(std::os::fd::AsRawFd::as_raw_fd(master)) };
+    if name.is_null() {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: non-null ptsname results are NUL-terminated.
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    Ok(std::ffi::OsStr::from_bytes(name.to_bytes()).into())
+}
+
+fn set_window_size(master: &File, size: PtySize) -> std::io::Result<()> {
+    let ws = libc::winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: ioctl on a valid PTY master fd with a valid winsize.
+    let rc = unsafe {
+        libc::ioctl(
+            std::os::fd::AsRawFd::as_raw_fd(master),
+            libc::TIOCSWINSZ as _,
+            &ws,
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+static PTY_PORT_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+fn next_pty_port() -> u32 {
+    PROC_PTY_PORT_BASE + PTY_PORT_COUNTER.fetch_add(1, Ordering::Relaxed) % PROC_PTY_PORT_SPAN
+}
+
 /// Spawn a drain thread that copies bytes from `reader` into `buf`,
 /// truncating at `cap` so a chatty child can't exhaust agent memory.
 fn spawn_drain<R: Read + Send + 'static>(mut reader: R, buf: Arc<Mutex<Vec<u8>>>, cap: usize) {
@@ -291,6 +452,9 @@ fn spawn_drain<R: Read + Send + 'static>(mut reader: R, buf: Arc<Mutex<Vec<u8>>>
 /// `ProcStart` handler — validates inputs, spawns the child with
 /// the security envelope, registers it, and returns the opaque
 /// `pid_token` the host uses for the rest of the process's life.
+///
+/// With `pty`, the result is `PtyStarted` carrying the data port the
+/// caller must bind and hand to [`serve_pty`].
 pub fn handle_proc_start(
     registry: &Registry,
     caps: &Caps,
@@ -298,6 +462,7 @@ pub fn handle_proc_start(
     env: &BTreeMap<String, String>,
     cwd: Option<&str>,
     initial_stdin: &[u8],
+    pty: Option<PtySize>,
 ) -> ProcResult {
     registry.gc_inplace(Instant::now());
 
@@ -308,12 +473,37 @@ pub fn handle_proc_start(
         };
     }
 
-    let mut cmd = match build_command(argv, env, cwd) {
+    let mut cmd = match build_command(argv, env, cwd, pty.is_some()) {
         Ok(c) => c,
         Err((kind, message)) => return ProcResult::Error { kind, message },
     };
 
-    let mut child = match cmd.spawn() {
+    let pty_master = match pty {
+        None => None,
+        Some(size) => {
+            let wired = open_pty(size).and_then(|(master, slave)| {
+                cmd.stdin(slave.try_clone()?);
+                cmd.stdout(slave.try_clone()?);
+                cmd.stderr(slave);
+                Ok(master)
+            });
+            match wired {
+                Ok(master) => Some(master),
+                Err(e) => {
+                    return ProcResult::Error {
+                        kind: ProcErrorKind::SpawnFailed,
+                        message: format!("openpty: {e}"),
+                    };
+                }
+            }
+        }
+    };
+
+    let spawned = cmd.spawn();
+    // Drop our copies of the PTY slave so the master sees EOF/EIO
+    // once the child (and anything it forked) exits.
+    drop(cmd);
+    let mut child = match spawned {
         Ok(c) => c,
         Err(e) => {
             return ProcResult::Error {
@@ -341,22 +531,135 @@ pub fn handle_proc_start(
         started_at,
         child: Mutex::new(Some(child)),
         stdin: Mutex::new(stdin),
+        pty_master,
         stdout_buf,
         stderr_buf,
         terminal: Mutex::new(None),
         reap_after: Mutex::new(None),
     });
 
-    if !initial_stdin.is_empty()
-        && let Some(ref mut s) = *record.stdin.lock().expect("stdin mutex")
-    {
-        let _ = s.write_all(initial_stdin);
+    if !initial_stdin.is_empty() {
+        if let Some(mut master) = record.pty_master.as_ref() {
+            let _ = master.write_all(initial_stdin);
+        } else if let Some(ref mut s) = *record.stdin.lock().expect("stdin mutex") {
+            let _ = s.write_all(initial_stdin);
+        }
     }
 
     let token = fresh_token();
+    let is_pty = record.pty_master.is_some();
     registry.insert(token.clone(), record);
 
-    ProcResult::Started { pid_token: token }
+    if is_pty {
+        ProcResult::PtyStarted {
+            pid_token: token,
+            data_port: next_pty_port(),
+        }
+    } else {
+        ProcResult::Started { pid_token: token }
+    }
+}
+
+/// `ProcResize` handler — sets the PTY window size; the kernel sends
+/// the child's process group `SIGWINCH`.
+pub fn handle_proc_resize(
+    registry: &Registry,
+    pid_token: &str,
+    cols: u16,
+    rows: u16,
+) -> ProcResult {
+    let Some(record) = registry.lookup(pid_token) else {
+        return ProcResult::Error {
+            kind: ProcErrorKind::UnknownToken,
+            message: format!("no such pid_token: {pid_token}"),
+        };
+    };
+    let Some(master) = record.pty_master.as_ref() else {
+        return ProcResult::Error {
+            kind: ProcErrorKind::Other,
+            message: "process was not started with a pty".to_string(),
+        };
+    };
+    match set_window_size(master, PtySize { cols, rows }) {
+        Ok(()) => ProcResult::Resized,
+        Err(e) => ProcResult::Error {
+            kind: ProcErrorKind::Other,
+            message: e.to_string(),
+        },
+    }
+}
+
+/// Relay raw bytes between the PTY master of `pid_token` and `conn`
+/// (the accepted data-port connection) until the child side closes.
+/// Blocks; the agent runs it on its own thread.
+///
+/// When the host hangs up first the process group gets `SIGHUP`, as
+/// it would when a terminal closes. Output produced while nobody is
+/// connected stays in the PTY buffer and the child blocks on write —
+/// there is no unbounded buffering in the agent.
+pub fn serve_pty(registry: &Registry, pid_token: &str, conn: UnixStream) {
+    let Some(record) = registry.lookup(pid_token) else {
+        return;
+    };
+    let Some(master) = record.pty_master.as_ref() else {
+        return;
+    };
+    let (Ok(mut pty_in), Ok(mut pty_out), Ok(mut conn_in)) =
+        (master.try_clone(), master.try_clone(), conn.try_clone())
+    else {
+        return;
+    };
+    let mut conn_out = conn;
+    let pgid = record
+        .child
+        .lock()
+        .expect("child mutex")
+        .as_ref()
+        .map(|c| c.id() as libc::pid_t);
+    drop(record);
+    let child_side_closed = Arc::new(AtomicBool::new(false));
+    let closed = Arc::clone(&child_side_closed);
+
+    // host → PTY
+    let input = thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match conn_in.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if pty_in.write_all(&buf[..n]).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+        // Only a host hang-up gets SIGHUP; after the child side closed
+        // the group may already be gone.
+        if let Some(pgid) = pgid
+            && !closed.load(Ordering::SeqCst)
+        {
+            // SAFETY: signalling the child's own process group.
+            unsafe {
+                libc::kill(-pgid, libc::SIGHUP);
+            }
+        }
+    });
+
+    // PTY → host. Reads fail with EIO once every slave fd is closed.
+    let mut buf = [0u8; 4096];
+    loop {
+        match pty_out.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if conn_out.write_all(&buf[..n]).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    child_side_closed.store(true, Ordering::SeqCst);
+    let _ = conn_out.shutdown(std::net::Shutdown::Both);
+    let _ = input.join();
 }
 
 /// `ProcList` handler.
@@ -428,6 +731,17 @@ pub fn handle_proc_send_input(
             message: format!("no such pid_token: {pid_token}"),
         };
     };
+    if let Some(mut master) = record.pty_master.as_ref() {
+        return match master.write_all(bytes) {
+            Ok(()) => ProcResult::InputAccepted {
+                bytes_accepted: bytes.len() as u64,
+            },
+            Err(e) => ProcResult::Error {
+                kind: ProcErrorKind::Other,
+                message: e.to_string(),
+            },
+        };
+    }
     let mut stdin_guard = record.stdin.lock().expect("stdin mutex");
     let Some(ref mut stdin) = *stdin_guard else {
         return ProcResult::Error {
@@ -657,14 +971,14 @@ mod tests {
     #[test]
     fn build_command_rejects_empty_argv() {
         let env = BTreeMap::new();
-        let err = build_command(&[], &env, None).unwrap_err();
+        let err = build_command(&[], &env, None, false).unwrap_err();
         assert_eq!(err.0, ProcErrorKind::InvalidArgv);
     }
 
     #[test]
     fn build_command_rejects_relative_argv0() {
         let env = BTreeMap::new();
-        let err = build_command(&["echo".to_string()], &env, None).unwrap_err();
+        let err = build_command(&["echo".to_string()], &env, None, false).unwrap_err();
         assert_eq!(err.0, ProcErrorKind::InvalidArgv);
     }
 
@@ -672,7 +986,7 @@ mod tests {
     fn build_command_rejects_env_with_eq_in_key() {
         let mut env = BTreeMap::new();
         env.insert("BAD=KEY".to_string(), "v".to_string());
-        let err = build_command(&["/bin/echo".to_string()], &env, None).unwrap_err();
+        let err = build_command(&["/bin/echo".to_string()], &env, None, false).unwrap_err();
         assert_eq!(err.0, ProcErrorKind::InvalidEnv);
     }
 
@@ -680,7 +994,7 @@ mod tests {
     fn build_command_rejects_env_with_nul() {
         let mut env = BTreeMap::new();
         env.insert("KEY".to_string(), "val\0ue".to_string());
-        let err = build_command(&["/bin/echo".to_string()], &env, None).unwrap_err();
+        let err = build_command(&["/bin/echo".to_string()], &env, None, false).unwrap_err();
         assert_eq!(err.0, ProcErrorKind::InvalidEnv);
     }
 
@@ -744,6 +1058,7 @@ mod tests {
             &BTreeMap::new(),
             None,
             &[],
+            None,
         );
         let token = match started {
             ProcResult::Started { pid_token } => pid_token,
@@ -784,6 +1099,7 @@ mod tests {
             &BTreeMap::new(),
             None,
             &[],
+            None,
         );
         let token = match started {
             ProcResult::Started { pid_token } => pid_token,
@@ -828,6 +1144,7 @@ mod tests {
             &BTreeMap::new(),
             None,
             &[],
+            None,
         );
         let token = match first {
             ProcResult::Started { pid_token } => pid_token,
@@ -841,6 +1158,7 @@ mod tests {
             &BTreeMap::new(),
             None,
             &[],
+            None,
         );
         match blocked {
             ProcResult::Error { kind, .. } => assert_eq!(kind, ProcErrorKind::CapExceeded),
@@ -864,6 +1182,7 @@ mod tests {
             &BTreeMap::new(),
             None,
             &[],
+            None,
         );
         let token = match started {
             ProcResult::Started { pid_token } => pid_token,
@@ -897,6 +1216,7 @@ mod tests {
             &BTreeMap::new(),
             None,
             &[],
+            None,
         );
         let token = match started {
             ProcResult::Started { pid_token } => pid_token,
@@ -910,6 +1230,111 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn proc_start_pty_relays_terminal_and_reports_exit() {
+        let reg = Registry::new();
+        let caps = small_caps();
+
+        let started = handle_proc_start(
+            &reg,
+            &caps,
+            &[
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "stty size; test -t 0 && echo is-a-tty; exit 3".to_string(),
+            ],
+            &BTreeMap::new(),
+            None,
+            &[],
+            Some(PtySize {
+                cols: 120,
+                rows: 40,
+            }),
+        );
+        let (token, data_port) = match started {
+            ProcResult::PtyStarted {
+                pid_token,
+                data_port,
+            } => (pid_token, data_port),
+            other => panic!("expected PtyStarted, got {other:?}"),
+        };
+        assert!((PROC_PTY_PORT_BASE..PROC_PTY_PORT_BASE + PROC_PTY_PORT_SPAN).contains(&data_port));
+
+        let (agent_end, mut host_end) = UnixStream::pair().expect("socketpair");
+        let relay = {
+            let reg = reg.clone();
+            let token = token.clone();
+            thread::spawn(move || serve_pty(&reg, &token, agent_end))
+        };
+        let mut out = Vec::new();
+        host_end.read_to_end(&mut out).expect("read relay");
+        relay.join().expect("relay thread");
+
+        let out = String::from_utf8_lossy(&out);
+        assert!(out.contains("40 120"), "unexpected pty output: {out:?}");
+        assert!(out.contains("is-a-tty"), "unexpected pty output: {out:?}");
+        let terminal = handle_proc_wait(&reg, &caps, &token, Some(5), |_| {});
+        assert!(
+            matches!(terminal, ProcWaitEvent::Exit { code: 3 }),
+            "expected Exit 3, got {terminal:?}"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn pty_pair_is_close_on_exec() {
+        use std::os::fd::AsRawFd;
+
+        let (master, slave) = open_pty(PtySize { cols: 80, rows: 24 }).expect("open pty");
+        for fd in [master.as_raw_fd(), slave.as_raw_fd()] {
+            // SAFETY: fcntl F_GETFD on a valid fd.
+            let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+            assert!(
+                flags >= 0 && flags & libc::FD_CLOEXEC != 0,
+                "fd {fd} flags {flags}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn proc_resize_requires_a_pty() {
+        let reg = Registry::new();
+        let caps = small_caps();
+        let start = |pty| match handle_proc_start(
+            &reg,
+            &caps,
+            &["/bin/sleep".to_string(), "30".to_string()],
+            &BTreeMap::new(),
+            None,
+            &[],
+            pty,
+        ) {
+            ProcResult::Started { pid_token } | ProcResult::PtyStarted { pid_token, .. } => {
+                pid_token
+            }
+            other => panic!("expected a start, got {other:?}"),
+        };
+
+        let piped = start(None);
+        match handle_proc_resize(&reg, &piped, 80, 24) {
+            ProcResult::Error { kind, .. } => assert_eq!(kind, ProcErrorKind::Other),
+            other => panic!("expected Error, got {other:?}"),
+        }
+        let tty = start(Some(PtySize { cols: 80, rows: 24 }));
+        assert_eq!(handle_proc_resize(&reg, &tty, 132, 50), ProcResult::Resized);
+        match handle_proc_resize(&reg, "no-such-token", 80, 24) {
+            ProcResult::Error { kind, .. } => assert_eq!(kind, ProcErrorKind::UnknownToken),
+            other => panic!("expected UnknownToken, got {other:?}"),
+        }
+
+        for token in [piped, tty] {
+            let _ = handle_proc_kill(&reg, &token);
+            let _ = handle_proc_wait(&reg, &caps, &token, Some(5), |_| {});
+        }
+    }
+
     // ---------------- Backpressure (ADR-053 §5 / plan 74 W4) ----------------
     //
     // Tests target `check_output_backpressure` directly. The unit
@@ -925,6 +1350,7 @@ mod tests {
             started_at: "2025-01-01T00:00:00Z".to_string(),
             child: Mutex::new(None),
             stdin: Mutex::new(None),
+            pty_master: None,
             stdout_buf: Arc::new(Mutex::new(vec![0u8; stdout_bytes])),
             stderr_buf: Arc::new(Mutex::new(vec![0u8; stderr_bytes])),
             terminal: Mutex::new(None),
@@ -0,0 +1,304 @@
+//! Guest-side client for the host services broker (Plan 104 §"Guest
+//! side").
+//!
+//! Speaks the broker's wire format — 4-byte big-endian length prefix +
+//! JSON `ServiceCall`, answered by one length-prefixed
+//! `ServiceResponse` — over any byte stream. The broker serves one call
+//! per connection, so [`BrokerClient`] holds a connect function rather
+//! than a stream and dials once per call. In a guest that function
+//! opens vsock [`BROKER_VSOCK_PORT`] to the host; tests pass a
+//! `UnixStream` pair.
+//!
+//! Typed helpers cover the built-in services:
+//!
+//! - [`BrokerClient::host_time`] — `host.time.v1::now` with a fresh
+//!   nonce; the signed reading is verified and the broker's key pinned
+//!   on first use.
+//! - [`BrokerClient::workload_cost`] — `host.cost.v1::workload`.
+//! - [`BrokerClient::list_services`] — `broker.v1::list_services`.
+
+use std::io::{self, Read, Write};
+
+use ed25519_dalek::VerifyingKey;
+use mvm_core::protocol::broker::{
+    CorrelationId, ServiceCall, ServiceErrorCode, ServiceId, ServiceResponse,
+};
+use mvm_core::protocol::broker_meta::{ListServicesRequest, ListServicesResponse};
+use mvm_core::protocol::host_cost::{WorkloadCostRequest, WorkloadCostResponse};
+use mvm_core::protocol::host_time::{
+    HostTimeError, NowRequest, NowResponse, TimeReading, verify_now_response,
+};
+use rand::RngCore;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+/// vsock port the broker listens on for guest calls (Plan 104
+/// §"Wire shape").
+pub const BROKER_VSOCK_PORT: u32 = 5300;
+
+/// Default cap on a response frame. Matches the broker's 64 KiB
+/// request cap and the handlers' default `response_size_cap`.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum BrokerClientError {
+    #[error("broker transport: {0}")]
+    Io(#[from] io::Error),
+    #[error("broker response frame too large: {len} > {max}")]
+    FrameTooLarge { len: usize, max: usize },
+    #[error("broker payload encode/decode failed: {0}")]
+    Codec(#[from] serde_json::Error),
+    /// The broker answered with a typed error.
+    #[error("broker refused call ({code:?}): {message}")]
+    Service {
+        code: ServiceErrorCode,
+        message: String,
+    },
+    #[error("invalid service id `{0}`")]
+    InvalidServiceId(String),
+    /// A `host.time.v1` reading failed verification.
+    #[error(transparent)]
+    Time(#[from] HostTimeError),
+}
+
+/// Broker client. `connect` opens a fresh stream to the broker.
+pub struct BrokerClient<C> {
+    connect: C,
+    max_response_bytes: usize,
+    time_key: Option<VerifyingKey>,
+}
+
+impl<C, S> BrokerClient<C>
+where
+    C: FnMut() -> io::Result<S>,
+    S: Read + Write,
+{
+    pub fn new(connect: C) -> Self {
+        Self {
+            connect,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            time_key: None,
+        }
+    }
+
+    /// Pin the `host.time.v1` signing key up front instead of trusting
+    /// the first response.
+    pub fn with_time_key(mut self, key: VerifyingKey) -> Self {
+        self.time_key = Some(key);
+        self
+    }
+
+    /// The pinned `host.time.v1` key, if any.
+    pub fn time_key(&self) -> Option<&VerifyingKey> {
+        self.time_key.as_ref()
+    }
+
+    /// Forget the pinned time key. Call after the host reports a broker
+    /// respawn; the next [`Self::host_time`] re-pins.
+    pub fn reset_time_key(&mut self) {
+        self.time_key = None;
+    }
+
+    /// One untyped call. Returns the response payload, or the broker's
+    /// typed error as [`BrokerClientError::Service`].
+    pub fn call(
+        &mut self,
+        service: &str,
+        verb: &str,
+        payload: serde_json::Value,
+    ) -> Result<serde_json::Value, BrokerClientError> {
+        let call = ServiceCall {
+            service: ServiceId::parse(service)
+                .map_err(|_| BrokerClientError::InvalidServiceId(service.to_string()))?,
+            verb: verb.to_string(),
+            // The supervisor assigns the authoritative id (H-L4.6); this
+            // one only has to be unique enough to read in guest logs.
+            correlation_id: CorrelationId::new(format!("guest-{}", random_hex(8))),
+            payload,
+        };
+        let mut stream = (self.connect)()?;
+        write_frame(&mut stream, &call)?;
+        match read_frame::<ServiceResponse, _>(&mut stream, self.max_response_bytes)? {
+            ServiceResponse::Ok { payload, .. } => Ok(payload),
+            ServiceResponse::Err { code, message, .. } => {
+                Err(BrokerClientError::Service { code, message })
+            }
+        }
+    }
+
+    fn call_typed<Req: Serialize, Resp: DeserializeOwned>(
+        &mut self,
+        service: &str,
+        verb: &str,
+        req: &Req,
+    ) -> Result<Resp, BrokerClientError> {
+        let payload = self.call(service, verb, serde_json::to_value(req)?)?;
+        Ok(serde_json::from_value(payload)?)
+    }
+
+    /// `host.time.v1::now`, verified. The first successful call pins the
+    /// broker's signing key; later calls must be signed by it.
+    pub fn host_time(&mut self) -> Result<TimeReading, BrokerClientError> {
+        let nonce = random_hex(16);
+        let req = NowRequest {
+            nonce: Some(nonce.clone()),
+        };
+        let resp: NowResponse = self.call_typed("host.time.v1", "now", &req)?;
+        let key = verify_now_response(&resp, self.time_key.as_ref(), Some(&nonce))?;
+        self.time_key = Some(key);
+        Ok(resp.reading)
+    }
+
+    /// `host.cost.v1::workload` — this VM's usage totals.
+    pub fn workload_cost(&mut self) -> Result<WorkloadCostResponse, BrokerClientError> {
+        self.call_typed("host.cost.v1", "workload", &WorkloadCostRequest::default())
+    }
+
+    /// `broker.v1::list_services` — the services bound to this VM.
+    pub fn list_services(&mut self) -> Result<ListServicesResponse, BrokerClientError> {
+        self.call_typed(
+            "broker.v1",
+            "list_services",
+            &ListServicesRequest::default(),
+        )
+    }
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    hex::encode(buf)
+}
+
+/// Write one length-prefixed JSON frame.
+pub fn write_frame<W: Write, T: Serialize>(w: &mut W, value: &T) -> Result<(), BrokerClientError> {
+    let body = serde_json::to_vec(value)?;
+    let len = u32::try_from(body.len()).map_err(|_| BrokerClientError::FrameTooLarge {
+        len: body.len(),
+        max: u32::MAX as usize,
+    })?;
+    w.write_all(&len.to_be_bytes())?;
+    w.write_all(&body)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// Read one length-prefixed JSON frame, refusing bodies over `max`
+/// before allocating.
+pub fn read_frame<T: DeserializeOwned, R: Read>(
+    r: &mut R,
+    max: usize,
+) -> Result<T, BrokerClientError> {
+    let mut len_buf = [0u8; 4];
+    r.read_exact(&mut len_buf)?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len > max {
+        return Err(BrokerClientError::FrameTooLarge { len, max });
+    }
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body)?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::net::UnixStream;
+    use std::thread;
+
+    use ed25519_dalek::SigningKey;
+    use mvm_core::protocol::host_time::sign_reading;
+
+    use super::*;
+
+    /// Connect function backed by a one-shot fake broker thread that
+    /// answers each call with `respond(call)`.
+    fn fake_broker(
+        respond: impl Fn(ServiceCall) -> ServiceResponse + Send + Sync + Clone + 'static,
+    ) -> impl FnMut() -> io::Result<UnixStream> {
+        move || {
+            let (client, mut server) = UnixStream::pair()?;
+            let respond = respond.clone();
+            thread::spawn(move || {
+                let call: ServiceCall = read_frame(&mut server, 64 * 1024).unwrap();
+                write_frame(&mut server, &respond(call)).unwrap();
+            });
+            Ok(client)
+        }
+    }
+
+    fn ok(call: &ServiceCall, payload: impl Serialize) -> ServiceResponse {
+        ServiceResponse::Ok {
+            correlation_id: call.correlation_id.clone(),
+            payload: serde_json::to_value(payload).unwrap(),
+        }
+    }
+
+    fn time_broker(sk: SigningKey) -> impl FnMut() -> io::Result<UnixStream> {
+        fake_broker(move |call| {
+            assert_eq!(call.service.as_str(), "host.time.v1");
+            let req: NowRequest = serde_json::from_value(call.payload.clone()).unwrap();
+            let reading = TimeReading {
+                wall_unix_ns: 1,
+                monotonic_ns: 2,
+                seq: 3,
+                workload_id: "wl-1".into(),
+                nonce: req.nonce,
+            };
+            ok(&call, sign_reading(&sk, reading))
+        })
+    }
+
+    #[test]
+    fn host_time_verifies_and_pins_the_broker_key() {
+        let sk = SigningKey::generate(&mut rand::rngs::OsRng);
+        let mut client = BrokerClient::new(time_broker(sk.clone()));
+        let reading = client.host_time().unwrap();
+        assert_eq!(reading.seq, 3);
+        assert_eq!(client.time_key(), Some(&sk.verifying_key()));
+        client.host_time().unwrap();
+    }
+
+    #[test]
+    fn host_time_rejects_a_key_other_than_the_pinned_one() {
+        let pinned = SigningKey::generate(&mut rand::rngs::OsRng).verifying_key();
+        let other = SigningKey::generate(&mut rand::rngs::OsRng);
+        let mut client = BrokerClient::new(time_broker(other)).with_time_key(pinned);
+        assert!(matches!(
+            client.host_time().unwrap_err(),
+            BrokerClientError::Time(HostTimeError::UnexpectedSignerKey { .. })
+        ));
+    }
+
+    #[test]
+    fn service_errors_surface_typed() {
+        let mut client = BrokerClient::new(fake_broker(|call| ServiceResponse::Err {
+            correlation_id: call.correlation_id,
+            code: ServiceErrorCode::NotBound,
+            message: "service `host.cost.v1` not bound".into(),
+        }));
+        match client.workload_cost().unwrap_err() {
+            BrokerClientError::Service { code, .. } => {
+                assert_eq!(code, ServiceErrorCode::NotBound)
+            }
+            other => panic!("expected Service error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn list_services_round_trips() {
+        let mut client = BrokerClient::new(fake_broker(|call| {
+            assert_eq!(call.verb, "list_services");
+            ok(&call, ListServicesResponse::default())
+        }));
+        assert!(client.list_services().unwrap().services.is_empty());
+    }
+
+    #[test]
+    fn oversize_response_frame_is_refused() {
+        let (mut a, mut b) = UnixStream::pair().unwrap();
+        b.write_all(&(1u32 << 20).to_be_bytes()).unwrap();
+        let err = read_frame::<serde_json::Value, _>(&mut a, 1024).unwrap_err();
+        assert!(matches!(err, BrokerClientError::FrameTooLarge { .. }));
+    }
+}
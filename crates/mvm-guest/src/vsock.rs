@@ -10,6 +10,7 @@ use mvm_core::security::{
     SessionHello, SessionHelloAck,
 };
 use mvm_core::signing::SignedPayload;
+use mvm_security::quota_meter::FsUsage;
 use serde::{Deserialize, Serialize};
 
 /// Default vsock guest CID (Firecracker convention).
@@ -48,6 +49,25 @@ pub const PORT_FORWARD_BASE: u32 = 10000;
 /// Base vsock port for interactive console PTY sessions.
 pub const CONSOLE_PORT_BASE: u32 = 20000;
 
+/// Base vsock port for streaming filesystem transfers and watches.
+/// Each `FsUploadStream` / `FsDownloadStream` / `FsWatch` gets its own
+/// one-shot data port in `FS_STREAM_PORT_BASE..FS_STREAM_PORT_BASE +
+/// FS_STREAM_PORT_SPAN`, allocated by the agent at open time.
+pub const FS_STREAM_PORT_BASE: u32 = 30000;
+
+/// Width of the streaming-filesystem data-port range. Stream ids wrap
+/// inside it; the agent's concurrent-stream cap is far below the span.
+pub const FS_STREAM_PORT_SPAN: u32 = 10000;
+
+/// Base vsock port for PTY-backed processes. A `ProcStart` with `pty`
+/// set gets a one-shot data port in `PROC_PTY_PORT_BASE..PROC_PTY_PORT_BASE
+/// + PROC_PTY_PORT_SPAN` that relays raw terminal bytes.
+pub const PROC_PTY_PORT_BASE: u32 = 40000;
+
+/// Width of the PTY data-port range. Ids wrap inside it; the agent's
+/// concurrent-process cap is far below the span.
+pub const PROC_PTY_PORT_SPAN: u32 = 10000;
+
 /// Default connect/read timeout in seconds.
 pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
 
@@ -224,8 +244,7 @@ pub enum GuestRequest {
     // ========================================================================
     /// Read up to `length` bytes from `path`, optionally starting at
     /// `offset`. The agent enforces `length` ≤ a hard cap (default
-    /// 16 MiB); callers wanting larger reads must use the streaming
-    /// surface (lands in W2).
+    /// 16 MiB); callers wanting larger reads use `FsDownloadStream`.
     FsRead {
         path: String,
         offset: Option<u64>,
@@ -237,7 +256,7 @@ pub enum GuestRequest {
         follow_symlinks: bool,
     },
     /// Write `content` to `path`. Small-content path; large writes
-    /// must use the streaming surface (W2).
+    /// use `FsUploadStream`.
     FsWrite {
         path: String,
         content: Vec<u8>,
@@ -293,6 +312,48 @@ pub enum GuestRequest {
         #[serde(default)]
         follow_symlinks: bool,
     },
+    /// Open a chunked upload of `size` bytes to `path`. The agent
+    /// answers `FsResult::StreamOpened` and listens on the returned
+    /// data port (`FS_STREAM_PORT_BASE + n`); the host then sends the
+    /// bytes from `offset` onwards as `fs_stream` frames. The agent
+    /// stages into a sibling temp file keyed by `sha256`, verifies the
+    /// whole-file digest on the terminal frame, and only then renames
+    /// onto `path`. Re-issuing the same request after a dropped
+    /// connection resumes from the staged length.
+    FsUploadStream {
+        path: String,
+        /// Total file size in bytes. Bounded by the agent's
+        /// `fs_stream::Caps::max_stream_bytes`.
+        size: u64,
+        /// Lowercase hex SHA-256 of the complete file.
+        sha256: String,
+        /// File mode applied to the renamed file (e.g. `0o644`).
+        mode: u32,
+        #[serde(default)]
+        create_parents: bool,
+    },
+    /// Open a chunked download of `path` starting at `offset`. The
+    /// agent answers `FsResult::StreamOpened` with `total_size`, then
+    /// streams bytes on the data port and terminates with the
+    /// whole-file SHA-256 so a resumed download is verified end to
+    /// end.
+    FsDownloadStream {
+        path: String,
+        #[serde(default)]
+        offset: u64,
+        #[serde(default = "default_true")]
+        follow_symlinks: bool,
+    },
+    /// Watch `path` for changes (inotify-backed). The agent answers
+    /// `FsResult::StreamOpened` and emits one `FsWatchEvent` frame per
+    /// change on the data port until the host disconnects.
+    FsWatch {
+        path: String,
+        /// Also watch every subdirectory, including ones created after
+        /// the watch starts. Capped by `fs_stream::Caps::max_watch_dirs`.
+        #[serde(default)]
+        recursive: bool,
+    },
 
     // ========================================================================
     // Process control RPC (W1 / A2 of the filesystem-volumes plan).
@@ -347,6 +408,13 @@ pub enum GuestRequest {
         /// `ProcSignal` or `ProcKill`.
         #[serde(default)]
         timeout_secs: Option<u64>,
+        /// Run the process on a PTY of this initial size instead of
+        /// pipes. The agent answers `ProcResult::PtyStarted` with a
+        /// data port that relays raw terminal bytes both ways; stdout
+        /// and stderr never reach `ProcWait`, which still reports the
+        /// exit status. Requires `GuestCapability::ProcPty`.
+        #[serde(default)]
+        pty: Option<PtySize>,
     },
     /// List processes currently tracked by the agent's PID-token
     /// map. Includes still-running and recently-exited entries
@@ -376,6 +444,13 @@ pub enum GuestRequest {
     /// from `ProcSignal { signum: 9 }` so the audit emit can be
     /// typed `ProcKilled` instead of a generic signal event.
     ProcKill { pid_token: String },
+    /// Set the window size of a PTY-backed process. The child sees
+    /// `SIGWINCH`. Errors for processes started without `pty`.
+    ProcResize {
+        pid_token: String,
+        cols: u16,
+        rows: u16,
+    },
 
     // ========================================================================
     // virtio-fs share mount control (W1 / D of the filesystem-volumes plan).
@@ -480,12 +555,16 @@ impl GuestRequest {
             Self::FsMkdir { .. } => "fs-mkdir",
             Self::FsRemove { .. } => "fs-remove",
             Self::FsMove { .. } => "fs-move",
+            Self::FsUploadStream { .. } => "fs-upload-stream",
+            Self::FsDownloadStream { .. } => "fs-download-stream",
+            Self::FsWatch { .. } => "fs-watch",
             Self::ProcStart { .. } => "proc-start",
             Self::ProcList => "proc-list",
             Self::ProcSignal { .. } => "proc-signal",
             Self::ProcSendInput { .. } => "proc-send-input",
             Self::ProcWait { .. } => "proc-wait",
             Self::ProcKill { .. } => "proc-kill",
+            Self::ProcResize { .. } => "proc-resize",
             Self::MountVolume { .. } => "mount-volume",
             Self::UnmountVolume { .. } => "unmount-volume",
             Self::UpdateIdleTimeout { .. } => "update-idle-timeout",
@@ -573,6 +652,31 @@ pub struct BootTimingReport {
     pub probes_ready_ms: Option<u64>,
 }
 
+/// Mandatory-deny routes `mvm-guest-netinit` installed at boot, as
+/// read back from [`crate::netinit::REPORT_PATH`]. A readiness view of
+/// the netinit [`crate::netinit::Report`]: just the CIDRs, not the
+/// per-route categories and error strings the audit path carries.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MandatoryDenyReadiness {
+    /// CIDRs with a blackhole route in force, IPv4 and IPv6.
+    pub installed: Vec<ipnet::IpNet>,
+    /// CIDRs whose route failed to install.
+    pub failed: Vec<ipnet::IpNet>,
+    /// Whether the IPv6 half of the deny set is in force.
+    pub ipv6: crate::netinit::Ipv6Deny,
+}
+
+impl From<&crate::netinit::Report> for MandatoryDenyReadiness {
+    fn from(report: &crate::netinit::Report) -> Self {
+        Self {
+            installed: report.installed.iter().map(|r| r.cidr).collect(),
+            failed: report.failed.iter().map(|r| r.cidr).collect(),
+            ipv6: report.ipv6,
+        }
+    }
+}
+
 /// Snapshot of agent readiness at the moment of a `ReadinessStatus`
 /// call.
 ///
@@ -618,6 +722,11 @@ pub struct ReadinessReport {
     pub profile: AgentProfile,
     /// Per-phase monotonic timings.
     pub boot_millis: BootTimingReport,
+    /// Boot-time mandatory-deny routes. `None` when the image didn't
+    /// run `mvm-guest-netinit` (or predates its report file); omitted
+    /// from the wire in that case so older hosts still parse it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mandatory_deny: Option<MandatoryDenyReadiness>,
 }
 
 // ============================================================================
@@ -679,12 +788,16 @@ impl GuestRequest {
             GuestRequest::FsMkdir { .. } => "FsMkdir",
             GuestRequest::FsRemove { .. } => "FsRemove",
             GuestRequest::FsMove { .. } => "FsMove",
+            GuestRequest::FsUploadStream { .. } => "FsUploadStream",
+            GuestRequest::FsDownloadStream { .. } => "FsDownloadStream",
+            GuestRequest::FsWatch { .. } => "FsWatch",
             GuestRequest::ProcStart { .. } => "ProcStart",
             GuestRequest::ProcList => "ProcList",
             GuestRequest::ProcSignal { .. } => "ProcSignal",
             GuestRequest::ProcSendInput { .. } => "ProcSendInput",
             GuestRequest::ProcWait { .. } => "ProcWait",
             GuestRequest::ProcKill { .. } => "ProcKill",
+            GuestRequest::ProcResize { .. } => "ProcResize",
             GuestRequest::MountVolume { .. } => "MountVolume",
             GuestRequest::UnmountVolume { .. } => "UnmountVolume",
             GuestRequest::UpdateIdleTimeout { .. } => "UpdateIdleTimeout",
@@ -739,12 +852,16 @@ impl GuestRequest {
             | GuestRequest::FsMkdir { .. }
             | GuestRequest::FsRemove { .. }
             | GuestRequest::FsMove { .. }
+            | GuestRequest::FsUploadStream { .. }
+            | GuestRequest::FsDownloadStream { .. }
+            | GuestRequest::FsWatch { .. }
             | GuestRequest::ProcStart { .. }
             | GuestRequest::ProcList
             | GuestRequest::ProcSignal { .. }
             | GuestRequest::ProcSendInput { .. }
             | GuestRequest::ProcWait { .. }
             | GuestRequest::ProcKill { .. }
+            | GuestRequest::ProcResize { .. }
             | GuestRequest::RunCode { .. } => RequestClass::DevOnly,
         }
     }
@@ -767,6 +884,27 @@ impl GuestRequest {
                 | (RequestClass::BuilderOnly, AgentProfile::Builder)
         )
     }
+
+    /// What this request costs against the filesystem RPC quota
+    /// (`mvm_security::quota_meter`), or `None` for a verb that is not
+    /// FS RPC. Host and agent meter the same figure: the requested
+    /// `FsRead` length, the `FsWrite` payload, an upload's declared
+    /// size. A download's size is charged once the agent reports it.
+    pub fn fs_usage(&self) -> Option<FsUsage> {
+        match self {
+            GuestRequest::FsRead { length, .. } => Some(FsUsage::Read(*length)),
+            GuestRequest::FsWrite { content, .. } => Some(FsUsage::Write(content.len() as u64)),
+            GuestRequest::FsUploadStream { size, .. } => Some(FsUsage::Stream(*size)),
+            GuestRequest::FsDownloadStream { .. } => Some(FsUsage::Stream(0)),
+            GuestRequest::FsList { .. }
+            | GuestRequest::FsStat { .. }
+            | GuestRequest::FsMkdir { .. }
+            | GuestRequest::FsRemove { .. }
+            | GuestRequest::FsMove { .. }
+            | GuestRequest::FsWatch { .. } => Some(FsUsage::Meta),
+            _ => None,
+        }
+    }
 }
 
 /// Response from guest vsock agent to host.
@@ -877,8 +1015,10 @@ pub enum GuestResponse {
     },
 
     /// Result of a `ReadinessStatus` query. Plan 76 Phase 2.
-    /// Snapshot of every component plus per-phase timings.
-    ReadinessStatusReport(ReadinessReport),
+    /// Snapshot of every component plus per-phase timings. Boxed so
+    /// the report doesn't set the size of every `GuestResponse`
+    /// (clippy `large_enum_variant`); the wire shape is unaffected.
+    ReadinessStatusReport(Box<ReadinessReport>),
 
     /// Result of a filesystem RPC call. The single top-level variant
     /// keeps `GuestResponse` from sprawling — the `FsResult` sub-enum
@@ -929,6 +1069,12 @@ pub enum GuestCapability {
     /// `GuestResponse::ReadinessStatusReport(ReadinessReport)`.
     /// `mvmctl wait` / `mvmctl boot-report` require this capability.
     Readiness,
+    /// `FsUploadStream` / `FsDownloadStream` / `FsWatch` over the
+    /// dedicated `FS_STREAM_PORT_BASE` data ports.
+    FsStream,
+    /// `ProcStart { pty: Some(..) }` and `ProcResize`, relaying over
+    /// the `PROC_PTY_PORT_BASE` data ports.
+    ProcPty,
 }
 
 /// Required remediation for a host/guest protocol mismatch.
@@ -962,6 +1108,8 @@ pub fn supported_capabilities() -> Vec<GuestCapability> {
         GuestCapability::VolumeMount,
         GuestCapability::UpdateIdleTimeout,
         GuestCapability::Readiness,
+        GuestCapability::FsStream,
+        GuestCapability::ProcPty,
     ]
 }
 
@@ -1083,6 +1231,12 @@ pub enum ProcResult {
     InputAccepted { bytes_accepted: u64 },
     /// `ProcKill` issued SIGKILL.
     Killed,
+    /// `ProcStart` with `pty` succeeded. The host connects to
+    /// `data_port` once to relay raw terminal bytes; `ProcWait` on
+    /// `pid_token` reports the exit status.
+    PtyStarted { pid_token: String, data_port: u32 },
+    /// `ProcResize` applied the new window size.
+    Resized,
     /// Verb-specific error. Distinct from `GuestResponse::Error`,
     /// which is reserved for transport-layer failures.
     Error {
@@ -1091,6 +1245,14 @@ pub enum ProcResult {
     },
 }
 
+/// Initial terminal size for a PTY-backed `ProcStart`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PtySize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
 /// Per-process metadata returned by `ProcList`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -1231,10 +1393,29 @@ pub enum FsResult {
     Remove { entries_removed: u64 },
     /// Move / rename completed.
     Move,
+    /// A streaming verb was accepted. Connect to `data_port` for the
+    /// `fs_stream` frames. `offset` is where the transfer starts —
+    /// the staged length for a resumed upload, the requested offset
+    /// for a download, `0` for a watch. `total_size` is the file size
+    /// (`0` for a watch).
+    StreamOpened {
+        stream_id: u32,
+        data_port: u32,
+        offset: u64,
+        total_size: u64,
+    },
     /// Verb-specific error. Distinct from `GuestResponse::Error`,
     /// which is reserved for transport-layer failures the agent
     /// can't attribute to a specific verb.
     Error { kind: FsErrorKind, message: String },
+    /// The agent's FS quota rejected the call before it touched the
+    /// disk. `reason` is `FsRateLimited` (retry once the one-second
+    /// window slides) or `FsSessionQuotaExhausted`. `detail` names
+    /// the limit — never a path or payload.
+    Backpressure {
+        reason: mvm_core::domain::instance::BackpressureReason,
+        detail: String,
+    },
 }
 
 /// One entry in an `FsList` response.
@@ -1303,6 +1484,12 @@ pub enum FsErrorKind {
     /// Path canonicalization succeeded but produced a path the agent
     /// refuses to operate on (e.g. `/proc/self`).
     BadPath,
+    /// A streamed transfer's SHA-256 did not match the declared
+    /// digest. The staged bytes are discarded.
+    ChecksumMismatch,
+    /// The verb is not available on this agent build or platform
+    /// (e.g. `FsWatch` without inotify).
+    Unsupported,
     /// Other / unclassified.
     Other,
 }
@@ -2317,7 +2504,8 @@ pub fn query_fs_diff_at(vsock_uds_path: &str) -> Result<Vec<FsChange>> {
 
 /// Dispatch a non-streaming process-control request to a running
 /// VM and return the `ProcResult`. Single-frame surface — the
-/// streaming `ProcWait` verb has its own helper below.
+/// streaming `ProcWait` verb has its own helper below. PTY starts and
+/// `ProcResize` additionally require `GuestCapability::ProcPty`.
 pub fn send_proc_request(instance_dir: &str, req: GuestRequest) -> Result<ProcResult> {
     debug_assert!(matches!(
         req,
@@ -2326,9 +2514,21 @@ pub fn send_proc_request(instance_dir: &str, req: GuestRequest) -> Result<ProcRe
             | GuestRequest::ProcSignal { .. }
             | GuestRequest::ProcSendInput { .. }
             | GuestRequest::ProcKill { .. }
+            | GuestRequest::ProcResize { .. }
     ));
+    let pty = matches!(
+        req,
+        GuestRequest::ProcStart { pty: Some(_), .. } | GuestRequest::ProcResize { .. }
+    );
     let mut stream = connect(instance_dir, DEFAULT_TIMEOUT_SECS)?;
-    require_capabilities(&mut stream, &[GuestCapability::ProcessRpc])?;
+    if pty {
+        require_capabilities(
+            &mut stream,
+            &[GuestCapability::ProcessRpc, GuestCapability::ProcPty],
+        )?;
+    } else {
+        require_capabilities(&mut stream, &[GuestCapability::ProcessRpc])?;
+    }
     let resp = send_request(&mut stream, &req)?;
     match resp {
         GuestResponse::ProcResult(r) => Ok(r),
@@ -2397,6 +2597,49 @@ pub fn send_fs_request(instance_dir: &str, req: GuestRequest) -> Result<FsResult
     }
 }
 
+/// Open a streaming FS verb (`FsUploadStream`, `FsDownloadStream`,
+/// `FsWatch`) on the control port and return the agent's answer —
+/// `FsResult::StreamOpened` on success, `FsResult::Error` otherwise.
+/// The data itself flows over [`connect_fs_stream`]; see
+/// `crate::fs_stream` for the frame format.
+pub fn open_fs_stream(instance_dir: &str, req: GuestRequest) -> Result<FsResult> {
+    debug_assert!(matches!(
+        req,
+        GuestRequest::FsUploadStream { .. }
+            | GuestRequest::FsDownloadStream { .. }
+            | GuestRequest::FsWatch { .. }
+    ));
+    let mut stream = connect(instance_dir, DEFAULT_TIMEOUT_SECS)?;
+    require_capabilities(&mut stream, &[GuestCapability::FsStream])?;
+    let resp = send_request(&mut stream, &req)?;
+    match resp {
+        GuestResponse::FsResult(r) => Ok(r),
+        GuestResponse::Error { message } => bail!("Guest FS stream transport error: {}", message),
+        _ => bail!("Unexpected response to FS stream verb"),
+    }
+}
+
+/// Connect to the data port returned in `FsResult::StreamOpened`. The
+/// agent binds the port before answering the open, so no settle delay
+/// is needed (unlike the console data port).
+pub fn connect_fs_stream(instance_dir: &str, data_port: u32) -> Result<UnixStream> {
+    connect_to_port(
+        &vsock_uds_path(instance_dir),
+        data_port,
+        DEFAULT_TIMEOUT_SECS,
+    )
+}
+
+/// Connect to the data port returned in `ProcResult::PtyStarted`. Like
+/// the FS stream ports, the agent binds before answering.
+pub fn connect_proc_pty(instance_dir: &str, data_port: u32) -> Result<UnixStream> {
+    connect_to_port(
+        &vsock_uds_path(instance_dir),
+        data_port,
+        DEFAULT_TIMEOUT_SECS,
+    )
+}
+
 /// Send a `StartPortForward` request on an already-connected stream.
 ///
 /// Used by the Apple Container backend where the vsock connection is
@@ -2521,6 +2764,22 @@ mod tests {
                 to: "/tmp/b".to_string(),
                 follow_symlinks: false,
             },
+            GuestRequest::FsUploadStream {
+                path: "/work/big.tar".to_string(),
+                size: 64 * 1024 * 1024,
+                sha256: "ab".repeat(32),
+                mode: 0o644,
+                create_parents: true,
+            },
+            GuestRequest::FsDownloadStream {
+                path: "/work/big.tar".to_string(),
+                offset: 4096,
+                follow_symlinks: true,
+            },
+            GuestRequest::FsWatch {
+                path: "/work".to_string(),
+                recursive: true,
+            },
             GuestRequest::ProcStart {
                 argv: vec!["/usr/bin/echo".to_string(), "hello".to_string()],
                 env: {
@@ -2531,6 +2790,18 @@ mod tests {
                 cwd: Some("/tmp".to_string()),
                 stdin: vec![],
                 timeout_secs: Some(30),
+                pty: None,
+            },
+            GuestRequest::ProcStart {
+                argv: vec!["/usr/bin/python3".to_string()],
+                env: Default::default(),
+                cwd: None,
+                stdin: vec![],
+                timeout_secs: None,
+                pty: Some(PtySize {
+                    cols: 120,
+                    rows: 40,
+                }),
             },
             GuestRequest::ProcList,
             GuestRequest::ProcSignal {
@@ -2548,6 +2819,11 @@ mod tests {
             GuestRequest::ProcKill {
                 pid_token: "tok-abc".to_string(),
             },
+            GuestRequest::ProcResize {
+                pid_token: "tok-abc".to_string(),
+                cols: 100,
+                rows: 30,
+            },
             GuestRequest::MountVolume {
                 volume_name: "data-volume".to_string(),
                 guest_path: "/data/foo".to_string(),
@@ -2609,7 +2885,7 @@ mod tests {
                 profile: AgentProfile::SealedProd,
                 verb: "Exec".to_string(),
             },
-            GuestResponse::ReadinessStatusReport(ReadinessReport {
+            GuestResponse::ReadinessStatusReport(Box::new(ReadinessReport {
                 control_plane: ComponentState::Ready,
                 entrypoint: ComponentState::Starting,
                 warm_pool: ComponentState::Disabled,
@@ -2626,7 +2902,12 @@ mod tests {
                     integrations_ready_ms: None,
                     probes_ready_ms: None,
                 },
-            }),
+                mandatory_deny: Some(MandatoryDenyReadiness {
+                    installed: vec!["169.254.169.254/32".parse().unwrap()],
+                    failed: vec![],
+                    ipv6: crate::netinit::Ipv6Deny::Enforced,
+                }),
+            })),
             GuestResponse::IntegrationStatusReport {
                 integrations: vec![IntegrationStateReport {
                     name: "whatsapp".to_string(),
@@ -2714,10 +2995,20 @@ mod tests {
             GuestResponse::FsResult(FsResult::Mkdir),
             GuestResponse::FsResult(FsResult::Remove { entries_removed: 7 }),
             GuestResponse::FsResult(FsResult::Move),
+            GuestResponse::FsResult(FsResult::StreamOpened {
+                stream_id: 3,
+                data_port: FS_STREAM_PORT_BASE + 3,
+                offset: 0,
+                total_size: 1 << 30,
+            }),
             GuestResponse::FsResult(FsResult::Error {
                 kind: FsErrorKind::PolicyDenied,
                 message: "path under /etc/mvm/* is denied".to_string(),
             }),
+            GuestResponse::FsResult(FsResult::Backpressure {
+                reason: mvm_core::domain::instance::BackpressureReason::FsRateLimited,
+                detail: "fs ops limit 200/s".to_string(),
+            }),
             GuestResponse::ProcResult(ProcResult::Started {
                 pid_token: "tok-1".to_string(),
             }),
@@ -2732,6 +3023,11 @@ mod tests {
             GuestResponse::ProcResult(ProcResult::Signaled),
             GuestResponse::ProcResult(ProcResult::InputAccepted { bytes_accepted: 3 }),
             GuestResponse::ProcResult(ProcResult::Killed),
+            GuestResponse::ProcResult(ProcResult::PtyStarted {
+                pid_token: "tok-2".to_string(),
+                data_port: PROC_PTY_PORT_BASE + 1,
+            }),
+            GuestResponse::ProcResult(ProcResult::Resized),
             GuestResponse::ProcResult(ProcResult::Error {
                 kind: ProcErrorKind::UnknownToken,
                 message: "no such pid_token".to_string(),
@@ -3069,6 +3365,8 @@ mod tests {
             r#"{"ProcSendInput":{"pid_token":"t","bytes":[],"smuggled":1}}"#,
             r#"{"ProcWait":{"pid_token":"t","timeout_secs":null,"smuggled":1}}"#,
             r#"{"ProcKill":{"pid_token":"t","smuggled":1}}"#,
+            r#"{"ProcResize":{"pid_token":"t","cols":80,"rows":24,"smuggled":1}}"#,
+            r#"{"ProcStart":{"argv":["/x"],"pty":{"cols":80,"rows":24,"smuggled":1}}}"#,
         ];
         for json in cases {
             let err = serde_json::from_str::<GuestRequest>(json).unwrap_err();
@@ -4583,6 +4881,7 @@ mod tests {
                 cwd: None,
                 stdin: vec![],
                 timeout_secs: None,
+                pty: None,
             },
             GuestRequest::ProcList,
             GuestRequest::ProcSignal {
@@ -4600,6 +4899,11 @@ mod tests {
             GuestRequest::ProcKill {
                 pid_token: "t".into(),
             },
+            GuestRequest::ProcResize {
+                pid_token: "t".into(),
+                cols: 80,
+                rows: 24,
+            },
             GuestRequest::MountVolume {
                 volume_name: "v".into(),
                 guest_path: "/x".into(),
@@ -4663,6 +4967,7 @@ mod tests {
                 cwd: None,
                 stdin: vec![],
                 timeout_secs: None,
+                pty: None,
             },
             GuestRequest::RunCode {
                 code: "print(1)".into(),
@@ -4765,6 +5070,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fs_usage_covers_fs_verbs_only() {
+        let read = GuestRequest::FsRead {
+            path: "/tmp/a".to_string(),
+            offset: None,
+            length: 4096,
+            follow_symlinks: true,
+        };
+        assert_eq!(read.fs_usage(), Some(FsUsage::Read(4096)));
+        let write = GuestRequest::FsWrite {
+            path: "/tmp/a".to_string(),
+            content: b"hello".to_vec(),
+            mode: 0o644,
+            create_parents: false,
+            follow_symlinks: false,
+        };
+        assert_eq!(write.fs_usage(), Some(FsUsage::Write(5)));
+        let upload = GuestRequest::FsUploadStream {
+            path: "/tmp/a".to_string(),
+            size: 1 << 20,
+            sha256: "00".repeat(32),
+            mode: 0o644,
+            create_parents: false,
+        };
+        assert_eq!(upload.fs_usage(), Some(FsUsage::Stream(1 << 20)));
+        let stat = GuestRequest::FsStat {
+            path: "/tmp/a".to_string(),
+            follow_symlinks: true,
+        };
+        assert_eq!(stat.fs_usage(), Some(FsUsage::Meta));
+        assert_eq!(GuestRequest::FsDiff.fs_usage(), None);
+        assert_eq!(GuestRequest::Ping.fs_usage(), None);
+    }
+
     // ========================================================================
     // Plan 76 Phase 2 — readiness model
     // ========================================================================
@@ -4866,6 +5205,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_readiness_report_omits_absent_mandatory_deny() {
+        // An agent with no netinit report must produce the same wire
+        // shape older hosts (with `deny_unknown_fields`) expect.
+        let json = serde_json::to_value(ReadinessReport::default()).unwrap();
+        assert!(json.get("mandatory_deny").is_none());
+
+        let report = crate::netinit::Report {
+            installed: vec![crate::netinit::RouteInstalled {
+                cidr: "fd00:ec2::254/128".parse().unwrap(),
+                category: "cloud-metadata".to_string(),
+            }],
+            failed: vec![crate::netinit::RouteFailed {
+                cidr: "fc00::/7".parse().unwrap(),
+                category: "unique-local".to_string(),
+                reason: "EPERM".to_string(),
+            }],
+            skipped_ipv6: vec![],
+            ipv6: crate::netinit::Ipv6Deny::Enforced,
+        };
+        let deny = MandatoryDenyReadiness::from(&report);
+        assert_eq!(deny.installed, vec![report.installed[0].cidr]);
+        assert_eq!(deny.failed, vec![report.failed[0].cidr]);
+        let json = serde_json::to_value(&deny).unwrap();
+        assert_eq!(json["ipv6"], "enforced");
+    }
+
     #[test]
     fn test_run_entrypoint_error_not_ready_roundtrip() {
         // Plan 76 Phase 2: the typed variant returned when a host
@@ -5041,6 +5407,31 @@ mod tests {
                 },
                 "fs-write",
             ),
+            (
+                GuestRequest::FsUploadStream {
+                    path: String::new(),
+                    size: 0,
+                    sha256: String::new(),
+                    mode: 0,
+                    create_parents: false,
+                },
+                "fs-upload-stream",
+            ),
+            (
+                GuestRequest::FsDownloadStream {
+                    path: String::new(),
+                    offset: 0,
+                    follow_symlinks: true,
+                },
+                "fs-download-stream",
+            ),
+            (
+                GuestRequest::FsWatch {
+                    path: String::new(),
+                    recursive: false,
+                },
+                "fs-watch",
+            ),
         ];
         for (req, expected) in cases {
             assert_eq!(req.kind_name(), *expected, "verb name for {req:?}");
@@ -42,6 +42,17 @@ pub enum JailerError {
     },
 }
 
+/// System resolver configuration `getaddrinfo` reads. The bridge's
+/// guest DNS endpoint resolves allow-listed names after confinement,
+/// so whichever of these exist on the host stay readable.
+pub const RESOLVER_CONFIG_PATHS: &[&str] = &[
+    "/etc/resolv.conf",
+    "/etc/hosts",
+    "/etc/nsswitch.conf",
+    "/etc/host.conf",
+    "/etc/gai.conf",
+];
+
 #[derive(Debug, Clone)]
 pub struct ConfinementSpec {
     pub readable_paths: Vec<PathBuf>,
@@ -69,8 +80,15 @@ impl ConfinementSpec {
             .collect();
         #[cfg(not(target_os = "linux"))]
         let allowed_syscalls: Vec<&'static str> = Vec::new();
+        let mut readable_paths = vec![passt_path, keys_dir];
+        readable_paths.extend(
+            RESOLVER_CONFIG_PATHS
+                .iter()
+                .map(PathBuf::from)
+                .filter(|p| p.exists()),
+        );
         Self {
-            readable_paths: vec![passt_path, keys_dir],
+            readable_paths,
             read_write_paths: vec![audit_dir],
             allowed_syscalls,
         }
@@ -120,6 +138,23 @@ pub mod seccomp;
 mod tests {
     use super::*;
 
+    #[test]
+    fn firecracker_bridge_spec_reads_only_present_resolver_config() {
+        let spec = ConfinementSpec::firecracker_bridge(
+            "/tmp/audit".into(),
+            "/tmp/keys".into(),
+            "/usr/bin/passt".into(),
+        );
+        for path in RESOLVER_CONFIG_PATHS {
+            let path = std::path::Path::new(path);
+            assert_eq!(spec.readable_paths.iter().any(|p| p == path), path.exists());
+        }
+        assert!(
+            !spec.read_write_paths.iter().any(|p| p.starts_with("/etc")),
+            "resolver config is read-only"
+        );
+    }
+
     #[test]
     fn firecracker_bridge_spec_has_audit_write_paths() {
         let spec = ConfinementSpec::firecracker_bridge(
@@ -33,8 +33,9 @@ const TARGET_ARCH: TargetArch = TargetArch::aarch64;
 /// `SYS_lstat` on x86_64 and are folded into `SYS_fstatat` on aarch64
 /// (aarch64 doesn't expose the path-stat syscalls separately).
 /// `epoll_wait` maps to `SYS_epoll_wait` on x86_64 and is folded into
-/// `SYS_epoll_pwait` on aarch64 for the same reason. The fold happens
-/// here so the policy layer (`ConfinementSpec`) stays arch-agnostic.
+/// `SYS_epoll_pwait` on aarch64 for the same reason; `poll` likewise
+/// folds into `SYS_ppoll`. The fold happens here so the policy layer
+/// (`ConfinementSpec`) stays arch-agnostic.
 #[cfg(target_arch = "x86_64")]
 pub(crate) const BRIDGE_SYSCALLS: &[(&str, libc::c_long)] = &[
     ("read", libc::SYS_read),
@@ -81,6 +82,35 @@ pub(crate) const BRIDGE_SYSCALLS: &[(&str, libc::c_long)] = &[
     ("prctl", libc::SYS_prctl),
     ("set_tid_address", libc::SYS_set_tid_address),
     ("set_robust_list", libc::SYS_set_robust_list),
+    // Thread creation for the bridge thread and tokio's blocking
+    // pool (which runs getaddrinfo): glibc >= 2.34 uses `clone3`,
+    // older glibc `clone`; each new thread registers `rseq`, gets a
+    // `sigaltstack` from std, and `sched_getaffinity` runs inside
+    // `pthread_getattr_np`. `madvise` releases exited thread stacks.
+    ("clone", libc::SYS_clone),
+    ("clone3", libc::SYS_clone3),
+    ("rseq", libc::SYS_rseq),
+    ("sigaltstack", libc::SYS_sigaltstack),
+    ("sched_getaffinity", libc::SYS_sched_getaffinity),
+    ("madvise", libc::SYS_madvise),
+    // tokio I/O driver: mio's waker (`eventfd2`) and the signal
+    // driver's socket pair (`socketpair`, dup'd via `fcntl`).
+    ("eventfd2", libc::SYS_eventfd2),
+    ("socketpair", libc::SYS_socketpair),
+    ("fcntl", libc::SYS_fcntl),
+    // glibc stub resolver (guest DNS via `TokioDnsResolver`): reading
+    // resolv.conf / hosts, then the UDP exchange in `send_dg` and the
+    // TCP fallback in `send_vc`.
+    ("newfstatat", libc::SYS_newfstatat),
+    ("lseek", libc::SYS_lseek),
+    ("uname", libc::SYS_uname),
+    ("setsockopt", libc::SYS_setsockopt),
+    ("sendmmsg", libc::SYS_sendmmsg),
+    // arch divergence: x86_64 keeps `poll` as its own syscall;
+    // aarch64 folds it into `ppoll` (see aarch64 block).
+    ("poll", libc::SYS_poll),
+    ("ioctl", libc::SYS_ioctl),
+    ("writev", libc::SYS_writev),
 ];
 
 #[cfg(target_arch = "aarch64")]
@@ -130,6 +160,35 @@ pub(crate) const BRIDGE_SYSCALLS: &[(&str, libc::c_long)] = &[
     ("prctl", libc::SYS_prctl),
     ("set_tid_address", libc::SYS_set_tid_address),
     ("set_robust_list", libc::SYS_set_robust_list),
+    // Thread creation for the bridge thread and tokio's blocking
+    // pool (which runs getaddrinfo): glibc >= 2.34 uses `clone3`,
+    // older glibc `clone`; each new thread registers `rseq`, gets a
+    // `sigaltstack` from std, and `sched_getaffinity` runs inside
+    // `pthread_getattr_np`. `madvise` releases exited thread stacks.
+    ("clone", libc::SYS_clone),
+    ("clone3", libc::SYS_clone3),
+    ("rseq", libc::SYS_rseq),
+    ("sigaltstack", libc::SYS_sigaltstack),
+    ("sched_getaffinity", libc::SYS_sched_getaffinity),
+    ("madvise", libc::SYS_madvise),
+    // tokio I/O driver: mio's waker (`eventfd2`) and the signal
+    // driver's socket pair (`socketpair`, dup'd via `fcntl`).
+    ("eventfd2", libc::SYS_eventfd2),
+    ("socketpair", libc::SYS_socketpair),
+    ("fcntl", libc::SYS_fcntl),
+    // glibc stub resolver (guest DNS via `TokioDnsResolver`): reading
+    // resolv.conf / hosts, then the UDP exchange in `send_dg` and the
+    // TCP fallback in `send_vc`.
+    ("newfstatat", libc::SYS_newfstatat),
+    ("lseek", libc::SYS_lseek),
+    ("uname", libc::SYS_uname),
+    ("setsockopt", libc::SYS_setsockopt),
+    ("sendmmsg", libc::SYS_sendmmsg),
+    // arch divergence: aarch64 does not expose `poll` — fold into
+    // `ppoll` (the policy layer keeps the legacy name).
+    ("poll", libc::SYS_ppoll),
+    ("ioctl", libc::SYS_ioctl),
+    ("writev", libc::SYS_writev),
 ];
 
 /// Resolve a syscall by name to its `libc::SYS_*` number on the current
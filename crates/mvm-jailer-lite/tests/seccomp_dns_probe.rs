@@ -0,0 +1,87 @@
+//! ADR-064 — guest DNS under the bridge's seccomp filter.
+//!
+//! The bridge answers guest DNS through `TokioDnsResolver`
+//! (`tokio::net::lookup_host` → glibc `getaddrinfo` on tokio's
+//! blocking pool), from a thread spawned *after* `confine_self`. A
+//! syscall that path needs but `BRIDGE_SYSCALLS` lacks kills the
+//! bridge with SIGSYS on the first guest lookup.
+//!
+//! Parent test re-spawns the test binary with `SECCOMP_DNS_PROBE=1`;
+//! the child applies the bridge's seccomp filter via `seccomp::apply`
+//! (no Landlock — this probe is about syscalls, not paths), then
+//! mirrors the bridge: spawn a thread, build a current-thread runtime
+//! with `enable_io` + `enable_time`, and resolve one name through
+//! `/etc/hosts` and one through the nameservers in `resolv.conf`.
+//! Lookup *results* are not asserted — the sandbox may have no
+//! working DNS — only that the child survives them.
+//!
+//! File is `#![cfg(target_os = "linux")]` for the same reason as
+//! `seccomp_property.rs`.
+
+#![cfg(target_os = "linux")]
+
+use mvm_jailer_lite::ConfinementSpec;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Command, Stdio};
+
+const PROBE_ENV: &str = "SECCOMP_DNS_PROBE";
+
+#[test]
+fn guest_dns_lookup_survives_the_bridge_filter() {
+    let child_status = Command::new(std::env::current_exe().expect("current_exe"))
+        .env(PROBE_ENV, "1")
+        // Bound the nameserver leg: one try, one second.
+        .env("RES_OPTIONS", "timeout:1 attempts:1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .status()
+        .expect("spawn probe child");
+
+    assert_ne!(
+        child_status.signal(),
+        Some(libc::SIGSYS),
+        "a syscall on the DNS path is missing from BRIDGE_SYSCALLS"
+    );
+    assert!(
+        child_status.success(),
+        "child exited unexpectedly: status={:?}, signal={:?}",
+        child_status.code(),
+        child_status.signal()
+    );
+}
+
+#[ctor::ctor]
+fn maybe_run_as_probe_child() {
+    if std::env::var(PROBE_ENV).is_ok() {
+        run_probe();
+        // Probe always exits explicitly; we never return here.
+    }
+}
+
+fn run_probe() {
+    // Paths are irrelevant: `seccomp::apply` only reads the spec's
+    // syscall allowlist.
+    let spec = ConfinementSpec::firecracker_bridge(
+        "/tmp/mvm-seccomp-dns-probe-audit".into(),
+        "/tmp/mvm-seccomp-dns-probe-keys".into(),
+        "/usr/bin/passt".into(),
+    );
+    if let Err(e) = mvm_jailer_lite::seccomp::apply(&spec) {
+        eprintln!("seccomp::apply failed: {e}");
+        std::process::exit(2);
+    }
+
+    let bridge = std::thread::spawn(|| {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .expect("build probe runtime");
+        rt.block_on(async {
+            let _ = tokio::net::lookup_host(("localhost", 53)).await;
+            let _ = tokio::net::lookup_host(("mvm-seccomp-dns-probe.invalid", 53)).await;
+        });
+    });
+    let code = if bridge.join().is_ok() { 0 } else { 3 };
+    std::process::exit(code);
+}
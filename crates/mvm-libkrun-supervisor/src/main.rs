@@ -58,6 +58,9 @@ use mvm_supervisor::audit_file::FileAuditSigner;
 use mvm_supervisor::gateway_bridge::{
     AllowAll, BridgeConfig, BridgeEndpoints, spawn_bridge_thread,
 };
+use mvm_supervisor::l7_proxy::{SignedEgressAuditSink, TokioDnsResolver};
+use mvm_supervisor::proxy::datapath::L4Datapath;
+use mvm_supervisor::proxy::dns::DnsPinStore;
 
 fn main() -> ExitCode {
     // macOS Hypervisor.framework rejects any process without
@@ -251,10 +254,36 @@ fn run_with_bridge(cfg: SupervisorConfig) -> Result<std::convert::Infallible> {
         pipe.build_observers()
     };
 
+    let plan = Arc::new(plan);
+    let bundle = bundle.map(Arc::new);
+
+    // `[[network.l4]]` rows or a restricted `[egress]` allow-list in
+    // the bundle engage the frame-level L4 datapath in the bridge;
+    // bundles with neither keep the unfiltered gateway path. The
+    // datapath carries the VM's guest DNS endpoint: it answers the
+    // guest's queries for `[egress]` allow-listed names and pins each
+    // answer for the L4 gate. Its flow and DNS decisions are
+    // chain-signed with the same signer as the bridge's flow events.
+    let l4 = match &bundle {
+        Some(b) => L4Datapath::from_bundle(
+            b,
+            Arc::new(DnsPinStore::for_vm(vm_name.clone())),
+            Arc::new(TokioDnsResolver),
+            Arc::new(SignedEgressAuditSink::new(
+                Arc::clone(&signer),
+                Arc::clone(&plan),
+                bundle.clone(),
+            )),
+        )
+        .context("build the L4 datapath from the bundle")?
+        .map(Arc::new),
+        None => None,
+    };
+
     let bridge_cfg = BridgeConfig {
         vm_name: vm_name.clone(),
-        plan: Arc::new(plan),
-        bundle: bundle.map(Arc::new),
+        plan,
+        bundle,
         audit_socket,
         signer,
         policy: Arc::new(AllowAll),
@@ -264,6 +293,7 @@ fn run_with_bridge(cfg: SupervisorConfig) -> Result<std::convert::Infallible> {
         // pre-Plan-113 behavior); non-empty for tenant policies that
         // reference an allowlisted observer by name.
         observers,
+        l4,
     };
 
     run_supervisor_with_bridge(&cfg, move |bridge_fds| {
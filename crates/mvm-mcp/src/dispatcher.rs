@@ -18,9 +18,111 @@
 //!   `mvm.code_eval`) through a shared registry. Default impl
 //!   returns an `is_error: true` ToolResult so a dispatcher that
 //!   doesn't opt in still answers MCP `tools/call` without trapping.
+//!
+//! ## Calling back into the client
+//!
+//! Transports that can send server-to-client requests hand the
+//! dispatcher a [`ClientPeer`] through [`Dispatcher::run_with_peer`]
+//! and [`Dispatcher::invoke_tool_with_peer`]. The local dispatcher
+//! uses it to ask the user to approve a command the gate parked
+//! (`elicitation/create`). Both default to the peer-less methods, so
+//! dispatchers that never call back need not change.
+//!
+//! The same peer carries the per-call side channels: progress
+//! notifications for clients that sent a progress token, and the
+//! [`CancelToken`] a `notifications/cancelled` trips.
+//!
+//! ## Resources and the tool list
+//!
+//! [`Dispatcher::list_resources`] / [`Dispatcher::read_resource`]
+//! back MCP `resources/*`; the defaults expose nothing.
+//! [`Dispatcher::list_tools`] answers `tools/list`, and transports
+//! poll [`Dispatcher::tools_revision`] to tell clients when that
+//! answer changes.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::protocol::{
+    ContentBlock, ElicitResult, JsonRpcError, Resource, ResourceContents, ResourceTemplate,
+    ToolResult,
+};
+use crate::tools::{RunParams, ToolSchema, all_tools};
+
+/// Tripped when the client cancels the call in flight. Cheap to
+/// clone and `Send + Sync`, so a dispatcher can hand it to whichever
+/// thread waits on the guest.
+// allow(secret-debug): a cancellation flag, not a credential.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// The client end of the connection, as seen from inside a call.
+pub trait ClientPeer {
+    /// Whether the client declared the `elicitation` capability.
+    fn supports_elicitation(&self) -> bool;
+
+    /// `clientInfo.name` from `initialize`, if the client sent one.
+    fn client_name(&self) -> Option<String>;
+
+    /// Send `elicitation/create` and block until the client answers.
+    /// `requested_schema` is the flat JSON Schema object the spec
+    /// allows for the form.
+    fn elicit(
+        &self,
+        message: &str,
+        requested_schema: serde_json::Value,
+    ) -> Result<ElicitResult, JsonRpcError>;
+
+    /// Report progress on the current call. `progress` must increase
+    /// from one report to the next. A no-op unless the client asked
+    /// for progress on this call.
+    fn progress(&self, _progress: f64, _total: Option<f64>, _message: Option<&str>) {}
+
+    /// The current call's cancellation token.
+    fn cancel_token(&self) -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// The transport session the call arrived on, for transports that
+    /// serve several clients from one dispatcher. Their `run` session
+    /// ids arrive prefixed `<scope>:`; resources are limited to them.
+    fn session_scope(&self) -> Option<String> {
+        None
+    }
+}
 
-use crate::protocol::{ContentBlock, ToolResult};
-use crate::tools::RunParams;
+/// Peer for transports that cannot call back into the client.
+pub struct NoClientPeer;
+
+impl ClientPeer for NoClientPeer {
+    fn supports_elicitation(&self) -> bool {
+        false
+    }
+
+    fn client_name(&self) -> Option<String> {
+        None
+    }
+
+    fn elicit(
+        &self,
+        _message: &str,
+        _requested_schema: serde_json::Value,
+    ) -> Result<ElicitResult, JsonRpcError> {
+        Err(JsonRpcError::invalid_request(
+            "this transport cannot send elicitation requests",
+        ))
+    }
+}
 
 /// One method per MCP tool surface we expose.
 pub trait Dispatcher {
@@ -60,4 +162,51 @@ pub trait Dispatcher {
             is_error: true,
         }
     }
+
+    /// [`Self::run`] with access to the client. Transports that can
+    /// call back into the client invoke this instead of `run`.
+    fn run_with_peer(&self, params: RunParams, _peer: &dyn ClientPeer) -> ToolResult {
+        self.run(params)
+    }
+
+    /// [`Self::invoke_tool`] with access to the client.
+    fn invoke_tool_with_peer(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        _peer: &dyn ClientPeer,
+    ) -> ToolResult {
+        self.invoke_tool(name, params)
+    }
+
+    /// Tools for `tools/list`.
+    fn list_tools(&self) -> Vec<ToolSchema> {
+        all_tools()
+    }
+
+    /// Changes whenever [`Self::list_tools`] would answer
+    /// differently. Transports poll it and send
+    /// `notifications/tools/list_changed` when it moves.
+    fn tools_revision(&self) -> u64 {
+        0
+    }
+
+    /// Concrete resources for `resources/list`.
+    fn list_resources(&self, _peer: &dyn ClientPeer) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    /// URI templates for `resources/templates/list`.
+    fn resource_templates(&self) -> Vec<ResourceTemplate> {
+        Vec::new()
+    }
+
+    /// Contents of `uri` for `resources/read`.
+    fn read_resource(
+        &self,
+        uri: &str,
+        _peer: &dyn ClientPeer,
+    ) -> Result<Vec<ResourceContents>, JsonRpcError> {
+        Err(JsonRpcError::resource_not_found(uri))
+    }
 }
@@ -0,0 +1,1396 @@
+//! Streamable-HTTP JSON-RPC transport.
+//!
+//! Lets several MCP clients (editors, a CI harness) share one
+//! `mvmctl mcp` host process. The same [`handle_one`] that drives
+//! the stdio loop answers each request; this module only adds the
+//! framing, auth and session handling of the MCP streamable-HTTP
+//! transport:
+//!
+//! - One endpoint, [`ENDPOINT_PATH`]. `POST` carries a single
+//!   JSON-RPC message; requests are answered with an
+//!   `application/json` body, notifications and responses with
+//!   `202 Accepted`. `DELETE` ends the session.
+//! - A `tools/call` whose `Accept` lists `text/event-stream` is
+//!   answered as an SSE stream once the call reports progress: each
+//!   `notifications/progress` is an event, and the response is the
+//!   last. A call the client cancelled (`notifications/cancelled` on
+//!   another POST) gets no response: `202`, or the stream just ends.
+//! - `GET` with `Accept: text/event-stream` opens the session's
+//!   server-to-client stream, which carries
+//!   `notifications/tools/list_changed`. Without it, `GET` is `406`.
+//! - The listener is a loopback TCP socket or a unix socket (mode
+//!   0600). Non-loopback binds are refused: remote, tenant-aware
+//!   HTTP stays mvmd's (ADR-003 §Decisions 5).
+//! - Every request must carry `Authorization: Bearer <token>`,
+//!   compared in constant time. A browser `Origin` that is not
+//!   loopback is refused, which closes the DNS-rebinding hole a
+//!   loopback listener otherwise has.
+//! - `initialize` mints an `Mcp-Session-Id`. The id is a
+//!   [`SessionMap`] entry, so transport sessions share the idle/max
+//!   expiry of `run` sessions and reach the caller's [`Reaper`] with
+//!   the same [`ReapReason`]s. An unknown or expired id gets `404`,
+//!   which tells the client to initialize again.
+//! - A `run` or guest-tool call's `session` argument is scoped to the
+//!   transport session (`<mcp-session-id>:<session>`), so two clients
+//!   that both pick `session: "s1"` do not share a warm VM.
+//!
+//! Elicitation is not relayed: its answer would arrive on a separate
+//! POST while the asking call holds its own. Peers report no
+//! elicitation support and parked approvals wait for
+//! `mvmctl approvals` instead.
+//!
+//! Hand-rolled over `std::net` like the rest of this crate's framing
+//! (ADR-003 §Decisions 2): HTTP/1.1, `Content-Length` bodies only,
+//! one thread per connection up to [`MAX_CONNECTIONS`].
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use secrecy::{ExposeSecret, SecretBox};
+use serde_json::Value;
+
+use crate::dispatcher::{ClientPeer, Dispatcher};
+use crate::protocol::{
+    ElicitResult, JsonRpcError, JsonRpcResponse, PROTOCOL_VERSION, SERVER_NAME, SERVER_VERSION,
+    TOOLS_LIST_CHANGED_METHOD, notification,
+};
+use crate::server::{Cancellations, ClientState, InitializingPeer, handle_one};
+use crate::session::{ReapReason, Reaper, SessionConfig, SessionMap, SessionState};
+
+/// The single MCP endpoint.
+pub const ENDPOINT_PATH: &str = "/mcp";
+
+/// Header carrying the transport session id.
+pub const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+/// `SessionState::env` recorded for transport sessions, so a reaper
+/// shared with `run` sessions can tell the two apart.
+pub const TRANSPORT_SESSION_ENV: &str = "mcp-http";
+
+/// Concurrent connections served; further accepts get `503`.
+pub const MAX_CONNECTIONS: usize = 32;
+
+/// Request line plus headers. Anything longer is refused with `431`.
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+
+/// JSON-RPC body cap. `mvm.upload` carries base64 file content, so
+/// this is well above any control message.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// A keep-alive connection with no request for this long is closed.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often the accept loop sweeps expired transport sessions.
+const REAP_TICK: Duration = Duration::from_secs(30);
+
+/// How long the non-blocking accept loop sleeps between polls.
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+/// How often a `GET` stream checks for shutdown, a closed session and
+/// a changed tool list.
+const STREAM_TICK: Duration = Duration::from_secs(1);
+
+/// An idle `GET` stream sends an SSE comment this often, so a client
+/// (or proxy) can tell a quiet stream from a dead one.
+const STREAM_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Where the server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Listen {
+    /// Loopback TCP address (`127.0.0.1:PORT` / `[::1]:PORT`).
+    Tcp(SocketAddr),
+    /// Unix socket path. A stale socket at the path is replaced.
+    Unix(PathBuf),
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener, PathBuf),
+}
+
+/// The streamable-HTTP server. Bind with [`HttpServer::bind`], then
+/// call [`HttpServer::serve`] on the thread that owns the dispatcher.
+pub struct HttpServer {
+    listener: Listener,
+    token: SecretBox<String>,
+    sessions: Mutex<SessionMap>,
+    clients: Mutex<BTreeMap<String, HttpClient>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// What a transport session keeps between requests.
+#[derive(Clone)]
+struct HttpClient {
+    state: ClientState,
+    /// Shared by the session's POSTs, so a `notifications/cancelled`
+    /// reaches a call running on another connection.
+    cancellations: Arc<Cancellations>,
+}
+
+impl HttpServer {
+    /// Bind `listen`. Every request must present `token` as a bearer
+    /// credential; an empty token is refused. Surrounding whitespace
+    /// (a token file's trailing newline) is trimmed, matching how the
+    /// presented header value is trimmed.
+    pub fn bind(listen: &Listen, token: SecretBox<String>, config: SessionConfig) -> Result<Self> {
+        let token = SecretBox::new(Box::new(token.expose_secret().trim().to_string()));
+        if token.expose_secret().is_empty() {
+            bail!("refusing to serve MCP over HTTP with an empty bearer token");
+        }
+        let listener = match listen {
+            Listen::Tcp(addr) => {
+                if !addr.ip().is_loopback() {
+                    bail!(
+                        "refusing to bind MCP HTTP on non-loopback address {addr}; \
+                         remote MCP is served by mvmd"
+                    );
+                }
+                let l = TcpListener::bind(addr)
+                    .with_context(|| format!("binding MCP HTTP listener on {addr}"))?;
+                Listener::Tcp(l)
+            }
+            Listen::Unix(path) => Listener::Unix(bind_unix(path)?, path.clone()),
+        };
+        match &listener {
+            Listener::Tcp(l) => l.set_nonblocking(true),
+            Listener::Unix(l, _) => l.set_nonblocking(true),
+        }
+        .context("setting MCP HTTP listener to non-blocking")?;
+        Ok(Self {
+            listener,
+            token,
+            sessions: Mutex::new(SessionMap::new(config)),
+            clients: Mutex::new(BTreeMap::new()),
+            shutdown: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// The bound TCP address (resolves port 0). `None` for a unix
+    /// socket.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match &self.listener {
+            Listener::Tcp(l) => l.local_addr().ok(),
+            Listener::Unix(..) => None,
+        }
+    }
+
+    /// Set the returned flag to make [`HttpServer::serve`] return
+    /// after its next accept poll.
+    pub fn shutdown_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shutdown)
+    }
+
+    /// Number of live transport sessions.
+    pub fn session_count(&self) -> usize {
+        self.sessions.lock().map(|m| m.len()).unwrap_or(0)
+    }
+
+    /// Accept connections until shut down. Each connection gets a
+    /// scoped thread, so `dispatcher` is shared by reference. Transport
+    /// sessions reach `reaper` when they expire, are deleted, or are
+    /// drained as [`ReapReason::Shutdown`] when this returns.
+    pub fn serve<D: Dispatcher + Sync>(&self, dispatcher: &D, reaper: &dyn Reaper) -> Result<()> {
+        tracing::info!(
+            protocol_version = PROTOCOL_VERSION,
+            server = SERVER_NAME,
+            version = SERVER_VERSION,
+            listen = %self.describe(),
+            "mvm-mcp http transport ready"
+        );
+        let reaper = ClientReaper {
+            clients: &self.clients,
+            inner: reaper,
+        };
+        let active = AtomicUsize::new(0);
+        let result = std::thread::scope(|scope| -> Result<()> {
+            let mut last_sweep = Instant::now();
+            while !self.shutdown.load(Ordering::Relaxed) {
+                if last_sweep.elapsed() >= REAP_TICK {
+                    self.reap_expired(&reaper);
+                    last_sweep = Instant::now();
+                }
+                let accepted = match &self.listener {
+                    Listener::Tcp(l) => l.accept().map(|(s, _)| Conn::Tcp(s)),
+                    Listener::Unix(l, _) => l.accept().map(|(s, _)| Conn::Unix(s)),
+                };
+                let conn = match accepted {
+                    Ok(conn) => conn,
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(ACCEPT_POLL);
+                        continue;
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(anyhow::Error::new(e).context("accepting MCP HTTP")),
+                };
+                if let Err(e) = conn.prepare() {
+                    tracing::warn!(err = %e, "configuring MCP HTTP connection");
+                    continue;
+                }
+                if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    let mut conn = conn;
+                    let _ = write_response(
+                        &mut conn,
+                        &Reply::status(503, "too many MCP HTTP connections"),
+                        false,
+                    );
+                    continue;
+                }
+                let (active, reaper) = (&active, &reaper);
+                scope.spawn(move || {
+                    self.serve_connection(conn, dispatcher, reaper);
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Ok(())
+        });
+        if let Ok(mut map) = self.sessions.lock() {
+            let n = map.drain(&reaper);
+            if n > 0 {
+                tracing::info!(drained = n, "MCP HTTP shutdown drained sessions");
+            }
+        }
+        result
+    }
+
+    fn describe(&self) -> String {
+        match &self.listener {
+            Listener::Tcp(l) => l
+                .local_addr()
+                .map(|a| format!("http://{a}{ENDPOINT_PATH}"))
+                .unwrap_or_else(|_| "tcp".to_string()),
+            Listener::Unix(_, path) => format!("unix:{}", path.display()),
+        }
+    }
+
+    fn reap_expired(&self, reaper: &dyn Reaper) {
+        if let Ok(mut map) = self.sessions.lock() {
+            let n = map.reap_expired(reaper);
+            if n > 0 {
+                tracing::debug!(reaped = n, "MCP HTTP session reaper swept");
+            }
+        }
+    }
+
+    fn serve_connection<D: Dispatcher>(&self, conn: Conn, dispatcher: &D, reaper: &dyn Reaper) {
+        let mut reader = BufReader::new(conn);
+        loop {
+            let request = match read_request(&mut reader, |r| self.authorized(r)) {
+                Ok(Some(r)) => r,
+                Ok(None) => return,
+                Err(reply) => {
+                    let _ = write_response(reader.get_mut(), &reply, false);
+                    return;
+                }
+            };
+            let keep_alive = request.keep_alive();
+            // `None`: the reply went out as an event stream, which
+            // ends the connection.
+            let Some(reply) = self.respond(&request, dispatcher, reaper, reader.get_mut()) else {
+                return;
+            };
+            if write_response(reader.get_mut(), &reply, keep_alive).is_err() || !keep_alive {
+                return;
+            }
+        }
+    }
+
+    /// Route one request. `read_request` has already refused an
+    /// unauthenticated one before reading its body; the check is
+    /// repeated here so routing never depends on the caller.
+    /// `None` when the answer was streamed to `out` as SSE.
+    fn respond<D: Dispatcher>(
+        &self,
+        request: &Request,
+        dispatcher: &D,
+        reaper: &dyn Reaper,
+        out: &mut dyn Write,
+    ) -> Option<Reply> {
+        if request.path() != ENDPOINT_PATH {
+            return Some(Reply::status(404, "not found"));
+        }
+        if !self.authorized(request) {
+            return Some(Reply::unauthorized());
+        }
+        if let Some(origin) = request.header("origin")
+            && !is_loopback_origin(origin)
+        {
+            return Some(Reply::status(403, "cross-origin requests are refused"));
+        }
+        match request.method.as_str() {
+            "POST" => self.post(request, dispatcher, reaper, out),
+            "GET" => self.listen(request, dispatcher, reaper, out),
+            "DELETE" => Some(self.delete(request, reaper)),
+            _ => Some(
+                Reply::status(405, "method not allowed")
+                    .header("Allow", "GET, POST, DELETE".to_string()),
+            ),
+        }
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        let Some(presented) = request
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+        else {
+            return false;
+        };
+        // Both sides are trimmed: header values at parse, the token at
+        // `bind`.
+        constant_time_eq(presented.as_bytes(), self.token.expose_secret().as_bytes())
+    }
+
+    fn post<D: Dispatcher>(
+        &self,
+        request: &Request,
+        dispatcher: &D,
+        reaper: &dyn Reaper,
+        out: &mut dyn Write,
+    ) -> Option<Reply> {
+        let mut message: Value = match serde_json::from_slice(&request.body) {
+            Ok(v) => v,
+            Err(e) => {
+                return Some(Reply::rpc(
+                    400,
+                    &JsonRpcResponse::err(Value::Null, JsonRpcError::parse_error(e.to_string())),
+                ));
+            }
+        };
+        if message.is_array() {
+            return Some(Reply::rpc(
+                400,
+                &JsonRpcResponse::err(
+                    Value::Null,
+                    JsonRpcError::invalid_request("JSON-RPC batches are not supported"),
+                ),
+            ));
+        }
+
+        if message.get("method").and_then(Value::as_str) == Some("initialize") {
+            return Some(self.initialize(&message, dispatcher));
+        }
+
+        let Some(session_id) = request.header(SESSION_HEADER) else {
+            return Some(Reply::status(400, "missing Mcp-Session-Id header"));
+        };
+        let Some(client) = self.touch(session_id, reaper) else {
+            return Some(Reply::status(404, "unknown or expired MCP session"));
+        };
+        scope_run_session(&mut message, session_id);
+
+        let streams = message.get("method").and_then(Value::as_str) == Some("tools/call")
+            && accepts_event_stream(request);
+        let peer = HttpPeer::new(
+            client,
+            Some(session_id.to_string()),
+            streams.then(|| EventStream::new(out)),
+        );
+        let response = handle_one(&message.to_string(), dispatcher, &peer);
+        let Some(mut events) = peer.events.into_inner().filter(|e| e.started) else {
+            return Some(match response {
+                Some(response) => Reply::rpc(200, &response),
+                None => Reply::status(202, ""),
+            });
+        };
+        if let Some(response) = response
+            && let Ok(frame) = serde_json::to_value(&response)
+        {
+            let _ = events.send(&frame);
+        }
+        None
+    }
+
+    /// `GET`: hold the session's server-to-client stream open until
+    /// the session ends, the client goes away or the server stops.
+    fn listen<D: Dispatcher>(
+        &self,
+        request: &Request,
+        dispatcher: &D,
+        reaper: &dyn Reaper,
+        out: &mut dyn Write,
+    ) -> Option<Reply> {
+        if !accepts_event_stream(request) {
+            return Some(Reply::status(
+                406,
+                "GET opens an event stream; send Accept: text/event-stream",
+            ));
+        }
+        let Some(session_id) = request.header(SESSION_HEADER) else {
+            return Some(Reply::status(400, "missing Mcp-Session-Id header"));
+        };
+        if self.touch(session_id, reaper).is_none() {
+            return Some(Reply::status(404, "unknown or expired MCP session"));
+        }
+        let mut events = EventStream::new(out);
+        if events.start().is_err() {
+            return None;
+        }
+        tracing::debug!(session = %session_id, "MCP HTTP event stream opened");
+        let mut revision = dispatcher.tools_revision();
+        let mut last_write = Instant::now();
+        while !self.shutdown.load(Ordering::Relaxed) {
+            std::thread::sleep(STREAM_TICK);
+            let live = self
+                .sessions
+                .lock()
+                .is_ok_and(|map| map.get(session_id).is_some());
+            if !live {
+                break;
+            }
+            let current = dispatcher.tools_revision();
+            let sent = if current != revision {
+                revision = current;
+                events.send(&notification(
+                    TOOLS_LIST_CHANGED_METHOD,
+                    serde_json::json!({}),
+                ))
+            } else if last_write.elapsed() >= STREAM_KEEPALIVE {
+                events.comment("keepalive")
+            } else {
+                continue;
+            };
+            if sent.is_err() {
+                break;
+            }
+            last_write = Instant::now();
+        }
+        None
+    }
+
+    fn initialize<D: Dispatcher>(&self, message: &Value, dispatcher: &D) -> Reply {
+        let peer = HttpPeer::new(
+            HttpClient {
+                state: ClientState::default(),
+                cancellations: Arc::default(),
+            },
+            None,
+            None,
+        );
+        let Some(response) = handle_one(&message.to_string(), dispatcher, &peer) else {
+            // `initialize` sent as a notification: nothing to answer
+            // and nothing to open a session for.
+            return Reply::status(202, "");
+        };
+        if response.error.is_some() {
+            return Reply::rpc(200, &response);
+        }
+        let session_id = match new_session_id() {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::error!(err = %e, "minting MCP session id");
+                return Reply::status(500, "could not mint a session id");
+            }
+        };
+        if let Ok(mut map) = self.sessions.lock() {
+            map.touch_or_insert(&session_id, TRANSPORT_SESSION_ENV, None);
+        }
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.insert(
+                session_id.clone(),
+                HttpClient {
+                    state: peer.client.into_inner(),
+                    cancellations: peer.cancellations,
+                },
+            );
+        }
+        tracing::info!(session = %session_id, "MCP HTTP session opened");
+        Reply::rpc(200, &response).header(SESSION_HEADER, session_id)
+    }
+
+    fn delete(&self, request: &Request, reaper: &dyn Reaper) -> Reply {
+        let Some(session_id) = request.header(SESSION_HEADER) else {
+            return Reply::status(400, "missing Mcp-Session-Id header");
+        };
+        let removed = self
+            .sessions
+            .lock()
+            .map(|mut map| map.remove(session_id, ReapReason::Closed, reaper))
+            .unwrap_or(false);
+        if removed {
+            Reply::status(204, "")
+        } else {
+            Reply::status(404, "unknown or expired MCP session")
+        }
+    }
+
+    /// Sweep, then bump `last_used` on a live session and return what
+    /// its client declared in `initialize`.
+    fn touch(&self, session_id: &str, reaper: &dyn Reaper) -> Option<HttpClient> {
+        {
+            let mut map = self.sessions.lock().ok()?;
+            map.reap_expired(reaper);
+            map.get(session_id)?;
+            map.touch_or_insert(session_id, TRANSPORT_SESSION_ENV, None);
+        }
+        self.clients.lock().ok()?.get(session_id).cloned()
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        if let Listener::Unix(_, path) = &self.listener {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Bind a unix socket readable only by the owner. A leftover socket
+/// from a previous run is removed; any other file at the path is an
+/// error rather than something to delete.
+///
+/// The socket is bound inside a fresh 0700 directory next to `path`,
+/// narrowed to 0600 there, and only then renamed into place, so it is
+/// never reachable with the umask's looser mode.
+fn bind_unix(path: &Path) -> Result<UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt};
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            bail!(
+                "{} exists and is not a socket; refusing to replace it",
+                path.display()
+            );
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("removing stale socket {}", path.display()))?;
+    }
+    let name = path
+        .file_name()
+        .with_context(|| format!("{} has no file name", path.display()))?;
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let staging = parent.join(format!(
+        ".{}.{}.bind",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("creating {}", staging.display()))?;
+    let staged = staging.join(name);
+    let bound = (|| {
+        let listener = UnixListener::bind(&staged)
+            .with_context(|| format!("binding MCP HTTP socket {}", path.display()))?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting {} to mode 0600", path.display()))?;
+        std::fs::rename(&staged, path)
+            .with_context(|| format!("moving MCP HTTP socket into {}", path.display()))?;
+        Ok(listener)
+    })();
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    bound
+}
+
+/// Forwards reaps to the caller's reaper and forgets the session's
+/// client state.
+struct ClientReaper<'a> {
+    clients: &'a Mutex<BTreeMap<String, HttpClient>>,
+    inner: &'a dyn Reaper,
+}
+
+impl Reaper for ClientReaper<'_> {
+    fn on_reap(&self, session_id: &str, state: &SessionState, reason: ReapReason) {
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.remove(session_id);
+        }
+        tracing::info!(session = %session_id, ?reason, "MCP HTTP session closed");
+        self.inner.on_reap(session_id, state, reason);
+    }
+}
+
+/// Per-request peer. Notifications go out on the request's event
+/// stream when it has one. It never asks the client anything: the
+/// answer to an `elicitation/create` would arrive on another POST.
+struct HttpPeer<'w> {
+    client: RefCell<ClientState>,
+    cancellations: Arc<Cancellations>,
+    session: Option<String>,
+    events: RefCell<Option<EventStream<'w>>>,
+}
+
+impl<'w> HttpPeer<'w> {
+    fn new(client: HttpClient, session: Option<String>, events: Option<EventStream<'w>>) -> Self {
+        Self {
+            client: RefCell::new(client.state),
+            cancellations: client.cancellations,
+            session,
+            events: RefCell::new(events),
+        }
+    }
+}
+
+impl ClientPeer for HttpPeer<'_> {
+    fn supports_elicitation(&self) -> bool {
+        false
+    }
+
+    fn client_name(&self) -> Option<String> {
+        self.client.borrow().name.clone()
+    }
+
+    fn elicit(&self, _message: &str, _schema: Value) -> Result<ElicitResult, JsonRpcError> {
+        Err(JsonRpcError::invalid_request(
+            "the HTTP transport does not relay elicitation",
+        ))
+    }
+
+    fn session_scope(&self) -> Option<String> {
+        self.session.clone()
+    }
+}
+
+impl InitializingPeer for HttpPeer<'_> {
+    fn record_initialize(&self, params: Option<&Value>) {
+        *self.client.borrow_mut() = ClientState::from_initialize(params);
+    }
+
+    fn cancellations(&self) -> &Cancellations {
+        &self.cancellations
+    }
+
+    fn notify(&self, frame: &Value) {
+        if let Some(events) = self.events.borrow_mut().as_mut()
+            && let Err(e) = events.send(frame)
+        {
+            tracing::debug!(err = %e, "MCP HTTP event stream closed");
+        }
+    }
+}
+
+/// A `text/event-stream` response. The head is written with the
+/// first event, so a call that never notifies still gets a plain
+/// JSON reply.
+struct EventStream<'w> {
+    out: &'w mut dyn Write,
+    started: bool,
+}
+
+impl<'w> EventStream<'w> {
+    fn new(out: &'w mut dyn Write) -> Self {
+        Self {
+            out,
+            started: false,
+        }
+    }
+
+    fn start(&mut self) -> std::io::Result<()> {
+        if !self.started {
+            self.started = true;
+            self.out.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                  Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+            )?;
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, frame: &Value) -> std::io::Result<()> {
+        self.start()?;
+        write!(self.out, "event: message\ndata: {frame}\n\n")?;
+        self.out.flush()
+    }
+
+    fn comment(&mut self, text: &str) -> std::io::Result<()> {
+        self.start()?;
+        write!(self.out, ": {text}\n\n")?;
+        self.out.flush()
+    }
+}
+
+fn accepts_event_stream(request: &Request) -> bool {
+    request
+        .header("accept")
+        .is_some_and(|v| v.contains("text/event-stream"))
+}
+
+/// Prefix the `session` argument of a `tools/call` to `run` or a
+/// guest tool with the transport session id.
+fn scope_run_session(message: &mut Value, transport_session: &str) {
+    if message.get("method").and_then(Value::as_str) != Some("tools/call")
+        || !message
+            .pointer("/params/name")
+            .and_then(Value::as_str)
+            .is_some_and(|name| name == "run" || crate::is_guest_tool(name))
+    {
+        return;
+    }
+    if let Some(session) = message.pointer_mut("/params/arguments/session")
+        && let Some(id) = session.as_str()
+    {
+        *session = Value::String(format!("{transport_session}:{id}"));
+    }
+}
+
+/// 128 random bits, hex-encoded.
+fn new_session_id() -> std::io::Result<String> {
+    let mut bytes = [0u8; 16];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// `http(s)://localhost`, `127.0.0.0/8` or `[::1]`, any port.
+fn is_loopback_origin(origin: &str) -> bool {
+    let Some(rest) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host = if let Some(v6) = authority.strip_prefix('[') {
+        v6.split(']').next().unwrap_or_default()
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+enum Conn {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Conn {
+    /// Accepted sockets inherit the listener's non-blocking mode on
+    /// some platforms; connections are served blocking, with an idle
+    /// timeout.
+    fn prepare(&self) -> std::io::Result<()> {
+        match self {
+            Conn::Tcp(s) => {
+                s.set_nonblocking(false)?;
+                s.set_read_timeout(Some(IDLE_TIMEOUT))
+            }
+            Conn::Unix(s) => {
+                s.set_nonblocking(false)?;
+                s.set_read_timeout(Some(IDLE_TIMEOUT))
+            }
+        }
+    }
+}
+
+impl Read for Conn {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Conn::Tcp(s) => s.read(buf),
+            Conn::Unix(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Conn {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Conn::Tcp(s) => s.write(buf),
+            Conn::Unix(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Conn::Tcp(s) => s.flush(),
+            Conn::Unix(s) => s.flush(),
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    target: String,
+    version: String,
+    /// Lower-cased names.
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn keep_alive(&self) -> bool {
+        let connection = self.header("connection").map(str::to_ascii_lowercase);
+        match self.version.as_str() {
+            "HTTP/1.0" => connection.as_deref() == Some("keep-alive"),
+            _ => connection.as_deref() != Some("close"),
+        }
+    }
+}
+
+/// Read one request. `Ok(None)` when the client closed (or idled out)
+/// between requests; `Err` carries the reply for a malformed one.
+/// `authorized` runs as soon as the headers are parsed, so an
+/// unauthenticated client gets `401` without the body being read.
+fn read_request<R: BufRead>(
+    reader: &mut R,
+    authorized: impl Fn(&Request) -> bool,
+) -> Result<Option<Request>, Reply> {
+    let mut head_bytes = 0usize;
+    let mut next_line = |reader: &mut R| -> Result<Option<String>, Reply> {
+        let mut line = String::new();
+        let n = reader
+            .by_ref()
+            .take((MAX_HEADER_BYTES - head_bytes + 1) as u64)
+            .read_line(&mut line)
+            .map_err(|_| Reply::status(400, "malformed request"))?;
+        head_bytes += n;
+        if head_bytes > MAX_HEADER_BYTES {
+            return Err(Reply::status(431, "request headers too large"));
+        }
+        Ok((n > 0).then(|| line.trim_end_matches(['\r', '\n']).to_string()))
+    };
+
+    let request_line = match reader.fill_buf() {
+        Ok([]) => return Ok(None),
+        Ok(_) => match next_line(reader)? {
+            Some(l) => l,
+            None => return Ok(None),
+        },
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+            ) =>
+        {
+            return Ok(None);
+        }
+        Err(_) => return Ok(None),
+    };
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(Reply::status(400, "malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(Reply::status(505, "HTTP version not supported"));
+    }
+
+    let mut headers = Vec::new();
+    loop {
+        let Some(line) = next_line(reader)? else {
+            return Err(Reply::status(400, "truncated request headers"));
+        };
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(Reply::status(400, "malformed header"));
+        };
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    let mut request = Request {
+        method: method.to_string(),
+        target: target.to_string(),
+        version: version.to_string(),
+        headers,
+        body: Vec::new(),
+    };
+    if !authorized(&request) {
+        return Err(Reply::unauthorized());
+    }
+
+    if request.header("transfer-encoding").is_some() {
+        return Err(Reply::status(411, "chunked bodies are not supported"));
+    }
+    let length = match request.header("content-length") {
+        Some(v) => v
+            .parse::<usize>()
+            .map_err(|_| Reply::status(400, "malformed Content-Length"))?,
+        None if request.method == "POST" => {
+            return Err(Reply::status(411, "Content-Length required"));
+        }
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Reply::status(413, "request body too large"));
+    }
+    request.body.resize(length, 0);
+    reader
+        .read_exact(&mut request.body)
+        .map_err(|_| Reply::status(400, "truncated request body"))?;
+    Ok(Some(request))
+}
+
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn unauthorized() -> Self {
+        Reply::status(401, "missing or invalid bearer token")
+            .header("WWW-Authenticate", "Bearer".to_string())
+    }
+
+    fn status(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            headers: Vec::new(),
+            body: message.as_bytes().to_vec(),
+        }
+    }
+
+    fn rpc(status: u16, response: &JsonRpcResponse) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: serde_json::to_vec(response).unwrap_or_default(),
+        }
+    }
+
+    fn header(mut self, name: &'static str, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+}
+
+fn write_response<W: Write>(w: &mut W, reply: &Reply, keep_alive: bool) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: {}\r\n",
+        reply.status,
+        reason_phrase(reply.status),
+        reply.content_type,
+        reply.body.len(),
+        if keep_alive { "keep-alive" } else { "close" },
+    );
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    w.write_all(head.as_bytes())?;
+    w.write_all(&reply.body)?;
+    w.flush()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        505 => "HTTP Version Not Supported",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_origins_are_recognised() {
+        assert!(is_loopback_origin("http://localhost:3000"));
+        assert!(is_loopback_origin("https://127.0.0.1"));
+        assert!(is_loopback_origin("http://[::1]:8080/x"));
+        assert!(!is_loopback_origin("https://evil.example"));
+        assert!(!is_loopback_origin("http://localhost.evil.example"));
+        assert!(!is_loopback_origin("null"));
+    }
+
+    #[test]
+    fn constant_time_eq_compares_bytes() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+
+    #[test]
+    fn run_session_is_scoped_to_the_transport_session() {
+        let mut msg = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+            "params": {"name": "run", "arguments": {"env": "shell", "code": "x", "session": "s1"}}
+        });
+        scope_run_session(&mut msg, "abc");
+        assert_eq!(msg.pointer("/params/arguments/session").unwrap(), "abc:s1");
+
+        let mut other = serde_json::json!({
+            "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": {"name": "mvm.time_now", "arguments": {"session": "s1"}}
+        });
+        scope_run_session(&mut other, "abc");
+        assert_eq!(other.pointer("/params/arguments/session").unwrap(), "s1");
+
+        let mut guest = serde_json::json!({
+            "jsonrpc": "2.0", "id": 3, "method": "tools/call",
+            "params": {"name": "mvm.fs.read", "arguments": {"session": "s1", "path": "/f"}}
+        });
+        scope_run_session(&mut guest, "abc");
+        assert_eq!(
+            guest.pointer("/params/arguments/session").unwrap(),
+            "abc:s1"
+        );
+    }
+
+    #[test]
+    fn read_request_parses_headers_and_body() {
+        let raw =
+            b"POST /mcp HTTP/1.1\r\nHost: x\r\nContent-Length: 2\r\nMcp-Session-Id: s\r\n\r\n{}";
+        let req = read_request(&mut &raw[..], |_| true)
+            .ok()
+            .flatten()
+            .unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.path(), "/mcp");
+        assert_eq!(req.header(SESSION_HEADER), Some("s"));
+        assert_eq!(req.body, b"{}");
+        assert!(req.keep_alive());
+    }
+
+    #[test]
+    fn read_request_refuses_post_without_length_and_oversized_heads() {
+        let raw = b"POST /mcp HTTP/1.1\r\n\r\n";
+        assert_eq!(
+            read_request(&mut &raw[..], |_| true).err().unwrap().status,
+            411
+        );
+
+        let mut raw = b"GET /mcp HTTP/1.1\r\nX: ".to_vec();
+        raw.extend(std::iter::repeat_n(b'a', MAX_HEADER_BYTES));
+        raw.extend(b"\r\n\r\n");
+        assert_eq!(
+            read_request(&mut &raw[..], |_| true).err().unwrap().status,
+            431
+        );
+    }
+
+    #[test]
+    fn unauthenticated_request_is_refused_before_the_body_is_read() {
+        // Claims a 16 MiB body but sends none: the 401 must come from
+        // the headers alone, not a `truncated request body` 400.
+        let raw =
+            format!("POST /mcp HTTP/1.1\r\nHost: x\r\nContent-Length: {MAX_BODY_BYTES}\r\n\r\n");
+        let reply = read_request(&mut raw.as_bytes(), |_| false).err().unwrap();
+        assert_eq!(reply.status, 401);
+    }
+
+    #[test]
+    fn token_whitespace_is_trimmed_on_both_sides() {
+        let server = HttpServer::bind(
+            &Listen::Tcp("127.0.0.1:0".parse().unwrap()),
+            SecretBox::new(Box::new("s3cret\n".to_string())),
+            SessionConfig::default(),
+        )
+        .unwrap();
+        let raw = b"GET /mcp HTTP/1.1\r\nAuthorization: Bearer s3cret  \r\n\r\n";
+        let req = read_request(&mut &raw[..], |_| true)
+            .ok()
+            .flatten()
+            .unwrap();
+        assert!(server.authorized(&req));
+    }
+
+    #[test]
+    fn non_loopback_bind_is_refused() {
+        let token = SecretBox::new(Box::new("t".to_string()));
+        let err = HttpServer::bind(
+            &Listen::Tcp("0.0.0.0:0".parse().unwrap()),
+            token,
+            SessionConfig::default(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("non-loopback"), "{err}");
+    }
+
+    struct EchoDispatcher;
+
+    impl Dispatcher for EchoDispatcher {
+        fn run(&self, params: crate::tools::RunParams) -> crate::protocol::ToolResult {
+            crate::protocol::ToolResult {
+                content: vec![crate::protocol::ContentBlock::Text {
+                    text: params.session.unwrap_or_default(),
+                }],
+                is_error: false,
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingReaper(Mutex<Vec<(String, ReapReason)>>);
+
+    impl Reaper for RecordingReaper {
+        fn on_reap(&self, session_id: &str, state: &SessionState, reason: ReapReason) {
+            assert_eq!(state.env, TRANSPORT_SESSION_ENV);
+            self.0
+                .lock()
+                .unwrap()
+                .push((session_id.to_string(), reason));
+        }
+    }
+
+    /// Send one request on a fresh connection; return status, the
+    /// session header and the body.
+    fn call(
+        addr: SocketAddr,
+        method: &str,
+        token: &str,
+        session: Option<&str>,
+        body: &str,
+    ) -> (u16, Option<String>, String) {
+        read_reply(send(addr, method, token, session, &[], body))
+    }
+
+    fn send(
+        addr: SocketAddr,
+        method: &str,
+        token: &str,
+        session: Option<&str>,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut head = format!(
+            "{method} {ENDPOINT_PATH} HTTP/1.1\r\nHost: x\r\nAuthorization: Bearer {token}\r\n\
+             Content-Length: {}\r\nConnection: close\r\n",
+            body.len()
+        );
+        if let Some(id) = session {
+            head.push_str(&format!("{SESSION_HEADER}: {id}\r\n"));
+        }
+        for (name, value) in headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.write_all(body.as_bytes()).unwrap();
+        stream
+    }
+
+    fn read_reply(mut stream: TcpStream) -> (u16, Option<String>, String) {
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).unwrap();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        let status = head[9..12].parse().unwrap();
+        let session = head.lines().find_map(|l| {
+            l.strip_prefix(&format!("{SESSION_HEADER}: "))
+                .map(str::to_string)
+        });
+        (status, session, body.to_string())
+    }
+
+    #[test]
+    fn sessions_round_trip_over_loopback() {
+        let server = HttpServer::bind(
+            &Listen::Tcp("127.0.0.1:0".parse().unwrap()),
+            SecretBox::new(Box::new("s3cret".to_string())),
+            SessionConfig::default(),
+        )
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        let reaper = RecordingReaper::default();
+        let stop = server.shutdown_handle();
+
+        let session = std::thread::scope(|scope| {
+            scope.spawn(|| server.serve(&EchoDispatcher, &reaper).unwrap());
+
+            let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"clientInfo":{"name":"ci"}}}"#;
+            assert_eq!(call(addr, "POST", "wrong", None, init).0, 401);
+            let (status, session, body) = call(addr, "POST", "s3cret", None, init);
+            assert_eq!(status, 200, "{body}");
+            assert!(body.contains("protocolVersion"), "{body}");
+            let session = session.expect("initialize mints a session id");
+            assert_eq!(session.len(), 32);
+
+            let run = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"run","arguments":{"env":"shell","code":"x","session":"s1"}}}"#;
+            assert_eq!(call(addr, "POST", "s3cret", None, run).0, 400);
+            assert_eq!(call(addr, "POST", "s3cret", Some("nope"), run).0, 404);
+            let (status, _, body) = call(addr, "POST", "s3cret", Some(&session), run);
+            assert_eq!(status, 200, "{body}");
+            assert!(body.contains(&format!("{session}:s1")), "{body}");
+
+            let note = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+            assert_eq!(call(addr, "POST", "s3cret", Some(&session), note).0, 202);
+            assert_eq!(call(addr, "GET", "s3cret", Some(&session), "").0, 406);
+
+            assert_eq!(server.session_count(), 1);
+            assert_eq!(call(addr, "DELETE", "s3cret", Some(&session), "").0, 204);
+            assert_eq!(call(addr, "POST", "s3cret", Some(&session), run).0, 404);
+
+            stop.store(true, Ordering::Relaxed);
+            session
+        });
+        assert_eq!(
+            reaper.0.lock().unwrap().as_slice(),
+            &[(session, ReapReason::Closed)]
+        );
+    }
+
+    /// Reports progress, then waits (bounded) for a cancel. The tool
+    /// revision is bumped by every call.
+    #[derive(Default)]
+    struct ProgressDispatcher {
+        revision: AtomicUsize,
+    }
+
+    impl Dispatcher for ProgressDispatcher {
+        fn run(&self, _params: crate::tools::RunParams) -> crate::protocol::ToolResult {
+            unreachable!("run_with_peer is overridden")
+        }
+
+        fn run_with_peer(
+            &self,
+            params: crate::tools::RunParams,
+            peer: &dyn ClientPeer,
+        ) -> crate::protocol::ToolResult {
+            self.revision.fetch_add(1, Ordering::SeqCst);
+            peer.progress(1.0, None, Some("working"));
+            let token = peer.cancel_token();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while params.code == "wait" && !token.is_cancelled() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            crate::protocol::ToolResult {
+                content: vec![crate::protocol::ContentBlock::Text {
+                    text: peer.session_scope().unwrap_or_default(),
+                }],
+                is_error: false,
+            }
+        }
+
+        fn tools_revision(&self) -> u64 {
+            self.revision.load(Ordering::SeqCst) as u64
+        }
+    }
+
+    #[test]
+    fn calls_stream_progress_and_honour_cancellation() {
+        let server = HttpServer::bind(
+            &Listen::Tcp("127.0.0.1:0".parse().unwrap()),
+            SecretBox::new(Box::new("t".to_string())),
+            SessionConfig::default(),
+        )
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        let reaper = RecordingReaper::default();
+        let stop = server.shutdown_handle();
+        let dispatcher = ProgressDispatcher::default();
+        let sse = [("Accept", "application/json, text/event-stream")];
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| server.serve(&dispatcher, &reaper).unwrap());
+            let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#;
+            let session = call(addr, "POST", "t", None, init).1.unwrap();
+
+            // The list-changed stream, opened before the calls below
+            // bump the revision.
+            let mut listener = BufReader::new(send(addr, "GET", "t", Some(&session), &sse, ""));
+            let mut status = String::new();
+            listener.read_line(&mut status).unwrap();
+            assert!(status.starts_with("HTTP/1.1 200"), "{status}");
+
+            let run = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"run","arguments":{"env":"shell","code":"x"},"_meta":{"progressToken":7}}}"#;
+            let (status, _, body) = read_reply(send(addr, "POST", "t", Some(&session), &sse, run));
+            assert_eq!(status, 200);
+            let events: Vec<Value> = body
+                .lines()
+                .filter_map(|l| l.strip_prefix("data: "))
+                .map(|d| serde_json::from_str(d).unwrap())
+                .collect();
+            assert_eq!(events.len(), 2, "{body}");
+            assert_eq!(events[0]["params"]["progressToken"], 7);
+            assert_eq!(events[1]["id"], 2);
+            assert_eq!(events[1]["result"]["content"][0]["text"], session.as_str());
+
+            // Without an event-stream Accept, progress is dropped.
+            let (status, _, body) = call(addr, "POST", "t", Some(&session), run);
+            assert_eq!(status, 200);
+            assert!(body.starts_with('{'), "{body}");
+
+            let wait = r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"run","arguments":{"env":"shell","code":"wait"}}}"#;
+            let pending = send(addr, "POST", "t", Some(&session), &[], wait);
+            let cancel =
+                r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":3}}"#;
+            assert_eq!(call(addr, "POST", "t", Some(&session), cancel).0, 202);
+            let started = Instant::now();
+            assert_eq!(read_reply(pending).0, 202);
+            assert!(started.elapsed() < Duration::from_secs(4));
+
+            let mut saw_list_changed = false;
+            for line in listener.by_ref().lines() {
+                if line.unwrap().contains(TOOLS_LIST_CHANGED_METHOD) {
+                    saw_list_changed = true;
+                    break;
+                }
+            }
+            assert!(saw_list_changed);
+
+            stop.store(true, Ordering::Relaxed);
+        });
+    }
+
+    #[test]
+    fn unix_socket_is_owner_only() {
+        let dir = std::env::temp_dir().join(format!("mvm-mcp-http-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mcp.sock");
+        let server = HttpServer::bind(
+            &Listen::Unix(path.clone()),
+            SecretBox::new(Box::new("t".to_string())),
+            SessionConfig::default(),
+        )
+        .unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // Only the socket is left behind; the bind staging dir is gone.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        drop(server);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_token_is_refused() {
+        let token = SecretBox::new(Box::new(String::new()));
+        assert!(
+            HttpServer::bind(
+                &Listen::Tcp("127.0.0.1:0".parse().unwrap()),
+                token,
+                SessionConfig::default()
+            )
+            .is_err()
+        );
+    }
+}
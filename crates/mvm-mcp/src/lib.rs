@@ -3,6 +3,8 @@
 //! Exposes mvm's microVM template registry as a single parameterized
 //! `run` tool. LLM clients (Claude Code, opencode, etc.) connect over
 //! stdio, list tools, and dispatch code into transient microVMs.
+//! Warm sessions are also readable as MCP resources, and a handful of
+//! prompts steer clients toward them.
 //!
 //! ## Features
 //!
@@ -15,33 +17,48 @@
 //!   mvmctl impl lives in `mvm-cli::commands::ops::mcp` (which has
 //!   access to `crate::exec` for the actual VM dispatch).
 //!
+//! - `http` — adds the local streamable-HTTP transport (loopback TCP
+//!   or unix socket, bearer-token auth, `Mcp-Session-Id` sessions) so
+//!   several clients can share one host process.
+//!
 //! ## Threat model
 //!
 //! See `specs/adrs/003-local-mcp-server.md` for the full posture.
-//! Summary: the transport is host-local stdio. No new attacker
+//! Summary: the transport is host-local (stdio, or HTTP on loopback
+//! / a 0600 unix socket behind a bearer token). No new attacker
 //! surface beyond ADR-002 — code runs entirely inside the
 //! already-isolated microVM. The `env` parameter is allowlisted
 //! against the existing template registry; `code` is passed as a
 //! single argv element to the guest interpreter.
 
 pub mod dispatcher;
+pub mod prompts;
 pub mod protocol;
 pub mod session;
 pub mod tools;
 
-pub use dispatcher::Dispatcher;
+pub use dispatcher::{CancelToken, ClientPeer, Dispatcher, NoClientPeer};
+pub use prompts::{Prompt, PromptArgument, PromptMessage, all_prompts};
 pub use protocol::{
-    ContentBlock, JsonRpcError, JsonRpcRequest, JsonRpcResponse, PROTOCOL_VERSION, SERVER_NAME,
-    SERVER_VERSION, ToolResult,
+    CANCELLED_METHOD, ContentBlock, ELICITATION_METHOD, ElicitAction, ElicitResult, JsonRpcError,
+    JsonRpcRequest, JsonRpcResponse, PROGRESS_METHOD, PROTOCOL_VERSION, Resource, ResourceContents,
+    ResourceTemplate, SERVER_NAME, SERVER_VERSION, TOOLS_LIST_CHANGED_METHOD, ToolResult,
 };
 pub use session::{
     DEFAULT_IDLE_SECS, DEFAULT_MAX_SECS, ReapReason, Reaper, SessionConfig, SessionLookup,
     SessionMap, SessionState,
 };
-pub use tools::{RunParams, ToolSchema, all_tools, run_input_schema};
+pub use tools::guest::{GuestToolCall, guest_tools, is_guest_tool};
+pub use tools::{RunParams, ToolSchema, all_tools, all_tools_for_envs, run_input_schema};
 
 #[cfg(feature = "stdio")]
 pub mod server;
 
 #[cfg(feature = "stdio")]
 pub use server::{init_stderr_tracing, run_with_dispatcher};
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "http")]
+pub use http::{HttpServer, Listen};
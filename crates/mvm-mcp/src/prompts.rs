@@ -0,0 +1,158 @@
+//! Prompt templates exposed via MCP `prompts/list` / `prompts/get`.
+//!
+//! Like the tool schemas, these are `protocol-only`: static text that
+//! any transport can serve without a dispatcher. They steer a client
+//! toward the patterns the `run` tool and the session resources are
+//! built for (one warm session per task, closed at the end).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::protocol::{ContentBlock, JsonRpcError};
+
+/// One prompt in `prompts/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prompt {
+    pub name: String,
+    pub description: String,
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// A named argument the client fills in before `prompts/get`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// One message of a rendered prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    pub role: String,
+    pub content: ContentBlock,
+}
+
+/// All prompts exposed by mvmctl mcp.
+///
+/// - `sandbox-task` — carry out a task in one warm `run` session.
+/// - `inspect-session` — review a session through its resources.
+pub fn all_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt {
+            name: "sandbox-task".to_string(),
+            description: "Carry out a task inside one warm mvm microVM session.".to_string(),
+            arguments: vec![
+                PromptArgument {
+                    name: "task".to_string(),
+                    description: "What to do.".to_string(),
+                    required: true,
+                },
+                PromptArgument {
+                    name: "env".to_string(),
+                    description: "Environment for the `run` tool. Default 'shell'.".to_string(),
+                    required: false,
+                },
+            ],
+        },
+        Prompt {
+            name: "inspect-session".to_string(),
+            description: "Summarise what a `run` session did and the files it left behind."
+                .to_string(),
+            arguments: vec![PromptArgument {
+                name: "session".to_string(),
+                description: "The `session` id passed to `run`.".to_string(),
+                required: true,
+            }],
+        },
+    ]
+}
+
+/// Render prompt `name` with `arguments`. Returns the description and
+/// the messages of a `prompts/get` result.
+pub fn render_prompt(
+    name: &str,
+    arguments: &Map<String, Value>,
+) -> Result<(String, Vec<PromptMessage>), JsonRpcError> {
+    let prompt = all_prompts()
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| JsonRpcError::invalid_params(format!("unknown prompt {name:?}")))?;
+    let arg = |key: &str| arguments.get(key).and_then(Value::as_str);
+    for required in prompt.arguments.iter().filter(|a| a.required) {
+        if arg(&required.name).is_none_or(str::is_empty) {
+            return Err(JsonRpcError::invalid_params(format!(
+                "prompt {name:?} needs argument {:?}",
+                required.name
+            )));
+        }
+    }
+    let text = match name {
+        "sandbox-task" => format!(
+            "Carry out the task below inside an mvm microVM using the `run` tool with \
+             env '{env}'. Pass the same `session` id to every call so files and installed \
+             packages persist, check each call's exit_code before moving on, and set \
+             `close: true` on the last call.\n\nTask: {task}",
+            env = arg("env").filter(|e| !e.is_empty()).unwrap_or("shell"),
+            task = arg("task").unwrap_or_default(),
+        ),
+        "inspect-session" => {
+            let session = arg("session").unwrap_or_default();
+            format!(
+                "Inspect the mvm session '{session}'. Read mvm://sessions/{session}/boot-report \
+                 and mvm://sessions/{session}/audit, list mvm://sessions/{session}/files/ and \
+                 read any files that look like results. Summarise what the session ran, \
+                 whether it succeeded, and what it produced."
+            )
+        }
+        _ => unreachable!("prompt {name:?} is listed but not rendered"),
+    };
+    Ok((
+        prompt.description,
+        vec![PromptMessage {
+            role: "user".to_string(),
+            content: ContentBlock::Text { text },
+        }],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(pairs: &[(&str, &str)]) -> Map<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn every_listed_prompt_renders() {
+        for prompt in all_prompts() {
+            let filled: Vec<(&str, &str)> = prompt
+                .arguments
+                .iter()
+                .map(|a| (a.name.as_str(), "x"))
+                .collect();
+            let (_, messages) = render_prompt(&prompt.name, &args(&filled)).unwrap();
+            assert_eq!(messages.len(), 1, "{}", prompt.name);
+        }
+    }
+
+    #[test]
+    fn sandbox_task_defaults_env_to_shell() {
+        let (_, messages) = render_prompt("sandbox-task", &args(&[("task", "count")])).unwrap();
+        let ContentBlock::Text { text } = &messages[0].content;
+        assert!(text.contains("env 'shell'"), "{text}");
+        assert!(text.ends_with("Task: count"), "{text}");
+    }
+
+    #[test]
+    fn missing_required_argument_is_invalid_params() {
+        let err = render_prompt("inspect-session", &Map::new()).unwrap_err();
+        assert_eq!(err.code, -32602);
+        assert_eq!(render_prompt("nope", &Map::new()).unwrap_err().code, -32602);
+    }
+}
@@ -22,9 +22,7 @@ pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// JSON-RPC 2.0 request frame.
 ///
-/// `id` is `Option` because `notifications/*` methods are id-less; we
-/// don't currently emit notifications but the wire format requires
-/// tolerating them on the way in.
+/// `id` is `Option` because `notifications/*` methods are id-less.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct JsonRpcRequest {
@@ -92,6 +90,15 @@ impl JsonRpcError {
             data: None,
         }
     }
+    /// MCP's code for `resources/read` of a URI the server does not
+    /// serve. `data.uri` echoes the URI.
+    pub fn resource_not_found(uri: &str) -> Self {
+        Self {
+            code: -32002,
+            message: "Resource not found".to_string(),
+            data: Some(serde_json::json!({ "uri": uri })),
+        }
+    }
 }
 
 impl JsonRpcResponse {
@@ -133,6 +140,96 @@ fn is_false(b: &bool) -> bool {
     !*b
 }
 
+/// Server-to-client request asking the user for structured input
+/// mid-call. Only sent to clients that declared the `elicitation`
+/// capability in `initialize`.
+pub const ELICITATION_METHOD: &str = "elicitation/create";
+
+/// What the user did with an elicitation prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    /// Submitted the form; `content` carries the answers.
+    Accept,
+    /// Explicitly refused.
+    Decline,
+    /// Dismissed without choosing.
+    Cancel,
+}
+
+/// `elicitation/create` result shape per the MCP spec.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitResult {
+    pub action: ElicitAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<serde_json::Value>,
+}
+
+/// Server-to-client notification reporting progress on a request
+/// that carried `_meta.progressToken`.
+pub const PROGRESS_METHOD: &str = "notifications/progress";
+
+/// Either side's notification that it gave up on a request. The
+/// receiver stops the work and sends no response.
+pub const CANCELLED_METHOD: &str = "notifications/cancelled";
+
+/// Server-to-client notification that `tools/list` would now answer
+/// differently.
+pub const TOOLS_LIST_CHANGED_METHOD: &str = "notifications/tools/list_changed";
+
+/// A JSON-RPC notification frame (no `id`), as the server sends it.
+pub fn notification(method: &str, params: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// One entry of `resources/list`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// One entry of `resources/templates/list`: an RFC 6570 URI template
+/// the client can fill in and pass to `resources/read`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// One item of a `resources/read` result. Exactly one of `text` or
+/// `blob` (base64) is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(default, rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+impl ResourceContents {
+    pub fn text(uri: impl Into<String>, mime_type: &str, text: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            mime_type: Some(mime_type.to_string()),
+            text: Some(text.into()),
+            blob: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +279,24 @@ mod tests {
         assert!(!s.contains("isError"), "is_error=false omitted");
     }
 
+    #[test]
+    fn resource_types_use_mcp_field_names() {
+        let r = Resource {
+            uri: "mvm://x".to_string(),
+            name: "x".to_string(),
+            description: None,
+            mime_type: Some("text/plain".to_string()),
+        };
+        let s = serde_json::to_string(&r).unwrap();
+        assert!(s.contains(r#""mimeType":"text/plain""#), "{s}");
+        assert!(!s.contains("description"), "{s}");
+
+        let c = ResourceContents::text("mvm://x", "application/json", "{}");
+        let s = serde_json::to_string(&c).unwrap();
+        assert!(!s.contains("blob"), "{s}");
+        assert_eq!(JsonRpcError::resource_not_found("mvm://x").code, -32002);
+    }
+
     #[test]
     fn tool_result_error_emits_is_error_field() {
         let r = ToolResult {
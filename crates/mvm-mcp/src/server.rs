@@ -5,17 +5,42 @@
 //! output must go to stderr** — a stray byte on stdout corrupts the
 //! wire. Cross-cutting "A: stdout-only-JSON-RPC discipline" enforces
 //! this via `init_stderr_tracing` below and a CI smoke test.
+//!
+//! A reader thread owns stdin so input is seen while a call runs:
+//! `notifications/cancelled` trips the named call's [`CancelToken`]
+//! straight away, and every other line is queued for the loop. When
+//! the loop is idle, and after each `tools/call`, it polls
+//! [`Dispatcher::tools_revision`] and sends
+//! `notifications/tools/list_changed` when the revision moved.
 
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
 use std::io::{BufRead, Write};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
+use serde::Serialize;
 use serde_json::Value;
 
-use crate::dispatcher::Dispatcher;
+use crate::dispatcher::{CancelToken, ClientPeer, Dispatcher};
+use crate::prompts::{all_prompts, render_prompt};
 use crate::protocol::{
-    JsonRpcError, JsonRpcRequest, JsonRpcResponse, PROTOCOL_VERSION, SERVER_NAME, SERVER_VERSION,
+    CANCELLED_METHOD, ELICITATION_METHOD, ElicitResult, JsonRpcError, JsonRpcRequest,
+    JsonRpcResponse, PROGRESS_METHOD, PROTOCOL_VERSION, SERVER_NAME, SERVER_VERSION,
+    TOOLS_LIST_CHANGED_METHOD, notification,
 };
-use crate::tools::{RunParams, all_tools};
+use crate::tools::RunParams;
+
+/// How long an idle loop waits for input before polling
+/// [`Dispatcher::tools_revision`].
+pub(crate) const LIST_CHANGED_POLL: Duration = Duration::from_secs(5);
+
+/// A `notifications/cancelled` can overtake the request it names
+/// (HTTP requests race on separate connections). Such a cancel is
+/// kept this long for the request to pick up.
+const EARLY_CANCEL_TTL: Duration = Duration::from_secs(60);
 
 /// Initialize a stderr-only tracing subscriber. MUST be called before
 /// the dispatch loop, since `tracing` defaults to stdout — and a
@@ -29,9 +54,9 @@ pub fn init_stderr_tracing() {
         .try_init();
 }
 
-/// Dispatch one JSON-RPC line through `dispatcher` and either return
-/// a response (request) or `None` (notification — no reply expected).
-pub fn run_with_dispatcher<R: BufRead, W: Write, D: Dispatcher>(
+/// Serve JSON-RPC lines from `reader` through `dispatcher` until the
+/// client closes its end. Responses and notifications go to `writer`.
+pub fn run_with_dispatcher<R: BufRead + Send, W: Write, D: Dispatcher>(
     reader: R,
     writer: &mut W,
     dispatcher: &D,
@@ -46,31 +71,398 @@ pub fn run_with_dispatcher<R: BufRead, W: Write, D: Dispatcher>(
         version = SERVER_VERSION,
         "mvm-mcp stdio loop ready"
     );
-    for line in reader.lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(e) => {
+    let cancellations = Cancellations::default();
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|scope| {
+        let routed = &cancellations;
+        scope.spawn(move || {
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                };
+                // A cancel must not queue behind the call it cancels.
+                if routed.route(&line) {
+                    continue;
+                }
+                if tx.send(Ok(line)).is_err() {
+                    return;
+                }
+            }
+        });
+        let peer = StdioPeer::new(rx, writer, &cancellations);
+        serve_lines(&peer, dispatcher)
+    })
+}
+
+fn serve_lines<W: Write, D: Dispatcher>(peer: &StdioPeer<'_, W>, dispatcher: &D) -> Result<()> {
+    let mut tools_revision = dispatcher.tools_revision();
+    loop {
+        let check_tools = match peer.next_line(LIST_CHANGED_POLL) {
+            Inbound::Line(line) => {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let is_call = line.contains("\"tools/call\"");
+                if let Some(resp) = handle_one(&line, dispatcher, peer) {
+                    peer.send(&resp)?;
+                }
+                is_call
+            }
+            Inbound::Idle => true,
+            Inbound::Closed => break,
+            Inbound::Failed(e) => {
                 tracing::error!(err=%e, "stdin read error");
                 return Err(e.into());
             }
         };
-        if line.trim().is_empty() {
-            continue;
-        }
-        match handle_one(&line, dispatcher) {
-            Some(resp) => {
-                let s = serde_json::to_string(&resp)?;
-                writeln!(writer, "{s}")?;
-                writer.flush()?;
+        if check_tools && peer.initialized.get() {
+            let revision = dispatcher.tools_revision();
+            if revision != tools_revision {
+                tools_revision = revision;
+                peer.send(&notification(
+                    TOOLS_LIST_CHANGED_METHOD,
+                    serde_json::json!({}),
+                ))?;
             }
-            None => continue,
         }
     }
     Ok(())
 }
 
-/// Parse one line and produce zero (notification) or one response.
-fn handle_one<D: Dispatcher>(line: &str, dispatcher: &D) -> Option<JsonRpcResponse> {
+/// What the loop got from the reader thread.
+enum Inbound {
+    Line(String),
+    /// Nothing arrived within the poll interval.
+    Idle,
+    /// The client closed stdin.
+    Closed,
+    Failed(std::io::Error),
+}
+
+/// The stdio connection, shared between the dispatch loop and any
+/// [`ClientPeer`] call a dispatcher makes mid-request.
+///
+/// The loop is serial, so while a dispatcher waits for its
+/// `elicitation/create` answer, every other line the client sends
+/// (new requests, notifications) is deferred and handled after the
+/// current call returns.
+struct StdioPeer<'a, W> {
+    inbound: Receiver<std::io::Result<String>>,
+    writer: RefCell<&'a mut W>,
+    deferred: RefCell<VecDeque<String>>,
+    next_request_id: Cell<u64>,
+    client: RefCell<ClientState>,
+    initialized: Cell<bool>,
+    cancellations: &'a Cancellations,
+}
+
+/// What the client told us about itself in `initialize`.
+#[derive(Default, Clone)]
+pub(crate) struct ClientState {
+    pub(crate) elicitation: bool,
+    pub(crate) name: Option<String>,
+}
+
+impl ClientState {
+    pub(crate) fn from_initialize(params: Option<&Value>) -> Self {
+        Self {
+            elicitation: params
+                .and_then(|p| p.pointer("/capabilities/elicitation"))
+                .is_some_and(Value::is_object),
+            name: params
+                .and_then(|p| p.pointer("/clientInfo/name"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }
+    }
+}
+
+/// A [`ClientPeer`] that also learns about the client from its
+/// `initialize` request. Each transport keeps the result for the
+/// lifetime of its connection or session.
+pub(crate) trait InitializingPeer: ClientPeer {
+    fn record_initialize(&self, params: Option<&Value>);
+
+    /// Requests in flight on this connection or session.
+    fn cancellations(&self) -> &Cancellations;
+
+    /// Send a server-to-client notification. Transports that cannot
+    /// reach the client right now drop it.
+    fn notify(&self, frame: &Value);
+}
+
+/// Cancel tokens of the requests in flight, keyed by JSON-RPC id.
+#[derive(Default)]
+pub(crate) struct Cancellations(Mutex<BTreeMap<String, InFlight>>);
+
+struct InFlight {
+    token: CancelToken,
+    /// Set when the cancel arrived before its request started.
+    cancelled_early: Option<Instant>,
+}
+
+impl Cancellations {
+    /// Register request `id`. A cancel that overtook it leaves the
+    /// returned token already tripped.
+    pub(crate) fn begin(&self, id: &Value) -> CancelToken {
+        let Ok(mut map) = self.0.lock() else {
+            return CancelToken::default();
+        };
+        let entry = map.entry(id.to_string()).or_insert_with(|| InFlight {
+            token: CancelToken::default(),
+            cancelled_early: None,
+        });
+        entry.cancelled_early = None;
+        entry.token.clone()
+    }
+
+    pub(crate) fn finish(&self, id: &Value) {
+        if let Ok(mut map) = self.0.lock() {
+            map.remove(&id.to_string());
+        }
+    }
+
+    /// Handle `notifications/cancelled` params: trip the token of the
+    /// request `requestId` names.
+    pub(crate) fn cancel(&self, params: Option<&Value>) {
+        let Some(id) = params.and_then(|p| p.get("requestId")) else {
+            return;
+        };
+        let Ok(mut map) = self.0.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        map.retain(|_, f| {
+            f.cancelled_early
+                .is_none_or(|at| now.duration_since(at) < EARLY_CANCEL_TTL)
+        });
+        let reason = params
+            .and_then(|p| p.get("reason"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        tracing::info!(request = %id, reason, "client cancelled request");
+        map.entry(id.to_string())
+            .or_insert_with(|| InFlight {
+                token: CancelToken::default(),
+                cancelled_early: Some(now),
+            })
+            .token
+            .cancel();
+    }
+
+    /// Act on `line` if it is a `notifications/cancelled`; `true`
+    /// when it was.
+    pub(crate) fn route(&self, line: &str) -> bool {
+        if !line.contains(CANCELLED_METHOD) {
+            return false;
+        }
+        let Ok(frame) = serde_json::from_str::<Value>(line) else {
+            return false;
+        };
+        if frame.get("method").and_then(Value::as_str) != Some(CANCELLED_METHOD)
+            || frame.get("id").is_some()
+        {
+            return false;
+        }
+        self.cancel(frame.get("params"));
+        true
+    }
+}
+
+impl<'a, W: Write> StdioPeer<'a, W> {
+    fn new(
+        inbound: Receiver<std::io::Result<String>>,
+        writer: &'a mut W,
+        cancellations: &'a Cancellations,
+    ) -> Self {
+        Self {
+            inbound,
+            writer: RefCell::new(writer),
+            deferred: RefCell::new(VecDeque::new()),
+            next_request_id: Cell::new(1),
+            client: RefCell::new(ClientState::default()),
+            initialized: Cell::new(false),
+            cancellations,
+        }
+    }
+
+    /// Next line for the dispatch loop: deferred lines first.
+    fn next_line(&self, wait: Duration) -> Inbound {
+        if let Some(line) = self.deferred.borrow_mut().pop_front() {
+            return Inbound::Line(line);
+        }
+        match self.inbound.recv_timeout(wait) {
+            Ok(Ok(line)) => Inbound::Line(line),
+            Ok(Err(e)) => Inbound::Failed(e),
+            Err(RecvTimeoutError::Timeout) => Inbound::Idle,
+            Err(RecvTimeoutError::Disconnected) => Inbound::Closed,
+        }
+    }
+
+    fn send(&self, frame: &impl Serialize) -> Result<()> {
+        let s = serde_json::to_string(frame)?;
+        let mut writer = self.writer.borrow_mut();
+        writeln!(writer, "{s}")?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: Write> InitializingPeer for StdioPeer<'_, W> {
+    fn record_initialize(&self, params: Option<&Value>) {
+        *self.client.borrow_mut() = ClientState::from_initialize(params);
+        self.initialized.set(true);
+    }
+
+    fn cancellations(&self) -> &Cancellations {
+        self.cancellations
+    }
+
+    fn notify(&self, frame: &Value) {
+        if let Err(e) = self.send(frame) {
+            tracing::warn!(err = %e, "sending notification");
+        }
+    }
+}
+
+impl<W: Write> ClientPeer for StdioPeer<'_, W> {
+    fn supports_elicitation(&self) -> bool {
+        self.client.borrow().elicitation
+    }
+
+    fn client_name(&self) -> Option<String> {
+        self.client.borrow().name.clone()
+    }
+
+    fn elicit(&self, message: &str, requested_schema: Value) -> Result<ElicitResult, JsonRpcError> {
+        if !self.supports_elicitation() {
+            return Err(JsonRpcError::invalid_request(
+                "client did not declare the elicitation capability",
+            ));
+        }
+        let n = self.next_request_id.get();
+        self.next_request_id.set(n + 1);
+        let id = Value::String(format!("mvm-elicit-{n}"));
+        self.send(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": ELICITATION_METHOD,
+            "params": {
+                "message": message,
+                "requestedSchema": requested_schema,
+            },
+        }))
+        .map_err(|e| JsonRpcError::internal_error(format!("sending elicitation: {e}")))?;
+
+        loop {
+            let line = match self.inbound.recv() {
+                Ok(Ok(line)) => line,
+                Ok(Err(e)) => {
+                    return Err(JsonRpcError::internal_error(format!(
+                        "reading elicitation answer: {e}"
+                    )));
+                }
+                Err(_) => {
+                    return Err(JsonRpcError::internal_error(
+                        "client closed the connection before answering the elicitation",
+                    ));
+                }
+            };
+            let Some(frame) = response_frame(&line) else {
+                if !line.trim().is_empty() {
+                    self.deferred.borrow_mut().push_back(line);
+                }
+                continue;
+            };
+            if frame.get("id") != Some(&id) {
+                tracing::warn!(line = %line, "dropping response to an unknown request");
+                continue;
+            }
+            if let Some(error) = frame.get("error") {
+                return Err(serde_json::from_value(error.clone()).unwrap_or_else(|_| {
+                    JsonRpcError::internal_error(format!("elicitation failed: {error}"))
+                }));
+            }
+            return serde_json::from_value(frame.get("result").cloned().unwrap_or(Value::Null))
+                .map_err(|e| {
+                    JsonRpcError::invalid_params(format!("decoding elicitation result: {e}"))
+                });
+        }
+    }
+}
+
+/// The peer a dispatcher sees for one `tools/call`: the connection's
+/// peer plus that request's progress token and cancel token.
+struct CallPeer<'p, P> {
+    peer: &'p P,
+    progress_token: Option<Value>,
+    cancel: CancelToken,
+}
+
+impl<P: InitializingPeer> ClientPeer for CallPeer<'_, P> {
+    fn supports_elicitation(&self) -> bool {
+        self.peer.supports_elicitation()
+    }
+
+    fn client_name(&self) -> Option<String> {
+        self.peer.client_name()
+    }
+
+    fn elicit(&self, message: &str, requested_schema: Value) -> Result<ElicitResult, JsonRpcError> {
+        self.peer.elicit(message, requested_schema)
+    }
+
+    fn progress(&self, progress: f64, total: Option<f64>, message: Option<&str>) {
+        let Some(token) = &self.progress_token else {
+            return;
+        };
+        let mut params = serde_json::json!({ "progressToken": token, "progress": progress });
+        if let Some(total) = total {
+            params["total"] = total.into();
+        }
+        if let Some(message) = message {
+            params["message"] = message.into();
+        }
+        self.peer.notify(&notification(PROGRESS_METHOD, params));
+    }
+
+    fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    fn session_scope(&self) -> Option<String> {
+        self.peer.session_scope()
+    }
+}
+
+/// Parse `line` as a JSON-RPC *response* (no `method`, carries
+/// `result` or `error`). `None` for requests, notifications and
+/// anything unparseable.
+fn response_frame(line: &str) -> Option<Value> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let object = value.as_object()?;
+    let is_response = !object.contains_key("method")
+        && (object.contains_key("result") || object.contains_key("error"));
+    is_response.then_some(value)
+}
+
+/// Parse one line and produce zero (notification, cancelled request)
+/// or one response.
+pub(crate) fn handle_one<D: Dispatcher, P: InitializingPeer>(
+    line: &str,
+    dispatcher: &D,
+    peer: &P,
+) -> Option<JsonRpcResponse> {
+    // A response outside an elicitation wait answers nothing we
+    // asked; replying to it would only confuse the client.
+    if response_frame(line).is_some() {
+        tracing::warn!(line = %line, "dropping unsolicited JSON-RPC response");
+        return None;
+    }
     let req: JsonRpcRequest = match serde_json::from_str(line) {
         Ok(r) => r,
         Err(e) => {
@@ -89,12 +481,46 @@ fn handle_one<D: Dispatcher>(line: &str, dispatcher: &D) -> Option<JsonRpcRespon
     }
 
     // Notifications: no id, no response.
-    let id = req.id?;
+    let Some(id) = req.id else {
+        if req.method == CANCELLED_METHOD {
+            peer.cancellations().cancel(req.params.as_ref());
+        }
+        return None;
+    };
 
     let result = match req.method.as_str() {
-        "initialize" => Ok(initialize_response()),
-        "tools/list" => Ok(tools_list_response()),
-        "tools/call" => tools_call_response(req.params, dispatcher),
+        "initialize" => {
+            peer.record_initialize(req.params.as_ref());
+            Ok(initialize_response())
+        }
+        "tools/list" => Ok(serde_json::json!({ "tools": dispatcher.list_tools() })),
+        "tools/call" => {
+            let call = CallPeer {
+                peer,
+                progress_token: req
+                    .params
+                    .as_ref()
+                    .and_then(|p| p.pointer("/_meta/progressToken"))
+                    .cloned(),
+                cancel: peer.cancellations().begin(&id),
+            };
+            let result = tools_call_response(req.params, dispatcher, &call);
+            peer.cancellations().finish(&id);
+            if call.cancel.is_cancelled() {
+                // The client gave up on this request: per the spec it
+                // gets no response.
+                tracing::info!(request = %id, "dropping response to cancelled request");
+                return None;
+            }
+            result
+        }
+        "resources/list" => Ok(serde_json::json!({ "resources": dispatcher.list_resources(peer) })),
+        "resources/templates/list" => Ok(serde_json::json!({
+            "resourceTemplates": dispatcher.resource_templates()
+        })),
+        "resources/read" => resources_read_response(req.params, dispatcher, peer),
+        "prompts/list" => Ok(serde_json::json!({ "prompts": all_prompts() })),
+        "prompts/get" => prompts_get_response(req.params),
         other => Err(JsonRpcError::method_not_found(other)),
     };
 
@@ -108,8 +534,11 @@ fn initialize_response() -> Value {
     serde_json::json!({
         "protocolVersion": PROTOCOL_VERSION,
         "capabilities": {
-            // We do not change the tool list at runtime.
-            "tools": { "listChanged": false }
+            // The `run` tool lists the registered envs, which change
+            // as templates are built and removed.
+            "tools": { "listChanged": true },
+            "resources": { "subscribe": false, "listChanged": false },
+            "prompts": { "listChanged": false },
         },
         "serverInfo": {
             "name": SERVER_NAME,
@@ -123,16 +552,15 @@ fn instructions_text() -> &'static str {
     "Run code in an mvm microVM. Use the `run` tool with `env` (built-in preset like `shell` / \
      `python` / `node`, OR an absolute path to a project directory whose `mvm.toml` has been \
      built) and `code` (the program text). Discover available envs via `mvmctl manifest ls` on \
-     the host. Users build their own via `mvmctl init <DIR> && mvmctl build <DIR>`."
-}
-
-fn tools_list_response() -> Value {
-    serde_json::json!({ "tools": all_tools() })
+     the host. Users build their own via `mvmctl init <DIR> && mvmctl build <DIR>`. A warm \
+     `session`'s files, boot report and audit trail are readable as resources under \
+     mvm://sessions/<session>/."
 }
 
 fn tools_call_response<D: Dispatcher>(
     params: Option<Value>,
     dispatcher: &D,
+    peer: &dyn ClientPeer,
 ) -> Result<Value, JsonRpcError> {
     let params = params.ok_or_else(|| JsonRpcError::invalid_params("missing params object"))?;
     let name = params
@@ -144,22 +572,51 @@ fn tools_call_response<D: Dispatcher>(
         // Legacy single-tool path — typed RunParams + microVM dispatch.
         let run_params: RunParams = serde_json::from_value(args)
             .map_err(|e| JsonRpcError::invalid_params(format!("decoding `run` params: {e}")))?;
-        dispatcher.run(run_params)
+        dispatcher.run_with_peer(run_params, peer)
     } else {
         // Plan 60 Phase 7 — typed-name registry tools (mvm.time_now,
         // mvm.web_fetch, mvm.web_search, …). The dispatcher's
         // `invoke_tool` decides; an unwired dispatcher's default impl
         // returns an `is_error: true` ToolResult naming the tool.
-        dispatcher.invoke_tool(name, args)
+        dispatcher.invoke_tool_with_peer(name, args, peer)
     };
     serde_json::to_value(&result)
         .map_err(|e| JsonRpcError::internal_error(format!("encoding tool result: {e}")))
 }
 
+fn resources_read_response<D: Dispatcher>(
+    params: Option<Value>,
+    dispatcher: &D,
+    peer: &dyn ClientPeer,
+) -> Result<Value, JsonRpcError> {
+    let uri = params
+        .as_ref()
+        .and_then(|p| p.get("uri"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| JsonRpcError::invalid_params("missing resource uri"))?;
+    let contents = dispatcher.read_resource(uri, peer)?;
+    Ok(serde_json::json!({ "contents": contents }))
+}
+
+fn prompts_get_response(params: Option<Value>) -> Result<Value, JsonRpcError> {
+    let params = params.ok_or_else(|| JsonRpcError::invalid_params("missing params object"))?;
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| JsonRpcError::invalid_params("missing prompt name"))?;
+    let arguments = match params.get("arguments") {
+        None | Some(Value::Null) => serde_json::Map::new(),
+        Some(Value::Object(map)) => map.clone(),
+        Some(_) => return Err(JsonRpcError::invalid_params("arguments must be an object")),
+    };
+    let (description, messages) = render_prompt(name, &arguments)?;
+    Ok(serde_json::json!({ "description": description, "messages": messages }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::protocol::{ContentBlock, ToolResult};
+    use crate::protocol::{ContentBlock, Resource, ResourceContents, ToolResult};
 
     /// Mock dispatcher that records the params it saw and returns a
     /// canned ToolResult.
@@ -178,8 +635,109 @@ mod tests {
         }
     }
 
+    /// `handle_one` against a client that sends nothing else.
+    fn handle(line: &str, dispatcher: &impl Dispatcher) -> Option<JsonRpcResponse> {
+        let mut out = Vec::new();
+        let cancellations = Cancellations::default();
+        let (_, inbound) = mpsc::channel();
+        let peer = StdioPeer::new(inbound, &mut out, &cancellations);
+        handle_one(line, dispatcher, &peer)
+    }
+
     fn run_one(req_json: &str, dispatcher: &impl Dispatcher) -> JsonRpcResponse {
-        handle_one(req_json, dispatcher).expect("response")
+        handle(req_json, dispatcher).expect("response")
+    }
+
+    /// Asks the client to confirm every registry tool call.
+    struct ElicitingDispatcher;
+    impl Dispatcher for ElicitingDispatcher {
+        fn run(&self, _params: RunParams) -> ToolResult {
+            unreachable!("run is not exercised")
+        }
+
+        fn invoke_tool_with_peer(
+            &self,
+            name: &str,
+            _params: Value,
+            peer: &dyn ClientPeer,
+        ) -> ToolResult {
+            let text = match peer.elicit(
+                &format!("allow {name}?"),
+                serde_json::json!({
+                    "type": "object",
+                    "properties": { "approve": { "type": "boolean" } },
+                }),
+            ) {
+                Ok(answer) => format!(
+                    "{:?} by {}",
+                    answer.action,
+                    peer.client_name().unwrap_or_default()
+                ),
+                Err(e) => format!("no elicitation: {}", e.message),
+            };
+            ToolResult {
+                content: vec![ContentBlock::Text { text }],
+                is_error: false,
+            }
+        }
+    }
+
+    fn frames(out: &[u8]) -> Vec<Value> {
+        String::from_utf8_lossy(out)
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn elicitation_round_trips_and_defers_interleaved_requests() {
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{"elicitation":{}},"clientInfo":{"name":"test-client"}}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"mvm.time_now","arguments":{}}}"#,
+            // Arrives while the server waits for the elicitation answer.
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#,
+            r#"{"jsonrpc":"2.0","id":"mvm-elicit-1","result":{"action":"accept","content":{"approve":true}}}"#,
+        ]
+        .join("\n");
+        let mut out = Vec::new();
+        run_with_dispatcher(input.as_bytes(), &mut out, &ElicitingDispatcher).unwrap();
+        let frames = frames(&out);
+        assert_eq!(frames.len(), 4, "got {frames:?}");
+        assert_eq!(frames[0]["id"], 1);
+        assert_eq!(frames[1]["method"], ELICITATION_METHOD);
+        assert_eq!(frames[1]["params"]["message"], "allow mvm.time_now?");
+        assert_eq!(frames[2]["id"], 2);
+        assert_eq!(
+            frames[2]["result"]["content"][0]["text"],
+            "Accept by test-client"
+        );
+        // The deferred request is answered after the call completes.
+        assert_eq!(frames[3]["id"], 3);
+        assert!(frames[3]["result"]["tools"].is_array());
+    }
+
+    #[test]
+    fn elicitation_needs_the_client_capability() {
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"mvm.time_now","arguments":{}}}"#,
+        ]
+        .join("\n");
+        let mut out = Vec::new();
+        run_with_dispatcher(input.as_bytes(), &mut out, &ElicitingDispatcher).unwrap();
+        let frames = frames(&out);
+        assert_eq!(frames.len(), 2, "got {frames:?}");
+        let text = frames[1]["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("elicitation capability"), "got: {text}");
+    }
+
+    #[test]
+    fn unsolicited_response_gets_no_reply() {
+        let dispatcher = MockDispatcher {
+            last_env: std::sync::Mutex::new(None),
+        };
+        let line = r#"{"jsonrpc":"2.0","id":"stale","result":{}}"#;
+        assert!(handle(line, &dispatcher).is_none());
     }
 
     #[test]
@@ -193,7 +751,9 @@ mod tests {
         let result = resp.result.unwrap();
         assert_eq!(result["protocolVersion"], PROTOCOL_VERSION);
         assert_eq!(result["serverInfo"]["name"], SERVER_NAME);
-        assert_eq!(result["capabilities"]["tools"]["listChanged"], false);
+        assert_eq!(result["capabilities"]["tools"]["listChanged"], true);
+        assert!(result["capabilities"]["resources"].is_object());
+        assert!(result["capabilities"]["prompts"].is_object());
     }
 
     #[test]
@@ -274,7 +834,7 @@ mod tests {
         let dispatcher = MockDispatcher {
             last_env: std::sync::Mutex::new(None),
         };
-        let req = r#"{"jsonrpc":"2.0","id":6,"method":"resources/list","params":{}}"#;
+        let req = r#"{"jsonrpc":"2.0","id":6,"method":"completion/complete","params":{}}"#;
         let resp = run_one(req, &dispatcher);
         let err = resp.error.unwrap();
         assert_eq!(err.code, -32601);
@@ -285,7 +845,7 @@ mod tests {
         let dispatcher = MockDispatcher {
             last_env: std::sync::Mutex::new(None),
         };
-        let resp = handle_one("not-json", &dispatcher).unwrap();
+        let resp = handle("not-json", &dispatcher).unwrap();
         let err = resp.error.unwrap();
         assert_eq!(err.code, -32700);
         assert_eq!(resp.id, Value::Null);
@@ -298,7 +858,7 @@ mod tests {
         };
         // No `id` field = notification.
         let req = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
-        assert!(handle_one(req, &dispatcher).is_none());
+        assert!(handle(req, &dispatcher).is_none());
     }
 
     #[test]
@@ -311,4 +871,178 @@ mod tests {
         let err = resp.error.unwrap();
         assert_eq!(err.code, -32600);
     }
+
+    /// Reports progress, then waits for the client to cancel.
+    struct CancellableDispatcher;
+    impl Dispatcher for CancellableDispatcher {
+        fn run(&self, _params: RunParams) -> ToolResult {
+            unreachable!("run_with_peer is overridden")
+        }
+
+        fn run_with_peer(&self, _params: RunParams, peer: &dyn ClientPeer) -> ToolResult {
+            peer.progress(1.0, Some(10.0), Some("started"));
+            let token = peer.cancel_token();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !token.is_cancelled() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            ToolResult {
+                content: vec![ContentBlock::Text {
+                    text: format!("cancelled={}", token.is_cancelled()),
+                }],
+                is_error: false,
+            }
+        }
+    }
+
+    #[test]
+    fn cancelled_call_reports_progress_and_gets_no_response() {
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"run","arguments":{"env":"shell","code":"sleep 60"},"_meta":{"progressToken":"p-2"}}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":2,"reason":"user"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#,
+        ]
+        .join("\n");
+        let mut out = Vec::new();
+        let started = Instant::now();
+        run_with_dispatcher(input.as_bytes(), &mut out, &CancellableDispatcher).unwrap();
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "cancel was lost"
+        );
+        let frames = frames(&out);
+        assert_eq!(frames.len(), 3, "got {frames:?}");
+        assert_eq!(frames[0]["id"], 1);
+        assert_eq!(frames[1]["method"], PROGRESS_METHOD);
+        assert_eq!(frames[1]["params"]["progressToken"], "p-2");
+        assert_eq!(frames[1]["params"]["total"], 10.0);
+        assert_eq!(frames[1]["params"]["message"], "started");
+        assert_eq!(frames[2]["id"], 3);
+    }
+
+    #[test]
+    fn progress_without_a_token_is_dropped() {
+        let mut out = Vec::new();
+        let cancellations = Cancellations::default();
+        let (_, inbound) = mpsc::channel();
+        let peer = StdioPeer::new(inbound, &mut out, &cancellations);
+        // Cancel first so the call returns at once.
+        cancellations.cancel(Some(&serde_json::json!({ "requestId": 9 })));
+        let line = r#"{"jsonrpc":"2.0","id":9,"method":"tools/call","params":{"name":"run","arguments":{"env":"shell","code":"x"}}}"#;
+        assert!(handle_one(line, &CancellableDispatcher, &peer).is_none());
+        drop(peer);
+        assert!(out.is_empty(), "{}", String::from_utf8_lossy(&out));
+    }
+
+    /// Serves one resource and bumps its tool revision on every call.
+    struct ResourceDispatcher {
+        revision: std::sync::atomic::AtomicU64,
+    }
+    impl Dispatcher for ResourceDispatcher {
+        fn run(&self, _params: RunParams) -> ToolResult {
+            self.revision
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            ToolResult {
+                content: vec![],
+                is_error: false,
+            }
+        }
+
+        fn tools_revision(&self) -> u64 {
+            self.revision.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        fn list_resources(&self, _peer: &dyn ClientPeer) -> Vec<Resource> {
+            vec![Resource {
+                uri: "mvm://test/a".to_string(),
+                name: "a".to_string(),
+                description: None,
+                mime_type: Some("text/plain".to_string()),
+            }]
+        }
+
+        fn read_resource(
+            &self,
+            uri: &str,
+            _peer: &dyn ClientPeer,
+        ) -> Result<Vec<ResourceContents>, JsonRpcError> {
+            if uri != "mvm://test/a" {
+                return Err(JsonRpcError::resource_not_found(uri));
+            }
+            Ok(vec![ResourceContents::text(uri, "text/plain", "hello")])
+        }
+    }
+
+    #[test]
+    fn resources_and_prompts_route_to_their_handlers() {
+        let dispatcher = ResourceDispatcher {
+            revision: Default::default(),
+        };
+        let list = run_one(
+            r#"{"jsonrpc":"2.0","id":1,"method":"resources/list"}"#,
+            &dispatcher,
+        );
+        assert_eq!(list.result.unwrap()["resources"][0]["uri"], "mvm://test/a");
+
+        let read = run_one(
+            r#"{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{"uri":"mvm://test/a"}}"#,
+            &dispatcher,
+        );
+        let contents = &read.result.unwrap()["contents"][0];
+        assert_eq!(contents["text"], "hello");
+        assert_eq!(contents["mimeType"], "text/plain");
+
+        let missing = run_one(
+            r#"{"jsonrpc":"2.0","id":3,"method":"resources/read","params":{"uri":"mvm://test/b"}}"#,
+            &dispatcher,
+        );
+        assert_eq!(missing.error.unwrap().code, -32002);
+
+        let templates = run_one(
+            r#"{"jsonrpc":"2.0","id":4,"method":"resources/templates/list"}"#,
+            &dispatcher,
+        );
+        assert!(templates.result.unwrap()["resourceTemplates"].is_array());
+
+        let prompts = run_one(
+            r#"{"jsonrpc":"2.0","id":5,"method":"prompts/list"}"#,
+            &dispatcher,
+        );
+        assert_eq!(
+            prompts.result.unwrap()["prompts"][0]["name"],
+            "sandbox-task"
+        );
+
+        let prompt = run_one(
+            r#"{"jsonrpc":"2.0","id":6,"method":"prompts/get","params":{"name":"inspect-session","arguments":{"session":"s1"}}}"#,
+            &dispatcher,
+        );
+        let text = prompt.result.unwrap()["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(text.contains("mvm://sessions/s1/audit"), "{text}");
+    }
+
+    #[test]
+    fn tool_revision_change_sends_list_changed() {
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"run","arguments":{"env":"shell","code":"x"}}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#,
+        ]
+        .join("\n");
+        let dispatcher = ResourceDispatcher {
+            revision: Default::default(),
+        };
+        let mut out = Vec::new();
+        run_with_dispatcher(input.as_bytes(), &mut out, &dispatcher).unwrap();
+        let frames = frames(&out);
+        assert_eq!(frames.len(), 4, "got {frames:?}");
+        assert_eq!(frames[1]["id"], 2);
+        assert_eq!(frames[2]["method"], TOOLS_LIST_CHANGED_METHOD);
+        assert!(frames[2].get("id").is_none());
+        assert_eq!(frames[3]["id"], 3);
+    }
 }
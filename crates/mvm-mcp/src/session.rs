@@ -1,6 +1,6 @@
 //! Session lifecycle for the MCP `run` tool.
 //!
-//! Plan 32 / Proposal A.2. v1 ships the bookkeeping layer:
+//! Plan 32 / Proposal A.2:
 //!
 //! - [`SessionMap`] tracks `(session_id → SessionState)` with idle and
 //!   max-lifetime expiry — *protocol-only safe*, no I/O.
@@ -10,14 +10,12 @@
 //!   side-effecting world (kill the VM, audit-log the close); the
 //!   stdio server and mvmd's hosted variant each plug in their own.
 //!
-//! Warm-VM materialisation (booting a long-running VM that persists
-//! across `tools/call` invocations) is deferred to A.2 v2 because the
-//! existing `crate::exec` boot/dispatch/teardown path is tightly
-//! coupled and a clean split is risky without live-KVM integration
-//! tests. The wire schema (`session`, `close`) is already in place —
-//! v1 honours the schema for bookkeeping/correlation; v2 will materialise
-//! the VM behind the bookkeeping. ADR-003 §"Decisions" 6 documents the
-//! split.
+//! The map stays pure; the VM lives behind it. The local dispatcher
+//! (`mvm-cli::commands::ops::mcp`) boots a warm VM on a session's
+//! first `run` call, records its name with
+//! [`SessionMap::set_vm_name`], reuses it for later calls, and tears
+//! it down from its [`Reaper`] whatever the [`ReapReason`]. ADR-003
+//! §"Decisions" 6 documents the split.
 
 use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
@@ -65,9 +63,9 @@ impl SessionConfig {
 
 /// One row in the session map.
 ///
-/// `vm_name` is `Option` so v1 (bookkeeping-only) can record a
-/// session without owning a VM. v2 sets it when a warm VM is
-/// materialised. Either way the lifetime tracking is identical.
+/// `vm_name` is `None` until the session's warm VM has booted (and
+/// stays `None` for sessions that never own one, like the HTTP
+/// transport's). Either way the lifetime tracking is identical.
 #[derive(Debug, Clone)]
 pub struct SessionState {
     pub env: String,
@@ -99,6 +97,13 @@ pub enum ReapReason {
     Closed,
     /// Server shutting down; reap everything still in the map.
     Shutdown,
+    /// The session's VM stopped answering. Closing the session makes
+    /// the next call with the same id start from a fresh VM instead
+    /// of failing forever.
+    VmLost,
+    /// The client cancelled a call its VM could not stop short of
+    /// being torn down (an agent without process RPC).
+    Cancelled,
 }
 
 /// The bridge between the pure [`SessionMap`] and the side-effecting
@@ -143,9 +148,9 @@ impl SessionMap {
     /// `last_used` to `now` either way. Returns whether the session
     /// was already present.
     ///
-    /// `vm_name_hint` is what to record on first insert. v1 callers
-    /// pass `None` because warm VMs aren't materialised yet; v2
-    /// passes the booted VM name so the reaper has something to kill.
+    /// `vm_name_hint` is what to record on first insert. The local
+    /// dispatcher passes `None` and records the name with
+    /// [`SessionMap::set_vm_name`] once the warm VM has booted.
     pub fn touch_or_insert(
         &mut self,
         session_id: &str,
@@ -166,8 +171,8 @@ impl SessionMap {
         if let Some(state) = self.sessions.get_mut(session_id) {
             state.last_used = now;
             // env mismatch on a reused session is a client bug; the
-            // map records the original env. Caller may want to surface
-            // a warning, but the map itself is permissive.
+            // map records the original env and stays permissive. The
+            // local dispatcher refuses the call.
             return SessionLookup::Existing;
         }
         self.sessions.insert(
@@ -188,6 +193,11 @@ impl SessionMap {
         self.sessions.get(session_id)
     }
 
+    /// Live sessions in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SessionState)> {
+        self.sessions.iter().map(|(id, state)| (id.as_str(), state))
+    }
+
     /// Record the warm-VM name on an existing session. Used by A.2 v2
     /// after `boot_session_vm` succeeds, so the reaper has something
     /// to tear down. No-op if the session is unknown (the VM was
@@ -214,6 +224,30 @@ impl SessionMap {
         }
     }
 
+    /// Remove every session whose id satisfies `matches`, calling the
+    /// reaper with `reason` for each. Returns how many were removed.
+    /// The HTTP transport uses it to close the `run` sessions scoped
+    /// to a transport session that ended.
+    pub fn remove_matching(
+        &mut self,
+        matches: impl Fn(&str) -> bool,
+        reason: ReapReason,
+        reaper: &dyn Reaper,
+    ) -> usize {
+        let ids: Vec<String> = self
+            .sessions
+            .keys()
+            .filter(|id| matches(id))
+            .cloned()
+            .collect();
+        for id in &ids {
+            if let Some(state) = self.sessions.remove(id) {
+                reaper.on_reap(id, &state, reason);
+            }
+        }
+        ids.len()
+    }
+
     /// Sweep expired sessions, calling the reaper for each. Returns
     /// the number of sessions reaped.
     pub fn reap_expired(&mut self, reaper: &dyn Reaper) -> usize {
@@ -374,6 +408,21 @@ mod tests {
         assert!(reaper.calls.lock().unwrap().is_empty());
     }
 
+    #[test]
+    fn remove_matching_reaps_only_matching_ids() {
+        let mut map = SessionMap::new(cfg(300, 3600));
+        let reaper = RecordingReaper::default();
+        map.touch_or_insert("t1:a", "shell", None);
+        map.touch_or_insert("t1:b", "shell", None);
+        map.touch_or_insert("t2:a", "shell", None);
+        let n = map.remove_matching(|id| id.starts_with("t1:"), ReapReason::Idle, &reaper);
+        assert_eq!(n, 2);
+        assert_eq!(map.len(), 1);
+        assert!(map.get("t2:a").is_some());
+        let calls = reaper.calls.lock().unwrap();
+        assert!(calls.iter().all(|(_, r)| *r == ReapReason::Idle));
+    }
+
     #[test]
     fn drain_reports_reason_shutdown() {
         let mut map = SessionMap::new(cfg(300, 3600));
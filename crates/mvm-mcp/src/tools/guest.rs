@@ -0,0 +1,456 @@
+//! Guest filesystem and process tools (`mvm.fs.*`, `mvm.proc.*`).
+//!
+//! Each tool is one guest-agent RPC verb against the warm VM of a
+//! `run` session: the client opens the session with `run` and passes
+//! the same `session` id here. The FS verbs are production-safe; the
+//! process verbs need a dev (`dev-shell`) guest agent and report
+//! `UnsupportedInProduction` otherwise.
+//!
+//! Unlike `run`, none of these tools is reachable by default: the
+//! dispatcher lists and serves only the names on the workload's
+//! `ToolPolicy.allowed`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::ToolSchema;
+
+pub const FS_READ: &str = "mvm.fs.read";
+pub const FS_WRITE: &str = "mvm.fs.write";
+pub const FS_LIST: &str = "mvm.fs.list";
+pub const FS_STAT: &str = "mvm.fs.stat";
+pub const FS_MKDIR: &str = "mvm.fs.mkdir";
+pub const FS_REMOVE: &str = "mvm.fs.remove";
+pub const FS_MOVE: &str = "mvm.fs.move";
+pub const PROC_START: &str = "mvm.proc.start";
+pub const PROC_LIST: &str = "mvm.proc.list";
+pub const PROC_SIGNAL: &str = "mvm.proc.signal";
+pub const PROC_INPUT: &str = "mvm.proc.input";
+pub const PROC_WAIT: &str = "mvm.proc.wait";
+pub const PROC_KILL: &str = "mvm.proc.kill";
+
+/// Every guest tool name, in `tools/list` order.
+pub const GUEST_TOOL_NAMES: &[&str] = &[
+    FS_READ,
+    FS_WRITE,
+    FS_LIST,
+    FS_STAT,
+    FS_MKDIR,
+    FS_REMOVE,
+    FS_MOVE,
+    PROC_START,
+    PROC_LIST,
+    PROC_SIGNAL,
+    PROC_INPUT,
+    PROC_WAIT,
+    PROC_KILL,
+];
+
+/// True for the `mvm.fs.*` / `mvm.proc.*` names served by this module.
+pub fn is_guest_tool(name: &str) -> bool {
+    GUEST_TOOL_NAMES.contains(&name)
+}
+
+/// `mvm.fs.read` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsReadParams {
+    pub session: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u64>,
+}
+
+/// `mvm.fs.write` params. Exactly one of `content` and
+/// `content_base64` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsWriteParams {
+    pub session: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_base64: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub create_parents: Option<bool>,
+}
+
+/// `mvm.fs.list` / `mvm.fs.stat` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsPathParams {
+    pub session: String,
+    pub path: String,
+}
+
+/// `mvm.fs.mkdir` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsMkdirParams {
+    pub session: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parents: Option<bool>,
+}
+
+/// `mvm.fs.remove` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsRemoveParams {
+    pub session: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recursive: Option<bool>,
+}
+
+/// `mvm.fs.move` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsMoveParams {
+    pub session: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// `mvm.proc.start` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcStartParams {
+    pub session: String,
+    pub argv: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// `mvm.proc.list` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcListParams {
+    pub session: String,
+}
+
+/// `mvm.proc.signal` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcSignalParams {
+    pub session: String,
+    pub pid_token: String,
+    pub signum: i32,
+}
+
+/// `mvm.proc.input` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcInputParams {
+    pub session: String,
+    pub pid_token: String,
+    pub input: String,
+}
+
+/// `mvm.proc.wait` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcWaitParams {
+    pub session: String,
+    pub pid_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// `mvm.proc.kill` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcKillParams {
+    pub session: String,
+    pub pid_token: String,
+}
+
+/// A decoded guest tool call.
+#[derive(Debug, Clone)]
+pub enum GuestToolCall {
+    FsRead(FsReadParams),
+    FsWrite(FsWriteParams),
+    FsList(FsPathParams),
+    FsStat(FsPathParams),
+    FsMkdir(FsMkdirParams),
+    FsRemove(FsRemoveParams),
+    FsMove(FsMoveParams),
+    ProcStart(ProcStartParams),
+    ProcList(ProcListParams),
+    ProcSignal(ProcSignalParams),
+    ProcInput(ProcInputParams),
+    ProcWait(ProcWaitParams),
+    ProcKill(ProcKillParams),
+}
+
+impl GuestToolCall {
+    /// Decode `arguments` for tool `name`. `Err` carries a message for
+    /// an invalid-params tool error.
+    pub fn parse(name: &str, arguments: Value) -> Result<Self, String> {
+        fn decode<T: serde::de::DeserializeOwned>(arguments: Value) -> Result<T, String> {
+            serde_json::from_value(arguments).map_err(|e| e.to_string())
+        }
+        match name {
+            FS_READ => decode(arguments).map(Self::FsRead),
+            FS_WRITE => {
+                let params: FsWriteParams = decode(arguments)?;
+                if params.content.is_some() == params.content_base64.is_some() {
+                    return Err("set exactly one of `content` and `content_base64`".to_string());
+                }
+                Ok(Self::FsWrite(params))
+            }
+            FS_LIST => decode(arguments).map(Self::FsList),
+            FS_STAT => decode(arguments).map(Self::FsStat),
+            FS_MKDIR => decode(arguments).map(Self::FsMkdir),
+            FS_REMOVE => decode(arguments).map(Self::FsRemove),
+            FS_MOVE => decode(arguments).map(Self::FsMove),
+            PROC_START => decode(arguments).map(Self::ProcStart),
+            PROC_LIST => decode(arguments).map(Self::ProcList),
+            PROC_SIGNAL => decode(arguments).map(Self::ProcSignal),
+            PROC_INPUT => decode(arguments).map(Self::ProcInput),
+            PROC_WAIT => decode(arguments).map(Self::ProcWait),
+            PROC_KILL => decode(arguments).map(Self::ProcKill),
+            other => Err(format!("{other:?} is not a guest tool")),
+        }
+    }
+
+    /// The `run` session the call targets.
+    pub fn session(&self) -> &str {
+        match self {
+            Self::FsRead(p) => &p.session,
+            Self::FsWrite(p) => &p.session,
+            Self::FsList(p) | Self::FsStat(p) => &p.session,
+            Self::FsMkdir(p) => &p.session,
+            Self::FsRemove(p) => &p.session,
+            Self::FsMove(p) => &p.session,
+            Self::ProcStart(p) => &p.session,
+            Self::ProcList(p) => &p.session,
+            Self::ProcSignal(p) => &p.session,
+            Self::ProcInput(p) => &p.session,
+            Self::ProcWait(p) => &p.session,
+            Self::ProcKill(p) => &p.session,
+        }
+    }
+}
+
+/// Input schema with the shared `session` property plus `properties`.
+fn schema(required: &[&str], properties: Value) -> Value {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "session".to_string(),
+        json!({
+            "type": "string",
+            "description": "Id of a warm `run` session; the call runs in its VM."
+        }),
+    );
+    if let Value::Object(extra) = properties {
+        props.extend(extra);
+    }
+    let mut required_all = vec!["session"];
+    required_all.extend_from_slice(required);
+    json!({
+        "type": "object",
+        "required": required_all,
+        "properties": props,
+        "additionalProperties": false
+    })
+}
+
+fn path_property() -> Value {
+    json!({ "type": "string", "description": "Absolute guest path." })
+}
+
+fn pid_token_property() -> Value {
+    json!({ "type": "string", "description": "Handle returned by mvm.proc.start." })
+}
+
+fn tool(name: &str, description: &str, input_schema: Value) -> ToolSchema {
+    ToolSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        input_schema,
+    }
+}
+
+/// Schemas for every guest tool. Dispatchers list the subset their
+/// tool policy allows.
+pub fn guest_tools() -> Vec<ToolSchema> {
+    vec![
+        tool(
+            FS_READ,
+            "Read a file in a session's VM. UTF-8 comes back as `content`, anything else as URL-safe-no-pad `content_base64`.",
+            schema(
+                &["path"],
+                json!({
+                    "path": path_property(),
+                    "offset": { "type": "integer", "minimum": 0, "description": "Byte offset. Default 0." },
+                    "length": { "type": "integer", "minimum": 1, "description": "Bytes to read. Default and maximum 1 MiB." }
+                }),
+            ),
+        ),
+        tool(
+            FS_WRITE,
+            "Create or overwrite a file in a session's VM.",
+            schema(
+                &["path"],
+                json!({
+                    "path": path_property(),
+                    "content": { "type": "string", "description": "UTF-8 text to write." },
+                    "content_base64": { "type": "string", "description": "URL-safe-no-pad base64 bytes to write, instead of `content`." },
+                    "mode": { "type": "integer", "description": "Mode of a new file. Default 0o644." },
+                    "create_parents": { "type": "boolean", "description": "Create missing parent directories." }
+                }),
+            ),
+        ),
+        tool(
+            FS_LIST,
+            "List a directory in a session's VM.",
+            schema(&["path"], json!({ "path": path_property() })),
+        ),
+        tool(
+            FS_STAT,
+            "Stat a path in a session's VM.",
+            schema(&["path"], json!({ "path": path_property() })),
+        ),
+        tool(
+            FS_MKDIR,
+            "Create a directory in a session's VM.",
+            schema(
+                &["path"],
+                json!({
+                    "path": path_property(),
+                    "parents": { "type": "boolean", "description": "Behave like `mkdir -p`." }
+                }),
+            ),
+        ),
+        tool(
+            FS_REMOVE,
+            "Remove a file or directory in a session's VM.",
+            schema(
+                &["path"],
+                json!({
+                    "path": path_property(),
+                    "recursive": { "type": "boolean", "description": "Remove a non-empty directory." }
+                }),
+            ),
+        ),
+        tool(
+            FS_MOVE,
+            "Rename a path within one filesystem of a session's VM.",
+            schema(
+                &["from", "to"],
+                json!({ "from": path_property(), "to": path_property() }),
+            ),
+        ),
+        tool(
+            PROC_START,
+            "Start a background process in a session's VM (dev guest agents only). Returns a `pid_token`.",
+            schema(
+                &["argv"],
+                json!({
+                    "argv": { "type": "array", "items": { "type": "string" }, "minItems": 1, "description": "argv[0] must be an absolute path." },
+                    "env": { "type": "object", "additionalProperties": { "type": "string" }, "description": "Replaces the environment. PATH defaults to the system profile." },
+                    "cwd": path_property(),
+                    "stdin": { "type": "string", "description": "Initial stdin." },
+                    "timeout_secs": { "type": "integer", "minimum": 1, "description": "Kill the process after this long." }
+                }),
+            ),
+        ),
+        tool(
+            PROC_LIST,
+            "List the processes started in a session's VM.",
+            schema(&[], json!({})),
+        ),
+        tool(
+            PROC_SIGNAL,
+            "Send a signal to a started process.",
+            schema(
+                &["pid_token", "signum"],
+                json!({
+                    "pid_token": pid_token_property(),
+                    "signum": { "type": "integer", "description": "Signal number, e.g. 15 or 2." }
+                }),
+            ),
+        ),
+        tool(
+            PROC_INPUT,
+            "Append text to a started process's stdin.",
+            schema(
+                &["pid_token", "input"],
+                json!({ "pid_token": pid_token_property(), "input": { "type": "string" } }),
+            ),
+        ),
+        tool(
+            PROC_WAIT,
+            "Wait for a started process to exit; returns its output and exit_code. A process still running at the timeout is killed.",
+            schema(
+                &["pid_token"],
+                json!({
+                    "pid_token": pid_token_property(),
+                    "timeout_secs": { "type": "integer", "minimum": 1, "maximum": 600, "description": "Default 60." }
+                }),
+            ),
+        ),
+        tool(
+            PROC_KILL,
+            "SIGKILL a started process.",
+            schema(&["pid_token"], json!({ "pid_token": pid_token_property() })),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_name_has_a_schema_that_requires_session() {
+        let tools = guest_tools();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, GUEST_TOOL_NAMES);
+        for t in &tools {
+            let required = t.input_schema["required"].as_array().unwrap();
+            assert_eq!(required[0], "session", "{}", t.name);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_unknown_fields_and_ambiguous_writes() {
+        let call = GuestToolCall::parse(FS_STAT, json!({"session": "s1", "path": "/tmp"})).unwrap();
+        assert_eq!(call.session(), "s1");
+        assert!(
+            GuestToolCall::parse(FS_STAT, json!({"session": "s1", "path": "/", "x": 1})).is_err()
+        );
+        let both = json!({"session": "s", "path": "/f", "content": "a", "content_base64": "YQ"});
+        assert!(GuestToolCall::parse(FS_WRITE, both).is_err());
+        let neither = json!({"session": "s", "path": "/f"});
+        assert!(GuestToolCall::parse(FS_WRITE, neither).is_err());
+        assert!(GuestToolCall::parse("run", json!({})).is_err());
+    }
+
+    #[test]
+    fn guest_tools_list_token_budget_under_2000() {
+        // Same heuristic as `tools_list_token_budget_under_2000`; a
+        // policy that allows every guest tool roughly doubles the
+        // `tools/list` payload.
+        let serialized = serde_json::to_string(&guest_tools()).unwrap();
+        assert!(
+            serialized.len() / 4 < 2000,
+            "guest tools too large: {} bytes",
+            serialized.len()
+        );
+    }
+}
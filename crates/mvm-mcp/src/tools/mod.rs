@@ -7,7 +7,10 @@
 //! Single-tool design ("borrow nix-sandbox-mcp's insight"): we expose
 //! one parameterized tool (`run`) so the LLM context-window cost
 //! stays flat at ~420 tokens regardless of how many templates the
-//! user has built.
+//! user has built. The guest FS / process tools in [`guest`] are the
+//! exception: they are listed only when the tool policy allows them.
+
+pub mod guest;
 
 use serde::{Deserialize, Serialize};
 
@@ -37,17 +40,15 @@ pub struct RunParams {
     /// shell-env case is intentional and noted in ADR-003: there is
     /// no in-microVM interpreter sandbox beyond the microVM itself.
     pub code: String,
-    /// Reserved for Proposal A.2 — session-pinned warm VMs. Ignored
-    /// in v1; sending it does not error so clients can adopt the
-    /// session API ahead of the server.
+    /// Proposal A.2 — pins the call to a warm VM. The first call
+    /// with a new id boots a VM for `env`; later calls with the same
+    /// id run in that VM, so files and installed packages persist
+    /// until the session is closed or reaped. A session is bound to
+    /// the `env` it was opened with.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub session: Option<String>,
-    /// Reserved for Proposal A.2 — when paired with `session`, signals
-    /// "this is the last call against this session, tear the VM down
-    /// (snapshot first if the env was registered with
-    /// `persist_on_close=true`)". Ignored in v1; the schema accepts
-    /// it so clients can adopt the session lifecycle ahead of the
-    /// server.
+    /// Proposal A.2 — when paired with `session`, this is the last
+    /// call against the session: the VM is torn down once it returns.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub close: Option<bool>,
     /// Per-call timeout in seconds. Bounded `[1, 600]`; out-of-range
@@ -76,11 +77,11 @@ pub fn run_input_schema() -> serde_json::Value {
             },
             "session": {
                 "type": "string",
-                "description": "Optional session ID for warm-VM persistence (reserved; v1 ignores)."
+                "description": "Optional session ID. Calls sharing an ID run in the same warm VM, so files and installed packages persist between them. The session is bound to the env of its first call and ends after a period of inactivity."
             },
             "close": {
                 "type": "boolean",
-                "description": "When paired with `session`, signals this is the last call against the session — server may snapshot + tear down (reserved; v1 ignores)."
+                "description": "When paired with `session`, this is the last call against the session; its VM is torn down afterwards."
             },
             "timeout_secs": {
                 "type": "integer",
@@ -221,7 +222,7 @@ pub fn all_tools() -> Vec<ToolSchema> {
         ToolSchema {
             name: "run".to_string(),
             description:
-                "Run code inside a fresh mvm microVM. Single tool; the `env` parameter selects which pre-built environment to boot — either a built-in preset (`shell`, `bash`, `python`, `node`) or a path to a project directory whose `mvm.toml` has been built via `mvmctl build`. Output is captured (stdout, stderr, exit_code). Each call boots and tears down a transient VM (session reuse is reserved). Use `mvmctl manifest ls` on the host to discover available manifest-keyed environments."
+                "Run code inside a fresh mvm microVM. Single tool; the `env` parameter selects which pre-built environment to boot — either a built-in preset (`shell`, `bash`, `python`, `node`) or a path to a project directory whose `mvm.toml` has been built via `mvmctl build`. Output is captured (stdout, stderr, exit_code). Without `session`, each call boots and tears down a transient VM; with `session`, calls reuse one warm VM. Cancelling a call kills its guest process; on guest agents without process RPC (production images) it tears the VM down instead, which closes the `session`. Use `mvmctl manifest ls` on the host to discover available manifest-keyed environments."
                     .to_string(),
             input_schema: run_input_schema(),
         },
@@ -263,6 +264,25 @@ pub fn all_tools() -> Vec<ToolSchema> {
     ]
 }
 
+/// [`all_tools`] with the `run` tool's `env` description naming
+/// `envs`, the templates registered on the host right now. Saves the
+/// client a trip to `mvmctl manifest ls`; transports announce a
+/// changed set with `notifications/tools/list_changed`.
+pub fn all_tools_for_envs(envs: &[String]) -> Vec<ToolSchema> {
+    let mut tools = all_tools();
+    if envs.is_empty() {
+        return tools;
+    }
+    if let Some(run) = tools.iter_mut().find(|t| t.name == "run")
+        && let Some(description) = run.input_schema.pointer_mut("/properties/env/description")
+        && let Some(text) = description.as_str()
+    {
+        *description =
+            serde_json::Value::String(format!("{text} Registered envs: {}.", envs.join(", ")));
+    }
+    tools
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,8 +304,7 @@ mod tests {
 
     #[test]
     fn run_params_accepts_session_and_close() {
-        // A.2 schema readiness: clients adopting session+close ahead
-        // of server-side support must not get a parse error.
+        // A.2: session + close are part of the wire schema.
         let json = r#"{"env":"shell","code":"x","session":"s1","close":true}"#;
         let parsed: RunParams = serde_json::from_str(json).unwrap();
         assert_eq!(parsed.session.as_deref(), Some("s1"));
@@ -311,6 +330,23 @@ mod tests {
         assert_eq!(tools.len(), 6);
     }
 
+    #[test]
+    fn all_tools_for_envs_names_the_registered_envs() {
+        let tools = all_tools_for_envs(&["python".to_string(), "scratch".to_string()]);
+        let run = tools.iter().find(|t| t.name == "run").unwrap();
+        let description = run.input_schema["properties"]["env"]["description"]
+            .as_str()
+            .unwrap();
+        assert!(
+            description.ends_with("Registered envs: python, scratch."),
+            "{description}"
+        );
+        assert_eq!(
+            serde_json::to_value(all_tools_for_envs(&[])).unwrap(),
+            serde_json::to_value(all_tools()).unwrap()
+        );
+    }
+
     #[test]
     fn tools_list_token_budget_under_2000() {
         // Byte-count heuristic where 1 token ≈ 4 bytes (well-known
@@ -0,0 +1,217 @@
+//! Registry token authentication for identity tokens.
+//!
+//! Docker credential helpers return an *identity token* — a
+//! long-lived OAuth2 refresh token, signalled by the username
+//! `<token>` — instead of a password for registries that issue them.
+//! It is never presented to the registry itself. The distribution
+//! token-auth flow exchanges it at the realm the registry names in
+//! its `WWW-Authenticate: Bearer` challenge for a short-lived,
+//! repository-scoped access token, and only that access token is
+//! used for the pull.
+//!
+//! Basic credentials need no help here: `oci-client` runs the same
+//! challenge with HTTP basic auth itself.
+
+use crate::OciError;
+use crate::manifest::{ClientProtocol, RegistryAuthConfig, upstream_reference};
+use crate::reference::ImageReference;
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// `client_id` sent with the refresh-token grant. Registries log it;
+/// none use it for authorization.
+const TOKEN_CLIENT_ID: &str = "mvm";
+
+/// Bound on each of the two token-flow requests.
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Token endpoint response. Distribution servers send `token`,
+/// OAuth2 servers `access_token`; either is accepted.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    token: Option<String>,
+}
+
+/// The parameters of a `WWW-Authenticate: Bearer` challenge that the
+/// token flow needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BearerChallenge {
+    realm: String,
+    service: Option<String>,
+}
+
+/// Exchange `identity_token` for a bearer token scoped to pulls of
+/// `reference`'s repository.
+///
+/// Pings `/v2/` for the challenge, then posts a `refresh_token`
+/// grant to its realm. Returns [`RegistryAuthConfig::Anonymous`]
+/// when the registry issues no challenge (it does not need auth).
+/// The realm must be HTTPS unless `protocol` already allows plain
+/// HTTP for this registry, so the identity token never crosses a
+/// weaker transport than the registry itself.
+///
+/// Failures are [`OciError::Auth`] and never carry the token or the
+/// token endpoint's response body.
+pub async fn exchange_identity_token(
+    reference: &ImageReference,
+    identity_token: &SecretString,
+    protocol: &ClientProtocol,
+) -> Result<RegistryAuthConfig, OciError> {
+    let upstream = upstream_reference(reference)?;
+    let registry = upstream.resolve_registry();
+    let scheme = scheme_for(protocol, registry);
+    let http = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(TOKEN_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| OciError::Auth(format!("build token client: {e}")))?;
+
+    let ping = http
+        .get(format!("{scheme}://{registry}/v2/"))
+        .send()
+        .await
+        .map_err(|e| OciError::Registry(format!("ping {registry}: {e}")))?;
+    let Some(header) = ping.headers().get(reqwest::header::WWW_AUTHENTICATE) else {
+        return Ok(RegistryAuthConfig::Anonymous);
+    };
+    let challenge = header
+        .to_str()
+        .ok()
+        .and_then(parse_bearer_challenge)
+        .ok_or_else(|| {
+            OciError::Auth(format!(
+                "{registry} did not issue a bearer challenge; identity tokens need token auth"
+            ))
+        })?;
+
+    let realm = url::Url::parse(&challenge.realm)
+        .map_err(|e| OciError::Auth(format!("bearer realm {:?}: {e}", challenge.realm)))?;
+    if realm.scheme() != "https" && !(realm.scheme() == "http" && scheme == "http") {
+        return Err(OciError::Auth(format!(
+            "refusing to send identity token to non-HTTPS realm {realm}"
+        )));
+    }
+
+    let scope = format!("repository:{}:pull", upstream.repository());
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("client_id", TOKEN_CLIENT_ID),
+        ("scope", scope.as_str()),
+        ("refresh_token", identity_token.expose_secret()),
+    ];
+    if let Some(service) = challenge.service.as_deref() {
+        form.push(("service", service));
+    }
+    let response = http
+        .post(realm.clone())
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| OciError::Auth(format!("token endpoint {realm}: {e}")))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(OciError::Auth(format!(
+            "token endpoint {realm} refused the identity token: HTTP {status}"
+        )));
+    }
+    let body: TokenResponse = response.json().await.map_err(|_| {
+        OciError::Auth(format!(
+            "token endpoint {realm} returned a malformed response"
+        ))
+    })?;
+    let access = body
+        .access_token
+        .or(body.token)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| OciError::Auth(format!("token endpoint {realm} returned no token")))?;
+    Ok(RegistryAuthConfig::bearer(access))
+}
+
+/// `oci-client` keeps its own copy of this private.
+fn scheme_for<'a>(protocol: &ClientProtocol, registry: &str) -> &'a str {
+    match protocol {
+        ClientProtocol::Http => "http",
+        ClientProtocol::Https => "https",
+        ClientProtocol::HttpsExcept(exceptions) if exceptions.iter().any(|e| e == registry) => {
+            "http"
+        }
+        ClientProtocol::HttpsExcept(_) => "https",
+    }
+}
+
+/// Parse `Bearer realm="…",service="…",scope="…"`. Returns `None` for
+/// any other scheme or a challenge without a realm. Unknown
+/// parameters are ignored.
+fn parse_bearer_challenge(header: &str) -> Option<BearerChallenge> {
+    let (scheme, params) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    let mut realm = None;
+    let mut service = None;
+    let mut rest = params.trim();
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=')?;
+        let key = key.trim().to_ascii_lowercase();
+        let after_key = after_key.trim_start();
+        let (value, after_value) = if let Some(quoted) = after_key.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            (&quoted[..end], &quoted[end + 1..])
+        } else {
+            let end = after_key.find(',').unwrap_or(after_key.len());
+            (after_key[..end].trim(), &after_key[end..])
+        };
+        match key.as_str() {
+            "realm" => realm = Some(value.to_string()),
+            "service" => service = Some(value.to_string()),
+            _ => {}
+        }
+        rest = after_value
+            .trim_start()
+            .trim_start_matches(',')
+            .trim_start();
+    }
+    Some(BearerChallenge {
+        realm: realm.filter(|r| !r.is_empty())?,
+        service,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_docker_hub_style_challenge() {
+        let challenge = parse_bearer_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/alpine:pull""#,
+        )
+        .expect("bearer challenge");
+        assert_eq!(challenge.realm, "https://auth.docker.io/token");
+        assert_eq!(challenge.service.as_deref(), Some("registry.docker.io"));
+    }
+
+    #[test]
+    fn parses_unquoted_and_spaced_parameters() {
+        let challenge =
+            parse_bearer_challenge("bearer realm=https://r.example/token, service=r.example")
+                .expect("bearer challenge");
+        assert_eq!(challenge.realm, "https://r.example/token");
+        assert_eq!(challenge.service.as_deref(), Some("r.example"));
+    }
+
+    #[test]
+    fn rejects_basic_and_realmless_challenges() {
+        assert_eq!(parse_bearer_challenge(r#"Basic realm="registry""#), None);
+        assert_eq!(parse_bearer_challenge(r#"Bearer service="registry""#), None);
+    }
+
+    #[test]
+    fn scheme_follows_client_protocol_exceptions() {
+        let protocol = ClientProtocol::HttpsExcept(vec!["127.0.0.1:5000".to_string()]);
+        assert_eq!(scheme_for(&protocol, "127.0.0.1:5000"), "http");
+        assert_eq!(scheme_for(&protocol, "ghcr.io"), "https");
+    }
+}
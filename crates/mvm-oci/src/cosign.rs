@@ -0,0 +1,203 @@
+//! Cosign signature discovery.
+//!
+//! Collects the signature material cosign attaches to a
+//! digest-pinned image, in both layouts cosign publishes:
+//!
+//! - **Signature tag.** An image manifest under the
+//!   `sha256-<hex>.sig` tag whose layers are simple-signing payloads
+//!   ([`SIMPLE_SIGNING_MEDIA_TYPE`]), each carrying its base64
+//!   signature in the [`COSIGN_SIGNATURE_ANNOTATION`] annotation.
+//! - **Sigstore bundles as referrers.** OCI 1.1 artifacts of type
+//!   [`SIGSTORE_BUNDLE_ARTIFACT_TYPE`] whose single blob is a
+//!   Sigstore bundle, found through
+//!   [`OciManifestFetcher::fetch_referrers`].
+//!
+//! This module only *collects*. Every manifest and blob is
+//! digest-verified on the way in, but no signature is checked here —
+//! that is `mvm-security::image_verify`'s job, which keeps this crate
+//! a distribution client with no trust policy of its own.
+
+use crate::OciError;
+use crate::digest::{DigestAlgorithm, parse_digest};
+use crate::layer::{LayerDescriptor, LayerFetchOptions, OciLayerFetcher};
+use crate::manifest::{OciManifestFetcher, is_not_found, upstream_reference, verify_pulled};
+use crate::reference::ImageReference;
+use oci_client::manifest::OciManifest;
+
+/// Layer media type of a cosign simple-signing payload.
+pub const SIMPLE_SIGNING_MEDIA_TYPE: &str = "application/vnd.dev.cosign.simplesigning.v1+json";
+
+/// Layer annotation carrying the base64 signature over the payload.
+pub const COSIGN_SIGNATURE_ANNOTATION: &str = "dev.cosignproject.cosign/signature";
+
+/// `artifactType` of a Sigstore bundle attached as a referrer.
+pub const SIGSTORE_BUNDLE_ARTIFACT_TYPE: &str = "application/vnd.dev.sigstore.bundle.v0.3+json";
+
+/// Size cap for a single payload or bundle blob. Real ones are a few
+/// KiB; anything near this is not a signature.
+pub const SIGNATURE_BLOB_MAX_BYTES: u64 = 1024 * 1024;
+
+/// One simple-signing layer from the signature tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleSigningSignature {
+    /// The payload blob, verbatim. The signature covers these bytes.
+    pub payload: Vec<u8>,
+    /// Base64 signature from [`COSIGN_SIGNATURE_ANNOTATION`].
+    pub signature: String,
+}
+
+/// Everything cosign has published for one image.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CosignSignatures {
+    /// Layers of the `sha256-<hex>.sig` manifest.
+    pub simple_signing: Vec<SimpleSigningSignature>,
+    /// Raw Sigstore bundle JSON, one per referrer.
+    pub bundles: Vec<Vec<u8>>,
+}
+
+impl CosignSignatures {
+    /// True when neither layout yielded anything.
+    pub fn is_empty(&self) -> bool {
+        self.simple_signing.is_empty() && self.bundles.is_empty()
+    }
+}
+
+/// The tag cosign stores signatures under for `subject_digest`:
+/// `<alg>-<hex>.sig`. `None` for `sha512` subjects, whose tag would
+/// exceed the distribution spec's 128-character limit — those can
+/// only be signed through referrers.
+pub fn signature_tag(subject_digest: &str) -> Result<Option<String>, OciError> {
+    let (algorithm, hex_part) = parse_digest(subject_digest)?;
+    Ok(match algorithm {
+        DigestAlgorithm::Sha256 => Some(format!("{}-{hex_part}.sig", algorithm.name())),
+        DigestAlgorithm::Sha512 => None,
+    })
+}
+
+impl OciManifestFetcher {
+    /// Collect the cosign signatures published for `subject`.
+    ///
+    /// `subject` must be digest-pinned. A missing signature tag and
+    /// an empty referrers list are not errors — they yield an empty
+    /// [`CosignSignatures`], and the caller decides whether unsigned
+    /// is acceptable. Malformed signature manifests, missing
+    /// signature annotations and blobs that fail their digest or
+    /// [`SIGNATURE_BLOB_MAX_BYTES`] are errors.
+    pub async fn fetch_cosign_signatures(
+        &self,
+        subject: &ImageReference,
+    ) -> Result<CosignSignatures, OciError> {
+        let subject_digest = subject.digest.clone().ok_or_else(|| {
+            OciError::InvalidReference(format!(
+                "{}: signatures need a digest-pinned subject",
+                subject.canonical()
+            ))
+        })?;
+        let blobs = OciLayerFetcher::from_manifest_fetcher(
+            self,
+            LayerFetchOptions {
+                max_size: SIGNATURE_BLOB_MAX_BYTES,
+                ..LayerFetchOptions::default()
+            },
+        );
+
+        let mut signatures = CosignSignatures::default();
+        if let Some(tag) = signature_tag(&subject_digest)? {
+            let mut by_tag = subject.clone();
+            by_tag.digest = None;
+            by_tag.tag = Some(tag);
+            let pulled = match self.pull_raw(&upstream_reference(&by_tag)?).await {
+                Ok(pulled) => Some(verify_pulled(&by_tag, pulled)?),
+                Err(e) if is_not_found(&e) => None,
+                Err(e) => return Err(self.auth().registry_error(e)),
+            };
+            if let Some(manifest) = pulled {
+                let OciManifest::Image(image) = serde_json::from_slice(&manifest.bytes)
+                    .map_err(|e| OciError::Registry(format!("parse signature manifest: {e}")))?
+                else {
+                    return Err(OciError::Registry(format!(
+                        "signature tag for {subject_digest} is an image index"
+                    )));
+                };
+                for layer in image.layers {
+                    if layer.media_type != SIMPLE_SIGNING_MEDIA_TYPE {
+                        continue;
+                    }
+                    let signature = layer
+                        .annotations
+                        .as_ref()
+                        .and_then(|a| a.get(COSIGN_SIGNATURE_ANNOTATION))
+                        .cloned()
+                        .ok_or_else(|| {
+                            OciError::Registry(format!(
+                                "signature layer {} has no {COSIGN_SIGNATURE_ANNOTATION} annotation",
+                                layer.digest
+                            ))
+                        })?;
+                    let descriptor = LayerDescriptor {
+                        digest: layer.digest,
+                        size: layer.size as u64,
+                        media_type: layer.media_type,
+                    };
+                    let payload = fetch_blob(&blobs, subject, &descriptor).await?;
+                    signatures
+                        .simple_signing
+                        .push(SimpleSigningSignature { payload, signature });
+                }
+            }
+        }
+
+        for artifact in self
+            .fetch_referrers(subject, Some(SIGSTORE_BUNDLE_ARTIFACT_TYPE))
+            .await?
+        {
+            let [blob] = artifact.blobs.as_slice() else {
+                return Err(OciError::Registry(format!(
+                    "sigstore bundle referrer {} must carry exactly one blob, has {}",
+                    artifact.manifest.digest,
+                    artifact.blobs.len()
+                )));
+            };
+            signatures
+                .bundles
+                .push(fetch_blob(&blobs, subject, blob).await?);
+        }
+        Ok(signatures)
+    }
+}
+
+async fn fetch_blob(
+    blobs: &OciLayerFetcher,
+    subject: &ImageReference,
+    descriptor: &LayerDescriptor,
+) -> Result<Vec<u8>, OciError> {
+    let mut sink = Vec::new();
+    blobs.fetch_layer(subject, descriptor, &mut sink).await?;
+    Ok(sink)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_tag_follows_cosign_layout() {
+        let digest = format!("sha256:{}", "a".repeat(64));
+        assert_eq!(
+            signature_tag(&digest).unwrap(),
+            Some(format!("sha256-{}.sig", "a".repeat(64)))
+        );
+    }
+
+    #[test]
+    fn sha512_subjects_have_no_signature_tag() {
+        let digest = format!("sha512:{}", "a".repeat(128));
+        assert_eq!(signature_tag(&digest).unwrap(), None);
+    }
+
+    #[test]
+    fn signature_tag_rejects_malformed_digest() {
+        let err = signature_tag("sha256:abc").unwrap_err();
+        assert!(matches!(err, OciError::MalformedDigest(_)), "got {err:?}");
+    }
+}
@@ -0,0 +1,204 @@
+//! Content-digest parsing, hashing, and verification.
+//!
+//! OCI digests are `<algorithm>:<encoded>`. The image spec registers
+//! two algorithms — `sha256` (the default everywhere) and `sha512` —
+//! and those are the only two this crate accepts. Anything else
+//! surfaces as [`OciError::UnsupportedDigestAlgorithm`]; adding an
+//! algorithm is an explicit, reviewed change to [`DigestAlgorithm`].
+//!
+//! Parsing is strict: the encoded part must be exactly the
+//! algorithm's hex length and lowercase. We do not normalize, because
+//! a normalized digest is a different string than the one the
+//! manifest (or the caller) pinned.
+
+use crate::OciError;
+use sha2::{Digest, Sha256, Sha512};
+
+/// A digest algorithm registered by the OCI image spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    /// The algorithm component as it appears on the wire.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Length of the lowercase hex encoding.
+    pub fn hex_len(self) -> usize {
+        match self {
+            Self::Sha256 => 64,
+            Self::Sha512 => 128,
+        }
+    }
+
+    /// `<algorithm>:<lowercase-hex>` over `bytes`.
+    pub fn digest(self, bytes: &[u8]) -> String {
+        let hex = match self {
+            Self::Sha256 => hex::encode(Sha256::digest(bytes)),
+            Self::Sha512 => hex::encode(Sha512::digest(bytes)),
+        };
+        format!("{}:{hex}", self.name())
+    }
+
+    fn from_name(alg: &str) -> Result<Self, OciError> {
+        match alg {
+            "sha256" => Ok(Self::Sha256),
+            "sha512" => Ok(Self::Sha512),
+            other => Err(OciError::UnsupportedDigestAlgorithm(other.to_string())),
+        }
+    }
+}
+
+/// Split and validate a digest string. Returns the algorithm and the
+/// hex part.
+///
+/// Fails with [`OciError::MalformedDigest`] when the `algorithm:`
+/// prefix is missing or the hex part has the wrong length or case,
+/// and with [`OciError::UnsupportedDigestAlgorithm`] for algorithms
+/// other than `sha256` / `sha512`.
+pub fn parse_digest(d: &str) -> Result<(DigestAlgorithm, &str), OciError> {
+    let (alg, hex_part) = d
+        .split_once(':')
+        .ok_or_else(|| OciError::MalformedDigest(format!("missing algorithm prefix: {d:?}")))?;
+    let algorithm = DigestAlgorithm::from_name(alg)?;
+    if hex_part.len() != algorithm.hex_len() {
+        return Err(OciError::MalformedDigest(format!(
+            "{alg} digest must be {} hex chars, got {} in {d:?}",
+            algorithm.hex_len(),
+            hex_part.len()
+        )));
+    }
+    if !hex_part
+        .bytes()
+        .all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase())
+    {
+        return Err(OciError::MalformedDigest(format!(
+            "digest hex must be lowercase ascii: {d:?}"
+        )));
+    }
+    Ok((algorithm, hex_part))
+}
+
+/// Verify that `bytes` hashes to `expected`, using whichever
+/// supported algorithm `expected` names.
+///
+/// Always fails closed. Returns [`OciError::DigestMismatch`] on
+/// content drift and the [`parse_digest`] errors for a malformed or
+/// unsupported `expected`.
+pub fn verify_digest(bytes: &[u8], expected: &str) -> Result<(), OciError> {
+    let (algorithm, _) = parse_digest(expected)?;
+    let computed = algorithm.digest(bytes);
+    if computed != expected {
+        return Err(OciError::DigestMismatch {
+            expected: expected.to_string(),
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Incremental hasher for streamed content (layer and artifact
+/// blobs), picked by the algorithm the descriptor pins.
+#[derive(Clone)]
+pub(crate) enum DigestHasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl DigestHasher {
+    pub(crate) fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            DigestAlgorithm::Sha512 => Self::Sha512(Sha512::new()),
+        }
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Sha256(h) => h.update(bytes),
+            Self::Sha512(h) => h.update(bytes),
+        }
+    }
+
+    /// `<algorithm>:<lowercase-hex>` over everything fed so far.
+    /// Finalizes a clone, so `self` is left untouched.
+    pub(crate) fn digest(&self) -> String {
+        match self {
+            Self::Sha256(h) => format!("sha256:{}", hex::encode(h.clone().finalize())),
+            Self::Sha512(h) => format!("sha512:{}", hex::encode(h.clone().finalize())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KNOWN_BYTES: &[u8] = b"hello mvm";
+    // Recompute via: printf 'hello mvm' | shasum -a 512
+    const KNOWN_SHA512: &str = "sha512:d51ae58b822d642de072a0c8a8ad21d5a8bef5f1e0b61e654f85c01f85ccdf8045e128f8d215aae632a7485cecd72e05b383cd5c4cadb109a9661bd086ecdf87";
+
+    #[test]
+    fn parse_accepts_both_registered_algorithms() {
+        let d256 = format!("sha256:{}", "a".repeat(64));
+        let (alg, hex) = parse_digest(&d256).unwrap();
+        assert_eq!((alg, hex.len()), (DigestAlgorithm::Sha256, 64));
+        let d512 = format!("sha512:{}", "b".repeat(128));
+        let (alg, hex) = parse_digest(&d512).unwrap();
+        assert_eq!((alg, hex.len()), (DigestAlgorithm::Sha512, 128));
+    }
+
+    #[test]
+    fn parse_rejects_unregistered_algorithm() {
+        let err = parse_digest(&format!("sha384:{}", "a".repeat(96))).unwrap_err();
+        assert!(
+            matches!(&err, OciError::UnsupportedDigestAlgorithm(a) if a == "sha384"),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_sha256_length_under_sha512() {
+        let err = parse_digest(&format!("sha512:{}", "a".repeat(64))).unwrap_err();
+        assert!(matches!(err, OciError::MalformedDigest(_)), "got {err:?}");
+    }
+
+    #[test]
+    fn parse_rejects_uppercase_sha512() {
+        let err = parse_digest(&format!("sha512:{}", "A".repeat(128))).unwrap_err();
+        assert!(matches!(err, OciError::MalformedDigest(_)), "got {err:?}");
+    }
+
+    #[test]
+    fn verify_digest_checks_sha512_content() {
+        let digest = DigestAlgorithm::Sha512.digest(KNOWN_BYTES);
+        verify_digest(KNOWN_BYTES, &digest).expect("matching content verifies");
+        let err = verify_digest(b"hello mvm!", &digest).unwrap_err();
+        assert!(
+            matches!(err, OciError::DigestMismatch { .. }),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn streaming_hasher_matches_one_shot_digest() {
+        for alg in [DigestAlgorithm::Sha256, DigestAlgorithm::Sha512] {
+            let mut h = DigestHasher::new(alg);
+            h.update(&KNOWN_BYTES[..5]);
+            h.update(&KNOWN_BYTES[5..]);
+            assert_eq!(h.digest(), alg.digest(KNOWN_BYTES));
+        }
+    }
+
+    #[test]
+    fn known_sha512_constant_is_self_consistent() {
+        assert_eq!(DigestAlgorithm::Sha512.digest(KNOWN_BYTES), KNOWN_SHA512);
+    }
+}
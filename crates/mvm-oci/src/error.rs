@@ -3,11 +3,12 @@
 //! Each variant carries only the information needed to diagnose the
 //! failure. Registry hostnames, repository names, and manifest media
 //! types are not secrets and appear in error messages verbatim.
-//! When private-registry auth lands (later W1 PR), credential
-//! material will be carried via [`secrecy::SecretString`] and the
-//! `Display` impl will redact — that path is gated by ADR-049 and the
-//! `xtask check-no-display-on-secret-types` lint, so this enum can
-//! stay plain until then.
+//! Credential material never enters a variant: it is carried in
+//! [`secrecy::SecretString`], and every upstream message passes
+//! through [`crate::RegistryAuthConfig::redact`] before it is
+//! wrapped (the ADR-049 redaction contract, backed by the
+//! `xtask check-no-display-on-secret-types` lint). That keeps this
+//! enum's `Display` safe to log as-is.
 
 use thiserror::Error;
 
@@ -25,9 +26,9 @@ pub enum OciError {
     DigestMismatch { expected: String, computed: String },
 
     /// Caller asked us to verify a digest using an algorithm we do
-    /// not implement. v1 supports `sha256` only; the OCI spec allows
-    /// other algorithms but we narrow until they are explicitly
-    /// audited.
+    /// not implement. We support the OCI-registered `sha256` and
+    /// `sha512`; anything else stays unsupported until it is
+    /// explicitly audited.
     #[error("unsupported digest algorithm: {0}")]
     UnsupportedDigestAlgorithm(String),
 
@@ -43,6 +44,13 @@ pub enum OciError {
     #[error("registry error: {0}")]
     Registry(String),
 
+    /// Registry authentication failed — the bearer-token challenge
+    /// was malformed, or the token endpoint refused the credential.
+    /// Carries status and endpoint only; response bodies are dropped
+    /// because token servers may echo the credential back.
+    #[error("registry authentication failed: {0}")]
+    Auth(String),
+
     /// A layer descriptor's declared size, or the streamed byte
     /// count, exceeded the configured size cap. Plan 74 §Risks R10
     /// — decompression-bomb / oversized-layer mitigation. The
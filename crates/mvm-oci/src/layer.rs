@@ -4,7 +4,7 @@
 //! A single OCI layer can be hundreds of MB; loading it into memory
 //! up front is not viable. [`OciLayerFetcher::fetch_layer`] streams
 //! bytes through a hashing wrapper to a caller-supplied writer,
-//! computing the SHA-256 incrementally and aborting the moment a
+//! computing the digest incrementally and aborting the moment a
 //! size cap is exceeded or the running digest diverges from what
 //! the layer descriptor promised.
 //!
@@ -15,8 +15,9 @@
 //!   oversized layers as a CVE-class category; the cap fails fast
 //!   before the rest of the pull pipeline reads a single byte of
 //!   poisoned content.
-//! - **Digest verification.** SHA-256 over the streamed bytes is
-//!   compared against the descriptor's pinned digest. Mismatches
+//! - **Digest verification.** The streamed bytes are hashed with
+//!   the algorithm the descriptor's digest names (`sha256` or
+//!   `sha512`) and compared against that digest. Mismatches
 //!   surface as [`OciError::DigestMismatch`]. Always fail closed.
 //! - **Bounded retry.** [`LayerFetchOptions::max_retries`] caps the
 //!   number of attempts on transient registry errors with
@@ -25,10 +26,10 @@
 //!   retry — those won't get better with more tries.
 
 use crate::OciError;
+use crate::digest::{DigestAlgorithm, DigestHasher, parse_digest};
 use crate::manifest::{OciManifestFetcher, RegistryAuthConfig};
 use crate::reference::ImageReference;
 use oci_client::client::Client;
-use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
@@ -41,12 +42,15 @@ use tokio::io::{AsyncWrite, AsyncWriteExt};
 const CAP_EXCEEDED_MSG: &str = "mvm-oci: layer size cap exceeded";
 
 /// One layer's worth of content-addressable storage. Extracted
-/// from a parsed manifest via [`crate::FetchedManifest::layers`].
+/// from a parsed manifest via [`crate::FetchedManifest::layers`],
+/// or from an artifact manifest via
+/// [`crate::ArtifactManifest::blobs`] — artifact blobs are fetched
+/// through the same [`OciLayerFetcher::fetch_layer`] path.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LayerDescriptor {
-    /// `sha256:<lowercase-hex>` content digest. Layer content is
-    /// fetched, hashed, and compared against this value; any
-    /// mismatch fails closed.
+    /// `sha256:<hex>` or `sha512:<hex>` content digest. Layer
+    /// content is fetched, hashed, and compared against this value;
+    /// any mismatch fails closed.
     pub digest: String,
     /// Byte size the manifest advertises. The size cap is checked
     /// against [`LayerFetchOptions::max_size`] before the fetch
@@ -87,9 +91,9 @@ impl Default for LayerFetchOptions {
     }
 }
 
-/// Real layer fetcher backed by [`oci_client`]. Anonymous-only
-/// (W1.1 scope discipline); private-registry auth lands in a later
-/// W1 PR with credential material flowing through `secrecy`.
+/// Real layer fetcher backed by [`oci_client`]. Private-registry
+/// credentials arrive as a [`RegistryAuthConfig`] and are redacted
+/// from every error.
 pub struct OciLayerFetcher {
     client: Client,
     options: LayerFetchOptions,
@@ -166,7 +170,7 @@ impl OciLayerFetcher {
     /// Fails closed on any of:
     /// - declared `layer.size` exceeds `options.max_size`
     /// - streamed byte count exceeds `options.max_size`
-    /// - SHA-256 over the streamed bytes != `layer.digest`
+    /// - digest over the streamed bytes != `layer.digest`
     /// - registry network or permission failure (with bounded
     ///   retry on transient classes)
     pub async fn fetch_layer(
@@ -183,7 +187,7 @@ impl OciLayerFetcher {
                 cap: self.options.max_size,
             });
         }
-        validate_layer_digest(&layer.digest)?;
+        let algorithm = validate_layer_digest(&layer.digest)?;
 
         let upstream_ref: oci_client::Reference =
             reference
@@ -203,7 +207,7 @@ impl OciLayerFetcher {
         // from a generic IO error in the retry guard.
         let count = Arc::new(AtomicU64::new(0));
         let cap_hit = Arc::new(AtomicBool::new(false));
-        let mut hasher = Sha256::new();
+        let mut hasher = DigestHasher::new(algorithm);
 
         let mut attempt: u32 = 0;
         let mut delay = self.options.initial_backoff;
@@ -247,7 +251,7 @@ impl OciLayerFetcher {
                     // initial state; reset defensively in case a
                     // partial chunk got through without
                     // incrementing count.
-                    hasher = Sha256::new();
+                    hasher = DigestHasher::new(algorithm);
                 }
                 Err(e) => return Err(e),
             }
@@ -259,7 +263,7 @@ impl OciLayerFetcher {
         reference: &oci_client::Reference,
         layer: &LayerDescriptor,
         writer: &mut (dyn AsyncWrite + Send + Unpin),
-        hasher: &mut Sha256,
+        hasher: &mut DigestHasher,
         count: Arc<AtomicU64>,
         cap_hit: Arc<AtomicBool>,
     ) -> Result<u64, OciError> {
@@ -283,7 +287,7 @@ impl OciLayerFetcher {
         self.client
             .pull_blob(reference, layer.digest.as_str(), &mut capped_writer)
             .await
-            .map_err(map_oci_error)?;
+            .map_err(|e| self.auth.registry_error(e))?;
 
         capped_writer
             .inner
@@ -292,10 +296,10 @@ impl OciLayerFetcher {
             .map_err(|e| OciError::Registry(format!("flush after blob fetch: {e}")))?;
 
         let final_count = count.load(Ordering::SeqCst);
-        // Finalize via clone so the original hasher stays in a
-        // defined state if a future revision adds a post-finalize
-        // step. `Sha256` is `Clone`.
-        let computed = format!("sha256:{}", hex::encode(hasher.clone().finalize()));
+        // `digest()` finalizes a clone so the original hasher stays
+        // in a defined state if a future revision adds a
+        // post-finalize step.
+        let computed = hasher.digest();
         if computed != layer.digest {
             return Err(OciError::DigestMismatch {
                 expected: layer.digest.clone(),
@@ -314,7 +318,7 @@ impl OciLayerFetcher {
 /// inspect them after `pull_blob` returns.
 struct CappedHashingWriter<'a, W: ?Sized> {
     inner: &'a mut W,
-    hasher: &'a mut Sha256,
+    hasher: &'a mut DigestHasher,
     count: Arc<AtomicU64>,
     cap_hit: Arc<AtomicBool>,
     cap: u64,
@@ -367,28 +371,8 @@ where
     }
 }
 
-fn validate_layer_digest(d: &str) -> Result<(), OciError> {
-    let (alg, hex_part) = d
-        .split_once(':')
-        .ok_or_else(|| OciError::MalformedDigest(format!("missing algorithm prefix: {d:?}")))?;
-    if alg != "sha256" {
-        return Err(OciError::UnsupportedDigestAlgorithm(alg.to_string()));
-    }
-    if hex_part.len() != 64 {
-        return Err(OciError::MalformedDigest(format!(
-            "sha256 digest must be 64 hex chars, got {} in {d:?}",
-            hex_part.len()
-        )));
-    }
-    if !hex_part
-        .bytes()
-        .all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase())
-    {
-        return Err(OciError::MalformedDigest(format!(
-            "digest hex must be lowercase ascii: {d:?}"
-        )));
-    }
-    Ok(())
+fn validate_layer_digest(d: &str) -> Result<DigestAlgorithm, OciError> {
+    parse_digest(d).map(|(algorithm, _)| algorithm)
 }
 
 /// Best-effort classification of `oci_client` errors. We hand
@@ -401,10 +385,6 @@ fn validate_layer_digest(d: &str) -> Result<(), OciError> {
 /// is a follow-up — for W1.2 the optimistic-retry policy is good
 /// enough (the worst case is "we waited a bit before reporting a
 /// permanent error," which is harmless).
-fn map_oci_error(e: oci_client::errors::OciDistributionError) -> OciError {
-    OciError::Registry(e.to_string())
-}
-
 fn is_transient(e: &OciError) -> bool {
     match e {
         // Most registry errors that bubble through `oci_client`
@@ -418,6 +398,7 @@ fn is_transient(e: &OciError) -> bool {
         | OciError::InvalidReference(_)
         | OciError::MalformedDigest(_)
         | OciError::UnsupportedDigestAlgorithm(_)
+        | OciError::Auth(_)
         | OciError::LayerTooLarge { .. } => false,
     }
 }
@@ -432,9 +413,15 @@ mod tests {
         validate_layer_digest(&d).unwrap();
     }
 
+    #[test]
+    fn validate_layer_digest_accepts_lowercase_sha512() {
+        let d = format!("sha512:{}", "a".repeat(128));
+        assert_eq!(validate_layer_digest(&d).unwrap(), DigestAlgorithm::Sha512);
+    }
+
     #[test]
     fn validate_layer_digest_rejects_unsupported_algorithm() {
-        let err = validate_layer_digest(&format!("sha512:{}", "a".repeat(128))).unwrap_err();
+        let err = validate_layer_digest(&format!("sha384:{}", "a".repeat(96))).unwrap_err();
         assert!(matches!(err, OciError::UnsupportedDigestAlgorithm(_)));
     }
 
@@ -16,9 +16,21 @@
 //!   (see `tests/hermetic_registry.rs`).
 //! - **Digest verification.** Every fetched manifest's content digest
 //!   is verified before it leaves the fetcher.
-//!   [`verify_sha256_digest`] is the standalone primitive — algorithm
-//!   support is intentionally narrow (sha256 only in v1) so any
-//!   future expansion is an explicit, reviewed decision.
+//!   [`verify_digest`] is the standalone primitive. Algorithm
+//!   support is intentionally narrow — the two OCI-registered
+//!   algorithms, `sha256` and `sha512`, and nothing else — so any
+//!   further expansion is an explicit, reviewed decision
+//!   ([`digest::DigestAlgorithm`]).
+//! - **Referrers.** [`OciManifestFetcher::fetch_referrers`] discovers
+//!   the OCI 1.1 artifacts attached to a digest-pinned image
+//!   (signatures, SBOMs, attestations) and returns each as a
+//!   verified [`ArtifactManifest`]; their blobs go through the layer
+//!   fetcher like any other content.
+//! - **Cosign signatures.** [`OciManifestFetcher::fetch_cosign_signatures`]
+//!   collects the simple-signing payloads under cosign's
+//!   `sha256-<hex>.sig` tag and the Sigstore bundles attached as
+//!   referrers. Checking them is `mvm-security::image_verify`'s job;
+//!   this crate only fetches and digest-verifies the material.
 //! - **Layer fetch.** [`OciLayerFetcher`] streams a single layer
 //!   from the registry into a caller-supplied `AsyncWrite`,
 //!   hashing as it goes, enforcing
@@ -31,8 +43,11 @@
 //! (ADR-050), template registration, and the `mvmctl image pull`
 //! CLI surface land in subsequent W1 PRs. Private-registry
 //! authentication is explicit: callers pass bearer/basic credentials
-//! through [`RegistryAuthConfig`], and this crate deliberately does
-//! not read Docker credential helpers or `~/.docker/config.json`.
+//! through [`RegistryAuthConfig`], exchanging identity tokens for a
+//! bearer token with [`exchange_identity_token`] first. This crate
+//! deliberately does not read Docker credential helpers or
+//! `~/.docker/config.json`; mvm's credential store and helper
+//! delegation live in `mvm-security::registry_credentials`.
 //!
 //! Crate-level invariant: this crate **only** speaks the OCI
 //! distribution wire format. It does not touch the host filesystem,
@@ -46,6 +61,9 @@
 
 #![forbid(unsafe_code)]
 
+pub mod auth;
+pub mod cosign;
+pub mod digest;
 pub mod error;
 pub mod layer;
 pub mod manifest;
@@ -58,11 +76,14 @@ pub mod reference;
 // (Phase E, claim 10).
 pub mod unpack;
 
+pub use auth::exchange_identity_token;
+pub use cosign::{CosignSignatures, SimpleSigningSignature};
+pub use digest::{DigestAlgorithm, parse_digest, verify_digest};
 pub use error::OciError;
 pub use layer::{LayerDescriptor, LayerFetchOptions, OciLayerFetcher};
 pub use manifest::{
-    ClientConfig, ClientProtocol, FetchedManifest, LinuxPlatform, ManifestFetcher,
-    OciManifestFetcher, RegistryAuthConfig, verify_sha256_digest,
+    ArtifactManifest, ClientConfig, ClientProtocol, FetchedManifest, LinuxPlatform,
+    ManifestFetcher, OciManifestFetcher, RegistryAuthConfig, verify_sha256_digest,
 };
 pub use reference::ImageReference;
 pub use unpack::{
@@ -8,28 +8,46 @@
 //! exercises [`OciManifestFetcher`] directly via a wiremock-backed
 //! HTTP server on a random localhost port.
 //!
-//! Digest verification is always content-addressable: the SHA-256
-//! over the manifest bytes is compared against the digest the caller
-//! pinned (or the digest the registry advertised). A mismatch is a
-//! hard error; we never paper over it with a warning.
+//! Digest verification is always content-addressable: the manifest
+//! bytes are hashed with the algorithm each digest names (`sha256`
+//! or `sha512`, see [`crate::digest`]) and compared against both the
+//! digest the caller pinned and the digest the registry advertised.
+//! A mismatch is a hard error; we never paper over it with a warning.
+//!
+//! Beyond image manifests, [`OciManifestFetcher::fetch_referrers`]
+//! discovers the OCI 1.1 artifacts (signatures, SBOMs, attestations)
+//! attached to an image through the referrers API, falling back to
+//! the referrers tag schema on registries that predate it. Each
+//! referrer is fetched and digest-verified like any other manifest
+//! and surfaces as an [`ArtifactManifest`].
 
 use crate::OciError;
+use crate::digest::{parse_digest, verify_digest};
 use crate::layer::LayerDescriptor;
 use crate::reference::ImageReference;
 use async_trait::async_trait;
+use base64::Engine as _;
 use oci_client::client::Client;
 pub use oci_client::client::{ClientConfig, ClientProtocol};
-use oci_client::manifest::OciManifest;
+use oci_client::errors::{OciDistributionError, OciErrorCode};
+use oci_client::manifest::{ImageIndexEntry, OciImageIndex, OciManifest};
 use oci_client::secrets::RegistryAuth;
 use secrecy::{ExposeSecret, SecretString};
-use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 
 /// Explicit registry authentication material for OCI pulls.
 ///
 /// This deliberately does not read Docker credential helpers or
 /// `~/.docker/config.json`. Callers pass the credential source they
-/// trust, and this crate only forwards it to the OCI Distribution
-/// client for the current process.
+/// trust (mvm's own registry credential store lives in
+/// `mvm-security::registry_credentials`), and this crate only
+/// forwards it to the OCI Distribution client for the current
+/// process. Identity tokens are exchanged for a bearer token first,
+/// via [`crate::auth::exchange_identity_token`].
+///
+/// Secret material never reaches an [`OciError`]: every upstream
+/// error a fetcher surfaces passes through [`Self::redact`] (ADR-049
+/// redaction contract).
 #[derive(Clone, Default)]
 pub enum RegistryAuthConfig {
     #[default]
@@ -83,6 +101,32 @@ impl RegistryAuthConfig {
         !matches!(self, Self::Anonymous)
     }
 
+    /// Replace every occurrence of this config's secret material in
+    /// `message` with `REDACTED` — the raw value and, for basic
+    /// auth, the base64 `user:password` pair an `Authorization`
+    /// header would carry.
+    pub fn redact(&self, message: &str) -> String {
+        let secrets: Vec<String> = match self {
+            Self::Anonymous => Vec::new(),
+            Self::Bearer { token } => vec![token.expose_secret().to_string()],
+            Self::Basic { username, password } => vec![
+                password.expose_secret().to_string(),
+                base64::engine::general_purpose::STANDARD
+                    .encode(format!("{username}:{}", password.expose_secret())),
+            ],
+        };
+        let mut redacted = message.to_string();
+        for secret in secrets.iter().filter(|s| !s.is_empty()) {
+            redacted = redacted.replace(secret.as_str(), "REDACTED");
+        }
+        redacted
+    }
+
+    /// Wrap an upstream failure as [`OciError::Registry`], redacted.
+    pub(crate) fn registry_error(&self, e: impl std::fmt::Display) -> OciError {
+        OciError::Registry(self.redact(&e.to_string()))
+    }
+
     pub(crate) fn to_registry_auth(&self) -> RegistryAuth {
         match self {
             Self::Anonymous => RegistryAuth::Anonymous,
@@ -103,8 +147,10 @@ pub struct FetchedManifest {
     /// The reference the manifest was fetched against, after
     /// canonicalization.
     pub reference: ImageReference,
-    /// SHA-256 digest as `sha256:<lowercase-hex>`. Always verified
-    /// against the bytes before this struct is constructed.
+    /// Content digest as `<algorithm>:<lowercase-hex>` — the digest
+    /// the caller pinned when fetching by digest (`sha256` or
+    /// `sha512`), otherwise the one the registry advertised. Always
+    /// verified against the bytes before this struct is constructed.
     pub digest: String,
     /// Raw manifest bytes as received from the registry.
     pub bytes: Vec<u8>,
@@ -175,6 +221,69 @@ impl FetchedManifest {
             )),
         }
     }
+
+    /// Parse the manifest as an OCI 1.1 artifact manifest. The
+    /// artifact type is the manifest's `artifactType`, falling back
+    /// to `config.mediaType` when that is not the empty descriptor
+    /// (the image spec's rule for pre-1.1 artifacts). Image indexes
+    /// and manifests with no artifact type are rejected.
+    pub fn artifact(&self) -> Result<ArtifactManifest, OciError> {
+        let manifest: OciManifest = serde_json::from_slice(&self.bytes)
+            .map_err(|e| OciError::Registry(format!("parse manifest: {e}")))?;
+        let OciManifest::Image(img) = manifest else {
+            return Err(OciError::Registry(format!(
+                "{} is an image index, not an artifact manifest",
+                self.digest
+            )));
+        };
+        let artifact_type = match img.artifact_type {
+            Some(t) => t,
+            None if img.config.media_type != EMPTY_CONFIG_MEDIA_TYPE => img.config.media_type,
+            None => {
+                return Err(OciError::Registry(format!(
+                    "{} has an empty config and no artifactType",
+                    self.digest
+                )));
+            }
+        };
+        Ok(ArtifactManifest {
+            artifact_type,
+            subject: img.subject.map(|s| s.digest),
+            blobs: img
+                .layers
+                .into_iter()
+                .map(|l| LayerDescriptor {
+                    digest: l.digest,
+                    size: l.size as u64,
+                    media_type: l.media_type,
+                })
+                .collect(),
+            annotations: img.annotations.unwrap_or_default(),
+            manifest: self.clone(),
+        })
+    }
+}
+
+/// `config.mediaType` of an OCI 1.1 artifact with no config blob.
+const EMPTY_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.empty.v1+json";
+
+/// An OCI 1.1 artifact manifest — a signature, SBOM, attestation or
+/// other content attached to an image through its `subject`.
+#[derive(Debug, Clone)]
+pub struct ArtifactManifest {
+    /// The verified manifest this was parsed from.
+    pub manifest: FetchedManifest,
+    /// `artifactType` (e.g. `application/vnd.dev.sigstore.bundle.v0.3+json`,
+    /// `application/spdx+json`).
+    pub artifact_type: String,
+    /// Digest of the manifest this artifact is attached to, if any.
+    pub subject: Option<String>,
+    /// Content blobs. Fetch them with
+    /// [`crate::OciLayerFetcher::fetch_layer`]; digest and size
+    /// checks apply exactly as for image layers.
+    pub blobs: Vec<LayerDescriptor>,
+    /// Manifest-level annotations.
+    pub annotations: BTreeMap<String, String>,
 }
 
 /// Contract for "fetch the manifest of this image and verify its
@@ -185,10 +294,9 @@ pub trait ManifestFetcher: Send + Sync {
     async fn fetch(&self, reference: &ImageReference) -> Result<FetchedManifest, OciError>;
 }
 
-/// Real fetcher backed by [`oci_client`]. Anonymous-only in W1.1;
-/// private-registry auth lands in a later W1 PR with the credential
-/// material flowing through [`secrecy::SecretString`] and the
-/// `check-no-display-on-secret-types` lint.
+/// Real fetcher backed by [`oci_client`]. Private-registry
+/// credentials arrive as a [`RegistryAuthConfig`], held in
+/// [`secrecy::SecretString`] and redacted from every error.
 pub struct OciManifestFetcher {
     client: Client,
     auth: RegistryAuthConfig,
@@ -295,6 +403,134 @@ impl OciManifestFetcher {
         by_digest.digest = Some(descriptor.digest.clone());
         self.fetch(&by_digest).await
     }
+
+    /// Discover and fetch the artifacts attached to `subject`.
+    ///
+    /// `subject` must be digest-pinned: referrers are keyed by
+    /// digest, never by a mutable tag. Uses the OCI 1.1 referrers API
+    /// and, when the registry answers 404, the referrers tag schema
+    /// (`<alg>-<hex>` tag holding an image index). Every referrer
+    /// manifest is fetched by digest and verified; one whose
+    /// `subject` does not name `subject` fails the whole call.
+    ///
+    /// `artifact_type` is passed to the registry as a filter and
+    /// applied again here, since registries may ignore it.
+    pub async fn fetch_referrers(
+        &self,
+        subject: &ImageReference,
+        artifact_type: Option<&str>,
+    ) -> Result<Vec<ArtifactManifest>, OciError> {
+        let subject_digest = subject.digest.clone().ok_or_else(|| {
+            OciError::InvalidReference(format!(
+                "{}: referrers need a digest-pinned subject",
+                subject.canonical()
+            ))
+        })?;
+        let upstream_ref = upstream_reference(subject)?;
+        let auth = self.auth.to_registry_auth();
+        self.client
+            .store_auth_if_needed(upstream_ref.resolve_registry(), &auth)
+            .await;
+
+        let entries = match self
+            .client
+            .pull_referrers(&upstream_ref, artifact_type)
+            .await
+        {
+            Ok(index) => index.manifests,
+            Err(e) if is_not_found(&e) => {
+                self.referrers_from_tag_schema(subject, &subject_digest)
+                    .await?
+            }
+            Err(e) => return Err(self.auth.registry_error(e)),
+        };
+
+        let mut artifacts = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let mut by_digest = subject.clone();
+            by_digest.tag = None;
+            by_digest.digest = Some(entry.digest);
+            let artifact = self.fetch(&by_digest).await?.artifact()?;
+            if artifact.subject.as_deref() != Some(subject_digest.as_str()) {
+                return Err(OciError::Registry(format!(
+                    "referrer {} does not name subject {subject_digest}",
+                    artifact.manifest.digest
+                )));
+            }
+            if artifact_type.is_some_and(|t| t != artifact.artifact_type) {
+                continue;
+            }
+            artifacts.push(artifact);
+        }
+        Ok(artifacts)
+    }
+
+    /// Referrers tag schema fallback (distribution spec v1.1
+    /// §"Referrers Tag Schema"). A missing tag means no referrers.
+    async fn referrers_from_tag_schema(
+        &self,
+        subject: &ImageReference,
+        subject_digest: &str,
+    ) -> Result<Vec<ImageIndexEntry>, OciError> {
+        let (algorithm, hex_part) = parse_digest(subject_digest)?;
+        let mut by_tag = subject.clone();
+        by_tag.digest = None;
+        by_tag.tag = Some(format!("{}-{}", algorithm.name(), &hex_part[..64]));
+        let bytes = match self.pull_raw(&upstream_reference(&by_tag)?).await {
+            Ok(pulled) => verify_pulled(&by_tag, pulled)?.bytes,
+            Err(e) if is_not_found(&e) => return Ok(Vec::new()),
+            Err(e) => return Err(self.auth.registry_error(e)),
+        };
+        let index: OciImageIndex = serde_json::from_slice(&bytes)
+            .map_err(|e| OciError::Registry(format!("parse referrers index: {e}")))?;
+        Ok(index.manifests)
+    }
+
+    /// Raw manifest bytes plus the digest the registry advertised.
+    pub(crate) async fn pull_raw(
+        &self,
+        reference: &oci_client::Reference,
+    ) -> Result<(Vec<u8>, String), OciDistributionError> {
+        let auth = self.auth.to_registry_auth();
+        let (bytes, advertised_digest) = self
+            .client
+            .pull_manifest_raw(reference, &auth, ACCEPTED_MANIFEST_MEDIA)
+            .await?;
+        // `pull_manifest_raw` hands back `bytes::Bytes`; the
+        // public `FetchedManifest::bytes` field is `Vec<u8>` so
+        // callers don't have to depend on the `bytes` crate. The
+        // `.to_vec()` is one copy, which is fine for manifest-sized
+        // payloads (single-digit KB).
+        Ok((bytes.to_vec(), advertised_digest))
+    }
+}
+
+/// Convert mvm's structured reference into the shape `oci_client`
+/// consumes. Canonical form is round-tripped through the upstream
+/// parser — round-trip failure here would indicate our `canonical()`
+/// and oci-client disagree about the same string, which is a bug we
+/// want to surface immediately rather than paper over.
+pub(crate) fn upstream_reference(
+    reference: &ImageReference,
+) -> Result<oci_client::Reference, OciError> {
+    let canonical = reference.canonical();
+    canonical
+        .parse()
+        .map_err(|e| OciError::InvalidReference(format!("{canonical}: {e}")))
+}
+
+/// A 404 from the registry, however it was phrased.
+pub(crate) fn is_not_found(e: &OciDistributionError) -> bool {
+    match e {
+        OciDistributionError::ServerError { code, .. } => *code == 404,
+        OciDistributionError::RegistryError { envelope, .. } => envelope.errors.iter().any(|err| {
+            matches!(
+                err.code,
+                OciErrorCode::ManifestUnknown | OciErrorCode::NotFound
+            )
+        }),
+        _ => false,
+    }
 }
 
 /// The OCI media types we accept on a manifest fetch. Listed in
@@ -314,17 +550,6 @@ const ACCEPTED_MANIFEST_MEDIA: &[&str] = &[
 #[async_trait]
 impl ManifestFetcher for OciManifestFetcher {
     async fn fetch(&self, reference: &ImageReference) -> Result<FetchedManifest, OciError> {
-        // Convert mvm's structured reference into the shape
-        // `oci_client` consumes. Canonical form is round-tripped
-        // through the upstream parser — round-trip failure here
-        // would indicate our `canonical()` and oci-client disagree
-        // about the same string, which is a bug we want to surface
-        // immediately rather than paper over.
-        let canonical = reference.canonical();
-        let upstream_ref: oci_client::Reference = canonical
-            .parse()
-            .map_err(|e| OciError::InvalidReference(format!("{canonical}: {e}")))?;
-
         // Fetch the *raw* wire bytes, not the parsed-then-
         // re-serialized form. JSON re-serialization is not
         // byte-stable (key ordering, whitespace, escape choices),
@@ -332,49 +557,52 @@ impl ManifestFetcher for OciManifestFetcher {
         // digest against what the registry advertised. The
         // content digest is a property of the *wire* bytes;
         // anything else is a bug.
-        let auth = self.auth.to_registry_auth();
-        let (bytes, advertised_digest) = self
-            .client
-            .pull_manifest_raw(&upstream_ref, &auth, ACCEPTED_MANIFEST_MEDIA)
+        let upstream_ref = upstream_reference(reference)?;
+        let pulled = self
+            .pull_raw(&upstream_ref)
             .await
-            .map_err(|e| OciError::Registry(e.to_string()))?;
-
-        let computed = format!("sha256:{}", hex::encode(Sha256::digest(&bytes)));
-        if computed != advertised_digest {
-            return Err(OciError::DigestMismatch {
-                expected: advertised_digest,
-                computed,
-            });
-        }
-        if let Some(pinned) = &reference.digest {
-            if pinned != &computed {
-                return Err(OciError::DigestMismatch {
-                    expected: pinned.clone(),
-                    computed,
-                });
-            }
-        }
+            .map_err(|e| self.auth.registry_error(e))?;
+        verify_pulled(reference, pulled)
+    }
+}
 
-        // Parse the bytes once more to classify the media type
-        // for the caller's downstream branching (image vs index).
-        // This parse is purely diagnostic; the *digest* check
-        // above is the load-bearing one.
-        let manifest: OciManifest = serde_json::from_slice(&bytes)
-            .map_err(|e| OciError::Registry(format!("parse manifest after digest verify: {e}")))?;
-        let media_type = manifest_media_type(&manifest).to_string();
-
-        Ok(FetchedManifest {
-            reference: reference.clone(),
-            digest: computed,
-            // `pull_manifest_raw` hands back `bytes::Bytes`; the
-            // public `FetchedManifest::bytes` field is `Vec<u8>`
-            // so callers don't have to depend on the `bytes`
-            // crate. The `.to_vec()` is one copy, which is fine
-            // for manifest-sized payloads (single-digit KB).
-            bytes: bytes.to_vec(),
-            media_type,
-        })
+/// Verify raw manifest bytes against the digest the registry
+/// advertised and, when `reference` is digest-pinned, the pinned
+/// digest. Each is checked with its own algorithm.
+pub(crate) fn verify_pulled(
+    reference: &ImageReference,
+    (bytes, advertised_digest): (Vec<u8>, String),
+) -> Result<FetchedManifest, OciError> {
+    let (algorithm, _) = parse_digest(&advertised_digest)?;
+    let computed = algorithm.digest(&bytes);
+    if computed != advertised_digest {
+        return Err(OciError::DigestMismatch {
+            expected: advertised_digest,
+            computed,
+        });
     }
+    let digest = match &reference.digest {
+        Some(pinned) => {
+            verify_digest(&bytes, pinned)?;
+            pinned.clone()
+        }
+        None => computed,
+    };
+
+    // Parse the bytes once more to classify the media type
+    // for the caller's downstream branching (image vs index).
+    // This parse is purely diagnostic; the *digest* check
+    // above is the load-bearing one.
+    let manifest: OciManifest = serde_json::from_slice(&bytes)
+        .map_err(|e| OciError::Registry(format!("parse manifest after digest verify: {e}")))?;
+    let media_type = manifest_media_type(&manifest).to_string();
+
+    Ok(FetchedManifest {
+        reference: reference.clone(),
+        digest,
+        bytes,
+        media_type,
+    })
 }
 
 fn manifest_media_type(manifest: &OciManifest) -> &'static str {
@@ -389,45 +617,27 @@ fn manifest_media_type(manifest: &OciManifest) -> &'static str {
 /// hand (e.g. from a cache) and want to assert content integrity
 /// without going through the full fetcher.
 ///
+/// Kept sha256-only for callers that pin that algorithm on purpose;
+/// [`crate::digest::verify_digest`] accepts `sha512` as well.
+///
 /// Always fails closed. Returns [`OciError::DigestMismatch`] on
 /// content drift, [`OciError::MalformedDigest`] if `expected` does
 /// not match `sha256:<64 lowercase hex chars>`,
 /// [`OciError::UnsupportedDigestAlgorithm`] for non-sha256 inputs.
 pub fn verify_sha256_digest(bytes: &[u8], expected: &str) -> Result<(), OciError> {
-    let (alg, hex_part) = expected.split_once(':').ok_or_else(|| {
+    let (alg, _) = expected.split_once(':').ok_or_else(|| {
         OciError::MalformedDigest(format!("missing algorithm prefix: {expected:?}"))
     })?;
     if alg != "sha256" {
         return Err(OciError::UnsupportedDigestAlgorithm(alg.to_string()));
     }
-    if hex_part.len() != 64 {
-        return Err(OciError::MalformedDigest(format!(
-            "sha256 digest must be 64 hex chars, got {} in {expected:?}",
-            hex_part.len()
-        )));
-    }
-    if !hex_part
-        .bytes()
-        .all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase())
-    {
-        return Err(OciError::MalformedDigest(format!(
-            "digest hex must be lowercase ascii: {expected:?}"
-        )));
-    }
-
-    let computed = format!("sha256:{}", hex::encode(Sha256::digest(bytes)));
-    if computed != expected {
-        return Err(OciError::DigestMismatch {
-            expected: expected.to_string(),
-            computed,
-        });
-    }
-    Ok(())
+    verify_digest(bytes, expected)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sha2::{Digest, Sha256};
 
     const KNOWN_BYTES: &[u8] = b"hello mvm";
     // sha256("hello mvm") — kept as a constant so the test for
@@ -441,6 +651,34 @@ mod tests {
         format!("sha256:{}", hex::encode(Sha256::digest(bytes)))
     }
 
+    #[test]
+    fn redact_strips_bearer_token_from_upstream_messages() {
+        let auth = RegistryAuthConfig::bearer("s3cr3t-token");
+        let err = auth.registry_error("401 for Bearer s3cr3t-token at ghcr.io");
+        let shown = err.to_string();
+        assert!(!shown.contains("s3cr3t-token"), "leaked: {shown}");
+        assert!(shown.contains("Bearer REDACTED"), "got: {shown}");
+    }
+
+    #[test]
+    fn redact_strips_basic_password_and_header_encoding() {
+        let auth = RegistryAuthConfig::basic("alice", "hunter2");
+        let header = base64::engine::general_purpose::STANDARD.encode("alice:hunter2");
+        let shown = auth
+            .registry_error(format!("password hunter2 rejected (Basic {header})"))
+            .to_string();
+        assert!(!shown.contains("hunter2"), "leaked: {shown}");
+        assert!(!shown.contains(&header), "leaked: {shown}");
+    }
+
+    #[test]
+    fn redact_is_identity_for_anonymous_auth() {
+        assert_eq!(
+            RegistryAuthConfig::Anonymous.redact("plain message"),
+            "plain message"
+        );
+    }
+
     #[test]
     fn verify_digest_accepts_matching_content() {
         let digest = computed_digest(KNOWN_BYTES);
@@ -16,11 +16,15 @@
 //! - `ghcr.io/foo/bar:v1@sha256:…` — tag *and* digest. Both are
 //!   preserved; production-profile admission uses the digest.
 //!
+//! Digest pins may use either OCI-registered algorithm, `sha256` or
+//! `sha512` (see [`crate::digest`]).
+//!
 //! Production-profile admission (plan 74 W1.6) rejects references
 //! that resolve to a tag *without* a digest — that's the
 //! mutable-tag-rejection rule from ADR-048 §"OCI ingest".
 
 use crate::OciError;
+use crate::digest::parse_digest;
 use std::fmt;
 use std::str::FromStr;
 
@@ -178,34 +182,11 @@ impl FromStr for ImageReference {
 }
 
 fn validate_digest(d: &str) -> Result<(), OciError> {
-    // Format: `<algorithm>:<hex>` where `<algorithm>` is sha256 in
-    // v1 and `<hex>` is the canonical lowercase hex digest. The OCI
-    // spec allows multi-algorithm refs; we narrow on purpose and
-    // surface the unsupported case as a distinct error.
-    let (alg, hex) = d
-        .split_once(':')
-        .ok_or_else(|| OciError::MalformedDigest(format!("missing algorithm prefix: {d:?}")))?;
-    match alg {
-        "sha256" => {}
-        other => {
-            return Err(OciError::UnsupportedDigestAlgorithm(other.to_string()));
-        }
-    }
-    if hex.len() != 64 {
-        return Err(OciError::MalformedDigest(format!(
-            "sha256 digest must be 64 hex chars, got {} in {d:?}",
-            hex.len()
-        )));
-    }
-    if !hex
-        .bytes()
-        .all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase())
-    {
-        return Err(OciError::MalformedDigest(format!(
-            "digest hex must be lowercase ascii: {d:?}"
-        )));
-    }
-    Ok(())
+    // Format: `<algorithm>:<hex>` where `<algorithm>` is one of the
+    // OCI-registered algorithms (sha256, sha512) and `<hex>` is the
+    // canonical lowercase hex digest. Anything else surfaces as a
+    // distinct unsupported-algorithm error.
+    parse_digest(d).map(|_| ())
 }
 
 fn validate_repository(repo: &str) -> Result<(), OciError> {
@@ -350,16 +331,31 @@ mod tests {
     }
 
     #[test]
-    fn digest_must_be_sha256_in_v1() {
-        let err = "alpine@sha512:abc".parse::<ImageReference>().unwrap_err();
+    fn digest_algorithm_must_be_oci_registered() {
+        let err = "alpine@sha384:abc".parse::<ImageReference>().unwrap_err();
         match err {
             OciError::UnsupportedDigestAlgorithm(alg) => {
-                assert_eq!(alg, "sha512");
+                assert_eq!(alg, "sha384");
             }
             other => panic!("expected UnsupportedDigestAlgorithm, got {other:?}"),
         }
     }
 
+    #[test]
+    fn sha512_digest_pin_is_accepted() {
+        let digest = format!("sha512:{}", "c".repeat(128));
+        let r = parse(&format!("ghcr.io/foo/bar@{digest}"));
+        assert_eq!(r.digest.as_deref(), Some(digest.as_str()));
+        assert!(r.is_digest_pinned());
+
+        // sha512 with a sha256-length hex part is malformed, not
+        // silently accepted.
+        let err = format!("alpine@sha512:{}", "c".repeat(64))
+            .parse::<ImageReference>()
+            .unwrap_err();
+        assert!(matches!(err, OciError::MalformedDigest(_)), "got {err:?}");
+    }
+
     #[test]
     fn digest_must_be_64_lowercase_hex_chars() {
         // wrong length
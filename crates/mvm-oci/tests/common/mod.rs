@@ -12,22 +12,25 @@
 //!   pair, with the right `Docker-Content-Digest` header.
 //! - `GET /v2/<repo>/blobs/<digest>` — returns the
 //!   pre-registered blob bytes for a digest.
+//! - `GET /v2/<repo>/referrers/<digest>` — returns a pre-registered
+//!   OCI 1.1 referrers index. Left unregistered, wiremock answers
+//!   404, which is what a registry without the referrers API does.
 //!
 //! The fixture is *not* a complete OCI registry. It does the
 //! happy path plus a couple of error injections (5xx-then-200 for
-//! retry tests, content tamper for digest-mismatch tests). Auth,
-//! manifest upload, and the full v2 catalog API are out of scope —
-//! W1.1/W1.2 ship anonymous-pull-only.
+//! retry tests, content tamper for digest-mismatch tests), plus
+//! bearer-gated manifests and blobs and the token-auth challenge.
+//! Manifest upload and the full v2 catalog API are out of scope.
 
 #![allow(dead_code)] // not every helper is used by every test
 
 use mvm_oci::{ClientConfig, ClientProtocol, ImageReference};
 use oci_client::client::Client as OciClient;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
-use wiremock::matchers::{header, method, path, path_regex};
+use wiremock::matchers::{body_string_contains, header, method, path, path_regex};
 use wiremock::{Mock, MockServer, Respond, ResponseTemplate};
 
 /// A running hermetic registry. Drop the value to tear it down.
@@ -127,6 +130,49 @@ impl HermeticRegistry {
         digest
     }
 
+    /// Serve `bytes` at `/v2/<repository>/manifests/<sha512-digest>`.
+    /// The `Docker-Content-Digest` header carries the sha256 digest,
+    /// as registries advertise their canonical digest whatever the
+    /// client asked for. Returns the sha512 digest.
+    pub async fn register_sha512_manifest(
+        &self,
+        repository: &str,
+        media_type: &str,
+        bytes: &[u8],
+    ) -> String {
+        let sha512 = format!("sha512:{}", hex::encode(Sha512::digest(bytes)));
+        let sha256 = format!("sha256:{}", hex::encode(Sha256::digest(bytes)));
+        let path = format!("/v2/{repository}/manifests/{sha512}");
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(path))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Type", media_type)
+                    .insert_header("Docker-Content-Digest", sha256.as_str())
+                    .set_body_bytes(bytes.to_vec()),
+            )
+            .mount(&self.server)
+            .await;
+        sha512
+    }
+
+    /// Serve an OCI 1.1 referrers index at
+    /// `/v2/<repository>/referrers/<subject_digest>`. The
+    /// `artifactType` query filter is ignored, like registries that
+    /// don't implement it.
+    pub async fn register_referrers(&self, repository: &str, subject_digest: &str, index: &[u8]) {
+        let path = format!("/v2/{repository}/referrers/{subject_digest}");
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(path))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Type", "application/vnd.oci.image.index.v1+json")
+                    .set_body_bytes(index.to_vec()),
+            )
+            .mount(&self.server)
+            .await;
+    }
+
     /// Serve a manifest only when the request includes the given
     /// bearer token. Also registers the digest path with the same
     /// auth requirement.
@@ -156,6 +202,46 @@ impl HermeticRegistry {
         digest
     }
 
+    /// Put the registry behind distribution token auth: `/v2/`
+    /// answers 401 with a bearer challenge whose realm is this
+    /// fixture's `/token`, and `/token` trades `identity_token` (as a
+    /// `refresh_token` grant) for `access_token`. Any other grant is
+    /// refused with a body that echoes the request, the way a careless
+    /// token server might.
+    pub async fn register_token_auth(&self, identity_token: &str, access_token: &str) {
+        Mock::given(method("GET"))
+            .and(path("/v2/"))
+            .respond_with(ResponseTemplate::new(401).insert_header(
+                "WWW-Authenticate",
+                format!(
+                    r#"Bearer realm="http://{}/token",service="hermetic-registry""#,
+                    self.host()
+                ),
+            ))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .and(body_string_contains(format!(
+                "refresh_token={identity_token}"
+            )))
+            .and(body_string_contains("service=hermetic-registry"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "access_token": access_token })),
+            )
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(EchoBody(401))
+            .mount(&self.server)
+            .await;
+    }
+
     /// Serve `bytes` at `/v2/<repository>/blobs/<sha256-digest>`.
     /// Returns the digest the bytes hash to (caller can compare
     /// against the manifest's layer descriptor).
@@ -174,6 +260,28 @@ impl HermeticRegistry {
         digest
     }
 
+    /// Serve `bytes` at `/v2/<repository>/blobs/<sha512-digest>`.
+    /// Returns the sha512 digest.
+    pub async fn register_sha512_blob(
+        &self,
+        repository: &str,
+        media_type: &str,
+        bytes: &[u8],
+    ) -> String {
+        let digest = format!("sha512:{}", hex::encode(Sha512::digest(bytes)));
+        let path = format!("/v2/{repository}/blobs/{digest}");
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(path))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Type", media_type)
+                    .set_body_bytes(bytes.to_vec()),
+            )
+            .mount(&self.server)
+            .await;
+        digest
+    }
+
     /// Serve a blob only when the request includes the given bearer
     /// token.
     pub async fn register_bearer_blob(
@@ -244,6 +352,15 @@ impl HermeticRegistry {
     }
 }
 
+/// Responds with `status` and the request body echoed back.
+struct EchoBody(u16);
+
+impl Respond for EchoBody {
+    fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+        ResponseTemplate::new(self.0).set_body_bytes(request.body.clone())
+    }
+}
+
 /// Build an `oci_client::Client` configured to talk plaintext HTTP
 /// to the fixture's localhost address. Production callers use
 /// `ClientProtocol::Https`; tests need `HttpsExcept` listing the
@@ -281,6 +398,98 @@ pub fn minimal_image_manifest(layer_bytes: &[u8], media_type: &str) -> (Vec<u8>,
     )
 }
 
+/// Builds an OCI 1.1 artifact manifest attached to `subject`, with
+/// the empty config descriptor and one blob. Returns
+/// `(manifest_bytes, blob_digest)`.
+pub fn artifact_manifest(
+    subject_digest: &str,
+    artifact_type: &str,
+    blob_bytes: &[u8],
+    blob_media_type: &str,
+) -> (Vec<u8>, String) {
+    let blob_digest = format!("sha256:{}", hex::encode(Sha256::digest(blob_bytes)));
+    let manifest = serde_json::json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "artifactType": artifact_type,
+        "config": {
+            "mediaType": "application/vnd.oci.empty.v1+json",
+            "digest": "sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+            "size": 2
+        },
+        "layers": [{
+            "mediaType": blob_media_type,
+            "digest": blob_digest.as_str(),
+            "size": blob_bytes.len()
+        }],
+        "subject": {
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "digest": subject_digest,
+            "size": 0
+        },
+        "annotations": { "org.opencontainers.image.created": "2026-01-01T00:00:00Z" }
+    });
+    (
+        serde_json::to_vec(&manifest).expect("artifact manifest serializes"),
+        blob_digest,
+    )
+}
+
+/// Builds the image manifest cosign publishes under the
+/// `sha256-<hex>.sig` tag: one simple-signing layer per
+/// `(payload, signature)` pair, the signature in the layer's
+/// annotation (omitted when `None`). Returns the manifest bytes.
+pub fn cosign_signature_manifest(signatures: &[(&[u8], Option<&str>)]) -> Vec<u8> {
+    let layers: Vec<_> = signatures
+        .iter()
+        .map(|(payload, signature)| {
+            let mut layer = serde_json::json!({
+                "mediaType": "application/vnd.dev.cosign.simplesigning.v1+json",
+                "digest": format!("sha256:{}", hex::encode(Sha256::digest(payload))),
+                "size": payload.len()
+            });
+            if let Some(signature) = signature {
+                layer["annotations"] =
+                    serde_json::json!({ "dev.cosignproject.cosign/signature": signature });
+            }
+            layer
+        })
+        .collect();
+    serde_json::to_vec(&serde_json::json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "config": {
+            "mediaType": "application/vnd.oci.image.config.v1+json",
+            "digest": "sha256:0000000000000000000000000000000000000000000000000000000000000000",
+            "size": 0
+        },
+        "layers": layers
+    }))
+    .expect("signature manifest serializes")
+}
+
+/// Builds a referrers image index listing `(digest, size,
+/// artifact_type)` manifest descriptors.
+pub fn referrers_index(entries: &[(&str, usize, &str)]) -> Vec<u8> {
+    let manifests: Vec<_> = entries
+        .iter()
+        .map(|(digest, size, artifact_type)| {
+            serde_json::json!({
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": digest,
+                "size": size,
+                "artifactType": artifact_type
+            })
+        })
+        .collect();
+    serde_json::to_vec(&serde_json::json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.index.v1+json",
+        "manifests": manifests
+    }))
+    .expect("referrers index serializes")
+}
+
 /// Custom responder that fails the first `fail_first` calls with
 /// 503 then serves a success body. We track call count via
 /// `AtomicU32` because `Respond::respond` takes `&self`.
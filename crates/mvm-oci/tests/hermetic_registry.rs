@@ -7,16 +7,23 @@
 
 mod common;
 
-use common::{HermeticRegistry, client_for, minimal_image_manifest};
+use common::{
+    HermeticRegistry, artifact_manifest, client_for, cosign_signature_manifest,
+    minimal_image_manifest, referrers_index,
+};
 use mvm_oci::{
-    LayerDescriptor, LayerFetchOptions, LinuxPlatform, ManifestFetcher, OciError, OciLayerFetcher,
-    OciManifestFetcher, RegistryAuthConfig, verify_sha256_digest,
+    ClientProtocol, DigestAlgorithm, LayerDescriptor, LayerFetchOptions, LinuxPlatform,
+    ManifestFetcher, OciError, OciLayerFetcher, OciManifestFetcher, RegistryAuthConfig,
+    exchange_identity_token, verify_digest, verify_sha256_digest,
 };
+use secrecy::SecretString;
 use sha2::Digest;
 use std::time::Duration;
 
 const LAYER_MEDIA: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
 const MANIFEST_MEDIA: &str = "application/vnd.oci.image.manifest.v1+json";
+const SIGNATURE_TYPE: &str = "application/vnd.dev.sigstore.bundle.v0.3+json";
+const SBOM_TYPE: &str = "application/spdx+json";
 
 #[tokio::test]
 async fn manifest_fetch_round_trip_against_hermetic_registry() {
@@ -116,6 +123,73 @@ async fn manifest_fetch_rejects_wrong_bearer_auth() {
     assert!(matches!(err, OciError::Registry(_)));
 }
 
+#[tokio::test]
+async fn identity_token_is_exchanged_for_a_bearer_token() {
+    let reg = HermeticRegistry::start().await;
+    reg.register_token_auth("identity-fixture", "access-fixture")
+        .await;
+    let (manifest_bytes, _) = minimal_image_manifest(b"private-layer", LAYER_MEDIA);
+    let manifest_digest = reg
+        .register_bearer_manifest_with_digest_path(
+            "private/app",
+            "v1",
+            MANIFEST_MEDIA,
+            &manifest_bytes,
+            "access-fixture",
+        )
+        .await;
+    let image = reg.image_ref("private/app", "v1");
+
+    let auth = exchange_identity_token(
+        &image,
+        &SecretString::from("identity-fixture"),
+        &ClientProtocol::HttpsExcept(vec![reg.host()]),
+    )
+    .await
+    .expect("token exchange");
+    assert_eq!(auth.kind(), "bearer");
+
+    let fetcher = OciManifestFetcher::with_client_and_auth(client_for(&reg), auth);
+    let fetched = fetcher.fetch(&image).await.expect("manifest fetch");
+    assert_eq!(fetched.digest, manifest_digest);
+}
+
+#[tokio::test]
+async fn refused_identity_token_never_appears_in_the_error() {
+    let reg = HermeticRegistry::start().await;
+    reg.register_token_auth("identity-fixture", "access-fixture")
+        .await;
+    let image = reg.image_ref("private/app", "v1");
+
+    let err = exchange_identity_token(
+        &image,
+        &SecretString::from("stale-identity-token"),
+        &ClientProtocol::HttpsExcept(vec![reg.host()]),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(err, OciError::Auth(_)), "got {err:?}");
+    let shown = format!("{err} {err:?}");
+    assert!(!shown.contains("stale-identity-token"), "leaked: {shown}");
+}
+
+#[tokio::test]
+async fn registry_without_challenge_needs_no_token() {
+    let reg = HermeticRegistry::start().await;
+    let image = reg.image_ref("library/test", "v1");
+
+    let auth = exchange_identity_token(
+        &image,
+        &SecretString::from("identity-fixture"),
+        &ClientProtocol::HttpsExcept(vec![reg.host()]),
+    )
+    .await
+    .expect("no challenge");
+
+    assert!(!auth.is_authenticated());
+}
+
 #[tokio::test]
 async fn manifest_layers_extracts_single_layer() {
     let reg = HermeticRegistry::start().await;
@@ -496,3 +570,341 @@ async fn verify_sha256_digest_works_against_fixture_blob_bytes() {
     let digest = format!("sha256:{}", hex::encode(sha2::Sha256::digest(bytes)));
     verify_sha256_digest(bytes, &digest).expect("self-consistent");
 }
+
+#[tokio::test]
+async fn sha512_pinned_manifest_fetch_verifies_against_the_pin() {
+    let reg = HermeticRegistry::start().await;
+    let (manifest_bytes, _) = minimal_image_manifest(b"sha512-layer", LAYER_MEDIA);
+    let pinned = reg
+        .register_sha512_manifest("library/test", MANIFEST_MEDIA, &manifest_bytes)
+        .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/test", &pinned);
+    let fetched = fetcher.fetch(&image).await.expect("sha512-pinned fetch");
+
+    assert_eq!(fetched.digest, pinned);
+    verify_digest(&fetched.bytes, &pinned).expect("bytes hash to the sha512 pin");
+}
+
+#[tokio::test]
+async fn sha512_pin_that_does_not_match_fails_closed() {
+    let reg = HermeticRegistry::start().await;
+    let (manifest_bytes, _) = minimal_image_manifest(b"served", LAYER_MEDIA);
+    let served = reg
+        .register_sha512_manifest("library/test", MANIFEST_MEDIA, &manifest_bytes)
+        .await;
+    // Ask for the served path but pin a different sha512.
+    let (other_bytes, _) = minimal_image_manifest(b"expected", LAYER_MEDIA);
+    let wrong_pin = DigestAlgorithm::Sha512.digest(&other_bytes);
+    assert_ne!(served, wrong_pin);
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/test", &wrong_pin);
+    let err = fetcher.fetch(&image).await.unwrap_err();
+
+    // The registry has nothing at the wrong pin's path, or (if it
+    // did) the content check rejects it. Either way: no bytes.
+    assert!(
+        matches!(err, OciError::DigestMismatch { .. } | OciError::Registry(_)),
+        "got {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn layer_fetch_verifies_sha512_blob() {
+    let reg = HermeticRegistry::start().await;
+    let blob = b"sha512-addressed-blob".to_vec();
+    let digest = reg
+        .register_sha512_blob("library/test", LAYER_MEDIA, &blob)
+        .await;
+    assert!(digest.starts_with("sha512:"));
+
+    let layer = LayerDescriptor {
+        digest,
+        size: blob.len() as u64,
+        media_type: LAYER_MEDIA.to_string(),
+    };
+    let fetcher = OciLayerFetcher::with_client(client_for(&reg), LayerFetchOptions::default());
+    let image = reg.image_ref("library/test", "v1");
+    let mut sink: Vec<u8> = Vec::new();
+    let n = fetcher
+        .fetch_layer(&image, &layer, &mut sink)
+        .await
+        .expect("sha512 layer fetch");
+
+    assert_eq!(n, blob.len() as u64);
+    assert_eq!(sink, blob);
+}
+
+/// Registers an image manifest plus a signature and an SBOM
+/// attached to it. Returns `(subject_digest, sbom_bytes,
+/// referrer_index_entries)`.
+async fn image_with_signature_and_sbom(
+    reg: &HermeticRegistry,
+    repository: &str,
+) -> (String, Vec<u8>, Vec<u8>) {
+    let (image_bytes, _) = minimal_image_manifest(b"app-layer", LAYER_MEDIA);
+    let subject = reg
+        .register_manifest_with_digest_path(repository, "v1", MANIFEST_MEDIA, &image_bytes)
+        .await;
+
+    let sbom = br#"{"spdxVersion":"SPDX-2.3"}"#.to_vec();
+    reg.register_blob(repository, SBOM_TYPE, &sbom).await;
+    let (sbom_manifest, _) = artifact_manifest(&subject, SBOM_TYPE, &sbom, SBOM_TYPE);
+    let sbom_digest = reg
+        .register_manifest_with_digest_path(repository, "sbom", MANIFEST_MEDIA, &sbom_manifest)
+        .await;
+
+    let (sig_manifest, _) = artifact_manifest(&subject, SIGNATURE_TYPE, b"sig", SIGNATURE_TYPE);
+    let sig_digest = reg
+        .register_manifest_with_digest_path(repository, "sig", MANIFEST_MEDIA, &sig_manifest)
+        .await;
+
+    let index = referrers_index(&[
+        (sig_digest.as_str(), sig_manifest.len(), SIGNATURE_TYPE),
+        (sbom_digest.as_str(), sbom_manifest.len(), SBOM_TYPE),
+    ]);
+    (subject, sbom, index)
+}
+
+#[tokio::test]
+async fn referrers_api_discovers_and_fetches_attached_artifacts() {
+    let reg = HermeticRegistry::start().await;
+    let (subject, sbom, index) = image_with_signature_and_sbom(&reg, "library/app").await;
+    reg.register_referrers("library/app", &subject, &index)
+        .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/app", &subject);
+    let artifacts = fetcher
+        .fetch_referrers(&image, None)
+        .await
+        .expect("referrers");
+
+    let types: Vec<&str> = artifacts.iter().map(|a| a.artifact_type.as_str()).collect();
+    assert_eq!(types, vec![SIGNATURE_TYPE, SBOM_TYPE]);
+    for artifact in &artifacts {
+        assert_eq!(artifact.subject.as_deref(), Some(subject.as_str()));
+        verify_digest(&artifact.manifest.bytes, &artifact.manifest.digest)
+            .expect("referrer manifest verified");
+    }
+
+    // The SBOM blob comes down through the layer fetcher with the
+    // usual digest check.
+    let sbom_artifact = &artifacts[1];
+    assert_eq!(sbom_artifact.blobs.len(), 1);
+    let layer_fetcher =
+        OciLayerFetcher::from_manifest_fetcher(&fetcher, LayerFetchOptions::default());
+    let mut sink: Vec<u8> = Vec::new();
+    layer_fetcher
+        .fetch_layer(&image, &sbom_artifact.blobs[0], &mut sink)
+        .await
+        .expect("sbom blob");
+    assert_eq!(sink, sbom);
+}
+
+#[tokio::test]
+async fn referrers_artifact_type_filter_is_applied_client_side() {
+    let reg = HermeticRegistry::start().await;
+    let (subject, _, index) = image_with_signature_and_sbom(&reg, "library/app").await;
+    // The fixture ignores `?artifactType=`, so both come back.
+    reg.register_referrers("library/app", &subject, &index)
+        .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/app", &subject);
+    let artifacts = fetcher
+        .fetch_referrers(&image, Some(SBOM_TYPE))
+        .await
+        .expect("referrers");
+
+    assert_eq!(artifacts.len(), 1);
+    assert_eq!(artifacts[0].artifact_type, SBOM_TYPE);
+}
+
+#[tokio::test]
+async fn referrers_fall_back_to_tag_schema_without_referrers_api() {
+    let reg = HermeticRegistry::start().await;
+    let (subject, _, index) = image_with_signature_and_sbom(&reg, "library/legacy").await;
+    // No referrers route: the API answers 404. Publish the index
+    // under the `<alg>-<hex>` tag instead.
+    let tag = subject.replace(':', "-");
+    reg.register_manifest(
+        "library/legacy",
+        &tag,
+        "application/vnd.oci.image.index.v1+json",
+        &index,
+    )
+    .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/legacy", &subject);
+    let artifacts = fetcher
+        .fetch_referrers(&image, Some(SIGNATURE_TYPE))
+        .await
+        .expect("tag-schema referrers");
+
+    assert_eq!(artifacts.len(), 1);
+    assert_eq!(artifacts[0].artifact_type, SIGNATURE_TYPE);
+}
+
+#[tokio::test]
+async fn referrers_are_empty_when_neither_api_nor_tag_exists() {
+    let reg = HermeticRegistry::start().await;
+    let (image_bytes, _) = minimal_image_manifest(b"lonely", LAYER_MEDIA);
+    let subject = reg
+        .register_manifest_with_digest_path("library/lonely", "v1", MANIFEST_MEDIA, &image_bytes)
+        .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/lonely", &subject);
+    let artifacts = fetcher.fetch_referrers(&image, None).await.expect("empty");
+
+    assert!(artifacts.is_empty());
+}
+
+#[tokio::test]
+async fn referrer_naming_a_different_subject_is_rejected() {
+    let reg = HermeticRegistry::start().await;
+    let (image_bytes, _) = minimal_image_manifest(b"real-image", LAYER_MEDIA);
+    let subject = reg
+        .register_manifest_with_digest_path("library/app", "v1", MANIFEST_MEDIA, &image_bytes)
+        .await;
+    let other = format!("sha256:{}", "e".repeat(64));
+    let (foreign, _) = artifact_manifest(&other, SIGNATURE_TYPE, b"sig", SIGNATURE_TYPE);
+    let foreign_digest = reg
+        .register_manifest_with_digest_path("library/app", "foreign", MANIFEST_MEDIA, &foreign)
+        .await;
+    let index = referrers_index(&[(foreign_digest.as_str(), foreign.len(), SIGNATURE_TYPE)]);
+    reg.register_referrers("library/app", &subject, &index)
+        .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let image = reg.image_ref_by_digest("library/app", &subject);
+    let err = fetcher.fetch_referrers(&image, None).await.unwrap_err();
+
+    assert!(matches!(err, OciError::Registry(_)), "got {err:?}");
+}
+
+#[tokio::test]
+async fn referrers_require_a_digest_pinned_subject() {
+    let reg = HermeticRegistry::start().await;
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let err = fetcher
+        .fetch_referrers(&reg.image_ref("library/app", "v1"), None)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OciError::InvalidReference(_)), "got {err:?}");
+}
+
+const SIMPLE_SIGNING_MEDIA: &str = "application/vnd.dev.cosign.simplesigning.v1+json";
+
+/// Registers an unsigned image and returns its digest.
+async fn plain_image(reg: &HermeticRegistry, repository: &str) -> String {
+    let (image_bytes, _) = minimal_image_manifest(b"signed-app", LAYER_MEDIA);
+    reg.register_manifest_with_digest_path(repository, "v1", MANIFEST_MEDIA, &image_bytes)
+        .await
+}
+
+#[tokio::test]
+async fn cosign_signature_tag_payloads_are_collected() {
+    let reg = HermeticRegistry::start().await;
+    let subject = plain_image(&reg, "library/signed").await;
+    let payload = br#"{"critical":{"type":"cosign container image signature"}}"#;
+    reg.register_blob("library/signed", SIMPLE_SIGNING_MEDIA, payload)
+        .await;
+    let sig_manifest = cosign_signature_manifest(&[(payload, Some("MEUCIQ=="))]);
+    reg.register_manifest(
+        "library/signed",
+        &format!("{}.sig", subject.replace(':', "-")),
+        MANIFEST_MEDIA,
+        &sig_manifest,
+    )
+    .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let signatures = fetcher
+        .fetch_cosign_signatures(&reg.image_ref_by_digest("library/signed", &subject))
+        .await
+        .expect("signatures");
+
+    assert_eq!(signatures.simple_signing.len(), 1);
+    assert_eq!(signatures.simple_signing[0].payload, payload);
+    assert_eq!(signatures.simple_signing[0].signature, "MEUCIQ==");
+    assert!(signatures.bundles.is_empty());
+}
+
+#[tokio::test]
+async fn cosign_bundles_are_collected_from_referrers() {
+    let reg = HermeticRegistry::start().await;
+    let subject = plain_image(&reg, "library/bundled").await;
+    let bundle = br#"{"mediaType":"application/vnd.dev.sigstore.bundle.v0.3+json"}"#;
+    reg.register_blob("library/bundled", SIGNATURE_TYPE, bundle)
+        .await;
+    let (bundle_manifest, _) = artifact_manifest(&subject, SIGNATURE_TYPE, bundle, SIGNATURE_TYPE);
+    let bundle_digest = reg
+        .register_manifest_with_digest_path(
+            "library/bundled",
+            "bundle",
+            MANIFEST_MEDIA,
+            &bundle_manifest,
+        )
+        .await;
+    let index = referrers_index(&[(
+        bundle_digest.as_str(),
+        bundle_manifest.len(),
+        SIGNATURE_TYPE,
+    )]);
+    reg.register_referrers("library/bundled", &subject, &index)
+        .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let signatures = fetcher
+        .fetch_cosign_signatures(&reg.image_ref_by_digest("library/bundled", &subject))
+        .await
+        .expect("signatures");
+
+    assert!(signatures.simple_signing.is_empty());
+    assert_eq!(signatures.bundles, vec![bundle.to_vec()]);
+}
+
+#[tokio::test]
+async fn unsigned_image_yields_no_cosign_signatures() {
+    let reg = HermeticRegistry::start().await;
+    let subject = plain_image(&reg, "library/unsigned").await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let signatures = fetcher
+        .fetch_cosign_signatures(&reg.image_ref_by_digest("library/unsigned", &subject))
+        .await
+        .expect("lookup succeeds");
+
+    assert!(signatures.is_empty());
+}
+
+#[tokio::test]
+async fn cosign_signature_layer_without_annotation_is_rejected() {
+    let reg = HermeticRegistry::start().await;
+    let subject = plain_image(&reg, "library/broken").await;
+    let payload = b"payload";
+    reg.register_blob("library/broken", SIMPLE_SIGNING_MEDIA, payload)
+        .await;
+    let sig_manifest = cosign_signature_manifest(&[(payload, None)]);
+    reg.register_manifest(
+        "library/broken",
+        &format!("{}.sig", subject.replace(':', "-")),
+        MANIFEST_MEDIA,
+        &sig_manifest,
+    )
+    .await;
+
+    let fetcher = OciManifestFetcher::with_client(client_for(&reg));
+    let err = fetcher
+        .fetch_cosign_signatures(&reg.image_ref_by_digest("library/broken", &subject))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OciError::Registry(_)), "got {err:?}");
+}
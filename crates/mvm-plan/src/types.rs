@@ -43,8 +43,10 @@ pub struct SignedImageRef {
     pub name: String,
     /// Lowercase hex SHA-256.
     pub sha256: String,
-    /// Cosign-keyless `.bundle` reference. Path on disk or URL
-    /// resolvable by the supervisor. Stub in dev.
+    /// Path to the cosign signature material for the image (for OCI
+    /// images, the signature record `mvmctl image pull --prod`
+    /// caches). Required for prod plans, which admission refuses
+    /// unsigned; stub in dev.
     pub cosign_bundle: Option<String>,
 }
 
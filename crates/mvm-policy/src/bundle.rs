@@ -2,11 +2,13 @@
 
 use std::collections::BTreeMap;
 
+use mvm_core::security::FsQuotaPolicy;
 use mvm_plan::TenantId;
 use serde::{Deserialize, Serialize};
 
 use crate::policies::{
-    ArtifactPolicy, AuditPolicy, EgressPolicy, KeyPolicy, NetworkPolicy, PiiPolicy, ToolPolicy,
+    ArtifactPolicy, AuditPolicy, EgressPolicy, KeyPolicy, NetworkPolicy, PiiPolicy, ThreatPolicy,
+    ToolPolicy,
 };
 
 /// Wire-format version. Same fail-closed semantics as
@@ -39,6 +41,16 @@ pub struct PolicyBundle {
     pub artifact: ArtifactPolicy,
     pub keys: KeyPolicy,
     pub audit: AuditPolicy,
+    /// Threat-classifier actions. Optional in the wire format and
+    /// omitted when unset, so bundles signed before it existed keep
+    /// their signatures.
+    #[serde(default, skip_serializing_if = "ThreatPolicy::is_unset")]
+    pub threat: ThreatPolicy,
+    /// Filesystem RPC quota for the host-side per-VM meter. `None`
+    /// takes `FsQuotaPolicy::default()`; omitted when unset for the
+    /// same signature-stability reason as `threat`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_quota: Option<FsQuotaPolicy>,
 
     /// Per-tenant overlays. Resolved by composing the bundle's
     /// base policy with the matching tenant overlay (overlay wins
@@ -59,4 +71,8 @@ pub struct TenantOverlay {
     pub artifact: Option<ArtifactPolicy>,
     pub keys: Option<KeyPolicy>,
     pub audit: Option<AuditPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threat: Option<ThreatPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_quota: Option<FsQuotaPolicy>,
 }
@@ -1,6 +1,6 @@
 //! mvm-policy — signed `PolicyBundle` carrying network, egress, PII,
-//! tool, artifact, key, and audit policies referenced by an
-//! `ExecutionPlan`.
+//! tool, artifact, key, audit, threat, and FS quota policies
+//! referenced by an `ExecutionPlan`.
 //!
 //! Plan 37 §10. Every executable artifact (image, kernel, policy
 //! bundle) is signed and verified at admission. Plan 36 closed the
@@ -29,7 +29,7 @@ pub mod toml_loader;
 pub use bundle::{PolicyBundle, PolicyId, SCHEMA_VERSION, TenantOverlay};
 pub use policies::{
     ArtifactPolicy, AuditPolicy, DEFAULT_BODY_CAP_BYTES, EgressPolicy, KeyPolicy, L4RuleSpec,
-    NetworkPolicy, PiiPolicy, ToolPolicy,
+    NetworkPolicy, PiiPolicy, ThreatPolicy, ToolPolicy,
 };
 pub use resolver::{EffectivePolicy, EmergencyDeny, resolve};
 pub use signing::{BundleVerifyError, SignedPolicyBundle, sign_bundle, verify_bundle};
@@ -17,6 +17,10 @@
 //! `Option<T>` semantics ("None inherits from base") compose
 //! cleanly with the bundle's resolution algorithm.
 
+use std::collections::BTreeMap;
+
+use mvm_core::policy::egress_rule::{AllowRule, PortRange, RuleError};
+use mvm_core::security::{BlocklistAction, ThreatCategory};
 use serde::{Deserialize, Serialize};
 
 /// Network policy. Plan 60 Phase 3 Slice B fills `l4` — the L4
@@ -93,16 +97,24 @@ pub struct L4RuleSpec {
 
 /// L7 egress policy. Plan 37 §15 differentiator. Wave 2.6 fills the
 /// fields the `L7EgressProxy` actually consumes:
-/// - `allow_list` is the (host, port) destination policy.
+/// - `allow_list` is the (host, port) destination policy. Hosts may
+///   be exact names, `*.example.com` (subdomains only) or
+///   `.example.com` (apex and subdomains); see
+///   [`mvm_core::policy::egress_rule`] for the grammar.
+/// - `allow_port_ranges` extends it with `(host, lo, hi)` entries
+///   for destinations that listen on a port range.
 /// - `allow_plain_http` opens the plain-HTTP code path; **the
 ///   supervisor refuses to honour `true` for `Variant::Prod`** so
 ///   production workloads can never accidentally egress unencrypted.
-/// - `body_cap_bytes` bounds the body read for plain-HTTP. `0` means
-///   "use default" ([`DEFAULT_BODY_CAP_BYTES`], 16 MiB) — matches
-///   AI-provider request sizes (long contexts + image uploads).
+/// - `body_cap_bytes` bounds the body read for plain-HTTP and
+///   intercepted HTTPS. `0` means "use default"
+///   ([`DEFAULT_BODY_CAP_BYTES`], 16 MiB) — matches AI-provider
+///   request sizes (long contexts + image uploads).
 /// - `disabled_inspectors` lets operators turn off specific
 ///   inspectors by name (e.g., disable `pii_redactor` for an
 ///   analytics workload that scrubs upstream).
+/// - `tls_intercept` (Wave 2.6.5) opts into terminating HTTPS so the
+///   body inspectors see decrypted requests.
 ///
 /// `mode` is retained for compatibility with Wave 1's stub; the
 /// supervisor honours `mode = Some("open")` as a kill-switch that
@@ -115,16 +127,23 @@ pub struct EgressPolicy {
     pub mode: Option<String>,
     /// (host, port) allowlist consumed by `DestinationPolicy`.
     /// `port = 0` is the explicit "any port for this host" wildcard.
+    /// `host` may be a wildcard or suffix pattern.
     #[serde(default)]
     pub allow_list: Vec<(String, u16)>,
+    /// (host, lo, hi) entries allowing the inclusive port range
+    /// `lo..=hi`; same host grammar as `allow_list`. Omitted from the
+    /// serialised form when empty so existing bundle signatures are
+    /// unaffected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_port_ranges: Vec<(String, u16, u16)>,
     /// Whether plain HTTP (not just CONNECT/HTTPS) is permitted.
     /// **Forbidden for `Variant::Prod`** — the supervisor's
     /// `with_l7_egress` builder rejects this combination at policy
     /// load.
     #[serde(default)]
     pub allow_plain_http: bool,
-    /// Body read cap for plain-HTTP (bytes). `0` means "use default"
-    /// ([`DEFAULT_BODY_CAP_BYTES`]).
+    /// Body read cap for plain-HTTP and intercepted HTTPS (bytes).
+    /// `0` means "use default" ([`DEFAULT_BODY_CAP_BYTES`]).
     #[serde(default)]
     pub body_cap_bytes: u64,
     /// Per-name inspector opt-out. Empty == every inspector enabled.
@@ -133,6 +152,55 @@ pub struct EgressPolicy {
     /// `pii_redactor`.
     #[serde(default)]
     pub disabled_inspectors: Vec<String>,
+    /// Terminate HTTPS CONNECT tunnels so the inspector chain runs on
+    /// decrypted requests (ADR-006). The supervisor mints a per-VM CA
+    /// name-constrained to the hostnames in `allow_list` and stages it
+    /// into the guest trust store. Off by default; omitted from the
+    /// serialised form when off so existing bundle signatures are
+    /// unaffected.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tls_intercept: bool,
+    /// Total payload bytes (both directions) the VM may move through
+    /// the proxy over its lifetime. `None` = uncapped. Once spent, new
+    /// tunnels are refused and live ones cut, each audited as
+    /// `byte_cap`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_cap_bytes: Option<u64>,
+    /// Sustained egress throughput for the whole VM, with one second
+    /// of burst. Excess traffic is delayed, not dropped. `None` =
+    /// unthrottled; `Some(0)` is rejected at policy load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bandwidth_bytes_per_sec: Option<u64>,
+}
+
+impl EgressPolicy {
+    /// Every allow-list entry — `allow_list` then `allow_port_ranges`
+    /// — as a validated [`AllowRule`]. Fails on the first pattern or
+    /// range that doesn't parse; the loader and the supervisor both
+    /// call this so a bad entry is refused rather than silently never
+    /// matching.
+    pub fn allow_rules(&self) -> Result<Vec<AllowRule>, RuleError> {
+        let tuples = self
+            .allow_list
+            .iter()
+            .map(|(host, port)| AllowRule::from_tuple(host, *port));
+        let ranges = self
+            .allow_port_ranges
+            .iter()
+            .map(|(host, lo, hi)| AllowRule::new(host, PortRange::new(*lo, *hi)?));
+        tuples.chain(ranges).collect()
+    }
+
+    /// Reject byte limits no enforcer can honour. `bandwidth_bytes_per_sec
+    /// = 0` would stall every tunnel rather than deny it. Called by the
+    /// loader and by `Supervisor::with_l7_egress`.
+    pub fn check_limits(&self) -> Result<(), &'static str> {
+        if self.bandwidth_bytes_per_sec == Some(0) {
+            return Err("bandwidth_bytes_per_sec = 0 would stall all egress; \
+                        use an empty allow_list to deny instead");
+        }
+        Ok(())
+    }
 }
 
 /// Default body cap when `EgressPolicy::body_cap_bytes` is 0.
@@ -162,6 +230,49 @@ pub struct ToolPolicy {
     pub allowed: Vec<String>,
 }
 
+/// Threat-classifier gate. Maps each `ThreatCategory` the
+/// `mvm-security` classifier reports to what the supervisor does with
+/// a match on `Exec`/`ProcStart` argv, `RunEntrypoint` stdin and
+/// host-mediated tool params: `Log`, `Block`, or `RequireApproval`.
+///
+/// ```toml
+/// [threat]
+/// default_action = "Log"
+///
+/// [threat.categories]
+/// Destructive = "Block"
+/// SecretExposure = "RequireApproval"
+/// ```
+///
+/// Categories without an entry take `default_action`, and an unset
+/// `default_action` means `Log` — a bundle without `[threat]` audits
+/// every finding and blocks nothing. Omitted from the serialised
+/// bundle when unset so existing bundle signatures are unaffected.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ThreatPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_action: Option<BlocklistAction>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub categories: BTreeMap<ThreatCategory, BlocklistAction>,
+}
+
+impl ThreatPolicy {
+    /// True when neither field is set, i.e. log-only.
+    pub fn is_unset(&self) -> bool {
+        self.default_action.is_none() && self.categories.is_empty()
+    }
+
+    /// Action for a finding in `category`.
+    pub fn action_for(&self, category: &ThreatCategory) -> BlocklistAction {
+        self.categories
+            .get(category)
+            .or(self.default_action.as_ref())
+            .cloned()
+            .unwrap_or(BlocklistAction::Log)
+    }
+}
+
 /// Artifact policy. Distinct from `mvm-plan::ArtifactPolicy` —
 /// the plan field is a per-run snapshot; this is the bundle-side
 /// source of truth that the supervisor's `ArtifactCollector` (Wave 3)
@@ -238,3 +349,173 @@ port_hi  = 443
         assert!(p.observers.is_empty());
     }
 }
+
+#[cfg(test)]
+mod tls_intercept_tests {
+    use super::*;
+
+    #[test]
+    fn egress_policy_tls_intercept_defaults_off_and_is_omitted() {
+        let p: EgressPolicy = toml::from_str(r#"allow_list = [["api.example.com", 443]]"#)
+            .expect("parse bundle without tls_intercept");
+        assert!(!p.tls_intercept);
+        let json = serde_json::to_string(&p).unwrap();
+        assert!(!json.contains("tls_intercept"));
+    }
+
+    #[test]
+    fn egress_policy_parses_tls_intercept() {
+        let p: EgressPolicy = toml::from_str(
+            r#"
+allow_list = [["api.example.com", 443]]
+tls_intercept = true
+"#,
+        )
+        .expect("parse");
+        assert!(p.tls_intercept);
+        let json = serde_json::to_string(&p).unwrap();
+        assert!(json.contains(r#""tls_intercept":true"#));
+    }
+}
+
+#[cfg(test)]
+mod egress_budget_tests {
+    use super::*;
+
+    #[test]
+    fn egress_policy_byte_limits_default_unset_and_are_omitted() {
+        let p: EgressPolicy = toml::from_str(r#"allow_list = [["api.example.com", 443]]"#)
+            .expect("parse bundle without byte limits");
+        assert_eq!(p.byte_cap_bytes, None);
+        assert_eq!(p.bandwidth_bytes_per_sec, None);
+        let json = serde_json::to_string(&p).unwrap();
+        assert!(!json.contains("byte_cap_bytes"));
+        assert!(!json.contains("bandwidth_bytes_per_sec"));
+    }
+
+    #[test]
+    fn egress_policy_parses_byte_limits() {
+        let p: EgressPolicy = toml::from_str(
+            r#"
+allow_list = [["api.example.com", 443]]
+byte_cap_bytes = 1073741824
+bandwidth_bytes_per_sec = 1048576
+"#,
+        )
+        .expect("parse");
+        assert_eq!(p.byte_cap_bytes, Some(1 << 30));
+        assert_eq!(p.bandwidth_bytes_per_sec, Some(1 << 20));
+        assert!(p.check_limits().is_ok());
+    }
+
+    #[test]
+    fn zero_bandwidth_fails_limit_check() {
+        let p: EgressPolicy = toml::from_str("bandwidth_bytes_per_sec = 0").expect("parse");
+        assert!(p.check_limits().unwrap_err().contains("stall"));
+    }
+}
+
+#[cfg(test)]
+mod egress_rule_tests {
+    use super::*;
+
+    #[test]
+    fn allow_rules_cover_patterns_and_port_ranges() {
+        let p: EgressPolicy = toml::from_str(
+            r#"
+allow_list = [["*.githubusercontent.com", 443], [".example.com", 0]]
+allow_port_ranges = [["api.example.com", 8000, 8100]]
+"#,
+        )
+        .expect("parse");
+        let rules: Vec<String> = p
+            .allow_rules()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                "*.githubusercontent.com:443",
+                ".example.com:*",
+                "api.example.com:8000-8100"
+            ]
+        );
+    }
+
+    #[test]
+    fn allow_rules_refuse_bad_entries() {
+        let tld = EgressPolicy {
+            allow_list: vec![("*.com".to_string(), 443)],
+            ..Default::default()
+        };
+        assert!(tld.allow_rules().is_err());
+        let inverted = EgressPolicy {
+            allow_port_ranges: vec![("api.example.com".to_string(), 9000, 8000)],
+            ..Default::default()
+        };
+        assert!(inverted.allow_rules().is_err());
+    }
+
+    #[test]
+    fn empty_port_ranges_are_omitted_from_the_serialised_form() {
+        let p: EgressPolicy = toml::from_str(r#"allow_list = [["api.example.com", 443]]"#)
+            .expect("parse bundle without port ranges");
+        let json = serde_json::to_string(&p).unwrap();
+        assert!(!json.contains("allow_port_ranges"));
+    }
+}
+
+#[cfg(test)]
+mod threat_policy_tests {
+    use super::*;
+
+    #[test]
+    fn unset_threat_policy_logs_everything() {
+        let p = ThreatPolicy::default();
+        assert!(p.is_unset());
+        assert_eq!(
+            p.action_for(&ThreatCategory::Destructive),
+            BlocklistAction::Log
+        );
+    }
+
+    #[test]
+    fn threat_policy_parses_per_category_actions() {
+        let p: ThreatPolicy = toml::from_str(
+            r#"
+default_action = "RequireApproval"
+
+[categories]
+Destructive = "Block"
+NetworkAbuse = "Log"
+"#,
+        )
+        .expect("parse");
+        assert_eq!(
+            p.action_for(&ThreatCategory::Destructive),
+            BlocklistAction::Block
+        );
+        assert_eq!(
+            p.action_for(&ThreatCategory::NetworkAbuse),
+            BlocklistAction::Log
+        );
+        assert_eq!(
+            p.action_for(&ThreatCategory::SecretExposure),
+            BlocklistAction::RequireApproval
+        );
+    }
+
+    #[test]
+    fn threat_policy_rejects_unknown_categories() {
+        let err = toml::from_str::<ThreatPolicy>(
+            r#"
+[categories]
+Telepathy = "Block"
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Telepathy"), "{err}");
+    }
+}
@@ -35,8 +35,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::bundle::PolicyBundle;
 use crate::policies::{
-    ArtifactPolicy, AuditPolicy, EgressPolicy, KeyPolicy, NetworkPolicy, PiiPolicy, ToolPolicy,
+    ArtifactPolicy, AuditPolicy, EgressPolicy, KeyPolicy, NetworkPolicy, PiiPolicy, ThreatPolicy,
+    ToolPolicy,
 };
+use mvm_core::security::FsQuotaPolicy;
 use mvm_plan::TenantId;
 
 /// An out-of-band deny instruction with bounded lifetime. Plan 37
@@ -93,6 +95,10 @@ pub struct EffectivePolicy {
     pub artifact: ArtifactPolicy,
     pub keys: KeyPolicy,
     pub audit: AuditPolicy,
+    #[serde(default, skip_serializing_if = "ThreatPolicy::is_unset")]
+    pub threat: ThreatPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_quota: Option<FsQuotaPolicy>,
 }
 
 /// Resolve `bundle` for `tenant` at `now`, with `emergency` applied
@@ -119,6 +125,8 @@ pub fn resolve(
         artifact: pick(overlay.and_then(|o| o.artifact.clone()), &bundle.artifact),
         keys: pick(overlay.and_then(|o| o.keys.clone()), &bundle.keys),
         audit: pick(overlay.and_then(|o| o.audit.clone()), &bundle.audit),
+        threat: pick(overlay.and_then(|o| o.threat.clone()), &bundle.threat),
+        fs_quota: overlay.and_then(|o| o.fs_quota).or(bundle.fs_quota),
     };
 
     if emergency.is_active(now) {
@@ -144,6 +152,7 @@ mod tests {
     use super::*;
     use crate::bundle::{PolicyBundle, PolicyId, SCHEMA_VERSION, TenantOverlay};
     use chrono::TimeZone;
+    use mvm_core::security::BlocklistAction;
     use std::collections::BTreeMap;
 
     fn base_bundle() -> PolicyBundle {
@@ -158,9 +167,13 @@ mod tests {
             egress: EgressPolicy {
                 mode: Some("base-egress".to_string()),
                 allow_list: vec![],
+                allow_port_ranges: vec![],
                 allow_plain_http: false,
                 body_cap_bytes: 0,
                 disabled_inspectors: vec![],
+                tls_intercept: false,
+                byte_cap_bytes: None,
+                bandwidth_bytes_per_sec: None,
             },
             pii: PiiPolicy {
                 mode: Some("detect".to_string()),
@@ -184,6 +197,8 @@ mod tests {
                 chain_signing: true,
                 stream_destinations: vec!["audit://base".to_string()],
             },
+            threat: Default::default(),
+            fs_quota: None,
             tenant_overlays: BTreeMap::new(),
         }
     }
@@ -261,6 +276,60 @@ mod tests {
         assert_eq!(eff.audit, b.audit);
     }
 
+    #[test]
+    fn overlay_threat_replaces_base_threat() {
+        let mut b = base_bundle();
+        b.threat.default_action = Some(BlocklistAction::Log);
+        let overlay_threat = ThreatPolicy {
+            default_action: Some(BlocklistAction::Block),
+            ..Default::default()
+        };
+        b.tenant_overlays.insert(
+            tenant_a(),
+            TenantOverlay {
+                threat: Some(overlay_threat.clone()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            resolve(&b, &tenant_a(), now(), &empty_emergency()).threat,
+            overlay_threat
+        );
+        assert_eq!(
+            resolve(&b, &tenant_b(), now(), &empty_emergency()).threat,
+            b.threat
+        );
+    }
+
+    #[test]
+    fn overlay_fs_quota_replaces_base_fs_quota() {
+        let mut b = base_bundle();
+        assert_eq!(
+            resolve(&b, &tenant_a(), now(), &empty_emergency()).fs_quota,
+            None
+        );
+        b.fs_quota = Some(FsQuotaPolicy::default());
+        let overlay_quota = FsQuotaPolicy {
+            session_bytes: 1 << 20,
+            ..FsQuotaPolicy::default()
+        };
+        b.tenant_overlays.insert(
+            tenant_a(),
+            TenantOverlay {
+                fs_quota: Some(overlay_quota),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            resolve(&b, &tenant_a(), now(), &empty_emergency()).fs_quota,
+            Some(overlay_quota)
+        );
+        assert_eq!(
+            resolve(&b, &tenant_b(), now(), &empty_emergency()).fs_quota,
+            b.fs_quota
+        );
+    }
+
     #[test]
     fn overlay_for_different_tenant_does_not_apply() {
         let mut b = base_bundle();
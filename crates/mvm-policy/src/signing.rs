@@ -120,6 +120,8 @@ mod tests {
                 chain_signing: true,
                 stream_destinations: vec!["audit://tenant-a".to_string()],
             },
+            threat: Default::default(),
+            fs_quota: None,
             tenant_overlays: BTreeMap::from([(
                 TenantId("tenant-a".to_string()),
                 TenantOverlay {
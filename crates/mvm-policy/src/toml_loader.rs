@@ -21,7 +21,8 @@
 //!
 //! [egress]
 //! mode = "default"
-//! allow_list = [["api.example.com", 443]]
+//! allow_list = [["api.example.com", 443], ["*.githubusercontent.com", 443]]
+//! allow_port_ranges = [["grpc.example.com", 50051, 50059]]
 //! allow_plain_http = false
 //!
 //! [pii]
@@ -42,6 +43,16 @@
 //! chain_signing = true
 //! stream_destinations = ["file:///var/log/mvm/audit.jsonl"]
 //!
+//! [threat]            # optional; log-only when absent
+//! default_action = "Log"
+//! categories = { Destructive = "Block", SecretExposure = "RequireApproval" }
+//!
+//! [fs_quota]          # optional; built-in defaults when absent, 0 = unlimited
+//! ops_per_second = 200
+//! read_bytes_per_second = 67108864
+//! write_bytes_per_second = 67108864
+//! session_bytes = 1073741824
+//!
 //! [tenant_overlays]   # optional; empty by default
 //! ```
 //!
@@ -85,6 +96,10 @@ pub enum LoadError {
          understands version {known}"
     )]
     SchemaMismatch { path: PathBuf, got: u32, known: u32 },
+    #[error("policy bundle {path:?} has an invalid egress allow-list entry: {detail}")]
+    InvalidAllowList { path: PathBuf, detail: String },
+    #[error("policy bundle {path:?} has an invalid egress limit: {detail}")]
+    InvalidEgressLimit { path: PathBuf, detail: String },
 }
 
 /// Default directory the loader searches: `~/.mvm/policies/`.
@@ -116,6 +131,7 @@ pub fn parse_bundle(toml_text: &str) -> Result<PolicyBundle, LoadError> {
             known: SCHEMA_VERSION,
         });
     }
+    validate_egress(&bundle, Path::new("<in-memory>"))?;
     Ok(bundle)
 }
 
@@ -147,13 +163,37 @@ pub fn load_bundle_from_path(
             known: SCHEMA_VERSION,
         });
     }
+    validate_egress(&bundle, &path)?;
     Ok(bundle)
 }
 
+/// Refuse bundles whose `[egress]` allow-list holds a pattern or port
+/// range the enforcers can't parse (`*.com`, `9000-8000`, ...), so a
+/// typo surfaces at load rather than as a rule that never matches.
+/// Limits no enforcer can honour (`bandwidth_bytes_per_sec = 0`) are
+/// refused here too.
+fn validate_egress(bundle: &PolicyBundle, path: &Path) -> Result<(), LoadError> {
+    bundle
+        .egress
+        .allow_rules()
+        .map_err(|e| LoadError::InvalidAllowList {
+            path: path.to_path_buf(),
+            detail: e.to_string(),
+        })?;
+    bundle
+        .egress
+        .check_limits()
+        .map_err(|detail| LoadError::InvalidEgressLimit {
+            path: path.to_path_buf(),
+            detail: detail.to_string(),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::PolicyId;
+    use mvm_core::security::{BlocklistAction, ThreatCategory};
 
     fn minimal_bundle_toml() -> String {
         format!(
@@ -232,6 +272,65 @@ stream_destinations = ["file:///var/log/mvm/audit.jsonl"]
         assert_eq!(bundle.keys.rotation_interval_days, 14);
         assert!(bundle.audit.chain_signing);
         assert_eq!(bundle.audit.stream_destinations.len(), 1);
+        assert!(bundle.threat.is_unset());
+        assert!(bundle.fs_quota.is_none());
+    }
+
+    #[test]
+    fn parse_bundle_reads_fs_quota() {
+        let text = format!(
+            r#"{}
+[fs_quota]
+ops_per_second = 50
+session_bytes = 1048576
+"#,
+            minimal_bundle_toml()
+        );
+        let quota = parse_bundle(&text).unwrap().fs_quota.expect("fs_quota");
+        assert_eq!(quota.ops_per_second, 50);
+        assert_eq!(quota.session_bytes, 1 << 20);
+        // Unlisted limits keep their defaults.
+        assert_eq!(
+            quota.read_bytes_per_second,
+            mvm_core::security::FsQuotaPolicy::default().read_bytes_per_second
+        );
+
+        let typo = format!(
+            "{}
+[fs_quota]
+ops_per_sec = 50
+",
+            minimal_bundle_toml()
+        );
+        assert!(parse_bundle(&typo).is_err());
+    }
+
+    #[test]
+    fn parse_bundle_reads_threat_actions() {
+        let text = format!(
+            r#"{}
+[threat]
+default_action = "Log"
+
+[threat.categories]
+Destructive = "Block"
+SecretExposure = "RequireApproval"
+"#,
+            minimal_bundle_toml()
+        );
+        let bundle = parse_bundle(&text).unwrap();
+        assert_eq!(
+            bundle.threat.action_for(&ThreatCategory::Destructive),
+            BlocklistAction::Block
+        );
+        assert_eq!(
+            bundle.threat.action_for(&ThreatCategory::SecretExposure),
+            BlocklistAction::RequireApproval
+        );
+        assert_eq!(
+            bundle.threat.action_for(&ThreatCategory::Injection),
+            BlocklistAction::Log
+        );
     }
 
     #[test]
@@ -399,6 +498,43 @@ typo_field = "nope"
         assert!(matches!(err, LoadError::Parse { .. }));
     }
 
+    #[test]
+    fn parse_bundle_rejects_invalid_allow_list_patterns() {
+        let text = format!(
+            r#"
+schema_version = {SCHEMA_VERSION}
+bundle_id      = "acme/web"
+bundle_version = 1
+
+[network]
+[egress]
+allow_list = [["*.githubusercontent.com", 443], ["*.com", 443]]
+
+[pii]
+[tool]
+[artifact]
+[keys]
+[audit]
+"#,
+        );
+        let err = parse_bundle(&text).unwrap_err();
+        match err {
+            LoadError::InvalidAllowList { detail, .. } => assert!(detail.contains("*.com")),
+            other => panic!("expected InvalidAllowList, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_bundle_rejects_zero_bandwidth() {
+        let text =
+            minimal_bundle_toml().replace("[egress]", "[egress]\nbandwidth_bytes_per_sec = 0");
+        let err = parse_bundle(&text).unwrap_err();
+        assert!(
+            matches!(err, LoadError::InvalidEgressLimit { .. }),
+            "expected InvalidEgressLimit, got {err:?}"
+        );
+    }
+
     #[test]
     fn parse_bundle_rejects_malformed_toml() {
         let err = parse_bundle("schema_version = [[\n").unwrap_err();
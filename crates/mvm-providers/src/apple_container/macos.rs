@@ -108,7 +108,7 @@ fn start_vsock_proxy_listener(id: &str) -> Result<(), String> {
 /// port and then expects bidirectional bytes to that vsock port. Without
 /// an allowlist, a client could connect to *any* vsock port the guest
 /// happens to have listening — not just the guest agent. We restrict
-/// to the three ranges mvmctl actually uses:
+/// to the ranges mvmctl actually uses:
 ///
 /// * `5252` — guest agent control channel (`GUEST_AGENT_PORT`,
 ///   formerly 52; moved out of the privileged-port range so the agent
@@ -117,12 +117,22 @@ fn start_vsock_proxy_listener(id: &str) -> Result<(), String> {
 ///   `start_port_proxy` (BASE = 10000).
 /// * `CONSOLE_PORT_BASE..=BASE+65535` — `ConsoleOpen` data ports
 ///   (BASE = 20000).
+/// * `FS_STREAM_PORT_BASE..BASE+SPAN` — streaming FS transfer / watch
+///   data ports (BASE = 30000, SPAN = 10000).
+/// * `PROC_PTY_PORT_BASE..BASE+SPAN` — PTY-backed `ProcStart` data
+///   ports (BASE = 40000, SPAN = 10000).
 fn proxy_port_is_allowed(port: u32) -> bool {
     const PORT_FORWARD_BASE: u32 = 10_000;
     const CONSOLE_PORT_BASE: u32 = 20_000;
+    const FS_STREAM_PORT_BASE: u32 = 30_000;
+    const FS_STREAM_PORT_SPAN: u32 = 10_000;
+    const PROC_PTY_PORT_BASE: u32 = 40_000;
+    const PROC_PTY_PORT_SPAN: u32 = 10_000;
     port == crate::apple_container::GUEST_AGENT_PORT
         || (PORT_FORWARD_BASE..=PORT_FORWARD_BASE + 65_535).contains(&port)
         || (CONSOLE_PORT_BASE..=CONSOLE_PORT_BASE + 65_535).contains(&port)
+        || (FS_STREAM_PORT_BASE..FS_STREAM_PORT_BASE + FS_STREAM_PORT_SPAN).contains(&port)
+        || (PROC_PTY_PORT_BASE..PROC_PTY_PORT_BASE + PROC_PTY_PORT_SPAN).contains(&port)
 }
 
 fn proxy_accept_loop(listener: std::os::unix::net::UnixListener, id: String) {
@@ -1197,6 +1207,15 @@ mod tests {
         assert!(proxy_port_is_allowed(20_001));
         assert!(proxy_port_is_allowed(85_535));
 
+        // Allowed: FS stream data ports (inside the union above, listed
+        // explicitly so narrowing either range can't drop them).
+        assert!(proxy_port_is_allowed(30_000));
+        assert!(proxy_port_is_allowed(39_999));
+
+        // Allowed: PTY data ports (same reasoning as FS stream).
+        assert!(proxy_port_is_allowed(40_000));
+        assert!(proxy_port_is_allowed(49_999));
+
         // Rejected: low ports outside the agent slot. Includes the old
         // pre-5252 historical port (52) — anyone still pointing at it
         // is using stale config and we'd rather fail loudly.
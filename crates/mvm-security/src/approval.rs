@@ -0,0 +1,889 @@
+//! Human approval for `BlocklistAction::RequireApproval` matches.
+//!
+//! [`CommandGate`] answers `RequiresApproval` for commands that are
+//! neither safe nor forbidden outright. This module is what acts on
+//! that answer: the gated exec, process start or tool call is parked
+//! until a human releases or rejects it, or until it times out.
+//!
+//! - [`ApprovalQueue`] — file-backed queue under
+//!   `<mvm_runtime_dir>/approvals/`. Each parked call is one
+//!   `<id>.request.json`; a decision adds `<id>.decision.json`. The
+//!   queue is shared across processes: the parked `mvmctl exec` polls
+//!   it while `mvmctl approvals approve <id>` writes to it.
+//! - [`ApprovalBroker`] — the gate, the queue and the timeout in one
+//!   place. Callers either [`ApprovalBroker::authorize`] (evaluate,
+//!   park, block until decided) or drive the steps themselves when
+//!   they can ask a human directly, as the MCP server does through
+//!   elicitation.
+//!
+//! ## Decisions
+//!
+//! Decisions are first-writer-wins. The decision file is published
+//! with a hard link, which fails when one already exists, so an
+//! approval racing the timeout — or two approvers racing each other —
+//! resolves to exactly one verdict. The process that wins reports it
+//! to its [`ApprovalAuditSink`] with the approver identity; the
+//! losers read the winning decision back instead.
+
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use mvm_core::security::{ApprovalVerdict, GateDecision};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::command_gate::CommandGate;
+
+/// How long a parked call waits when the operator configured nothing.
+pub const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often a waiting caller re-reads the queue.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Approver recorded on decisions the broker makes itself when a
+/// parked call expires.
+pub const TIMEOUT_APPROVER: &str = "mvm:timeout";
+
+const REQUEST_SUFFIX: &str = ".request.json";
+const DECISION_SUFFIX: &str = ".decision.json";
+
+/// What kind of call was parked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalSubject {
+    /// A one-shot command in a (usually transient) microVM.
+    Exec,
+    /// A `ProcStart` against a running VM.
+    Process,
+    /// A host-mediated tool call.
+    Tool,
+    /// A `RunEntrypoint` payload held by the threat gate.
+    Entrypoint,
+}
+
+impl ApprovalSubject {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Exec => "exec",
+            Self::Process => "process",
+            Self::Tool => "tool",
+            Self::Entrypoint => "entrypoint",
+        }
+    }
+}
+
+impl std::fmt::Display for ApprovalSubject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One parked call, as listed by `mvmctl approvals ls`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// 32 lowercase hex characters.
+    pub id: String,
+    pub subject: ApprovalSubject,
+    /// VM, MCP env or tool the call targets. `None` for a transient VM.
+    pub target: Option<String>,
+    /// The text the gate evaluated.
+    pub command: String,
+    /// The gate's reason, naming the matched pattern.
+    pub reason: String,
+    /// Identity of the caller that was parked.
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ApprovalRequest {
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// The verdict on one parked call and who gave it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    pub id: String,
+    pub verdict: ApprovalVerdict,
+    /// Approver identity; [`TIMEOUT_APPROVER`] for timeouts.
+    pub approver: String,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Result of [`ApprovalBroker::evaluate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Evaluation {
+    /// Nothing matched, or only `Log` entries did.
+    Allowed,
+    /// A `Block` entry matched. Nothing was parked.
+    Blocked { pattern: String, reason: String },
+    /// A `RequireApproval` entry matched and the call is now queued.
+    Parked(ApprovalRequest),
+}
+
+/// Final answer for a gated call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateOutcome {
+    Allowed,
+    Approved(ApprovalDecision),
+    Blocked {
+        pattern: String,
+        reason: String,
+    },
+    /// Denied by an approver, or timed out.
+    Rejected(ApprovalDecision),
+}
+
+impl GateOutcome {
+    pub fn is_permitted(&self) -> bool {
+        matches!(self, Self::Allowed | Self::Approved(_))
+    }
+
+    /// Operator-facing reason the call may not proceed; `None` when
+    /// it may.
+    pub fn refusal(&self) -> Option<String> {
+        match self {
+            Self::Allowed | Self::Approved(_) => None,
+            Self::Blocked { pattern, reason } => Some(format!(
+                "blocked by command gate ({reason}): matched {pattern:?}"
+            )),
+            Self::Rejected(decision) => Some(match &decision.verdict {
+                ApprovalVerdict::Denied { reason } => format!(
+                    "approval {} denied by {}: {reason}",
+                    decision.id, decision.approver
+                ),
+                ApprovalVerdict::Timeout => {
+                    format!("approval {} timed out with no decision", decision.id)
+                }
+                ApprovalVerdict::Approved => unreachable!("approved decisions are not rejected"),
+            }),
+        }
+    }
+}
+
+/// Receives every parked call and every decision this process makes.
+pub trait ApprovalAuditSink: Send + Sync {
+    fn requested(&self, request: &ApprovalRequest);
+    fn decided(&self, request: &ApprovalRequest, decision: &ApprovalDecision);
+}
+
+/// Drops every event. The default until a caller wires its audit
+/// stream.
+pub struct NoopApprovalAuditSink;
+
+impl ApprovalAuditSink for NoopApprovalAuditSink {
+    fn requested(&self, _request: &ApprovalRequest) {}
+    fn decided(&self, _request: &ApprovalRequest, _decision: &ApprovalDecision) {}
+}
+
+/// File-backed queue of parked calls. See the module docs for the
+/// layout.
+#[derive(Debug, Clone)]
+pub struct ApprovalQueue {
+    dir: PathBuf,
+}
+
+impl ApprovalQueue {
+    /// `<mvm_runtime_dir>/approvals/`.
+    pub fn default_dir() -> PathBuf {
+        PathBuf::from(mvm_core::config::mvm_runtime_dir()).join("approvals")
+    }
+
+    /// Open (creating mode 0700 if needed) the queue at `dir`.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("create approvals dir {}", dir.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))
+                .with_context(|| format!("restrict approvals dir {}", dir.display()))?;
+        }
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Queue `request`. Fails if the id is malformed or already used.
+    pub fn submit(&self, request: &ApprovalRequest) -> Result<()> {
+        validate_id(&request.id)?;
+        let bytes = serde_json::to_vec_pretty(request).context("serialize approval request")?;
+        match self.publish(&self.request_path(&request.id), &bytes)? {
+            true => Ok(()),
+            false => bail!("approval {} already exists", request.id),
+        }
+    }
+
+    pub fn request(&self, id: &str) -> Result<Option<ApprovalRequest>> {
+        validate_id(id)?;
+        read_json(&self.request_path(id))
+    }
+
+    pub fn decision(&self, id: &str) -> Result<Option<ApprovalDecision>> {
+        validate_id(id)?;
+        read_json(&self.decision_path(id))
+    }
+
+    /// Undecided, unexpired requests, oldest first. Entries that fail
+    /// to parse are skipped with a warning.
+    pub fn pending(&self) -> Result<Vec<ApprovalRequest>> {
+        let now = Utc::now();
+        let mut out = Vec::new();
+        for id in self.ids()? {
+            let request = match self.request(&id) {
+                Ok(Some(request)) => request,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!(id = %id, error = %e, "skipping unreadable approval request");
+                    continue;
+                }
+            };
+            if request.is_expired_at(now) || self.decision_path(&id).exists() {
+                continue;
+            }
+            out.push(request);
+        }
+        out.sort_by_key(|r| r.requested_at);
+        Ok(out)
+    }
+
+    /// Remove both files of `id`. Missing files are not an error.
+    pub fn remove(&self, id: &str) -> Result<()> {
+        validate_id(id)?;
+        for path in [self.request_path(id), self.decision_path(id)] {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("remove {}", path.display()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove requests that expired more than `grace` ago. Their
+    /// waiter normally cleans up after itself; these are left behind
+    /// by callers that exited while parked.
+    pub fn prune(&self, grace: Duration) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(grace).unwrap_or_default();
+        let mut removed = 0;
+        for id in self.ids()? {
+            if let Ok(Some(request)) = self.request(&id)
+                && request.is_expired_at(cutoff)
+            {
+                self.remove(&id)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Publish a decision for `id` unless one exists. `Ok(Err(..))`
+    /// carries the decision that got there first.
+    fn record_decision(
+        &self,
+        decision: &ApprovalDecision,
+    ) -> Result<std::result::Result<(), ApprovalDecision>> {
+        let bytes = serde_json::to_vec_pretty(decision).context("serialize approval decision")?;
+        if self.publish(&self.decision_path(&decision.id), &bytes)? {
+            return Ok(Ok(()));
+        }
+        let existing = self
+            .decision(&decision.id)?
+            .with_context(|| format!("approval {} decision vanished", decision.id))?;
+        Ok(Err(existing))
+    }
+
+    /// Write `bytes` to a private temp file and hard-link it to
+    /// `path`. Returns `false` if `path` already existed. The link is
+    /// the atomic create-if-absent step, so readers never see a
+    /// partial file and concurrent writers cannot both win.
+    fn publish(&self, path: &Path, bytes: &[u8]) -> Result<bool> {
+        let mut tmp = tempfile::NamedTempFile::new_in(&self.dir)
+            .with_context(|| format!("create temp file in {}", self.dir.display()))?;
+        tmp.write_all(bytes)
+            .and_then(|()| tmp.as_file().sync_all())
+            .with_context(|| format!("write {}", tmp.path().display()))?;
+        match fs::hard_link(tmp.path(), path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("publish {}", path.display())),
+        }
+    }
+
+    fn ids(&self) -> Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("read {}", self.dir.display())),
+        };
+        let mut ids = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name();
+            if let Some(id) = name.to_str().and_then(|n| n.strip_suffix(REQUEST_SUFFIX))
+                && validate_id(id).is_ok()
+            {
+                ids.push(id.to_string());
+            }
+        }
+        Ok(ids)
+    }
+
+    fn request_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}{REQUEST_SUFFIX}"))
+    }
+
+    fn decision_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}{DECISION_SUFFIX}"))
+    }
+}
+
+/// Command gate plus approval queue plus timeout.
+pub struct ApprovalBroker {
+    gate: CommandGate,
+    queue: ApprovalQueue,
+    timeout: Duration,
+    poll_interval: Duration,
+    audit: Arc<dyn ApprovalAuditSink>,
+}
+
+impl ApprovalBroker {
+    pub fn new(gate: CommandGate, queue: ApprovalQueue) -> Self {
+        Self {
+            gate,
+            queue,
+            timeout: DEFAULT_APPROVAL_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            audit: Arc::new(NoopApprovalAuditSink),
+        }
+    }
+
+    /// How long a parked call waits for a decision.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_audit_sink(mut self, audit: Arc<dyn ApprovalAuditSink>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    pub fn queue(&self) -> &ApprovalQueue {
+        &self.queue
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Run `command` through the gate and park it if it needs
+    /// approval. Never blocks.
+    pub fn evaluate(
+        &self,
+        subject: ApprovalSubject,
+        target: Option<&str>,
+        command: &str,
+        requested_by: &str,
+    ) -> Result<Evaluation> {
+        let reason = match self.gate.evaluate(command) {
+            GateDecision::Allow => return Ok(Evaluation::Allowed),
+            GateDecision::Blocked { pattern, reason } => {
+                return Ok(Evaluation::Blocked { pattern, reason });
+            }
+            GateDecision::RequiresApproval { reason } => reason,
+        };
+        self.park(subject, target, command, &reason, requested_by)
+            .map(Evaluation::Parked)
+    }
+
+    /// Queue `command` for approval with `reason`, without consulting
+    /// the command gate. For callers with their own gate — the threat
+    /// classifier — that share this queue and timeout.
+    pub fn park(
+        &self,
+        subject: ApprovalSubject,
+        target: Option<&str>,
+        command: &str,
+        reason: &str,
+        requested_by: &str,
+    ) -> Result<ApprovalRequest> {
+        let requested_at = Utc::now();
+        let request = ApprovalRequest {
+            id: new_id(),
+            subject,
+            target: target.map(str::to_string),
+            command: command.to_string(),
+            reason: reason.to_string(),
+            requested_by: requested_by.to_string(),
+            requested_at,
+            expires_at: requested_at
+                + chrono::Duration::from_std(self.timeout).unwrap_or(chrono::TimeDelta::MAX),
+        };
+        self.queue.submit(&request)?;
+        self.audit.requested(&request);
+        Ok(request)
+    }
+
+    /// Block until `request` is decided, deciding it as timed out
+    /// once it expires.
+    pub fn wait(&self, request: &ApprovalRequest) -> Result<ApprovalDecision> {
+        loop {
+            if let Some(decision) = self.queue.decision(&request.id)? {
+                return Ok(decision);
+            }
+            let now = Utc::now();
+            if request.is_expired_at(now) {
+                return self.settle(request, ApprovalVerdict::Timeout, TIMEOUT_APPROVER);
+            }
+            let remaining = (request.expires_at - now).to_std().unwrap_or_default();
+            std::thread::sleep(self.poll_interval.min(remaining));
+        }
+    }
+
+    /// Record an operator's verdict on a pending request. Fails if
+    /// the request is unknown, expired or already decided.
+    pub fn decide(
+        &self,
+        id: &str,
+        verdict: ApprovalVerdict,
+        approver: &str,
+    ) -> Result<ApprovalDecision> {
+        let request = self
+            .queue
+            .request(id)?
+            .with_context(|| format!("no pending approval {id}"))?;
+        if request.is_expired_at(Utc::now()) {
+            bail!(
+                "approval {id} expired at {}",
+                request.expires_at.to_rfc3339()
+            );
+        }
+        let decision = new_decision(&request, verdict, approver);
+        match self.queue.record_decision(&decision)? {
+            Ok(()) => {
+                self.audit.decided(&request, &decision);
+                Ok(decision)
+            }
+            Err(existing) => bail!(
+                "approval {id} was already decided by {}: {}",
+                existing.approver,
+                verdict_label(&existing.verdict)
+            ),
+        }
+    }
+
+    /// Like [`Self::decide`], for callers that hold the request and
+    /// must end up with *a* decision: if another decision won the
+    /// race it is returned instead, and a verdict that arrives after
+    /// expiry is recorded as a timeout.
+    pub fn settle(
+        &self,
+        request: &ApprovalRequest,
+        verdict: ApprovalVerdict,
+        approver: &str,
+    ) -> Result<ApprovalDecision> {
+        let (verdict, approver) =
+            if verdict != ApprovalVerdict::Timeout && request.is_expired_at(Utc::now()) {
+                (ApprovalVerdict::Timeout, TIMEOUT_APPROVER)
+            } else {
+                (verdict, approver)
+            };
+        let decision = new_decision(request, verdict, approver);
+        match self.queue.record_decision(&decision)? {
+            Ok(()) => {
+                self.audit.decided(request, &decision);
+                Ok(decision)
+            }
+            Err(existing) => Ok(existing),
+        }
+    }
+
+    /// Turn a decision into the caller's outcome and drop the
+    /// request from the queue.
+    pub fn finish(&self, request: &ApprovalRequest, decision: ApprovalDecision) -> GateOutcome {
+        if let Err(e) = self.queue.remove(&request.id) {
+            tracing::warn!(id = %request.id, error = %e, "could not remove decided approval");
+        }
+        match decision.verdict {
+            ApprovalVerdict::Approved => GateOutcome::Approved(decision),
+            ApprovalVerdict::Denied { .. } | ApprovalVerdict::Timeout => {
+                GateOutcome::Rejected(decision)
+            }
+        }
+    }
+
+    /// Gate `command`, parking and blocking on a decision when the
+    /// gate asks for one. `on_parked` runs once, before blocking, so
+    /// the caller can tell its user how to approve.
+    pub fn authorize(
+        &self,
+        subject: ApprovalSubject,
+        target: Option<&str>,
+        command: &str,
+        requested_by: &str,
+        on_parked: impl FnOnce(&ApprovalRequest),
+    ) -> Result<GateOutcome> {
+        match self.evaluate(subject, target, command, requested_by)? {
+            Evaluation::Allowed => Ok(GateOutcome::Allowed),
+            Evaluation::Blocked { pattern, reason } => Ok(GateOutcome::Blocked { pattern, reason }),
+            Evaluation::Parked(request) => {
+                on_parked(&request);
+                let decision = self.wait(&request)?;
+                Ok(self.finish(&request, decision))
+            }
+        }
+    }
+
+    /// [`Self::park`] `command`, then block on its decision like
+    /// [`Self::authorize`] does.
+    pub fn hold(
+        &self,
+        subject: ApprovalSubject,
+        target: Option<&str>,
+        command: &str,
+        reason: &str,
+        requested_by: &str,
+        on_parked: impl FnOnce(&ApprovalRequest),
+    ) -> Result<GateOutcome> {
+        let request = self.park(subject, target, command, reason, requested_by)?;
+        on_parked(&request);
+        let decision = self.wait(&request)?;
+        Ok(self.finish(&request, decision))
+    }
+}
+
+/// Short lowercase label for a verdict: `approved`, `denied`,
+/// `timed_out`.
+pub fn verdict_label(verdict: &ApprovalVerdict) -> &'static str {
+    match verdict {
+        ApprovalVerdict::Approved => "approved",
+        ApprovalVerdict::Denied { .. } => "denied",
+        ApprovalVerdict::Timeout => "timed_out",
+    }
+}
+
+fn new_decision(
+    request: &ApprovalRequest,
+    verdict: ApprovalVerdict,
+    approver: &str,
+) -> ApprovalDecision {
+    ApprovalDecision {
+        id: request.id.clone(),
+        verdict,
+        approver: approver.to_string(),
+        decided_at: Utc::now(),
+    }
+}
+
+fn new_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Ids become file names, so only the shape [`new_id`] produces is
+/// accepted.
+fn validate_id(id: &str) -> Result<()> {
+    if id.len() != 32 || !id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        bail!("invalid approval id {id:?}: expected 32 lowercase hex characters");
+    }
+    Ok(())
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+    };
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .with_context(|| format!("parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mvm_core::security::{BlocklistAction, BlocklistEntry, BlocklistSeverity};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CapturingSink {
+        requested: Mutex<Vec<ApprovalRequest>>,
+        decided: Mutex<Vec<ApprovalDecision>>,
+    }
+
+    impl ApprovalAuditSink for CapturingSink {
+        fn requested(&self, request: &ApprovalRequest) {
+            self.requested.lock().unwrap().push(request.clone());
+        }
+        fn decided(&self, _request: &ApprovalRequest, decision: &ApprovalDecision) {
+            self.decided.lock().unwrap().push(decision.clone());
+        }
+    }
+
+    fn gate() -> CommandGate {
+        CommandGate::new(vec![
+            BlocklistEntry {
+                pattern: "chroot".to_string(),
+                category: "privilege_escalation".to_string(),
+                severity: BlocklistSeverity::High,
+                action: BlocklistAction::RequireApproval,
+            },
+            BlocklistEntry {
+                pattern: "mkfs".to_string(),
+                category: "destructive".to_string(),
+                severity: BlocklistSeverity::Critical,
+                action: BlocklistAction::Block,
+            },
+        ])
+    }
+
+    fn broker(dir: &Path, timeout: Duration) -> (ApprovalBroker, Arc<CapturingSink>) {
+        let sink = Arc::new(CapturingSink::default());
+        let broker = ApprovalBroker::new(gate(), ApprovalQueue::open(dir).unwrap())
+            .with_timeout(timeout)
+            .with_poll_interval(Duration::from_millis(10))
+            .with_audit_sink(sink.clone());
+        (broker, sink)
+    }
+
+    fn park(broker: &ApprovalBroker) -> ApprovalRequest {
+        match broker
+            .evaluate(ApprovalSubject::Exec, Some("vm1"), "chroot /new", "tester")
+            .unwrap()
+        {
+            Evaluation::Parked(request) => request,
+            other => panic!("expected a parked request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn allowed_and_blocked_commands_are_never_parked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, sink) = broker(tmp.path(), Duration::from_secs(60));
+        assert_eq!(
+            broker
+                .evaluate(ApprovalSubject::Exec, None, "echo hi", "tester")
+                .unwrap(),
+            Evaluation::Allowed
+        );
+        assert!(matches!(
+            broker
+                .evaluate(ApprovalSubject::Exec, None, "mkfs.ext4 /dev/vda", "tester")
+                .unwrap(),
+            Evaluation::Blocked { .. }
+        ));
+        assert!(broker.queue().pending().unwrap().is_empty());
+        assert!(sink.requested.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parked_request_is_listed_and_audited() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, sink) = broker(tmp.path(), Duration::from_secs(60));
+        let request = park(&broker);
+        assert_eq!(request.id.len(), 32);
+        assert_eq!(request.reason, "matched pattern: chroot");
+        assert_eq!(broker.queue().pending().unwrap(), vec![request.clone()]);
+        assert_eq!(*sink.requested.lock().unwrap(), vec![request]);
+    }
+
+    #[test]
+    fn approval_from_another_handle_releases_the_waiter() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (approver, approver_sink) = broker(tmp.path(), Duration::from_secs(30));
+        let (waiting, _) = broker(tmp.path(), Duration::from_secs(30));
+        let outcome = std::thread::scope(|s| {
+            let waiter = s.spawn(|| {
+                waiting.authorize(
+                    ApprovalSubject::Process,
+                    Some("vm1"),
+                    "chroot /new",
+                    "tester",
+                    |_| {},
+                )
+            });
+            let id = loop {
+                if let Some(request) = approver.queue().pending().unwrap().pop() {
+                    break request.id;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            };
+            approver
+                .decide(&id, ApprovalVerdict::Approved, "alice")
+                .unwrap();
+            waiter.join().unwrap().unwrap()
+        });
+        let GateOutcome::Approved(decision) = outcome else {
+            panic!("expected approval, got {outcome:?}");
+        };
+        assert_eq!(decision.approver, "alice");
+        assert_eq!(*approver_sink.decided.lock().unwrap(), vec![decision]);
+        // The waiter cleans its entry up once it has the answer.
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn denial_is_a_rejection_carrying_the_reason() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, _) = broker(tmp.path(), Duration::from_secs(60));
+        let request = park(&broker);
+        broker
+            .decide(
+                &request.id,
+                ApprovalVerdict::Denied {
+                    reason: "not today".to_string(),
+                },
+                "bob",
+            )
+            .unwrap();
+        let decision = broker.wait(&request).unwrap();
+        let outcome = broker.finish(&request, decision);
+        assert!(!outcome.is_permitted());
+        let refusal = outcome.refusal().unwrap();
+        assert!(refusal.contains("denied by bob: not today"), "{refusal}");
+    }
+
+    #[test]
+    fn unanswered_request_times_out_and_is_audited() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, sink) = broker(tmp.path(), Duration::from_millis(30));
+        let outcome = broker
+            .authorize(
+                ApprovalSubject::Tool,
+                Some("mvm.web_fetch"),
+                "chroot",
+                "tester",
+                |_| {},
+            )
+            .unwrap();
+        let GateOutcome::Rejected(decision) = &outcome else {
+            panic!("expected a timeout, got {outcome:?}");
+        };
+        assert_eq!(decision.verdict, ApprovalVerdict::Timeout);
+        assert_eq!(decision.approver, TIMEOUT_APPROVER);
+        assert_eq!(sink.decided.lock().unwrap().len(), 1);
+        assert!(outcome.refusal().unwrap().contains("timed out"));
+    }
+
+    #[test]
+    fn first_decision_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, sink) = broker(tmp.path(), Duration::from_secs(60));
+        let request = park(&broker);
+        broker
+            .decide(&request.id, ApprovalVerdict::Approved, "alice")
+            .unwrap();
+        let err = broker
+            .decide(
+                &request.id,
+                ApprovalVerdict::Denied {
+                    reason: "late".to_string(),
+                },
+                "bob",
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("already decided by alice"),
+            "{err}"
+        );
+        // `settle` hands back the winner instead of failing.
+        let settled = broker
+            .settle(&request, ApprovalVerdict::Timeout, TIMEOUT_APPROVER)
+            .unwrap();
+        assert_eq!(settled.approver, "alice");
+        assert_eq!(sink.decided.lock().unwrap().len(), 1);
+        assert!(broker.queue().pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn late_verdicts_are_recorded_as_timeouts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, _) = broker(tmp.path(), Duration::ZERO);
+        let request = park(&broker);
+        let err = broker
+            .decide(&request.id, ApprovalVerdict::Approved, "alice")
+            .unwrap_err();
+        assert!(err.to_string().contains("expired"), "{err}");
+        let settled = broker
+            .settle(&request, ApprovalVerdict::Approved, "alice")
+            .unwrap();
+        assert_eq!(settled.verdict, ApprovalVerdict::Timeout);
+    }
+
+    #[test]
+    fn malformed_ids_never_reach_the_filesystem() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, _) = broker(tmp.path(), Duration::from_secs(60));
+        let err = broker
+            .decide("../../etc/passwd", ApprovalVerdict::Approved, "alice")
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid approval id"), "{err}");
+    }
+
+    #[test]
+    fn prune_removes_abandoned_requests() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, _) = broker(tmp.path(), Duration::ZERO);
+        park(&broker);
+        assert_eq!(broker.queue().prune(Duration::from_secs(3600)).unwrap(), 0);
+        assert_eq!(broker.queue().prune(Duration::ZERO).unwrap(), 1);
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn queue_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("approvals");
+        let (broker, _) = broker(&dir, Duration::from_secs(60));
+        let request = park(&broker);
+        let mode = |p: PathBuf| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(dir.clone()), 0o700);
+        assert_eq!(
+            mode(dir.join(format!("{}{REQUEST_SUFFIX}", request.id))),
+            0o600
+        );
+    }
+
+    #[test]
+    fn hold_parks_without_consulting_the_command_gate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (broker, sink) = broker(tmp.path(), Duration::from_millis(30));
+        let mut parked = None;
+        let outcome = broker
+            .hold(
+                ApprovalSubject::Entrypoint,
+                Some("vm1"),
+                "echo hi",
+                "threat classifier: Destructive",
+                "tester",
+                |request| parked = Some(request.clone()),
+            )
+            .unwrap();
+        let parked = parked.expect("on_parked runs before blocking");
+        assert_eq!(parked.reason, "threat classifier: Destructive");
+        assert_eq!(parked.subject, ApprovalSubject::Entrypoint);
+        assert!(matches!(outcome, GateOutcome::Rejected(_)));
+        assert_eq!(*sink.requested.lock().unwrap(), vec![parked]);
+    }
+}
@@ -19,7 +19,7 @@ use std::path::Path;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 
 /// Current manifest schema version. Bump whenever fields change in a way
 /// older verifiers can't ignore. Older verifiers must reject unknown
@@ -141,6 +141,9 @@ pub enum VerifyError {
     #[error("manifest does not list expected artifact {name}")]
     ArtifactNotInManifest { name: String },
 
+    #[error("image {image} carries no cosign signature")]
+    Unsigned { image: String },
+
     #[error("manifest parse failed: {0}")]
     Parse(String),
 
@@ -386,6 +389,236 @@ pub fn sha256_file(path: &Path) -> io::Result<String> {
     Ok(format!("{:x}", hasher.finalize()))
 }
 
+/// `critical.type` of a cosign simple-signing payload.
+const COSIGN_PAYLOAD_TYPE: &str = "cosign container image signature";
+
+/// What an OCI image signature must chain to. Comes from the
+/// operator's trust policy, never from the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CosignTrust {
+    /// `cosign sign --key`: an ECDSA P-256 public key in PEM (the
+    /// `cosign.pub` that `cosign generate-key-pair` writes). Checked
+    /// against simple-signing signatures.
+    PublicKey { pem: String },
+    /// Keyless signing, checked offline: a Sigstore bundle whose
+    /// certificate chains to `trusted_root_json` (a Sigstore
+    /// `trusted_root.json` the operator pinned) and names exactly
+    /// this identity and issuer. The bundle's own inclusion proof
+    /// stands in for a Rekor lookup.
+    Keyless {
+        trusted_root_json: Vec<u8>,
+        certificate_identity: String,
+        certificate_oidc_issuer: String,
+    },
+}
+
+/// Signature material published for an OCI image — what
+/// `mvm_oci::OciManifestFetcher::fetch_cosign_signatures` collects.
+/// Untrusted until [`verify_oci_image_signature`] accepts it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CosignEvidence {
+    /// A simple-signing payload and its base64 signature, from the
+    /// `sha256-<hex>.sig` tag.
+    SimpleSigning {
+        payload: Vec<u8>,
+        signature_b64: String,
+    },
+    /// A Sigstore bundle whose message signature covers the image
+    /// manifest bytes, from an OCI 1.1 referrer.
+    Bundle { bundle_json: Vec<u8> },
+}
+
+/// Verify that at least one piece of `evidence` signs the image
+/// manifest `manifest_digest` under `trust`.
+///
+/// `manifest_bytes` must hash to `manifest_digest` (`sha256` or
+/// `sha512`); the digest is what simple-signing payloads name and the
+/// bytes are what bundles sign, so both are checked against each
+/// other first. Evidence that does not fit the trust anchor (a bundle
+/// under a public key, a simple-signing payload under a keyless
+/// identity) is skipped with a reason rather than accepted; keyed
+/// Sigstore bundles are not supported.
+///
+/// Returns `Unsigned` when there is no evidence at all and
+/// `SignatureInvalid` — listing every per-signature reason — when
+/// nothing verified. Bundle checks never contact Rekor, and without
+/// the `manifest-verify` feature they fail closed.
+pub fn verify_oci_image_signature(
+    manifest_digest: &str,
+    manifest_bytes: &[u8],
+    evidence: &[CosignEvidence],
+    trust: &CosignTrust,
+) -> VerifyResult<()> {
+    check_manifest_digest(manifest_digest, manifest_bytes)?;
+    if evidence.is_empty() {
+        return Err(VerifyError::Unsigned {
+            image: manifest_digest.to_string(),
+        });
+    }
+    let mut failures = Vec::with_capacity(evidence.len());
+    for item in evidence {
+        let outcome = match (trust, item) {
+            (
+                CosignTrust::PublicKey { pem },
+                CosignEvidence::SimpleSigning {
+                    payload,
+                    signature_b64,
+                },
+            ) => verify_simple_signing(manifest_digest, payload, signature_b64, pem),
+            (
+                CosignTrust::Keyless {
+                    trusted_root_json,
+                    certificate_identity,
+                    certificate_oidc_issuer,
+                },
+                CosignEvidence::Bundle { bundle_json },
+            ) => verify_bundle_offline(
+                manifest_bytes,
+                bundle_json,
+                trusted_root_json,
+                certificate_identity,
+                certificate_oidc_issuer,
+            ),
+            (CosignTrust::PublicKey { .. }, CosignEvidence::Bundle { .. }) => Err(
+                "keyed sigstore bundles are not supported; a public-key trust anchor needs \
+                 the simple-signing `.sig` signature (cosign sign --key without \
+                 --new-bundle-format)"
+                    .to_string(),
+            ),
+            (CosignTrust::Keyless { .. }, CosignEvidence::SimpleSigning { .. }) => {
+                Err("simple-signing signature does not apply to a keyless trust anchor".to_string())
+            }
+        };
+        match outcome {
+            Ok(()) => return Ok(()),
+            Err(reason) => failures.push(reason),
+        }
+    }
+    Err(VerifyError::SignatureInvalid {
+        reason: format!(
+            "no signature on {manifest_digest} verified: {}",
+            failures.join("; ")
+        ),
+    })
+}
+
+fn check_manifest_digest(manifest_digest: &str, manifest_bytes: &[u8]) -> VerifyResult<()> {
+    let computed = match manifest_digest.split_once(':') {
+        Some(("sha256", _)) => format!("sha256:{:x}", Sha256::digest(manifest_bytes)),
+        Some(("sha512", _)) => format!("sha512:{:x}", Sha512::digest(manifest_bytes)),
+        _ => {
+            return Err(VerifyError::Parse(format!(
+                "unsupported image manifest digest {manifest_digest:?}"
+            )));
+        }
+    };
+    if computed != manifest_digest {
+        return Err(VerifyError::SignatureInvalid {
+            reason: format!("manifest bytes hash to {computed}, not {manifest_digest}"),
+        });
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct SimpleSigningPayload {
+    critical: SimpleSigningCritical,
+}
+
+#[derive(Deserialize)]
+struct SimpleSigningCritical {
+    #[serde(rename = "type")]
+    kind: String,
+    image: SimpleSigningImage,
+}
+
+#[derive(Deserialize)]
+struct SimpleSigningImage {
+    #[serde(rename = "docker-manifest-digest")]
+    docker_manifest_digest: String,
+}
+
+fn verify_simple_signing(
+    manifest_digest: &str,
+    payload: &[u8],
+    signature_b64: &str,
+    public_key_pem: &str,
+) -> Result<(), String> {
+    use base64::Engine as _;
+    use p256::ecdsa::signature::Verifier as _;
+    use p256::ecdsa::{Signature, VerifyingKey};
+    use p256::pkcs8::DecodePublicKey as _;
+
+    let key = VerifyingKey::from_public_key_pem(public_key_pem)
+        .map_err(|e| format!("cosign public key parse failed: {e}"))?;
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(signature_b64.trim())
+        .map_err(|e| format!("signature is not base64: {e}"))?;
+    let signature =
+        Signature::from_der(&der).map_err(|e| format!("signature is not DER ECDSA: {e}"))?;
+    key.verify(payload, &signature)
+        .map_err(|_| "signature does not verify under the trusted public key".to_string())?;
+
+    // Only parse the payload once its signature has checked out.
+    let parsed: SimpleSigningPayload = serde_json::from_slice(payload)
+        .map_err(|e| format!("simple-signing payload parse failed: {e}"))?;
+    if parsed.critical.kind != COSIGN_PAYLOAD_TYPE {
+        return Err(format!(
+            "payload type {:?} is not a cosign image signature",
+            parsed.critical.kind
+        ));
+    }
+    if parsed.critical.image.docker_manifest_digest != manifest_digest {
+        return Err(format!(
+            "payload signs {}, not {manifest_digest}",
+            parsed.critical.image.docker_manifest_digest
+        ));
+    }
+    Ok(())
+}
+
+/// Verify a Sigstore bundle over `manifest_bytes` against a local
+/// trust root with `offline = true`: the certificate chain, SCT and
+/// the bundle's inclusion proof are checked, Rekor is never queried.
+/// The trust root is parsed without TUF metadata checks because the
+/// operator's policy file is itself the anchor.
+#[cfg(feature = "manifest-verify")]
+fn verify_bundle_offline(
+    manifest_bytes: &[u8],
+    bundle_json: &[u8],
+    trusted_root_json: &[u8],
+    certificate_identity: &str,
+    certificate_oidc_issuer: &str,
+) -> Result<(), String> {
+    use sigstore::bundle::Bundle;
+    use sigstore::bundle::verify::{blocking::Verifier, policy::Identity};
+    use sigstore::trust::sigstore::SigstoreTrustRoot;
+
+    let bundle: Bundle = serde_json::from_slice(bundle_json)
+        .map_err(|e| format!("sigstore bundle parse failed: {e}"))?;
+    let trust_root = SigstoreTrustRoot::from_trusted_root_json_unchecked(trusted_root_json)
+        .map_err(|e| format!("trusted root parse failed: {e}"))?;
+    let verifier = Verifier::new(Default::default(), trust_root)
+        .map_err(|e| format!("sigstore verifier init failed: {e}"))?;
+    let policy = Identity::new(certificate_identity, certificate_oidc_issuer);
+    verifier
+        .verify(manifest_bytes, bundle, &policy, true)
+        .map_err(|e| format!("bundle verification failed: {e}"))
+}
+
+#[cfg(not(feature = "manifest-verify"))]
+fn verify_bundle_offline(
+    _manifest_bytes: &[u8],
+    _bundle_json: &[u8],
+    _trusted_root_json: &[u8],
+    _certificate_identity: &str,
+    _certificate_oidc_issuer: &str,
+) -> Result<(), String> {
+    Err("manifest-verify feature is disabled in this build; \
+         sigstore bundles cannot be verified"
+        .to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -739,4 +972,243 @@ mod tests {
             other => panic!("expected SignatureInvalid, got {other:?}"),
         }
     }
+
+    // ---- OCI image signatures ----
+
+    const IMAGE_MANIFEST: &[u8] = br#"{"schemaVersion":2,"layers":[]}"#;
+
+    fn image_digest() -> String {
+        format!("sha256:{:x}", Sha256::digest(IMAGE_MANIFEST))
+    }
+
+    fn signing_key(seed: u8) -> p256::ecdsa::SigningKey {
+        p256::ecdsa::SigningKey::from_bytes(&[seed; 32].into()).expect("valid scalar")
+    }
+
+    fn public_key_trust(key: &p256::ecdsa::SigningKey) -> CosignTrust {
+        use p256::pkcs8::{EncodePublicKey, LineEnding};
+        CosignTrust::PublicKey {
+            pem: key
+                .verifying_key()
+                .to_public_key_pem(LineEnding::LF)
+                .expect("pem encodes"),
+        }
+    }
+
+    fn simple_signing(key: &p256::ecdsa::SigningKey, signed_digest: &str) -> CosignEvidence {
+        use base64::Engine as _;
+        use p256::ecdsa::signature::Signer as _;
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "critical": {
+                "identity": { "docker-reference": "ghcr.io/acme/app" },
+                "image": { "docker-manifest-digest": signed_digest },
+                "type": "cosign container image signature"
+            },
+            "optional": null
+        }))
+        .expect("payload serializes");
+        let signature: p256::ecdsa::Signature = key.sign(&payload);
+        CosignEvidence::SimpleSigning {
+            payload,
+            signature_b64: base64::engine::general_purpose::STANDARD
+                .encode(signature.to_der().as_bytes()),
+        }
+    }
+
+    #[test]
+    fn keyed_signature_over_the_image_digest_verifies() {
+        let key = signing_key(7);
+        let evidence = [simple_signing(&key, &image_digest())];
+        verify_oci_image_signature(
+            &image_digest(),
+            IMAGE_MANIFEST,
+            &evidence,
+            &public_key_trust(&key),
+        )
+        .expect("valid keyed signature");
+    }
+
+    #[test]
+    fn keyed_signature_from_another_key_is_rejected() {
+        let evidence = [simple_signing(&signing_key(7), &image_digest())];
+        let err = verify_oci_image_signature(
+            &image_digest(),
+            IMAGE_MANIFEST,
+            &evidence,
+            &public_key_trust(&signing_key(9)),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, VerifyError::SignatureInvalid { reason } if reason.contains("does not verify")),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn keyed_signature_over_another_digest_is_rejected() {
+        let key = signing_key(7);
+        let other = format!("sha256:{}", "b".repeat(64));
+        let evidence = [simple_signing(&key, &other)];
+        let err = verify_oci_image_signature(
+            &image_digest(),
+            IMAGE_MANIFEST,
+            &evidence,
+            &public_key_trust(&key),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, VerifyError::SignatureInvalid { reason } if reason.contains("payload signs")),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn one_valid_signature_among_bad_ones_is_enough() {
+        let key = signing_key(7);
+        let evidence = [
+            simple_signing(&signing_key(9), &image_digest()),
+            simple_signing(&key, &image_digest()),
+        ];
+        verify_oci_image_signature(
+            &image_digest(),
+            IMAGE_MANIFEST,
+            &evidence,
+            &public_key_trust(&key),
+        )
+        .expect("second signature verifies");
+    }
+
+    #[test]
+    fn image_without_evidence_is_unsigned() {
+        let err = verify_oci_image_signature(
+            &image_digest(),
+            IMAGE_MANIFEST,
+            &[],
+            &public_key_trust(&signing_key(7)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, VerifyError::Unsigned { .. }), "got {err:?}");
+    }
+
+    #[test]
+    fn manifest_bytes_must_match_the_signed_digest() {
+        let key = signing_key(7);
+        let evidence = [simple_signing(&key, &image_digest())];
+        let err =
+            verify_oci_image_signature(&image_digest(), b"{}", &evidence, &public_key_trust(&key))
+                .unwrap_err();
+        assert!(
+            matches!(&err, VerifyError::SignatureInvalid { reason } if reason.contains("hash to")),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn evidence_of_the_wrong_kind_never_verifies() {
+        let keyless = CosignTrust::Keyless {
+            trusted_root_json: b"{}".to_vec(),
+            certificate_identity: "id".to_string(),
+            certificate_oidc_issuer: "issuer".to_string(),
+        };
+        let evidence = [simple_signing(&signing_key(7), &image_digest())];
+        let err = verify_oci_image_signature(&image_digest(), IMAGE_MANIFEST, &evidence, &keyless)
+            .unwrap_err();
+        assert!(
+            matches!(&err, VerifyError::SignatureInvalid { reason } if reason.contains("does not apply")),
+            "got {err:?}"
+        );
+
+        let bundle = [CosignEvidence::Bundle {
+            bundle_json: b"{}".to_vec(),
+        }];
+        let err = verify_oci_image_signature(
+            &image_digest(),
+            IMAGE_MANIFEST,
+            &bundle,
+            &public_key_trust(&signing_key(7)),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, VerifyError::SignatureInvalid { reason } if reason.contains("keyed sigstore bundles are not supported")),
+            "got {err:?}"
+        );
+    }
+
+    /// A real public-good keyless bundle (sigstore-protobuf-specs'
+    /// `a.txt` sample) and the Sigstore production trust root,
+    /// verified offline. The message stands in for a manifest.
+    #[cfg(feature = "manifest-verify")]
+    fn keyless_fixture() -> (Vec<u8>, CosignEvidence) {
+        const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sigstore");
+        let read = |name: &str| fs::read(format!("{FIXTURES}/{name}")).expect("fixture");
+        let bundle = CosignEvidence::Bundle {
+            bundle_json: read("a.txt.sigstore"),
+        };
+        (read("a.txt"), bundle)
+    }
+
+    #[cfg(feature = "manifest-verify")]
+    fn keyless_fixture_trust(identity: &str) -> CosignTrust {
+        CosignTrust::Keyless {
+            trusted_root_json: fs::read(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/sigstore/trusted_root.json"
+            ))
+            .expect("trusted root fixture"),
+            certificate_identity: identity.to_string(),
+            certificate_oidc_issuer: "https://github.com/login/oauth".to_string(),
+        }
+    }
+
+    #[cfg(feature = "manifest-verify")]
+    #[test]
+    fn keyless_bundle_verifies_offline_against_the_trust_root() {
+        let (message, bundle) = keyless_fixture();
+        let digest = format!("sha256:{}", hex_sha256(&message));
+        verify_oci_image_signature(
+            &digest,
+            &message,
+            &[bundle],
+            &keyless_fixture_trust("a@tny.town"),
+        )
+        .expect("real keyless bundle verifies");
+    }
+
+    #[cfg(feature = "manifest-verify")]
+    #[test]
+    fn keyless_bundle_for_another_identity_is_rejected() {
+        let (message, bundle) = keyless_fixture();
+        let digest = format!("sha256:{}", hex_sha256(&message));
+        let err = verify_oci_image_signature(
+            &digest,
+            &message,
+            &[bundle],
+            &keyless_fixture_trust("someone@else.example"),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, VerifyError::SignatureInvalid { reason } if reason.contains("bundle verification failed")),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn garbage_bundle_is_rejected_under_keyless_trust() {
+        // Feature-on fails at the bundle parse, feature-off at the
+        // disabled-feature guard; either way nothing verifies.
+        let keyless = CosignTrust::Keyless {
+            trusted_root_json: b"{}".to_vec(),
+            certificate_identity: "id".to_string(),
+            certificate_oidc_issuer: "issuer".to_string(),
+        };
+        let bundle = [CosignEvidence::Bundle {
+            bundle_json: b"not a bundle".to_vec(),
+        }];
+        let err = verify_oci_image_signature(&image_digest(), IMAGE_MANIFEST, &bundle, &keyless)
+            .unwrap_err();
+        assert!(
+            matches!(err, VerifyError::SignatureInvalid { .. }),
+            "got {err:?}"
+        );
+    }
 }
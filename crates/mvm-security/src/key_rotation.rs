@@ -21,13 +21,12 @@
 //!
 //! ## Scope boundary
 //!
-//! Per plan 45 §D5 / plan 63 §"Convergence rule", the actual
-//! `EncryptedBackend<B>` decorator + AEAD/AES-SIV/HKDF crypto code
-//! live in mvmd, not mvm. This module's `rewrap_dek` therefore
-//! supports `WrapAlgorithm::Aes256Gcm` (mvm-side substrate via
-//! [`snapshot_crypto`]) and returns
-//! [`RotationError::UnsupportedAlgorithm`] for `WrapAlgorithm::AesKwp`
-//! — mvmd implements the AES-KWP unwrap path.
+//! mvm-storage's `EncryptedBackend<B>` wraps its per-volume DEKs
+//! with `WrapAlgorithm::Aes256Gcm` (via [`snapshot_crypto`]) and
+//! rotates them through [`rewrap_dek`]. The AES-KWP envelopes mvmd
+//! produces are out of scope here: `rewrap_dek` returns
+//! [`RotationError::UnsupportedAlgorithm`] for
+//! `WrapAlgorithm::AesKwp` — mvmd implements that unwrap path.
 //!
 //! ## Idempotency
 //!
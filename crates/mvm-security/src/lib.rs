@@ -1,3 +1,4 @@
+pub mod approval;
 pub mod attestation;
 pub mod command_gate;
 pub mod image_verify;
@@ -5,10 +6,13 @@ pub mod key_rotation;
 pub mod keystore;
 pub mod policy;
 pub mod posture;
+pub mod quota_meter;
 pub mod rate_limiter;
+pub mod registry_credentials;
 pub mod seccomp;
 pub mod secret_store;
 pub mod snapshot_crypto;
 pub mod snapshot_encryption;
 pub mod snapshot_hmac;
 pub mod threat_classifier;
+pub mod threat_gate;
@@ -0,0 +1,365 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use mvm_core::domain::instance::BackpressureReason;
+use mvm_core::security::FsQuotaPolicy;
+
+/// What one filesystem RPC call costs against the meter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsUsage {
+    /// Metadata-only call (`FsStat`, `FsList`, `FsMkdir`, ...). Counts
+    /// as one op and moves no bytes.
+    Meta,
+    /// Single-shot read of up to this many bytes.
+    Read(u64),
+    /// Single-shot write of this many bytes.
+    Write(u64),
+    /// Streaming transfer open. Carries the bytes known up front (the
+    /// declared upload size, `0` for a download, whose size is only
+    /// known once the agent opens it — see
+    /// [`QuotaMeter::record_streamed`]). Streams are flow-controlled
+    /// by vsock, so they count against ops and the session budget but
+    /// not the per-second byte rates.
+    Stream(u64),
+}
+
+/// Which limit rejected a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaExceeded {
+    OpsPerSecond,
+    ReadBytesPerSecond,
+    WriteBytesPerSecond,
+    SessionBytes,
+}
+
+impl QuotaExceeded {
+    /// Wire reason reported to the caller.
+    pub fn reason(self) -> BackpressureReason {
+        match self {
+            Self::SessionBytes => BackpressureReason::FsSessionQuotaExhausted,
+            _ => BackpressureReason::FsRateLimited,
+        }
+    }
+
+    /// Bounded, operator-facing detail naming the limit. Never carries
+    /// paths or payload.
+    pub fn detail(self, policy: &FsQuotaPolicy) -> String {
+        match self {
+            Self::OpsPerSecond => format!("fs ops limit {}/s", policy.ops_per_second),
+            Self::ReadBytesPerSecond => {
+                format!("fs read limit {} bytes/s", policy.read_bytes_per_second)
+            }
+            Self::WriteBytesPerSecond => {
+                format!("fs write limit {} bytes/s", policy.write_bytes_per_second)
+            }
+            Self::SessionBytes => format!("fs session limit {} bytes", policy.session_bytes),
+        }
+    }
+}
+
+/// Sliding-window quota meter for filesystem RPC.
+///
+/// Where [`crate::rate_limiter::RateLimiter`] counts frames, this
+/// meter weighs each call by the bytes it moves: ops per second, read
+/// and write bytes per second, and a total byte budget for the
+/// session. Like the frame limiter it is a pure data structure —
+/// callers drive it with `check_and_record()` before dispatching.
+///
+/// A call larger than a per-second byte limit is admitted when the
+/// window is otherwise empty, so a single read or write sized up to
+/// the per-call cap can never be starved; it then holds the window
+/// for a full second.
+///
+/// Zero values in the policy disable that specific limit.
+pub struct QuotaMeter {
+    policy: FsQuotaPolicy,
+    /// Timestamps of calls within the last second.
+    ops_window: VecDeque<Instant>,
+    /// `(timestamp, bytes)` of reads within the last second.
+    read_window: VecDeque<(Instant, u64)>,
+    /// `(timestamp, bytes)` of writes within the last second.
+    write_window: VecDeque<(Instant, u64)>,
+    /// Bytes moved since the meter was created.
+    session_bytes: u64,
+    /// Total calls allowed through.
+    pub allowed_count: u64,
+    /// Total calls rejected.
+    pub rejected_count: u64,
+}
+
+impl QuotaMeter {
+    /// Create a new meter from a policy.
+    pub fn new(policy: &FsQuotaPolicy) -> Self {
+        Self {
+            policy: *policy,
+            ops_window: VecDeque::new(),
+            read_window: VecDeque::new(),
+            write_window: VecDeque::new(),
+            session_bytes: 0,
+            allowed_count: 0,
+            rejected_count: 0,
+        }
+    }
+
+    /// The policy this meter enforces.
+    pub fn policy(&self) -> &FsQuotaPolicy {
+        &self.policy
+    }
+
+    /// Check whether a call costing `usage` is allowed now, and if so,
+    /// record it.
+    pub fn check_and_record(&mut self, usage: FsUsage) -> Result<(), QuotaExceeded> {
+        self.check_and_record_at(Instant::now(), usage)
+    }
+
+    /// Check and record at a specific instant (for testing).
+    pub fn check_and_record_at(
+        &mut self,
+        now: Instant,
+        usage: FsUsage,
+    ) -> Result<(), QuotaExceeded> {
+        self.expire_old(now);
+        if let Err(exceeded) = self.check(usage) {
+            self.rejected_count += 1;
+            return Err(exceeded);
+        }
+
+        self.ops_window.push_back(now);
+        match usage {
+            FsUsage::Read(n) => self.read_window.push_back((now, n)),
+            FsUsage::Write(n) => self.write_window.push_back((now, n)),
+            FsUsage::Meta | FsUsage::Stream(_) => {}
+        }
+        self.record_streamed(usage.bytes());
+        self.allowed_count += 1;
+        Ok(())
+    }
+
+    /// Charge bytes learned only after dispatch (a download's size)
+    /// against the session budget. Never rejects; the next call that
+    /// moves bytes sees the exhausted budget.
+    pub fn record_streamed(&mut self, bytes: u64) {
+        self.session_bytes = self.session_bytes.saturating_add(bytes);
+    }
+
+    fn check(&self, usage: FsUsage) -> Result<(), QuotaExceeded> {
+        let ops_limit = self.policy.ops_per_second as usize;
+        if ops_limit > 0 && self.ops_window.len() >= ops_limit {
+            return Err(QuotaExceeded::OpsPerSecond);
+        }
+
+        match usage {
+            FsUsage::Read(n)
+                if window_full(&self.read_window, self.policy.read_bytes_per_second, n) =>
+            {
+                return Err(QuotaExceeded::ReadBytesPerSecond);
+            }
+            FsUsage::Write(n)
+                if window_full(&self.write_window, self.policy.write_bytes_per_second, n) =>
+            {
+                return Err(QuotaExceeded::WriteBytesPerSecond);
+            }
+            _ => {}
+        }
+
+        let limit = self.policy.session_bytes;
+        if limit > 0
+            && usage != FsUsage::Meta
+            && (self.session_bytes >= limit
+                || self.session_bytes.saturating_add(usage.bytes()) > limit)
+        {
+            return Err(QuotaExceeded::SessionBytes);
+        }
+        Ok(())
+    }
+
+    /// Remove entries older than one second from every window.
+    fn expire_old(&mut self, now: Instant) {
+        let one_second_ago = now.checked_sub(Duration::from_secs(1)).unwrap_or(now);
+
+        while self
+            .ops_window
+            .front()
+            .is_some_and(|&t| t <= one_second_ago)
+        {
+            self.ops_window.pop_front();
+        }
+        for window in [&mut self.read_window, &mut self.write_window] {
+            while window.front().is_some_and(|&(t, _)| t <= one_second_ago) {
+                window.pop_front();
+            }
+        }
+    }
+
+    /// Bytes moved since the meter was created.
+    pub fn session_bytes(&self) -> u64 {
+        self.session_bytes
+    }
+
+    /// Replace the session total with one kept outside this meter —
+    /// a ledger several host processes share.
+    pub fn restore_session_bytes(&mut self, bytes: u64) {
+        self.session_bytes = bytes;
+    }
+
+    /// Whether every limit is disabled.
+    pub fn is_unlimited(&self) -> bool {
+        self.policy == FsQuotaPolicy::unlimited()
+    }
+}
+
+impl FsUsage {
+    /// Bytes this call moves up front.
+    pub fn bytes(self) -> u64 {
+        match self {
+            Self::Meta => 0,
+            Self::Read(n) | Self::Write(n) | Self::Stream(n) => n,
+        }
+    }
+}
+
+/// Whether adding `bytes` to `window` would exceed `limit` (0 =
+/// unlimited). An empty window always admits one call.
+fn window_full(window: &VecDeque<(Instant, u64)>, limit: u64, bytes: u64) -> bool {
+    if limit == 0 || window.is_empty() {
+        return false;
+    }
+    let used: u64 = window.iter().map(|&(_, n)| n).sum();
+    used.saturating_add(bytes) > limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(ops: u32, read: u64, write: u64, session: u64) -> FsQuotaPolicy {
+        FsQuotaPolicy {
+            ops_per_second: ops,
+            read_bytes_per_second: read,
+            write_bytes_per_second: write,
+            session_bytes: session,
+        }
+    }
+
+    #[test]
+    fn test_ops_per_second_limit() {
+        let mut meter = QuotaMeter::new(&policy(3, 0, 0, 0));
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(meter.check_and_record_at(now, FsUsage::Meta), Ok(()));
+        }
+        assert_eq!(
+            meter.check_and_record_at(now, FsUsage::Meta),
+            Err(QuotaExceeded::OpsPerSecond)
+        );
+        assert_eq!(meter.allowed_count, 3);
+        assert_eq!(meter.rejected_count, 1);
+
+        // The window slides after a second.
+        let later = now + Duration::from_millis(1001);
+        assert_eq!(meter.check_and_record_at(later, FsUsage::Meta), Ok(()));
+    }
+
+    #[test]
+    fn test_read_and_write_bytes_are_metered_separately() {
+        let mut meter = QuotaMeter::new(&policy(0, 100, 50, 0));
+        let now = Instant::now();
+
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Read(60)), Ok(()));
+        assert_eq!(
+            meter.check_and_record_at(now, FsUsage::Read(41)),
+            Err(QuotaExceeded::ReadBytesPerSecond)
+        );
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Read(40)), Ok(()));
+
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Write(50)), Ok(()));
+        assert_eq!(
+            meter.check_and_record_at(now, FsUsage::Write(1)),
+            Err(QuotaExceeded::WriteBytesPerSecond)
+        );
+        // Metadata calls are unaffected by saturated byte windows.
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Meta), Ok(()));
+    }
+
+    #[test]
+    fn test_oversized_call_admitted_into_empty_window() {
+        let mut meter = QuotaMeter::new(&policy(0, 10, 0, 0));
+        let now = Instant::now();
+
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Read(1000)), Ok(()));
+        assert_eq!(
+            meter.check_and_record_at(now + Duration::from_millis(500), FsUsage::Read(1)),
+            Err(QuotaExceeded::ReadBytesPerSecond)
+        );
+        assert_eq!(
+            meter.check_and_record_at(now + Duration::from_millis(1001), FsUsage::Read(1)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_session_budget_counts_streams_and_post_dispatch_bytes() {
+        let mut meter = QuotaMeter::new(&policy(0, 0, 0, 100));
+        let now = Instant::now();
+
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Write(30)), Ok(()));
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Stream(50)), Ok(()));
+        assert_eq!(
+            meter.check_and_record_at(now, FsUsage::Read(21)),
+            Err(QuotaExceeded::SessionBytes)
+        );
+
+        // A download opens with no bytes known, then charges its size.
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Stream(0)), Ok(()));
+        meter.record_streamed(40);
+        assert_eq!(meter.session_bytes(), 120);
+        assert_eq!(
+            meter.check_and_record_at(now, FsUsage::Stream(0)),
+            Err(QuotaExceeded::SessionBytes)
+        );
+        // Metadata calls still pass once the budget is spent.
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Meta), Ok(()));
+
+        // A total restored from a shared ledger replaces this meter's own.
+        meter.restore_session_bytes(90);
+        assert_eq!(meter.check_and_record_at(now, FsUsage::Write(10)), Ok(()));
+        assert_eq!(meter.session_bytes(), 100);
+    }
+
+    #[test]
+    fn test_unlimited_policy_admits_everything() {
+        let mut meter = QuotaMeter::new(&FsQuotaPolicy::unlimited());
+        assert!(meter.is_unlimited());
+        let now = Instant::now();
+        for _ in 0..10_000 {
+            assert_eq!(
+                meter.check_and_record_at(now, FsUsage::Read(u64::MAX / 2)),
+                Ok(())
+            );
+        }
+        assert_eq!(meter.rejected_count, 0);
+    }
+
+    #[test]
+    fn test_rejections_map_to_backpressure_reasons() {
+        let p = policy(5, 10, 20, 30);
+        assert_eq!(
+            QuotaExceeded::OpsPerSecond.reason(),
+            BackpressureReason::FsRateLimited
+        );
+        assert_eq!(
+            QuotaExceeded::WriteBytesPerSecond.reason(),
+            BackpressureReason::FsRateLimited
+        );
+        assert_eq!(
+            QuotaExceeded::SessionBytes.reason(),
+            BackpressureReason::FsSessionQuotaExhausted
+        );
+        assert_eq!(QuotaExceeded::OpsPerSecond.detail(&p), "fs ops limit 5/s");
+        assert_eq!(
+            QuotaExceeded::SessionBytes.detail(&p),
+            "fs session limit 30 bytes"
+        );
+    }
+}
@@ -0,0 +1,542 @@
+//! OCI registry credentials.
+//!
+//! mvm keeps its own registry credentials rather than reading
+//! `~/.docker/config.json`. Two sources feed an OCI pull:
+//!
+//! - [`RegistryCredentialStore`] — per-registry entries in the
+//!   [`SecretStore`], under the reserved namespace
+//!   [`REGISTRY_CREDENTIALS_TENANT`]. `mvmctl image login` writes
+//!   here; entries are encrypted at rest like every other secret.
+//! - [`DockerCredentialHelper`] — delegation to a
+//!   `docker-credential-<name>` binary (ecr-login, gcloud,
+//!   osxkeychain, …) speaking the docker credential-helper protocol.
+//!   Helpers are only run when the operator names one; mvm never
+//!   discovers them from Docker's config.
+//!
+//! Which source applies to which registry is the caller's policy
+//! (`mvmctl`'s `registry-auth.toml`). This module only stores,
+//! fetches and redacts.
+//!
+//! A [`RegistryCredential`] never prints its secret: `Debug` redacts,
+//! there is no `Display`, and helper failures report the exit status
+//! and stderr only — never stdout, which carries the secret.
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use secrecy::{ExposeSecret, SecretBox, SecretString};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::secret_store::SecretStore;
+
+/// Secret-store namespace holding registry credentials. Distinct
+/// from any tenant id `mvmctl secret` accepts by convention (tenants
+/// are `local` or fleet ids), so registry logins never show up in a
+/// tenant's secret listing.
+pub const REGISTRY_CREDENTIALS_TENANT: &str = "oci-registries";
+
+/// Username docker credential helpers return when `Secret` is an
+/// identity (refresh) token rather than a password.
+pub const IDENTITY_TOKEN_USERNAME: &str = "<token>";
+
+/// How long a credential helper may run before it is killed.
+pub const CREDENTIAL_HELPER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One registry's credential.
+pub enum RegistryCredential {
+    /// Username and password (or personal access token), sent as
+    /// HTTP basic auth to the registry's token endpoint.
+    Basic {
+        username: String,
+        password: SecretString,
+    },
+    /// OAuth2 refresh token, exchanged for a short-lived access
+    /// token before use (`mvm_oci::exchange_identity_token`).
+    IdentityToken { token: SecretString },
+    /// Pre-minted bearer token, sent as-is.
+    Bearer { token: SecretString },
+}
+
+impl std::fmt::Debug for RegistryCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("RegistryCredential::Basic")
+                .field("username", username)
+                .field("password", &"REDACTED")
+                .finish(),
+            Self::IdentityToken { .. } => {
+                f.write_str("RegistryCredential::IdentityToken { token: REDACTED }")
+            }
+            Self::Bearer { .. } => f.write_str("RegistryCredential::Bearer { token: REDACTED }"),
+        }
+    }
+}
+
+impl RegistryCredential {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Basic { .. } => "basic",
+            Self::IdentityToken { .. } => "identity_token",
+            Self::Bearer { .. } => "bearer",
+        }
+    }
+
+    fn to_record(&self) -> StoredCredential {
+        let (kind, username, secret) = match self {
+            Self::Basic { username, password } => ("basic", Some(username.clone()), password),
+            Self::IdentityToken { token } => ("identity_token", None, token),
+            Self::Bearer { token } => ("bearer", None, token),
+        };
+        StoredCredential {
+            kind: kind.to_string(),
+            username,
+            secret: secret.expose_secret().to_string(),
+        }
+    }
+
+    fn from_record(mut record: StoredCredential) -> Result<Self> {
+        let secret = SecretString::from(std::mem::take(&mut record.secret));
+        match (record.kind.as_str(), record.username.take()) {
+            ("basic", Some(username)) => Ok(Self::Basic {
+                username,
+                password: secret,
+            }),
+            ("identity_token", None) => Ok(Self::IdentityToken { token: secret }),
+            ("bearer", None) => Ok(Self::Bearer { token: secret }),
+            (kind, _) => bail!("malformed registry credential record (kind {kind:?})"),
+        }
+    }
+}
+
+/// On-store JSON shape. Never logged; `secret` is zeroized on drop.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StoredCredential {
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    secret: String,
+}
+
+/// Secret-store entry name for `registry`. Registry hosts are DNS
+/// names with an optional port; `.` maps to `_` and `:` to `__`,
+/// which is reversible because neither `_` nor `..` can appear in a
+/// valid host.
+pub fn registry_entry_name(registry: &str) -> Result<String> {
+    if registry.is_empty() {
+        bail!("registry host must not be empty");
+    }
+    let mut name = String::with_capacity(registry.len() + 2);
+    for ch in registry.chars() {
+        match ch {
+            c if c.is_ascii_alphanumeric() || c == '-' => name.push(c.to_ascii_lowercase()),
+            '.' => name.push('_'),
+            ':' => name.push_str("__"),
+            other => bail!("registry host {registry:?} contains unsupported character {other:?}"),
+        }
+    }
+    Ok(name)
+}
+
+fn registry_from_entry_name(name: &str) -> String {
+    name.replace("__", ":").replace('_', ".")
+}
+
+/// Registry logins kept in the mvm secret store.
+pub struct RegistryCredentialStore<'a> {
+    store: &'a dyn SecretStore,
+}
+
+impl<'a> RegistryCredentialStore<'a> {
+    pub fn new(store: &'a dyn SecretStore) -> Self {
+        Self { store }
+    }
+
+    /// Store or replace the credential for `registry`.
+    pub fn put(&self, registry: &str, credential: &RegistryCredential) -> Result<()> {
+        let json = Zeroizing::new(
+            serde_json::to_string(&credential.to_record())
+                .context("serialize registry credential")?,
+        );
+        self.store.put(
+            REGISTRY_CREDENTIALS_TENANT,
+            &registry_entry_name(registry)?,
+            &SecretBox::new(Box::new(json.to_string())),
+        )
+    }
+
+    /// The credential stored for `registry`, or `None` when there is
+    /// no login for it.
+    pub fn get(&self, registry: &str) -> Result<Option<RegistryCredential>> {
+        let name = registry_entry_name(registry)?;
+        if !self
+            .store
+            .list(REGISTRY_CREDENTIALS_TENANT)?
+            .contains(&name)
+        {
+            return Ok(None);
+        }
+        let raw = self.store.get(REGISTRY_CREDENTIALS_TENANT, &name)?;
+        let record: StoredCredential = serde_json::from_str(raw.expose_secret())
+            .map_err(|_| anyhow!("stored credential for {registry} is malformed"))?;
+        RegistryCredential::from_record(record).map(Some)
+    }
+
+    /// Remove the login for `registry`. Returns `false` when there
+    /// was none.
+    pub fn delete(&self, registry: &str) -> Result<bool> {
+        let name = registry_entry_name(registry)?;
+        if !self
+            .store
+            .list(REGISTRY_CREDENTIALS_TENANT)?
+            .contains(&name)
+        {
+            return Ok(false);
+        }
+        self.store.delete(REGISTRY_CREDENTIALS_TENANT, &name)?;
+        Ok(true)
+    }
+
+    /// Registries with a stored login.
+    pub fn registries(&self) -> Result<Vec<String>> {
+        Ok(self
+            .store
+            .list(REGISTRY_CREDENTIALS_TENANT)?
+            .iter()
+            .map(|name| registry_from_entry_name(name))
+            .collect())
+    }
+}
+
+impl Drop for StoredCredential {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.secret.zeroize();
+    }
+}
+
+/// Response body of `docker-credential-<name> get`.
+#[derive(Deserialize)]
+struct HelperResponse {
+    #[serde(rename = "Username")]
+    username: String,
+    #[serde(rename = "Secret")]
+    secret: String,
+}
+
+/// A `docker-credential-<name>` binary.
+// allow(secret-debug): names a helper program; holds no credential material.
+#[derive(Debug, Clone)]
+pub struct DockerCredentialHelper {
+    name: String,
+    program: PathBuf,
+    timeout: Duration,
+}
+
+impl DockerCredentialHelper {
+    /// The helper `docker-credential-<name>`, resolved on `$PATH`.
+    /// `name` is the suffix (`ecr-login`, `gcloud`, …) and is limited
+    /// to the characters helper binaries use, so it cannot smuggle a
+    /// path.
+    pub fn new(name: &str) -> Result<Self> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            || name.starts_with('.')
+        {
+            bail!("invalid docker credential helper name {name:?}");
+        }
+        Ok(Self {
+            name: name.to_string(),
+            program: PathBuf::from(format!("docker-credential-{name}")),
+            timeout: CREDENTIAL_HELPER_TIMEOUT,
+        })
+    }
+
+    /// Run `program` instead of looking the helper up on `$PATH`.
+    pub fn with_program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Ask the helper for `registry`'s credential. `None` when the
+    /// helper reports it has none ("credentials not found").
+    pub fn get(&self, registry: &str) -> Result<Option<RegistryCredential>> {
+        let mut child = Command::new(&self.program)
+            .arg("get")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("running docker-credential-{}", self.name))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A helper that exits without reading stdin closes the
+            // pipe; its exit status says what went wrong.
+            let _ = stdin.write_all(registry.as_bytes());
+        }
+        let stdout = child.stdout.take().map(read_to_end_in_background);
+        let stderr = child.stderr.take().map(read_to_end_in_background);
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "docker-credential-{} timed out after {:?}",
+                    self.name,
+                    self.timeout
+                );
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        let stdout = Zeroizing::new(join_output(stdout));
+        let stderr = join_output(stderr);
+
+        if !status.success() {
+            let stdout_text = String::from_utf8_lossy(&stdout);
+            if is_not_found(&stdout_text) || is_not_found(&String::from_utf8_lossy(&stderr)) {
+                return Ok(None);
+            }
+            bail!(
+                "docker-credential-{} get failed ({status}): {}",
+                self.name,
+                String::from_utf8_lossy(&stderr).trim()
+            );
+        }
+        let response: HelperResponse = serde_json::from_slice(&stdout).map_err(|_| {
+            anyhow!(
+                "docker-credential-{} returned a malformed response",
+                self.name
+            )
+        })?;
+        let HelperResponse { username, secret } = response;
+        let secret = SecretString::from(secret);
+        if secret.expose_secret().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(if username == IDENTITY_TOKEN_USERNAME {
+            RegistryCredential::IdentityToken { token: secret }
+        } else {
+            RegistryCredential::Basic {
+                username,
+                password: secret,
+            }
+        }))
+    }
+}
+
+fn read_to_end_in_background(
+    mut pipe: impl Read + Send + 'static,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+fn join_output(handle: Option<std::thread::JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    handle.and_then(|h| h.join().ok()).unwrap_or_default()
+}
+
+/// The helper protocol's "no entry" answer. Helpers print it on
+/// stdout and exit non-zero.
+fn is_not_found(output: &str) -> bool {
+    output
+        .to_ascii_lowercase()
+        .contains("credentials not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret_store::FileSecretStore;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn fake_helper(dir: &std::path::Path, script: &str) -> DockerCredentialHelper {
+        let path = dir.join("docker-credential-fake");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        DockerCredentialHelper::new("fake")
+            .unwrap()
+            .with_program(path)
+    }
+
+    #[test]
+    fn entry_names_round_trip_hosts_and_ports() {
+        for host in ["ghcr.io", "registry.example.test:5000", "my-registry.local"] {
+            let name = registry_entry_name(host).unwrap();
+            crate::keystore::validate_shell_id(&name).unwrap();
+            assert_eq!(registry_from_entry_name(&name), host);
+        }
+    }
+
+    #[test]
+    fn entry_name_rejects_path_characters() {
+        assert!(registry_entry_name("ghcr.io/../x").is_err());
+        assert!(registry_entry_name("").is_err());
+    }
+
+    #[test]
+    fn store_round_trips_each_credential_kind() {
+        let tmp = tempfile::tempdir().unwrap();
+        let secrets = FileSecretStore::with_dir(tmp.path());
+        let store = RegistryCredentialStore::new(&secrets);
+
+        store
+            .put(
+                "ghcr.io",
+                &RegistryCredential::Basic {
+                    username: "alice".to_string(),
+                    password: SecretString::from("pat-123"),
+                },
+            )
+            .unwrap();
+        store
+            .put(
+                "registry.example.test:5000",
+                &RegistryCredential::IdentityToken {
+                    token: SecretString::from("refresh-456"),
+                },
+            )
+            .unwrap();
+
+        match store.get("ghcr.io").unwrap() {
+            Some(RegistryCredential::Basic { username, password }) => {
+                assert_eq!(username, "alice");
+                assert_eq!(password.expose_secret(), "pat-123");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match store.get("registry.example.test:5000").unwrap() {
+            Some(RegistryCredential::IdentityToken { token }) => {
+                assert_eq!(token.expose_secret(), "refresh-456");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        let mut registries = store.registries().unwrap();
+        registries.sort();
+        assert_eq!(registries, ["ghcr.io", "registry.example.test:5000"]);
+    }
+
+    #[test]
+    fn store_get_and_delete_of_unknown_registry_are_not_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let secrets = FileSecretStore::with_dir(tmp.path());
+        let store = RegistryCredentialStore::new(&secrets);
+        assert!(store.get("ghcr.io").unwrap().is_none());
+        assert!(!store.delete("ghcr.io").unwrap());
+    }
+
+    #[test]
+    fn credential_debug_redacts_secrets() {
+        let cred = RegistryCredential::Basic {
+            username: "alice".to_string(),
+            password: SecretString::from("pat-123"),
+        };
+        let shown = format!("{cred:?}");
+        assert!(shown.contains("alice"));
+        assert!(!shown.contains("pat-123"), "leaked: {shown}");
+        let token = RegistryCredential::IdentityToken {
+            token: SecretString::from("refresh-456"),
+        };
+        assert!(!format!("{token:?}").contains("refresh-456"));
+    }
+
+    #[test]
+    fn helper_basic_credentials_are_parsed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let helper = fake_helper(
+            tmp.path(),
+            r#"read host; [ "$1" = get ] && [ "$host" = ghcr.io ] || exit 3
+printf '{"ServerURL":"ghcr.io","Username":"bob","Secret":"pw-789"}'"#,
+        );
+        match helper.get("ghcr.io").unwrap() {
+            Some(RegistryCredential::Basic { username, password }) => {
+                assert_eq!(username, "bob");
+                assert_eq!(password.expose_secret(), "pw-789");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn helper_token_username_yields_identity_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        let helper = fake_helper(
+            tmp.path(),
+            r#"printf '{"Username":"<token>","Secret":"refresh-abc"}'"#,
+        );
+        assert!(matches!(
+            helper.get("ghcr.io").unwrap(),
+            Some(RegistryCredential::IdentityToken { .. })
+        ));
+    }
+
+    #[test]
+    fn helper_not_found_is_none() {
+        let tmp = tempfile::tempdir().unwrap();
+        let helper = fake_helper(
+            tmp.path(),
+            "echo 'credentials not found in native keychain'; exit 1",
+        );
+        assert!(helper.get("ghcr.io").unwrap().is_none());
+    }
+
+    #[test]
+    fn helper_failure_reports_stderr_but_never_stdout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let helper = fake_helper(
+            tmp.path(),
+            r#"printf '{"Username":"bob","Secret":"leaky-secret"}'; echo 'keychain locked' >&2; exit 1"#,
+        );
+        let err = format!("{:#}", helper.get("ghcr.io").unwrap_err());
+        assert!(err.contains("keychain locked"), "got: {err}");
+        assert!(!err.contains("leaky-secret"), "leaked: {err}");
+    }
+
+    #[test]
+    fn helper_malformed_output_does_not_echo_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let helper = fake_helper(tmp.path(), "echo 'Secret=plain-leak'");
+        let err = format!("{:#}", helper.get("ghcr.io").unwrap_err());
+        assert!(err.contains("malformed"), "got: {err}");
+        assert!(!err.contains("plain-leak"), "leaked: {err}");
+    }
+
+    #[test]
+    fn hung_helper_is_killed_at_the_timeout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let helper =
+            fake_helper(tmp.path(), "exec sleep 30").with_timeout(Duration::from_millis(200));
+        let err = helper.get("ghcr.io").unwrap_err();
+        assert!(err.to_string().contains("timed out"), "got: {err}");
+    }
+
+    #[test]
+    fn helper_name_cannot_be_a_path() {
+        assert!(DockerCredentialHelper::new("../evil").is_err());
+        assert!(DockerCredentialHelper::new("a/b").is_err());
+        assert!(DockerCredentialHelper::new("").is_err());
+        assert!(DockerCredentialHelper::new("ecr-login").is_ok());
+    }
+}
@@ -1,17 +1,33 @@
 //! `VolumeBackend` async trait — the contract every backing storage
 //! implementation honours.
+//!
+//! ## Streaming and ranged I/O
+//!
+//! `put` / `get` move whole `Bytes` buffers. Large objects go through
+//! [`VolumeBackend::get_stream`], [`VolumeBackend::put_stream`] and
+//! [`VolumeBackend::get_range`] instead. Every one of them has a
+//! default built on the buffered methods, so a backend only overrides
+//! what it can do natively — the defaults are correct but hold the
+//! whole object in memory.
 
+use std::ops::Range;
 use std::path::Path;
+use std::pin::Pin;
 
 use async_trait::async_trait;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 use mvm_core::volume::{VolumeEntry, VolumeError, VolumePath};
 
+/// Byte stream returned by [`VolumeBackend::get_stream`] and accepted
+/// by [`VolumeBackend::put_stream`].
+pub type VolumeReader = Pin<Box<dyn AsyncRead + Send>>;
+
 /// Storage backing for a volume.
 ///
-/// Implementations: `LocalBackend` (this crate), `ObjectStoreBackend`
-/// + `EncryptedBackend<B>` (mvmd-side, see plan 45 §D5).
+/// Implementations: `LocalBackend`, `ObjectStoreBackend` and the
+/// `EncryptedBackend<B>` decorator (all in this crate).
 ///
 /// All operations are scoped to the volume the backend was constructed
 /// for — the trait does not take an `org_id`/`workspace_id`/`name`
@@ -53,4 +69,68 @@ pub trait VolumeBackend: Send + Sync {
     /// into a guest (i.e., it's a real local filesystem path that
     /// virtiofsd can export). `None` for object-store backends in v1.
     fn local_export_path(&self) -> Option<&Path>;
+
+    /// Read the half-open byte range `range` of `key`. The range is
+    /// clamped to the object: reading past the end returns the bytes
+    /// that exist (possibly none). Errors with
+    /// [`VolumeError::NotFound`] if the key doesn't exist.
+    async fn get_range(&self, key: &VolumePath, range: Range<u64>) -> Result<Bytes, VolumeError> {
+        let data = self.get(key).await?;
+        Ok(slice_range(&data, range))
+    }
+
+    /// Open `key` for streaming reads. Errors with
+    /// [`VolumeError::NotFound`] up front if the key doesn't exist;
+    /// I/O failures mid-stream surface as read errors.
+    async fn get_stream(&self, key: &VolumePath) -> Result<VolumeReader, VolumeError> {
+        let data = self.get(key).await?;
+        Ok(Box::pin(std::io::Cursor::new(data)))
+    }
+
+    /// Write everything `data` yields to `key`, returning the byte
+    /// count. Same atomicity as [`VolumeBackend::put`]: a failed or
+    /// interrupted stream leaves the previous content (or no entry)
+    /// in place.
+    async fn put_stream(
+        &self,
+        key: &VolumePath,
+        mut data: VolumeReader,
+    ) -> Result<u64, VolumeError> {
+        let mut buf = BytesMut::new();
+        loop {
+            buf.reserve(64 * 1024);
+            if data.read_buf(&mut buf).await.map_err(VolumeError::Io)? == 0 {
+                break;
+            }
+        }
+        let len = buf.len() as u64;
+        self.put(key, buf.freeze()).await?;
+        Ok(len)
+    }
+
+    /// Append `data` to `key`, creating it if missing. Appends from
+    /// concurrent writers to the same key are not ordered against
+    /// each other; the default read-modify-write can lose one of
+    /// them.
+    async fn append(&self, key: &VolumePath, data: Bytes) -> Result<(), VolumeError> {
+        let existing = match self.get(key).await {
+            Ok(existing) => existing,
+            Err(VolumeError::NotFound(_)) => Bytes::new(),
+            Err(e) => return Err(e),
+        };
+        let mut joined = BytesMut::with_capacity(existing.len() + data.len());
+        joined.extend_from_slice(&existing);
+        joined.extend_from_slice(&data);
+        self.put(key, joined.freeze()).await
+    }
+}
+
+/// Clamp `range` to `data` and slice it.
+fn slice_range(data: &Bytes, range: Range<u64>) -> Bytes {
+    let len = data.len() as u64;
+    let end = range.end.min(len);
+    if range.start >= end {
+        return Bytes::new();
+    }
+    data.slice(range.start as usize..end as usize)
 }
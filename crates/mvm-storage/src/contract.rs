@@ -1,16 +1,17 @@
 //! Generic [`VolumeBackend`] contract test fixture.
 //!
-//! Every backend impl — [`crate::LocalBackend`], mvmd-side
-//! `ObjectStoreBackend`, mvmd-side `EncryptedBackend<B>` — must pass
+//! Every backend impl — [`crate::LocalBackend`],
+//! [`crate::ObjectStoreBackend`], [`crate::EncryptedBackend`] — must pass
 //! [`assert_backend_contract`] for the same set of operations.
 //!
 //! Re-export so mvmd can pull this in directly via the `mvmctl` facade.
 
 use bytes::Bytes;
+use tokio::io::AsyncReadExt;
 
 use mvm_core::volume::{VolumeError, VolumePath};
 
-use crate::backend::VolumeBackend;
+use crate::backend::{VolumeBackend, VolumeReader};
 
 /// Run the full trait contract against `backend`. Panics on the first
 /// violation, with a message identifying the failed assertion.
@@ -29,12 +30,20 @@ pub async fn assert_backend_contract<B: VolumeBackend>(backend: &B) {
     rename_to_existing_is_already_exists(backend).await;
     stat_returns_metadata(backend).await;
     list_returns_entries(backend).await;
+    get_range_slices_and_clamps(backend).await;
+    get_stream_round_trip(backend).await;
+    put_stream_round_trip(backend).await;
+    append_creates_then_extends(backend).await;
 }
 
 fn key(s: &str) -> VolumePath {
     VolumePath::new(s).expect("valid test key")
 }
 
+fn reader(data: &'static [u8]) -> VolumeReader {
+    Box::pin(std::io::Cursor::new(data))
+}
+
 async fn health_check_passes<B: VolumeBackend>(b: &B) {
     b.health_check()
         .await
@@ -187,6 +196,108 @@ async fn list_returns_entries<B: VolumeBackend>(b: &B) {
     b.delete(&nested).await.expect("contract: backend op");
 }
 
+async fn get_range_slices_and_clamps<B: VolumeBackend>(b: &B) {
+    let k = key("contract/range/file.bin");
+    b.put(&k, Bytes::from_static(b"0123456789"))
+        .await
+        .expect("contract: backend op");
+    let got = b.get_range(&k, 2..5).await.expect("contract: get_range");
+    assert_eq!(
+        &got[..],
+        b"234",
+        "contract: get_range must return the slice"
+    );
+    let got = b.get_range(&k, 8..64).await.expect("contract: get_range");
+    assert_eq!(
+        &got[..],
+        b"89",
+        "contract: get_range past the end must truncate"
+    );
+    for empty in [4..4, 20..30] {
+        let got = b
+            .get_range(&k, empty.clone())
+            .await
+            .expect("contract: get_range");
+        assert!(
+            got.is_empty(),
+            "contract: get_range {empty:?} must be empty, got {got:?}"
+        );
+    }
+    match b.get_range(&key("contract/range/missing"), 0..1).await {
+        Err(VolumeError::NotFound(_)) => {}
+        Ok(_) => panic!("contract: get_range on missing key must fail"),
+        Err(e) => panic!("contract: get_range on missing key must return NotFound, got: {e}"),
+    }
+    b.delete(&k).await.expect("contract: backend op");
+}
+
+async fn get_stream_round_trip<B: VolumeBackend>(b: &B) {
+    let k = key("contract/get-stream/file.txt");
+    b.put(&k, Bytes::from_static(b"streamed read"))
+        .await
+        .expect("contract: backend op");
+    let mut stream = b.get_stream(&k).await.expect("contract: get_stream");
+    let mut buf = Vec::new();
+    stream
+        .read_to_end(&mut buf)
+        .await
+        .expect("contract: reading get_stream");
+    assert_eq!(
+        &buf[..],
+        b"streamed read",
+        "contract: get_stream must yield exactly the bytes from put"
+    );
+    match b.get_stream(&key("contract/get-stream/missing")).await {
+        Err(VolumeError::NotFound(_)) => {}
+        Ok(_) => panic!("contract: get_stream on missing key must fail"),
+        Err(e) => panic!("contract: get_stream on missing key must return NotFound, got: {e}"),
+    }
+    b.delete(&k).await.expect("contract: backend op");
+}
+
+async fn put_stream_round_trip<B: VolumeBackend>(b: &B) {
+    let k = key("contract/put-stream/file.txt");
+    let n = b
+        .put_stream(&k, reader(b"first"))
+        .await
+        .expect("contract: put_stream");
+    assert_eq!(n, 5, "contract: put_stream must report bytes written");
+    let n = b
+        .put_stream(&k, reader(b"second, longer"))
+        .await
+        .expect("contract: put_stream");
+    assert_eq!(n, 14, "contract: put_stream must report bytes written");
+    let bytes = b.get(&k).await.expect("contract: backend op");
+    assert_eq!(
+        &bytes[..],
+        b"second, longer",
+        "contract: put_stream must overwrite like put"
+    );
+    b.delete(&k).await.expect("contract: backend op");
+}
+
+async fn append_creates_then_extends<B: VolumeBackend>(b: &B) {
+    let k = key("contract/append/log.txt");
+    b.append(&k, Bytes::from_static(b"one\n"))
+        .await
+        .expect("contract: append to missing key must create it");
+    b.append(&k, Bytes::from_static(b"two\n"))
+        .await
+        .expect("contract: append");
+    let bytes = b.get(&k).await.expect("contract: backend op");
+    assert_eq!(
+        &bytes[..],
+        b"one\ntwo\n",
+        "contract: append must extend existing content"
+    );
+    let entry = b.stat(&k).await.expect("contract: stat after append");
+    assert_eq!(
+        entry.size, 8,
+        "contract: stat size must include appended bytes"
+    );
+    b.delete(&k).await.expect("contract: backend op");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
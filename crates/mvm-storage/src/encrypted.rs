@@ -0,0 +1,563 @@
+//! `EncryptedBackend<B>` — client-side AES-256-GCM over any
+//! [`VolumeBackend`].
+//!
+//! ## Key hierarchy
+//!
+//! Each volume has one random 256-bit data key (DEK). The DEK never
+//! touches the backing store; it is persisted by the caller as a
+//! [`WrappedKey`] (AES-256-GCM under a versioned master key, the
+//! `mvm_security::snapshot_crypto` envelope) in the volume record.
+//! Master keys come from a [`MasterKeySource`]:
+//!
+//! - [`TenantKeySource`] — the tenant's data key from an
+//!   `mvm_security::keystore::KeyProvider` (env / file / OS keyring).
+//!   Unversioned, so it always answers as version 1.
+//! - [`RotatingKeySource`] — the versioned master keys that
+//!   `mvm_security::key_rotation::rotate_master_key` maintains under
+//!   `~/.mvm/master-keys/<org_id>/`.
+//!
+//! Master rotation never touches object data: [`rewrap_volume_key`]
+//! re-wraps the DEK under the active master version via
+//! `key_rotation::rewrap_dek`, and the caller swaps the stored
+//! `WrappedKey`.
+//!
+//! ## Object format
+//!
+//! Every object is stored as
+//! `MAGIC (4) || nonce (12) || ciphertext || tag (16)`, with a fresh
+//! nonce per put. The associated data is `MAGIC || key path`, so a
+//! ciphertext copied or renamed to another key fails authentication
+//! instead of silently decrypting. That binding is also why `rename`
+//! re-encrypts rather than delegating to the inner backend's rename.
+//!
+//! Sizes reported by `stat` / `list` are plaintext sizes.
+//!
+//! Streaming, ranged and append I/O use the trait's buffered defaults:
+//! each object is a single AEAD seal, so there is no way to authenticate
+//! a byte range without reading (and decrypting) the whole object.
+
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use async_trait::async_trait;
+use bytes::{BufMut, Bytes, BytesMut};
+use secrecy::{ExposeSecret, SecretBox};
+
+use mvm_core::volume::{
+    MasterKeyState, VolumeEntry, VolumeError, VolumePath, WrapAlgorithm, WrappedKey,
+};
+use mvm_security::key_rotation;
+use mvm_security::keystore::KeyProvider;
+use mvm_security::snapshot_crypto;
+
+use crate::backend::VolumeBackend;
+
+/// Leading bytes of every encrypted object. Bumped if the envelope
+/// layout ever changes.
+pub const MAGIC: &[u8; 4] = b"MVE1";
+
+const NONCE_SIZE: usize = snapshot_crypto::NONCE_SIZE;
+const TAG_SIZE: usize = snapshot_crypto::TAG_SIZE;
+
+/// Bytes an encrypted object carries on top of its plaintext.
+pub const OVERHEAD: u64 = (MAGIC.len() + NONCE_SIZE + TAG_SIZE) as u64;
+
+/// Source of the versioned master keys that wrap volume DEKs.
+pub trait MasterKeySource: Send + Sync {
+    /// Version new wraps should use.
+    fn active_version(&self) -> Result<u32, VolumeError>;
+
+    /// Key bytes for `version`. Fails for unknown or revoked versions.
+    fn master_key(&self, version: u32) -> Result<SecretBox<Vec<u8>>, VolumeError>;
+}
+
+/// Master key from a tenant [`KeyProvider`]. Providers hold a single
+/// key per tenant, so this source only knows version 1.
+pub struct TenantKeySource {
+    provider: Box<dyn KeyProvider>,
+    tenant: String,
+}
+
+impl TenantKeySource {
+    pub fn new(provider: Box<dyn KeyProvider>, tenant: impl Into<String>) -> Self {
+        Self {
+            provider,
+            tenant: tenant.into(),
+        }
+    }
+}
+
+impl MasterKeySource for TenantKeySource {
+    fn active_version(&self) -> Result<u32, VolumeError> {
+        Ok(1)
+    }
+
+    fn master_key(&self, version: u32) -> Result<SecretBox<Vec<u8>>, VolumeError> {
+        if version != 1 {
+            return Err(VolumeError::Other(format!(
+                "tenant key provider has no master key version {version} (unversioned; only 1)"
+            )));
+        }
+        self.provider
+            .get_data_key(&self.tenant)
+            .map_err(|e| key_err("loading tenant master key", e))
+    }
+}
+
+/// Versioned master keys managed by `mvm_security::key_rotation` in
+/// `active_dir` (`v<N>.bin` + `manifest.json`).
+pub struct RotatingKeySource {
+    active_dir: PathBuf,
+}
+
+impl RotatingKeySource {
+    pub fn new(active_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            active_dir: active_dir.into(),
+        }
+    }
+}
+
+impl MasterKeySource for RotatingKeySource {
+    fn active_version(&self) -> Result<u32, VolumeError> {
+        let manifest = key_rotation::load_manifest(&self.active_dir)
+            .map_err(|e| key_err("loading master key manifest", e))?;
+        manifest
+            .entries
+            .iter()
+            .filter(|e| e.state == MasterKeyState::Active)
+            .map(|e| e.version)
+            .max()
+            .ok_or_else(|| {
+                VolumeError::Other(format!(
+                    "no active master key in {}; run a master key rotation first",
+                    self.active_dir.display()
+                ))
+            })
+    }
+
+    fn master_key(&self, version: u32) -> Result<SecretBox<Vec<u8>>, VolumeError> {
+        let manifest = key_rotation::load_manifest(&self.active_dir)
+            .map_err(|e| key_err("loading master key manifest", e))?;
+        match manifest.get(version).map(|e| e.state) {
+            Some(MasterKeyState::Active | MasterKeyState::Legacy) => {}
+            Some(MasterKeyState::Revoked) => {
+                return Err(VolumeError::Other(format!(
+                    "master key version {version} is revoked"
+                )));
+            }
+            None => {
+                return Err(VolumeError::Other(format!(
+                    "master key version {version} is not in {}",
+                    self.active_dir.display()
+                )));
+            }
+        }
+        let key = key_rotation::load_master_key(&self.active_dir, version)
+            .map_err(|e| key_err("loading master key", e))?;
+        Ok(SecretBox::new(Box::new(key.expose_secret().to_vec())))
+    }
+}
+
+/// Generate a fresh volume DEK wrapped under the active master key.
+/// Persist the result with the volume record and hand it to
+/// [`EncryptedBackend::new`].
+pub fn new_volume_key(keys: &dyn MasterKeySource) -> Result<WrappedKey, VolumeError> {
+    let version = keys.active_version()?;
+    let master = keys.master_key(version)?;
+    let dek = SecretBox::new(Box::new(Aes256Gcm::generate_key(OsRng).to_vec()));
+    let wrapped = snapshot_crypto::encrypt(dek.expose_secret(), master.expose_secret())
+        .map_err(|e| key_err("wrapping volume key", e))?;
+    Ok(WrappedKey {
+        master_key_version: version,
+        wrapped,
+        algorithm: WrapAlgorithm::Aes256Gcm,
+    })
+}
+
+/// Re-wrap `wrapped` under the active master version. Returns `None`
+/// when it is already current. Object data is untouched — only the
+/// returned `WrappedKey` needs persisting.
+pub fn rewrap_volume_key(
+    wrapped: &WrappedKey,
+    keys: &dyn MasterKeySource,
+) -> Result<Option<WrappedKey>, VolumeError> {
+    let active = keys.active_version()?;
+    if wrapped.master_key_version == active {
+        return Ok(None);
+    }
+    let old = keys.master_key(wrapped.master_key_version)?;
+    let new = keys.master_key(active)?;
+    key_rotation::rewrap_dek(wrapped, old.expose_secret(), new.expose_secret(), active)
+        .map(Some)
+        .map_err(|e| key_err("re-wrapping volume key", e))
+}
+
+/// Encrypting decorator over an inner [`VolumeBackend`].
+pub struct EncryptedBackend<B> {
+    inner: B,
+    dek: SecretBox<[u8; 32]>,
+}
+
+impl<B: VolumeBackend> EncryptedBackend<B> {
+    /// Unwrap `wrapped` with `keys` and wrap `inner`.
+    pub fn new(
+        inner: B,
+        wrapped: &WrappedKey,
+        keys: &dyn MasterKeySource,
+    ) -> Result<Self, VolumeError> {
+        if wrapped.algorithm != WrapAlgorithm::Aes256Gcm {
+            return Err(VolumeError::UnsupportedBackend {
+                kind: "encrypted",
+                reason: "only AES-256-GCM wrapped volume keys are supported in mvm-storage",
+            });
+        }
+        let master = keys.master_key(wrapped.master_key_version)?;
+        let unwrapped = SecretBox::new(Box::new(
+            snapshot_crypto::decrypt(&wrapped.wrapped, master.expose_secret())
+                .map_err(|e| key_err("unwrapping volume key", e))?,
+        ));
+        // The wrap authenticates, but a key wrapped by a buggy or foreign
+        // writer can still unwrap to the wrong length.
+        let unwrapped = unwrapped.expose_secret();
+        if unwrapped.len() != 32 {
+            return Err(VolumeError::Other(format!(
+                "unwrapped volume key is {} bytes, expected 32",
+                unwrapped.len()
+            )));
+        }
+        Ok(Self {
+            inner,
+            dek: SecretBox::init_with_mut(|dek: &mut [u8; 32]| dek.copy_from_slice(unwrapped)),
+        })
+    }
+
+    /// The wrapped backend. Reads through it return ciphertext.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(self.dek.expose_secret().into())
+    }
+
+    fn seal(&self, key: &VolumePath, plaintext: &[u8]) -> Result<Bytes, VolumeError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = aad(key);
+        let ct = self
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| VolumeError::Other(format!("encrypting {key} failed")))?;
+        let mut out = BytesMut::with_capacity(MAGIC.len() + NONCE_SIZE + ct.len());
+        out.put_slice(MAGIC);
+        out.put_slice(&nonce);
+        out.put_slice(&ct);
+        Ok(out.freeze())
+    }
+
+    fn open(&self, key: &VolumePath, sealed: &[u8]) -> Result<Bytes, VolumeError> {
+        if (sealed.len() as u64) < OVERHEAD || &sealed[..MAGIC.len()] != MAGIC {
+            return Err(VolumeError::Other(format!(
+                "{key} is not an encrypted volume object"
+            )));
+        }
+        let (nonce, ct) = sealed[MAGIC.len()..].split_at(NONCE_SIZE);
+        let aad = aad(key);
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ct, aad: &aad })
+            .map(Bytes::from)
+            .map_err(|_| {
+                VolumeError::Other(format!(
+                    "decrypting {key} failed: authentication tag mismatch \
+                     (wrong key, tampered data, or object moved outside the backend)"
+                ))
+            })
+    }
+
+    /// Re-encrypt every object under `from` to the same relative path
+    /// under `to`. Sources are left in place.
+    async fn copy_tree(&self, from: &VolumePath, to: &VolumePath) -> Result<(), VolumeError> {
+        let mut stack = vec![(from.clone(), to.clone())];
+        while let Some((src_dir, dst_dir)) = stack.pop() {
+            for entry in self.inner.list(&src_dir).await? {
+                let name = entry
+                    .path
+                    .as_str()
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(entry.path.as_str());
+                let dst = VolumePath::new(format!("{}/{name}", dst_dir.as_str()))
+                    .map_err(|e| VolumeError::InvalidPath(e.to_string()))?;
+                if entry.is_dir {
+                    stack.push((entry.path, dst));
+                } else {
+                    let plaintext = self.get(&entry.path).await?;
+                    self.put(&dst, plaintext).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: VolumeBackend> VolumeBackend for EncryptedBackend<B> {
+    fn kind(&self) -> &'static str {
+        "encrypted"
+    }
+
+    async fn put(&self, key: &VolumePath, data: Bytes) -> Result<(), VolumeError> {
+        let sealed = self.seal(key, &data)?;
+        self.inner.put(key, sealed).await
+    }
+
+    async fn get(&self, key: &VolumePath) -> Result<Bytes, VolumeError> {
+        let sealed = self.inner.get(key).await?;
+        self.open(key, &sealed)
+    }
+
+    async fn list(&self, prefix: &VolumePath) -> Result<Vec<VolumeEntry>, VolumeError> {
+        let mut entries = self.inner.list(prefix).await?;
+        for entry in &mut entries {
+            *entry = plaintext_entry(entry.clone());
+        }
+        Ok(entries)
+    }
+
+    async fn delete(&self, key: &VolumePath) -> Result<(), VolumeError> {
+        self.inner.delete(key).await
+    }
+
+    async fn stat(&self, key: &VolumePath) -> Result<VolumeEntry, VolumeError> {
+        self.inner.stat(key).await.map(plaintext_entry)
+    }
+
+    async fn rename(&self, from: &VolumePath, to: &VolumePath) -> Result<(), VolumeError> {
+        let src = self.inner.stat(from).await?;
+        match self.inner.stat(to).await {
+            Ok(_) => return Err(VolumeError::AlreadyExists(to.clone())),
+            Err(VolumeError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        // The key path is bound into each object's AAD, so a plain
+        // inner rename would leave objects that no longer decrypt.
+        // Write every destination first, then drop the sources.
+        if src.is_dir {
+            self.copy_tree(from, to).await?;
+            return self.inner.delete(from).await;
+        }
+        let plaintext = self.get(from).await?;
+        self.put(to, plaintext).await?;
+        self.inner.delete(from).await
+    }
+
+    async fn health_check(&self) -> Result<(), VolumeError> {
+        self.inner.health_check().await
+    }
+
+    fn local_export_path(&self) -> Option<&Path> {
+        // The host directory holds ciphertext; exporting it over
+        // virtio-fs would hand the guest unreadable bytes.
+        None
+    }
+}
+
+fn aad(key: &VolumePath) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(MAGIC.len() + key.as_str().len());
+    aad.extend_from_slice(MAGIC);
+    aad.extend_from_slice(key.as_str().as_bytes());
+    aad
+}
+
+fn plaintext_entry(mut entry: VolumeEntry) -> VolumeEntry {
+    if !entry.is_dir {
+        entry.size = entry.size.saturating_sub(OVERHEAD);
+    }
+    entry
+}
+
+fn key_err(what: &str, err: anyhow::Error) -> VolumeError {
+    VolumeError::Other(format!("{what}: {err:#}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalBackend;
+    use crate::contract::assert_backend_contract;
+    use mvm_core::volume::OrgId;
+
+    /// Fixed-key provider so tests don't depend on env / keyring.
+    struct StaticProvider([u8; 32]);
+
+    impl KeyProvider for StaticProvider {
+        fn get_data_key(&self, _tenant_id: &str) -> anyhow::Result<SecretBox<Vec<u8>>> {
+            Ok(SecretBox::new(Box::new(self.0.to_vec())))
+        }
+    }
+
+    fn tenant_keys() -> TenantKeySource {
+        TenantKeySource::new(Box::new(StaticProvider([7; 32])), "acme")
+    }
+
+    async fn fresh() -> (tempfile::TempDir, EncryptedBackend<LocalBackend>) {
+        let tmp = tempfile::tempdir().unwrap();
+        let inner = LocalBackend::new(tmp.path().to_path_buf()).await.unwrap();
+        let keys = tenant_keys();
+        let wrapped = new_volume_key(&keys).unwrap();
+        (tmp, EncryptedBackend::new(inner, &wrapped, &keys).unwrap())
+    }
+
+    fn key(s: &str) -> VolumePath {
+        VolumePath::new(s).unwrap()
+    }
+
+    #[tokio::test]
+    async fn encrypted_local_backend_passes_contract() {
+        let (_tmp, b) = fresh().await;
+        assert_backend_contract(&b).await;
+    }
+
+    #[tokio::test]
+    async fn data_at_rest_is_ciphertext() {
+        let (tmp, b) = fresh().await;
+        b.put(
+            &key("secret.txt"),
+            Bytes::from_static(b"RECOGNIZABLE_PLAINTEXT"),
+        )
+        .await
+        .unwrap();
+        let raw = std::fs::read(tmp.path().join("secret.txt")).unwrap();
+        assert!(raw.starts_with(MAGIC));
+        assert_eq!(raw.len() as u64, 22 + OVERHEAD);
+        assert!(!String::from_utf8_lossy(&raw).contains("RECOGNIZABLE_PLAINTEXT"));
+    }
+
+    #[tokio::test]
+    async fn ciphertext_moved_to_another_key_fails_authentication() {
+        let (tmp, b) = fresh().await;
+        b.put(&key("a"), Bytes::from_static(b"payload"))
+            .await
+            .unwrap();
+        std::fs::copy(tmp.path().join("a"), tmp.path().join("b")).unwrap();
+        let err = b.get(&key("b")).await.unwrap_err();
+        assert!(err.to_string().contains("authentication"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn tampered_ciphertext_fails_authentication() {
+        let (tmp, b) = fresh().await;
+        b.put(&key("a"), Bytes::from_static(b"payload"))
+            .await
+            .unwrap();
+        let path = tmp.path().join("a");
+        let mut raw = std::fs::read(&path).unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0x01;
+        std::fs::write(&path, raw).unwrap();
+        assert!(b.get(&key("a")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn wrong_master_key_cannot_unwrap() {
+        let keys = tenant_keys();
+        let wrapped = new_volume_key(&keys).unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let inner = LocalBackend::new(tmp.path().to_path_buf()).await.unwrap();
+        let other = TenantKeySource::new(Box::new(StaticProvider([9; 32])), "acme");
+        let err = EncryptedBackend::new(inner, &wrapped, &other)
+            .err()
+            .expect("foreign master must not unwrap the DEK");
+        assert!(err.to_string().contains("unwrapping volume key"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn short_unwrapped_key_is_an_error_not_a_panic() {
+        let keys = tenant_keys();
+        let version = keys.active_version().unwrap();
+        let master = keys.master_key(version).unwrap();
+        let wrapped = WrappedKey {
+            master_key_version: version,
+            wrapped: snapshot_crypto::encrypt(&[1; 16], master.expose_secret()).unwrap(),
+            algorithm: WrapAlgorithm::Aes256Gcm,
+        };
+        let tmp = tempfile::tempdir().unwrap();
+        let inner = LocalBackend::new(tmp.path().to_path_buf()).await.unwrap();
+        let err = EncryptedBackend::new(inner, &wrapped, &keys)
+            .err()
+            .expect("a 16-byte DEK must be refused");
+        assert!(err.to_string().contains("expected 32"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn directory_rename_reencrypts_under_new_paths() {
+        let (_tmp, b) = fresh().await;
+        b.put(&key("d/a"), Bytes::from_static(b"1")).await.unwrap();
+        b.put(&key("d/sub/b"), Bytes::from_static(b"22"))
+            .await
+            .unwrap();
+        b.rename(&key("d"), &key("e")).await.unwrap();
+        assert_eq!(&b.get(&key("e/a")).await.unwrap()[..], b"1");
+        assert_eq!(&b.get(&key("e/sub/b")).await.unwrap()[..], b"22");
+        assert!(matches!(
+            b.stat(&key("d")).await,
+            Err(VolumeError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn master_rotation_rewraps_without_touching_data() {
+        let keys_dir = tempfile::tempdir().unwrap();
+        let org = OrgId::new("acme").unwrap();
+        key_rotation::rotate_master_key(keys_dir.path(), &org).unwrap();
+        let keys = RotatingKeySource::new(keys_dir.path());
+
+        let wrapped_v1 = new_volume_key(&keys).unwrap();
+        assert_eq!(wrapped_v1.master_key_version, 1);
+        assert!(rewrap_volume_key(&wrapped_v1, &keys).unwrap().is_none());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let inner = LocalBackend::new(tmp.path().to_path_buf()).await.unwrap();
+        let b = EncryptedBackend::new(inner, &wrapped_v1, &keys).unwrap();
+        b.put(&key("f"), Bytes::from_static(b"survives rotation"))
+            .await
+            .unwrap();
+        let before = std::fs::read(tmp.path().join("f")).unwrap();
+
+        key_rotation::rotate_master_key(keys_dir.path(), &org).unwrap();
+        let wrapped_v2 = rewrap_volume_key(&wrapped_v1, &keys)
+            .unwrap()
+            .expect("v1 wrap is stale after rotation");
+        assert_eq!(wrapped_v2.master_key_version, 2);
+
+        let inner = LocalBackend::new(tmp.path().to_path_buf()).await.unwrap();
+        let b = EncryptedBackend::new(inner, &wrapped_v2, &keys).unwrap();
+        assert_eq!(&b.get(&key("f")).await.unwrap()[..], b"survives rotation");
+        assert_eq!(std::fs::read(tmp.path().join("f")).unwrap(), before);
+    }
+
+    #[test]
+    fn revoked_master_version_is_refused() {
+        let keys_dir = tempfile::tempdir().unwrap();
+        let org = OrgId::new("acme").unwrap();
+        key_rotation::rotate_master_key(keys_dir.path(), &org).unwrap();
+        let mut manifest = key_rotation::load_manifest(keys_dir.path()).unwrap();
+        manifest.entries[0].state = MasterKeyState::Revoked;
+        std::fs::write(
+            keys_dir.path().join(key_rotation::MANIFEST_FILENAME),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        let keys = RotatingKeySource::new(keys_dir.path());
+        assert!(keys.master_key(1).is_err());
+        assert!(new_volume_key(&keys).is_err());
+    }
+}
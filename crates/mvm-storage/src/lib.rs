@@ -1,9 +1,11 @@
-//! mvm-storage — `VolumeBackend` trait + `LocalBackend` impl.
+//! mvm-storage — `VolumeBackend` trait + `LocalBackend`,
+//! `ObjectStoreBackend` and `EncryptedBackend<B>` impls.
 //!
-//! Per plan 45 §D5 (Path C), this crate ships **only** the trait and
-//! `LocalBackend`. `ObjectStoreBackend` and `EncryptedBackend<B>` live
-//! in mvmd — they wrap the same trait so callers don't see the
-//! difference.
+//! Plan 45 §D5 (Path C) originally kept the object-store and
+//! encryption backends in mvmd. Both now ship here so standalone
+//! mvmctl gets S3-compatible and encrypted-at-rest volumes without
+//! going through mvmd; they wrap the same trait so callers don't see
+//! the difference.
 //!
 //! ## Why the trait lives here
 //!
@@ -16,42 +18,75 @@
 //!
 //! [`contract::assert_backend_contract`] runs the full trait contract
 //! (put → get round-trip, list, delete, rename, idempotent stat,
-//! concurrent put/get) against any [`VolumeBackend`] impl. mvmd
-//! re-uses it for `ObjectStoreBackend` and `EncryptedBackend<B>`.
+//! concurrent put/get) against any [`VolumeBackend`] impl, including
+//! mvmd's own backings.
 
 pub mod backend;
 pub mod contract;
+pub mod encrypted;
 pub mod local;
+pub mod object_store;
 
 pub use backend::VolumeBackend;
+pub use encrypted::{EncryptedBackend, MasterKeySource, RotatingKeySource, TenantKeySource};
 pub use local::LocalBackend;
+pub use object_store::{
+    CredentialResolver, ObjectStoreBackend, ObjectStoreCredentials, SecretStoreResolver,
+};
 
 use std::sync::Arc;
 
-use mvm_core::volume::{VolumeBackendConfig, VolumeError};
+use mvm_core::volume::{SecretRef, VolumeBackendConfig, VolumeError};
 
 /// Construct a backend from a declarative [`VolumeBackendConfig`].
 ///
-/// In the mvm-side build this only constructs `LocalBackend`. For
-/// `VolumeBackendConfig::ObjectStore`, returns
-/// [`VolumeError::UnsupportedBackend`] with a clear redirect to
-/// `--remote` (which proxies through mvmd, where the object-store
-/// impl lives).
+/// Object-store specs that carry a `credentials_ref` need a secret
+/// store to resolve it; those go through
+/// [`make_backend_with_credentials`] instead and fail here with
+/// [`VolumeError::UnsupportedBackend`].
 ///
 /// mvmd has its own `make_backend_for_bucket` that handles every
 /// variant — see mvmd Sprint 137 W2.
 pub async fn make_backend(
     config: &VolumeBackendConfig,
+) -> Result<Arc<dyn VolumeBackend>, VolumeError> {
+    make_backend_with_credentials(config, &NoCredentials).await
+}
+
+/// [`make_backend`], resolving object-store `credentials_ref`s through
+/// `resolver` (typically a [`SecretStoreResolver`] over the tenant
+/// secret store).
+pub async fn make_backend_with_credentials(
+    config: &VolumeBackendConfig,
+    resolver: &dyn CredentialResolver,
 ) -> Result<Arc<dyn VolumeBackend>, VolumeError> {
     match config {
         VolumeBackendConfig::Local { root } => {
             let backend = LocalBackend::new(root.clone()).await?;
             Ok(Arc::new(backend))
         }
-        VolumeBackendConfig::ObjectStore(_) => Err(VolumeError::UnsupportedBackend {
+        VolumeBackendConfig::ObjectStore(spec) => {
+            let credentials = spec
+                .credentials_ref
+                .as_ref()
+                .map(|r| resolver.resolve(r))
+                .transpose()?;
+            let backend = ObjectStoreBackend::new(spec, credentials.as_ref())?;
+            Ok(Arc::new(backend))
+        }
+    }
+}
+
+/// Resolver for callers without a secret store: anonymous specs work,
+/// anything referencing credentials is refused.
+struct NoCredentials;
+
+impl CredentialResolver for NoCredentials {
+    fn resolve(&self, _secret: &SecretRef) -> Result<ObjectStoreCredentials, VolumeError> {
+        Err(VolumeError::UnsupportedBackend {
             kind: "object-store",
-            reason: "ObjectStore backend lives in mvmd; use `mvmctl --remote` to dispatch through mvmd",
-        }),
+            reason: "credentials_ref needs a secret store; use make_backend_with_credentials",
+        })
     }
 }
 
@@ -72,21 +107,89 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn make_backend_object_store_returns_unsupported() {
+    async fn make_backend_object_store_succeeds() {
         let cfg = VolumeBackendConfig::ObjectStore(ObjectStoreSpec {
             url: "s3://b/".into(),
             prefix: None,
             credentials_ref: None,
         });
+        let backend = make_backend(&cfg).await.unwrap();
+        assert_eq!(backend.kind(), "object-store");
+        assert!(backend.local_export_path().is_none());
+    }
+
+    #[tokio::test]
+    async fn make_backend_refuses_memory_scheme() {
+        let cfg = VolumeBackendConfig::ObjectStore(ObjectStoreSpec {
+            url: "memory://".into(),
+            prefix: None,
+            credentials_ref: None,
+        });
         match make_backend(&cfg).await {
-            Ok(_) => panic!("must reject ObjectStore in mvm-storage"),
+            Ok(_) => panic!("memory:// must not back a volume"),
+            Err(err) => assert!(
+                matches!(err, VolumeError::UnsupportedBackend { .. }),
+                "{err}"
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn make_backend_refuses_credentials_ref_without_resolver() {
+        let cfg = VolumeBackendConfig::ObjectStore(ObjectStoreSpec {
+            url: "s3://b/".into(),
+            prefix: None,
+            credentials_ref: Some(SecretRef::new("s3-creds").unwrap()),
+        });
+        match make_backend(&cfg).await {
+            Ok(_) => panic!("must not silently drop credentials_ref"),
             Err(err) => {
                 let msg = err.to_string();
                 assert!(
-                    msg.contains("object-store") && msg.contains("--remote"),
-                    "error must redirect to --remote: {msg}"
+                    msg.contains("make_backend_with_credentials"),
+                    "error must point at the resolver entry point: {msg}"
                 );
             }
         }
     }
+
+    #[tokio::test]
+    async fn make_backend_with_credentials_reads_secret_store() {
+        use mvm_security::secret_store::{FileSecretStore, SecretStore};
+        use secrecy::SecretBox;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let store = FileSecretStore::with_dir(tmp.path());
+        let payload =
+            r#"{"endpoint": "http://127.0.0.1:9", "access_key_id": "k", "secret_access_key": "s"}"#;
+        store
+            .put(
+                "acme",
+                "s3-creds",
+                &SecretBox::new(Box::new(payload.to_string())),
+            )
+            .unwrap();
+        let resolver = SecretStoreResolver::new(Box::new(store), "acme");
+
+        let cfg = VolumeBackendConfig::ObjectStore(ObjectStoreSpec {
+            url: "s3://b/".into(),
+            prefix: None,
+            credentials_ref: Some(SecretRef::new("s3-creds").unwrap()),
+        });
+        let backend = make_backend_with_credentials(&cfg, &resolver)
+            .await
+            .unwrap();
+        assert_eq!(backend.kind(), "object-store");
+
+        let missing = VolumeBackendConfig::ObjectStore(ObjectStoreSpec {
+            url: "s3://b/".into(),
+            prefix: None,
+            credentials_ref: Some(SecretRef::new("nope").unwrap()),
+        });
+        assert!(
+            make_backend_with_credentials(&missing, &resolver)
+                .await
+                .is_err()
+        );
+    }
 }
@@ -3,23 +3,28 @@
 //! Backing layout:
 //! - Each `VolumePath` resolves to `<root>/<key>`.
 //! - Atomic puts: write to `<root>/.tmp.<random>`, fsync, rename.
+//!   `put_stream` uses the same temp-then-rename path; `append`
+//!   writes in place with `O_APPEND | O_NOFOLLOW` and checks the fd
+//!   it opened.
 //! - Symlink-escape defence: every operation re-canonicalises the
 //!   resolved path and asserts `starts_with(<root>)` after resolution.
 //!
 //! This impl ships in mvm-storage. mvmd uses it directly for buckets
 //! whose backend is `BucketProvider::LocalVirtiofs`.
 
+use std::io::SeekFrom;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use chrono::Utc;
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
 use mvm_core::volume::{VolumeEntry, VolumeError, VolumePath};
 
-use crate::backend::VolumeBackend;
+use crate::backend::{VolumeBackend, VolumeReader};
 
 pub struct LocalBackend {
     root: PathBuf,
@@ -73,21 +78,76 @@ impl LocalBackend {
         }
         Ok(())
     }
-}
 
-#[async_trait]
-impl VolumeBackend for LocalBackend {
-    fn kind(&self) -> &'static str {
-        "local"
+    /// Open an existing file for reading, mapping a missing file to
+    /// [`VolumeError::NotFound`] and refusing symlink escapes.
+    async fn open_existing(&self, key: &VolumePath) -> Result<fs::File, VolumeError> {
+        let target = self.resolve(key)?;
+        let file = match fs::File::open(&target).await {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(VolumeError::NotFound(key.clone()));
+            }
+            Err(e) => return Err(VolumeError::Io(e)),
+        };
+        self.assert_in_root(&target).await?;
+        Ok(file)
     }
 
-    async fn put(&self, key: &VolumePath, data: Bytes) -> Result<(), VolumeError> {
+    /// Open (or create) `target` for an in-place append without
+    /// following a symlink at its name, then check that the fd is a
+    /// single-link regular file that still sits at `target` under a
+    /// parent inside the root. Unlike `put`, an append has no rename
+    /// to land atomically, so a symlink (or hard link) planted at the
+    /// key would otherwise redirect the write outside the volume.
+    async fn open_for_append(&self, target: &Path) -> Result<fs::File, VolumeError> {
+        use std::os::unix::fs::MetadataExt;
+
+        let refuse = |what: &str| {
+            VolumeError::InvalidPath(format!(
+                "refusing to append to {}: {what}",
+                target.display()
+            ))
+        };
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .custom_flags(libc::O_NOFOLLOW)
+            .open(target)
+            .await
+            .map_err(|e| {
+                if e.raw_os_error() == Some(libc::ELOOP) {
+                    refuse("it is a symlink")
+                } else {
+                    VolumeError::Io(e)
+                }
+            })?;
+        let opened = file.metadata().await.map_err(VolumeError::Io)?;
+        if !opened.is_file() {
+            return Err(refuse("not a regular file"));
+        }
+        if opened.nlink() != 1 {
+            return Err(refuse("it has other links"));
+        }
+        if let Some(parent) = target.parent() {
+            self.assert_in_root(parent).await?;
+        }
+        let at_path = fs::symlink_metadata(target)
+            .await
+            .map_err(VolumeError::Io)?;
+        if at_path.dev() != opened.dev() || at_path.ino() != opened.ino() {
+            return Err(refuse("it was replaced while opening"));
+        }
+        Ok(file)
+    }
+
+    /// Resolve `key` for writing: creates parent dirs and returns the
+    /// target plus a sibling temp path for the atomic rename.
+    async fn prepare_write(&self, key: &VolumePath) -> Result<(PathBuf, PathBuf), VolumeError> {
         let target = self.resolve(key)?;
         if let Some(parent) = target.parent() {
             fs::create_dir_all(parent).await.map_err(VolumeError::Io)?;
         }
-
-        // Atomic write: temp file in target's parent dir, then rename.
         let tmp_name = format!(
             ".mvm-tmp-{}-{}",
             std::process::id(),
@@ -97,6 +157,19 @@ impl VolumeBackend for LocalBackend {
             .parent()
             .map(|p| p.join(&tmp_name))
             .unwrap_or_else(|| PathBuf::from(&tmp_name));
+        Ok((target, tmp_path))
+    }
+}
+
+#[async_trait]
+impl VolumeBackend for LocalBackend {
+    fn kind(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &VolumePath, data: Bytes) -> Result<(), VolumeError> {
+        // Atomic write: temp file in target's parent dir, then rename.
+        let (target, tmp_path) = self.prepare_write(key).await?;
 
         let mut file = fs::File::create(&tmp_path).await.map_err(VolumeError::Io)?;
         file.write_all(&data).await.map_err(VolumeError::Io)?;
@@ -242,6 +315,58 @@ impl VolumeBackend for LocalBackend {
     fn local_export_path(&self) -> Option<&Path> {
         Some(&self.root)
     }
+
+    async fn get_range(&self, key: &VolumePath, range: Range<u64>) -> Result<Bytes, VolumeError> {
+        let mut file = self.open_existing(key).await?;
+        let len = file.metadata().await.map_err(VolumeError::Io)?.len();
+        let end = range.end.min(len);
+        if range.start >= end {
+            return Ok(Bytes::new());
+        }
+        file.seek(SeekFrom::Start(range.start))
+            .await
+            .map_err(VolumeError::Io)?;
+        let mut buf = vec![0u8; (end - range.start) as usize];
+        file.read_exact(&mut buf).await.map_err(VolumeError::Io)?;
+        Ok(Bytes::from(buf))
+    }
+
+    async fn get_stream(&self, key: &VolumePath) -> Result<VolumeReader, VolumeError> {
+        let file = self.open_existing(key).await?;
+        Ok(Box::pin(file))
+    }
+
+    async fn put_stream(
+        &self,
+        key: &VolumePath,
+        mut data: VolumeReader,
+    ) -> Result<u64, VolumeError> {
+        let (target, tmp_path) = self.prepare_write(key).await?;
+        let mut file = fs::File::create(&tmp_path).await.map_err(VolumeError::Io)?;
+        let written = match tokio::io::copy(&mut data, &mut file).await {
+            Ok(n) => n,
+            Err(e) => {
+                drop(file);
+                let _ = fs::remove_file(&tmp_path).await;
+                return Err(VolumeError::Io(e));
+            }
+        };
+        file.sync_all().await.map_err(VolumeError::Io)?;
+        drop(file);
+
+        fs::rename(&tmp_path, &target)
+            .await
+            .map_err(VolumeError::Io)?;
+        Ok(written)
+    }
+
+    async fn append(&self, key: &VolumePath, data: Bytes) -> Result<(), VolumeError> {
+        let (target, _) = self.prepare_write(key).await?;
+        let mut file = self.open_for_append(&target).await?;
+        file.write_all(&data).await.map_err(VolumeError::Io)?;
+        file.sync_data().await.map_err(VolumeError::Io)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -401,4 +526,88 @@ mod tests {
         let names: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
         assert_eq!(names, vec!["./real.txt"]);
     }
+
+    /// Yields some bytes, then fails — a client hanging up mid-upload.
+    struct BrokenReader(bool);
+
+    impl tokio::io::AsyncRead for BrokenReader {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            if self.0 {
+                return std::task::Poll::Ready(Err(std::io::Error::other("peer went away")));
+            }
+            self.0 = true;
+            buf.put_slice(b"partial");
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_put_stream_keeps_old_content_and_no_tmp() {
+        let (tmp, b) = fresh().await;
+        let key = VolumePath::new("k").unwrap();
+        b.put(&key, Bytes::from_static(b"old")).await.unwrap();
+
+        let err = b
+            .put_stream(&key, Box::pin(BrokenReader(false)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VolumeError::Io(_)));
+
+        assert_eq!(&b.get(&key).await.unwrap()[..], b"old");
+        let names: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("k")]);
+    }
+
+    #[tokio::test]
+    async fn append_creates_parent_dirs() {
+        let (_tmp, b) = fresh().await;
+        let key = VolumePath::new("logs/today.log").unwrap();
+        b.append(&key, Bytes::from_static(b"a")).await.unwrap();
+        b.append(&key, Bytes::from_static(b"b")).await.unwrap();
+        assert_eq!(&b.get(&key).await.unwrap()[..], b"ab");
+    }
+
+    #[tokio::test]
+    async fn append_refuses_a_symlink_out_of_the_root() {
+        let (tmp, b) = fresh().await;
+        let outside = tempfile::tempdir().unwrap();
+        let victim = outside.path().join("victim");
+        std::fs::write(&victim, b"keep").unwrap();
+        std::os::unix::fs::symlink(&victim, tmp.path().join("log")).unwrap();
+
+        let key = VolumePath::new("log").unwrap();
+        let err = b
+            .append(&key, Bytes::from_static(b"evil"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VolumeError::InvalidPath(_)), "{err:?}");
+        assert_eq!(std::fs::read(&victim).unwrap(), b"keep");
+    }
+
+    #[tokio::test]
+    async fn append_refuses_a_hard_link_to_another_file() {
+        let (tmp, b) = fresh().await;
+        let outside = tempfile::tempdir().unwrap();
+        let victim = outside.path().join("victim");
+        std::fs::write(&victim, b"keep").unwrap();
+        // Same filesystem only; skip where tempdirs span devices.
+        if std::fs::hard_link(&victim, tmp.path().join("log")).is_err() {
+            return;
+        }
+
+        let key = VolumePath::new("log").unwrap();
+        let err = b
+            .append(&key, Bytes::from_static(b"evil"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VolumeError::InvalidPath(_)), "{err:?}");
+        assert_eq!(std::fs::read(&victim).unwrap(), b"keep");
+    }
 }
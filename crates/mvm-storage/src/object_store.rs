@@ -0,0 +1,765 @@
+//! `ObjectStoreBackend` — S3-compatible [`VolumeBackend`] over `opendal`.
+//!
+//! Backing layout:
+//! - `s3://bucket[/prefix]` selects the bucket; the URL path and
+//!   [`ObjectStoreSpec::prefix`] compose into the key root, so each
+//!   `VolumePath` resolves to `<url-prefix>/<spec-prefix>/<key>`.
+//! - `memory://` is refused: the in-process store behind it loses
+//!   every byte on drop, so it is only reachable through the
+//!   test-only [`ObjectStoreBackend::in_memory`].
+//!
+//! ## Operation mapping
+//!
+//! - `put` / `put_stream` — single `PutObject` up to the part size,
+//!   multipart upload above it. Both are atomic from a reader's point
+//!   of view: the object only becomes visible on `PutObject` /
+//!   `CompleteMultipartUpload`, and a failed stream aborts the upload.
+//! - `get` / `get_range` / `get_stream` — `GetObject`, with `Range`
+//!   headers for the ranged and streaming forms (the stream fetches
+//!   one part-sized range at a time).
+//! - `append` — the trait's read-modify-write default; S3 has no
+//!   append.
+//! - `list` — `ListObjectsV2` with `delimiter=/`; common prefixes come
+//!   back as directory entries.
+//! - `rename` — `CopyObject` then `DeleteObject`. Not atomic: a crash
+//!   between the two leaves both keys present, never neither.
+//!
+//! Object stores have no real directories. A "directory" exists iff at
+//! least one object lives under `<key>/`, which is what `stat`, `list`
+//! and `delete` report.
+//!
+//! ## Credentials
+//!
+//! Credentials come from the secret referenced by
+//! [`ObjectStoreSpec::credentials_ref`], resolved through a
+//! [`CredentialResolver`] — never from ambient `AWS_*` env vars,
+//! `~/.aws/config`, or the EC2 metadata endpoint. The payload is the
+//! JSON form of [`ObjectStoreCredentials`]. No `credentials_ref` means
+//! anonymous requests against the default AWS endpoint.
+
+use std::ops::Range;
+use std::path::Path;
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use opendal::services::S3;
+use opendal::{EntryMode, ErrorKind, Metadata, Metakey, Operator};
+use secrecy::{ExposeSecret, SecretBox};
+use serde::Deserialize;
+use tokio::io::AsyncReadExt;
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+use mvm_core::volume::{ObjectStoreSpec, SecretRef, VolumeEntry, VolumeError, VolumePath};
+use mvm_security::secret_store::SecretStore;
+
+use crate::backend::{VolumeBackend, VolumeReader};
+
+/// Part size used for multipart uploads unless overridden with
+/// [`ObjectStoreBackend::with_multipart_part_size`].
+pub const DEFAULT_MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Smallest non-final part S3 accepts. Smaller part sizes are clamped
+/// up to this.
+pub const MIN_MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Read size when pulling from a `put_stream` source.
+const STREAM_READ_SIZE: usize = 1024 * 1024;
+
+/// Decoded payload of an object-store credentials secret.
+///
+/// ```json
+/// {
+///   "endpoint": "https://s3.eu-central-1.amazonaws.com",
+///   "region": "eu-central-1",
+///   "access_key_id": "AKIA…",
+///   "secret_access_key": "…"
+/// }
+/// ```
+///
+/// Every field is optional; an empty object means "anonymous against
+/// the default AWS endpoint". `allow_http` must be set to talk
+/// plaintext HTTP to anything other than a loopback endpoint.
+// allow(secret-debug): the secret-bearing fields are `SecretBox`,
+// whose Debug prints a redaction marker; the rest (endpoint, region,
+// access key *id*) is routing metadata.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectStoreCredentials {
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub secret_access_key: Option<SecretBox<String>>,
+    #[serde(default)]
+    pub session_token: Option<SecretBox<String>>,
+    #[serde(default)]
+    pub allow_http: bool,
+}
+
+impl ObjectStoreCredentials {
+    /// Parse the JSON payload of a credentials secret. The error never
+    /// echoes payload content — serde's messages can quote values.
+    pub fn from_json(secret: &SecretRef, payload: &str) -> Result<Self, VolumeError> {
+        serde_json::from_str(payload).map_err(|e| {
+            VolumeError::Other(format!(
+                "credentials secret {secret} is not a valid object-store payload \
+                 (line {}, column {})",
+                e.line(),
+                e.column()
+            ))
+        })
+    }
+}
+
+/// Resolves a [`SecretRef`] into object-store credentials.
+pub trait CredentialResolver: Send + Sync {
+    fn resolve(&self, secret: &SecretRef) -> Result<ObjectStoreCredentials, VolumeError>;
+}
+
+/// [`CredentialResolver`] backed by the mvm-security tenant secret
+/// store — the `mvmctl secret put` surface on a dev box.
+pub struct SecretStoreResolver {
+    store: Box<dyn SecretStore>,
+    tenant: String,
+}
+
+impl SecretStoreResolver {
+    pub fn new(store: Box<dyn SecretStore>, tenant: impl Into<String>) -> Self {
+        Self {
+            store,
+            tenant: tenant.into(),
+        }
+    }
+}
+
+impl CredentialResolver for SecretStoreResolver {
+    fn resolve(&self, secret: &SecretRef) -> Result<ObjectStoreCredentials, VolumeError> {
+        let value = self.store.get(&self.tenant, secret.as_str()).map_err(|e| {
+            VolumeError::Other(format!(
+                "resolving credentials secret {secret} for tenant {}: {e:#}",
+                self.tenant
+            ))
+        })?;
+        ObjectStoreCredentials::from_json(secret, value.expose_secret())
+    }
+}
+
+/// S3-compatible [`VolumeBackend`].
+pub struct ObjectStoreBackend {
+    op: Operator,
+    part_size: usize,
+}
+
+impl std::fmt::Debug for ObjectStoreBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let info = self.op.info();
+        f.debug_struct("ObjectStoreBackend")
+            .field("scheme", &info.scheme())
+            .field("name", &info.name())
+            .field("root", &info.root())
+            .field("part_size", &self.part_size)
+            .finish()
+    }
+}
+
+impl ObjectStoreBackend {
+    /// Construct a backend for `spec`. `credentials` is the already-
+    /// resolved payload of `spec.credentials_ref` (see
+    /// [`crate::make_backend_with_credentials`]).
+    ///
+    /// Construction does no I/O; call
+    /// [`VolumeBackend::health_check`] to probe reachability.
+    pub fn new(
+        spec: &ObjectStoreSpec,
+        credentials: Option<&ObjectStoreCredentials>,
+    ) -> Result<Self, VolumeError> {
+        let url = url::Url::parse(&spec.url).map_err(|e| {
+            VolumeError::Other(format!("object-store url {:?} is invalid: {e}", spec.url))
+        })?;
+        let root = key_root(url.path(), spec.prefix.as_deref());
+
+        let op = match url.scheme() {
+            "s3" => {
+                let bucket = url.host_str().filter(|b| !b.is_empty()).ok_or_else(|| {
+                    VolumeError::Other(format!(
+                        "object-store url {:?} has no bucket (expected s3://bucket[/prefix])",
+                        spec.url
+                    ))
+                })?;
+                s3_operator(bucket, &root, credentials)?
+            }
+            "memory" => {
+                return Err(VolumeError::UnsupportedBackend {
+                    kind: "object-store",
+                    reason: "memory:// is an in-process test store, not a volume backing",
+                });
+            }
+            _ => {
+                return Err(VolumeError::UnsupportedBackend {
+                    kind: "object-store",
+                    reason: "mvm-storage supports s3:// object-store URLs",
+                });
+            }
+        };
+
+        Ok(Self {
+            op,
+            part_size: DEFAULT_MULTIPART_PART_SIZE,
+        })
+    }
+
+    /// In-process backend for this crate's tests. Volumes never reach
+    /// it: [`ObjectStoreBackend::new`] refuses `memory://`.
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        let builder = opendal::services::Memory::default().root("/");
+        Self {
+            op: Operator::new(builder).expect("in-memory operator").finish(),
+            part_size: DEFAULT_MULTIPART_PART_SIZE,
+        }
+    }
+
+    /// Override the multipart part size. Values below
+    /// [`MIN_MULTIPART_PART_SIZE`] are clamped up to it.
+    pub fn with_multipart_part_size(mut self, bytes: usize) -> Self {
+        self.part_size = bytes.max(MIN_MULTIPART_PART_SIZE);
+        self
+    }
+
+    /// Length of the object at `key`; directories are refused like
+    /// `get` refuses them.
+    async fn object_len(&self, key: &VolumePath) -> Result<u64, VolumeError> {
+        let entry = self.stat(key).await?;
+        if entry.is_dir {
+            return Err(VolumeError::Other(format!("{key} is a directory")));
+        }
+        Ok(entry.size)
+    }
+
+    /// True iff at least one object lives under `dir/`.
+    async fn dir_exists(&self, dir: &str) -> Result<bool, VolumeError> {
+        if dir.is_empty() {
+            return Ok(true);
+        }
+        let listing = format!("{dir}/");
+        let entries = self
+            .op
+            .list(&listing)
+            .await
+            .map_err(|e| VolumeError::Other(format!("object store list {listing}: {e}")))?;
+        Ok(entries.iter().any(|e| e.path() != listing))
+    }
+
+    /// Every object key (no directory markers) under `dir/`.
+    async fn objects_under(&self, dir: &str) -> Result<Vec<String>, VolumeError> {
+        let listing = format!("{dir}/");
+        let entries = self
+            .op
+            .list_with(&listing)
+            .recursive(true)
+            .await
+            .map_err(|e| VolumeError::Other(format!("object store list {listing}: {e}")))?;
+        Ok(entries
+            .into_iter()
+            .filter(|e| e.metadata().mode() == EntryMode::FILE)
+            .map(|e| e.path().to_string())
+            .collect())
+    }
+
+    async fn copy_object(&self, from: &str, to: &str) -> Result<(), VolumeError> {
+        if self.op.info().full_capability().copy {
+            return self.op.copy(from, to).await.map_err(backend_err);
+        }
+        // Services without server-side copy (the in-memory store):
+        // round-trip through the client.
+        let data = self.op.read(from).await.map_err(backend_err)?;
+        self.op.write(to, data).await.map_err(backend_err)
+    }
+}
+
+#[async_trait]
+impl VolumeBackend for ObjectStoreBackend {
+    fn kind(&self) -> &'static str {
+        "object-store"
+    }
+
+    async fn put(&self, key: &VolumePath, data: Bytes) -> Result<(), VolumeError> {
+        let path = file_path(key)?;
+        // `chunk` flips the S3 writer into multipart once more than
+        // one part's worth of data is buffered; smaller payloads
+        // still go out as a single PutObject.
+        self.op
+            .write_with(&path, data)
+            .chunk(self.part_size)
+            .await
+            .map_err(|e| map_err(key, e))
+    }
+
+    async fn get(&self, key: &VolumePath) -> Result<Bytes, VolumeError> {
+        let path = file_path(key)?;
+        let buf = self.op.read(&path).await.map_err(|e| map_err(key, e))?;
+        Ok(buf.to_bytes())
+    }
+
+    async fn list(&self, prefix: &VolumePath) -> Result<Vec<VolumeEntry>, VolumeError> {
+        let dir = object_path(prefix);
+        let listing = if dir.is_empty() {
+            "/".to_string()
+        } else {
+            format!("{dir}/")
+        };
+        let entries = self
+            .op
+            .list_with(&listing)
+            .metakey(Metakey::Mode | Metakey::ContentLength | Metakey::Etag)
+            .await
+            .map_err(|e| map_err(prefix, e))?;
+
+        let mut out = Vec::new();
+        for entry in entries {
+            // opendal reports the listed prefix itself alongside its
+            // children.
+            if entry.path() == listing || entry.path() == "/" {
+                continue;
+            }
+            let path = VolumePath::new(entry.path().trim_end_matches('/'))
+                .map_err(|e| VolumeError::InvalidPath(e.to_string()))?;
+            out.push(to_entry(path, entry.metadata()));
+        }
+        if out.is_empty() && !dir.is_empty() {
+            return Err(VolumeError::NotFound(prefix.clone()));
+        }
+        Ok(out)
+    }
+
+    async fn delete(&self, key: &VolumePath) -> Result<(), VolumeError> {
+        let entry = self.stat(key).await?;
+        let path = object_path(key);
+        if !entry.is_dir {
+            return self.op.delete(&path).await.map_err(|e| map_err(key, e));
+        }
+        for object in self.objects_under(&path).await? {
+            self.op.delete(&object).await.map_err(backend_err)?;
+        }
+        Ok(())
+    }
+
+    async fn stat(&self, key: &VolumePath) -> Result<VolumeEntry, VolumeError> {
+        let path = object_path(key);
+        if !path.is_empty() {
+            match self.op.stat(&path).await {
+                Ok(meta) if !meta.is_dir() => return Ok(to_entry(key.clone(), &meta)),
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(map_err(key, e)),
+            }
+        }
+        if self.dir_exists(&path).await? {
+            return Ok(VolumeEntry {
+                path: key.clone(),
+                size: 0,
+                is_dir: true,
+                etag: None,
+            });
+        }
+        Err(VolumeError::NotFound(key.clone()))
+    }
+
+    async fn rename(&self, from: &VolumePath, to: &VolumePath) -> Result<(), VolumeError> {
+        let src = self.stat(from).await?;
+        match self.stat(to).await {
+            Ok(_) => return Err(VolumeError::AlreadyExists(to.clone())),
+            Err(VolumeError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let src_path = object_path(from);
+        let dst_path = file_path(to)?;
+        if !src.is_dir {
+            self.copy_object(&src_path, &dst_path).await?;
+            return self.op.delete(&src_path).await.map_err(backend_err);
+        }
+
+        // Directory rename: copy every object first, then delete, so
+        // an interruption never loses data.
+        let objects = self.objects_under(&src_path).await?;
+        for object in &objects {
+            let suffix = &object[src_path.len()..];
+            self.copy_object(object, &format!("{dst_path}{suffix}"))
+                .await?;
+        }
+        for object in &objects {
+            self.op.delete(object).await.map_err(backend_err)?;
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), VolumeError> {
+        self.op
+            .check()
+            .await
+            .map_err(|e| VolumeError::Other(format!("object store health check: {e}")))
+    }
+
+    fn local_export_path(&self) -> Option<&Path> {
+        None
+    }
+
+    async fn get_range(&self, key: &VolumePath, range: Range<u64>) -> Result<Bytes, VolumeError> {
+        // Costs a HeadObject before the ranged GetObject: services
+        // disagree on out-of-bounds ranges (S3 truncates, 416s when
+        // the start is past the end, the in-memory store panics), so
+        // the range is clamped against the object length up front.
+        let len = self.object_len(key).await?;
+        let end = range.end.min(len);
+        if range.start >= end {
+            return Ok(Bytes::new());
+        }
+        let buf = self
+            .op
+            .read_with(&file_path(key)?)
+            .range(range.start..end)
+            .await
+            .map_err(|e| map_err(key, e))?;
+        Ok(buf.to_bytes())
+    }
+
+    async fn get_stream(&self, key: &VolumePath) -> Result<VolumeReader, VolumeError> {
+        let len = self.object_len(key).await?;
+        let reader = self
+            .op
+            .reader_with(&file_path(key)?)
+            .chunk(self.part_size)
+            .await
+            .map_err(|e| map_err(key, e))?;
+        let stream = reader
+            .into_futures_async_read(0..len)
+            .await
+            .map_err(|e| map_err(key, e))?;
+        Ok(Box::pin(stream.compat()))
+    }
+
+    async fn put_stream(
+        &self,
+        key: &VolumePath,
+        mut data: VolumeReader,
+    ) -> Result<u64, VolumeError> {
+        let path = file_path(key)?;
+        let mut writer = self
+            .op
+            .writer_with(&path)
+            .chunk(self.part_size)
+            .await
+            .map_err(|e| map_err(key, e))?;
+
+        let mut total = 0u64;
+        let mut buf = BytesMut::new();
+        let result: Result<(), VolumeError> = async {
+            loop {
+                buf.reserve(STREAM_READ_SIZE);
+                let n = data.read_buf(&mut buf).await.map_err(VolumeError::Io)?;
+                if n == 0 {
+                    break;
+                }
+                total += n as u64;
+                writer
+                    .write(buf.split().freeze())
+                    .await
+                    .map_err(|e| map_err(key, e))?;
+            }
+            writer.close().await.map_err(|e| map_err(key, e))
+        }
+        .await;
+
+        if let Err(e) = result {
+            // Abort so no half-written object (or orphaned multipart
+            // upload) becomes visible.
+            if let Err(abort) = writer.abort().await {
+                tracing::warn!(key = %key, error = %abort, "aborting object-store upload failed");
+            }
+            return Err(e);
+        }
+        Ok(total)
+    }
+}
+
+fn s3_operator(
+    bucket: &str,
+    root: &str,
+    credentials: Option<&ObjectStoreCredentials>,
+) -> Result<Operator, VolumeError> {
+    let anonymous = ObjectStoreCredentials::default();
+    let creds = credentials.unwrap_or(&anonymous);
+
+    // Path-style addressing (the builder default) is what MinIO,
+    // Hetzner and most S3-compatibles expect. Ambient credential
+    // sources are switched off: a volume only ever uses the secret
+    // its spec references.
+    let mut builder = S3::default()
+        .bucket(bucket)
+        .root(root)
+        .region(creds.region.as_deref().unwrap_or(DEFAULT_REGION))
+        .disable_config_load()
+        .disable_ec2_metadata();
+
+    if let Some(endpoint) = &creds.endpoint {
+        check_endpoint(endpoint, creds.allow_http)?;
+        builder = builder.endpoint(endpoint);
+    }
+
+    match (&creds.access_key_id, &creds.secret_access_key) {
+        (Some(id), Some(secret)) => {
+            builder = builder
+                .access_key_id(id)
+                .secret_access_key(secret.expose_secret());
+            if let Some(token) = &creds.session_token {
+                builder = builder.session_token(token.expose_secret());
+            }
+        }
+        (None, None) => builder = builder.allow_anonymous(),
+        _ => {
+            return Err(VolumeError::Other(
+                "object-store credentials must set both access_key_id and secret_access_key"
+                    .to_string(),
+            ));
+        }
+    }
+
+    Ok(Operator::new(builder).map_err(backend_err)?.finish())
+}
+
+/// Refuse plaintext endpoints unless they are loopback or the secret
+/// explicitly opts in — request signatures are replayable.
+fn check_endpoint(endpoint: &str, allow_http: bool) -> Result<(), VolumeError> {
+    let url = url::Url::parse(endpoint).map_err(|e| {
+        VolumeError::Other(format!(
+            "object-store endpoint {endpoint:?} is invalid: {e}"
+        ))
+    })?;
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if allow_http || is_loopback(&url) => Ok(()),
+        "http" => Err(VolumeError::Other(format!(
+            "object-store endpoint {endpoint} is plaintext http; set allow_http in the credentials secret to permit it"
+        ))),
+        other => Err(VolumeError::Other(format!(
+            "object-store endpoint scheme {other:?} is not supported (expected https or http)"
+        ))),
+    }
+}
+
+fn is_loopback(url: &url::Url) -> bool {
+    match url.host() {
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        Some(url::Host::Domain(d)) => d.eq_ignore_ascii_case("localhost"),
+        None => false,
+    }
+}
+
+/// Compose the URL path and the spec prefix into an opendal root
+/// (`/a/b/`).
+fn key_root(url_path: &str, prefix: Option<&str>) -> String {
+    let mut root = String::from("/");
+    for part in [url_path, prefix.unwrap_or("")] {
+        let part = part.trim_matches('/');
+        if !part.is_empty() {
+            root.push_str(part);
+            root.push('/');
+        }
+    }
+    root
+}
+
+/// Object key for `key`, relative to the backend root. `.` (and a
+/// leading `./`) name the root itself.
+fn object_path(key: &VolumePath) -> String {
+    let s = key.as_str();
+    let s = s.strip_prefix("./").unwrap_or(s);
+    if s == "." {
+        return String::new();
+    }
+    s.trim_end_matches('/').to_string()
+}
+
+/// Like [`object_path`], but refuses the root — it can't hold bytes.
+fn file_path(key: &VolumePath) -> Result<String, VolumeError> {
+    let path = object_path(key);
+    if path.is_empty() {
+        return Err(VolumeError::InvalidPath(format!(
+            "{key} names the volume root, not an object"
+        )));
+    }
+    Ok(path)
+}
+
+fn to_entry(path: VolumePath, meta: &Metadata) -> VolumeEntry {
+    let is_dir = meta.is_dir();
+    VolumeEntry {
+        path,
+        size: if is_dir { 0 } else { meta.content_length() },
+        is_dir,
+        etag: meta.etag().map(|e| e.trim_matches('"').to_string()),
+    }
+}
+
+fn map_err(key: &VolumePath, err: opendal::Error) -> VolumeError {
+    match err.kind() {
+        ErrorKind::NotFound => VolumeError::NotFound(key.clone()),
+        ErrorKind::AlreadyExists => VolumeError::AlreadyExists(key.clone()),
+        _ => backend_err(err),
+    }
+}
+
+fn backend_err(err: opendal::Error) -> VolumeError {
+    VolumeError::Other(format!("object store: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::assert_backend_contract;
+
+    fn spec(url: &str, prefix: Option<&str>) -> ObjectStoreSpec {
+        ObjectStoreSpec {
+            url: url.into(),
+            prefix: prefix.map(str::to_string),
+            credentials_ref: None,
+        }
+    }
+
+    fn memory() -> ObjectStoreBackend {
+        ObjectStoreBackend::in_memory()
+    }
+
+    #[tokio::test]
+    async fn memory_backend_passes_contract() {
+        assert_backend_contract(&memory()).await;
+    }
+
+    #[test]
+    fn key_root_composes_url_path_and_prefix() {
+        assert_eq!(key_root("", None), "/");
+        assert_eq!(key_root("/", Some("")), "/");
+        assert_eq!(key_root("/vols/", None), "/vols/");
+        assert_eq!(key_root("/vols", Some("/team-a/")), "/vols/team-a/");
+        assert_eq!(key_root("", Some("team-a")), "/team-a/");
+    }
+
+    #[test]
+    fn object_path_maps_dot_to_root() {
+        let p = |s: &str| object_path(&VolumePath::new(s).unwrap());
+        assert_eq!(p("."), "");
+        assert_eq!(p("./a.txt"), "a.txt");
+        assert_eq!(p("a/b"), "a/b");
+    }
+
+    #[test]
+    fn unsupported_scheme_is_refused() {
+        let err = ObjectStoreBackend::new(&spec("gs://bucket/", None), None).unwrap_err();
+        assert!(matches!(err, VolumeError::UnsupportedBackend { .. }));
+    }
+
+    #[test]
+    fn s3_without_bucket_is_refused() {
+        let err = ObjectStoreBackend::new(&spec("s3:///prefix", None), None).unwrap_err();
+        assert!(err.to_string().contains("no bucket"), "{err}");
+    }
+
+    #[test]
+    fn plaintext_remote_endpoint_needs_opt_in() {
+        let mut creds = ObjectStoreCredentials {
+            endpoint: Some("http://minio.internal:9000".into()),
+            ..Default::default()
+        };
+        let err = ObjectStoreBackend::new(&spec("s3://b", None), Some(&creds)).unwrap_err();
+        assert!(err.to_string().contains("allow_http"), "{err}");
+
+        creds.allow_http = true;
+        ObjectStoreBackend::new(&spec("s3://b", None), Some(&creds)).unwrap();
+
+        let loopback = ObjectStoreCredentials {
+            endpoint: Some("http://127.0.0.1:9000".into()),
+            ..Default::default()
+        };
+        ObjectStoreBackend::new(&spec("s3://b", None), Some(&loopback)).unwrap();
+    }
+
+    #[test]
+    fn half_configured_key_pair_is_refused() {
+        let creds = ObjectStoreCredentials {
+            access_key_id: Some("AKIA".into()),
+            ..Default::default()
+        };
+        let err = ObjectStoreBackend::new(&spec("s3://b", None), Some(&creds)).unwrap_err();
+        assert!(err.to_string().contains("both"), "{err}");
+    }
+
+    #[test]
+    fn credentials_parse_error_does_not_echo_payload() {
+        let secret = SecretRef::new("s3-creds").unwrap();
+        let err = ObjectStoreCredentials::from_json(
+            &secret,
+            r#"{"access_key_id": "AKIA", "secret_access_key": 42}"#,
+        )
+        .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("s3-creds"), "{msg}");
+        assert!(!msg.contains("AKIA"), "payload leaked: {msg}");
+    }
+
+    #[tokio::test]
+    async fn get_range_returns_slice() {
+        let b = memory();
+        let key = VolumePath::new("r.bin").unwrap();
+        b.put(&key, Bytes::from_static(b"0123456789"))
+            .await
+            .unwrap();
+        assert_eq!(&b.get_range(&key, 2..5).await.unwrap()[..], b"234");
+        assert_eq!(&b.get_range(&key, 8..64).await.unwrap()[..], b"89");
+        assert!(b.get_range(&key, 4..4).await.unwrap().is_empty());
+        let missing = VolumePath::new("nope").unwrap();
+        assert!(matches!(
+            b.get_range(&missing, 0..1).await,
+            Err(VolumeError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn directory_delete_and_rename_cover_every_object() {
+        let b = memory();
+        for k in ["d/a", "d/sub/b"] {
+            b.put(&VolumePath::new(k).unwrap(), Bytes::from_static(b"x"))
+                .await
+                .unwrap();
+        }
+        let d = VolumePath::new("d").unwrap();
+        assert!(b.stat(&d).await.unwrap().is_dir);
+
+        let moved = VolumePath::new("moved").unwrap();
+        b.rename(&d, &moved).await.unwrap();
+        assert!(matches!(b.stat(&d).await, Err(VolumeError::NotFound(_))));
+        let got = b
+            .get(&VolumePath::new("moved/sub/b").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(&got[..], b"x");
+
+        b.delete(&moved).await.unwrap();
+        assert!(matches!(
+            b.stat(&moved).await,
+            Err(VolumeError::NotFound(_))
+        ));
+        assert!(
+            b.list(&VolumePath::new(".").unwrap())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
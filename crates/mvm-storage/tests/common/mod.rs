@@ -0,0 +1,359 @@
+//! Hermetic S3 stand-in for object-store integration tests.
+//!
+//! Spawns a wiremock-backed HTTP server on a random localhost port
+//! that speaks just enough of the S3 REST API (path-style, one
+//! bucket) to exercise [`mvm_storage::ObjectStoreBackend`]
+//! end-to-end, the way a local MinIO would:
+//!
+//! - `PUT /<bucket>/<key>` — PutObject, or CopyObject when
+//!   `x-amz-copy-source` is set, or UploadPart with
+//!   `?partNumber=&uploadId=`.
+//! - `GET /<bucket>/<key>` — GetObject, honouring `Range: bytes=a-b`.
+//! - `HEAD /<bucket>/<key>` — HeadObject.
+//! - `DELETE /<bucket>/<key>` — DeleteObject / AbortMultipartUpload.
+//! - `POST /<bucket>/<key>?uploads` / `?uploadId=` — multipart
+//!   initiate / complete.
+//! - `GET /<bucket>?list-type=2` — ListObjectsV2 with `prefix` and
+//!   `delimiter` (no pagination).
+//!
+//! Requests are not signature-checked; the stand-in records whether
+//! an `Authorization` header was present so tests can assert signing
+//! happened. Every request is appended to an operation log.
+
+#![allow(dead_code)] // not every helper is used by every test
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use mvm_core::volume::ObjectStoreSpec;
+use mvm_storage::{ObjectStoreBackend, ObjectStoreCredentials};
+use wiremock::matchers::path_regex;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+pub const BUCKET: &str = "mvm-test";
+
+/// One logged request: the S3 operation name plus the object key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Op {
+    pub name: &'static str,
+    pub key: String,
+    pub signed: bool,
+    pub range: Option<String>,
+}
+
+#[derive(Default)]
+struct State {
+    objects: BTreeMap<String, Vec<u8>>,
+    uploads: HashMap<String, BTreeMap<u32, Vec<u8>>>,
+    next_upload: u64,
+    ops: Vec<Op>,
+}
+
+/// A running S3 stand-in. Drop the value to tear it down.
+pub struct S3StandIn {
+    pub server: MockServer,
+    state: Arc<Mutex<State>>,
+}
+
+impl S3StandIn {
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let state = Arc::new(Mutex::new(State::default()));
+        Mock::given(path_regex(format!("^/{BUCKET}(/.*)?$")))
+            .respond_with(Responder {
+                state: state.clone(),
+            })
+            .mount(&server)
+            .await;
+        Self { server, state }
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Static credentials pointing at this stand-in.
+    pub fn credentials(&self) -> ObjectStoreCredentials {
+        let payload = format!(
+            r#"{{"endpoint": "{}", "access_key_id": "minio", "secret_access_key": "minio123"}}"#,
+            self.endpoint()
+        );
+        serde_json::from_str(&payload).expect("fixture credentials parse")
+    }
+
+    /// A backend for `s3://<bucket><url_path>` with an optional spec
+    /// prefix.
+    pub fn backend(&self, url_path: &str, prefix: Option<&str>) -> ObjectStoreBackend {
+        let spec = ObjectStoreSpec {
+            url: format!("s3://{BUCKET}{url_path}"),
+            prefix: prefix.map(str::to_string),
+            credentials_ref: None,
+        };
+        ObjectStoreBackend::new(&spec, Some(&self.credentials())).expect("fixture backend")
+    }
+
+    /// Raw bytes stored under the full bucket key, bypassing the
+    /// backend.
+    pub fn object(&self, key: &str) -> Option<Vec<u8>> {
+        self.state.lock().unwrap().objects.get(key).cloned()
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.state.lock().unwrap().objects.keys().cloned().collect()
+    }
+
+    pub fn ops(&self) -> Vec<Op> {
+        self.state.lock().unwrap().ops.clone()
+    }
+
+    pub fn clear_ops(&self) {
+        self.state.lock().unwrap().ops.clear();
+    }
+}
+
+struct Responder {
+    state: Arc<Mutex<State>>,
+}
+
+impl Respond for Responder {
+    fn respond(&self, req: &Request) -> ResponseTemplate {
+        let mut state = self.state.lock().unwrap();
+        let key = percent_decode(
+            req.url
+                .path()
+                .strip_prefix(&format!("/{BUCKET}"))
+                .unwrap_or("")
+                .trim_start_matches('/'),
+        );
+        let query: HashMap<String, String> = req.url.query_pairs().into_owned().collect();
+        let header = |name: &str| {
+            req.headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let signed = header("authorization").is_some();
+        let range = header("range");
+
+        let (name, resp) = match req.method.as_str() {
+            "GET" if key.is_empty() && query.get("list-type").map(String::as_str) == Some("2") => {
+                ("ListObjectsV2", list(&state, &query))
+            }
+            "GET" => ("GetObject", get(&state, &key, range.as_deref())),
+            "HEAD" => ("HeadObject", head(&state, &key)),
+            "PUT" if header("x-amz-copy-source").is_some() => {
+                let src = header("x-amz-copy-source").unwrap();
+                let src = percent_decode(src.trim_start_matches('/'));
+                let src = src
+                    .strip_prefix(&format!("{BUCKET}/"))
+                    .unwrap_or(&src)
+                    .to_string();
+                let resp = match state.objects.get(&src).cloned() {
+                    Some(data) => {
+                        let etag = etag(&data);
+                        state.objects.insert(key.clone(), data);
+                        xml(format!(
+                            "<CopyObjectResult><ETag>{etag}</ETag></CopyObjectResult>"
+                        ))
+                    }
+                    None => no_such_key(),
+                };
+                ("CopyObject", resp)
+            }
+            "PUT" if query.contains_key("uploadId") => {
+                let upload = query["uploadId"].clone();
+                let part: u32 = query["partNumber"].parse().unwrap();
+                let resp = match state.uploads.get_mut(&upload) {
+                    Some(parts) => {
+                        let etag = etag(&req.body);
+                        parts.insert(part, req.body.clone());
+                        ResponseTemplate::new(200).insert_header("ETag", etag.as_str())
+                    }
+                    None => ResponseTemplate::new(404),
+                };
+                ("UploadPart", resp)
+            }
+            "PUT" => {
+                let etag = etag(&req.body);
+                state.objects.insert(key.clone(), req.body.clone());
+                (
+                    "PutObject",
+                    ResponseTemplate::new(200).insert_header("ETag", etag.as_str()),
+                )
+            }
+            "POST" if query.contains_key("uploads") => {
+                state.next_upload += 1;
+                let upload = format!("upload-{}", state.next_upload);
+                state.uploads.insert(upload.clone(), BTreeMap::new());
+                (
+                    "CreateMultipartUpload",
+                    xml(format!(
+                        "<InitiateMultipartUploadResult><Bucket>{BUCKET}</Bucket>\
+                         <Key>{}</Key><UploadId>{upload}</UploadId>\
+                         </InitiateMultipartUploadResult>",
+                        escape(&key)
+                    )),
+                )
+            }
+            "POST" if query.contains_key("uploadId") => {
+                let resp = match state.uploads.remove(&query["uploadId"]) {
+                    Some(parts) => {
+                        let data: Vec<u8> = parts.into_values().flatten().collect();
+                        let etag = etag(&data);
+                        state.objects.insert(key.clone(), data);
+                        xml(format!(
+                            "<CompleteMultipartUploadResult><ETag>{etag}</ETag>\
+                             </CompleteMultipartUploadResult>"
+                        ))
+                    }
+                    None => ResponseTemplate::new(404),
+                };
+                ("CompleteMultipartUpload", resp)
+            }
+            "DELETE" if query.contains_key("uploadId") => {
+                state.uploads.remove(&query["uploadId"]);
+                ("AbortMultipartUpload", ResponseTemplate::new(204))
+            }
+            "DELETE" => {
+                state.objects.remove(&key);
+                ("DeleteObject", ResponseTemplate::new(204))
+            }
+            _ => ("Unsupported", ResponseTemplate::new(501)),
+        };
+        state.ops.push(Op {
+            name,
+            key,
+            signed,
+            range,
+        });
+        resp
+    }
+}
+
+fn list(state: &State, query: &HashMap<String, String>) -> ResponseTemplate {
+    let prefix = query.get("prefix").cloned().unwrap_or_default();
+    let delimiter = query.get("delimiter").cloned().unwrap_or_default();
+    let mut contents = String::new();
+    let mut prefixes: Vec<String> = Vec::new();
+    for (key, data) in state.objects.range(prefix.clone()..) {
+        let Some(rest) = key.strip_prefix(&prefix) else {
+            break;
+        };
+        if !delimiter.is_empty()
+            && let Some(idx) = rest.find(&delimiter)
+        {
+            let common = format!("{prefix}{}", &rest[..idx + delimiter.len()]);
+            if prefixes.last() != Some(&common) {
+                prefixes.push(common);
+            }
+            continue;
+        }
+        contents.push_str(&format!(
+            "<Contents><Key>{}</Key><Size>{}</Size>\
+             <LastModified>2026-01-01T00:00:00.000Z</LastModified>\
+             <ETag>{}</ETag></Contents>",
+            escape(key),
+            data.len(),
+            etag(data)
+        ));
+    }
+    let common: String = prefixes
+        .iter()
+        .map(|p| {
+            format!(
+                "<CommonPrefixes><Prefix>{}</Prefix></CommonPrefixes>",
+                escape(p)
+            )
+        })
+        .collect();
+    xml(format!(
+        "<ListBucketResult><Name>{BUCKET}</Name><Prefix>{}</Prefix>\
+         <IsTruncated>false</IsTruncated>{contents}{common}</ListBucketResult>",
+        escape(&prefix)
+    ))
+}
+
+fn get(state: &State, key: &str, range: Option<&str>) -> ResponseTemplate {
+    let Some(data) = state.objects.get(key) else {
+        return no_such_key();
+    };
+    let Some(spec) = range.and_then(|r| r.strip_prefix("bytes=")) else {
+        return object_response(200, data).set_body_bytes(data.clone());
+    };
+    let (start, end) = spec.split_once('-').expect("fixture: bytes=a-b");
+    let start: usize = start.parse().unwrap();
+    let end: usize = if end.is_empty() {
+        data.len() - 1
+    } else {
+        end.parse::<usize>().unwrap().min(data.len() - 1)
+    };
+    if start >= data.len() {
+        return ResponseTemplate::new(416);
+    }
+    let slice = data[start..=end].to_vec();
+    object_response(206, &slice)
+        .insert_header(
+            "Content-Range",
+            format!("bytes {start}-{end}/{}", data.len()).as_str(),
+        )
+        .set_body_bytes(slice)
+}
+
+fn head(state: &State, key: &str) -> ResponseTemplate {
+    match state.objects.get(key) {
+        Some(data) => object_response(200, data).set_body_bytes(data.clone()),
+        None => ResponseTemplate::new(404),
+    }
+}
+
+fn object_response(status: u16, data: &[u8]) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .insert_header("ETag", etag(data).as_str())
+        .insert_header("Last-Modified", "Thu, 01 Jan 2026 00:00:00 GMT")
+        .insert_header("Content-Type", "application/octet-stream")
+}
+
+fn no_such_key() -> ResponseTemplate {
+    ResponseTemplate::new(404).set_body_raw(
+        "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>",
+        "application/xml",
+    )
+}
+
+fn xml(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/xml")
+}
+
+/// Cheap content tag — stable per content, which is all the backend
+/// looks at.
+fn etag(data: &[u8]) -> String {
+    let sum = data.iter().fold(0u64, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(u64::from(*b))
+    });
+    format!("\"{sum:016x}-{}\"", data.len())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && let Ok(b) = u8::from_str_radix(&s[i + 1..i + 3], 16)
+        {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).expect("fixture keys are utf-8")
+}
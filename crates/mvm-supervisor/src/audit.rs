@@ -203,6 +203,12 @@ pub enum AuditError {
 
     #[error("io error writing audit entry: {0}")]
     Io(String),
+
+    /// A bounded stream spool is full of entries its collector has
+    /// not acknowledged yet. The entry was *not* signed; the caller
+    /// decides whether to retry, drop, or fail the operation.
+    #[error("audit stream backpressure: {0}")]
+    Backpressure(String),
 }
 
 #[async_trait]
@@ -345,6 +351,8 @@ mod tests {
             artifact: mvm_policy::policies::ArtifactPolicy::default(),
             keys: KeyPolicy::default(),
             audit: AuditPolicy::default(),
+            threat: Default::default(),
+            fs_quota: None,
             tenant_overlays: BTreeMap::new(),
         }
     }
@@ -190,7 +190,7 @@ impl AuditSigner for FileAuditSigner {
 ///
 /// Plan 102 W6.A commit 2: cross-process chain integrity for the
 /// per-tenant audit chain.
-fn flock_exclusive(file: &std::fs::File) -> std::io::Result<()> {
+pub(crate) fn flock_exclusive(file: &std::fs::File) -> std::io::Result<()> {
     use rustix::fs::{FlockOperation, flock};
     use std::os::fd::AsFd;
     flock(file.as_fd(), FlockOperation::LockExclusive).map_err(std::io::Error::from)
@@ -267,7 +267,7 @@ pub fn verify_audit_chain(path: &Path, verifying_key: &VerifyingKey) -> Result<u
     Ok(count)
 }
 
-fn hash_line(bytes: &[u8]) -> [u8; 32] {
+pub(crate) fn hash_line(bytes: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(bytes);
     hasher.finalize().into()
@@ -6,12 +6,19 @@
 //! resolver); for Wave 2.1 the policy is constructed directly from
 //! a list of (host, port) pairs.
 //!
-//! Match semantics:
-//! - Exact host match (no wildcards). Wave 2.5 introduces SNI-pin
-//!   semantics where the cert SAN is what we match — for now
-//!   `host` is the literal request host string.
-//! - Port match exact. `0` in the allowlist means "any port for
-//!   this host", which the caller opts into per entry.
+//! Match semantics are [`mvm_core::policy::egress_rule`]'s, shared
+//! with the guest DNS endpoint and the TLS-interception CA:
+//! - Hosts match exactly, as `*.example.com` (subdomains only), or
+//!   as `.example.com` (apex and subdomains). Case-insensitive. The
+//!   match is on the literal request host string — Wave 2.5's
+//!   SNI-pin semantics match the cert SAN instead.
+//! - Ports match exactly or by inclusive range. `0` in the
+//!   `(host, port)` form means "any port for this host", which the
+//!   caller opts into per entry.
+//!
+//! On allow the inspector records the rule that matched in
+//! [`RequestCtx::matched_rule`] — the most specific one when several
+//! overlap — so the audit trail explains the decision.
 //!
 //! Threat shape addressed:
 //! - SSRF that probes random ports of internal hosts.
@@ -19,75 +26,71 @@
 //!   never authorised to call.
 //! - Tool-call exfiltration over a side-channel host.
 
-use std::collections::BTreeSet;
-
 use async_trait::async_trait;
+use mvm_core::policy::egress_rule::{AllowRule, RuleError};
 
 use crate::inspector::{Inspector, InspectorVerdict, RequestCtx};
 
-/// Allowed destination entry. `port = 0` is the explicit "any port
-/// for this host" wildcard; we use `0` rather than `Option<u16>` so
-/// the type is `Copy` and `Ord` (handy for the BTreeSet).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Destination {
-    pub host_hash: u64,
-    pub port: u16,
-}
-
-/// Internal helper: stable hash of the host string. We can't store
-/// `String` in a `Copy + Ord` type, so we hash the host once at
-/// build-time and store only the (hash, port) pair. The original
-/// host string lives alongside in a parallel `Vec<String>` for
-/// audit-rendering.
-fn host_hash(host: &str) -> u64 {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    let mut hasher = DefaultHasher::new();
-    host.to_ascii_lowercase().hash(&mut hasher);
-    hasher.finish()
-}
-
-/// Explicit (host, port) allowlist inspector. Constructed from the
-/// workload's `EgressPolicy.allow_list` (Wave 2.6 wires the resolver).
+/// Explicit allowlist inspector. Constructed from the workload's
+/// `EgressPolicy` (Wave 2.6 wires the resolver).
 pub struct DestinationPolicy {
-    allowed: BTreeSet<Destination>,
-    /// Parallel store of original host strings (case preserved) so
-    /// the deny-reason text is human-readable. Index aligns with the
-    /// hash-based set is *not* preserved — we rebuild this set on
-    /// every `new()` call from the input list. The set is small
-    /// (typical workload allowlist is <50 entries), so storage is
-    /// trivial.
+    rules: Vec<AllowRule>,
+    /// Rendered allow-list for deny reasons, in input order. Also
+    /// lists entries [`Self::new`] refused as invalid, so an operator
+    /// reading a deny can see the typo. The set is small (typical
+    /// workload allowlist is <50 entries), so storage is trivial.
     allowed_display: Vec<String>,
 }
 
 impl DestinationPolicy {
     /// Build from a list of `(host, port)` pairs. `port = 0` means
-    /// "any port for this host". Duplicate entries are deduped.
+    /// "any port for this host"; `host` may be a pattern. Duplicate
+    /// entries are deduped. An entry that isn't a valid pattern is
+    /// logged and never matches — callers that can report errors
+    /// validate first (see [`Self::from_egress_policy`]).
     pub fn new<I, H>(entries: I) -> Self
     where
         I: IntoIterator<Item = (H, u16)>,
         H: AsRef<str>,
     {
-        let mut allowed = BTreeSet::new();
-        let mut display = Vec::new();
+        let mut policy = Self::from_rules([]);
         for (host, port) in entries {
-            let host_str = host.as_ref();
-            allowed.insert(Destination {
-                host_hash: host_hash(host_str),
-                port,
-            });
-            let entry = if port == 0 {
-                format!("{host_str}:*")
-            } else {
-                format!("{host_str}:{port}")
-            };
-            if !display.contains(&entry) {
-                display.push(entry);
+            let host = host.as_ref();
+            match AllowRule::from_tuple(host, port) {
+                Ok(rule) => policy.push(rule),
+                Err(e) => {
+                    tracing::warn!(host, port, error = %e, "ignoring invalid allow-list entry");
+                    policy
+                        .allowed_display
+                        .push(format!("{host}:{port} (invalid)"));
+                }
             }
         }
-        Self {
-            allowed,
-            allowed_display: display,
+        policy
+    }
+
+    /// Build from already-validated rules.
+    pub fn from_rules(rules: impl IntoIterator<Item = AllowRule>) -> Self {
+        let mut policy = Self {
+            rules: Vec::new(),
+            allowed_display: Vec::new(),
+        };
+        for rule in rules {
+            policy.push(rule);
+        }
+        policy
+    }
+
+    /// Build from a bundle's `[egress]` section: `allow_list` and
+    /// `allow_port_ranges`. Fails on the first invalid entry.
+    pub fn from_egress_policy(policy: &mvm_policy::EgressPolicy) -> Result<Self, RuleError> {
+        Ok(Self::from_rules(policy.allow_rules()?))
+    }
+
+    fn push(&mut self, rule: AllowRule) {
+        if !self.rules.contains(&rule) {
+            self.allowed_display.push(rule.to_string());
+            self.rules.push(rule);
         }
     }
 
@@ -95,20 +98,26 @@ impl DestinationPolicy {
     /// `egress_policy` resolved yet, or when the policy bundle's
     /// allow-list is empty (a deliberate fail-closed configuration).
     pub fn deny_all() -> Self {
-        Self::new::<_, &str>(std::iter::empty())
+        Self::from_rules([])
     }
 
-    fn matches(&self, host: &str, port: u16) -> bool {
-        let h = host_hash(host);
-        // Exact (host, port) match.
-        if self.allowed.contains(&Destination { host_hash: h, port }) {
-            return true;
-        }
-        // (host, *) wildcard match.
-        self.allowed.contains(&Destination {
-            host_hash: h,
-            port: 0,
-        })
+    /// The rule that admits `(host, port)`, or `None` when nothing
+    /// does. The most specific host pattern wins, then the narrowest
+    /// port range.
+    pub fn explain(&self, host: &str, port: u16) -> Option<&AllowRule> {
+        AllowRule::best_match(&self.rules, host, port)
+    }
+
+    /// `true` when `(host, port)` is on the allowlist. Case-insensitive.
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        self.explain(host, port).is_some()
+    }
+
+    /// `true` when `host` is allowed on *any* port. The guest DNS
+    /// endpoint ([`crate::proxy::dns`]) answers by name alone, before
+    /// the port is known, so it needs the host-only projection.
+    pub fn matches_host(&self, host: &str) -> bool {
+        self.rules.iter().any(|r| r.host.matches(host))
     }
 
     /// Render the allowlist for inclusion in deny reasons.
@@ -128,17 +137,19 @@ impl Inspector for DestinationPolicy {
     }
 
     async fn inspect(&self, ctx: &mut RequestCtx) -> InspectorVerdict {
-        if self.matches(&ctx.host, ctx.port) {
-            InspectorVerdict::Allow
-        } else {
-            InspectorVerdict::Deny {
+        match self.explain(&ctx.host, ctx.port) {
+            Some(rule) => {
+                ctx.matched_rule = Some(rule.to_string());
+                InspectorVerdict::Allow
+            }
+            None => InspectorVerdict::Deny {
                 reason: format!(
                     "destination {}:{} not in policy allowlist (allowed: {})",
                     ctx.host,
                     ctx.port,
                     self.display_allowlist()
                 ),
-            }
+            },
         }
     }
 }
@@ -226,4 +237,72 @@ mod tests {
             other => panic!("expected Deny, got {other:?}"),
         }
     }
+
+    #[test]
+    fn matches_host_ignores_the_port() {
+        let policy = DestinationPolicy::new([("api.example.com", 443u16), ("any.example.com", 0)]);
+        assert!(policy.matches_host("api.example.com"));
+        assert!(policy.matches_host("API.example.com"));
+        assert!(policy.matches_host("any.example.com"));
+        assert!(!policy.matches_host("example.com"));
+        assert!(!DestinationPolicy::deny_all().matches_host("api.example.com"));
+    }
+
+    #[tokio::test]
+    async fn wildcard_allows_subdomains_and_names_the_rule() {
+        let policy = DestinationPolicy::new([
+            ("*.githubusercontent.com", 443u16),
+            ("raw.githubusercontent.com", 443),
+        ]);
+        let mut c = ctx("objects.githubusercontent.com", 443);
+        assert!(policy.inspect(&mut c).await.is_allow());
+        assert_eq!(
+            c.matched_rule.as_deref(),
+            Some("*.githubusercontent.com:443")
+        );
+
+        // The exact entry is more specific than the wildcard.
+        let mut c = ctx("raw.githubusercontent.com", 443);
+        assert!(policy.inspect(&mut c).await.is_allow());
+        assert_eq!(
+            c.matched_rule.as_deref(),
+            Some("raw.githubusercontent.com:443")
+        );
+
+        // `*.` never covers the apex.
+        let mut c = ctx("githubusercontent.com", 443);
+        assert!(policy.inspect(&mut c).await.is_deny());
+        assert_eq!(c.matched_rule, None);
+    }
+
+    #[test]
+    fn suffix_and_port_range_rules_from_an_egress_policy() {
+        let egress = mvm_policy::EgressPolicy {
+            allow_list: vec![(".example.com".to_string(), 443)],
+            allow_port_ranges: vec![("grpc.example.net".to_string(), 50051, 50059)],
+            ..Default::default()
+        };
+        let policy = DestinationPolicy::from_egress_policy(&egress).unwrap();
+        assert!(policy.matches("example.com", 443));
+        assert!(policy.matches("api.example.com", 443));
+        assert!(!policy.matches("api.example.com", 80));
+        assert!(policy.matches("grpc.example.net", 50055));
+        assert!(!policy.matches("grpc.example.net", 50060));
+        assert!(policy.matches_host("deep.api.example.com"));
+    }
+
+    #[test]
+    fn invalid_entries_fail_closed() {
+        let policy = DestinationPolicy::new([("*.com", 443u16), ("api.example.com", 443)]);
+        assert!(!policy.matches("evil.com", 443));
+        assert!(!policy.matches_host("evil.com"));
+        assert!(policy.matches("api.example.com", 443));
+        assert!(policy.display_allowlist().contains("*.com:443 (invalid)"));
+
+        let egress = mvm_policy::EgressPolicy {
+            allow_list: vec![("*.com".to_string(), 443)],
+            ..Default::default()
+        };
+        assert!(DestinationPolicy::from_egress_policy(&egress).is_err());
+    }
 }
@@ -0,0 +1,199 @@
+//! Per-VM egress byte budget for [`crate::l7_proxy::L7EgressProxy`].
+//!
+//! Two independent knobs, both sourced from `EgressPolicy`:
+//!
+//! - **Byte cap** (`byte_cap_bytes`) — total payload bytes the VM may
+//!   move through the proxy, both directions combined, over the
+//!   proxy's lifetime. Once spent, new CONNECTs are refused with
+//!   `403 byte_cap_exceeded` and in-flight tunnels are cut at the
+//!   next chunk. Every refusal is audited with the `byte_cap`
+//!   pseudo-inspector.
+//! - **Bandwidth** (`bandwidth_bytes_per_sec`) — a token bucket
+//!   shared by every tunnel of the VM. The bucket holds one second of
+//!   burst; traffic past it is delayed, never dropped.
+//!
+//! Only payload bytes count: spliced TLS records on opaque tunnels,
+//! request and response bodies on intercepted ones. HTTP framing and
+//! headers are not metered.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The VM spent its whole `byte_cap_bytes` allowance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("byte_cap_exceeded: egress budget of {cap} bytes spent")]
+pub struct ByteCapExceeded {
+    pub cap: u64,
+}
+
+// allow(secret-debug): rate-limiter state (byte counts), not a credential.
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_sec: u64,
+    /// Negative while callers are paying off a burst they already
+    /// sent; the deficit is what the next caller waits out.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            tokens: bytes_per_sec as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn take(&mut self, n: u64) -> Duration {
+        let now = Instant::now();
+        let rate = self.bytes_per_sec as f64;
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.refilled_at = now;
+        self.tokens -= n as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate)
+        }
+    }
+}
+
+/// Shared byte accounting for one VM's egress. Cheap to consult on
+/// every chunk; hold it behind an `Arc`.
+#[derive(Debug)]
+pub struct EgressBudget {
+    byte_cap: Option<u64>,
+    used: AtomicU64,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl EgressBudget {
+    /// `None` disables the corresponding limit. A zero rate is
+    /// rejected upstream at policy load (it would stall forever).
+    pub fn new(byte_cap: Option<u64>, bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            byte_cap,
+            used: AtomicU64::new(0),
+            bucket: bytes_per_sec
+                .filter(|rate| *rate > 0)
+                .map(|rate| Mutex::new(TokenBucket::new(rate))),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None, None)
+    }
+
+    /// True when neither limit is configured — the proxy keeps its
+    /// zero-overhead `copy_bidirectional` splice in that case.
+    pub fn is_unlimited(&self) -> bool {
+        self.byte_cap.is_none() && self.bucket.is_none()
+    }
+
+    pub fn byte_cap(&self) -> Option<u64> {
+        self.byte_cap
+    }
+
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        self.bucket
+            .as_ref()
+            .map(|b| b.lock().expect("bucket mutex poisoned").bytes_per_sec)
+    }
+
+    /// Payload bytes metered so far.
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Fails once the cap is fully spent; used to refuse new work
+    /// before any bytes move.
+    pub fn check(&self) -> Result<(), ByteCapExceeded> {
+        match self.byte_cap {
+            Some(cap) if self.used() >= cap => Err(ByteCapExceeded { cap }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Account for `n` bytes about to be forwarded. Returns how long
+    /// the caller must wait before forwarding them to stay under the
+    /// bandwidth limit, or an error if they would overrun the cap.
+    pub fn reserve(&self, n: u64) -> Result<Duration, ByteCapExceeded> {
+        if let Some(cap) = self.byte_cap {
+            let before = self.used.fetch_add(n, Ordering::Relaxed);
+            if before.saturating_add(n) > cap {
+                return Err(ByteCapExceeded { cap });
+            }
+        } else {
+            self.used.fetch_add(n, Ordering::Relaxed);
+        }
+        Ok(match &self.bucket {
+            Some(bucket) => bucket.lock().expect("bucket mutex poisoned").take(n),
+            None => Duration::ZERO,
+        })
+    }
+
+    /// [`reserve`](Self::reserve) plus the wait.
+    pub async fn consume(&self, n: u64) -> Result<(), ByteCapExceeded> {
+        let wait = self.reserve(n)?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+}
+
+impl Default for EgressBudget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_budget_never_waits_or_fails() {
+        let budget = EgressBudget::unlimited();
+        assert!(budget.is_unlimited());
+        assert_eq!(budget.reserve(u64::MAX / 2).unwrap(), Duration::ZERO);
+        assert!(budget.check().is_ok());
+    }
+
+    #[test]
+    fn byte_cap_trips_on_the_chunk_that_overruns() {
+        let budget = EgressBudget::new(Some(100), None);
+        assert!(budget.reserve(60).is_ok());
+        assert!(budget.check().is_ok());
+        assert_eq!(budget.reserve(41), Err(ByteCapExceeded { cap: 100 }));
+        assert_eq!(budget.check(), Err(ByteCapExceeded { cap: 100 }));
+    }
+
+    #[test]
+    fn exact_cap_is_allowed_then_refused() {
+        let budget = EgressBudget::new(Some(10), None);
+        assert!(budget.reserve(10).is_ok());
+        assert!(budget.check().is_err());
+    }
+
+    #[test]
+    fn bandwidth_allows_one_second_burst_then_delays() {
+        let budget = EgressBudget::new(None, Some(1000));
+        assert_eq!(budget.bytes_per_sec(), Some(1000));
+        assert_eq!(budget.reserve(1000).unwrap(), Duration::ZERO);
+        let wait = budget.reserve(500).unwrap();
+        assert!(
+            wait >= Duration::from_millis(450) && wait <= Duration::from_millis(500),
+            "wait {wait:?}"
+        );
+    }
+
+    #[test]
+    fn zero_rate_is_treated_as_unset() {
+        let budget = EgressBudget::new(None, Some(0));
+        assert!(budget.is_unlimited());
+    }
+}
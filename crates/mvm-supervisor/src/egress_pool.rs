@@ -0,0 +1,185 @@
+//! Upstream connection pool for intercepted egress.
+//!
+//! Agent workloads fire many short HTTPS calls at the same few API
+//! hosts. With TLS interception on, the proxy owns the upstream side
+//! of each tunnel, so it can keep those TCP + TLS sessions warm and
+//! reuse them across CONNECTs instead of paying a fresh handshake per
+//! call.
+//!
+//! Pool entries are keyed by `(host, port, pinned IP)`. The host is
+//! part of the key because the upstream certificate was verified for
+//! that name; the pinned IP is part of the key because every CONNECT
+//! still resolves and runs `SsrfGuard` against its own resolution —
+//! a connection is only reused when the fresh pin lands on the same
+//! address. DNS results are never cached here.
+//!
+//! HTTP/2 senders are shared (the connection multiplexes); HTTP/1.1
+//! senders are handed out exclusively and parked again after the
+//! response head arrives. Opaque (non-intercepted) tunnels are raw
+//! TLS bytes and never pooled.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::client::conn::{http1, http2};
+use hyper::{Request, Response};
+
+/// Idle connections older than this are dropped at the next pool
+/// access. Shorter than the common 120 s server-side keep-alive so
+/// the proxy rarely races an upstream close.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Upper bound on parked HTTP/1.1 connections per destination.
+pub const DEFAULT_POOL_MAX_IDLE_PER_DESTINATION: usize = 8;
+
+/// One upstream destination as pinned by a CONNECT.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PoolKey {
+    pub host: String,
+    pub port: u16,
+    pub pinned: IpAddr,
+}
+
+/// A ready-to-use upstream connection, HTTP/1.1 or HTTP/2 depending
+/// on what the upstream negotiated over ALPN.
+pub(crate) enum UpstreamSender {
+    Http1(http1::SendRequest<Full<Bytes>>),
+    Http2(http2::SendRequest<Full<Bytes>>),
+}
+
+impl UpstreamSender {
+    pub(crate) fn is_http2(&self) -> bool {
+        matches!(self, Self::Http2(_))
+    }
+
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Http1(s) => s.is_closed(),
+            Self::Http2(s) => s.is_closed(),
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        match self {
+            Self::Http1(s) => s.is_ready(),
+            Self::Http2(s) => s.is_ready(),
+        }
+    }
+
+    pub(crate) async fn send(
+        &mut self,
+        request: Request<Full<Bytes>>,
+    ) -> hyper::Result<Response<Incoming>> {
+        match self {
+            Self::Http1(s) => {
+                s.ready().await?;
+                s.send_request(request).await
+            }
+            Self::Http2(s) => {
+                s.ready().await?;
+                s.send_request(request).await
+            }
+        }
+    }
+}
+
+struct Idle {
+    sender: UpstreamSender,
+    parked_at: Instant,
+}
+
+/// Warm upstream connections, per [`PoolKey`].
+pub struct UpstreamPool {
+    idle: Mutex<HashMap<PoolKey, Vec<Idle>>>,
+    max_idle_per_destination: usize,
+    idle_timeout: Duration,
+}
+
+impl UpstreamPool {
+    pub fn new(max_idle_per_destination: usize, idle_timeout: Duration) -> Self {
+        Self {
+            idle: Mutex::new(HashMap::new()),
+            max_idle_per_destination,
+            idle_timeout,
+        }
+    }
+
+    /// Take a usable connection for `key`, if one is parked. Closed
+    /// and expired entries are pruned on the way.
+    pub(crate) fn checkout(&self, key: &PoolKey) -> Option<UpstreamSender> {
+        let mut idle = self.idle.lock().expect("pool mutex poisoned");
+        let entries = idle.get_mut(key)?;
+        let timeout = self.idle_timeout;
+        entries.retain(|e| !e.sender.is_closed() && e.parked_at.elapsed() < timeout);
+        let picked = entries.iter().position(|e| e.sender.is_ready());
+        let sender = picked.map(|i| match &entries[i].sender {
+            // h2 multiplexes — hand out a clone, keep the original.
+            UpstreamSender::Http2(s) => {
+                let shared = UpstreamSender::Http2(s.clone());
+                entries[i].parked_at = Instant::now();
+                shared
+            }
+            UpstreamSender::Http1(_) => entries.swap_remove(i).sender,
+        });
+        if entries.is_empty() {
+            idle.remove(key);
+        }
+        sender
+    }
+
+    /// Park a connection for reuse. HTTP/1.1 connections still
+    /// streaming a response body stay parked but are skipped by
+    /// [`checkout`](Self::checkout) until the body finishes.
+    pub(crate) fn checkin(&self, key: &PoolKey, sender: UpstreamSender) {
+        if sender.is_closed() {
+            return;
+        }
+        let mut idle = self.idle.lock().expect("pool mutex poisoned");
+        let entries = idle.entry(key.clone()).or_default();
+        if sender.is_http2() && entries.iter().any(|e| e.sender.is_http2()) {
+            // One shared h2 connection per destination is enough.
+            return;
+        }
+        if entries.len() >= self.max_idle_per_destination {
+            return;
+        }
+        entries.push(Idle {
+            sender,
+            parked_at: Instant::now(),
+        });
+    }
+
+    /// Parked connections for `key`, live or not yet pruned.
+    pub fn idle_connections(&self, key: &PoolKey) -> usize {
+        self.idle
+            .lock()
+            .expect("pool mutex poisoned")
+            .get(key)
+            .map_or(0, Vec::len)
+    }
+}
+
+impl Default for UpstreamPool {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_POOL_MAX_IDLE_PER_DESTINATION,
+            DEFAULT_POOL_IDLE_TIMEOUT,
+        )
+    }
+}
+
+impl std::fmt::Debug for UpstreamPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let idle = self.idle.lock().expect("pool mutex poisoned");
+        f.debug_struct("UpstreamPool")
+            .field("destinations", &idle.len())
+            .field("max_idle_per_destination", &self.max_idle_per_destination)
+            .field("idle_timeout", &self.idle_timeout)
+            .finish()
+    }
+}
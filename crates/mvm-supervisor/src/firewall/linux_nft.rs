@@ -19,25 +19,30 @@
 //!
 //! ```text
 //! table inet mvm_<vm_id> {
+//!   counter allowed {}
+//!   counter dropped {}
 //!   chain forward {
 //!     type filter hook forward priority 0; policy drop;
-//!     iifname "<tap>" oifname "<proxy>" accept
+//!     iifname "<tap>" oifname "<proxy>" counter name "allowed" accept
 //!     oifname "<tap>" iifname "<proxy>" ct state established,related accept
-//!     iifname "<tap>" drop  # final fail-closed (redundant w/ policy drop)
+//!     iifname "<tap>" counter name "dropped" drop  # final fail-closed
 //!   }
 //! }
 //! ```
 //!
 //! The `inet` family covers both IPv4 and IPv6 in one table.
 //! Each per-VM table is named `mvm_<vm_id>` (slug-validated) so
-//! the supervisor can `nft delete table inet mvm_<vm_id>` on
-//! teardown without touching unrelated tables.
+//! teardown touches nothing else. Table framing — atomic replace,
+//! idempotent delete, counter read-back — is shared with the bridge
+//! policy tables via [`mvm_core::policy::nft`].
 
 use std::io::Write;
 use std::process::{Command, Stdio};
 
 use thiserror::Error;
 
+use mvm_core::policy::nft::{self, COUNTER_ALLOWED, COUNTER_DROPPED, VmNftCounters};
+
 use crate::firewall::{FirewallEnforcer, FirewallError, FirewallSpec};
 
 /// Errors `apply` can return.
@@ -58,12 +63,16 @@ pub enum NftError {
     },
     #[error("nft binary not found in $PATH — install nftables")]
     NotInstalled,
+    #[error("reading nft counters failed: {0}")]
+    Counters(String),
 }
 
 /// Injectable nft backend. Production shells out via [`CommandNftApplier`];
 /// tests capture the generated script without requiring root or nftables.
 pub trait NftApplier {
     fn apply_rules(&self, rules: &str) -> Result<(), NftError>;
+    /// `nft -j list counters table inet <table>` output.
+    fn list_counters(&self, table: &str) -> Result<String, NftError>;
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -73,6 +82,13 @@ impl NftApplier for CommandNftApplier {
     fn apply_rules(&self, rules: &str) -> Result<(), NftError> {
         apply(rules)
     }
+
+    fn list_counters(&self, table: &str) -> Result<String, NftError> {
+        let args = nft::list_counters_args(table).map_err(|_| NftError::InvalidTableId {
+            value: table.to_string(),
+        })?;
+        run_nft(&args, None)
+    }
 }
 
 /// Linux implementation of the host firewall enforcer. It installs
@@ -107,6 +123,13 @@ impl<A: NftApplier> LinuxNftFirewall<A> {
         let rules = build_teardown_rules(vm_id)?;
         self.applier.apply_rules(&rules)
     }
+
+    /// Read back the table's `allowed` / `dropped` counters.
+    pub fn counters(&self, vm_id: &str) -> Result<VmNftCounters, NftError> {
+        let table = table_name(vm_id)?;
+        let json = self.applier.list_counters(&table)?;
+        nft::parse_counters(&table, &json).map_err(|e| NftError::Counters(e.to_string()))
+    }
 }
 
 impl<A: NftApplier + Send + Sync> FirewallEnforcer for LinuxNftFirewall<A> {
@@ -133,42 +156,48 @@ impl<A: NftApplier + Send + Sync> FirewallEnforcer for LinuxNftFirewall<A> {
 /// All three identifiers are slug-validated against
 /// `[A-Za-z0-9_-]+`; anything else fails at script-build time so
 /// shell-injection attempts via the policy bundle can't slip
-/// through.
+/// through. The script replaces any existing table for the VM in
+/// one transaction, so re-installing is safe.
 pub fn build_default_deny_rules(
     vm_id: &str,
     tap_iface: &str,
     proxy_iface: &str,
 ) -> Result<String, NftError> {
-    validate_slug("vm_id", vm_id).map_err(|_| NftError::InvalidTableId {
-        value: vm_id.to_string(),
-    })?;
-    validate_slug("tap_iface", tap_iface).map_err(|_| NftError::InvalidInterface {
+    let table = table_name(vm_id)?;
+    nft::validate_ident("tap_iface", tap_iface).map_err(|_| NftError::InvalidInterface {
         value: tap_iface.to_string(),
     })?;
-    validate_slug("proxy_iface", proxy_iface).map_err(|_| NftError::InvalidInterface {
+    nft::validate_ident("proxy_iface", proxy_iface).map_err(|_| NftError::InvalidInterface {
         value: proxy_iface.to_string(),
     })?;
-    Ok(format!(
-        "table inet mvm_{vm_id} {{\n\
-         \tchain forward {{\n\
+    let mut body = nft::counter_declarations(&[COUNTER_ALLOWED, COUNTER_DROPPED]);
+    body.push_str(&format!(
+        "\tchain forward {{\n\
          \t\ttype filter hook forward priority 0; policy drop;\n\
-         \t\tiifname \"{tap_iface}\" oifname \"{proxy_iface}\" accept\n\
+         \t\tiifname \"{tap_iface}\" oifname \"{proxy_iface}\" counter name \"{COUNTER_ALLOWED}\" accept\n\
          \t\toifname \"{tap_iface}\" iifname \"{proxy_iface}\" ct state established,related accept\n\
-         \t\tiifname \"{tap_iface}\" drop\n\
-         \t}}\n\
-         }}\n"
-    ))
+         \t\tiifname \"{tap_iface}\" counter name \"{COUNTER_DROPPED}\" drop\n\
+         \t}}\n"
+    ));
+    nft::replace_table_script(&table, &body).map_err(|_| NftError::InvalidTableId {
+        value: vm_id.to_string(),
+    })
 }
 
 /// Build the teardown ruleset — removes the per-VM table.
-/// Idempotent on the nft side: `nft delete table` errors if the
-/// table doesn't exist, so callers wrap with `nft list table`
-/// existence check or tolerate the error.
+/// Idempotent: the table is declared before it is deleted, so a
+/// second teardown (or one after a failed install) succeeds.
 pub fn build_teardown_rules(vm_id: &str) -> Result<String, NftError> {
-    validate_slug("vm_id", vm_id).map_err(|_| NftError::InvalidTableId {
+    nft::delete_table_script(&table_name(vm_id)?).map_err(|_| NftError::InvalidTableId {
+        value: vm_id.to_string(),
+    })
+}
+
+fn table_name(vm_id: &str) -> Result<String, NftError> {
+    nft::validate_ident("vm_id", vm_id).map_err(|_| NftError::InvalidTableId {
         value: vm_id.to_string(),
     })?;
-    Ok(format!("delete table inet mvm_{vm_id}\n"))
+    Ok(format!("mvm_{vm_id}"))
 }
 
 /// Pipe the ruleset to `nft -f -`. Errors with `NotInstalled` if
@@ -180,10 +209,18 @@ pub fn build_teardown_rules(vm_id: &str) -> Result<String, NftError> {
 /// without privileges can still exercise `build_default_deny_rules`
 /// directly.
 pub fn apply(rules: &str) -> Result<(), NftError> {
+    run_nft(&["-f".to_string(), "-".to_string()], Some(rules)).map(|_| ())
+}
+
+/// Run `nft` with `args`, optionally feeding `stdin`; returns stdout.
+fn run_nft(args: &[String], stdin: Option<&str>) -> Result<String, NftError> {
     let mut child = Command::new("nft")
-        .arg("-f")
-        .arg("-")
-        .stdin(Stdio::piped())
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -194,12 +231,12 @@ pub fn apply(rules: &str) -> Result<(), NftError> {
                 NftError::Spawn(e)
             }
         })?;
-    {
-        let stdin = child
+    if let Some(input) = stdin {
+        let pipe = child
             .stdin
             .as_mut()
             .ok_or_else(|| NftError::Spawn(std::io::Error::other("nft stdin unavailable")))?;
-        stdin.write_all(rules.as_bytes()).map_err(NftError::Stdin)?;
+        pipe.write_all(input.as_bytes()).map_err(NftError::Stdin)?;
     }
     let output = child.wait_with_output().map_err(NftError::Spawn)?;
     if !output.status.success() {
@@ -208,20 +245,7 @@ pub fn apply(rules: &str) -> Result<(), NftError> {
             stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
         });
     }
-    Ok(())
-}
-
-fn validate_slug(field: &'static str, value: &str) -> Result<(), &'static str> {
-    if value.is_empty() {
-        return Err(field);
-    }
-    if !value
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
-    {
-        return Err(field);
-    }
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 #[cfg(test)]
@@ -248,6 +272,15 @@ mod tests {
                 .push(rules.to_string());
             Ok(())
         }
+
+        fn list_counters(&self, table: &str) -> Result<String, NftError> {
+            Ok(format!(
+                r#"{{"nftables": [
+                    {{"counter": {{"family": "inet", "name": "allowed", "table": "{table}", "packets": 7, "bytes": 420}}}},
+                    {{"counter": {{"family": "inet", "name": "dropped", "table": "{table}", "packets": 2, "bytes": 120}}}}
+                ]}}"#
+            ))
+        }
     }
 
     #[derive(Debug, Clone, Copy)]
@@ -257,6 +290,10 @@ mod tests {
         fn apply_rules(&self, _rules: &str) -> Result<(), NftError> {
             Err(NftError::NotInstalled)
         }
+
+        fn list_counters(&self, _table: &str) -> Result<String, NftError> {
+            Err(NftError::NotInstalled)
+        }
     }
 
     // ──────────────────────────────────────────────────────────────
@@ -277,7 +314,11 @@ mod tests {
         assert_eq!(calls.len(), 1);
         assert!(calls[0].contains("table inet mvm_vm1"));
         assert!(calls[0].contains("policy drop"));
-        assert!(calls[0].contains("iifname \"mvmtap0\" oifname \"mvmtun0\" accept"));
+        assert!(
+            calls[0].contains(
+                "iifname \"mvmtap0\" oifname \"mvmtun0\" counter name \"allowed\" accept"
+            )
+        );
     }
 
     #[test]
@@ -288,7 +329,23 @@ mod tests {
         firewall.teardown_nft("vm1").expect("teardown rules");
 
         let calls = applier.calls();
-        assert_eq!(calls, vec!["delete table inet mvm_vm1\n".to_string()]);
+        assert_eq!(
+            calls,
+            vec!["table inet mvm_vm1\ndelete table inet mvm_vm1\n".to_string()]
+        );
+    }
+
+    #[test]
+    fn linux_firewall_reads_back_table_counters() {
+        let firewall = LinuxNftFirewall::new(RecordingApplier::default());
+
+        let counters = firewall.counters("vm1").expect("counters");
+        assert_eq!(counters.allowed.packets, 7);
+        assert_eq!(counters.dropped.bytes, 120);
+        assert!(matches!(
+            firewall.counters("vm1; rm"),
+            Err(NftError::InvalidTableId { .. })
+        ));
     }
 
     #[test]
@@ -353,7 +410,11 @@ mod tests {
     fn default_deny_rules_allow_tap_to_proxy_and_return_established() {
         let rules = build_default_deny_rules("vm1", "mvmtap0", "mvmtun0").unwrap();
         // Forward path: tap → proxy.
-        assert!(rules.contains("iifname \"mvmtap0\" oifname \"mvmtun0\" accept"));
+        assert!(
+            rules.contains(
+                "iifname \"mvmtap0\" oifname \"mvmtun0\" counter name \"allowed\" accept"
+            )
+        );
         // Return path: proxy → tap, only established/related.
         assert!(rules.contains("ct state established,related accept"));
     }
@@ -366,7 +427,22 @@ mod tests {
         // semantics. Pinning catches a "simplification" PR that
         // removes it.
         let rules = build_default_deny_rules("vm1", "mvmtap0", "mvmtun0").unwrap();
-        assert!(rules.contains("iifname \"mvmtap0\" drop"));
+        assert!(rules.contains("iifname \"mvmtap0\" counter name \"dropped\" drop"));
+    }
+
+    #[test]
+    fn default_deny_rules_replace_the_table_atomically() {
+        // Declare + delete + re-create in one `nft -f` transaction:
+        // re-installing never leaves the VM without rules, and a
+        // fresh host doesn't fail the `delete`.
+        let rules = build_default_deny_rules("vm1", "mvmtap0", "mvmtun0").unwrap();
+        assert!(
+            rules.starts_with(
+                "table inet mvm_vm1\ndelete table inet mvm_vm1\ntable inet mvm_vm1 {\n"
+            )
+        );
+        assert!(rules.contains("\tcounter allowed {"));
+        assert!(rules.contains("\tcounter dropped {"));
     }
 
     #[test]
@@ -413,13 +489,84 @@ mod tests {
     }
 
     // ──────────────────────────────────────────────────────────────
-    // apply() — error paths exercised hermetically; live nft
-    // success path needs root + an existing tap and is gated by
-    // MVM_LIVE_NFTABLES=1 (no such test included here — operators
-    // run the full smoke via `tests/smoke_network.rs::nftables_*`
-    // which lands in a follow-up).
+    // apply() — error paths exercised hermetically. The live path
+    // (below) needs root + nft and is gated by MVM_LIVE_NFTABLES=1;
+    // it runs in a throwaway network namespace, so it never touches
+    // the host's ruleset.
     // ──────────────────────────────────────────────────────────────
 
+    /// Run `script` under `sh` in a fresh network namespace. `None`
+    /// when the live gate is off.
+    fn in_throwaway_netns(script: &str) -> Option<std::process::Output> {
+        if std::env::var("MVM_LIVE_NFTABLES").as_deref() != Ok("1") {
+            return None;
+        }
+        let mut child = Command::new("unshare")
+            .args(["--net", "sh", "-s"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn unshare");
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(script.as_bytes())
+            .unwrap();
+        Some(child.wait_with_output().expect("unshare output"))
+    }
+
+    #[test]
+    fn live_tables_apply_replace_count_and_tear_down_in_a_netns() {
+        let firewall = build_default_deny_rules("vm1", "mvmtap0", "mvmtun0").unwrap();
+        let spec = nft::GuestNftSpec::new("vm1", "br-mvm", "172.16.0.3", Some("06:00:ac:10:00:03"))
+            .unwrap()
+            .with_dns_endpoint("172.16.0.1".parse().unwrap());
+        let policy = nft::render_policy_table(
+            &spec,
+            &mvm_core::network_policy::NetworkPolicy::allow_list(vec![
+                mvm_core::network_policy::HostPort::new("203.0.113.9", 443),
+            ]),
+            |_| Vec::new(),
+        )
+        .unwrap();
+        let teardown = build_teardown_rules("vm1").unwrap();
+        let policy_teardown = nft::delete_table_script("mvm_policy_vm1").unwrap();
+
+        // Install twice (atomic replace), read counters, tear down
+        // twice (idempotent), and confirm nothing is left.
+        let script = format!(
+            "set -e\n\
+             nft -f - <<'A'\n{firewall}A\n\
+             nft -f - <<'A'\n{firewall}A\n\
+             nft -f - <<'A'\n{policy}A\n\
+             nft -j list counters table inet mvm_vm1\n\
+             echo\n\
+             nft -j list counters table inet mvm_policy_vm1\n\
+             echo\n\
+             nft -f - <<'A'\n{teardown}A\n\
+             nft -f - <<'A'\n{teardown}A\n\
+             nft -f - <<'A'\n{policy_teardown}A\n\
+             nft list tables | grep -c mvm_ || true\n"
+        );
+        let Some(out) = in_throwaway_netns(&script) else {
+            return;
+        };
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(
+            out.status.success(),
+            "nft refused a rendered table: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let mut lines = stdout.lines().filter(|l| !l.trim().is_empty());
+        let fw = nft::parse_counters("mvm_vm1", lines.next().unwrap()).unwrap();
+        assert_eq!(fw.allowed.packets, 0);
+        let pol = nft::parse_counters("mvm_policy_vm1", lines.next().unwrap()).unwrap();
+        assert_eq!(pol.mandatory_deny.packets, 0);
+        assert_eq!(lines.next().map(str::trim), Some("0"), "tables left behind");
+    }
+
     #[test]
     fn apply_fails_with_not_installed_when_nft_absent() {
         // If `nft` is not on $PATH this returns NotInstalled cleanly.
@@ -39,6 +39,13 @@
 //! [`FlowDecisionCtx`] carries optional `sni_hostname` / `url_path`
 //! fields for them to fill).
 //!
+//! L4 enforcement: when [`BridgeConfig::l4`] is set, the passt and
+//! libkrun-gvproxy variants run every guest frame through the
+//! [`crate::proxy::datapath::L4Datapath`] before it reaches the
+//! gateway, so `[[network.l4]]` rules apply to any TCP/UDP protocol
+//! and the guest's DNS queries are answered by the VM's DNS endpoint
+//! instead of reaching the gateway.
+//!
 //! Concurrency model: each VM gets a dedicated `std::thread`
 //! hosting a current-thread tokio runtime + `LocalSet`. Three
 //! tasks run on that runtime — the bridge, the signer, and the
@@ -60,6 +67,7 @@ use tokio::sync::{broadcast, mpsc};
 
 use crate::audit::{AuditEntry, AuditSigner, FlowCloseReason, FlowDirection};
 use crate::gateway_audit::GatewayAuditSink;
+use crate::proxy::datapath::{FrameVerdict, L4Datapath, MAX_DNS_IN_FLIGHT};
 
 // ============================================================================
 // FlowPolicy hook
@@ -184,6 +192,11 @@ pub struct BridgeConfig {
     /// `mvm-libkrun-supervisor::main::run_with_bridge` to populate the
     /// list from the plan's resolved tenant policy bundle.
     pub observers: Vec<Arc<dyn crate::network::Observer>>,
+    /// Userspace L4 enforcement on the guest's frames (see
+    /// [`crate::proxy::datapath`]). `None` relays frames unfiltered.
+    /// Honoured by the passt and libkrun-gvproxy variants; the Vz
+    /// splice lives in Swift and never hands frames to Rust.
+    pub l4: Option<Arc<L4Datapath>>,
 }
 
 // ============================================================================
@@ -414,6 +427,7 @@ fn run_bridge_inner(endpoints: BridgeEndpoints, cfg: BridgeConfig) {
                     supervisor_fd,
                     cfg.vm_name.clone(),
                     cfg.policy.clone(),
+                    cfg.l4.clone(),
                     event_tx,
                 )
                 .await;
@@ -427,11 +441,18 @@ fn run_bridge_inner(endpoints: BridgeEndpoints, cfg: BridgeConfig) {
                     supervisor_listen_path,
                     cfg.vm_name.clone(),
                     cfg.policy.clone(),
+                    cfg.l4.clone(),
                     event_tx,
                 )
                 .await;
             }
             BridgeEndpoints::VzIngest { events_socket_path } => {
+                if cfg.l4.is_some() {
+                    tracing::warn!(
+                        vm = %cfg.vm_name,
+                        "L4 datapath not supported on Vz (splice is in Swift); frames are not filtered"
+                    );
+                }
                 run_vz_ingest_bridge(
                     events_socket_path,
                     cfg.vm_name.clone(),
@@ -459,6 +480,7 @@ async fn run_passt_bridge(
     supervisor_fd: OwnedFd,
     vm_name: String,
     policy: Arc<dyn FlowPolicy>,
+    l4: Option<Arc<L4Datapath>>,
     event_tx: mpsc::Sender<FlowEvent>,
 ) {
     let gateway_std = std::os::unix::net::UnixStream::from(gateway_fd);
@@ -478,9 +500,132 @@ async fn run_passt_bridge(
         }
     };
 
+    // With an L4 datapath, the guest talks to the filter and the
+    // filter talks to the audited byte pipe over an in-process pair.
+    let guest = match l4 {
+        Some(datapath) => {
+            let (filtered, pipe_side) = match tokio::net::UnixStream::pair() {
+                Ok(pair) => pair,
+                Err(e) => {
+                    tracing::error!(error = %e, "passt: failed to create L4 filter pair");
+                    return;
+                }
+            };
+            tokio::task::spawn_local(async move {
+                if let Err(e) = l4_filter_frames(guest, filtered, datapath).await {
+                    tracing::warn!(error = %e, "passt: L4 frame filter stopped");
+                }
+            });
+            pipe_side
+        }
+        None => guest,
+    };
+
     let _ = bridge_copy_bidirectional(gateway, guest, vm_name, policy, event_tx).await;
 }
 
+/// Largest frame accepted on the passt / libkrun unixstream: a
+/// 64 KiB IP packet plus Ethernet header. Anything bigger means the
+/// stream is desynchronised.
+const MAX_STREAM_FRAME: usize = 65_535 + 14;
+
+/// Read one frame in passt's stream framing (4-byte big-endian
+/// length, then the Ethernet frame). `Ok(None)` on clean EOF.
+async fn read_stream_frame<R>(rd: &mut R) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut len = [0u8; 4];
+    match rd.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_STREAM_FRAME {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame length {len} exceeds {MAX_STREAM_FRAME}"),
+        ));
+    }
+    let mut frame = vec![0u8; len];
+    rd.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+async fn write_stream_frame<W>(wr: &mut W, frame: &[u8]) -> std::io::Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let len = u32::try_from(frame.len())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "frame too large"))?;
+    wr.write_all(&len.to_be_bytes()).await?;
+    wr.write_all(frame).await
+}
+
+/// Relay framed Ethernet between the guest and the gateway side,
+/// applying the L4 datapath to every frame. RSTs for refused SYNs are
+/// written back to the guest; DNS answers are resolved on their own
+/// tasks and written back as they finish.
+async fn l4_filter_frames<G, W>(
+    guest: G,
+    gateway: W,
+    datapath: Arc<L4Datapath>,
+) -> std::io::Result<()>
+where
+    G: tokio::io::AsyncRead + tokio::io::AsyncWrite,
+    W: tokio::io::AsyncRead + tokio::io::AsyncWrite,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let (mut guest_rd, guest_wr) = tokio::io::split(guest);
+    let (mut gw_rd, mut gw_wr) = tokio::io::split(gateway);
+    let guest_wr = tokio::sync::Mutex::new(guest_wr);
+    let (answer_tx, mut answer_rx) = mpsc::channel::<Vec<u8>>(MAX_DNS_IN_FLIGHT);
+
+    let egress = async {
+        let answer_tx = answer_tx;
+        while let Some(frame) = read_stream_frame(&mut guest_rd).await? {
+            match datapath.egress(&frame).await {
+                FrameVerdict::Forward => write_stream_frame(&mut gw_wr, &frame).await?,
+                FrameVerdict::Drop => {}
+                FrameVerdict::Reject(reply) => {
+                    write_stream_frame(&mut *guest_wr.lock().await, &reply).await?;
+                }
+                FrameVerdict::Answer(pending) => {
+                    let answer_tx = answer_tx.clone();
+                    tokio::spawn(async move {
+                        if let Some(reply) = pending.resolve().await {
+                            let _ = answer_tx.send(reply).await;
+                        }
+                    });
+                }
+            }
+        }
+        drop(answer_tx);
+        gw_wr.shutdown().await
+    };
+    let answers = async {
+        while let Some(reply) = answer_rx.recv().await {
+            write_stream_frame(&mut *guest_wr.lock().await, &reply).await?;
+        }
+        Ok::<(), std::io::Error>(())
+    };
+    let ingress = async {
+        while let Some(frame) = read_stream_frame(&mut gw_rd).await? {
+            if datapath.ingress(&frame) {
+                write_stream_frame(&mut *guest_wr.lock().await, &frame).await?;
+            }
+        }
+        guest_wr.lock().await.shutdown().await
+    };
+    tokio::try_join!(egress, answers, ingress).map(|_| ())
+}
+
 /// Bidirectional byte-pipe between two `UnixStream`s with
 /// first-byte tracking. Emits `FlowOpened` on the first byte per
 /// direction (after `FlowPolicy::evaluate` returns `Allow`) and
@@ -656,6 +801,7 @@ async fn run_libkrun_gvproxy_bridge(
     supervisor_listen_path: PathBuf,
     vm_name: String,
     policy: Arc<dyn FlowPolicy>,
+    l4: Option<Arc<L4Datapath>>,
     event_tx: mpsc::Sender<FlowEvent>,
 ) {
     use tokio::net::UnixDatagram;
@@ -709,6 +855,7 @@ async fn run_libkrun_gvproxy_bridge(
     let outbound_a = outbound.clone();
     let libkrun_peer_a = libkrun_peer.clone();
     let flow_egress_a = flow_egress.clone();
+    let l4_a = l4.clone();
 
     let egress = async move {
         let mut buf = vec![0u8; 65536];
@@ -718,7 +865,7 @@ async fn run_libkrun_gvproxy_bridge(
                 Err(e) => return Err::<(), std::io::Error>(e),
             };
             // Cache libkrun's autobind peer for the return path.
-            *libkrun_peer_a.lock().await = Some(peer);
+            *libkrun_peer_a.lock().await = Some(peer.clone());
 
             if !egress_opened {
                 let action = policy_a.evaluate(&FlowDecisionCtx {
@@ -753,6 +900,31 @@ async fn run_libkrun_gvproxy_bridge(
                     }
                 }
             }
+            if let Some(datapath) = &l4_a {
+                match datapath.egress(&buf[..n]).await {
+                    FrameVerdict::Forward => {}
+                    FrameVerdict::Drop => continue,
+                    FrameVerdict::Reject(reply) => {
+                        if let Some(path) = peer.as_pathname() {
+                            inbound_a.send_to(&reply, path).await?;
+                        }
+                        continue;
+                    }
+                    FrameVerdict::Answer(pending) => {
+                        if let Some(path) = peer.as_pathname().map(|p| p.to_path_buf()) {
+                            let inbound = inbound_a.clone();
+                            tokio::spawn(async move {
+                                if let Some(reply) = pending.resolve().await
+                                    && let Err(e) = inbound.send_to(&reply, &path).await
+                                {
+                                    tracing::debug!(error = %e, "guest DNS answer not delivered");
+                                }
+                            });
+                        }
+                        continue;
+                    }
+                }
+            }
             // Relay datagram to gvproxy. send (not send_to) since
             // outbound is connected.
             outbound_a.send(&buf[..n]).await?;
@@ -765,6 +937,7 @@ async fn run_libkrun_gvproxy_bridge(
     let outbound_b = outbound.clone();
     let libkrun_peer_b = libkrun_peer.clone();
     let flow_ingress_b = flow_ingress.clone();
+    let l4_b = l4;
 
     let ingress = async move {
         let mut buf = vec![0u8; 65536];
@@ -806,6 +979,11 @@ async fn run_libkrun_gvproxy_bridge(
                     }
                 }
             }
+            if let Some(datapath) = &l4_b
+                && !datapath.ingress(&buf[..n])
+            {
+                continue;
+            }
             // Need libkrun's peer addr to send back. If we haven't
             // seen a packet from libkrun yet, drop this one (no
             // valid return path).
@@ -983,6 +1161,231 @@ async fn handle_vz_ingest(
 mod tests {
     use super::*;
 
+    // -----------------------------------------------------------------
+    // L4 frame filter (passt stream framing)
+    // -----------------------------------------------------------------
+
+    /// Minimal Ethernet + IPv4 + TCP SYN from 10.0.2.15:40000.
+    fn tcp_syn_frame(dst: [u8; 4], dport: u16) -> Vec<u8> {
+        let mut f = vec![0u8; 14 + 20 + 20];
+        f[12..14].copy_from_slice(&0x0800u16.to_be_bytes());
+        f[14] = 0x45;
+        f[16..18].copy_from_slice(&40u16.to_be_bytes());
+        f[23] = 6;
+        f[26..30].copy_from_slice(&[10, 0, 2, 15]);
+        f[30..34].copy_from_slice(&dst);
+        f[34..36].copy_from_slice(&40000u16.to_be_bytes());
+        f[36..38].copy_from_slice(&dport.to_be_bytes());
+        f[46] = 5 << 4;
+        f[47] = 0x02;
+        f
+    }
+
+    #[tokio::test]
+    async fn l4_filter_forwards_allowed_frames_and_rejects_denied_syns() {
+        use crate::l7_proxy::CapturingEgressAuditSink;
+        use crate::proxy::l4::{L4Policy, L4Rule, LiveL4Gate, Protocol};
+
+        let policy = L4Policy::new([L4Rule::single_port(
+            Protocol::Tcp,
+            "203.0.113.7/32".parse().unwrap(),
+            5432,
+        )]);
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        let datapath = Arc::new(L4Datapath::new(
+            Arc::new(LiveL4Gate::new(policy)),
+            audit.clone(),
+        ));
+
+        let (mut guest, guest_side) = tokio::net::UnixStream::pair().unwrap();
+        let (gateway_side, mut gateway) = tokio::net::UnixStream::pair().unwrap();
+        let filter = tokio::spawn(l4_filter_frames(guest_side, gateway_side, datapath));
+
+        let denied = tcp_syn_frame([198, 51, 100, 1], 22);
+        let allowed = tcp_syn_frame([203, 0, 113, 7], 5432);
+        write_stream_frame(&mut guest, &denied).await.unwrap();
+        write_stream_frame(&mut guest, &allowed).await.unwrap();
+
+        let forwarded = read_stream_frame(&mut gateway).await.unwrap().unwrap();
+        assert_eq!(
+            forwarded, allowed,
+            "only the allowed SYN reaches the gateway"
+        );
+        let rst = read_stream_frame(&mut guest).await.unwrap().unwrap();
+        assert_eq!(rst[47], 0x14, "denied SYN is answered with RST|ACK");
+
+        // Gateway → guest traffic for the allowed flow passes through.
+        let mut reply = allowed.clone();
+        reply[26..30].copy_from_slice(&[203, 0, 113, 7]);
+        reply[30..34].copy_from_slice(&[10, 0, 2, 15]);
+        reply[34..36].copy_from_slice(&5432u16.to_be_bytes());
+        reply[36..38].copy_from_slice(&40000u16.to_be_bytes());
+        write_stream_frame(&mut gateway, &reply).await.unwrap();
+        assert_eq!(read_stream_frame(&mut guest).await.unwrap().unwrap(), reply);
+
+        drop(guest);
+        drop(gateway);
+        let _ = filter.await.unwrap();
+        assert_eq!(audit.entries().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn datapath_decisions_are_chain_signed_against_the_plan() {
+        use crate::audit::CapturingAuditSigner;
+        use crate::l7_proxy::{EGRESS_ALLOWED_EVENT, EGRESS_DENIED_EVENT, SignedEgressAuditSink};
+        use crate::proxy::l4::{L4Policy, L4Rule, LiveL4Gate, Protocol};
+
+        let signer = Arc::new(CapturingAuditSigner::new());
+        let plan = Arc::new(test_plan());
+        let policy = L4Policy::new([L4Rule::single_port(
+            Protocol::Tcp,
+            "203.0.113.7/32".parse().unwrap(),
+            5432,
+        )]);
+        let datapath = L4Datapath::new(
+            Arc::new(LiveL4Gate::new(policy)),
+            Arc::new(SignedEgressAuditSink::new(
+                signer.clone(),
+                plan.clone(),
+                None,
+            )),
+        );
+
+        datapath
+            .egress(&tcp_syn_frame([203, 0, 113, 7], 5432))
+            .await;
+        datapath.egress(&tcp_syn_frame([198, 51, 100, 1], 22)).await;
+
+        let entries = signer.entries();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.plan_id == plan.plan_id
+            && e.tenant == plan.tenant
+            && e.labels.get("category").map(String::as_str) == Some("flow")
+            && e.labels.get("deciding_inspector").map(String::as_str) == Some("l4_policy")));
+        assert_eq!(entries[0].event, EGRESS_ALLOWED_EVENT);
+        assert_eq!(entries[0].labels["host"], "203.0.113.7");
+        assert_eq!(entries[0].labels["port"], "5432");
+        assert_eq!(entries[1].event, EGRESS_DENIED_EVENT);
+        assert_eq!(entries[1].labels["host"], "198.51.100.1");
+        assert!(entries[1].labels.contains_key("reason"));
+    }
+
+    /// UDP/53 A query from the guest to the gateway's resolver.
+    fn dns_query_frame(name: &str) -> Vec<u8> {
+        use hickory_proto::op::{Message, MessageType, OpCode, Query};
+        use hickory_proto::rr::{Name, RecordType};
+        use hickory_proto::serialize::binary::BinEncodable;
+
+        let mut message = Message::new(0x4242, MessageType::Query, OpCode::Query);
+        message.add_query(Query::query(Name::from_ascii(name).unwrap(), RecordType::A));
+        let body = message.to_bytes().unwrap();
+        let udp_len = 8 + body.len() as u16;
+        let mut f = vec![0u8; 14 + 20 + 8];
+        f[12..14].copy_from_slice(&0x0800u16.to_be_bytes());
+        f[14] = 0x45;
+        f[16..18].copy_from_slice(&(20 + udp_len).to_be_bytes());
+        f[22] = 64;
+        f[23] = 17;
+        f[26..30].copy_from_slice(&[10, 0, 2, 15]);
+        f[30..34].copy_from_slice(&[10, 0, 2, 3]);
+        f[34..36].copy_from_slice(&40053u16.to_be_bytes());
+        f[36..38].copy_from_slice(&53u16.to_be_bytes());
+        f[38..40].copy_from_slice(&udp_len.to_be_bytes());
+        f.extend_from_slice(&body);
+        f
+    }
+
+    /// Resolver that answers only once the test releases it.
+    struct GatedResolver(tokio::sync::Semaphore);
+
+    #[async_trait::async_trait]
+    impl crate::l7_proxy::DnsResolver for GatedResolver {
+        async fn resolve_one(
+            &self,
+            host: &str,
+            port: u16,
+        ) -> Result<std::net::IpAddr, crate::egress::EgressError> {
+            Ok(self.resolve_all(host, port).await?[0])
+        }
+
+        async fn resolve_all(
+            &self,
+            _host: &str,
+            _port: u16,
+        ) -> Result<Vec<std::net::IpAddr>, crate::egress::EgressError> {
+            let _released = self.0.acquire().await.unwrap();
+            Ok(vec![std::net::IpAddr::from([93, 184, 216, 34])])
+        }
+    }
+
+    #[tokio::test]
+    async fn l4_filter_keeps_relaying_while_dns_answers_are_pending() {
+        use crate::destination::DestinationPolicy;
+        use crate::l7_proxy::CapturingEgressAuditSink;
+        use crate::proxy::dns::{DnsPinStore, GuestDnsEndpoint};
+        use crate::proxy::l4::{L4Policy, L4Rule, LiveL4Gate, Protocol};
+
+        let policy = L4Policy::new([L4Rule::single_port(
+            Protocol::Tcp,
+            "203.0.113.7/32".parse().unwrap(),
+            5432,
+        )]);
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        let resolver = Arc::new(GatedResolver(tokio::sync::Semaphore::new(0)));
+        let endpoint = GuestDnsEndpoint::new(
+            DestinationPolicy::new([("api.example.com", 443)]),
+            resolver.clone(),
+            Arc::new(DnsPinStore::for_vm("vm-a")),
+            audit.clone(),
+        );
+        let datapath = Arc::new(
+            L4Datapath::new(Arc::new(LiveL4Gate::new(policy)), audit)
+                .with_dns_endpoint(Arc::new(endpoint)),
+        );
+
+        let (mut guest, guest_side) = tokio::net::UnixStream::pair().unwrap();
+        let (gateway_side, mut gateway) = tokio::net::UnixStream::pair().unwrap();
+        let filter = tokio::spawn(l4_filter_frames(guest_side, gateway_side, datapath));
+
+        // The query is stuck upstream; the SYN behind it still goes out.
+        let allowed = tcp_syn_frame([203, 0, 113, 7], 5432);
+        write_stream_frame(&mut guest, &dns_query_frame("api.example.com."))
+            .await
+            .unwrap();
+        write_stream_frame(&mut guest, &allowed).await.unwrap();
+        let forwarded = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            read_stream_frame(&mut gateway),
+        )
+        .await
+        .expect("relay stalled behind a pending DNS answer")
+        .unwrap()
+        .unwrap();
+        assert_eq!(forwarded, allowed);
+
+        resolver.0.add_permits(1);
+        let answer = read_stream_frame(&mut guest).await.unwrap().unwrap();
+        assert_eq!(answer[23], 17, "DNS answer is a UDP datagram");
+        assert_eq!(&answer[26..30], &[10, 0, 2, 3]);
+        assert_eq!(&answer[34..36], &53u16.to_be_bytes());
+
+        drop(guest);
+        drop(gateway);
+        let _ = filter.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stream_frame_reader_rejects_oversized_lengths() {
+        let (mut a, mut b) = tokio::net::UnixStream::pair().unwrap();
+        tokio::io::AsyncWriteExt::write_all(&mut a, &u32::MAX.to_be_bytes())
+            .await
+            .unwrap();
+        let err = read_stream_frame(&mut b).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        drop(a);
+        assert!(read_stream_frame(&mut b).await.unwrap().is_none());
+    }
+
     // -----------------------------------------------------------------
     // FlowPolicy
     // -----------------------------------------------------------------
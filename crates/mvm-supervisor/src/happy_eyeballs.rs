@@ -0,0 +1,136 @@
+//! Happy-eyeballs connect over a vetted pin set (RFC 8305 §4–5,
+//! minus the DNS-query racing — the proxy resolves once, up front).
+//!
+//! [`crate::l7_proxy::L7EgressProxy`] resolves every address of a
+//! destination, runs each one through the inspector chain (so
+//! `SsrfGuard` classifies every IP, not just the first), and keeps
+//! only the survivors. [`connect`] then races TCP connections across
+//! that set: families are interleaved starting with IPv6, a new
+//! attempt starts every [`CONNECTION_ATTEMPT_DELAY`] or as soon as
+//! the previous one fails, and the first established connection
+//! wins. Losing attempts are aborted.
+//!
+//! Nothing outside the pin set is ever dialled — the DNS-rebinding
+//! defence is unchanged, it just covers every address now.
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+
+/// RFC 8305 §5 recommended default.
+pub const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Reorder `addrs` to alternate address families, IPv6 first, keeping
+/// the resolver's order within each family (RFC 8305 §4).
+pub fn interleave_families(addrs: &[IpAddr]) -> Vec<IpAddr> {
+    let (v6, v4): (Vec<IpAddr>, Vec<IpAddr>) = addrs.iter().partition(|ip| ip.is_ipv6());
+    let mut out = Vec::with_capacity(addrs.len());
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => break,
+            (a, b) => out.extend(a.into_iter().chain(b)),
+        }
+    }
+    out
+}
+
+/// Race connections to `port` across `pinned`, returning the first
+/// stream to establish and the IP it reached. Errors with the last
+/// attempt's failure when every address fails.
+pub async fn connect(
+    pinned: &[IpAddr],
+    port: u16,
+    attempt_delay: Duration,
+) -> std::io::Result<(TcpStream, IpAddr)> {
+    let mut pending = interleave_families(pinned).into_iter();
+    let mut attempts: JoinSet<(IpAddr, std::io::Result<TcpStream>)> = JoinSet::new();
+    let mut last_err = std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "empty pin set: nothing to dial",
+    );
+
+    let spawn_next = |attempts: &mut JoinSet<_>, pending: &mut std::vec::IntoIter<IpAddr>| {
+        pending.next().map(|ip| {
+            attempts.spawn(async move { (ip, TcpStream::connect(SocketAddr::new(ip, port)).await) })
+        })
+    };
+    spawn_next(&mut attempts, &mut pending);
+
+    while !attempts.is_empty() {
+        tokio::select! {
+            joined = attempts.join_next() => match joined {
+                Some(Ok((ip, Ok(stream)))) => return Ok((stream, ip)),
+                Some(Ok((ip, Err(e)))) => {
+                    tracing::debug!(%ip, port, error = %e, "happy-eyeballs attempt failed");
+                    last_err = e;
+                    // A failure frees the slot immediately (§5).
+                    spawn_next(&mut attempts, &mut pending);
+                }
+                Some(Err(join)) => last_err = std::io::Error::other(join),
+                None => break,
+            },
+            _ = tokio::time::sleep(attempt_delay), if pending.len() != 0 => {
+                spawn_next(&mut attempts, &mut pending);
+            }
+        }
+    }
+    Err(last_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn interleave_starts_with_v6_and_alternates() {
+        let a4 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let b4 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+        let c4 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3));
+        let a6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        assert_eq!(interleave_families(&[a4, b4, a6, c4]), vec![a6, a4, b4, c4]);
+        assert_eq!(interleave_families(&[a4, b4]), vec![a4, b4]);
+        assert!(interleave_families(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn dead_first_address_falls_through_to_live_one() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // 127.0.0.2 is loopback on Linux but nothing listens on it
+        // at this port, so the attempt is refused straight away.
+        let dead = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2));
+        let live = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let (_stream, ip) = connect(&[dead, live], port, Duration::from_secs(5))
+            .await
+            .expect("falls through to the live address");
+        assert_eq!(ip, live);
+    }
+
+    #[tokio::test]
+    async fn all_dead_returns_last_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let err = connect(
+            &[IpAddr::V4(Ipv4Addr::LOCALHOST)],
+            port,
+            CONNECTION_ATTEMPT_DELAY,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+    }
+
+    #[tokio::test]
+    async fn empty_pin_set_is_an_error() {
+        let err = connect(&[], 443, CONNECTION_ATTEMPT_DELAY)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
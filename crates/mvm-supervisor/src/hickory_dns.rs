@@ -25,10 +25,10 @@
 //! - **No allow-listing of resolved IPs.** That's the L4 policy's
 //!   job ([`crate::proxy::l4`]) — DNS resolution returns the IPs;
 //!   policy decides whether the flow to those IPs is allowed.
-//! - **No DNS interception** of guest queries. The guest's `/etc/resolv.conf`
-//!   gets pointed at the supervisor's DNS endpoint by the per-VM
-//!   netns wiring (Phase 3 Slice C / mvm-hostd lift); this module
-//!   provides the resolver the endpoint uses.
+//! - **No DNS interception** of guest queries. That's
+//!   [`crate::proxy::dns::GuestDnsEndpoint`], which the per-VM netns
+//!   wiring points the guest's `/etc/resolv.conf` at; this module can
+//!   be the upstream resolver the endpoint uses.
 
 use std::net::IpAddr;
 use std::sync::Arc;
@@ -39,7 +39,7 @@ use hickory_resolver::config::{ResolverConfig, ResolverOpts};
 use tokio::sync::OnceCell;
 
 use crate::egress::EgressError;
-use crate::l7_proxy::DnsResolver;
+use crate::l7_proxy::{DnsResolver, dedup_ips};
 
 /// `DnsResolver` impl backed by `hickory-resolver`. Built around a
 /// `OnceCell` so construction stays sync while resolution runs on
@@ -96,6 +96,23 @@ impl DnsResolver for HickoryDnsResolver {
             EgressError::UpstreamUnreachable(format!("hickory resolved {host} to zero IPs"))
         })
     }
+
+    /// Both A and AAAA answers (per the configured
+    /// `LookupIpStrategy`), so the proxy can vet and race the whole
+    /// set.
+    async fn resolve_all(&self, host: &str, _port: u16) -> Result<Vec<IpAddr>, EgressError> {
+        let resolver = self.ensure_resolver().await;
+        let lookup = resolver.lookup_ip(host).await.map_err(|e| {
+            EgressError::UpstreamUnreachable(format!("hickory lookup_ip({host}): {e}"))
+        })?;
+        let ips = dedup_ips(lookup);
+        if ips.is_empty() {
+            return Err(EgressError::UpstreamUnreachable(format!(
+                "hickory resolved {host} to zero IPs"
+            )));
+        }
+        Ok(ips)
+    }
 }
 
 /// `Arc`-wrap the resolver. The `L7EgressProxy::new` constructor
@@ -54,6 +54,12 @@ pub struct RequestCtx {
     /// resolves here for the actual connect() call to defend
     /// against DNS rebinding.
     pub resolved_ip: Option<IpAddr>,
+    /// The allow-list rule that admitted this destination, rendered
+    /// as `host:ports` (e.g. `*.githubusercontent.com:443`). Set by
+    /// `DestinationPolicy` so audit records can say *why* a request
+    /// was allowed; `None` until that inspector has run, or when it
+    /// denied.
+    pub matched_rule: Option<String>,
 }
 
 impl RequestCtx {
@@ -64,6 +70,7 @@ impl RequestCtx {
             path: path.into(),
             body: Vec::new(),
             resolved_ip: None,
+            matched_rule: None,
         }
     }
 
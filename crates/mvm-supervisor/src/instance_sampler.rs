@@ -1,7 +1,8 @@
 //! Per-VM metrics sampler — A3 of the filesystem-volumes plan.
 //!
-//! Pulls CPU, memory, disk, and network counters for one VM and
-//! pushes them into `mvm_core::observability::instance_metrics`.
+//! Pulls CPU, memory, disk, network, and nftables egress counters
+//! for one VM and pushes them into
+//! `mvm_core::observability::instance_metrics`.
 //! Designed pure-logic-first behind a `Sources` trait so unit tests
 //! can stub the readings without touching `/proc` or `/sys`; the
 //! production `OsSources` impl reads the live host filesystem.
@@ -10,13 +11,27 @@
 //! sampler on a 5-second cadence. Until that loop lands, callers
 //! invoke `Sampler::sample_once` directly — it's idempotent and
 //! safe to call from any thread.
-
-use std::path::PathBuf;
+//!
+//! [`meter_once`] additionally appends the pass's resource-time deltas
+//! to the VM's [`MeteringLog`] — the JSONL file the VM's `mvm-broker`
+//! tails for `host.cost.v1` (see [`crate::services::broker_config`]).
+//! Nothing in the tree calls it yet: no supervisor runs a per-VM
+//! sampling loop or spawns the broker, so production VMs write no
+//! metering samples and `host.cost.v1` has nothing to read. The tick
+//! loop above is where `meter_once` belongs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use mvm_core::metering::MeteringSample;
 use mvm_core::observability::instance_metrics::{
     InstanceLabels, InstanceMetricsRegistry, InstanceMetricsValues,
 };
+use mvm_core::policy::nft::policy_table_name;
 
 /// One VM's resource readings as taken at a single instant.
 /// Optional fields are `None` when the underlying source is
@@ -33,6 +48,9 @@ pub struct Sample {
     pub net_tx_bytes: Option<u64>,
     pub net_rx_packets: Option<u64>,
     pub net_tx_packets: Option<u64>,
+    pub egress_allowed_packets: Option<u64>,
+    pub egress_dropped_packets: Option<u64>,
+    pub egress_mandatory_deny_packets: Option<u64>,
 }
 
 /// Shape of a VM the sampler will read from. Held inside the
@@ -50,11 +68,32 @@ pub struct SampleTarget {
     /// Block-device IO stat path. Optional — set when the backend
     /// exposes per-VM disk counters via a sysfs node.
     pub disk_stat_path: Option<PathBuf>,
+    /// The VM's nftables table (`mvm_<vm>` or `mvm_policy_<vm>`).
+    /// Its named counters feed the `egress_*` metrics.
+    pub nft_table: Option<String>,
     /// Wall-clock unix-seconds when the supervisor first saw this
     /// VM running. Used to compute `uptime_secs`.
     pub started_at_unix_secs: u64,
 }
 
+impl SampleTarget {
+    /// Target for the VM `labels` names, reading egress counters from
+    /// its bridge-policy table ([`policy_table_name`]). An instance id
+    /// that can't name a table leaves `nft_table` unset. The per-host
+    /// sources start unset; callers fill in what the backend exposes.
+    pub fn new(labels: InstanceLabels, started_at_unix_secs: u64) -> Self {
+        let nft_table = policy_table_name(&labels.instance_id).ok();
+        Self {
+            labels,
+            vmm_pid: None,
+            tap_iface: None,
+            disk_stat_path: None,
+            nft_table,
+            started_at_unix_secs,
+        }
+    }
+}
+
 /// Trait for the per-source readings. The production impl
 /// (`OsSources`) hits the live host fs; the test impl returns
 /// canned values. Each method is allowed to return `None` —
@@ -63,6 +102,7 @@ pub trait Sources {
     fn read_proc_stat(&self, pid: u32) -> Sample;
     fn read_tap_stats(&self, iface: &str) -> Sample;
     fn read_disk_stats(&self, path: &std::path::Path) -> Sample;
+    fn read_nft_counters(&self, table: &str) -> Sample;
 }
 
 /// Production `Sources` impl.
@@ -80,6 +120,10 @@ impl Sources for OsSources {
     fn read_disk_stats(&self, path: &std::path::Path) -> Sample {
         read_os_disk_stats(path)
     }
+
+    fn read_nft_counters(&self, table: &str) -> Sample {
+        read_os_nft_counters(table)
+    }
 }
 
 /// Sample one VM and push the resulting values into the registry.
@@ -107,6 +151,9 @@ pub fn sample_once<S: Sources>(
     if let Some(path) = target.disk_stat_path.as_deref() {
         merge(&mut combined, sources.read_disk_stats(path));
     }
+    if let Some(table) = target.nft_table.as_deref() {
+        merge(&mut combined, sources.read_nft_counters(table));
+    }
 
     // Preserve previous values where the latest reading returned
     // `None` (source temporarily unavailable). Building on the
@@ -129,12 +176,112 @@ pub fn sample_once<S: Sources>(
         net_tx_bytes: combined.net_tx_bytes.unwrap_or(prev.net_tx_bytes),
         net_rx_packets: combined.net_rx_packets.unwrap_or(prev.net_rx_packets),
         net_tx_packets: combined.net_tx_packets.unwrap_or(prev.net_tx_packets),
+        egress_allowed_packets: combined
+            .egress_allowed_packets
+            .unwrap_or(prev.egress_allowed_packets),
+        egress_dropped_packets: combined
+            .egress_dropped_packets
+            .unwrap_or(prev.egress_dropped_packets),
+        egress_mandatory_deny_packets: combined
+            .egress_mandatory_deny_packets
+            .unwrap_or(prev.egress_mandatory_deny_packets),
         uptime_secs,
         last_sample_unix_secs: now,
     };
     registry.update(&target.labels.instance_id, values)
 }
 
+/// Append-only JSONL log of [`MeteringSample`]s. The registry holds
+/// cumulative counters; samples carry deltas, so the log remembers
+/// each VM's previous reading.
+pub struct MeteringLog {
+    path: PathBuf,
+    marks: Mutex<HashMap<String, MeterMark>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MeterMark {
+    cpu_us: u64,
+    at: SystemTime,
+}
+
+impl MeteringLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            marks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The file samples are appended to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append the usage since this VM's previous sample. The first
+    /// sample charges the VMM's CPU time since start and no memory
+    /// time; a CPU counter that went backwards (VMM restarted) is
+    /// charged from zero. Storage has no per-VM source yet and is
+    /// reported as zero.
+    pub fn append(
+        &self,
+        labels: &InstanceLabels,
+        values: &InstanceMetricsValues,
+        now: SystemTime,
+    ) -> std::io::Result<MeteringSample> {
+        let cpu_us = values.cpu_user_us.saturating_add(values.cpu_system_us);
+        let prev = self
+            .marks
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(labels.instance_id.clone(), MeterMark { cpu_us, at: now });
+        let (cpu_delta_us, elapsed_secs) = match prev {
+            Some(mark) if cpu_us >= mark.cpu_us => (
+                cpu_us - mark.cpu_us,
+                now.duration_since(mark.at).map_or(0, |d| d.as_secs()),
+            ),
+            _ => (cpu_us, 0),
+        };
+        let sample = MeteringSample {
+            instance_id: labels.instance_id.clone(),
+            tenant_id: labels.tenant.clone(),
+            tags: BTreeMap::new(),
+            ts: now,
+            cpu_ns: cpu_delta_us.saturating_mul(1_000),
+            mem_byte_seconds: values.mem_resident_bytes.saturating_mul(elapsed_secs),
+            storage_byte_seconds_cold: 0,
+            storage_byte_seconds_hot: 0,
+        };
+        let mut line = serde_json::to_vec(&sample).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+        // One write per line so the broker's tail never sees two
+        // samples interleaved.
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)?;
+        Ok(sample)
+    }
+}
+
+/// [`sample_once`], then append the VM's usage delta to `log`.
+/// `Ok(None)` when the registry doesn't have the VM.
+pub fn meter_once<S: Sources>(
+    registry: &InstanceMetricsRegistry,
+    sources: &S,
+    target: &SampleTarget,
+    log: &MeteringLog,
+) -> std::io::Result<Option<MeteringSample>> {
+    if !sample_once(registry, sources, target) {
+        return Ok(None);
+    }
+    let Some((labels, values)) = registry.get(&target.labels.instance_id) else {
+        return Ok(None);
+    };
+    log.append(&labels, &values, SystemTime::now()).map(Some)
+}
+
 fn merge(into: &mut Sample, from: Sample) {
     if from.cpu_user_us.is_some() {
         into.cpu_user_us = from.cpu_user_us;
@@ -163,6 +310,15 @@ fn merge(into: &mut Sample, from: Sample) {
     if from.net_tx_packets.is_some() {
         into.net_tx_packets = from.net_tx_packets;
     }
+    if from.egress_allowed_packets.is_some() {
+        into.egress_allowed_packets = from.egress_allowed_packets;
+    }
+    if from.egress_dropped_packets.is_some() {
+        into.egress_dropped_packets = from.egress_dropped_packets;
+    }
+    if from.egress_mandatory_deny_packets.is_some() {
+        into.egress_mandatory_deny_packets = from.egress_mandatory_deny_packets;
+    }
 }
 
 // ============================================================================
@@ -269,6 +425,33 @@ fn read_os_disk_stats(_path: &std::path::Path) -> Sample {
     Sample::default()
 }
 
+#[cfg(target_os = "linux")]
+fn read_os_nft_counters(table: &str) -> Sample {
+    use mvm_core::policy::nft;
+
+    let Ok(args) = nft::list_counters_args(table) else {
+        return Sample::default();
+    };
+    let output = match std::process::Command::new("nft").args(&args).output() {
+        Ok(o) if o.status.success() => o,
+        _ => return Sample::default(),
+    };
+    match nft::parse_counters(table, &String::from_utf8_lossy(&output.stdout)) {
+        Ok(c) => Sample {
+            egress_allowed_packets: Some(c.allowed.packets),
+            egress_dropped_packets: Some(c.dropped.packets),
+            egress_mandatory_deny_packets: Some(c.mandatory_deny.packets),
+            ..Sample::default()
+        },
+        Err(_) => Sample::default(),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_os_nft_counters(_table: &str) -> Sample {
+    Sample::default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,6 +464,7 @@ mod tests {
         proc: Mutex<Sample>,
         tap: Mutex<Sample>,
         disk: Mutex<Sample>,
+        nft: Mutex<Sample>,
     }
 
     impl StubSources {
@@ -289,6 +473,7 @@ mod tests {
                 proc: Mutex::new(Sample::default()),
                 tap: Mutex::new(Sample::default()),
                 disk: Mutex::new(Sample::default()),
+                nft: Mutex::new(Sample::default()),
             }
         }
     }
@@ -303,22 +488,33 @@ mod tests {
         fn read_disk_stats(&self, _path: &Path) -> Sample {
             self.disk.lock().unwrap().clone()
         }
+        fn read_nft_counters(&self, _table: &str) -> Sample {
+            self.nft.lock().unwrap().clone()
+        }
     }
 
     fn target(id: &str) -> SampleTarget {
+        let labels = InstanceLabels {
+            instance_id: id.to_string(),
+            tenant: "acme".to_string(),
+            template: "python-3.12".to_string(),
+        };
         SampleTarget {
-            labels: InstanceLabels {
-                instance_id: id.to_string(),
-                tenant: "acme".to_string(),
-                template: "python-3.12".to_string(),
-            },
             vmm_pid: Some(123),
             tap_iface: Some("tap-foo".to_string()),
             disk_stat_path: Some(PathBuf::from("/sys/block/vda/stat")),
-            started_at_unix_secs: 1_000,
+            ..SampleTarget::new(labels, 1_000)
         }
     }
 
+    #[test]
+    fn new_target_reads_the_vms_policy_table() {
+        let t = target("i-1");
+        assert_eq!(t.nft_table.as_deref(), Some("mvm_policy_i-1"));
+        assert_eq!(t.nft_table, policy_table_name("i-1").ok());
+        assert_eq!(target("vm1; flush ruleset").nft_table, None);
+    }
+
     #[test]
     fn sample_once_returns_false_for_unknown_instance() {
         let reg = InstanceMetricsRegistry::new();
@@ -364,6 +560,30 @@ mod tests {
         assert!(v.last_sample_unix_secs > 0);
     }
 
+    #[test]
+    fn sample_once_folds_in_firewall_counters() {
+        let reg = InstanceMetricsRegistry::new();
+        reg.register(target("i-1").labels);
+
+        let sources = StubSources::new();
+        *sources.nft.lock().unwrap() = Sample {
+            egress_allowed_packets: Some(12),
+            egress_dropped_packets: Some(3),
+            egress_mandatory_deny_packets: Some(1),
+            ..Sample::default()
+        };
+        assert!(sample_once(&reg, &sources, &target("i-1")));
+        let (_, v) = reg.get("i-1").unwrap();
+        assert_eq!(v.egress_allowed_packets, 12);
+        assert_eq!(v.egress_dropped_packets, 3);
+        assert_eq!(v.egress_mandatory_deny_packets, 1);
+
+        // Table gone (VM torn down mid-tick): counters hold.
+        *sources.nft.lock().unwrap() = Sample::default();
+        sample_once(&reg, &sources, &target("i-1"));
+        assert_eq!(reg.get("i-1").unwrap().1.egress_dropped_packets, 3);
+    }
+
     #[test]
     fn sample_once_preserves_previous_values_on_missing_source() {
         let reg = InstanceMetricsRegistry::new();
@@ -468,6 +688,67 @@ mod tests {
         assert_eq!(acc.disk_read_bytes, Some(4096));
     }
 
+    #[test]
+    fn metering_log_appends_deltas_per_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = MeteringLog::new(dir.path().join("metering.jsonl"));
+        let labels = target("i-1").labels;
+        let t0 = UNIX_EPOCH + std::time::Duration::from_secs(2_000);
+        let values = |cpu_user_us, mem_resident_bytes| InstanceMetricsValues {
+            cpu_user_us,
+            cpu_system_us: 50,
+            mem_resident_bytes,
+            ..InstanceMetricsValues::default()
+        };
+
+        let first = log.append(&labels, &values(150, 4096), t0).unwrap();
+        assert_eq!(
+            first.cpu_ns, 200_000,
+            "first sample charges CPU since start"
+        );
+        assert_eq!(first.mem_byte_seconds, 0);
+        let second = log
+            .append(
+                &labels,
+                &values(450, 1024),
+                t0 + std::time::Duration::from_secs(5),
+            )
+            .unwrap();
+        assert_eq!(second.cpu_ns, 300_000);
+        assert_eq!(second.mem_byte_seconds, 5 * 1024);
+        // VMM restarted: the counter went backwards.
+        let third = log
+            .append(
+                &labels,
+                &values(10, 1024),
+                t0 + std::time::Duration::from_secs(10),
+            )
+            .unwrap();
+        assert_eq!(third.cpu_ns, 60_000);
+        assert_eq!(third.instance_id, "i-1");
+        assert_eq!(third.tenant_id, "acme");
+
+        let lines: Vec<MeteringSample> = std::fs::read_to_string(log.path())
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, vec![first, second, third]);
+    }
+
+    #[test]
+    fn meter_once_skips_unknown_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = MeteringLog::new(dir.path().join("metering.jsonl"));
+        let reg = InstanceMetricsRegistry::new();
+        assert!(
+            meter_once(&reg, &StubSources::new(), &target("ghost"), &log)
+                .unwrap()
+                .is_none()
+        );
+        assert!(!log.path().exists());
+    }
+
     #[test]
     #[cfg(target_os = "linux")]
     fn os_proc_stat_returns_default_for_missing_pid() {
@@ -36,12 +36,17 @@ pub mod audit;
 pub mod audit_dedup;
 pub mod audit_file;
 pub mod audit_recorder;
+// Plan 60 Phase 4 follow-on — spool-backed replication of the chain
+// to `unix://` / `tcp+tls://` / `https://` audit collectors.
+pub mod audit_stream;
 pub mod backend;
 pub mod balloon;
 pub mod balloon_runtime;
 pub mod circuit_breaker;
 pub mod destination;
 pub mod egress;
+pub mod egress_budget;
+pub mod egress_pool;
 pub mod event_bus;
 pub mod firewall;
 // Plan 102 W6.A commit 4 — per-VM gateway flow-event subscriber sink.
@@ -55,6 +60,7 @@ pub mod gateway_audit;
 // claim-8 chain, broadcasts NDJSON to gateway_audit subscribers,
 // and exposes a `FlowPolicy` hook for Plan 74 / SNI / L7 inspectors.
 pub mod gateway_bridge;
+pub mod happy_eyeballs;
 #[cfg(feature = "custom-dns")]
 pub mod hickory_dns;
 pub mod injection_guard;
@@ -83,6 +89,10 @@ pub mod services;
 pub mod ssrf_guard;
 pub mod state;
 pub mod supervisor;
+pub mod threat_screen;
+// Wave 2.6.5 (ADR-006) — per-VM name-constrained CA for the
+// TLS-intercepting mode of `L7EgressProxy`.
+pub mod tls_intercept;
 pub mod tool_gate;
 pub mod tools;
 
@@ -96,6 +106,10 @@ pub use audit_recorder::{
     EventCategory, Recorder, RecorderError, UNBOUND_IMAGE_NAME, UNBOUND_IMAGE_SHA256,
     UNBOUND_PLAN_ID,
 };
+pub use audit_stream::{
+    AuditStreamDestination, AuditStreamError, DEFAULT_MAX_SPOOL_BYTES, StreamAuditSigner,
+    StreamConfig,
+};
 pub use backend::{
     BackendError, BackendLaunchSpec, BackendLauncher, FirecrackerRunConfigLauncher,
     NoopBackendLauncher,
@@ -113,6 +127,10 @@ pub use circuit_breaker::{
 };
 pub use destination::DestinationPolicy;
 pub use egress::{EgressDecision, EgressError, EgressProxy, NoopEgressProxy};
+pub use egress_budget::{ByteCapExceeded, EgressBudget};
+pub use egress_pool::{
+    DEFAULT_POOL_IDLE_TIMEOUT, DEFAULT_POOL_MAX_IDLE_PER_DESTINATION, PoolKey, UpstreamPool,
+};
 pub use event_bus::{DEFAULT_CAPACITY as EVENT_BUS_DEFAULT_CAPACITY, EventBus, LifecycleEvent};
 #[cfg(any(target_os = "linux", test))]
 pub use firewall::linux_nft::{CommandNftApplier, LinuxNftFirewall, NftApplier, NftError};
@@ -140,7 +158,7 @@ pub use policy_tool_gate::{
 };
 pub use proxy::l4::{
     L4Decision, L4Error, L4Gate, L4Policy, L4Rule, L4SpecError, LiveL4Gate, NoopL4Gate,
-    Protocol as L4Protocol,
+    PassThroughL4Gate, Protocol as L4Protocol,
 };
 pub use reaper::{
     DEFAULT_INTERVAL as REAPER_DEFAULT_INTERVAL, DEFAULT_JITTER as REAPER_DEFAULT_JITTER,
@@ -152,8 +170,13 @@ pub use state::{PlanState, PlanStateMachine, StateTransitionError};
 pub use supervisor::{
     AuditPolicyValidationError, EgressPolicyValidationError, KNOWN_AUDIT_STREAM_SCHEMES,
     KNOWN_INSPECTOR_NAMES, Supervisor, SupervisorError, build_inspector_chain,
-    build_inspector_chain_with_pii, validate_audit_policy_stream_destinations,
+    build_inspector_chain_with_pii, build_l7_egress, validate_audit_policy_stream_destinations,
     validate_egress_policy_inspector_names,
 };
+pub use threat_screen::threat_gate_from_policy;
+pub use tls_intercept::{
+    DEFAULT_INTERCEPT_CA_TTL, GUEST_INTERCEPT_CA_PATH, INTERCEPT_CA_DRIVE_FILE, InterceptCa,
+    InterceptError, TlsInterceptor,
+};
 pub use tool_gate::{NoopToolGate, ToolDecision, ToolError, ToolGate};
 pub use tools::{HostMediatedTool, ToolInvokeError, ToolRegistry};
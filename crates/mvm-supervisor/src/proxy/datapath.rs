@@ -0,0 +1,1281 @@
+//! Userspace L4 enforcement datapath — the consumer of [`L4Gate`]
+//! decisions on the guest's virtio-net stream.
+//!
+//! Sits in the gateway bridge ([`crate::gateway_bridge`]) between the
+//! guest's virtio-net fd and passt / gvproxy. Every Ethernet frame
+//! the guest sends is parsed down to its `(proto, dst_ip, dst_port)`
+//! tuple; the first frame of each TCP/UDP flow is evaluated through
+//! the VM's [`L4Gate`] (typically a [`super::l4::LiveL4Gate`], or a
+//! [`super::l4::PinnedL4Gate`] when the guest resolves through the
+//! DNS endpoint), the decision is cached per 5-tuple, and the frame
+//! is forwarded or dropped. Because enforcement happens on raw
+//! frames, non-HTTP protocols (Postgres, gRPC, SMTP, …) get the same
+//! policy as HTTP without the guest opting into a proxy.
+//!
+//! Guest DNS: a datapath built by [`L4Datapath::from_bundle`] carries
+//! the VM's [`GuestDnsEndpoint`] and answers every UDP query to port
+//! 53 in place, whichever nameserver the guest's resolver names. The
+//! query never reaches passt / gvproxy, so the endpoint is the only
+//! resolver the guest can use, and every answer it gives lands in the
+//! [`DnsPinStore`] the datapath's [`super::l4::PinnedL4Gate`] checks.
+//! Answering may wait on an upstream resolver, so the datapath hands
+//! the bridge a [`PendingDnsAnswer`] to run off the relay loop; at
+//! most [`MAX_DNS_IN_FLIGHT`] are outstanding per VM, and queries
+//! past that are dropped for the guest's resolver to retry.
+//!
+//! Flow *termination* stays with the gateway: passt / gvproxy already
+//! turn guest TCP/UDP into host sockets, so the datapath only decides
+//! which flows reach them. A denied TCP SYN is answered with a
+//! synthesized RST so the guest's `connect()` fails immediately with
+//! `ECONNREFUSED` instead of timing out; denied UDP is dropped.
+//!
+//! Every new-flow decision is recorded through the existing
+//! [`EgressAuditSink`] with `deciding_inspector = "l4_policy"`
+//! (`host` = destination IP). Subsequent frames of a cached flow are
+//! not re-audited.
+//!
+//! Fail-closed rules for traffic the policy can't express:
+//! - ARP, ICMPv6 router / neighbour discovery, and DHCPv4 / DHCPv6
+//!   client traffic addressed to the link (255.255.255.255,
+//!   ff02::1:2, or the gateway's DHCP server once it has answered)
+//!   pass — the link doesn't come up without them and they never
+//!   leave the gateway. DHCP-shaped traffic to any other address is
+//!   an ordinary UDP flow and goes through the gate.
+//! - Other ICMP, IPv4 non-first fragments, IPv6 extension headers,
+//!   VLAN-tagged frames and unknown ethertypes are dropped.
+//!
+//! Frames from the gateway to the guest pass unless they belong to a
+//! flow the policy denied; inbound port-forwards are not egress and
+//! aren't evaluated here.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use std::ops::Range;
+
+use chrono::Utc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::dns::{DnsPinStore, GuestDnsEndpoint};
+use super::l4::{
+    L4Decision, L4Gate, L4SpecError, LiveL4Gate, PassThroughL4Gate, PinnedL4Gate, Protocol,
+};
+use crate::destination::DestinationPolicy;
+use crate::l7_proxy::{AuditFields, DnsResolver, EgressAuditSink, EgressOutcome};
+
+/// Idle timeout for established TCP flows.
+pub const TCP_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Idle timeout for UDP flows and for TCP flows that have seen a
+/// FIN or RST.
+pub const UDP_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Upper bound on tracked flows. Past this, decisions are still made
+/// (and audited) per frame, just not cached.
+pub const MAX_TRACKED_FLOWS: usize = 65_536;
+/// Upper bound on remembered DHCP server addresses. The gateway
+/// answers from one address per family.
+const MAX_DHCP_SERVERS: usize = 4;
+/// All_DHCP_Relay_Agents_and_Servers (RFC 8415 §7.1).
+const DHCPV6_SERVERS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 1, 2);
+
+/// Upper bound on guest DNS queries being answered at once.
+pub const MAX_DNS_IN_FLIGHT: usize = 64;
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_ARP: u16 = 0x0806;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const IPPROTO_ICMP: u8 = 1;
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+const IPPROTO_ICMPV6: u8 = 58;
+const TCP_FIN: u8 = 0x01;
+const TCP_SYN: u8 = 0x02;
+const TCP_RST: u8 = 0x04;
+const TCP_ACK: u8 = 0x10;
+
+/// What the bridge should do with one guest → gateway frame.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FrameVerdict {
+    /// Relay the frame to the gateway unchanged.
+    Forward,
+    /// Discard the frame.
+    Drop,
+    /// Discard the frame and write this frame back to the guest (a
+    /// TCP RST for a denied SYN).
+    Reject(Vec<u8>),
+    /// Discard the frame and, once [`PendingDnsAnswer::resolve`]
+    /// finishes, write its frame back to the guest. The bridge runs
+    /// it on its own task so a slow upstream doesn't stall the relay.
+    Answer(PendingDnsAnswer),
+}
+
+/// A guest DNS query the endpoint has yet to answer. Holds one of the
+/// datapath's [`MAX_DNS_IN_FLIGHT`] slots until dropped.
+pub struct PendingDnsAnswer {
+    endpoint: Arc<GuestDnsEndpoint>,
+    query: Vec<u8>,
+    packet: Packet,
+    _slot: OwnedSemaphorePermit,
+}
+
+impl PendingDnsAnswer {
+    /// Answer the query: the reply frame for the guest, or `None`
+    /// when the endpoint drops it.
+    pub async fn resolve(self) -> Option<Vec<u8>> {
+        let answer = self
+            .endpoint
+            .handle_packet(&self.query[self.packet.payload.clone()])
+            .await?;
+        Some(udp_reply_for(&self.query, &self.packet, &answer))
+    }
+}
+
+impl std::fmt::Debug for PendingDnsAnswer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingDnsAnswer")
+            .field("src", &self.packet.src)
+            .field("dst", &self.packet.dst)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Two pending answers are equal when they answer the same query
+/// frame.
+impl PartialEq for PendingDnsAnswer {
+    fn eq(&self, other: &Self) -> bool {
+        self.query == other.query
+    }
+}
+
+impl Eq for PendingDnsAnswer {}
+
+/// Direction-normalised flow identity: `guest` is always the VM's
+/// end, `remote` the far end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlowKey {
+    pub proto: Protocol,
+    pub guest: SocketAddr,
+    pub remote: SocketAddr,
+}
+
+#[derive(Debug)]
+struct FlowEntry {
+    allowed: bool,
+    closing: bool,
+    last_seen: Instant,
+}
+
+impl FlowEntry {
+    fn expired(&self, key: &FlowKey, now: Instant) -> bool {
+        let timeout = if key.proto == Protocol::Tcp && !self.closing {
+            TCP_IDLE_TIMEOUT
+        } else {
+            UDP_IDLE_TIMEOUT
+        };
+        now.duration_since(self.last_seen) > timeout
+    }
+}
+
+#[derive(Debug)]
+struct FlowTable {
+    entries: HashMap<FlowKey, FlowEntry>,
+    /// Addresses the gateway's DHCP server answered from; unicast
+    /// renewals to these are link control.
+    dhcp_servers: Vec<IpAddr>,
+    last_sweep: Instant,
+}
+
+impl FlowTable {
+    fn sweep(&mut self, now: Instant) {
+        if now.duration_since(self.last_sweep) < SWEEP_INTERVAL {
+            return;
+        }
+        self.last_sweep = now;
+        self.entries.retain(|key, entry| !entry.expired(key, now));
+    }
+}
+
+/// Per-VM L4 datapath. Cheap to share: the bridge's egress and
+/// ingress loops each hold an `Arc`.
+pub struct L4Datapath {
+    gate: Arc<dyn L4Gate>,
+    audit: Arc<dyn EgressAuditSink>,
+    /// Answers the guest's UDP/53 queries. `None` treats DNS as an
+    /// ordinary UDP flow.
+    dns: Option<Arc<GuestDnsEndpoint>>,
+    dns_slots: Arc<Semaphore>,
+    flows: Mutex<FlowTable>,
+}
+
+impl L4Datapath {
+    pub fn new(gate: Arc<dyn L4Gate>, audit: Arc<dyn EgressAuditSink>) -> Self {
+        Self {
+            gate,
+            audit,
+            dns: None,
+            dns_slots: Arc::new(Semaphore::new(MAX_DNS_IN_FLIGHT)),
+            flows: Mutex::new(FlowTable {
+                entries: HashMap::new(),
+                dhcp_servers: Vec::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// Build from a bundle's `[network]` section alone. `Ok(None)`
+    /// when it has no `[[network.l4]]` rows. The bridge binaries use
+    /// [`Self::from_bundle`], which also engages for a restricted
+    /// `[egress]` allow-list.
+    pub fn from_network_policy(
+        network: &mvm_policy::NetworkPolicy,
+        audit: Arc<dyn EgressAuditSink>,
+    ) -> Result<Option<Self>, L4SpecError> {
+        if network.l4.is_empty() {
+            return Ok(None);
+        }
+        let gate = LiveL4Gate::from_specs(&network.l4)?;
+        Ok(Some(Self::new(Arc::new(gate), audit)))
+    }
+
+    /// The per-VM datapath the bridge binaries install: the bundle's
+    /// `[[network.l4]]` rules, widened to the addresses the guest
+    /// resolved for `[egress]` allow-listed names, with the VM's
+    /// [`GuestDnsEndpoint`] answering its DNS. The endpoint writes
+    /// `pins` and the gate reads it.
+    ///
+    /// Engaged when the bundle has `[[network.l4]]` rows or a
+    /// restricted `[egress]` allow-list (non-empty, `mode` not
+    /// `open`). Without L4 rows the gate is a [`PassThroughL4Gate`]:
+    /// flows are not filtered, but guest DNS is still confined to the
+    /// allow-list. `Ok(None)` otherwise, so bundles with neither keep
+    /// the unfiltered gateway path.
+    pub fn from_bundle(
+        bundle: &mvm_policy::PolicyBundle,
+        pins: Arc<DnsPinStore>,
+        resolver: Arc<dyn DnsResolver>,
+        audit: Arc<dyn EgressAuditSink>,
+    ) -> Result<Option<Self>, L4SpecError> {
+        let egress = &bundle.egress;
+        let restricts_names = egress.mode.as_deref() != Some("open")
+            && !(egress.allow_list.is_empty() && egress.allow_port_ranges.is_empty());
+        if bundle.network.l4.is_empty() && !restricts_names {
+            return Ok(None);
+        }
+        let gate: Arc<dyn L4Gate> = if bundle.network.l4.is_empty() {
+            Arc::new(PassThroughL4Gate)
+        } else {
+            let rules = LiveL4Gate::from_specs(&bundle.network.l4)?;
+            let destinations = DestinationPolicy::from_egress_policy(egress).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "invalid egress allow-list; L4 gate ignores DNS pins");
+                DestinationPolicy::deny_all()
+            });
+            Arc::new(PinnedL4Gate::new(
+                Box::new(rules),
+                destinations,
+                Arc::clone(&pins),
+            ))
+        };
+        let endpoint = GuestDnsEndpoint::from_egress_policy(egress, resolver, pins, audit.clone());
+        Ok(Some(
+            Self::new(gate, audit).with_dns_endpoint(Arc::new(endpoint)),
+        ))
+    }
+
+    /// Answer the guest's UDP/53 queries with `endpoint` instead of
+    /// evaluating them as flows.
+    pub fn with_dns_endpoint(mut self, endpoint: Arc<GuestDnsEndpoint>) -> Self {
+        self.dns = Some(endpoint);
+        self
+    }
+
+    /// The pin store the DNS endpoint writes.
+    pub fn dns_pins(&self) -> Option<&Arc<DnsPinStore>> {
+        self.dns.as_ref().map(|endpoint| endpoint.pins())
+    }
+
+    /// Number of flows currently tracked (allowed or denied).
+    pub fn flow_count(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Decide a guest → gateway frame. Never waits on DNS: queries
+    /// come back as [`FrameVerdict::Answer`] for the caller to run.
+    pub async fn egress(&self, frame: &[u8]) -> FrameVerdict {
+        let packet = match parse_frame(frame) {
+            Parsed::LinkControl => return FrameVerdict::Forward,
+            Parsed::Unsupported(why) => {
+                tracing::debug!(why, "l4 datapath: dropping unsupported egress frame");
+                return FrameVerdict::Drop;
+            }
+            Parsed::Flow(packet) => packet,
+        };
+        if packet.is_dhcp_request() && self.lock().dhcp_servers.contains(&packet.dst.ip()) {
+            return FrameVerdict::Forward;
+        }
+        if let Some(dns) = &self.dns
+            && packet.is_dns_query()
+        {
+            let Ok(slot) = Arc::clone(&self.dns_slots).try_acquire_owned() else {
+                tracing::debug!("l4 datapath: too many guest DNS queries in flight; dropping");
+                return FrameVerdict::Drop;
+            };
+            return FrameVerdict::Answer(PendingDnsAnswer {
+                endpoint: Arc::clone(dns),
+                query: frame.to_vec(),
+                packet,
+                _slot: slot,
+            });
+        }
+        let key = FlowKey {
+            proto: packet.proto,
+            guest: packet.src,
+            remote: packet.dst,
+        };
+        let now = Instant::now();
+
+        if let Some(allowed) = self.touch(&key, packet.tcp.as_ref(), now) {
+            return if allowed {
+                FrameVerdict::Forward
+            } else {
+                self.refuse(frame, &packet)
+            };
+        }
+
+        let started = Instant::now();
+        let decision = match self
+            .gate
+            .evaluate(packet.proto, packet.dst.ip(), packet.dst.port())
+            .await
+        {
+            Ok(decision) => decision,
+            Err(e) => L4Decision::Deny {
+                reason: e.to_string(),
+            },
+        };
+        let allowed = decision == L4Decision::Allow;
+        self.record(&packet, decision, started).await;
+
+        {
+            let mut table = self.lock();
+            table.sweep(now);
+            if table.entries.len() < MAX_TRACKED_FLOWS {
+                table.entries.insert(
+                    key,
+                    FlowEntry {
+                        allowed,
+                        closing: packet.tcp.as_ref().is_some_and(TcpMeta::closes),
+                        last_seen: now,
+                    },
+                );
+            }
+        }
+        if allowed {
+            FrameVerdict::Forward
+        } else {
+            self.refuse(frame, &packet)
+        }
+    }
+
+    /// Decide a gateway → guest frame: `false` only for frames of a
+    /// flow the policy denied. A DHCP reply outside any tracked flow
+    /// came from the gateway's own server; its source address is
+    /// remembered so the guest's unicast renewals stay link control.
+    pub fn ingress(&self, frame: &[u8]) -> bool {
+        let Parsed::Flow(packet) = parse_frame(frame) else {
+            return true;
+        };
+        let key = FlowKey {
+            proto: packet.proto,
+            guest: packet.dst,
+            remote: packet.src,
+        };
+        match self.touch(&key, packet.tcp.as_ref(), Instant::now()) {
+            Some(allowed) => allowed,
+            None => {
+                if packet.is_dhcp_reply() {
+                    self.learn_dhcp_server(packet.src.ip());
+                }
+                true
+            }
+        }
+    }
+
+    fn learn_dhcp_server(&self, server: IpAddr) {
+        let mut table = self.lock();
+        if !table.dhcp_servers.contains(&server) && table.dhcp_servers.len() < MAX_DHCP_SERVERS {
+            table.dhcp_servers.push(server);
+        }
+    }
+
+    /// Refresh a cached flow; returns its decision, or `None` when
+    /// the flow isn't tracked (or has expired).
+    fn touch(&self, key: &FlowKey, tcp: Option<&TcpMeta>, now: Instant) -> Option<bool> {
+        let mut table = self.lock();
+        let entry = table.entries.get_mut(key)?;
+        if entry.expired(key, now) {
+            table.entries.remove(key);
+            return None;
+        }
+        entry.last_seen = now;
+        if tcp.is_some_and(TcpMeta::closes) {
+            entry.closing = true;
+        }
+        Some(entry.allowed)
+    }
+
+    fn refuse(&self, frame: &[u8], packet: &Packet) -> FrameVerdict {
+        match &packet.tcp {
+            Some(tcp) if tcp.flags & TCP_SYN != 0 && tcp.flags & TCP_ACK == 0 => {
+                FrameVerdict::Reject(tcp_rst_for(frame, packet, tcp))
+            }
+            _ => FrameVerdict::Drop,
+        }
+    }
+
+    async fn record(&self, packet: &Packet, decision: L4Decision, started: Instant) {
+        let (outcome, reason) = match decision {
+            L4Decision::Allow => (EgressOutcome::Allow, None),
+            L4Decision::Deny { reason } => (EgressOutcome::Deny, Some(reason)),
+        };
+        if outcome == EgressOutcome::Deny {
+            tracing::info!(
+                proto = ?packet.proto,
+                dst = %packet.dst,
+                "l4 datapath: flow denied"
+            );
+        }
+        let fields = AuditFields {
+            outcome,
+            deciding_inspector: "l4_policy",
+            host: packet.dst.ip().to_string(),
+            port: packet.dst.port(),
+            path: String::new(),
+            transforms: Vec::new(),
+            reason,
+            resolved_ip: Some(packet.dst.ip()),
+            pinned_ips: Vec::new(),
+            matched_rule: None,
+            duration_ms: u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX),
+            timestamp: Utc::now(),
+            tls_intercepted: false,
+        };
+        if let Err(e) = self.audit.record(&fields).await {
+            tracing::warn!(error = %e, dst = %packet.dst, "l4 datapath: audit record failed");
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FlowTable> {
+        self.flows.lock().expect("L4Datapath flow table poisoned")
+    }
+}
+
+// ──────────────────────────────────────────────────────────────────────
+// Frame parsing
+// ──────────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+enum Parsed {
+    /// ARP / DHCP / NDP — forwarded without evaluation.
+    LinkControl,
+    Unsupported(&'static str),
+    Flow(Packet),
+}
+
+#[derive(Debug)]
+struct Packet {
+    proto: Protocol,
+    src: SocketAddr,
+    dst: SocketAddr,
+    tcp: Option<TcpMeta>,
+    /// Offset of the IP header in the frame (always 14 today — no
+    /// VLAN support).
+    l3_offset: usize,
+    /// Where the TCP / UDP payload sits in the frame.
+    payload: Range<usize>,
+}
+
+#[derive(Debug)]
+struct TcpMeta {
+    seq: u32,
+    flags: u8,
+    /// Payload bytes after the TCP header (SYN / FIN each count one
+    /// more sequence number on top of this).
+    payload_len: usize,
+}
+
+impl Packet {
+    /// DHCPv4 / DHCPv6 client → server ports.
+    fn is_dhcp_request(&self) -> bool {
+        self.proto == Protocol::Udp
+            && matches!((self.src.port(), self.dst.port()), (68, 67) | (546, 547))
+    }
+
+    /// DHCPv4 / DHCPv6 server → client ports.
+    fn is_dhcp_reply(&self) -> bool {
+        self.proto == Protocol::Udp
+            && matches!((self.src.port(), self.dst.port()), (67, 68) | (547, 546))
+    }
+
+    fn is_dns_query(&self) -> bool {
+        self.proto == Protocol::Udp && self.dst.port() == 53
+    }
+}
+
+impl TcpMeta {
+    fn closes(&self) -> bool {
+        self.flags & (TCP_FIN | TCP_RST) != 0
+    }
+}
+
+fn be16(b: &[u8], at: usize) -> u16 {
+    u16::from_be_bytes([b[at], b[at + 1]])
+}
+
+fn parse_frame(frame: &[u8]) -> Parsed {
+    if frame.len() < 14 {
+        return Parsed::Unsupported("runt frame");
+    }
+    match be16(frame, 12) {
+        ETHERTYPE_ARP => Parsed::LinkControl,
+        ETHERTYPE_IPV4 => parse_ipv4(frame, 14),
+        ETHERTYPE_IPV6 => parse_ipv6(frame, 14),
+        _ => Parsed::Unsupported("ethertype"),
+    }
+}
+
+fn parse_ipv4(frame: &[u8], l3: usize) -> Parsed {
+    let ip = &frame[l3..];
+    if ip.len() < 20 || ip[0] >> 4 != 4 {
+        return Parsed::Unsupported("ipv4 header");
+    }
+    let ihl = usize::from(ip[0] & 0x0f) * 4;
+    let total = usize::from(be16(ip, 2));
+    if ihl < 20 || total < ihl || ip.len() < total {
+        return Parsed::Unsupported("ipv4 length");
+    }
+    if be16(ip, 6) & 0x1fff != 0 {
+        return Parsed::Unsupported("ipv4 fragment");
+    }
+    let src = IpAddr::V4(Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]));
+    let dst = IpAddr::V4(Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]));
+    match ip[9] {
+        IPPROTO_ICMP => Parsed::Unsupported("icmp"),
+        proto => parse_l4(proto, src, dst, &ip[ihl..total], l3, l3 + ihl),
+    }
+}
+
+fn parse_ipv6(frame: &[u8], l3: usize) -> Parsed {
+    let ip = &frame[l3..];
+    if ip.len() < 40 || ip[0] >> 4 != 6 {
+        return Parsed::Unsupported("ipv6 header");
+    }
+    let payload = usize::from(be16(ip, 4));
+    if ip.len() < 40 + payload {
+        return Parsed::Unsupported("ipv6 length");
+    }
+    let addr = |at: usize| {
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(&ip[at..at + 16]);
+        IpAddr::V6(Ipv6Addr::from(octets))
+    };
+    let (src, dst) = (addr(8), addr(24));
+    let body = &ip[40..40 + payload];
+    match ip[6] {
+        IPPROTO_ICMPV6 => match body.first() {
+            // Router solicitation/advertisement, neighbour
+            // solicitation/advertisement, redirect.
+            Some(133..=137) => Parsed::LinkControl,
+            _ => Parsed::Unsupported("icmpv6"),
+        },
+        proto => parse_l4(proto, src, dst, body, l3, l3 + 40),
+    }
+}
+
+fn parse_l4(
+    proto: u8,
+    src: IpAddr,
+    dst: IpAddr,
+    l4: &[u8],
+    l3_offset: usize,
+    l4_offset: usize,
+) -> Parsed {
+    match proto {
+        IPPROTO_TCP => {
+            if l4.len() < 20 {
+                return Parsed::Unsupported("tcp header");
+            }
+            let doff = usize::from(l4[12] >> 4) * 4;
+            if doff < 20 || l4.len() < doff {
+                return Parsed::Unsupported("tcp header");
+            }
+            Parsed::Flow(Packet {
+                proto: Protocol::Tcp,
+                src: SocketAddr::new(src, be16(l4, 0)),
+                dst: SocketAddr::new(dst, be16(l4, 2)),
+                tcp: Some(TcpMeta {
+                    seq: u32::from_be_bytes([l4[4], l4[5], l4[6], l4[7]]),
+                    flags: l4[13],
+                    payload_len: l4.len() - doff,
+                }),
+                l3_offset,
+                payload: l4_offset + doff..l4_offset + l4.len(),
+            })
+        }
+        IPPROTO_UDP => {
+            if l4.len() < 8 {
+                return Parsed::Unsupported("udp header");
+            }
+            let (sport, dport) = (be16(l4, 0), be16(l4, 2));
+            let len = usize::from(be16(l4, 4));
+            if len < 8 || l4.len() < len {
+                return Parsed::Unsupported("udp length");
+            }
+            // DHCPv4 / DHCPv6 client → server on the link; answered by
+            // the gateway. Unicast DHCP is decided in `egress`.
+            let on_link = match dst {
+                IpAddr::V4(a) => a.is_broadcast(),
+                IpAddr::V6(a) => a == DHCPV6_SERVERS,
+            };
+            if on_link && matches!((sport, dport), (68, 67) | (546, 547)) {
+                return Parsed::LinkControl;
+            }
+            Parsed::Flow(Packet {
+                proto: Protocol::Udp,
+                src: SocketAddr::new(src, sport),
+                dst: SocketAddr::new(dst, dport),
+                tcp: None,
+                l3_offset,
+                payload: l4_offset + 8..l4_offset + len,
+            })
+        }
+        _ => Parsed::Unsupported("ip protocol"),
+    }
+}
+
+// ──────────────────────────────────────────────────────────────────────
+// RST synthesis
+// ──────────────────────────────────────────────────────────────────────
+
+/// Start the frame a remote would send back for `packet`: MACs and
+/// addresses swapped, then an IP header for `l4_len` bytes of
+/// `proto`.
+fn reply_headers(frame: &[u8], packet: &Packet, proto: u8, l4_len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(packet.l3_offset + 40 + l4_len);
+    out.extend_from_slice(&frame[6..12]);
+    out.extend_from_slice(&frame[0..6]);
+    match (packet.dst.ip(), packet.src.ip()) {
+        (IpAddr::V4(s), IpAddr::V4(d)) => {
+            out.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+            let mut ip = [0u8; 20];
+            ip[0] = 0x45;
+            let total = u16::try_from(20 + l4_len).unwrap_or(u16::MAX);
+            ip[2..4].copy_from_slice(&total.to_be_bytes());
+            ip[6] = 0x40; // DF
+            ip[8] = 64;
+            ip[9] = proto;
+            ip[12..16].copy_from_slice(&s.octets());
+            ip[16..20].copy_from_slice(&d.octets());
+            let csum = checksum(0, &ip);
+            ip[10..12].copy_from_slice(&csum.to_be_bytes());
+            out.extend_from_slice(&ip);
+        }
+        (IpAddr::V6(s), IpAddr::V6(d)) => {
+            out.extend_from_slice(&ETHERTYPE_IPV6.to_be_bytes());
+            let mut ip = [0u8; 40];
+            ip[0] = 0x60;
+            let len = u16::try_from(l4_len).unwrap_or(u16::MAX);
+            ip[4..6].copy_from_slice(&len.to_be_bytes());
+            ip[6] = proto;
+            ip[7] = 64;
+            ip[8..24].copy_from_slice(&s.octets());
+            ip[24..40].copy_from_slice(&d.octets());
+            out.extend_from_slice(&ip);
+        }
+        _ => unreachable!("a parsed packet's addresses share a family"),
+    }
+    out
+}
+
+/// Build the RST|ACK a remote would send for a refused SYN: MACs,
+/// addresses and ports swapped, `ack = seq + 1 + payload`.
+fn tcp_rst_for(frame: &[u8], packet: &Packet, tcp: &TcpMeta) -> Vec<u8> {
+    let (src_ip, dst_ip) = (packet.dst.ip(), packet.src.ip());
+    let mut out = reply_headers(frame, packet, IPPROTO_TCP, 20);
+
+    let consumed = u32::try_from(tcp.payload_len)
+        .unwrap_or(u32::MAX)
+        .wrapping_add(1);
+    let mut seg = [0u8; 20];
+    seg[0..2].copy_from_slice(&packet.dst.port().to_be_bytes());
+    seg[2..4].copy_from_slice(&packet.src.port().to_be_bytes());
+    seg[8..12].copy_from_slice(&tcp.seq.wrapping_add(consumed).to_be_bytes());
+    seg[12] = 5 << 4;
+    seg[13] = TCP_RST | TCP_ACK;
+    let csum = checksum(
+        pseudo_header_sum(src_ip, dst_ip, IPPROTO_TCP, seg.len()),
+        &seg,
+    );
+    seg[16..18].copy_from_slice(&csum.to_be_bytes());
+    out.extend_from_slice(&seg);
+    out
+}
+
+/// Build the datagram the queried nameserver would have sent: `body`
+/// from the guest's destination address and port back to its source.
+fn udp_reply_for(frame: &[u8], packet: &Packet, body: &[u8]) -> Vec<u8> {
+    let (src_ip, dst_ip) = (packet.dst.ip(), packet.src.ip());
+    let len = 8 + body.len();
+    let mut out = reply_headers(frame, packet, IPPROTO_UDP, len);
+    let mut dgram = Vec::with_capacity(len);
+    dgram.extend_from_slice(&packet.dst.port().to_be_bytes());
+    dgram.extend_from_slice(&packet.src.port().to_be_bytes());
+    dgram.extend_from_slice(&u16::try_from(len).unwrap_or(u16::MAX).to_be_bytes());
+    dgram.extend_from_slice(&[0, 0]);
+    dgram.extend_from_slice(body);
+    // A computed zero is sent as all ones; zero means "no checksum"
+    // (and is invalid over IPv6).
+    let csum = match checksum(pseudo_header_sum(src_ip, dst_ip, IPPROTO_UDP, len), &dgram) {
+        0 => 0xffff,
+        c => c,
+    };
+    dgram[6..8].copy_from_slice(&csum.to_be_bytes());
+    out.extend_from_slice(&dgram);
+    out
+}
+
+fn pseudo_header_sum(src: IpAddr, dst: IpAddr, proto: u8, len: usize) -> u32 {
+    let mut sum = 0u32;
+    let mut add = |bytes: &[u8]| {
+        for pair in bytes.chunks(2) {
+            sum += u32::from(u16::from_be_bytes([pair[0], pair[1]]));
+        }
+    };
+    match (src, dst) {
+        (IpAddr::V4(s), IpAddr::V4(d)) => {
+            add(&s.octets());
+            add(&d.octets());
+        }
+        (IpAddr::V6(s), IpAddr::V6(d)) => {
+            add(&s.octets());
+            add(&d.octets());
+        }
+        _ => {}
+    }
+    sum + u32::from(proto) + u32::try_from(len).unwrap_or(0)
+}
+
+/// RFC 1071 internet checksum over `data`, seeded with `initial`.
+fn checksum(initial: u32, data: &[u8]) -> u16 {
+    let mut sum = initial;
+    for chunk in data.chunks(2) {
+        let word = match chunk {
+            [hi, lo] => u16::from_be_bytes([*hi, *lo]),
+            [hi] => u16::from_be_bytes([*hi, 0]),
+            _ => 0,
+        };
+        sum += u32::from(word);
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::egress::EgressError;
+    use crate::l7_proxy::CapturingEgressAuditSink;
+    use crate::proxy::l4::{L4Error, L4Policy, L4Rule, NoopL4Gate};
+    use async_trait::async_trait;
+    use hickory_proto::op::{Message, MessageType, OpCode, Query, ResponseCode};
+    use hickory_proto::rr::{Name, RData, RecordType};
+    use hickory_proto::serialize::binary::{BinDecodable, BinEncodable};
+
+    const GUEST_MAC: [u8; 6] = [0x52, 0x54, 0, 0, 0, 1];
+    const GW_MAC: [u8; 6] = [0x52, 0x54, 0, 0, 0, 2];
+
+    fn eth(ethertype: u16, payload: &[u8]) -> Vec<u8> {
+        let mut f = Vec::new();
+        f.extend_from_slice(&GW_MAC);
+        f.extend_from_slice(&GUEST_MAC);
+        f.extend_from_slice(&ethertype.to_be_bytes());
+        f.extend_from_slice(payload);
+        f
+    }
+
+    fn tcp_seg(sport: u16, dport: u16, seq: u32, flags: u8) -> Vec<u8> {
+        let mut seg = vec![0u8; 20];
+        seg[0..2].copy_from_slice(&sport.to_be_bytes());
+        seg[2..4].copy_from_slice(&dport.to_be_bytes());
+        seg[4..8].copy_from_slice(&seq.to_be_bytes());
+        seg[12] = 5 << 4;
+        seg[13] = flags;
+        seg
+    }
+
+    fn udp_dgram(sport: u16, dport: u16) -> Vec<u8> {
+        let mut d = vec![0u8; 12];
+        d[0..2].copy_from_slice(&sport.to_be_bytes());
+        d[2..4].copy_from_slice(&dport.to_be_bytes());
+        d[4..6].copy_from_slice(&12u16.to_be_bytes());
+        d
+    }
+
+    fn ipv4(proto: u8, src: [u8; 4], dst: [u8; 4], l4: &[u8]) -> Vec<u8> {
+        let mut ip = vec![0u8; 20];
+        ip[0] = 0x45;
+        ip[2..4].copy_from_slice(&(20 + l4.len() as u16).to_be_bytes());
+        ip[8] = 64;
+        ip[9] = proto;
+        ip[12..16].copy_from_slice(&src);
+        ip[16..20].copy_from_slice(&dst);
+        ip.extend_from_slice(l4);
+        eth(ETHERTYPE_IPV4, &ip)
+    }
+
+    fn ipv6(next: u8, src: Ipv6Addr, dst: Ipv6Addr, l4: &[u8]) -> Vec<u8> {
+        let mut ip = vec![0u8; 40];
+        ip[0] = 0x60;
+        ip[4..6].copy_from_slice(&(l4.len() as u16).to_be_bytes());
+        ip[6] = next;
+        ip[7] = 64;
+        ip[8..24].copy_from_slice(&src.octets());
+        ip[24..40].copy_from_slice(&dst.octets());
+        ip.extend_from_slice(l4);
+        eth(ETHERTYPE_IPV6, &ip)
+    }
+
+    const GUEST: [u8; 4] = [10, 0, 2, 15];
+    const DB: [u8; 4] = [203, 0, 113, 7];
+
+    fn datapath() -> (L4Datapath, Arc<CapturingEgressAuditSink>) {
+        let policy = L4Policy::new([
+            L4Rule::single_port(Protocol::Tcp, "203.0.113.7/32".parse().unwrap(), 5432),
+            L4Rule::single_port(Protocol::Udp, "8.8.8.8/32".parse().unwrap(), 53),
+            L4Rule::single_port(Protocol::Tcp, "2001:db8::/32".parse().unwrap(), 443),
+        ]);
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        (
+            L4Datapath::new(Arc::new(LiveL4Gate::new(policy)), audit.clone()),
+            audit,
+        )
+    }
+
+    #[tokio::test]
+    async fn allowed_tcp_flow_is_forwarded_and_audited_once() {
+        let (dp, audit) = datapath();
+        let syn = ipv4(IPPROTO_TCP, GUEST, DB, &tcp_seg(40000, 5432, 1000, TCP_SYN));
+        assert_eq!(dp.egress(&syn).await, FrameVerdict::Forward);
+        let ack = ipv4(IPPROTO_TCP, GUEST, DB, &tcp_seg(40000, 5432, 1001, TCP_ACK));
+        assert_eq!(dp.egress(&ack).await, FrameVerdict::Forward);
+        let reply = ipv4(IPPROTO_TCP, DB, GUEST, &tcp_seg(5432, 40000, 1, TCP_ACK));
+        assert!(dp.ingress(&reply));
+
+        let entries = audit.entries();
+        assert_eq!(entries.len(), 1, "cached flows are not re-audited");
+        assert_eq!(entries[0].outcome, EgressOutcome::Allow);
+        assert_eq!(entries[0].deciding_inspector, "l4_policy");
+        assert_eq!(entries[0].host, "203.0.113.7");
+        assert_eq!(entries[0].port, 5432);
+        assert_eq!(dp.flow_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn denied_syn_is_rejected_with_a_valid_rst() {
+        let (dp, audit) = datapath();
+        let syn = ipv4(IPPROTO_TCP, GUEST, DB, &tcp_seg(40001, 22, 7000, TCP_SYN));
+        let FrameVerdict::Reject(rst) = dp.egress(&syn).await else {
+            panic!("expected Reject");
+        };
+        assert_eq!(&rst[0..6], &GUEST_MAC);
+        assert_eq!(&rst[6..12], &GW_MAC);
+        let ip = &rst[14..34];
+        assert_eq!(checksum(0, ip), 0, "IPv4 header checksum must verify");
+        assert_eq!(&ip[12..16], &DB);
+        assert_eq!(&ip[16..20], &GUEST);
+        let seg = &rst[34..54];
+        assert_eq!(be16(seg, 0), 22);
+        assert_eq!(be16(seg, 2), 40001);
+        assert_eq!(u32::from_be_bytes([seg[8], seg[9], seg[10], seg[11]]), 7001);
+        assert_eq!(seg[13], TCP_RST | TCP_ACK);
+        let pseudo = pseudo_header_sum(
+            IpAddr::V4(DB.into()),
+            IpAddr::V4(GUEST.into()),
+            IPPROTO_TCP,
+            20,
+        );
+        assert_eq!(checksum(pseudo, seg), 0, "TCP checksum must verify");
+
+        let entries = audit.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, EgressOutcome::Deny);
+        assert!(
+            entries[0]
+                .reason
+                .as_deref()
+                .unwrap()
+                .contains("no L4 rule matched")
+        );
+
+        // Retransmitted SYN: still refused, not re-audited.
+        assert!(matches!(dp.egress(&syn).await, FrameVerdict::Reject(_)));
+        assert_eq!(audit.entries().len(), 1);
+        // Anything the gateway sends for the denied flow is dropped.
+        let reply = ipv4(IPPROTO_TCP, DB, GUEST, &tcp_seg(22, 40001, 1, TCP_ACK));
+        assert!(!dp.ingress(&reply));
+    }
+
+    #[tokio::test]
+    async fn ipv6_denied_syn_rst_checksums() {
+        let (dp, _) = datapath();
+        let guest: Ipv6Addr = "fd00::15".parse().unwrap();
+        let allowed: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let other: Ipv6Addr = "2606:4700::1".parse().unwrap();
+        let ok = ipv6(
+            IPPROTO_TCP,
+            guest,
+            allowed,
+            &tcp_seg(40002, 443, 1, TCP_SYN),
+        );
+        assert_eq!(dp.egress(&ok).await, FrameVerdict::Forward);
+        let denied = ipv6(IPPROTO_TCP, guest, other, &tcp_seg(40003, 443, 9, TCP_SYN));
+        let FrameVerdict::Reject(rst) = dp.egress(&denied).await else {
+            panic!("expected Reject");
+        };
+        let seg = &rst[54..74];
+        let pseudo = pseudo_header_sum(IpAddr::V6(other), IpAddr::V6(guest), IPPROTO_TCP, 20);
+        assert_eq!(checksum(pseudo, seg), 0);
+        assert_eq!(u32::from_be_bytes([seg[8], seg[9], seg[10], seg[11]]), 10);
+    }
+
+    #[tokio::test]
+    async fn udp_is_evaluated_per_flow_and_denials_drop() {
+        let (dp, audit) = datapath();
+        let dns = ipv4(IPPROTO_UDP, GUEST, [8, 8, 8, 8], &udp_dgram(5353, 53));
+        assert_eq!(dp.egress(&dns).await, FrameVerdict::Forward);
+        let other = ipv4(IPPROTO_UDP, GUEST, [1, 1, 1, 1], &udp_dgram(5354, 53));
+        assert_eq!(dp.egress(&other).await, FrameVerdict::Drop);
+        assert_eq!(audit.entries().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn link_control_passes_and_unsupported_frames_drop() {
+        let (dp, audit) = datapath();
+        assert_eq!(
+            dp.egress(&eth(ETHERTYPE_ARP, &[0u8; 28])).await,
+            FrameVerdict::Forward
+        );
+        let dhcp = ipv4(IPPROTO_UDP, [0; 4], [255; 4], &udp_dgram(68, 67));
+        assert_eq!(dp.egress(&dhcp).await, FrameVerdict::Forward);
+        let solicit = ipv6(
+            IPPROTO_UDP,
+            "fe80::1".parse().unwrap(),
+            DHCPV6_SERVERS,
+            &udp_dgram(546, 547),
+        );
+        assert_eq!(dp.egress(&solicit).await, FrameVerdict::Forward);
+        let ns = ipv6(
+            IPPROTO_ICMPV6,
+            "fe80::1".parse().unwrap(),
+            "ff02::1:ff00:2".parse().unwrap(),
+            &[135, 0, 0, 0, 0, 0, 0, 0],
+        );
+        assert_eq!(dp.egress(&ns).await, FrameVerdict::Forward);
+
+        let ping = ipv4(IPPROTO_ICMP, GUEST, DB, &[8, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(dp.egress(&ping).await, FrameVerdict::Drop);
+        assert_eq!(
+            dp.egress(&eth(0x8100, &[0u8; 64])).await,
+            FrameVerdict::Drop
+        );
+        let mut frag = ipv4(IPPROTO_UDP, GUEST, [8, 8, 8, 8], &udp_dgram(5353, 53));
+        frag[14 + 7] = 0x10; // fragment offset != 0
+        assert_eq!(dp.egress(&frag).await, FrameVerdict::Drop);
+        assert_eq!(dp.egress(&[0u8; 10]).await, FrameVerdict::Drop);
+        assert!(audit.entries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unicast_dhcp_is_link_control_only_to_the_gateway_server() {
+        let (dp, audit) = datapath();
+        // Off-link unicast "DHCP" is an ordinary flow: evaluated and
+        // denied.
+        let off_link = ipv4(IPPROTO_UDP, GUEST, DB, &udp_dgram(68, 67));
+        assert_eq!(dp.egress(&off_link).await, FrameVerdict::Drop);
+        let entries = audit.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, EgressOutcome::Deny);
+        assert_eq!(entries[0].host, "203.0.113.7");
+        let v6 = ipv6(
+            IPPROTO_UDP,
+            "fd00::15".parse().unwrap(),
+            "2606:4700::1".parse().unwrap(),
+            &udp_dgram(546, 547),
+        );
+        assert_eq!(dp.egress(&v6).await, FrameVerdict::Drop);
+        assert_eq!(audit.entries().len(), 2);
+
+        // Once the gateway's server has answered, renewals to it pass
+        // unaudited.
+        let gw = [10, 0, 2, 2];
+        let offer = ipv4(IPPROTO_UDP, gw, [255; 4], &udp_dgram(67, 68));
+        assert!(dp.ingress(&offer));
+        let renew = ipv4(IPPROTO_UDP, GUEST, gw, &udp_dgram(68, 67));
+        assert_eq!(dp.egress(&renew).await, FrameVerdict::Forward);
+        assert_eq!(audit.entries().len(), 2);
+
+        // A reply on a flow the gate decided teaches nothing.
+        let spoof = ipv4(IPPROTO_UDP, DB, GUEST, &udp_dgram(67, 68));
+        assert!(!dp.ingress(&spoof));
+        let off_link = ipv4(IPPROTO_UDP, GUEST, DB, &udp_dgram(68, 67));
+        assert_eq!(dp.egress(&off_link).await, FrameVerdict::Drop);
+    }
+
+    #[tokio::test]
+    async fn unwired_gate_fails_closed() {
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        let dp = L4Datapath::new(Arc::new(NoopL4Gate), audit.clone());
+        let syn = ipv4(IPPROTO_TCP, GUEST, DB, &tcp_seg(40000, 5432, 0, TCP_SYN));
+        assert!(matches!(dp.egress(&syn).await, FrameVerdict::Reject(_)));
+        assert_eq!(
+            audit.entries()[0].reason.as_deref(),
+            Some(L4Error::NotWired.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn from_network_policy_is_none_without_l4_rows() {
+        let audit: Arc<dyn EgressAuditSink> = Arc::new(CapturingEgressAuditSink::new());
+        let mut network = mvm_policy::NetworkPolicy::default();
+        assert!(
+            L4Datapath::from_network_policy(&network, audit.clone())
+                .unwrap()
+                .is_none()
+        );
+        network.l4.push(mvm_policy::L4RuleSpec {
+            proto: "tcp".to_string(),
+            dst_cidr: "10.0.0.0/8".to_string(),
+            port_lo: 443,
+            port_hi: 443,
+        });
+        assert!(
+            L4Datapath::from_network_policy(&network, audit)
+                .unwrap()
+                .is_some()
+        );
+    }
+    struct FixedResolver(IpAddr);
+
+    #[async_trait]
+    impl DnsResolver for FixedResolver {
+        async fn resolve_one(&self, _host: &str, _port: u16) -> Result<IpAddr, EgressError> {
+            Ok(self.0)
+        }
+
+        async fn resolve_all(&self, _host: &str, _port: u16) -> Result<Vec<IpAddr>, EgressError> {
+            Ok(vec![self.0])
+        }
+    }
+
+    const DNS_GW: [u8; 4] = [10, 0, 2, 3];
+    const PUBLIC: [u8; 4] = [93, 184, 216, 34];
+
+    fn dns_query_frame(sport: u16, name: &str) -> Vec<u8> {
+        let mut message = Message::new(0x4242, MessageType::Query, OpCode::Query);
+        message.add_query(Query::query(Name::from_ascii(name).unwrap(), RecordType::A));
+        let body = message.to_bytes().unwrap();
+        let mut dgram = Vec::new();
+        dgram.extend_from_slice(&sport.to_be_bytes());
+        dgram.extend_from_slice(&53u16.to_be_bytes());
+        dgram.extend_from_slice(&(8 + body.len() as u16).to_be_bytes());
+        dgram.extend_from_slice(&[0, 0]);
+        dgram.extend_from_slice(&body);
+        ipv4(IPPROTO_UDP, GUEST, DNS_GW, &dgram)
+    }
+
+    /// Check the reply frame's headers and return the decoded DNS body.
+    fn dns_answer(reply: &[u8], sport: u16) -> Message {
+        assert_eq!(&reply[0..6], &GUEST_MAC);
+        assert_eq!(&reply[6..12], &GW_MAC);
+        let ip = &reply[14..34];
+        assert_eq!(checksum(0, ip), 0, "IPv4 header checksum must verify");
+        assert_eq!(&ip[12..16], &DNS_GW);
+        assert_eq!(&ip[16..20], &GUEST);
+        let dgram = &reply[34..];
+        assert_eq!(be16(dgram, 0), 53);
+        assert_eq!(be16(dgram, 2), sport);
+        assert_eq!(be16(dgram, 4) as usize, dgram.len());
+        let pseudo = pseudo_header_sum(
+            IpAddr::V4(DNS_GW.into()),
+            IpAddr::V4(GUEST.into()),
+            IPPROTO_UDP,
+            dgram.len(),
+        );
+        assert_eq!(checksum(pseudo, dgram), 0, "UDP checksum must verify");
+        Message::from_bytes(&dgram[8..]).unwrap()
+    }
+
+    fn empty_bundle() -> mvm_policy::PolicyBundle {
+        mvm_policy::PolicyBundle {
+            schema_version: 1,
+            bundle_id: mvm_policy::PolicyId("bundle-dns".to_string()),
+            bundle_version: 1,
+            network: Default::default(),
+            egress: Default::default(),
+            pii: Default::default(),
+            tool: Default::default(),
+            artifact: Default::default(),
+            keys: Default::default(),
+            audit: Default::default(),
+            threat: Default::default(),
+            fs_quota: None,
+            tenant_overlays: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn from_bundle_answers_guest_dns_and_admits_pinned_flows() {
+        let dir = tempfile::tempdir().unwrap();
+        let pins = Arc::new(DnsPinStore::for_vm("vm-a").with_audit_log(dir.path().join("a.jsonl")));
+        let mut bundle = empty_bundle();
+        bundle.network.l4.push(mvm_policy::L4RuleSpec {
+            proto: "tcp".to_string(),
+            dst_cidr: "203.0.113.7/32".to_string(),
+            port_lo: 5432,
+            port_hi: 5432,
+        });
+        bundle
+            .egress
+            .allow_list
+            .push(("api.example.com".to_string(), 443));
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        let dp = L4Datapath::from_bundle(
+            &bundle,
+            pins.clone(),
+            Arc::new(FixedResolver(IpAddr::V4(PUBLIC.into()))),
+            audit.clone(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(Arc::ptr_eq(dp.dns_pins().unwrap(), &pins));
+
+        // Not yet resolved: the address is outside the L4 rules.
+        let syn = ipv4(IPPROTO_TCP, GUEST, PUBLIC, &tcp_seg(40010, 443, 1, TCP_SYN));
+        assert!(matches!(dp.egress(&syn).await, FrameVerdict::Reject(_)));
+
+        let FrameVerdict::Answer(pending) =
+            dp.egress(&dns_query_frame(40011, "api.example.com.")).await
+        else {
+            panic!("expected the datapath to answer the query");
+        };
+        let message = dns_answer(&pending.resolve().await.unwrap(), 40011);
+        assert_eq!(message.metadata.response_code, ResponseCode::NoError);
+        assert!(matches!(&message.answers[0].data, RData::A(a) if a.0.octets() == PUBLIC));
+
+        // Pinned for an allowed name: a new flow on the allowed port
+        // passes, any other port is still refused.
+        let syn = ipv4(IPPROTO_TCP, GUEST, PUBLIC, &tcp_seg(40012, 443, 1, TCP_SYN));
+        assert_eq!(dp.egress(&syn).await, FrameVerdict::Forward);
+        let syn = ipv4(IPPROTO_TCP, GUEST, PUBLIC, &tcp_seg(40013, 22, 1, TCP_SYN));
+        assert!(matches!(dp.egress(&syn).await, FrameVerdict::Reject(_)));
+
+        let FrameVerdict::Answer(pending) = dp
+            .egress(&dns_query_frame(40014, "evil.example.net."))
+            .await
+        else {
+            panic!("expected the datapath to answer the query");
+        };
+        let message = dns_answer(&pending.resolve().await.unwrap(), 40014);
+        assert_eq!(message.metadata.response_code, ResponseCode::NXDomain);
+        assert!(
+            audit
+                .entries()
+                .iter()
+                .any(|e| e.deciding_inspector == "dns_endpoint"
+                    && e.host == "evil.example.net"
+                    && e.outcome == EgressOutcome::Deny)
+        );
+        assert_eq!(dp.flow_count(), 3, "DNS queries are not tracked as flows");
+    }
+
+    #[tokio::test]
+    async fn dns_queries_in_flight_are_bounded() {
+        let pins = Arc::new(DnsPinStore::for_vm("vm-a"));
+        let endpoint = GuestDnsEndpoint::new(
+            DestinationPolicy::new([("api.example.com", 443)]),
+            Arc::new(FixedResolver(IpAddr::V4(PUBLIC.into()))),
+            pins,
+            Arc::new(CapturingEgressAuditSink::new()),
+        );
+        let (dp, _audit) = datapath();
+        let dp = dp.with_dns_endpoint(Arc::new(endpoint));
+
+        let mut pending = Vec::new();
+        for sport in 0..MAX_DNS_IN_FLIGHT as u16 {
+            match dp
+                .egress(&dns_query_frame(41000 + sport, "api.example.com."))
+                .await
+            {
+                FrameVerdict::Answer(answer) => pending.push(answer),
+                other => panic!("expected an answer, got {other:?}"),
+            }
+        }
+        let frame = dns_query_frame(40999, "api.example.com.");
+        assert_eq!(dp.egress(&frame).await, FrameVerdict::Drop);
+
+        // Finishing one answer frees its slot.
+        assert!(pending.pop().unwrap().resolve().await.is_some());
+        assert!(matches!(dp.egress(&frame).await, FrameVerdict::Answer(_)));
+    }
+
+    #[tokio::test]
+    async fn from_bundle_engages_for_a_restricted_allow_list_without_l4_rows() {
+        let resolver: Arc<dyn DnsResolver> = Arc::new(FixedResolver(IpAddr::V4(PUBLIC.into())));
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        let build = |bundle: &mvm_policy::PolicyBundle| {
+            L4Datapath::from_bundle(
+                bundle,
+                Arc::new(DnsPinStore::for_vm("vm-a")),
+                resolver.clone(),
+                audit.clone(),
+            )
+            .unwrap()
+        };
+
+        let mut bundle = empty_bundle();
+        assert!(build(&bundle).is_none(), "nothing to enforce");
+        bundle
+            .egress
+            .allow_list
+            .push(("api.example.com".to_string(), 443));
+        bundle.egress.mode = Some("open".to_string());
+        assert!(build(&bundle).is_none(), "open mode restricts nothing");
+        bundle.egress.mode = None;
+        let dp = build(&bundle).expect("restricted allow-list engages the datapath");
+
+        // No L4 rows: any flow passes, pinned or not.
+        let syn = ipv4(IPPROTO_TCP, GUEST, DB, &tcp_seg(40020, 22, 1, TCP_SYN));
+        assert_eq!(dp.egress(&syn).await, FrameVerdict::Forward);
+
+        // DNS is still confined to the allow-list.
+        let FrameVerdict::Answer(pending) = dp
+            .egress(&dns_query_frame(40021, "evil.example.net."))
+            .await
+        else {
+            panic!("expected the datapath to answer the query");
+        };
+        let message = dns_answer(&pending.resolve().await.unwrap(), 40021);
+        assert_eq!(message.metadata.response_code, ResponseCode::NXDomain);
+        let FrameVerdict::Answer(pending) =
+            dp.egress(&dns_query_frame(40022, "api.example.com.")).await
+        else {
+            panic!("expected the datapath to answer the query");
+        };
+        let message = dns_answer(&pending.resolve().await.unwrap(), 40022);
+        assert_eq!(message.metadata.response_code, ResponseCode::NoError);
+    }
+}
@@ -0,0 +1,841 @@
+//! Guest-facing DNS endpoint + per-VM DNS pin store.
+//!
+//! The bridge's L4 datapath ([`super::datapath::L4Datapath::from_bundle`])
+//! hands every UDP/53 query the guest sends to the VM's
+//! [`GuestDnsEndpoint`] and writes the answer back in place of the
+//! nameserver the guest addressed. It is deliberately not a general
+//! recursive resolver:
+//!
+//! - **Policy-filtered names.** Only names the workload's egress
+//!   allow-list permits (on any port — the port isn't known at
+//!   lookup time) get an answer. Everything else is `NXDOMAIN`, and
+//!   the refusal is recorded through the same [`EgressAuditSink`]
+//!   the L7 proxy uses, with `deciding_inspector = "dns_endpoint"`.
+//! - **Internal addresses stripped.** Upstream answers pass through
+//!   [`SsrfGuard::classify`] before they reach the guest, so a public
+//!   name that rebinds to IMDS / RFC1918 / loopback never lands in
+//!   the guest's cache. A name whose every address is internal is
+//!   answered `NXDOMAIN` and audited with `deciding_inspector =
+//!   "ssrf_guard"`.
+//! - **Every answer is pinned.** The vetted address set is recorded
+//!   in the VM's [`DnsPinStore`] (emitting `DnsPinSet`), and the
+//!   answer TTL is half the pin TTL so the guest re-asks before the
+//!   pin lapses. While a pin is live, queries for its name are
+//!   answered from it without re-resolving: replacing it with a
+//!   fresh (CDN-rotated) set would strand flows the guest opened
+//!   against the earlier answer.
+//!
+//! The bridge datapath's L4 gate ([`super::l4::PinnedL4Gate`]) reads
+//! the same store to admit flows to pinned addresses of allowed
+//! names, and refuses a named flow whose IP diverges from the live
+//! pin, emitting `DnsPinReject`. The L7 proxy can run the same check
+//! ([`crate::l7_proxy::L7EgressProxy::with_dns_pins`]) given the
+//! store, but it does not run in the bridge process, so no launch
+//! path hands it one yet.
+//!
+//! Only `A` / `AAAA` queries are resolved. Other types for an allowed
+//! name get an empty `NOERROR` (no data) rather than being forwarded,
+//! so TXT / SRV / HTTPS records can't be used as a side channel.
+//! UDP only — answers are a handful of address records and fit well
+//! under the 512-byte classic limit.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::{Duration, Utc};
+use hickory_proto::op::{Message, MessageType, OpCode, ResponseCode};
+use hickory_proto::rr::rdata::{A, AAAA};
+use hickory_proto::rr::{DNSClass, RData, Record, RecordType};
+use hickory_proto::serialize::binary::{BinDecodable, BinDecoder, BinEncodable, BinEncoder};
+use mvm_core::policy::audit::{LocalAuditKind, event};
+use mvm_core::policy::dns_pin::{DnsPin, DnsPinRegistry};
+use mvm_core::policy::network_policy::NetworkPolicy;
+
+use crate::destination::DestinationPolicy;
+use crate::l7_proxy::{AuditFields, DnsResolver, EgressAuditSink, EgressOutcome};
+use crate::ssrf_guard::SsrfGuard;
+
+/// Default pin validity window. Short enough that a CDN rotation is
+/// picked up within minutes, long enough that a chatty guest isn't
+/// re-resolving on every connection.
+pub const DEFAULT_PIN_TTL_SECS: i64 = 300;
+
+/// Outcome of checking an observed flow IP against the pin store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinCheck {
+    /// No live pin for the name — the guest didn't resolve it through
+    /// the endpoint (e.g. a CONNECT-by-name), or the pin expired.
+    Unpinned,
+    /// The observed IP is in the pinned set.
+    Matches,
+    /// A live pin exists and the observed IP isn't in it.
+    Diverged { pinned: Vec<IpAddr> },
+}
+
+/// Per-VM pin store shared by the DNS endpoint (writer) and the L7 /
+/// L4 enforcement points (readers). Wraps the wire-stable
+/// [`DnsPinRegistry`] and owns the audit emission for both pin kinds.
+pub struct DnsPinStore {
+    vm_name: String,
+    ttl: Duration,
+    registry: Mutex<DnsPinRegistry>,
+    /// Audit log override. `None` writes to the default local audit
+    /// log; tests redirect to a tempdir.
+    audit_log: Option<PathBuf>,
+}
+
+impl DnsPinStore {
+    pub fn new(vm_name: impl Into<String>, ttl: Duration) -> Self {
+        Self {
+            vm_name: vm_name.into(),
+            ttl,
+            registry: Mutex::new(DnsPinRegistry::new()),
+            audit_log: None,
+        }
+    }
+
+    /// A store for `vm_name` with the [`DEFAULT_PIN_TTL_SECS`] window.
+    pub fn for_vm(vm_name: impl Into<String>) -> Self {
+        Self::new(vm_name, Duration::seconds(DEFAULT_PIN_TTL_SECS))
+    }
+
+    /// Redirect `DnsPinSet` / `DnsPinReject` events to `path`.
+    pub fn with_audit_log(mut self, path: impl Into<PathBuf>) -> Self {
+        self.audit_log = Some(path.into());
+        self
+    }
+
+    pub fn vm_name(&self) -> &str {
+        &self.vm_name
+    }
+
+    /// TTL handed to the guest in DNS answers: half the pin lifetime,
+    /// so a cached answer never outlives the pin that vouches for it.
+    pub fn answer_ttl_secs(&self) -> u32 {
+        u32::try_from((self.ttl.num_seconds() / 2).max(1)).unwrap_or(u32::MAX)
+    }
+
+    /// Pin `ips` for `host`, replacing any previous pin, and emit
+    /// `DnsPinSet`.
+    pub fn record(&self, host: &str, ips: Vec<IpAddr>) -> DnsPin {
+        let pin = DnsPin::new(normalize_name(host), ips, self.ttl);
+        self.emit(
+            LocalAuditKind::DnsPinSet,
+            format!(
+                "dest={},ips={},ttl_s={}",
+                pin.dest,
+                join_ips(&pin.ips),
+                pin.ttl_secs()
+            ),
+        );
+        self.lock().add(pin.clone());
+        pin
+    }
+
+    /// The live pin for `host`, or pin `ips` for it and emit
+    /// `DnsPinSet`. Unlike [`Self::record`], a live pin is never
+    /// replaced, so concurrent queries for one name agree.
+    pub fn pin(&self, host: &str, ips: Vec<IpAddr>) -> DnsPin {
+        let host = normalize_name(host);
+        let pin = {
+            let mut registry = self.lock();
+            if let Some(live) = live_pin(&registry, &host) {
+                return live;
+            }
+            let pin = DnsPin::new(host, ips, self.ttl);
+            registry.add(pin.clone());
+            pin
+        };
+        self.emit(
+            LocalAuditKind::DnsPinSet,
+            format!(
+                "dest={},ips={},ttl_s={}",
+                pin.dest,
+                join_ips(&pin.ips),
+                pin.ttl_secs()
+            ),
+        );
+        pin
+    }
+
+    /// The live pin for `host`, if any.
+    pub fn live(&self, host: &str) -> Option<DnsPin> {
+        live_pin(&self.lock(), &normalize_name(host))
+    }
+
+    /// TTL for an answer drawn from `pin`: [`Self::answer_ttl_secs`],
+    /// cut to what is left of the pin so the guest's cache never
+    /// outlives it.
+    pub fn answer_ttl_for(&self, pin: &DnsPin) -> u32 {
+        let remaining = chrono::DateTime::parse_from_rfc3339(&pin.expires_at)
+            .map(|expires| (expires.with_timezone(&Utc) - Utc::now()).num_seconds())
+            .unwrap_or(0);
+        let remaining = u32::try_from(remaining.max(1)).unwrap_or(u32::MAX);
+        self.answer_ttl_secs().min(remaining)
+    }
+
+    /// Compare `observed` against the live pin for `host`. Pure —
+    /// callers decide whether a divergence is fatal and call
+    /// [`Self::reject`] to audit it.
+    pub fn check(&self, host: &str, observed: IpAddr) -> PinCheck {
+        let now = Utc::now().to_rfc3339();
+        let registry = self.lock();
+        match registry.lookup(&normalize_name(host)) {
+            Some(pin) if pin.is_valid_at(&now) => {
+                if pin.matches(&observed) {
+                    PinCheck::Matches
+                } else {
+                    PinCheck::Diverged {
+                        pinned: pin.ips.clone(),
+                    }
+                }
+            }
+            _ => PinCheck::Unpinned,
+        }
+    }
+
+    /// Names with a live pin containing `ip`, sorted. L4 flows carry
+    /// no hostname, so this is how a flow is tied back to the name
+    /// the guest resolved.
+    pub fn hosts_for(&self, ip: IpAddr) -> Vec<String> {
+        let now = Utc::now().to_rfc3339();
+        self.lock()
+            .iter()
+            .filter(|(_, pin)| pin.is_valid_at(&now) && pin.matches(&ip))
+            .map(|(dest, _)| dest.clone())
+            .collect()
+    }
+
+    /// Emit `DnsPinReject` for a flow to `host` that landed on
+    /// `observed` instead of one of `pinned`.
+    pub fn reject(&self, host: &str, pinned: &[IpAddr], observed: IpAddr) {
+        self.emit(
+            LocalAuditKind::DnsPinReject,
+            format!(
+                "dest={},pinned_ips={},observed_ip={observed}",
+                normalize_name(host),
+                join_ips(pinned)
+            ),
+        );
+    }
+
+    /// Copy of the registry with expired pins dropped.
+    pub fn snapshot(&self) -> DnsPinRegistry {
+        let mut registry = self.lock().clone();
+        registry.prune_expired(&Utc::now().to_rfc3339());
+        registry
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DnsPinRegistry> {
+        self.registry.lock().expect("DnsPinStore mutex poisoned")
+    }
+
+    fn emit(&self, kind: LocalAuditKind, detail: String) {
+        let mut builder = event(kind).vm_name(&self.vm_name).detail(detail);
+        if let Some(path) = &self.audit_log {
+            builder = builder.to_path(path);
+        }
+        builder.emit();
+    }
+}
+
+/// Policy-filtered DNS endpoint for one guest. See the module docs.
+pub struct GuestDnsEndpoint {
+    /// `None` answers every name (unrestricted network policy);
+    /// answers are still vetted and pinned.
+    destinations: Option<DestinationPolicy>,
+    resolver: Arc<dyn DnsResolver>,
+    pins: Arc<DnsPinStore>,
+    audit: Arc<dyn EgressAuditSink>,
+}
+
+impl GuestDnsEndpoint {
+    pub fn new(
+        destinations: DestinationPolicy,
+        resolver: Arc<dyn DnsResolver>,
+        pins: Arc<DnsPinStore>,
+        audit: Arc<dyn EgressAuditSink>,
+    ) -> Self {
+        Self {
+            destinations: Some(destinations),
+            resolver,
+            pins,
+            audit,
+        }
+    }
+
+    /// Build from a bundle's `[egress]` section — the same allow-list
+    /// the L7 chain's `DestinationPolicy` is built from, patterns
+    /// included. An invalid entry answers nothing.
+    pub fn from_egress_policy(
+        policy: &mvm_policy::EgressPolicy,
+        resolver: Arc<dyn DnsResolver>,
+        pins: Arc<DnsPinStore>,
+        audit: Arc<dyn EgressAuditSink>,
+    ) -> Self {
+        let destinations = DestinationPolicy::from_egress_policy(policy).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "invalid egress allow-list; guest DNS answers nothing");
+            DestinationPolicy::deny_all()
+        });
+        Self::new(destinations, resolver, pins, audit)
+    }
+
+    /// Build from a VM's [`NetworkPolicy`]. An unrestricted policy
+    /// answers every name.
+    pub fn from_network_policy(
+        policy: &NetworkPolicy,
+        resolver: Arc<dyn DnsResolver>,
+        pins: Arc<DnsPinStore>,
+        audit: Arc<dyn EgressAuditSink>,
+    ) -> Self {
+        Self {
+            destinations: policy.resolve_rules().map(|rules| {
+                DestinationPolicy::from_rules(rules.iter().filter_map(|r| r.allow_rule().ok()))
+            }),
+            resolver,
+            pins,
+            audit,
+        }
+    }
+
+    pub fn pins(&self) -> &Arc<DnsPinStore> {
+        &self.pins
+    }
+
+    /// Answer one DNS packet. Undecodable packets are dropped
+    /// (`None`); anything else gets a response.
+    pub async fn handle_packet(&self, packet: &[u8]) -> Option<Vec<u8>> {
+        let started = Instant::now();
+        let request = Message::read(&mut BinDecoder::new(packet)).ok()?;
+        if request.metadata.message_type != MessageType::Query
+            || request.metadata.op_code != OpCode::Query
+            || request.queries.len() != 1
+        {
+            return encode(error_response(&request, ResponseCode::FormErr));
+        }
+        let query = &request.queries[0];
+        if query.query_class() != DNSClass::IN {
+            return encode(error_response(&request, ResponseCode::Refused));
+        }
+        let name = normalize_name(&query.name().to_ascii());
+
+        if let Some(destinations) = &self.destinations
+            && !destinations.matches_host(&name)
+        {
+            self.audit_deny(
+                "dns_endpoint",
+                &name,
+                format!("dns: {name} not in policy allowlist"),
+                None,
+                started,
+            )
+            .await;
+            return encode(error_response(&request, ResponseCode::NXDomain));
+        }
+
+        let family_v4 = match query.query_type() {
+            RecordType::A => true,
+            RecordType::AAAA => false,
+            // Allowed name, unsupported type: authoritative "no data".
+            _ => return encode(response_base(&request)),
+        };
+
+        let pin = match self.pins.live(&name) {
+            Some(pin) => pin,
+            None => match self.resolve_and_pin(&name, started).await {
+                Ok(pin) => pin,
+                Err(code) => return encode(error_response(&request, code)),
+            },
+        };
+        let ttl = self.pins.answer_ttl_for(&pin);
+        let mut response = response_base(&request);
+        for ip in &pin.ips {
+            let rdata = match (ip, family_v4) {
+                (IpAddr::V4(v4), true) => RData::A(A(*v4)),
+                (IpAddr::V6(v6), false) => RData::AAAA(AAAA(*v6)),
+                _ => continue,
+            };
+            response.add_answer(Record::from_rdata(query.name().clone(), ttl, rdata));
+        }
+        encode(response)
+    }
+
+    /// Resolve `name` upstream, strip internal addresses and pin the
+    /// rest. Both families are resolved regardless of the query type
+    /// so the pin covers whatever the guest ends up dialling.
+    async fn resolve_and_pin(&self, name: &str, started: Instant) -> Result<DnsPin, ResponseCode> {
+        let resolved = match self.resolver.resolve_all(name, 0).await {
+            Ok(ips) => ips,
+            Err(e) => {
+                tracing::warn!(name, error = %e, "guest DNS upstream resolution failed");
+                return Err(ResponseCode::ServFail);
+            }
+        };
+        let (vetted, internal): (Vec<IpAddr>, Vec<IpAddr>) = resolved
+            .into_iter()
+            .partition(|ip| SsrfGuard::classify(*ip).is_none());
+        if vetted.is_empty() {
+            let reason = match internal.first().and_then(|ip| SsrfGuard::classify(*ip)) {
+                Some(class) => format!("dns: {name} resolves only to internal addresses ({class})"),
+                None => format!("dns: {name} resolved to zero addresses"),
+            };
+            self.audit_deny(
+                "ssrf_guard",
+                name,
+                reason,
+                internal.first().copied(),
+                started,
+            )
+            .await;
+            return Err(ResponseCode::NXDomain);
+        }
+        Ok(self.pins.pin(name, vetted))
+    }
+
+    async fn audit_deny(
+        &self,
+        deciding: &'static str,
+        name: &str,
+        reason: String,
+        resolved_ip: Option<IpAddr>,
+        started: Instant,
+    ) {
+        let fields = AuditFields {
+            outcome: EgressOutcome::Deny,
+            deciding_inspector: deciding,
+            host: name.to_string(),
+            port: 53,
+            path: String::new(),
+            transforms: Vec::new(),
+            reason: Some(reason),
+            resolved_ip,
+            pinned_ips: Vec::new(),
+            matched_rule: None,
+            duration_ms: u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX),
+            timestamp: Utc::now(),
+            tls_intercepted: false,
+        };
+        if let Err(e) = self.audit.record(&fields).await {
+            tracing::warn!(name, error = %e, "guest DNS audit record failed");
+        }
+    }
+}
+
+fn live_pin(registry: &DnsPinRegistry, host: &str) -> Option<DnsPin> {
+    registry
+        .lookup(host)
+        .filter(|pin| pin.is_valid_at(&Utc::now().to_rfc3339()))
+        .cloned()
+}
+
+/// Lower-case and strip the root dot so `API.Example.com.` and
+/// `api.example.com` share one pin and one allow-list entry.
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn join_ips(ips: &[IpAddr]) -> String {
+    ips.iter()
+        .map(IpAddr::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn response_base(request: &Message) -> Message {
+    let mut response = Message::response(request.metadata.id, request.metadata.op_code);
+    response.metadata.recursion_desired = request.metadata.recursion_desired;
+    response.metadata.checking_disabled = request.metadata.checking_disabled;
+    response.metadata.recursion_available = true;
+    response.add_queries(request.queries.clone());
+    response
+}
+
+fn error_response(request: &Message, code: ResponseCode) -> Message {
+    let mut response = response_base(request);
+    response.metadata.response_code = code;
+    response
+}
+
+fn encode(message: Message) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(512);
+    message.emit(&mut BinEncoder::new(&mut out)).ok()?;
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::egress::EgressError;
+    use crate::l7_proxy::CapturingEgressAuditSink;
+    use async_trait::async_trait;
+    use hickory_proto::op::Query;
+    use hickory_proto::rr::Name;
+    use mvm_core::policy::audit::LocalAuditEvent;
+    use mvm_core::policy::network_policy::HostPort;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    struct FixedResolver(Vec<IpAddr>);
+
+    #[async_trait]
+    impl DnsResolver for FixedResolver {
+        async fn resolve_one(&self, host: &str, _port: u16) -> Result<IpAddr, EgressError> {
+            self.0
+                .first()
+                .copied()
+                .ok_or_else(|| EgressError::UpstreamUnreachable(format!("dns {host}")))
+        }
+
+        async fn resolve_all(&self, host: &str, _port: u16) -> Result<Vec<IpAddr>, EgressError> {
+            if self.0.is_empty() {
+                return Err(EgressError::UpstreamUnreachable(format!("dns {host}")));
+            }
+            Ok(self.0.clone())
+        }
+    }
+
+    const PUBLIC_V4: IpAddr = IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34));
+    const PUBLIC_V6: IpAddr = IpAddr::V6(Ipv6Addr::new(0x2606, 0x2800, 0x220, 1, 0, 0, 0, 1));
+    const IMDS: IpAddr = IpAddr::V4(Ipv4Addr::new(169, 254, 169, 254));
+
+    struct Fixture {
+        endpoint: GuestDnsEndpoint,
+        audit: Arc<CapturingEgressAuditSink>,
+        log: PathBuf,
+        _dir: tempfile::TempDir,
+    }
+
+    fn fixture(allow: &[(&str, u16)], answers: Vec<IpAddr>) -> Fixture {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("audit.jsonl");
+        let pins = Arc::new(
+            DnsPinStore::new("vm-a", Duration::seconds(DEFAULT_PIN_TTL_SECS)).with_audit_log(&log),
+        );
+        let audit = Arc::new(CapturingEgressAuditSink::new());
+        let endpoint = GuestDnsEndpoint::new(
+            DestinationPolicy::new(allow.iter().copied()),
+            Arc::new(FixedResolver(answers)),
+            pins,
+            audit.clone(),
+        );
+        Fixture {
+            endpoint,
+            audit,
+            log,
+            _dir: dir,
+        }
+    }
+
+    fn query(name: &str, record_type: RecordType) -> Vec<u8> {
+        let mut message = Message::new(0x4242, MessageType::Query, OpCode::Query);
+        message.metadata.recursion_desired = true;
+        message.add_query(Query::query(Name::from_ascii(name).unwrap(), record_type));
+        encode(message).unwrap()
+    }
+
+    fn decode(packet: &[u8]) -> Message {
+        Message::read(&mut BinDecoder::new(packet)).unwrap()
+    }
+
+    fn audit_events(path: &std::path::Path) -> Vec<LocalAuditEvent> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn allowed_name_is_answered_pinned_and_audited_as_pin_set() {
+        let f = fixture(&[("api.example.com", 443)], vec![PUBLIC_V4, PUBLIC_V6]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("API.example.com.", RecordType::A))
+            .await
+            .unwrap();
+        let message = decode(&response);
+        assert_eq!(message.metadata.id, 0x4242);
+        assert_eq!(message.metadata.response_code, ResponseCode::NoError);
+        assert_eq!(message.answers.len(), 1, "A query answers only IPv4");
+        assert_eq!(
+            message.answers[0].data,
+            RData::A(A(Ipv4Addr::new(93, 184, 216, 34)))
+        );
+        assert_eq!(message.answers[0].ttl, (DEFAULT_PIN_TTL_SECS / 2) as u32);
+
+        // The pin covers both families so an AAAA-driven dial matches.
+        let pins = f.endpoint.pins();
+        assert_eq!(pins.check("api.example.com", PUBLIC_V6), PinCheck::Matches);
+        assert_eq!(pins.snapshot().len(), 1);
+
+        let events = audit_events(&f.log);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, LocalAuditKind::DnsPinSet);
+        assert_eq!(events[0].vm_name.as_deref(), Some("vm-a"));
+        assert_eq!(
+            events[0].detail.as_deref(),
+            Some(format!("dest=api.example.com,ips={PUBLIC_V4},{PUBLIC_V6},ttl_s=300").as_str())
+        );
+        assert!(f.audit.entries().is_empty());
+    }
+
+    /// Hands out a different address on every call, like a CDN
+    /// rotating its edge.
+    struct RotatingResolver(std::sync::atomic::AtomicU8);
+
+    #[async_trait]
+    impl DnsResolver for RotatingResolver {
+        async fn resolve_one(&self, host: &str, port: u16) -> Result<IpAddr, EgressError> {
+            Ok(self.resolve_all(host, port).await?[0])
+        }
+
+        async fn resolve_all(&self, _host: &str, _port: u16) -> Result<Vec<IpAddr>, EgressError> {
+            let n = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec![IpAddr::V4(Ipv4Addr::new(93, 184, 216, 10 + n))])
+        }
+    }
+
+    #[tokio::test]
+    async fn live_pin_answers_repeat_queries_without_re_resolving() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("audit.jsonl");
+        let pins = Arc::new(
+            DnsPinStore::new("vm-a", Duration::seconds(DEFAULT_PIN_TTL_SECS)).with_audit_log(&log),
+        );
+        let endpoint = GuestDnsEndpoint::new(
+            DestinationPolicy::new([("cdn.example.com", 443)]),
+            Arc::new(RotatingResolver(Default::default())),
+            pins.clone(),
+            Arc::new(CapturingEgressAuditSink::new()),
+        );
+        let first_ip = IpAddr::V4(Ipv4Addr::new(93, 184, 216, 10));
+
+        for _ in 0..3 {
+            let response = endpoint
+                .handle_packet(&query("cdn.example.com.", RecordType::A))
+                .await
+                .unwrap();
+            let message = decode(&response);
+            assert_eq!(message.answers.len(), 1);
+            assert_eq!(
+                message.answers[0].data,
+                RData::A(A(Ipv4Addr::new(93, 184, 216, 10)))
+            );
+            assert!(message.answers[0].ttl <= (DEFAULT_PIN_TTL_SECS / 2) as u32);
+        }
+
+        // A flow opened against the first answer still matches.
+        assert_eq!(pins.check("cdn.example.com", first_ip), PinCheck::Matches);
+        assert_eq!(pins.live("cdn.example.com").unwrap().ips, vec![first_ip]);
+        let events = audit_events(&log);
+        assert_eq!(events.len(), 1, "one DnsPinSet, not one per query");
+        assert_eq!(events[0].kind, LocalAuditKind::DnsPinSet);
+    }
+
+    #[test]
+    fn pin_keeps_the_live_pin_and_answer_ttl_is_capped_by_it() {
+        let pins = DnsPinStore::new("vm-a", Duration::seconds(4));
+        let first = pins.pin("api.example.com", vec![PUBLIC_V4]);
+        let second = pins.pin("API.example.com.", vec![PUBLIC_V6]);
+        assert_eq!(second.ips, first.ips);
+        assert_eq!(
+            pins.check("api.example.com", PUBLIC_V6),
+            PinCheck::Diverged {
+                pinned: vec![PUBLIC_V4]
+            }
+        );
+        // answer_ttl_secs is 2; the pin has at most 4s left.
+        assert_eq!(pins.answer_ttl_for(&first), 2);
+
+        let expiring = DnsPinStore::new("vm-a", Duration::seconds(600));
+        let nearly_gone = DnsPin::new("api.example.com", vec![PUBLIC_V4], Duration::seconds(1));
+        assert_eq!(expiring.answer_ttl_for(&nearly_gone), 1);
+    }
+
+    #[tokio::test]
+    async fn aaaa_query_answers_only_ipv6() {
+        let f = fixture(&[("api.example.com", 0)], vec![PUBLIC_V4, PUBLIC_V6]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("api.example.com.", RecordType::AAAA))
+            .await
+            .unwrap();
+        let message = decode(&response);
+        assert_eq!(message.answers.len(), 1);
+        assert!(matches!(message.answers[0].data, RData::AAAA(_)));
+    }
+
+    #[tokio::test]
+    async fn name_outside_policy_is_nxdomain_with_audit_and_no_pin() {
+        let f = fixture(&[("api.example.com", 443)], vec![PUBLIC_V4]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("evil.example.net.", RecordType::A))
+            .await
+            .unwrap();
+        let message = decode(&response);
+        assert_eq!(message.metadata.response_code, ResponseCode::NXDomain);
+        assert!(message.answers.is_empty());
+
+        let entries = f.audit.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, EgressOutcome::Deny);
+        assert_eq!(entries[0].deciding_inspector, "dns_endpoint");
+        assert_eq!(entries[0].host, "evil.example.net");
+        assert!(f.endpoint.pins().snapshot().is_empty());
+        assert!(audit_events(&f.log).is_empty());
+    }
+
+    #[tokio::test]
+    async fn internal_addresses_are_stripped_before_pinning() {
+        let f = fixture(&[("api.example.com", 443)], vec![IMDS, PUBLIC_V4]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("api.example.com.", RecordType::A))
+            .await
+            .unwrap();
+        let message = decode(&response);
+        assert_eq!(message.answers.len(), 1);
+        assert_eq!(
+            message.answers[0].data,
+            RData::A(A(Ipv4Addr::new(93, 184, 216, 34)))
+        );
+        assert_eq!(
+            f.endpoint.pins().check("api.example.com", IMDS),
+            PinCheck::Diverged {
+                pinned: vec![PUBLIC_V4]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn name_resolving_only_internally_is_nxdomain_via_ssrf_guard() {
+        let f = fixture(&[("rebind.example.com", 443)], vec![IMDS]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("rebind.example.com.", RecordType::A))
+            .await
+            .unwrap();
+        assert_eq!(
+            decode(&response).metadata.response_code,
+            ResponseCode::NXDomain
+        );
+        let entries = f.audit.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].deciding_inspector, "ssrf_guard");
+        assert_eq!(entries[0].resolved_ip, Some(IMDS));
+        assert!(f.endpoint.pins().snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn upstream_failure_is_servfail() {
+        let f = fixture(&[("api.example.com", 443)], vec![]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("api.example.com.", RecordType::A))
+            .await
+            .unwrap();
+        assert_eq!(
+            decode(&response).metadata.response_code,
+            ResponseCode::ServFail
+        );
+    }
+
+    #[tokio::test]
+    async fn other_record_types_get_no_data() {
+        let f = fixture(&[("api.example.com", 443)], vec![PUBLIC_V4]);
+        let response = f
+            .endpoint
+            .handle_packet(&query("api.example.com.", RecordType::TXT))
+            .await
+            .unwrap();
+        let message = decode(&response);
+        assert_eq!(message.metadata.response_code, ResponseCode::NoError);
+        assert!(message.answers.is_empty());
+        assert!(f.endpoint.pins().snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn garbage_is_dropped() {
+        let f = fixture(&[("api.example.com", 443)], vec![PUBLIC_V4]);
+        assert!(f.endpoint.handle_packet(&[0x01, 0x02]).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn unrestricted_network_policy_answers_any_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let pins = Arc::new(
+            DnsPinStore::new("vm-a", Duration::seconds(60))
+                .with_audit_log(dir.path().join("audit.jsonl")),
+        );
+        let endpoint = GuestDnsEndpoint::from_network_policy(
+            &NetworkPolicy::unrestricted(),
+            Arc::new(FixedResolver(vec![PUBLIC_V4])),
+            pins,
+            Arc::new(CapturingEgressAuditSink::new()),
+        );
+        let response = endpoint
+            .handle_packet(&query("anything.example.org.", RecordType::A))
+            .await
+            .unwrap();
+        assert_eq!(decode(&response).answers.len(), 1);
+
+        let restricted = GuestDnsEndpoint::from_network_policy(
+            &NetworkPolicy::allow_list(vec![HostPort::new("api.example.com", 443)]),
+            Arc::new(FixedResolver(vec![PUBLIC_V4])),
+            endpoint.pins().clone(),
+            Arc::new(CapturingEgressAuditSink::new()),
+        );
+        let response = restricted
+            .handle_packet(&query("anything.example.org.", RecordType::A))
+            .await
+            .unwrap();
+        assert_eq!(
+            decode(&response).metadata.response_code,
+            ResponseCode::NXDomain
+        );
+    }
+
+    #[test]
+    fn pin_store_check_reject_and_reverse_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("audit.jsonl");
+        let store = DnsPinStore::new("vm-a", Duration::seconds(60)).with_audit_log(&log);
+        assert_eq!(
+            store.check("api.example.com", PUBLIC_V4),
+            PinCheck::Unpinned
+        );
+
+        store.record("api.example.com.", vec![PUBLIC_V4]);
+        assert_eq!(store.check("API.example.com", PUBLIC_V4), PinCheck::Matches);
+        assert_eq!(
+            store.hosts_for(PUBLIC_V4),
+            vec!["api.example.com".to_string()]
+        );
+        assert!(store.hosts_for(PUBLIC_V6).is_empty());
+
+        store.reject("api.example.com", &[PUBLIC_V4], IMDS);
+        let events = audit_events(&log);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].kind, LocalAuditKind::DnsPinReject);
+        assert_eq!(
+            events[1].detail.as_deref(),
+            Some(
+                format!("dest=api.example.com,pinned_ips={PUBLIC_V4},observed_ip={IMDS}").as_str()
+            )
+        );
+    }
+
+    #[test]
+    fn expired_pins_read_as_unpinned() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DnsPinStore::new("vm-a", Duration::seconds(-1))
+            .with_audit_log(dir.path().join("audit.jsonl"));
+        store.record("api.example.com", vec![PUBLIC_V4]);
+        assert_eq!(store.check("api.example.com", IMDS), PinCheck::Unpinned);
+        assert!(store.hosts_for(PUBLIC_V4).is_empty());
+        assert!(store.snapshot().is_empty());
+        assert_eq!(store.answer_ttl_secs(), 1);
+    }
+}
@@ -3,8 +3,8 @@
 //! `(proto, dst_cidr, dst_port_range)` allow-list evaluated against
 //! `(proto, dst_ip, dst_port)` at flow-establishment time. Default-
 //! deny — an empty rule set refuses every outbound flow. Each
-//! evaluation produces an [`L4Decision`] that the eventual TUN /
-//! smoltcp consumer feeds into its `accept` / `drop + audit`
+//! evaluation produces an [`L4Decision`] that the frame datapath
+//! ([`super::datapath`]) feeds into its forward / drop + audit
 //! branches.
 //!
 //! ## Scope of this module
@@ -21,25 +21,29 @@
 //!
 //! ## What this module does NOT do
 //!
-//! - **No TUN device management.** The Linux TUN + smoltcp
-//!   integration that turns an `L4Policy::evaluate` decision into
-//!   accept/drop on a per-VM TAP lives in the per-tenant
-//!   network-namespace work (Phase 3 Slice C / mvm-hostd lift).
-//! - **No audit emission.** The consumer wires
+//! - **No packet handling.** Turning a decision into forward / drop
+//!   on the guest's frames is [`super::datapath`]'s job.
+//! - **No flow audit emission.** The consumer wires
 //!   `EgressAuditSink::record` with the flow tuple + decision.
 //!   This module returns the decision; the *what to do with it*
-//!   is the consumer's concern.
+//!   is the consumer's concern. The one exception is
+//!   [`PinnedL4Gate`], which emits `DnsPinReject` itself via the
+//!   pin store.
 //! - **No firewall rules.** Linux nftables / macOS pf / Windows
 //!   WFP rules are Slice C — the firewall is additive enforcement
 //!   beneath the proxy, not the proxy itself.
 
 use std::net::IpAddr;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::dns::{DnsPinStore, PinCheck};
+use crate::destination::DestinationPolicy;
+
 /// L4 protocols the egress policy understands today.
 ///
 /// `Other(u8)` is the wire-format escape hatch for IP protocols the
@@ -170,8 +174,8 @@ impl L4Policy {
 // Symmetric with `EgressProxy` (L7) / `ToolGate` / `KeystoreReleaser` /
 // `ArtifactCollector`: a `Box<dyn L4Gate>` slot the supervisor consults
 // at admission. Slice B wires `LiveL4Gate { policy: L4Policy }` from
-// a parsed bundle's `[[network.l4]]` rows; the smoltcp / TUN consumer
-// that turns an `Allow` into accept-the-connection ships with Slice C.
+// a parsed bundle's `[[network.l4]]` rows; `proxy::datapath` is the
+// consumer that turns an `Allow` into forwarded frames.
 // ──────────────────────────────────────────────────────────────────────
 
 /// Errors `L4Gate::evaluate` can return. `NotWired` is the
@@ -216,6 +220,26 @@ impl L4Gate for NoopL4Gate {
     }
 }
 
+/// Allows every flow. Installed by
+/// [`super::datapath::L4Datapath::from_bundle`] when a bundle restricts
+/// `[egress]` names but has no `[[network.l4]]` rows: the datapath is
+/// engaged for the guest DNS endpoint, and an empty L4 rule set means
+/// "no L4 restriction", not deny-all.
+#[derive(Debug, Default)]
+pub struct PassThroughL4Gate;
+
+#[async_trait]
+impl L4Gate for PassThroughL4Gate {
+    async fn evaluate(
+        &self,
+        _proto: Protocol,
+        _ip: IpAddr,
+        _port: u16,
+    ) -> Result<L4Decision, L4Error> {
+        Ok(L4Decision::Allow)
+    }
+}
+
 /// Live impl backed by a concrete `L4Policy`. Plan 60 Phase 3 Slice B
 /// constructs this from a parsed policy bundle's `[[network.l4]]`
 /// rows via [`LiveL4Gate::from_specs`].
@@ -288,6 +312,75 @@ impl L4Gate for LiveL4Gate {
     }
 }
 
+/// Name-aware wrapper around another [`L4Gate`], backed by the VM's
+/// [`DnsPinStore`]. A flow the inner CIDR policy denies is still
+/// allowed when its IP is pinned for a name the destination allow-list
+/// permits on that port — that's what lets a host-based allow-list
+/// cover non-HTTP traffic. When the flow's name is known (TLS SNI,
+/// CONNECT host), [`Self::evaluate_named`] additionally refuses an IP
+/// that diverges from the live pin and emits `DnsPinReject`.
+pub struct PinnedL4Gate {
+    inner: Box<dyn L4Gate>,
+    destinations: DestinationPolicy,
+    pins: Arc<DnsPinStore>,
+}
+
+impl PinnedL4Gate {
+    pub fn new(
+        inner: Box<dyn L4Gate>,
+        destinations: DestinationPolicy,
+        pins: Arc<DnsPinStore>,
+    ) -> Self {
+        Self {
+            inner,
+            destinations,
+            pins,
+        }
+    }
+
+    /// [`L4Gate::evaluate`] for a flow whose hostname is known.
+    pub async fn evaluate_named(
+        &self,
+        proto: Protocol,
+        ip: IpAddr,
+        port: u16,
+        host: &str,
+    ) -> Result<L4Decision, L4Error> {
+        if let PinCheck::Diverged { pinned } = self.pins.check(host, ip) {
+            self.pins.reject(host, &pinned, ip);
+            return Ok(L4Decision::Deny {
+                reason: format!("{host} pinned to a different address set than {ip} (DNS pin)"),
+            });
+        }
+        self.evaluate(proto, ip, port).await
+    }
+}
+
+#[async_trait]
+impl L4Gate for PinnedL4Gate {
+    async fn evaluate(
+        &self,
+        proto: Protocol,
+        ip: IpAddr,
+        port: u16,
+    ) -> Result<L4Decision, L4Error> {
+        let decision = self.inner.evaluate(proto, ip, port).await?;
+        if decision == L4Decision::Allow || matches!(proto, Protocol::Other(_)) {
+            return Ok(decision);
+        }
+        let pinned_allow = self
+            .pins
+            .hosts_for(ip)
+            .iter()
+            .any(|host| self.destinations.matches(host, port));
+        Ok(if pinned_allow {
+            L4Decision::Allow
+        } else {
+            decision
+        })
+    }
+}
+
 /// Translation errors from [`LiveL4Gate::from_specs`]. Each variant
 /// names the bundle row index so operators can fix the offending
 /// `[[network.l4]]` entry by `index = N` (zero-based).
@@ -545,6 +638,18 @@ mod tests {
         assert_eq!(err, L4Error::NotWired);
     }
 
+    #[test]
+    fn pass_through_l4_gate_allows_every_flow() {
+        let g = PassThroughL4Gate;
+        for (proto, ip, port) in [
+            (Protocol::Tcp, ip4("8.8.8.8"), 443),
+            (Protocol::Udp, ip6("2001:db8::1"), 5353),
+        ] {
+            let d = block_on(g.evaluate(proto, ip, port)).expect("pass-through ok");
+            assert_eq!(d, L4Decision::Allow);
+        }
+    }
+
     #[test]
     fn live_l4_gate_allows_matching_flow() {
         let policy = L4Policy::new([L4Rule::single_port(Protocol::Tcp, v4("10.0.0.0/24"), 443)]);
@@ -650,4 +755,64 @@ mod tests {
         let d = block_on(g.evaluate(Protocol::Tcp, ip4("1.1.1.1"), 443)).unwrap();
         assert!(matches!(d, L4Decision::Deny { .. }));
     }
+
+    fn pinned_gate(dir: &std::path::Path) -> (PinnedL4Gate, Arc<DnsPinStore>) {
+        let pins = Arc::new(
+            DnsPinStore::new("vm-a", chrono::Duration::seconds(60))
+                .with_audit_log(dir.join("audit.jsonl")),
+        );
+        let inner = LiveL4Gate::new(L4Policy::new([L4Rule::single_port(
+            Protocol::Udp,
+            v4("8.8.8.8/32"),
+            53,
+        )]));
+        let gate = PinnedL4Gate::new(
+            Box::new(inner),
+            DestinationPolicy::new([("db.example.com", 5432u16)]),
+            pins.clone(),
+        );
+        (gate, pins)
+    }
+
+    #[test]
+    fn pinned_gate_allows_pinned_ip_for_an_allowed_host_port() {
+        let dir = tempfile::tempdir().unwrap();
+        let (gate, pins) = pinned_gate(dir.path());
+        let db = ip4("203.0.113.7");
+        // Unpinned: the CIDR policy alone decides.
+        let d = block_on(gate.evaluate(Protocol::Tcp, db, 5432)).unwrap();
+        assert!(matches!(d, L4Decision::Deny { .. }));
+
+        pins.record("db.example.com", vec![db]);
+        let d = block_on(gate.evaluate(Protocol::Tcp, db, 5432)).unwrap();
+        assert_eq!(d, L4Decision::Allow);
+        // Wrong port for the host stays denied.
+        let d = block_on(gate.evaluate(Protocol::Tcp, db, 22)).unwrap();
+        assert!(matches!(d, L4Decision::Deny { .. }));
+        // The inner CIDR allow still applies.
+        let d = block_on(gate.evaluate(Protocol::Udp, ip4("8.8.8.8"), 53)).unwrap();
+        assert_eq!(d, L4Decision::Allow);
+    }
+
+    #[test]
+    fn pinned_gate_rejects_divergent_named_flow_with_dns_pin_reject() {
+        let dir = tempfile::tempdir().unwrap();
+        let (gate, pins) = pinned_gate(dir.path());
+        pins.record("db.example.com", vec![ip4("203.0.113.7")]);
+        let d = block_on(gate.evaluate_named(
+            Protocol::Tcp,
+            ip4("198.51.100.9"),
+            5432,
+            "db.example.com",
+        ))
+        .unwrap();
+        match d {
+            L4Decision::Deny { reason } => assert!(reason.contains("DNS pin")),
+            other => panic!("expected Deny, got {other:?}"),
+        }
+        let log = std::fs::read_to_string(dir.path().join("audit.jsonl")).unwrap();
+        let last = log.lines().last().unwrap();
+        assert!(last.contains("dns_pin_reject"), "{last}");
+        assert!(last.contains("observed_ip=198.51.100.9"), "{last}");
+    }
 }
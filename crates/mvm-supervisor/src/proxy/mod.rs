@@ -3,13 +3,17 @@
 //! Two layers ship as part of Phase 3:
 //!
 //! - **L4** ([`l4`]) — `(proto, dst_cidr, dst_port_range)` rules
-//!   evaluated against the destination IP + port. Pure-policy
-//!   substrate today; the TUN/smoltcp userspace-TCP termination
-//!   that consumes this policy ships with the per-tenant network-
-//!   namespace work (Phase 3 Slice C / mvm-hostd lift).
+//!   evaluated against the destination IP + port. Enforced on the
+//!   guest's virtio-net frames by the [`datapath`] the gateway
+//!   bridge splices in front of passt / gvproxy.
 //! - **L7** (see [`crate::l7_proxy`]) — HTTPS CONNECT + plain-HTTP
 //!   inspection chain. Already live; Slice A flipped the W5
 //!   resolver to construct `L7EgressProxy` from a parsed policy
 //!   bundle.
+//! - **DNS** ([`dns`]) — the guest-facing resolver endpoint. Answers
+//!   only allow-listed names and pins every answer for the bridge
+//!   datapath's [`l4::PinnedL4Gate`].
 
+pub mod datapath;
+pub mod dns;
 pub mod l4;
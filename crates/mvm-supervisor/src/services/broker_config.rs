@@ -0,0 +1,160 @@
+//! Startup config for a VM's `mvm-broker` subprocess.
+//!
+//! The broker stamps every call with `ServiceCallCtx.workload_id =
+//! SubprocessConfig.workload_id`, and `host.cost.v1` looks usage up by
+//! `(tenant_id, workload_id)` in the samples a [`MeteringLog`] holds.
+//! Both ids come from the VM's [`InstanceLabels`] here, so the
+//! broker's readout keys match the sampler's `instance_id` /
+//! `tenant_id` by construction.
+//!
+//! Not wired yet: no supervisor path spawns the broker, so these
+//! builders are only exercised by tests until that spawn site and the
+//! sampling loop feeding [`crate::instance_sampler::meter_once`] land.
+
+use std::path::PathBuf;
+
+use mvm_broker::config::{self, SubprocessConfig};
+use mvm_core::observability::instance_metrics::InstanceLabels;
+
+use super::spawn::SpawnRequest;
+use crate::instance_sampler::MeteringLog;
+
+/// Per-VM inputs to the broker's [`SubprocessConfig`] that aren't
+/// derived from the instance itself.
+#[derive(Debug, Clone)]
+pub struct BrokerPaths {
+    /// Per-VM UDS the broker binds.
+    pub uds_path: PathBuf,
+    /// Host signer's public key.
+    pub host_signer_public_key_path: PathBuf,
+    /// Audit-signer UDS; `None` leaves `host.audit.v1` unregistered.
+    pub audit_signer_uds_path: Option<PathBuf>,
+}
+
+/// The broker config for the VM `labels` names, reading usage from
+/// `metering`.
+pub fn broker_config(
+    labels: &InstanceLabels,
+    paths: BrokerPaths,
+    metering: &MeteringLog,
+) -> SubprocessConfig {
+    SubprocessConfig {
+        workload_id: labels.instance_id.clone(),
+        tenant_id: labels.tenant.clone(),
+        uds_path: paths.uds_path,
+        host_signer_public_key_path: paths.host_signer_public_key_path,
+        audit_signer_uds_path: paths.audit_signer_uds_path,
+        metering_samples_path: Some(metering.path().to_path_buf()),
+        max_frame_bytes: config::default_max_frame_bytes(),
+        parse_timeout: config::default_parse_timeout_ms(),
+    }
+}
+
+/// [`SpawnRequest`] for `binary` with `config` serialised as its stdin
+/// config.
+pub fn broker_spawn_request(
+    binary: impl Into<PathBuf>,
+    config: &SubprocessConfig,
+) -> Result<SpawnRequest, serde_json::Error> {
+    Ok(SpawnRequest::new(
+        binary,
+        config.uds_path.clone(),
+        serde_json::to_vec(config)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance_sampler::{Sample, SampleTarget, Sources, meter_once};
+    use mvm_broker::handlers::host_cost_v1::HostCostV1Handler;
+    use mvm_core::observability::instance_metrics::InstanceMetricsRegistry;
+    use mvm_core::policy::security::AgentProfile;
+    use mvm_core::protocol::broker::CorrelationId;
+    use mvm_core::protocol::handler::{ServiceCallCtx, ServiceHandler};
+    use mvm_core::protocol::host_cost::WorkloadCostResponse;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    struct ProcOnly(Mutex<u64>);
+
+    impl Sources for ProcOnly {
+        fn read_proc_stat(&self, _pid: u32) -> Sample {
+            Sample {
+                cpu_user_us: Some(*self.0.lock().unwrap()),
+                cpu_system_us: Some(0),
+                mem_resident_bytes: Some(4096),
+                ..Sample::default()
+            }
+        }
+        fn read_tap_stats(&self, _iface: &str) -> Sample {
+            Sample::default()
+        }
+        fn read_disk_stats(&self, _path: &Path) -> Sample {
+            Sample::default()
+        }
+        fn read_nft_counters(&self, _table: &str) -> Sample {
+            Sample::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn broker_readout_is_keyed_by_the_sampled_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let labels = InstanceLabels {
+            instance_id: "vm-7".to_string(),
+            tenant: "acme".to_string(),
+            template: "python-3.12".to_string(),
+        };
+        let registry = InstanceMetricsRegistry::new();
+        registry.register(labels.clone());
+        let target = SampleTarget {
+            vmm_pid: Some(1),
+            ..SampleTarget::new(labels.clone(), 0)
+        };
+        assert_eq!(target.nft_table.as_deref(), Some("mvm_policy_vm-7"));
+        let log = MeteringLog::new(dir.path().join("vm-7-metering.jsonl"));
+        let sources = ProcOnly(Mutex::new(100));
+        let first = meter_once(&registry, &sources, &target, &log)
+            .unwrap()
+            .unwrap();
+        *sources.0.lock().unwrap() = 250;
+        let second = meter_once(&registry, &sources, &target, &log)
+            .unwrap()
+            .unwrap();
+
+        let paths = BrokerPaths {
+            uds_path: dir.path().join("broker.sock"),
+            host_signer_public_key_path: dir.path().join("host-signer.pub"),
+            audit_signer_uds_path: None,
+        };
+        let request =
+            broker_spawn_request("mvm-broker", &broker_config(&labels, paths, &log)).unwrap();
+        let config = mvm_broker::config::parse(&request.config_json).unwrap();
+        assert_eq!(config.metering_samples_path.as_deref(), Some(log.path()));
+        assert_eq!(request.uds_path, config.uds_path);
+
+        // What the broker's server stamps on each call.
+        let ctx = ServiceCallCtx {
+            workload_id: config.workload_id.clone(),
+            tenant_id: config.tenant_id.clone(),
+            correlation_id: CorrelationId::new("01HCORR0000000000000000"),
+            session_id: "sess-1".into(),
+            profile: AgentProfile::Dev,
+            composition_depth: 0,
+            composition_width: 0,
+        };
+        assert_eq!(ctx.workload_id, first.instance_id);
+        assert_eq!(ctx.tenant_id, first.tenant_id);
+
+        let handler = HostCostV1Handler::new(config.metering_samples_path.unwrap());
+        let value = handler
+            .dispatch(&ctx, "workload", serde_json::json!({}))
+            .await
+            .unwrap();
+        let resp: WorkloadCostResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(resp.sample_count, 2);
+        assert_eq!(resp.totals.cpu_ns, first.cpu_ns + second.cpu_ns);
+        assert_eq!(resp.totals.cpu_ns, 250_000);
+    }
+}
@@ -45,6 +45,9 @@ pub mod audit_signer_proxy;
 // fexecve is the deferred follow-on; the seam is documented at
 // the spawn call site.
 pub mod binary_integrity;
+// Startup `SubprocessConfig` for a VM's `mvm-broker`, carrying the
+// sampler's metering log path so `host.cost.v1` registers.
+pub mod broker_config;
 pub mod broker_proxy;
 // Plan 104 W1b.2b.3 — supervisor-side config envelope signer
 // (§H-L3.6 / G1). Wraps SubprocessConfig bytes before stdin write.
@@ -12,10 +12,15 @@
 //! - Carrier-grade NAT (100.64.0.0/10) — frequently used as private
 //!   transit by cloud providers
 //! - Multicast / broadcast / unspecified / documentation
-//! - IPv6 unique-local (fc00::/7)
+//! - IPv6 unique-local (fc00::/7) and deprecated site-local (fec0::/10)
+//! - IPv6 forms that embed an IPv4 address — IPv4-mapped
+//!   (::ffff:0:0/96), NAT64 (64:ff9b::/96) and 6to4 (2002::/16) —
+//!   classified by the embedded IPv4, so `::ffff:169.254.169.254`
+//!   gets the same verdict as `169.254.169.254`
 //! - **Cloud metadata services** (169.254.169.254 — AWS / Azure /
-//!   GCP / Oracle; 100.100.100.200 — Alibaba). These are link-local
-//!   and CGNAT respectively, but they earn their own deny reasons
+//!   GCP / Oracle; fd00:ec2::254 — AWS over IPv6; 100.100.100.200 —
+//!   Alibaba). These are link-local, unique-local and CGNAT
+//!   respectively, but they earn their own deny reasons
 //!   because the audit signal "your workload tried to reach IMDS"
 //!   is much louder than "your workload tried to reach a link-local
 //!   address."
@@ -120,6 +125,11 @@ fn classify_v4(ip: Ipv4Addr) -> Option<&'static str> {
 }
 
 fn classify_v6(ip: Ipv6Addr) -> Option<&'static str> {
+    // Same ordering rule as IPv4: the metadata endpoint's reason
+    // wins over the unique-local range it sits in.
+    if ip == Ipv6Addr::new(0xfd00, 0x0ec2, 0, 0, 0, 0, 0, 0x0254) {
+        return Some("cloud metadata service (fd00:ec2::254 — AWS IMDS over IPv6)");
+    }
     if ip.is_loopback() {
         return Some("IPv6 loopback (::1)");
     }
@@ -135,6 +145,16 @@ fn classify_v6(ip: Ipv6Addr) -> Option<&'static str> {
     if let Some(v4) = ip.to_ipv4_mapped() {
         return classify_v4(v4);
     }
+    // NAT64 well-known prefix (64:ff9b::/96, RFC6052) — a NAT64
+    // gateway translates to the embedded IPv4 in the low 32 bits.
+    if segs[..6] == [0x0064, 0xff9b, 0, 0, 0, 0] {
+        return classify_v4(low_32_as_v4(&segs[6..]));
+    }
+    // 6to4 (2002::/16, RFC3056) — the relay tunnels to the IPv4 in
+    // bits 16..48.
+    if segs[0] == 0x2002 {
+        return classify_v4(low_32_as_v4(&segs[1..3]));
+    }
     // Unique-local fc00::/7
     if (segs[0] & 0xfe00) == 0xfc00 {
         return Some("IPv6 unique-local (fc00::/7)");
@@ -143,6 +163,11 @@ fn classify_v6(ip: Ipv6Addr) -> Option<&'static str> {
     if (segs[0] & 0xffc0) == 0xfe80 {
         return Some("IPv6 link-local (fe80::/10)");
     }
+    // Site-local fec0::/10 — deprecated (RFC3879) but still routed
+    // as private by some stacks.
+    if (segs[0] & 0xffc0) == 0xfec0 {
+        return Some("IPv6 site-local (fec0::/10)");
+    }
     // Documentation 2001:db8::/32
     if segs[0] == 0x2001 && segs[1] == 0x0db8 {
         return Some("IPv6 documentation range (2001:db8::/32)");
@@ -150,6 +175,13 @@ fn classify_v6(ip: Ipv6Addr) -> Option<&'static str> {
     None
 }
 
+/// Rebuild an IPv4 address from two IPv6 segments.
+fn low_32_as_v4(segs: &[u16]) -> Ipv4Addr {
+    let [a, b] = segs[0].to_be_bytes();
+    let [c, d] = segs[1].to_be_bytes();
+    Ipv4Addr::new(a, b, c, d)
+}
+
 #[async_trait]
 impl Inspector for SsrfGuard {
     fn name(&self) -> &'static str {
@@ -362,6 +394,72 @@ mod tests {
         assert!(v.is_deny(), "expected deny, got {v:?}");
     }
 
+    #[tokio::test]
+    async fn v6_v4mapped_imds_denies_with_metadata_reason() {
+        let v = SsrfGuard::new()
+            .inspect(&mut ctx_with_host("::ffff:169.254.169.254"))
+            .await;
+        match v {
+            InspectorVerdict::Deny { reason } => assert!(reason.contains("metadata"), "{reason}"),
+            other => panic!("expected deny, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn v6_aws_imds_denies_with_specific_reason() {
+        // fd00:ec2::254 is unique-local; the metadata reason must win.
+        let v = SsrfGuard::new()
+            .inspect(&mut ctx_with_host("fd00:ec2::254"))
+            .await;
+        match v {
+            InspectorVerdict::Deny { reason } => {
+                assert!(reason.contains("metadata"), "{reason}");
+                assert!(reason.contains("fd00:ec2::254"));
+            }
+            other => panic!("expected deny, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_v6_embedded_v4_uses_the_embedded_address() {
+        // NAT64 and 6to4 around an internal IPv4 deny; around a
+        // public IPv4 they're as routable as the address they carry.
+        for denied in ["64:ff9b::a00:1", "64:ff9b::7f00:1", "2002:a9fe:a9fe::1"] {
+            let ip: IpAddr = denied.parse().unwrap();
+            assert!(SsrfGuard::classify(ip).is_some(), "{denied} must deny");
+        }
+        for allowed in ["64:ff9b::808:808", "2002:808:808::1"] {
+            let ip: IpAddr = allowed.parse().unwrap();
+            assert!(SsrfGuard::classify(ip).is_none(), "{allowed} must allow");
+        }
+        assert_eq!(
+            SsrfGuard::classify("2002:a9fe:a9fe::1".parse().unwrap()),
+            SsrfGuard::classify("169.254.169.254".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn classify_v6_site_local_denies() {
+        assert!(SsrfGuard::classify("fec0::1".parse().unwrap()).is_some());
+    }
+
+    /// Every IPv6 entry in the mandatory-deny set must also be
+    /// refused here, so the L7 path never admits what the guest's
+    /// blackhole routes drop.
+    #[test]
+    fn classify_denies_every_ipv6_mandatory_deny_range() {
+        for net in mvm_core::network_policy::mandatory_deny_ranges() {
+            if let ipnet::IpNet::V6(v6) = net {
+                for ip in [v6.network(), v6.broadcast()] {
+                    assert!(
+                        SsrfGuard::classify(IpAddr::V6(ip)).is_some(),
+                        "{ip} (from {net}) must be denied"
+                    );
+                }
+            }
+        }
+    }
+
     #[tokio::test]
     async fn v6_public_allows() {
         // Google's public DNS over IPv6.
@@ -35,6 +35,7 @@ use crate::backend::{BackendError, BackendLauncher, NoopBackendLauncher};
 use crate::circuit_breaker::{CircuitBreaker, InspectorReporter};
 use crate::destination::DestinationPolicy;
 use crate::egress::{EgressProxy, NoopEgressProxy};
+use crate::egress_budget::EgressBudget;
 use crate::firewall::{FirewallEnforcer, FirewallError, FirewallSpec, NoopFirewallEnforcer};
 use crate::injection_guard::InjectionGuard;
 use crate::inspector::{Inspector, InspectorChain};
@@ -45,6 +46,7 @@ use crate::policy_tool_gate::PolicyToolGate;
 use crate::secrets_scanner::SecretsScanner;
 use crate::ssrf_guard::SsrfGuard;
 use crate::state::{PlanState, PlanStateMachine, StateTransitionError};
+use crate::tls_intercept::{DEFAULT_INTERCEPT_CA_TTL, InterceptCa, InterceptError, TlsInterceptor};
 use crate::tool_gate::{NoopToolGate, ToolGate};
 
 /// Clock abstraction. The supervisor reads the wall clock through
@@ -160,6 +162,13 @@ pub struct Supervisor {
     /// inspector built by [`Supervisor::with_l7_egress`] is wrapped
     /// in a [`CircuitBreaker`] that consults this reporter.
     pub circuit_breakers: Option<Arc<InspectorReporter>>,
+    /// Per-VM interception CA minted by [`Supervisor::with_l7_egress`]
+    /// when the egress policy sets `tls_intercept`. A launch path that
+    /// runs this supervisor's proxy for the VM stages
+    /// [`InterceptCa::cert_pem`] as [`crate::INTERCEPT_CA_DRIVE_FILE`];
+    /// nothing else may, since only this proxy holds the key. `None`
+    /// when interception is off.
+    pub intercept_ca: Option<Arc<InterceptCa>>,
 
     /// Root directory the deps-volume admission gate (Plan 73
     /// Followup A) walks to find `<volume_hash>/` directories.
@@ -195,6 +204,7 @@ impl Default for Supervisor {
             clock: Arc::new(SystemClock),
             nonce_store: Arc::new(Mutex::new(NonceStore::new())),
             circuit_breakers: None,
+            intercept_ca: None,
             deps_volumes_root: None,
             firewall_proxy_iface: None,
             installed_firewalls: BTreeMap::new(),
@@ -651,8 +661,8 @@ impl Supervisor {
     /// Wire the L7 egress proxy slot from a workload's
     /// [`EgressPolicy`] + variant. Wave 2.6 differentiator:
     ///
-    /// - Builds the inspector chain from `policy.allow_list` and the
-    ///   curated default rulesets, in Plan 37 §15's recommended order
+    /// - Builds the inspector chain from `policy.allow_list` /
+    ///   `allow_port_ranges` and the curated default rulesets, in Plan 37 §15's recommended order
     ///   (DestinationPolicy → SsrfGuard → SecretsScanner →
     ///   InjectionGuard → PiiRedactor).
     /// - **Refuses `Variant::Prod` ⊕ `policy.allow_plain_http = true`**
@@ -660,12 +670,24 @@ impl Supervisor {
     ///   secure default louder than a comment: a production policy
     ///   bundle that opts into plain HTTP fails policy load, not
     ///   silently accepts unencrypted egress.
+    /// - Refuses an allow-list entry that isn't a valid host pattern
+    ///   or port range with a [`SupervisorError::PolicyViolation`].
     /// - Honours `policy.disabled_inspectors` for opt-out by name.
     ///   Operators can disable a specific inspector (e.g.,
     ///   `pii_redactor` for an analytics workload) without rewriting
     ///   the chain order.
     /// - Honours `policy.body_cap_bytes` (defaults to
     ///   [`DEFAULT_BODY_CAP_BYTES`] when the field is 0).
+    /// - When `policy.tls_intercept` is set, mints a per-VM
+    ///   [`InterceptCa`] name-constrained to the allow-list hostnames
+    ///   (stored in [`Supervisor::intercept_ca`]) and switches the
+    ///   proxy into interception mode. An allow-list with no
+    ///   hostnames is a [`SupervisorError::PolicyViolation`] — the CA
+    ///   would have nothing to be constrained to.
+    /// - Enforces `policy.byte_cap_bytes` / `bandwidth_bytes_per_sec`
+    ///   through one [`EgressBudget`] shared by every tunnel of the
+    ///   VM. A zero bandwidth is a [`SupervisorError::PolicyViolation`]
+    ///   (it would stall all egress rather than deny it).
     pub fn with_l7_egress(
         mut self,
         policy: &EgressPolicy,
@@ -683,23 +705,8 @@ impl Supervisor {
         }
 
         let chain = build_inspector_chain(policy, self.circuit_breakers.clone());
-        let body_cap = if policy.body_cap_bytes == 0 {
-            DEFAULT_BODY_CAP_BYTES
-        } else {
-            policy.body_cap_bytes
-        };
-        // u64 → usize: clamp at usize::MAX on 32-bit platforms.
-        // 16 MiB fits in usize on all platforms we target, so this
-        // is purely defensive against a malicious policy bundle.
-        let body_cap = usize::try_from(body_cap).unwrap_or(usize::MAX);
-
-        let proxy = L7EgressProxy::new(
-            Arc::new(chain),
-            resolver,
-            audit_sink,
-            body_cap,
-            policy.allow_plain_http,
-        );
+        let (proxy, ca) = build_l7_egress(policy, chain, resolver, audit_sink)?;
+        self.intercept_ca = ca;
         self.egress = Arc::new(proxy);
         Ok(self)
     }
@@ -823,12 +830,12 @@ pub fn validate_egress_policy_inspector_names(
 }
 
 /// URL schemes accepted in [`mvm_policy::AuditPolicy::stream_destinations`].
-/// The supervisor's eventual audit-stream replicator (Plan 60 Phase 4
-/// follow-on after the mvm-hostd lift) will emit each entry to its
-/// matching backend; validating shape at admission means a typo
-/// (`fil:///var/log/...` vs `file:///var/log/...`) fails the boot
-/// loudly instead of silently dropping audit emissions.
-pub const KNOWN_AUDIT_STREAM_SCHEMES: &[&str] = &["file://", "unix://", "https://", "http://"];
+/// `file://` entries become exact-file replicas; the rest are shipped
+/// by [`crate::StreamAuditSigner`]. Validating shape at admission
+/// means a typo (`fil:///var/log/...` vs `file:///var/log/...`) fails
+/// the boot loudly instead of silently dropping audit emissions.
+pub const KNOWN_AUDIT_STREAM_SCHEMES: &[&str] =
+    &["file://", "unix://", "tcp+tls://", "https://", "http://"];
 
 /// Validation error for [`validate_audit_policy_stream_destinations`].
 ///
@@ -848,16 +855,22 @@ pub enum AuditPolicyValidationError {
         value: String,
         valid: &'static [&'static str],
     },
+    #[error("stream_destinations[{index}]: {source}")]
+    InvalidStreamDestination {
+        index: usize,
+        #[source]
+        source: crate::AuditStreamError,
+    },
 }
 
 /// Verify every entry in `policy.stream_destinations` starts with a
-/// known scheme prefix.
+/// known scheme prefix, and that every streaming entry parses as an
+/// [`crate::AuditStreamDestination`].
 ///
-/// The supervisor's eventual replicator (waiting on the mvm-hostd
-/// lift) is the consumer; this function is the *admission-time*
-/// shape gate so an operator who typo'd `htpps://` doesn't think
-/// they've configured TLS audit replication while the boot proceeds
-/// in silence. Plan 60 Phase 4 follow-on.
+/// This is the *admission-time* shape gate so an operator who typo'd
+/// `htpps://` — or pointed plaintext `http://` at a remote collector —
+/// doesn't think they've configured audit replication while the boot
+/// proceeds in silence. Plan 60 Phase 4 follow-on.
 pub fn validate_audit_policy_stream_destinations(
     policy: &mvm_policy::AuditPolicy,
 ) -> Result<(), AuditPolicyValidationError> {
@@ -872,10 +885,71 @@ pub fn validate_audit_policy_stream_destinations(
                 valid: KNOWN_AUDIT_STREAM_SCHEMES,
             });
         }
+        if !value.starts_with("file://") {
+            crate::AuditStreamDestination::parse(value).map_err(|source| {
+                AuditPolicyValidationError::InvalidStreamDestination { index, source }
+            })?;
+        }
     }
     Ok(())
 }
 
+/// Build the L7 egress proxy for `policy` around an already-built
+/// `chain`: body cap, byte / bandwidth budget and, when
+/// `policy.tls_intercept` is set, TLS interception under a fresh
+/// per-VM CA (returned alongside for the caller to hand out).
+///
+/// Shared by [`Supervisor::with_l7_egress`] and the plan-64 W5
+/// resolver in `mvm-cli::policy_resolver`, so a bundle's egress
+/// limits and interception switch mean the same thing on both
+/// paths. The caller applies any variant gate first.
+pub fn build_l7_egress(
+    policy: &EgressPolicy,
+    chain: InspectorChain,
+    resolver: Arc<dyn DnsResolver>,
+    audit_sink: Arc<dyn EgressAuditSink>,
+) -> Result<(L7EgressProxy, Option<Arc<InterceptCa>>), SupervisorError> {
+    policy
+        .check_limits()
+        .map_err(|e| SupervisorError::PolicyViolation(format!("EgressPolicy: {e}")))?;
+
+    let allow_rules = policy
+        .allow_rules()
+        .map_err(|e| SupervisorError::PolicyViolation(format!("EgressPolicy allow-list: {e}")))?;
+
+    let body_cap = if policy.body_cap_bytes == 0 {
+        DEFAULT_BODY_CAP_BYTES
+    } else {
+        policy.body_cap_bytes
+    };
+    // u64 → usize: clamp at usize::MAX on 32-bit platforms.
+    // 16 MiB fits in usize on all platforms we target, so this
+    // is purely defensive against a malicious policy bundle.
+    let body_cap = usize::try_from(body_cap).unwrap_or(usize::MAX);
+
+    let proxy = L7EgressProxy::new(
+        Arc::new(chain),
+        resolver,
+        audit_sink,
+        body_cap,
+        policy.allow_plain_http,
+    )
+    .with_budget(Arc::new(EgressBudget::new(
+        policy.byte_cap_bytes,
+        policy.bandwidth_bytes_per_sec,
+    )));
+    if !policy.tls_intercept {
+        return Ok((proxy, None));
+    }
+    let intercept_err = |e: InterceptError| {
+        SupervisorError::PolicyViolation(format!("EgressPolicy.tls_intercept: {e}"))
+    };
+    let ca =
+        Arc::new(InterceptCa::new(&allow_rules, DEFAULT_INTERCEPT_CA_TTL).map_err(intercept_err)?);
+    let interceptor = TlsInterceptor::new(Arc::clone(&ca)).map_err(intercept_err)?;
+    Ok((proxy.with_tls_interception(Arc::new(interceptor)), Some(ca)))
+}
+
 /// Build an [`InspectorChain`] from the workload's [`EgressPolicy`].
 /// Order matches Plan 37 §15: cheap/most-precise checks first, body
 /// inspectors last (so a destination-denied request never pays the
@@ -909,13 +983,9 @@ pub fn build_inspector_chain(
     };
     let mut chain = InspectorChain::new();
     if !disabled("destination_policy") {
-        let dp = DestinationPolicy::new(
-            policy
-                .allow_list
-                .iter()
-                .map(|(host, port)| (host.as_str(), *port)),
-        );
-        chain.push(wrap(Box::new(dp) as Box<dyn Inspector>));
+        chain.push(wrap(
+            Box::new(destination_policy(policy)) as Box<dyn Inspector>
+        ));
     }
     if !disabled("ssrf_guard") {
         chain.push(wrap(Box::new(SsrfGuard::new())));
@@ -932,6 +1002,17 @@ pub fn build_inspector_chain(
     chain
 }
 
+/// The chain's `DestinationPolicy`. `with_l7_egress` and the bundle
+/// loader refuse invalid allow-list entries before a chain is built;
+/// if one slips through anyway, deny everything rather than enforce
+/// a partial list.
+fn destination_policy(policy: &EgressPolicy) -> DestinationPolicy {
+    DestinationPolicy::from_egress_policy(policy).unwrap_or_else(|e| {
+        warn!(error = %e, "invalid egress allow-list; denying all destinations");
+        DestinationPolicy::deny_all()
+    })
+}
+
 /// Same as [`build_inspector_chain`] but the PII inspector is
 /// constructed from a parsed [`mvm_policy::PiiPolicy`] (mode +
 /// category filter) instead of hardwired to defaults. Used by the
@@ -963,13 +1044,9 @@ pub fn build_inspector_chain_with_pii(
     };
     let mut chain = InspectorChain::new();
     if !disabled("destination_policy") {
-        let dp = DestinationPolicy::new(
-            egress
-                .allow_list
-                .iter()
-                .map(|(host, port)| (host.as_str(), *port)),
-        );
-        chain.push(wrap(Box::new(dp) as Box<dyn Inspector>));
+        chain.push(wrap(
+            Box::new(destination_policy(egress)) as Box<dyn Inspector>
+        ));
     }
     if !disabled("ssrf_guard") {
         chain.push(wrap(Box::new(SsrfGuard::new())));
@@ -1777,9 +1854,13 @@ mod tests {
         EgressPolicy {
             mode: Some("l3_plus_l7".to_string()),
             allow_list: vec![("api.openai.com".to_string(), 443)],
+            allow_port_ranges: vec![],
             allow_plain_http,
             body_cap_bytes: 0,
             disabled_inspectors: vec![],
+            tls_intercept: false,
+            byte_cap_bytes: None,
+            bandwidth_bytes_per_sec: None,
         }
     }
 
@@ -1833,6 +1914,79 @@ mod tests {
         assert_eq!(s.state.current(), PlanState::Pending);
     }
 
+    #[test]
+    fn with_l7_egress_tls_intercept_mints_constrained_ca() {
+        let mut policy = dev_egress_policy(false);
+        policy.tls_intercept = true;
+        let s = Supervisor::default()
+            .with_l7_egress(
+                &policy,
+                Variant::Prod,
+                Arc::new(WiringResolver(IpAddr::from([8, 8, 8, 8]))),
+                Arc::new(NoopEgressAuditSink),
+            )
+            .expect("tls_intercept with a hostname allow-list wires");
+        let ca = s.intercept_ca.as_ref().expect("CA minted");
+        assert_eq!(ca.permitted_names(), ["api.openai.com"]);
+        assert!(ca.cert_pem().contains("BEGIN CERTIFICATE"));
+
+        assert!(Supervisor::default().intercept_ca.is_none());
+    }
+
+    #[test]
+    fn build_l7_egress_returns_the_ca_the_proxy_signs_with() {
+        let mut policy = dev_egress_policy(false);
+        policy.tls_intercept = true;
+        let (proxy, ca) = build_l7_egress(
+            &policy,
+            InspectorChain::new(),
+            Arc::new(WiringResolver(IpAddr::from([8, 8, 8, 8]))),
+            Arc::new(NoopEgressAuditSink),
+        )
+        .expect("tls_intercept with a hostname allow-list wires");
+        let ca = ca.expect("CA minted");
+        let signing = proxy.tls_interception().expect("interceptor").ca();
+        assert!(Arc::ptr_eq(signing, &ca));
+        assert_eq!(signing.cert_pem(), ca.cert_pem());
+    }
+
+    #[test]
+    fn with_l7_egress_zero_bandwidth_rejects() {
+        let mut policy = dev_egress_policy(false);
+        policy.bandwidth_bytes_per_sec = Some(0);
+        let result = Supervisor::default().with_l7_egress(
+            &policy,
+            Variant::Dev,
+            Arc::new(WiringResolver(IpAddr::from([8, 8, 8, 8]))),
+            Arc::new(NoopEgressAuditSink),
+        );
+        match result {
+            Err(SupervisorError::PolicyViolation(msg)) => {
+                assert!(msg.contains("bandwidth_bytes_per_sec"))
+            }
+            Err(other) => panic!("expected PolicyViolation, got error {other:?}"),
+            Ok(_) => panic!("expected PolicyViolation, got Ok"),
+        }
+    }
+
+    #[test]
+    fn with_l7_egress_tls_intercept_without_hostnames_rejects() {
+        let mut policy = dev_egress_policy(false);
+        policy.tls_intercept = true;
+        policy.allow_list = vec![("10.0.0.1".to_string(), 443)];
+        let result = Supervisor::default().with_l7_egress(
+            &policy,
+            Variant::Dev,
+            Arc::new(WiringResolver(IpAddr::from([8, 8, 8, 8]))),
+            Arc::new(NoopEgressAuditSink),
+        );
+        match result {
+            Err(SupervisorError::PolicyViolation(msg)) => assert!(msg.contains("tls_intercept")),
+            Err(other) => panic!("expected PolicyViolation, got error {other:?}"),
+            Ok(_) => panic!("expected PolicyViolation, got Ok"),
+        }
+    }
+
     #[tokio::test]
     async fn wired_supervisor_routes_through_inspector_chain() {
         // End-to-end: build a Supervisor with the L7 proxy wired,
@@ -2024,11 +2178,32 @@ mod tests {
                 "file:///var/log/mvm/audit.jsonl".to_string(),
                 "https://audit.example.com/ingest".to_string(),
                 "unix:///run/audit.sock".to_string(),
+                "tcp+tls://audit.example.com:6514".to_string(),
+                "http://127.0.0.1:9000/ingest".to_string(),
             ],
         };
         validate_audit_policy_stream_destinations(&policy).expect("known schemes ok");
     }
 
+    #[test]
+    fn validate_audit_policy_refuses_plaintext_remote_collector() {
+        let policy = mvm_policy::AuditPolicy {
+            chain_signing: true,
+            stream_destinations: vec!["http://audit.example.com/ingest".to_string()],
+        };
+        let err = validate_audit_policy_stream_destinations(&policy).expect_err("plaintext");
+        match err {
+            AuditPolicyValidationError::InvalidStreamDestination { index, source } => {
+                assert_eq!(index, 0);
+                assert!(matches!(
+                    source,
+                    crate::AuditStreamError::PlaintextNonLoopback(_)
+                ));
+            }
+            other => panic!("expected InvalidStreamDestination, got {other:?}"),
+        }
+    }
+
     #[test]
     fn validate_audit_policy_passes_empty_destinations() {
         // Empty stream_destinations is the common case — no audit
@@ -2058,6 +2233,7 @@ mod tests {
                 assert!(valid.contains(&"https://"));
                 assert!(valid.contains(&"file://"));
             }
+            other => panic!("expected UnknownStreamScheme, got {other:?}"),
         }
     }
 
@@ -2073,6 +2249,7 @@ mod tests {
                 assert_eq!(index, 0);
                 assert_eq!(value, "/var/log/audit.jsonl");
             }
+            other => panic!("expected UnknownStreamScheme, got {other:?}"),
         }
     }
 
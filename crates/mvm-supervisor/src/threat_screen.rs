@@ -0,0 +1,56 @@
+//! `[threat]` policy → [`ThreatGate`].
+//!
+//! The bundle's [`ThreatPolicy`] names an action per
+//! [`ThreatCategory`](mvm_core::security::ThreatCategory); the gate
+//! in `mvm-security` applies it. An unset policy yields a log-only
+//! gate, so wiring the gate never refuses a call the operator did
+//! not ask to refuse.
+
+use mvm_core::security::BlocklistAction;
+use mvm_policy::ThreatPolicy;
+use mvm_security::threat_gate::ThreatGate;
+
+/// Build the gate `policy` describes.
+pub fn threat_gate_from_policy(policy: &ThreatPolicy) -> ThreatGate {
+    ThreatGate::new(
+        policy
+            .default_action
+            .clone()
+            .unwrap_or(BlocklistAction::Log),
+        policy.categories.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mvm_core::security::ThreatCategory;
+
+    #[test]
+    fn unset_policy_builds_a_log_only_gate() {
+        let gate = threat_gate_from_policy(&ThreatPolicy::default());
+        assert_eq!(
+            gate.action_for(&ThreatCategory::Destructive),
+            BlocklistAction::Log
+        );
+    }
+
+    #[test]
+    fn categories_override_the_default_action() {
+        let policy = ThreatPolicy {
+            default_action: Some(BlocklistAction::RequireApproval),
+            categories: [(ThreatCategory::Destructive, BlocklistAction::Block)]
+                .into_iter()
+                .collect(),
+        };
+        let gate = threat_gate_from_policy(&policy);
+        assert_eq!(
+            gate.action_for(&ThreatCategory::Destructive),
+            BlocklistAction::Block
+        );
+        assert_eq!(
+            gate.action_for(&ThreatCategory::NetworkAbuse),
+            BlocklistAction::RequireApproval
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Per-VM interception CA for the TLS-intercepting egress proxy.
+//!
+//! Wave 2.6.5 (ADR-006). When a workload's `EgressPolicy` opts into
+//! `tls_intercept`, the supervisor mints one [`InterceptCa`] per VM.
+//! [`crate::L7EgressProxy`] then terminates each CONNECT tunnel with
+//! a leaf minted for the CONNECT host, runs the inspector chain on
+//! the decrypted request, and replays it to the pinned upstream over
+//! a fresh TLS session that is verified against the real roots.
+//!
+//! Interception needs the supervisor launch path: the launcher that
+//! runs this proxy for a VM writes the CA certificate to the VM's
+//! config drive as [`INTERCEPT_CA_DRIVE_FILE`], and mkGuest's `/init`
+//! copies it to [`GUEST_INTERCEPT_CA_PATH`] and appends it to the
+//! guest trust store. `mvmctl up` runs no supervisor proxy, so it
+//! stages no CA and its VMs are never intercepted.
+//!
+//! Blast radius (ADR-006 §1/§4):
+//! - The CA carries an X.509 `nameConstraints` extension whose
+//!   permitted DNS subtrees are exactly the hostnames in the
+//!   workload's allow-list, and excludes every IPv4/IPv6 address.
+//!   A guest that exfiltrates the cert cannot use it to vouch for
+//!   any other name.
+//! - `pathLenConstraint = 0`: the CA can only sign leaves.
+//! - The private key lives in supervisor memory only — it is never
+//!   written to disk and never enters the guest.
+//! - The CA and every leaf expire after the CA TTL
+//!   ([`DEFAULT_INTERCEPT_CA_TTL`]); a new VM boot mints a new CA.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mvm_core::policy::egress_rule::AllowRule;
+use rcgen::{
+    BasicConstraints, CertificateParams, CidrSubnet, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, GeneralSubtree, IsCa, KeyPair, KeyUsagePurpose, NameConstraints,
+    SerialNumber,
+};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use time::OffsetDateTime;
+
+/// Lifetime of a per-VM interception CA (and therefore of every leaf
+/// it signs). Matches the default session lifetime; a VM that outlives
+/// it must be restarted to get a fresh CA.
+pub const DEFAULT_INTERCEPT_CA_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// File name of the CA certificate on the VM's `mvm-config` drive.
+pub const INTERCEPT_CA_DRIVE_FILE: &str = "egress-ca.crt";
+
+/// Where the CA certificate is staged inside the guest. mkGuest's
+/// `/init` copies it off the config drive and appends it to the system
+/// bundle before the workload starts.
+pub const GUEST_INTERCEPT_CA_PATH: &str = "/run/mvm/egress-ca.crt";
+
+/// Clock skew tolerated on `notBefore`, so a guest whose clock lags
+/// the host slightly still accepts freshly minted certificates.
+const NOT_BEFORE_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Leaf configs an [`InterceptCa`] keeps cached. A wildcard allow-list
+/// rule admits unboundedly many hostnames, so the cache evicts the
+/// least recently used leaf rather than growing with every CONNECT.
+const MAX_CACHED_LEAVES: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InterceptError {
+    #[error(
+        "tls interception needs at least one hostname in the egress allow_list to constrain the CA to"
+    )]
+    NoPermittedNames,
+    #[error("{0} is outside the interception CA's name constraints")]
+    OutsideConstraints(String),
+    #[error("minting interception certificate: {0}")]
+    Mint(String),
+    #[error("building interception TLS config: {0}")]
+    Tls(String),
+}
+
+/// A per-VM, name-constrained interception CA plus a bounded cache of
+/// the per-host leaf configs it has minted.
+pub struct InterceptCa {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+    permitted: Vec<String>,
+    not_after: OffsetDateTime,
+    leaves: Mutex<LeafCache>,
+}
+
+/// LRU cache of minted leaf configs, capped at [`MAX_CACHED_LEAVES`].
+#[derive(Default)]
+struct LeafCache {
+    tick: u64,
+    entries: HashMap<String, (u64, Arc<rustls::ServerConfig>)>,
+}
+
+impl LeafCache {
+    fn get(&mut self, host: &str) -> Option<Arc<rustls::ServerConfig>> {
+        self.tick += 1;
+        let (used, config) = self.entries.get_mut(host)?;
+        *used = self.tick;
+        Some(Arc::clone(config))
+    }
+
+    fn insert(&mut self, host: String, config: Arc<rustls::ServerConfig>) {
+        if self.entries.len() >= MAX_CACHED_LEAVES
+            && !self.entries.contains_key(&host)
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(host, _)| host.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        self.entries.insert(host, (self.tick, config));
+    }
+}
+
+impl std::fmt::Debug for InterceptCa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterceptCa")
+            .field("permitted", &self.permitted)
+            .field("not_after", &self.not_after)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InterceptCa {
+    /// Mint a CA constrained to the hostnames in `allow_list`.
+    ///
+    /// IP-literal entries contribute nothing (the CA excludes every
+    /// address); `*.example.com` and `.example.com` both constrain to
+    /// `example.com`, because a DNS name constraint already covers
+    /// every subdomain. Refuses an allow-list with no hostnames — an
+    /// unconstrained CA in the guest trust store is exactly what
+    /// ADR-006 rules out.
+    pub fn new(allow_list: &[AllowRule], ttl: Duration) -> Result<Self, InterceptError> {
+        let mut permitted: Vec<String> = allow_list
+            .iter()
+            .filter_map(|rule| rule.host.dns_subtree().map(str::to_string))
+            .collect();
+        permitted.sort();
+        permitted.dedup();
+        if permitted.is_empty() {
+            return Err(InterceptError::NoPermittedNames);
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let not_after = now + ttl;
+
+        let mut params = CertificateParams::default();
+        let mut dn = DistinguishedName::new();
+        dn.push(DnType::CommonName, "mvm egress interception CA");
+        dn.push(DnType::OrganizationName, "mvm");
+        params.distinguished_name = dn;
+        params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        params.name_constraints = Some(NameConstraints {
+            permitted_subtrees: permitted
+                .iter()
+                .map(|n| GeneralSubtree::DnsName(n.clone()))
+                .collect(),
+            excluded_subtrees: vec![
+                GeneralSubtree::IpAddress(CidrSubnet::V4([0; 4], [0; 4])),
+                GeneralSubtree::IpAddress(CidrSubnet::V6([0; 16], [0; 16])),
+            ],
+        });
+        params.not_before = now - NOT_BEFORE_SKEW;
+        params.not_after = not_after;
+        params.serial_number = Some(random_serial());
+
+        let key = KeyPair::generate().map_err(|e| InterceptError::Mint(e.to_string()))?;
+        let cert = params
+            .self_signed(&key)
+            .map_err(|e| InterceptError::Mint(e.to_string()))?;
+        Ok(Self {
+            cert,
+            key,
+            permitted,
+            not_after,
+            leaves: Mutex::new(LeafCache::default()),
+        })
+    }
+
+    /// PEM of the CA certificate — the bytes staged at
+    /// [`GUEST_INTERCEPT_CA_PATH`].
+    pub fn cert_pem(&self) -> String {
+        self.cert.pem()
+    }
+
+    pub fn cert_der(&self) -> &CertificateDer<'static> {
+        self.cert.der()
+    }
+
+    /// DNS subtrees the CA is constrained to, sorted.
+    pub fn permitted_names(&self) -> &[String] {
+        &self.permitted
+    }
+
+    /// True iff `host` falls inside one of the permitted subtrees
+    /// (the name itself or any subdomain of it). IP literals never do.
+    pub fn permits(&self, host: &str) -> bool {
+        if host.parse::<IpAddr>().is_ok() {
+            return false;
+        }
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.permitted.iter().any(|name| {
+            host == *name
+                || host
+                    .strip_suffix(name.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+
+    /// Server-side TLS config presenting a leaf for `host`, minted on
+    /// first use and kept in a bounded LRU cache. Offers `h2` and
+    /// `http/1.1` over ALPN.
+    pub fn server_config(&self, host: &str) -> Result<Arc<rustls::ServerConfig>, InterceptError> {
+        if !self.permits(host) {
+            return Err(InterceptError::OutsideConstraints(host.to_string()));
+        }
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let mut leaves = self.leaves.lock().expect("InterceptCa leaf cache poisoned");
+        if let Some(config) = leaves.get(&host) {
+            return Ok(config);
+        }
+        let config = Arc::new(self.mint_server_config(&host)?);
+        leaves.insert(host, Arc::clone(&config));
+        Ok(config)
+    }
+
+    fn mint_server_config(&self, host: &str) -> Result<rustls::ServerConfig, InterceptError> {
+        let mut params = CertificateParams::new(vec![host.to_string()])
+            .map_err(|e| InterceptError::Mint(e.to_string()))?;
+        let mut dn = DistinguishedName::new();
+        dn.push(DnType::CommonName, host);
+        params.distinguished_name = dn;
+        params.is_ca = IsCa::ExplicitNoCa;
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        params.use_authority_key_identifier_extension = true;
+        params.not_before = OffsetDateTime::now_utc() - NOT_BEFORE_SKEW;
+        params.not_after = self.not_after;
+        params.serial_number = Some(random_serial());
+
+        let key = KeyPair::generate().map_err(|e| InterceptError::Mint(e.to_string()))?;
+        let leaf = params
+            .signed_by(&key, &self.cert, &self.key)
+            .map_err(|e| InterceptError::Mint(e.to_string()))?;
+        let chain = vec![leaf.der().clone(), self.cert.der().clone()];
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der()));
+
+        let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| InterceptError::Tls(e.to_string()))?
+        .with_no_client_auth()
+        .with_single_cert(chain, key)
+        .map_err(|e| InterceptError::Tls(e.to_string()))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+}
+
+/// The proxy-side half of interception: the CA that fronts the guest
+/// and the connector used to re-originate TLS to the real upstream.
+pub struct TlsInterceptor {
+    ca: Arc<InterceptCa>,
+    upstream: tokio_rustls::TlsConnector,
+}
+
+impl TlsInterceptor {
+    /// Verify upstreams against the bundled webpki roots.
+    pub fn new(ca: Arc<InterceptCa>) -> Result<Self, InterceptError> {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        Self::with_upstream_roots(ca, roots)
+    }
+
+    /// Verify upstreams against `roots` instead — private CAs and
+    /// tests.
+    pub fn with_upstream_roots(
+        ca: Arc<InterceptCa>,
+        roots: rustls::RootCertStore,
+    ) -> Result<Self, InterceptError> {
+        let mut config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| InterceptError::Tls(e.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Self {
+            ca,
+            upstream: tokio_rustls::TlsConnector::from(Arc::new(config)),
+        })
+    }
+
+    pub fn ca(&self) -> &Arc<InterceptCa> {
+        &self.ca
+    }
+
+    pub(crate) fn upstream(&self) -> &tokio_rustls::TlsConnector {
+        &self.upstream
+    }
+}
+
+impl std::fmt::Debug for TlsInterceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsInterceptor")
+            .field("ca", &self.ca)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Positive 64-bit serial; RFC 5280 wants serials unique per issuer
+/// and unpredictable serials are cheap insurance.
+fn random_serial() -> SerialNumber {
+    let mut bytes: [u8; 8] = rand::random();
+    bytes[0] &= 0x7f;
+    SerialNumber::from(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allow(hosts: &[&str]) -> Vec<AllowRule> {
+        hosts
+            .iter()
+            .map(|h| AllowRule::from_tuple(h, 443).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ca_is_constrained_to_allow_list_hostnames() {
+        let ca = InterceptCa::new(
+            &allow(&[
+                "API.OpenAI.com",
+                "*.github.com",
+                ".github.com",
+                "10.0.0.1",
+                "api.openai.com",
+            ]),
+            DEFAULT_INTERCEPT_CA_TTL,
+        )
+        .unwrap();
+        assert_eq!(ca.permitted_names(), ["api.openai.com", "github.com"]);
+        assert!(ca.permits("api.openai.com"));
+        assert!(ca.permits("raw.github.com"));
+        assert!(ca.permits("github.com."));
+        assert!(!ca.permits("notgithub.com"));
+        assert!(!ca.permits("evil.com"));
+        assert!(!ca.permits("10.0.0.1"));
+        assert!(ca.cert_pem().starts_with("-----BEGIN CERTIFICATE-----"));
+    }
+
+    #[test]
+    fn allow_list_without_hostnames_is_refused() {
+        let err = InterceptCa::new(&allow(&["10.0.0.1"]), DEFAULT_INTERCEPT_CA_TTL).unwrap_err();
+        assert!(matches!(err, InterceptError::NoPermittedNames));
+    }
+
+    #[test]
+    fn leaf_outside_constraints_is_refused() {
+        let ca = InterceptCa::new(&allow(&["api.openai.com"]), DEFAULT_INTERCEPT_CA_TTL).unwrap();
+        assert!(matches!(
+            ca.server_config("evil.com"),
+            Err(InterceptError::OutsideConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn leaf_configs_are_cached_per_host() {
+        let ca = InterceptCa::new(&allow(&["example.com"]), DEFAULT_INTERCEPT_CA_TTL).unwrap();
+        let a = ca.server_config("a.example.com").unwrap();
+        let again = ca.server_config("A.example.com").unwrap();
+        let b = ca.server_config("b.example.com").unwrap();
+        assert!(Arc::ptr_eq(&a, &again));
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(a.alpn_protocols, vec![b"h2".to_vec(), b"http/1.1".to_vec()]);
+    }
+
+    #[test]
+    fn leaf_cache_evicts_the_least_recently_used_host() {
+        let ca = InterceptCa::new(&allow(&["*.example.com"]), DEFAULT_INTERCEPT_CA_TTL).unwrap();
+        let first = ca.server_config("h0.example.com").unwrap();
+        for i in 1..MAX_CACHED_LEAVES {
+            ca.server_config(&format!("h{i}.example.com")).unwrap();
+        }
+        // Touch h0 so h1 is now the oldest, then overflow by one.
+        ca.server_config("h0.example.com").unwrap();
+        ca.server_config("overflow.example.com").unwrap();
+
+        let leaves = ca.leaves.lock().unwrap();
+        assert_eq!(leaves.entries.len(), MAX_CACHED_LEAVES);
+        assert!(leaves.entries.contains_key("h0.example.com"));
+        assert!(!leaves.entries.contains_key("h1.example.com"));
+        drop(leaves);
+        assert!(Arc::ptr_eq(
+            &first,
+            &ca.server_config("h0.example.com").unwrap()
+        ));
+    }
+}
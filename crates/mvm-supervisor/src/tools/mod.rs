@@ -17,7 +17,13 @@
 //!    the canonical event name `cmd.tool.<name>.{completed,failed}`.
 //!    The audit is best-effort: a chain-signer failure does not
 //!    fail the tool call.
-//! 3. **Uniform error rendering** — tool-side errors return
+//! 3. **Threat screening** — when a [`ThreatGate`] is wired, the
+//!    params of every call are classified first. Each match is
+//!    chain-audited as `policy.threat.<action>` with its
+//!    `pattern_id` and severity; a `Block` category refuses the call
+//!    and a `RequireApproval` one parks it on the wired
+//!    [`ApprovalBroker`] (refused when none is wired).
+//! 4. **Uniform error rendering** — tool-side errors return
 //!    [`ToolInvokeError`] so callers can surface a structured
 //!    failure to the LLM client (MCP semantics: errors must reach
 //!    the model, not the JSON-RPC error channel).
@@ -57,8 +63,12 @@ pub mod web_fetch;
 pub mod web_search;
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use mvm_core::security::BlocklistAction;
+use mvm_security::approval::{ApprovalBroker, ApprovalSubject};
+use mvm_security::threat_gate::ThreatGate;
 use thiserror::Error;
 
 use crate::audit_recorder::{EventCategory, Recorder};
@@ -111,6 +121,12 @@ pub enum ToolInvokeError {
     /// take care to redact secrets before constructing this variant.
     #[error("upstream failure in {tool}: {message}")]
     Upstream { tool: String, message: String },
+
+    /// The threat gate refused the params — a `Block` category
+    /// matched, or a `RequireApproval` one was denied, timed out or
+    /// had no broker to park on. The tool never ran.
+    #[error("{tool} refused: {reason}")]
+    Refused { tool: String, reason: String },
 }
 
 /// A bundle of available [`HostMediatedTool`] impls keyed by their
@@ -125,6 +141,8 @@ pub enum ToolInvokeError {
 pub struct ToolRegistry {
     tools: BTreeMap<&'static str, Box<dyn HostMediatedTool>>,
     recorder: Option<Recorder>,
+    threat_gate: Option<Arc<ThreatGate>>,
+    approvals: Option<(Arc<ApprovalBroker>, String)>,
 }
 
 impl ToolRegistry {
@@ -134,6 +152,8 @@ impl ToolRegistry {
         Self {
             tools: BTreeMap::new(),
             recorder: None,
+            threat_gate: None,
+            approvals: None,
         }
     }
 
@@ -173,6 +193,25 @@ impl ToolRegistry {
         self
     }
 
+    /// Screen every call's params through `gate` before the tool
+    /// runs. Returns `self` for chaining.
+    pub fn with_threat_gate(mut self, gate: Arc<ThreatGate>) -> Self {
+        self.threat_gate = Some(gate);
+        self
+    }
+
+    /// Park calls the threat gate holds for approval on `broker`,
+    /// recorded as requested by `requested_by`. Without a broker
+    /// those calls are refused.
+    pub fn with_approvals(
+        mut self,
+        broker: Arc<ApprovalBroker>,
+        requested_by: impl Into<String>,
+    ) -> Self {
+        self.approvals = Some((broker, requested_by.into()));
+        self
+    }
+
     /// Register a tool. Replaces any previous entry with the same
     /// `name()` — useful for tests that want to override a builtin
     /// with a stub. Production callers should register each name
@@ -221,6 +260,11 @@ impl ToolRegistry {
                 .await;
             return Err(err);
         };
+        if let Err(err) = self.screen(name, &params).await {
+            self.emit_audit(name, "failed", Some(&err.to_string()))
+                .await;
+            return Err(err);
+        }
         let result = tool.invoke(params).await;
         match &result {
             Ok(_) => self.emit_audit(name, "completed", None).await,
@@ -229,6 +273,72 @@ impl ToolRegistry {
         result
     }
 
+    /// Run `params` through the threat gate, auditing every match.
+    /// `Ok` when the call may proceed.
+    async fn screen(&self, name: &str, params: &serde_json::Value) -> Result<(), ToolInvokeError> {
+        let Some(ref gate) = self.threat_gate else {
+            return Ok(());
+        };
+        let text = params.to_string();
+        let screening = gate.screen(&text);
+        if let Some(ref rec) = self.recorder {
+            for m in &screening.matches {
+                let labels = m.audit_labels("tool", Some(name));
+                if let Err(e) = rec
+                    .record_unbound(EventCategory::Policy, m.audit_event(), labels)
+                    .await
+                {
+                    tracing::warn!(error = %e, tool = name, "threat audit emit failed");
+                }
+            }
+        }
+        let Some(decisive) = screening.decisive() else {
+            return Ok(());
+        };
+        let refused = |reason: String| ToolInvokeError::Refused {
+            tool: name.to_string(),
+            reason,
+        };
+        match decisive.action {
+            BlocklistAction::Log => Ok(()),
+            BlocklistAction::Block => Err(refused(format!("blocked by {}", decisive.reason()))),
+            BlocklistAction::RequireApproval => {
+                let Some((broker, requested_by)) = self.approvals.clone() else {
+                    return Err(refused(format!(
+                        "{} requires approval and no approval broker is wired",
+                        decisive.reason()
+                    )));
+                };
+                let reason = decisive.reason();
+                let target = name.to_string();
+                let command = format!("{name} {text}");
+                let outcome = tokio::task::spawn_blocking(move || {
+                    broker.hold(
+                        ApprovalSubject::Tool,
+                        Some(&target),
+                        &command,
+                        &reason,
+                        &requested_by,
+                        |request| {
+                            tracing::warn!(
+                                id = %request.id,
+                                tool = %target,
+                                "tool call parked for approval"
+                            )
+                        },
+                    )
+                })
+                .await
+                .map_err(|e| refused(format!("approval wait failed: {e}")))?
+                .map_err(|e| refused(format!("approval queue: {e:#}")))?;
+                match outcome.refusal() {
+                    None => Ok(()),
+                    Some(reason) => Err(refused(reason)),
+                }
+            }
+        }
+    }
+
     async fn emit_audit(&self, name: &str, phase: &str, error: Option<&str>) {
         let Some(ref rec) = self.recorder else { return };
         let event = format!("cmd.tool.{name}.{phase}");
@@ -481,6 +591,113 @@ mod tests {
         );
     }
 
+    // ──────────────────────────────────────────────────────────────
+    // Threat gate
+    // ──────────────────────────────────────────────────────────────
+
+    fn threat_gated(
+        actions: &[(mvm_core::security::ThreatCategory, BlocklistAction)],
+    ) -> (ToolRegistry, Arc<CapturingAuditSigner>) {
+        let (reg, signer) = build_registry_with_recorder();
+        let gate = ThreatGate::new(BlocklistAction::Log, actions.iter().cloned().collect());
+        (reg.with_threat_gate(Arc::new(gate)), signer)
+    }
+
+    #[tokio::test]
+    async fn logged_threat_is_audited_and_the_call_proceeds() {
+        let (reg, signer) = threat_gated(&[]);
+        reg.invoke(
+            "mvm.echo",
+            serde_json::json!({ "cmd": "mkfs.ext4 /dev/vda" }),
+        )
+        .await
+        .unwrap();
+        let entries = signer.entries();
+        let threat = entries
+            .iter()
+            .find(|e| e.event == "policy.threat.logged")
+            .expect("threat audit entry");
+        assert_eq!(threat.labels.get("pattern_id"), Some(&"mkfs".to_string()));
+        assert_eq!(threat.labels.get("severity"), Some(&"critical".to_string()));
+        assert_eq!(threat.labels.get("target"), Some(&"mvm.echo".to_string()));
+        assert_eq!(entries.last().unwrap().event, "cmd.tool.mvm.echo.completed");
+    }
+
+    #[tokio::test]
+    async fn blocked_threat_refuses_the_call() {
+        let (reg, signer) = threat_gated(&[(
+            mvm_core::security::ThreatCategory::Destructive,
+            BlocklistAction::Block,
+        )]);
+        let err = reg
+            .invoke(
+                "mvm.echo",
+                serde_json::json!({ "cmd": "mkfs.ext4 /dev/vda" }),
+            )
+            .await
+            .unwrap_err();
+        let ToolInvokeError::Refused { tool, reason } = err else {
+            panic!("expected Refused, got {err:?}");
+        };
+        assert_eq!(tool, "mvm.echo");
+        assert!(reason.contains("Destructive pattern mkfs"), "{reason}");
+        let events: Vec<_> = signer.entries().into_iter().map(|e| e.event).collect();
+        assert!(events.contains(&"policy.threat.blocked".to_string()));
+        assert_eq!(events.last().unwrap(), "cmd.tool.mvm.echo.failed");
+    }
+
+    #[tokio::test]
+    async fn approval_without_a_broker_fails_closed() {
+        let (reg, _) = threat_gated(&[(
+            mvm_core::security::ThreatCategory::Destructive,
+            BlocklistAction::RequireApproval,
+        )]);
+        let err = reg
+            .invoke(
+                "mvm.echo",
+                serde_json::json!({ "cmd": "mkfs.ext4 /dev/vda" }),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("no approval broker is wired"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn approval_timeout_refuses_the_call() {
+        let tmp = tempfile::tempdir().unwrap();
+        let broker = ApprovalBroker::new(
+            mvm_security::command_gate::CommandGate::new(Vec::new()),
+            mvm_security::approval::ApprovalQueue::open(tmp.path()).unwrap(),
+        )
+        .with_timeout(std::time::Duration::from_millis(30))
+        .with_poll_interval(std::time::Duration::from_millis(5));
+        let (reg, _) = threat_gated(&[(
+            mvm_core::security::ThreatCategory::Destructive,
+            BlocklistAction::RequireApproval,
+        )]);
+        let reg = reg.with_approvals(Arc::new(broker), "tester");
+        let err = reg
+            .invoke(
+                "mvm.echo",
+                serde_json::json!({ "cmd": "mkfs.ext4 /dev/vda" }),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn clean_params_skip_threat_audit() {
+        let (reg, signer) = threat_gated(&[]);
+        reg.invoke("mvm.echo", serde_json::json!({ "x": 1 }))
+            .await
+            .unwrap();
+        assert_eq!(signer.entries().len(), 1);
+    }
+
     // ──────────────────────────────────────────────────────────────
     // Recorder absent
     // ──────────────────────────────────────────────────────────────
@@ -199,6 +199,9 @@ fn run() -> Result<()> {
         signer,
         policy: Arc::new(AllowAll),
         observers,
+        // The Swift splice owns the Vz datapath; L4 frame
+        // enforcement isn't available on this backend.
+        l4: None,
     };
 
     let endpoints = BridgeEndpoints::VzIngest {
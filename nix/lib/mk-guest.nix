@@ -372,6 +372,45 @@ let
         -- /usr/local/bin/mvm-addon-dns &
     fi
 
+    # Stage 2.49 — egress interception CA (Wave 2.6.5, opt-in).
+    #
+    # When the VM's `EgressPolicy.tls_intercept` is on, the supervisor
+    # launch path that runs the intercepting proxy writes the per-VM CA
+    # (name-constrained to the allow-list hostnames) to the mvm-config
+    # drive as egress-ca.crt; `mvmctl up` never does. Copy it to
+    # /run/mvm/egress-ca.crt, append it to a tmpfs copy of the baked
+    # Mozilla bundle and bind-mount that over
+    # /etc/ssl/certs/ca-bundle.crt; the ca-certificates.crt symlink
+    # follows. Guests without interception (or without a config
+    # drive) skip this block and keep the build-time bundle
+    # byte-for-byte.
+    #
+    # The same mount stages the bundle's [fs_quota] limits
+    # (fs-quota.json) at /run/mvm/fs-quota.json, where the guest
+    # agent reads its FS RPC backstop limits.
+    MVM_CONFIG_DEV=$(/bin/busybox findfs LABEL=mvm-config 2>/dev/null || true)
+    if [ -n "$MVM_CONFIG_DEV" ]; then
+      /bin/busybox mkdir -p /run/mvm/config-drive
+      /bin/busybox chmod 0755 /run/mvm
+      if /bin/busybox mount -t ext4 -o ro "$MVM_CONFIG_DEV" /run/mvm/config-drive 2>/dev/null; then
+        if [ -r /run/mvm/config-drive/egress-ca.crt ]; then
+          /bin/busybox cp /run/mvm/config-drive/egress-ca.crt /run/mvm/egress-ca.crt
+          /bin/busybox chmod 0644 /run/mvm/egress-ca.crt
+        fi
+        if [ -r /run/mvm/config-drive/fs-quota.json ]; then
+          /bin/busybox cp /run/mvm/config-drive/fs-quota.json /run/mvm/fs-quota.json
+          /bin/busybox chmod 0644 /run/mvm/fs-quota.json
+        fi
+        /bin/busybox umount /run/mvm/config-drive
+      fi
+      /bin/busybox rmdir /run/mvm/config-drive 2>/dev/null || true
+    fi
+    if [ -r /run/mvm/egress-ca.crt ] && [ -r /etc/ssl/certs/ca-bundle.crt ]; then
+      /bin/busybox cat /etc/ssl/certs/ca-bundle.crt /run/mvm/egress-ca.crt > /run/mvm/ca-bundle.crt
+      /bin/busybox chmod 0644 /run/mvm/ca-bundle.crt
+      /bin/busybox mount --bind /run/mvm/ca-bundle.crt /etc/ssl/certs/ca-bundle.crt
+    fi
+
     # Stage 2.5 — guest agent supervisor. Fork the agent into
     # the background under its own uid before we drop to the
     # entrypoint. The agent is responsible for vsock RPC (host
@@ -115,6 +115,16 @@ const IMAGE_SUB: &[(&str, AuditPosture)] = &[
     ("ls", AuditPosture::ReadOnly),
     ("inspect", AuditPosture::ReadOnly),
     ("rm", AuditPosture::Emits("CachePrune")),
+    ("login", AuditPosture::Emits("RegistryLogin")),
+    ("logout", AuditPosture::Emits("RegistryLogout")),
+];
+
+// `approvals approve` / `deny` record the decision on the chain as
+// `policy.approval.{approved,denied}` (the broker's audit sink).
+const APPROVALS_SUB: &[(&str, AuditPosture)] = &[
+    ("ls", AuditPosture::ReadOnly),
+    ("approve", AuditPosture::Emits("policy.approval.approved")),
+    ("deny", AuditPosture::Emits("policy.approval.denied")),
 ];
 
 const VOLUME_SUB: &[(&str, AuditPosture)] = &[
@@ -299,6 +309,7 @@ const AUDIT_POSTURE: &[(&str, AuditPosture)] = &[
     ("network", AuditPosture::DelegatesToSub(NETWORK_SUB)),
     ("cache", AuditPosture::DelegatesToSub(CACHE_SUB)),
     ("mcp", AuditPosture::InteractiveOrControl),
+    ("approvals", AuditPosture::DelegatesToSub(APPROVALS_SUB)),
     ("secret", AuditPosture::DelegatesToSub(SECRET_SUB)),
     ("attest", AuditPosture::DelegatesToSub(ATTEST_SUB)),
     // Sprint 52 W2 — bundles + trust store.
@@ -470,6 +481,8 @@ fn audit_posture_emits_entries_reference_known_audit_kinds() {
         "ManifestTagRemove",
         "NetworkCreate",
         "NetworkRemove",
+        "RegistryLogin",
+        "RegistryLogout",
         "SecretGet",
         "SecretPut",
         "SecretRm",
@@ -512,6 +525,9 @@ fn audit_posture_emits_entries_reference_known_audit_kinds() {
         // bails before emit).
         "vm.snapshot_saved",
         "vm.snapshot_restored",
+        // Approval-broker decisions (`mvmctl approvals approve|deny`).
+        "policy.approval.approved",
+        "policy.approval.denied",
     ];
 
     let mut failures: Vec<(String, &'static str)> = Vec::new();
@@ -268,6 +268,40 @@ fn mk_guest_installs_netinit_at_boot() {
     );
 }
 
+/// Wave 2.6.5 — when `tls_intercept` is on, `mvmctl up` writes the
+/// per-VM CA to the `mvm-config` drive; `/init` must pick it up and
+/// merge it into the guest trust store, or every intercepted
+/// handshake fails verification inside the guest.
+#[test]
+fn mk_guest_stages_egress_ca_from_config_drive() {
+    let path = nix_dir().join("lib").join("mk-guest.nix");
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("nix/lib/mk-guest.nix must be present: {e}"));
+
+    assert!(
+        content.contains("findfs LABEL=mvm-config"),
+        "mk-guest.nix /init must locate the config drive by its \
+         `mvm-config` label to read the staged egress CA."
+    );
+    assert!(
+        content.contains("/run/mvm/config-drive/egress-ca.crt")
+            && content.contains("/run/mvm/egress-ca.crt"),
+        "mk-guest.nix /init must copy `egress-ca.crt` from the config \
+         drive to `/run/mvm/egress-ca.crt` (GUEST_INTERCEPT_CA_PATH)."
+    );
+    assert!(
+        content.contains("/run/mvm/config-drive/fs-quota.json")
+            && content.contains("/run/mvm/fs-quota.json"),
+        "mk-guest.nix /init must copy `fs-quota.json` from the config \
+         drive to `/run/mvm/fs-quota.json` (GUEST_FS_QUOTA_PATH)."
+    );
+    assert!(
+        content.contains("mount --bind /run/mvm/ca-bundle.crt /etc/ssl/certs/ca-bundle.crt"),
+        "mk-guest.nix /init must bind-mount the merged bundle over \
+         the guest trust store so TLS clients accept the CA."
+    );
+}
+
 /// Plan 74 W2 (deferred-list item) — the runtime overlay flake
 /// must stage `mvm-guest-netinit` at the canonical `/netinit`
 /// path inside the overlay so OCI-imported workloads get